@@ -1,3 +1,192 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
 fn main() {
+    write_registered_commands();
     tauri_build::build()
 }
+
+// Palette registry generated from the `generate_handler!` list in lib.rs, so
+// every registered command shows up without a hand-kept table. Parameters are
+// read from each command's signature; `State`, `AppHandle`, `Webview` and
+// `CommandTimer` arguments are injected by Tauri and left out. Only a
+// `DocumentId` parameter marks a command as acting on the active document;
+// terminal, recovery and other ids stay plain `String`s.
+
+struct CommandSignature {
+    name: String,
+    module: String,
+    needs_document: bool,
+    is_async: bool,
+    params: Vec<String>,
+}
+
+fn registered_command_paths(lib_source: &str) -> Vec<(String, String)> {
+    let start = lib_source
+        .find("generate_handler![")
+        .expect("lib.rs registers commands with generate_handler!");
+    let end = start
+        + lib_source[start..]
+            .find(']')
+            .expect("generate_handler! list is closed");
+
+    lib_source[start..end]
+        .split(|ch: char| ch == ',' || ch == '[' || ch.is_whitespace())
+        .filter_map(|path| path.strip_prefix("commands::"))
+        .map(|path| match path.rsplit_once("::") {
+            Some((module, name)) => (module.to_string(), name.to_string()),
+            None => (String::new(), path.to_string()),
+        })
+        .collect()
+}
+
+// The command's parameter list and whether it is an `async fn`.
+fn signature_params<'a>(source: &'a str, name: &str) -> Option<(&'a str, bool)> {
+    let marker = format!("fn {}(", name);
+    let (index, is_async) = source
+        .match_indices(&marker)
+        .map(|(index, _)| (index, source[..index].trim_end().ends_with("async")))
+        .find(|(index, is_async)| *is_async || source[..*index].trim_end().ends_with("pub"))?;
+    let start = index + marker.len();
+
+    let mut depth = 0usize;
+    for (offset, ch) in source[start..].char_indices() {
+        match ch {
+            '(' | '<' | '[' => depth += 1,
+            ')' if depth == 0 => return Some((&source[start..start + offset], is_async)),
+            ')' | '>' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn split_top_level(params: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut part_start = 0usize;
+    for (index, ch) in params.char_indices() {
+        match ch {
+            '(' | '<' | '[' => depth += 1,
+            ')' | '>' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&params[part_start..index]);
+                part_start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&params[part_start..]);
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn to_camel_case(name: &str) -> String {
+    let mut output = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for ch in name.chars() {
+        if ch == '_' {
+            upper_next = true;
+        } else if upper_next {
+            output.push(ch.to_ascii_uppercase());
+            upper_next = false;
+        } else {
+            output.push(ch);
+        }
+    }
+    output
+}
+
+fn is_injected_param(param_type: &str) -> bool {
+    param_type.starts_with("State<")
+        || param_type.starts_with("tauri::State<")
+        || matches!(
+            param_type,
+            "AppHandle" | "tauri::AppHandle" | "Webview" | "tauri::Webview" | "CommandTimer"
+        )
+}
+
+fn parse_command_signature(source: &str, module: &str, name: &str) -> CommandSignature {
+    let (params, is_async) = signature_params(source, name)
+        .unwrap_or_else(|| panic!("registered command {} has no signature", name));
+    // The invoke handler only sees async commands being spawned.
+    assert!(
+        !is_async || params.contains("CommandTimer"),
+        "async command {} needs a CommandTimer argument to be timed",
+        name
+    );
+
+    let mut needs_document = false;
+    let mut required = Vec::new();
+    for param in split_top_level(params) {
+        let (param_name, param_type) = param
+            .split_once(':')
+            .unwrap_or_else(|| panic!("unexpected parameter {} in {}", param, name));
+        let param_name = param_name.trim().trim_start_matches("mut ").trim();
+        let param_type = param_type.trim();
+        if is_injected_param(param_type) {
+            continue;
+        }
+        if param_type == "DocumentId" {
+            needs_document = true;
+        } else if !param_type.starts_with("Option<") {
+            required.push(to_camel_case(param_name));
+        }
+    }
+
+    CommandSignature {
+        name: name.to_string(),
+        module: module.to_string(),
+        needs_document,
+        is_async,
+        params: required,
+    }
+}
+
+fn write_registered_commands() {
+    let src_dir = Path::new("src");
+    let lib_source = fs::read_to_string(src_dir.join("lib.rs")).expect("read src/lib.rs");
+    println!("cargo:rerun-if-changed=src/lib.rs");
+
+    let mut sources = BTreeSet::new();
+    let mut generated = String::from(
+        "// Generated by build.rs from the generate_handler! list in lib.rs.\n\
+         const REGISTERED_COMMANDS: &[RegisteredCommand] = &[\n",
+    );
+    for (module, name) in registered_command_paths(&lib_source) {
+        let source_path: PathBuf = if module.is_empty() {
+            src_dir.join("commands.rs")
+        } else {
+            src_dir.join("commands").join(format!("{}.rs", module))
+        };
+        let source = fs::read_to_string(&source_path)
+            .unwrap_or_else(|error| panic!("read {}: {}", source_path.display(), error));
+        let signature = parse_command_signature(&source, &module, &name);
+        let params = signature
+            .params
+            .iter()
+            .map(|param| format!("{:?}", param))
+            .collect::<Vec<_>>()
+            .join(", ");
+        generated.push_str(&format!(
+            "    RegisteredCommand {{ name: {:?}, module: {:?}, needs_document: {}, is_async: {}, params: &[{}] }},\n",
+            signature.name, signature.module, signature.needs_document, signature.is_async, params
+        ));
+        sources.insert(source_path);
+    }
+    generated.push_str("];\n");
+    for source_path in sources {
+        println!("cargo:rerun-if-changed={}", source_path.display());
+    }
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set for build scripts");
+    fs::write(
+        Path::new(&out_dir).join("registered_commands.rs"),
+        generated,
+    )
+    .expect("write registered_commands.rs");
+}
@@ -1,4 +1,7 @@
-use crate::state::{default_line_ending, AppState, Document, EditOperation, LineEnding};
+use crate::state::{
+    default_line_ending, now_unix_millis, AppState, Document, EditOperation, FileShareMode,
+    LineAnnotation, LineEnding, StartupPath, TempHighlight,
+};
 use chardetng::EncodingDetector;
 use dashmap::DashMap;
 use encoding_rs::Encoding;
@@ -7,36 +10,99 @@ use pinyin::ToPinyin;
 use ropey::Rope;
 use std::collections::{BTreeSet, HashSet};
 use std::fs::{self, File};
+use std::path::Path;
 use std::process::Command;
 use std::sync::OnceLock;
-use tauri::State;
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use uuid::Uuid;
 
+mod annotations;
+mod auto_indent;
+mod auto_pair;
+mod auto_save;
+mod bracket_depth;
+mod byte_search;
+mod clipboard_history;
+mod column_sequence;
+mod comments;
 mod config;
 mod constants;
+mod csv_dialect;
 mod diff;
 mod document;
+mod document_export;
+mod document_views;
 mod editing;
 pub(crate) mod editing_commands;
+mod encodings;
+mod extreme_lines;
 mod file_io;
 pub(crate) mod file_io_commands;
+mod file_sniff;
+mod filtered_export;
+mod fold_ranges;
+mod folder_encoding;
+mod folder_line_endings;
+mod folder_walk;
 mod formatting;
+mod git;
+pub(crate) mod git_commands;
+mod hex_document;
+mod idle_maintenance;
+mod ini;
+mod json_lines;
+mod json_store;
+mod line_notes;
+mod locale;
+mod log_series;
+mod merge;
 mod outline;
+mod palette;
+mod patch_hunks;
 mod path_search;
 pub(crate) mod path_search_commands;
+mod performance;
+mod pinned_results;
+mod preview_mode;
+mod raw_bytes;
+mod reading_positions;
+mod recently_closed;
+mod recovery;
+mod remote_control;
+mod rule_alerts;
 mod search;
 pub(crate) mod search_commands;
+mod secrets;
+mod session;
+pub(crate) mod session_commands;
 mod settings;
+mod snapshots;
+mod streaming_document;
+mod streams;
+mod structured;
 mod syntax;
+mod syntax_suggestion;
+mod temp_highlights;
+mod terminal;
+pub(crate) mod terminal_commands;
+mod text_table;
+mod text_transform;
 mod text_utils;
 mod translation;
 mod types;
+mod virtual_document;
 
+pub use self::auto_save::{AutoSaveDebounce, AutoSaveMode, AutoSaveTrigger};
 use self::constants::*;
+use self::performance::CommandTimer;
+pub use self::idle_maintenance::{IdleActivityTracker, IdleMaintenanceReport};
 use self::search::*;
+use self::types::DocumentId;
 pub use self::settings::AppConfig;
 pub use self::types::{
-    DirEntry, EditHistoryState, FileInfo, WindowsFileAssociationStatus, WordCountInfo,
+    DirEntry, EditHistoryEntry, EditHistoryState, FileInfo, WindowsFileAssociationStatus,
+    WordCountInfo,
 };
 
 #[derive(Clone, Copy)]
@@ -52,9 +118,12 @@ fn external_change_notified_ids() -> &'static DashMap<String, ()> {
     EXTERNAL_CHANGE_NOTIFIED_IDS.get_or_init(DashMap::new)
 }
 
+// Only documents whose path passes `should_check` are compared with the disk;
+// the others keep their notified state until a later check covers them.
 fn collect_external_file_change_document_ids_impl(
     state: &AppState,
     tracking_enabled: bool,
+    should_check: impl Fn(&Path) -> bool,
 ) -> Vec<String> {
     let cache = external_change_notified_ids();
 
@@ -70,6 +139,10 @@ fn collect_external_file_change_document_ids_impl(
         let id = doc.key().clone();
         active_ids.insert(id.clone());
 
+        if doc.path.as_deref().is_some_and(|path| !should_check(path)) {
+            continue;
+        }
+
         let changed = doc
             .path
             .as_ref()
@@ -100,11 +173,18 @@ fn collect_external_file_change_document_ids_impl(
     changed_ids
 }
 
+pub fn persist_session_on_close(state: State<'_, AppState>) {
+    if let Err(error) = session::persist_session_on_close_impl(&state) {
+        eprintln!("failed to save session: {error}");
+    }
+}
+
 pub fn collect_external_file_change_document_ids_with_tracking(
     state: State<'_, AppState>,
     tracking_enabled: bool,
+    should_check: impl Fn(&Path) -> bool,
 ) -> Vec<String> {
-    collect_external_file_change_document_ids_impl(&state, tracking_enabled)
+    collect_external_file_change_document_ids_impl(&state, tracking_enabled, should_check)
 }
 
 #[tauri::command]
@@ -169,6 +249,120 @@ pub fn get_windows_file_association_status(
     config::get_windows_file_association_status_impl(extensions)
 }
 
+pub fn enable_safe_mode() {
+    config::enable_safe_mode_impl();
+}
+
+#[tauri::command]
+pub fn record_reading_position(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    line: usize,
+) -> Result<bool, String> {
+    reading_positions::record_reading_position_impl(&state, &id, line)
+}
+
+#[tauri::command]
+pub fn get_reading_position(
+    path: String,
+) -> Result<Option<reading_positions::ReadingPositionPayload>, String> {
+    reading_positions::get_reading_position_impl(path)
+}
+
+#[tauri::command]
+pub fn list_reading_positions() -> Result<Vec<reading_positions::ReadingPositionPayload>, String> {
+    reading_positions::list_reading_positions_impl()
+}
+
+#[tauri::command]
+pub fn store_secret(name: String, value: String) -> Result<(), String> {
+    secrets::store_secret_impl(name, value)
+}
+
+#[tauri::command]
+pub fn has_secret(name: String) -> Result<bool, String> {
+    secrets::has_secret_impl(name)
+}
+
+#[tauri::command]
+pub fn delete_secret(name: String) -> Result<bool, String> {
+    secrets::delete_secret_impl(name)
+}
+
+#[tauri::command]
+pub fn add_line_note(
+    state: State<'_, AppState>,
+    path: String,
+    line: usize,
+    text: String,
+) -> Result<line_notes::LineNotePayload, String> {
+    line_notes::add_line_note_impl(state, path, line, text)
+}
+
+#[tauri::command]
+pub fn list_line_notes(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<Vec<line_notes::LineNotePayload>, String> {
+    line_notes::list_line_notes_impl(state, path)
+}
+
+#[tauri::command]
+pub fn export_notes(state: State<'_, AppState>, path: String) -> Result<String, String> {
+    line_notes::export_notes_impl(state, path)
+}
+
+#[tauri::command]
+pub fn pin_search_results(
+    state: State<'_, AppState>,
+    session_id: String,
+    name: String,
+) -> Result<pinned_results::PinnedResultSummary, String> {
+    pinned_results::pin_search_results_impl(state, session_id, name)
+}
+
+#[tauri::command]
+pub fn list_pinned_results() -> Result<Vec<pinned_results::PinnedResultSummary>, String> {
+    pinned_results::list_pinned_results_impl()
+}
+
+#[tauri::command]
+pub fn open_pinned_results(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<pinned_results::OpenPinnedResultsPayload, String> {
+    pinned_results::open_pinned_results_impl(state, name)
+}
+
+#[tauri::command]
+pub fn get_locale_strings(language: Option<String>) -> locale::LocaleStringsPayload {
+    locale::get_locale_strings_impl(language)
+}
+
+#[tauri::command]
+pub fn list_palette_commands(language: Option<String>) -> Vec<palette::PaletteCommandPayload> {
+    palette::list_palette_commands_impl(language)
+}
+
+#[tauri::command]
+pub async fn invoke_palette_command(
+    webview: tauri::Webview,
+    name: String,
+    args: Option<serde_json::Value>,
+    _timer: CommandTimer,
+) -> Result<serde_json::Value, String> {
+    palette::invoke_palette_command_impl(webview, name, args).await
+}
+
+#[tauri::command]
+pub fn get_startup_mode() -> String {
+    if config::is_safe_mode_impl() {
+        "safe".to_string()
+    } else {
+        "normal".to_string()
+    }
+}
+
 #[tauri::command]
 pub fn load_config() -> Result<AppConfig, String> {
     config::load_config_impl()
@@ -182,6 +376,127 @@ pub fn is_remember_window_state_enabled_in_config() -> bool {
     config::is_remember_window_state_enabled_in_config_impl()
 }
 
+pub fn load_auto_save_settings_in_config() -> (AutoSaveMode, u64) {
+    auto_save::load_auto_save_settings()
+}
+
+pub async fn auto_save_dirty_documents(app: AppHandle, trigger: AutoSaveTrigger) {
+    auto_save::auto_save_dirty_documents_impl(app, trigger).await;
+}
+
+pub fn run_auto_save_tick(app: &AppHandle, debounce: &mut AutoSaveDebounce) {
+    auto_save::auto_save_tick_impl(app, debounce);
+}
+
+pub fn request_stdin_stream() {
+    streams::request_stdin_stream_impl();
+}
+
+#[tauri::command]
+pub fn open_stdin_stream(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<FileInfo>, String> {
+    streams::open_stdin_stream_impl(app, state)
+}
+
+#[tauri::command]
+pub fn open_named_pipe_stream(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<FileInfo, String> {
+    streams::open_named_pipe_stream_impl(app, state, path)
+}
+
+#[tauri::command]
+pub fn set_stream_follow(id: DocumentId, follow: bool) -> Result<(), String> {
+    streams::set_stream_follow_impl(id, follow)
+}
+
+#[tauri::command]
+pub fn stop_stream(id: DocumentId) -> bool {
+    streams::stop_stream_impl(id)
+}
+
+pub fn start_remote_control_server(
+    app: &AppHandle,
+    open_paths: fn(&AppHandle, Vec<StartupPath>),
+) {
+    remote_control::start_remote_control_server_impl(app, open_paths);
+}
+
+pub fn capture_document_snapshots(app: &AppHandle) {
+    snapshots::capture_document_snapshots_impl(app);
+}
+
+pub fn summarize_invoke_body(body: &tauri::ipc::InvokeBody) -> String {
+    performance::summarize_invoke_body_impl(body)
+}
+
+pub fn is_async_command(name: &str) -> bool {
+    palette::is_async_command_impl(name)
+}
+
+pub fn record_command_timing(command: &str, params: String, elapsed: std::time::Duration) {
+    performance::record_command_timing_impl(command, params, elapsed);
+}
+
+#[tauri::command]
+pub fn get_performance_report(limit: Option<usize>) -> performance::PerformanceReport {
+    performance::get_performance_report_impl(limit)
+}
+
+pub fn write_recovery_backups(app: &AppHandle) {
+    recovery::write_recovery_backups_impl(app);
+}
+
+pub fn discard_recovery_backups_on_exit() {
+    recovery::discard_recovery_backups_on_exit_impl();
+}
+
+#[tauri::command]
+pub fn list_recovery_files(
+    state: State<'_, AppState>,
+) -> Result<Vec<recovery::RecoveryFileSummary>, String> {
+    recovery::list_recovery_files_impl(state)
+}
+
+#[tauri::command]
+pub async fn restore_recovery_file(
+    state: State<'_, AppState>,
+    id: String,
+    _timer: CommandTimer,
+) -> Result<FileInfo, String> {
+    recovery::restore_recovery_file_impl(state, id).await
+}
+
+#[tauri::command]
+pub fn discard_recovery_file(id: String) -> Result<bool, String> {
+    recovery::discard_recovery_file_impl(id)
+}
+
+pub fn sync_shared_filter_rule_groups_watch(app: &AppHandle) {
+    config::sync_shared_filter_rule_groups_watch_impl(app);
+}
+
+pub fn handle_shared_filter_rule_groups_watch_batch(
+    app: &AppHandle,
+    batch: Option<&crate::state::FileWatchBatch>,
+    newly_watched: bool,
+) {
+    config::handle_shared_filter_rule_groups_watch_batch_impl(app, batch, newly_watched);
+}
+
+pub fn run_idle_maintenance_tick(app: &AppHandle, tracker: &mut IdleActivityTracker) {
+    idle_maintenance::idle_maintenance_tick_impl(app, tracker);
+}
+
+#[tauri::command]
+pub fn run_idle_maintenance(state: State<'_, AppState>) -> IdleMaintenanceReport {
+    idle_maintenance::run_idle_maintenance_impl(&state, idle_maintenance::load_idle_trim_settings())
+}
+
 pub fn load_main_window_state_in_config() -> Option<PersistedWindowState> {
     config::load_main_window_state_in_config_impl().map(|window_state| PersistedWindowState {
         width: window_state.width,
@@ -199,8 +514,30 @@ pub fn save_main_window_state_in_config(
 }
 
 #[tauri::command]
-pub fn save_config(config: AppConfig) -> Result<(), String> {
-    config::save_config_impl(config)
+pub fn save_config(app: AppHandle, config: AppConfig) -> Result<(), String> {
+    config::save_config_impl(config)?;
+    config::sync_shared_filter_rule_groups_watch_impl(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn add_recent_file(path: String) -> Result<Vec<String>, String> {
+    config::add_recent_path_impl("files", path)
+}
+
+#[tauri::command]
+pub fn add_recent_folder(path: String) -> Result<Vec<String>, String> {
+    config::add_recent_path_impl("folders", path)
+}
+
+#[tauri::command]
+pub fn remove_recent(kind: String, path: String) -> Result<Vec<String>, String> {
+    config::remove_recent_path_impl(&kind, path)
+}
+
+#[tauri::command]
+pub fn clear_recent(kind: String) -> Result<(), String> {
+    config::clear_recent_paths_impl(&kind)
 }
 
 #[tauri::command]
@@ -218,6 +555,14 @@ pub fn import_filter_rule_groups(path: String) -> Result<Vec<FilterRuleGroupConf
     config::import_filter_rule_groups_impl(path)
 }
 
+#[tauri::command]
+pub fn diff_filter_rule_groups(
+    left: Vec<FilterRuleGroupConfig>,
+    right: Vec<FilterRuleGroupConfig>,
+) -> Vec<config::FilterRuleGroupDiff> {
+    config::diff_filter_rule_groups_impl(left, right)
+}
+
 #[tauri::command]
 pub fn export_filter_rule_groups(
     path: String,
@@ -227,31 +572,336 @@ pub fn export_filter_rule_groups(
 }
 
 #[tauri::command]
-pub fn get_startup_paths(state: State<'_, AppState>) -> Vec<String> {
+pub fn set_rule_alert(
+    group: String,
+    rule_index: usize,
+    options: Option<FilterRuleAlertOptions>,
+) -> Result<Vec<FilterRuleGroupConfig>, String> {
+    config::set_rule_alert_impl(group, rule_index, options)
+}
+
+#[tauri::command]
+pub async fn export_filtered_view(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    rules: Vec<FilterRuleInput>,
+    format: String,
+    path: String,
+    _timer: CommandTimer,
+) -> Result<filtered_export::FilteredViewExportPayload, String> {
+    filtered_export::export_filtered_view_impl(state, id, rules, format, path).await
+}
+
+#[tauri::command]
+pub async fn export_document(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    format: String,
+    options: document_export::DocumentExportOptions,
+    _timer: CommandTimer,
+) -> Result<document_export::DocumentExportPayload, String> {
+    document_export::export_document_impl(state, id, format, options).await
+}
+
+#[tauri::command]
+pub fn get_startup_paths(state: State<'_, AppState>) -> Vec<StartupPath> {
     config::get_startup_paths_impl(state)
 }
 
 #[tauri::command]
 pub async fn translate_document_text(
     request: translation::TranslationRequest,
+    _timer: CommandTimer,
 ) -> Result<String, String> {
     translation::translate_document_text_impl(request).await
 }
 
 #[tauri::command]
-pub fn get_document_version(state: State<'_, AppState>, id: String) -> Result<u64, String> {
+pub fn detect_csv_dialect(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<csv_dialect::CsvDialectPayload, String> {
+    csv_dialect::detect_csv_dialect_impl(state, id)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn filter_json_lines(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    field_path: String,
+    operator: String,
+    value: Option<String>,
+    project_fields: Option<Vec<String>>,
+    include_line_text: Option<bool>,
+    max_results: Option<usize>,
+    _timer: CommandTimer,
+) -> Result<json_lines::JsonLinesFilterResultPayload, String> {
+    json_lines::filter_json_lines_impl(
+        state,
+        id,
+        field_path,
+        operator,
+        value,
+        project_fields,
+        include_line_text,
+        max_results,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn expand_json_lines_view(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    _timer: CommandTimer,
+) -> Result<FileInfo, String> {
+    json_lines::expand_json_lines_view_impl(state, id).await
+}
+
+#[tauri::command]
+pub fn map_derived_view_line(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    line: usize,
+) -> Result<json_lines::DerivedViewLinePayload, String> {
+    json_lines::map_derived_view_line_impl(state, id, line)
+}
+
+#[tauri::command]
+pub async fn open_log_series(
+    state: State<'_, AppState>,
+    path: String,
+    mode: String,
+    _timer: CommandTimer,
+) -> Result<log_series::LogSeriesOpenResultPayload, String> {
+    log_series::open_log_series_impl(state, path, mode).await
+}
+
+#[tauri::command]
+pub fn map_log_series_line(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    line: usize,
+) -> Result<log_series::LogSeriesLinePayload, String> {
+    log_series::map_log_series_line_impl(state, id, line)
+}
+
+#[tauri::command]
+pub fn promote_document_to_full_mode(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<bool, String> {
+    preview_mode::promote_document_to_full_mode_impl(state, id)
+}
+
+#[tauri::command]
+pub async fn open_virtual_document(
+    paths: Vec<String>,
+    name: Option<String>,
+    _timer: CommandTimer,
+) -> Result<virtual_document::VirtualDocumentInfo, String> {
+    virtual_document::open_virtual_document_impl(paths, name).await
+}
+
+#[tauri::command]
+pub async fn get_virtual_document_lines(
+    id: DocumentId,
+    start_line: usize,
+    max_lines: usize,
+    _timer: CommandTimer,
+) -> Result<Vec<virtual_document::VirtualLinePayload>, String> {
+    virtual_document::get_virtual_document_lines_impl(id, start_line, max_lines).await
+}
+
+#[tauri::command]
+pub async fn search_virtual_document(
+    id: DocumentId,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    max_results: usize,
+    _timer: CommandTimer,
+) -> Result<virtual_document::VirtualSearchPayload, String> {
+    virtual_document::search_virtual_document_impl(id, keyword, mode, case_sensitive, max_results)
+        .await
+}
+
+#[tauri::command]
+pub async fn filter_virtual_document(
+    id: DocumentId,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    max_lines: usize,
+    _timer: CommandTimer,
+) -> Result<virtual_document::VirtualFilterPayload, String> {
+    virtual_document::filter_virtual_document_impl(id, keyword, mode, case_sensitive, max_lines)
+        .await
+}
+
+#[tauri::command]
+pub fn close_virtual_document(id: DocumentId) -> bool {
+    virtual_document::close_virtual_document_impl(id)
+}
+
+#[tauri::command]
+pub async fn search_streaming_document(
+    id: DocumentId,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    start_line: Option<usize>,
+    max_results: usize,
+    _timer: CommandTimer,
+) -> Result<streaming_document::StreamingSearchPayload, String> {
+    streaming_document::search_streaming_document_impl(
+        id,
+        keyword,
+        mode,
+        case_sensitive,
+        start_line,
+        max_results,
+    )
+    .await
+}
+
+#[tauri::command]
+pub fn get_hex_view_chunk(
+    id: DocumentId,
+    start_offset: u64,
+    length: usize,
+) -> Result<hex_document::HexViewChunkPayload, String> {
+    hex_document::get_hex_view_chunk_impl(id, start_offset, length)
+}
+
+#[tauri::command]
+pub fn edit_bytes(
+    id: DocumentId,
+    offset: u64,
+    old_bytes: Vec<u8>,
+    new_bytes: Vec<u8>,
+) -> Result<hex_document::HexEditResultPayload, String> {
+    hex_document::edit_bytes_impl(id, offset, old_bytes, new_bytes)
+}
+
+#[tauri::command]
+pub fn undo_byte_edit(id: DocumentId) -> Result<hex_document::HexEditResultPayload, String> {
+    hex_document::undo_byte_edit_impl(id)
+}
+
+#[tauri::command]
+pub fn redo_byte_edit(id: DocumentId) -> Result<hex_document::HexEditResultPayload, String> {
+    hex_document::redo_byte_edit_impl(id)
+}
+
+#[tauri::command]
+pub async fn sort_csv_by_column(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    column: usize,
+    order: Option<String>,
+    numeric: Option<bool>,
+    has_header: Option<bool>,
+    _timer: CommandTimer,
+) -> Result<csv_dialect::CsvSortResultPayload, String> {
+    csv_dialect::sort_csv_by_column_impl(state, id, column, order, numeric, has_header).await
+}
+
+#[tauri::command]
+pub async fn filter_csv_rows(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    column: usize,
+    predicate: csv_dialect::CsvRowPredicate,
+    has_header: Option<bool>,
+    max_rows: Option<usize>,
+    _timer: CommandTimer,
+) -> Result<csv_dialect::CsvFilterResultPayload, String> {
+    csv_dialect::filter_csv_rows_impl(state, id, column, predicate, has_header, max_rows).await
+}
+
+#[tauri::command]
+pub fn get_document_version(state: State<'_, AppState>, id: DocumentId) -> Result<u64, String> {
     document::get_document_version_impl(state, id)
 }
 
+#[tauri::command]
+pub fn set_document_word_wrap(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    word_wrap: Option<bool>,
+) -> Result<(), String> {
+    document::set_document_word_wrap_impl(state, id, word_wrap)
+}
+
+#[tauri::command]
+pub fn get_visual_line_count(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    max_columns: usize,
+    tab_width: Option<u8>,
+) -> Result<document::VisualLineCountPayload, String> {
+    document::get_visual_line_count_impl(state, id, max_columns, tab_width)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn find_extreme_lines(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    metric: String,
+    top_n: usize,
+    keyword: Option<String>,
+    mode: Option<String>,
+    case_sensitive: Option<bool>,
+    tab_width: Option<u8>,
+    _timer: CommandTimer,
+) -> Result<extreme_lines::ExtremeLinesPayload, String> {
+    extreme_lines::find_extreme_lines_impl(
+        state,
+        id,
+        metric,
+        top_n,
+        keyword,
+        mode,
+        case_sensitive,
+        tab_width,
+    )
+    .await
+}
+
+#[tauri::command]
+pub fn get_word_at(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    char_offset: usize,
+) -> Result<Option<document::WordAtPayload>, String> {
+    document::get_word_at_impl(state, id, char_offset)
+}
+
 #[tauri::command]
 pub async fn get_outline(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     file_type: String,
+    _timer: CommandTimer,
 ) -> Result<Vec<outline::OutlineNode>, String> {
     outline::get_outline_impl(state, id, file_type).await
 }
 
+#[tauri::command]
+pub async fn export_outline(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    file_type: String,
+    format: String,
+    path: Option<String>,
+    _timer: CommandTimer,
+) -> Result<String, String> {
+    outline::export_outline_impl(state, id, file_type, format, path).await
+}
+
 #[tauri::command]
 pub fn filter_outline_nodes(
     nodes: Vec<outline::OutlineNode>,
@@ -260,19 +910,256 @@ pub fn filter_outline_nodes(
     outline::filter_outline_nodes_impl(nodes, keyword)
 }
 
+#[tauri::command]
+pub fn get_patch_hunks(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<Vec<patch_hunks::PatchHunk>, String> {
+    patch_hunks::get_patch_hunks_impl(state, id)
+}
+
+#[tauri::command]
+pub fn get_adjacent_patch_hunk(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    line: usize,
+    forward: bool,
+) -> Result<Option<patch_hunks::PatchHunk>, String> {
+    patch_hunks::get_adjacent_patch_hunk_impl(state, id, line, forward)
+}
+
+#[tauri::command]
+pub fn resolve_patch_hunk_source(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    line: usize,
+) -> Result<Option<patch_hunks::PatchHunkSourceLocation>, String> {
+    patch_hunks::resolve_patch_hunk_source_impl(state, id, line)
+}
+
+#[tauri::command]
+pub fn get_hover_info(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    char_offset: usize,
+) -> Result<Option<structured::HoverInfoPayload>, String> {
+    structured::get_hover_info_impl(state, id, char_offset)
+}
+
+#[tauri::command]
+pub fn resolve_structured_path(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    path_expression: String,
+) -> Result<Option<structured::StructuredPathLocation>, String> {
+    structured::resolve_structured_path_impl(state, id, path_expression)
+}
+
+#[tauri::command]
+pub fn validate_ini_document(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<Vec<ini::IniDuplicateKey>, String> {
+    ini::validate_ini_document_impl(state, id)
+}
+
+#[tauri::command]
+pub fn set_line_annotations(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    source: String,
+    annotations: Vec<LineAnnotation>,
+) -> Result<(), String> {
+    annotations::set_line_annotations_impl(state, id, source, annotations)
+}
+
+#[tauri::command]
+pub fn get_line_annotations(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_line: usize,
+    end_line: usize,
+) -> Result<Vec<annotations::LineAnnotationEntry>, String> {
+    annotations::get_line_annotations_impl(state, id, start_line, end_line)
+}
+
+#[tauri::command]
+pub fn clear_line_annotations(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    source: Option<String>,
+) -> Result<(), String> {
+    annotations::clear_line_annotations_impl(state, id, source)
+}
+
+#[tauri::command]
+pub fn add_temp_highlight(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    keyword: String,
+    color: String,
+) -> Result<Vec<TempHighlight>, String> {
+    temp_highlights::add_temp_highlight_impl(state, id, keyword, color)
+}
+
+#[tauri::command]
+pub fn list_temp_highlights(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<Vec<TempHighlight>, String> {
+    temp_highlights::list_temp_highlights_impl(state, id)
+}
+
+#[tauri::command]
+pub fn clear_temp_highlights(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    keyword: Option<String>,
+) -> Result<(), String> {
+    temp_highlights::clear_temp_highlights_impl(state, id, keyword)
+}
+
+#[tauri::command]
+pub fn get_temp_highlight_ranges(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_line: usize,
+    end_line: usize,
+) -> Result<Vec<temp_highlights::TempHighlightRange>, String> {
+    temp_highlights::get_temp_highlight_ranges_impl(state, id, start_line, end_line)
+}
+
+#[tauri::command]
+pub async fn get_bracket_depths(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_line: usize,
+    end_line: usize,
+    _timer: CommandTimer,
+) -> Result<bracket_depth::BracketDepthPayload, String> {
+    bracket_depth::get_bracket_depths_impl(state, id, start_line, end_line).await
+}
+
+#[tauri::command]
+pub async fn get_fold_ranges(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    min_block_lines: Option<usize>,
+    _timer: CommandTimer,
+) -> Result<fold_ranges::FoldRangesPayload, String> {
+    fold_ranges::get_fold_ranges_impl(state, id, min_block_lines).await
+}
+
 #[tauri::command]
 pub async fn compare_documents_by_line(
     state: State<'_, AppState>,
     source_id: String,
     target_id: String,
+    _timer: CommandTimer,
 ) -> Result<diff::LineDiffResult, String> {
     diff::compare_documents_by_line_impl(state, source_id, target_id).await
 }
 
+#[tauri::command]
+pub async fn compare_document_with_text(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    text: String,
+    _timer: CommandTimer,
+) -> Result<diff::LineDiffResult, String> {
+    diff::compare_document_with_text_impl(state, id, text).await
+}
+
+#[tauri::command]
+pub async fn convert_folder_encoding(
+    app: AppHandle,
+    root: String,
+    from: Option<String>,
+    to: String,
+    extensions: Vec<String>,
+    dry_run: bool,
+    _timer: CommandTimer,
+) -> Result<folder_encoding::FolderEncodingReport, String> {
+    folder_encoding::convert_folder_encoding_impl(app, root, from, to, extensions, dry_run).await
+}
+
+#[tauri::command]
+pub async fn normalize_folder_line_endings(
+    app: AppHandle,
+    root: String,
+    target: String,
+    extensions: Vec<String>,
+    dry_run: bool,
+    _timer: CommandTimer,
+) -> Result<folder_line_endings::FolderLineEndingsReport, String> {
+    folder_line_endings::normalize_folder_line_endings_impl(app, root, target, extensions, dry_run)
+        .await
+}
+
+#[tauri::command]
+pub async fn compare_with_snapshot(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    snapshot_id: String,
+    _timer: CommandTimer,
+) -> Result<diff::LineDiffResult, String> {
+    diff::compare_with_snapshot_impl(state, id, snapshot_id).await
+}
+
+#[tauri::command]
+pub fn take_document_snapshot(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<snapshots::DocumentSnapshotSummary, String> {
+    snapshots::take_document_snapshot_impl(state, id)
+}
+
+#[tauri::command]
+pub fn list_document_snapshots(id: DocumentId) -> Vec<snapshots::DocumentSnapshotSummary> {
+    snapshots::list_document_snapshots_impl(id)
+}
+
+#[tauri::command]
+pub async fn compare_text_fragments(
+    state: State<'_, AppState>,
+    source: diff::TextFragment,
+    target: diff::TextFragment,
+    _timer: CommandTimer,
+) -> Result<diff::TextFragmentDiffResult, String> {
+    diff::compare_text_fragments_impl(state, source, target).await
+}
+
+#[tauri::command]
+pub async fn search_in_diff(
+    state: State<'_, AppState>,
+    source_id: String,
+    target_id: String,
+    keyword: String,
+    side: Option<String>,
+    _timer: CommandTimer,
+) -> Result<diff::DiffSearchResult, String> {
+    diff::search_in_diff_impl(state, source_id, target_id, keyword, side).await
+}
+
+#[tauri::command]
+pub async fn compare_document_with_clipboard(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: DocumentId,
+    _timer: CommandTimer,
+) -> Result<diff::LineDiffResult, String> {
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|error| format!("Failed to read text from system clipboard: {error}"))?;
+    diff::compare_document_with_text_impl(state, id, text).await
+}
+
 #[tauri::command]
 pub async fn get_unsaved_change_line_numbers(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
+    _timer: CommandTimer,
 ) -> Result<Vec<usize>, String> {
     diff::get_unsaved_change_line_numbers_impl(state, id).await
 }
@@ -280,9 +1167,10 @@ pub async fn get_unsaved_change_line_numbers(
 #[tauri::command]
 pub async fn search_diff_panel_aligned_row_matches(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     keyword: String,
     aligned_present: Vec<bool>,
+    _timer: CommandTimer,
 ) -> Result<Vec<usize>, String> {
     diff::search_diff_panel_aligned_row_matches_impl(state, id, keyword, aligned_present).await
 }
@@ -293,6 +1181,7 @@ pub async fn preview_aligned_diff_state(
     aligned_target_lines: Vec<String>,
     aligned_source_present: Vec<bool>,
     aligned_target_present: Vec<bool>,
+    _timer: CommandTimer,
 ) -> Result<diff::LineDiffResult, String> {
     diff::preview_aligned_diff_state_impl(
         aligned_source_lines,
@@ -314,6 +1203,7 @@ pub async fn apply_aligned_diff_panel_copy(
     aligned_target_lines: Vec<String>,
     aligned_source_present: Vec<bool>,
     aligned_target_present: Vec<bool>,
+    _timer: CommandTimer,
 ) -> Result<diff::ApplyAlignedDiffPanelCopyResult, String> {
     diff::apply_aligned_diff_panel_copy_impl(
         from_side,
@@ -340,6 +1230,7 @@ pub async fn apply_aligned_diff_edit(
     aligned_source_present: Vec<bool>,
     aligned_target_present: Vec<bool>,
     edited_trailing_newline: bool,
+    _timer: CommandTimer,
 ) -> Result<diff::ApplyAlignedDiffEditResult, String> {
     diff::apply_aligned_diff_edit_impl(
         state,
@@ -355,12 +1246,59 @@ pub async fn apply_aligned_diff_edit(
     .await
 }
 
+#[tauri::command]
+pub async fn apply_diff_hunk(
+    state: State<'_, AppState>,
+    source_id: String,
+    target_id: String,
+    start_row_index: usize,
+    end_row_index: usize,
+    _timer: CommandTimer,
+) -> Result<diff::ApplyAlignedDiffEditResult, String> {
+    diff::apply_diff_hunk_impl(state, source_id, target_id, start_row_index, end_row_index).await
+}
+
+#[tauri::command]
+pub async fn merge_documents(
+    state: State<'_, AppState>,
+    base_id: String,
+    ours_id: String,
+    theirs_id: String,
+    result_id: String,
+    _timer: CommandTimer,
+) -> Result<merge::MergeDocumentsResult, String> {
+    merge::merge_documents_impl(state, base_id, ours_id, theirs_id, result_id).await
+}
+
+#[tauri::command]
+pub fn list_merge_conflicts(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<merge::MergeConflictsPayload, String> {
+    merge::list_merge_conflicts_impl(state, id)
+}
+
+#[tauri::command]
+pub fn resolve_merge_conflict(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    conflict_index: usize,
+    resolution: String,
+    expected_document_version: u64,
+) -> Result<merge::MergeConflictsPayload, String> {
+    merge::resolve_merge_conflict_impl(
+        state,
+        id,
+        conflict_index,
+        resolution,
+        expected_document_version,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::{collect_external_file_change_document_ids_impl, external_change_notified_ids};
-    use crate::state::{default_line_ending, AppState, Document, FileFingerprint};
-    use encoding_rs::UTF_8;
-    use ropey::Rope;
+    use crate::state::{AppState, Document, FileFingerprint};
     use std::path::PathBuf;
     use uuid::Uuid;
 
@@ -369,22 +1307,9 @@ mod tests {
         saved_file_fingerprint: Option<FileFingerprint>,
     ) -> Document {
         Document {
-            rope: Rope::new(),
-            saved_rope: Rope::new(),
-            encoding: UTF_8,
-            saved_encoding: UTF_8.name().to_string(),
-            line_ending: default_line_ending(),
-            saved_line_ending: default_line_ending(),
             path: Some(path),
-            syntax_override: None,
-            document_version: 0,
-            saved_document_version: 0,
-            next_edit_operation_id: 1,
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
-            saved_undo_depth: 0,
-            saved_undo_operation_id: None,
             saved_file_fingerprint,
+            ..Document::for_test("", None)
         }
     }
 
@@ -422,15 +1347,48 @@ mod tests {
             ),
         );
 
-        let changed_ids = collect_external_file_change_document_ids_impl(&state, true);
+        let changed_ids = collect_external_file_change_document_ids_impl(&state, true, |_| true);
         assert_eq!(changed_ids, vec!["doc-1".to_string()]);
         assert!(cache.contains_key("doc-1"));
 
         let changed_ids_when_disabled =
-            collect_external_file_change_document_ids_impl(&state, false);
+            collect_external_file_change_document_ids_impl(&state, false, |_| true);
         assert!(changed_ids_when_disabled.is_empty());
         assert!(!cache.contains_key("doc-1"));
 
         cache.clear();
     }
+
+    #[test]
+    fn collect_external_change_ids_should_only_check_selected_paths() {
+        let cache = external_change_notified_ids();
+        cache.clear();
+
+        let state = AppState::new(Vec::new());
+        let path = make_missing_test_path();
+        state.documents.insert(
+            "doc-selected".to_string(),
+            make_external_tracking_document(
+                path.clone(),
+                Some(FileFingerprint {
+                    size_bytes: 1,
+                    modified_unix_millis: Some(1),
+                }),
+            ),
+        );
+
+        let changed_ids =
+            collect_external_file_change_document_ids_impl(&state, true, |candidate| {
+                candidate != path.as_path()
+            });
+        assert!(changed_ids.is_empty());
+
+        let changed_ids =
+            collect_external_file_change_document_ids_impl(&state, true, |candidate| {
+                candidate == path.as_path()
+            });
+        assert_eq!(changed_ids, vec!["doc-selected".to_string()]);
+
+        cache.clear();
+    }
 }
@@ -0,0 +1,79 @@
+use super::*;
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineAnnotationEntry {
+    pub(super) source: String,
+    #[serde(flatten)]
+    pub(super) annotation: LineAnnotation,
+}
+
+fn normalize_annotation_source(source: &str) -> Result<String, String> {
+    let trimmed = source.trim();
+    if trimmed.is_empty() {
+        return Err("Annotation source must not be empty".to_string());
+    }
+
+    Ok(trimmed.to_string())
+}
+
+pub(super) fn set_line_annotations_impl(
+    state: State<'_, AppState>,
+    id: String,
+    source: String,
+    annotations: Vec<LineAnnotation>,
+) -> Result<(), String> {
+    let source = normalize_annotation_source(&source)?;
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    let line_count = doc.rope.len_lines();
+    let annotations = annotations
+        .into_iter()
+        .filter(|annotation| annotation.line <= line_count)
+        .collect();
+    doc.line_annotations.set(source, annotations);
+    Ok(())
+}
+
+pub(super) fn get_line_annotations_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_line: usize,
+    end_line: usize,
+) -> Result<Vec<LineAnnotationEntry>, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    Ok(doc
+        .line_annotations
+        .range(start_line.max(1), end_line)
+        .into_iter()
+        .map(|(source, annotation)| LineAnnotationEntry {
+            source: source.to_string(),
+            annotation: annotation.clone(),
+        })
+        .collect())
+}
+
+pub(super) fn clear_line_annotations_impl(
+    state: State<'_, AppState>,
+    id: String,
+    source: Option<String>,
+) -> Result<(), String> {
+    let source = source
+        .as_deref()
+        .map(normalize_annotation_source)
+        .transpose()?;
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    doc.line_annotations.clear(source.as_deref());
+    Ok(())
+}
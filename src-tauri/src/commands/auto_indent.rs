@@ -0,0 +1,188 @@
+// Indentation for the line Enter creates at a caret.
+//
+// The new line keeps the caret line's leading whitespace and gains one level
+// after an unclosed `{`, `[` or `(`, or after a `:` that opens a block in
+// Python and YAML. With a tree-sitter grammar the opener must be a real token,
+// so brackets in strings and comments do not indent. One level is a tab or
+// `tabWidth` spaces, following the `tabIndentMode` setting.
+
+use super::config;
+use super::outline::{caret_context_source, parse_source_tree, tree_sitter_language_for_syntax};
+use super::settings::normalize_tab_width;
+use super::syntax::resolve_document_syntax_key;
+use crate::state::AppState;
+use ropey::Rope;
+use tauri::State;
+use tree_sitter::{Language, Node};
+
+fn is_bracket_opener(value: char) -> bool {
+    matches!(value, '(' | '[' | '{')
+}
+
+// Syntaxes where a trailing `:` opens an indented block.
+fn colon_opens_block(syntax_key: &str) -> bool {
+    matches!(syntax_key, "python" | "yaml")
+}
+
+// `if x:`, `def f():`, `else:` and friends; a `:` in a dict, slice, lambda or
+// annotation has another parent.
+fn is_block_colon_parent(kind: &str) -> bool {
+    kind.ends_with("_statement")
+        || kind.ends_with("_definition")
+        || kind.ends_with("_clause")
+        || kind == "block_mapping_pair"
+        // Half-typed headers such as `def f():` with no body yet.
+        || kind == "ERROR"
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    let trimmed = line.trim_start_matches([' ', '\t']);
+    &line[..line.len() - trimmed.len()]
+}
+
+fn indent_unit(use_tabs: bool, tab_width: u8) -> String {
+    if use_tabs {
+        "\t".to_string()
+    } else {
+        " ".repeat(normalize_tab_width(tab_width) as usize)
+    }
+}
+
+fn token_opens_block(node: Node<'_>, syntax_key: &str) -> bool {
+    if node.is_named() || node.child_count() > 0 {
+        return false;
+    }
+
+    match node.kind() {
+        "(" | "[" | "{" => true,
+        ":" => {
+            colon_opens_block(syntax_key)
+                && node
+                    .parent()
+                    .is_some_and(|parent| is_block_colon_parent(parent.kind()))
+        }
+        _ => false,
+    }
+}
+
+// Parses only the text around `byte_offset`; see `caret_context_source`.
+fn syntax_opens_block(
+    rope: &Rope,
+    language: &Language,
+    syntax_key: &str,
+    byte_offset: usize,
+) -> Option<bool> {
+    let (source, window_start) = caret_context_source(rope, byte_offset);
+    let tree = parse_source_tree(&source, language).ok()?;
+    let window_offset = byte_offset - window_start;
+    let node = tree
+        .root_node()
+        .descendant_for_byte_range(window_offset, window_offset + 1)?;
+
+    Some(token_opens_block(node, syntax_key))
+}
+
+fn compute_indent(
+    rope: &Rope,
+    char_offset: usize,
+    syntax_key: &str,
+    language: Option<&Language>,
+    unit: &str,
+) -> String {
+    let line_index = rope.char_to_line(char_offset);
+    let line_start = rope.line_to_char(line_index);
+    let before_caret = rope.slice(line_start..char_offset).to_string();
+    let base = leading_whitespace(&before_caret).to_string();
+
+    let trimmed = before_caret.trim_end();
+    let Some(last_char) = trimmed.chars().next_back() else {
+        return base;
+    };
+
+    let text_opens_block =
+        is_bracket_opener(last_char) || (last_char == ':' && colon_opens_block(syntax_key));
+    let opens_block = match language {
+        Some(language) if text_opens_block => {
+            let byte_offset = rope.char_to_byte(line_start) + trimmed.len() - 1;
+            syntax_opens_block(rope, language, syntax_key, byte_offset).unwrap_or(text_opens_block)
+        }
+        _ => text_opens_block,
+    };
+
+    if opens_block {
+        base + unit
+    } else {
+        base
+    }
+}
+
+pub(super) async fn compute_auto_indent_impl(
+    state: State<'_, AppState>,
+    id: String,
+    char_offset: usize,
+) -> Result<String, String> {
+    let (rope, syntax_key) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (doc.rope.clone(), resolve_document_syntax_key(&doc))
+    };
+    let app_config = config::load_config_impl().unwrap_or_default();
+    let unit = indent_unit(app_config.tab_indent_mode == "tabs", app_config.tab_width);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let char_offset = char_offset.min(rope.len_chars());
+        let language = tree_sitter_language_for_syntax(&syntax_key);
+        compute_indent(&rope, char_offset, &syntax_key, language.as_ref(), &unit)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indent_at(source: &str, syntax_key: &str, marker: &str) -> String {
+        let source_without_marker = source.replacen('|', "", 1);
+        let rope = Rope::from_str(&source_without_marker);
+        let byte_offset = source.find(marker).expect("marker should exist");
+        let language = tree_sitter_language_for_syntax(syntax_key);
+        compute_indent(
+            &rope,
+            rope.byte_to_char(byte_offset),
+            syntax_key,
+            language.as_ref(),
+            "    ",
+        )
+    }
+
+    #[test]
+    fn compute_indent_should_add_a_level_after_openers() {
+        assert_eq!(indent_at("fn main() {|\n}\n", "rust", "|"), "    ");
+        assert_eq!(indent_at("  let a = [|\n", "rust", "|"), "      ");
+        assert_eq!(indent_at("  let a = \"{|\";\n", "rust", "|"), "  ");
+        assert_eq!(indent_at("\tx = 1;|\n", "rust", "|"), "\t");
+        assert_eq!(indent_at("notes {|\n", "plain_text", "|"), "    ");
+    }
+
+    #[test]
+    fn compute_indent_should_indent_block_colons_only() {
+        assert_eq!(indent_at("def run():|\n", "python", "|"), "    ");
+        assert_eq!(
+            indent_at("    if ok:|\n        pass\n", "python", "|"),
+            "        "
+        );
+        assert_eq!(indent_at("x = data[1:|]\n", "python", "|"), "");
+        assert_eq!(indent_at("server:|\n  port: 1\n", "yaml", "|"), "    ");
+        assert_eq!(indent_at("note: done:|\n", "plain_text", "|"), "");
+    }
+
+    #[test]
+    fn indent_unit_should_follow_tab_settings() {
+        assert_eq!(indent_unit(true, 2), "\t");
+        assert_eq!(indent_unit(false, 2), "  ");
+        assert_eq!(indent_unit(false, 0), " ");
+    }
+}
@@ -0,0 +1,298 @@
+use super::outline::{caret_context_source, parse_source_tree, tree_sitter_language_for_syntax};
+use super::syntax::resolve_document_syntax_key;
+use crate::state::AppState;
+use ropey::Rope;
+use tauri::State;
+use tree_sitter::{Language, Node};
+
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoClosePairPayload {
+    pub should_close: bool,
+    pub closing_char: Option<String>,
+    // One of: default, unsupportedCharacter, inString, inComment, closerAhead,
+    // wordCharacterAhead, wordCharacterBefore.
+    pub reason: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SyntaxContext {
+    Code,
+    String,
+    Comment,
+}
+
+fn closing_char_for(typed_char: char) -> Option<char> {
+    match typed_char {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        '"' => Some('"'),
+        '\'' => Some('\''),
+        '`' => Some('`'),
+        _ => None,
+    }
+}
+
+fn is_quote(value: char) -> bool {
+    matches!(value, '"' | '\'' | '`')
+}
+
+fn is_word_char(value: char) -> bool {
+    value.is_alphanumeric() || value == '_'
+}
+
+fn is_string_node_kind(kind: &str) -> bool {
+    let kind = kind.to_ascii_lowercase();
+    (kind.contains("string") || kind.contains("char_literal") || kind == "character_literal")
+        && !kind.contains("interpolation")
+}
+
+fn is_comment_node_kind(kind: &str) -> bool {
+    kind.to_ascii_lowercase().contains("comment")
+}
+
+fn classify_node_at(node: Node<'_>, source: &str, byte_offset: usize) -> SyntaxContext {
+    let mut current = Some(node);
+
+    while let Some(node) = current {
+        let kind = node.kind();
+        if is_comment_node_kind(kind) {
+            let text = &source[node.start_byte()..node.end_byte()];
+            // Line comments end right before the newline, so the caret sitting at
+            // their end is still inside the comment; block comments are closed.
+            let is_closed_block = text.ends_with("*/") || text.ends_with("-->");
+            let inside_end = if is_closed_block {
+                byte_offset < node.end_byte()
+            } else {
+                byte_offset <= node.end_byte()
+            };
+            if node.start_byte() < byte_offset && inside_end {
+                return SyntaxContext::Comment;
+            }
+        } else if is_string_node_kind(kind)
+            && node.start_byte() < byte_offset
+            && byte_offset < node.end_byte()
+        {
+            return SyntaxContext::String;
+        }
+
+        current = node.parent();
+    }
+
+    SyntaxContext::Code
+}
+
+fn resolve_syntax_context(source: &str, language: &Language, byte_offset: usize) -> SyntaxContext {
+    let Ok(tree) = parse_source_tree(source, language) else {
+        return SyntaxContext::Code;
+    };
+
+    // Look at the node just before the caret as well, so a caret at the end of
+    // a token is attributed to the token it follows.
+    let lookup_start = byte_offset.saturating_sub(1);
+    let Some(node) = tree
+        .root_node()
+        .descendant_for_byte_range(lookup_start, byte_offset)
+    else {
+        return SyntaxContext::Code;
+    };
+
+    classify_node_at(node, source, byte_offset)
+}
+
+// Returns true when the rest of the caret's line already holds more closers
+// than openers for the pair, i.e. the typed opener would adopt an orphan closer.
+fn has_unbalanced_closer_on_line(line_text: &str, opener: char, closer: char) -> bool {
+    let mut balance = 0isize;
+    for ch in line_text.chars() {
+        if ch == opener {
+            balance += 1;
+        } else if ch == closer {
+            balance -= 1;
+        }
+    }
+
+    balance < 0
+}
+
+fn decide_auto_close(
+    rope: &Rope,
+    char_offset: usize,
+    typed_char: char,
+    context: SyntaxContext,
+) -> AutoClosePairPayload {
+    let Some(closing_char) = closing_char_for(typed_char) else {
+        return AutoClosePairPayload {
+            should_close: false,
+            closing_char: None,
+            reason: "unsupportedCharacter".to_string(),
+        };
+    };
+    let reject = |reason: &str| AutoClosePairPayload {
+        should_close: false,
+        closing_char: Some(closing_char.to_string()),
+        reason: reason.to_string(),
+    };
+
+    match context {
+        SyntaxContext::String => return reject("inString"),
+        SyntaxContext::Comment => return reject("inComment"),
+        SyntaxContext::Code => {}
+    }
+
+    let next_char = (char_offset < rope.len_chars()).then(|| rope.char(char_offset));
+    let previous_char = (char_offset > 0).then(|| rope.char(char_offset - 1));
+
+    if is_quote(typed_char) {
+        if next_char == Some(typed_char) {
+            return reject("closerAhead");
+        }
+        if previous_char.is_some_and(is_word_char) {
+            return reject("wordCharacterBefore");
+        }
+    } else {
+        let line_index = rope.char_to_line(char_offset);
+        let line_text = rope.line(line_index).to_string();
+        if has_unbalanced_closer_on_line(&line_text, typed_char, closing_char) {
+            return reject("closerAhead");
+        }
+    }
+
+    if next_char.is_some_and(is_word_char) {
+        return reject("wordCharacterAhead");
+    }
+
+    AutoClosePairPayload {
+        should_close: true,
+        closing_char: Some(closing_char.to_string()),
+        reason: "default".to_string(),
+    }
+}
+
+fn resolve_syntax_context_in_window(
+    rope: &Rope,
+    language: &Language,
+    char_offset: usize,
+) -> SyntaxContext {
+    let byte_offset = rope.char_to_byte(char_offset);
+    let (source, window_start) = caret_context_source(rope, byte_offset);
+
+    resolve_syntax_context(&source, language, byte_offset - window_start)
+}
+
+pub(super) async fn should_auto_close_pair_impl(
+    state: State<'_, AppState>,
+    id: String,
+    char_offset: usize,
+    typed_char: String,
+) -> Result<AutoClosePairPayload, String> {
+    let mut chars = typed_char.chars();
+    let (Some(typed_char), None) = (chars.next(), chars.next()) else {
+        return Err("Typed character must be a single character".to_string());
+    };
+
+    let (rope, syntax_key) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (doc.rope.clone(), resolve_document_syntax_key(&doc))
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let char_offset = char_offset.min(rope.len_chars());
+        let context = match (
+            closing_char_for(typed_char),
+            tree_sitter_language_for_syntax(&syntax_key),
+        ) {
+            (Some(_), Some(language)) => {
+                resolve_syntax_context_in_window(&rope, &language, char_offset)
+            }
+            _ => SyntaxContext::Code,
+        };
+
+        decide_auto_close(&rope, char_offset, typed_char, context)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context_at(source: &str, syntax_key: &str, marker: &str) -> SyntaxContext {
+        let byte_offset = source.find(marker).expect("marker should exist");
+        let language = tree_sitter_language_for_syntax(syntax_key).expect("language");
+        resolve_syntax_context(source, &language, byte_offset)
+    }
+
+    #[test]
+    fn resolve_syntax_context_should_detect_strings_and_comments() {
+        let source = "let a = \"ab|c\";\n// note|\nlet b = x|;\n";
+
+        assert!(matches!(
+            context_at(source, "rust", "|c\""),
+            SyntaxContext::String
+        ));
+        assert!(matches!(
+            context_at(source, "rust", "|\nlet b"),
+            SyntaxContext::Comment
+        ));
+        assert!(matches!(
+            context_at(source, "rust", "|;\n"),
+            SyntaxContext::Code
+        ));
+    }
+
+    #[test]
+    fn resolve_syntax_context_in_window_should_parse_only_near_the_caret() {
+        let filler = "let x = 1;\n".repeat(8 * 1024);
+        let source = format!("/* far\n{filler}let s = \"ab|c\";\n{filler}");
+        let rope = Rope::from_str(&source);
+        let language = tree_sitter_language_for_syntax("rust").expect("language");
+        let caret = rope.byte_to_char(source.find("|c").expect("marker"));
+
+        let (_, window_start) = caret_context_source(&rope, rope.char_to_byte(caret));
+        assert!(window_start > 0);
+        assert_eq!(
+            rope.line_to_byte(rope.byte_to_line(window_start)),
+            window_start
+        );
+        assert!(matches!(
+            resolve_syntax_context_in_window(&rope, &language, caret),
+            SyntaxContext::String
+        ));
+    }
+
+    #[test]
+    fn decide_auto_close_should_respect_context_and_neighbours() {
+        let rope = Rope::from_str("call(x) don t\nfoo)");
+
+        let payload = decide_auto_close(&rope, 5, '(', SyntaxContext::Code);
+        assert_eq!(payload.reason, "wordCharacterAhead");
+
+        let payload = decide_auto_close(&rope, 7, '[', SyntaxContext::Code);
+        assert!(payload.should_close);
+        assert_eq!(payload.closing_char.as_deref(), Some("]"));
+
+        let payload = decide_auto_close(&rope, 11, '\'', SyntaxContext::Code);
+        assert_eq!(payload.reason, "wordCharacterBefore");
+
+        let payload = decide_auto_close(&rope, 14, '(', SyntaxContext::Code);
+        assert_eq!(payload.reason, "closerAhead");
+
+        let payload = decide_auto_close(&rope, 7, '"', SyntaxContext::String);
+        assert_eq!(payload.reason, "inString");
+    }
+
+    #[test]
+    fn decide_auto_close_should_skip_unsupported_characters() {
+        let rope = Rope::from_str("");
+
+        let payload = decide_auto_close(&rope, 0, '<', SyntaxContext::Code);
+        assert!(!payload.should_close);
+        assert_eq!(payload.closing_char, None);
+    }
+}
@@ -0,0 +1,240 @@
+use super::file_io::{self, SaveFileBatchResultItem};
+use crate::state::AppState;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoSaveMode {
+    Off,
+    AfterDelay,
+    OnFocusChange,
+}
+
+impl AutoSaveMode {
+    fn from_config_label(label: &str) -> Self {
+        match label {
+            "afterDelay" => Self::AfterDelay,
+            "onFocusChange" => Self::OnFocusChange,
+            _ => Self::Off,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoSaveTrigger {
+    AfterDelay,
+    FocusChange,
+}
+
+impl AutoSaveTrigger {
+    fn label(self) -> &'static str {
+        match self {
+            Self::AfterDelay => "afterDelay",
+            Self::FocusChange => "focusChange",
+        }
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AutoSavedEventPayload {
+    trigger: &'static str,
+    results: Vec<SaveFileBatchResultItem>,
+}
+
+pub(super) fn load_auto_save_settings() -> (AutoSaveMode, u64) {
+    let (mode, interval_secs) = super::config::load_auto_save_settings_in_config_impl();
+    (AutoSaveMode::from_config_label(&mode), interval_secs)
+}
+
+// Untitled documents have nowhere to go, so only dirty documents with a path
+// are candidates. Returns each candidate with its document version.
+fn collect_auto_save_candidates(state: &AppState) -> Vec<(String, u64)> {
+    let mut candidates: Vec<(String, u64)> = state
+        .documents
+        .iter()
+        .filter(|entry| {
+            let doc = entry.value();
+            doc.path.is_some()
+                && (doc.has_unsaved_text_changes()
+                    || doc.encoding.name() != doc.saved_encoding
+                    || doc.line_ending != doc.saved_line_ending)
+        })
+        .map(|entry| (entry.key().clone(), entry.value().document_version))
+        .collect();
+    candidates.sort();
+    candidates
+}
+
+fn collect_auto_save_document_ids(state: &AppState) -> Vec<String> {
+    collect_auto_save_candidates(state)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect()
+}
+
+// Debounces "after delay" auto-save: a dirty document is saved once its
+// version has stayed the same for the configured delay, so further edits push
+// the save back. Ticked by the file watcher thread in lib.rs.
+#[derive(Default)]
+pub struct AutoSaveDebounce {
+    // Document id -> version last seen and when it was first seen.
+    pending: HashMap<String, (u64, Instant)>,
+    saving: Arc<AtomicBool>,
+}
+
+impl AutoSaveDebounce {
+    fn take_due_ids(
+        &mut self,
+        candidates: Vec<(String, u64)>,
+        now: Instant,
+        delay: Duration,
+    ) -> Vec<String> {
+        let mut pending = HashMap::with_capacity(candidates.len());
+        let mut due_ids = Vec::new();
+        for (id, version) in candidates {
+            let since = match self.pending.get(&id) {
+                Some((seen_version, since)) if *seen_version == version => *since,
+                _ => now,
+            };
+            if now.saturating_duration_since(since) >= delay {
+                due_ids.push(id);
+            } else {
+                pending.insert(id, (version, since));
+            }
+        }
+        self.pending = pending;
+        due_ids
+    }
+}
+
+pub(super) fn auto_save_tick_impl(app: &AppHandle, debounce: &mut AutoSaveDebounce) {
+    let (mode, delay_secs) = load_auto_save_settings();
+    if mode != AutoSaveMode::AfterDelay {
+        debounce.pending.clear();
+        return;
+    }
+    // Documents edited during a save are picked up again once it finishes.
+    if debounce.saving.load(Ordering::Acquire) {
+        return;
+    }
+
+    let candidates = collect_auto_save_candidates(&app.state::<AppState>());
+    let due_ids =
+        debounce.take_due_ids(candidates, Instant::now(), Duration::from_secs(delay_secs));
+    if due_ids.is_empty() {
+        return;
+    }
+
+    debounce.saving.store(true, Ordering::Release);
+    let saving = debounce.saving.clone();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        save_and_report(app, due_ids, AutoSaveTrigger::AfterDelay).await;
+        saving.store(false, Ordering::Release);
+    });
+}
+
+pub(super) async fn auto_save_dirty_documents_impl(
+    app: AppHandle,
+    trigger: AutoSaveTrigger,
+) -> Vec<SaveFileBatchResultItem> {
+    let ids = collect_auto_save_document_ids(&app.state::<AppState>());
+    if ids.is_empty() {
+        return Vec::new();
+    }
+
+    save_and_report(app, ids, trigger).await
+}
+
+async fn save_and_report(
+    app: AppHandle,
+    ids: Vec<String>,
+    trigger: AutoSaveTrigger,
+) -> Vec<SaveFileBatchResultItem> {
+    let results = file_io::save_files_impl(app.state::<AppState>(), ids).await;
+
+    if let Some(window) = app.get_webview_window("main") {
+        let payload = AutoSavedEventPayload {
+            trigger: trigger.label(),
+            results: results.clone(),
+        };
+        if let Err(error) = window.emit("rutar://auto-saved", payload) {
+            eprintln!("failed to emit auto-saved event: {error}");
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Document;
+
+    #[test]
+    fn auto_save_mode_should_map_config_labels() {
+        assert_eq!(
+            AutoSaveMode::from_config_label("afterDelay"),
+            AutoSaveMode::AfterDelay
+        );
+        assert_eq!(
+            AutoSaveMode::from_config_label("onFocusChange"),
+            AutoSaveMode::OnFocusChange
+        );
+        assert_eq!(AutoSaveMode::from_config_label("off"), AutoSaveMode::Off);
+    }
+
+    #[test]
+    fn collect_auto_save_document_ids_should_skip_clean_and_untitled_documents() {
+        let state = AppState::new(Vec::new());
+
+        let mut dirty = Document::for_test("text", Some("/tmp/dirty.txt"));
+        dirty.saved_encoding = "GBK".to_string();
+        state.documents.insert("dirty".to_string(), dirty);
+
+        let mut untitled = Document::for_test("text", None);
+        untitled.saved_encoding = "GBK".to_string();
+        state.documents.insert("untitled".to_string(), untitled);
+
+        state.documents.insert(
+            "clean".to_string(),
+            Document::for_test("text", Some("/tmp/clean.txt")),
+        );
+
+        assert_eq!(
+            collect_auto_save_document_ids(&state),
+            vec!["dirty".to_string()]
+        );
+    }
+
+    #[test]
+    fn auto_save_debounce_should_wait_until_versions_settle() {
+        let mut debounce = AutoSaveDebounce::default();
+        let delay = Duration::from_secs(2);
+        let start = Instant::now();
+        let candidate = |version: u64| vec![("doc".to_string(), version)];
+
+        assert!(debounce.take_due_ids(candidate(1), start, delay).is_empty());
+        // An edit restarts the delay.
+        assert!(debounce
+            .take_due_ids(candidate(2), start + Duration::from_secs(1), delay)
+            .is_empty());
+        assert!(debounce
+            .take_due_ids(candidate(2), start + Duration::from_secs(2), delay)
+            .is_empty());
+        assert_eq!(
+            debounce.take_due_ids(candidate(2), start + Duration::from_secs(3), delay),
+            vec!["doc".to_string()]
+        );
+        assert!(debounce.pending.is_empty());
+
+        // Documents that stop being dirty are forgotten.
+        debounce.take_due_ids(candidate(3), start, delay);
+        debounce.take_due_ids(Vec::new(), start, delay);
+        assert!(debounce.pending.is_empty());
+    }
+}
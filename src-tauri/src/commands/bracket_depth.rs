@@ -0,0 +1,239 @@
+// Nesting depth of bracket characters for bracket pair colorization.
+//
+// With a tree-sitter grammar only bracket tokens count, so brackets inside
+// strings and comments are ignored; other documents fall back to a plain scan.
+
+use super::outline::{parse_source_tree, tree_sitter_language_for_syntax};
+use super::syntax::resolve_document_syntax_key;
+use crate::state::AppState;
+use ropey::Rope;
+use tauri::State;
+use tree_sitter::Language;
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BracketDepthEntry {
+    // 1-based line and char column.
+    pub line: usize,
+    pub column: usize,
+    pub bracket: char,
+    // 0 for the outermost pair; a closing bracket shares its opener's depth.
+    pub depth: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BracketDepthPayload {
+    pub brackets: Vec<BracketDepthEntry>,
+    pub from_syntax_tree: bool,
+    pub document_version: u64,
+}
+
+fn bracket_direction(bracket: char) -> Option<bool> {
+    match bracket {
+        '(' | '[' | '{' => Some(true),
+        ')' | ']' | '}' => Some(false),
+        _ => None,
+    }
+}
+
+// Tracks the running depth and keeps only entries inside the requested lines.
+struct BracketDepthCollector {
+    depth: usize,
+    start_line: usize,
+    end_line: usize,
+    entries: Vec<BracketDepthEntry>,
+}
+
+impl BracketDepthCollector {
+    fn push(&mut self, line: usize, column: usize, bracket: char, opening: bool) {
+        let depth = if opening {
+            self.depth += 1;
+            self.depth - 1
+        } else {
+            // An unmatched closer stays at the outermost level.
+            self.depth = self.depth.saturating_sub(1);
+            self.depth
+        };
+
+        if line >= self.start_line && line <= self.end_line {
+            self.entries.push(BracketDepthEntry {
+                line,
+                column,
+                bracket,
+                depth,
+            });
+        }
+    }
+}
+
+fn collect_plain_bracket_depths(rope: &Rope, collector: &mut BracketDepthCollector) {
+    let last_line = collector.end_line.min(rope.len_lines());
+    for line_index in 0..last_line {
+        for (column, ch) in rope.line(line_index).chars().enumerate() {
+            if let Some(opening) = bracket_direction(ch) {
+                collector.push(line_index + 1, column + 1, ch, opening);
+            }
+        }
+    }
+}
+
+fn collect_syntax_bracket_depths(
+    rope: &Rope,
+    language: &Language,
+    collector: &mut BracketDepthCollector,
+) -> bool {
+    let source: String = rope.chunks().collect();
+    let Ok(tree) = parse_source_tree(&source, language) else {
+        return false;
+    };
+
+    // Iterative walk: deeply nested JSON would overflow a recursive one.
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        if node.start_position().row + 1 > collector.end_line {
+            break;
+        }
+
+        if node.child_count() == 0 && !node.is_named() && !node.is_missing() {
+            let mut chars = node.kind().chars();
+            if let (Some(bracket), None) = (chars.next(), chars.next()) {
+                if let Some(opening) = bracket_direction(bracket) {
+                    let char_index = rope.byte_to_char(node.start_byte());
+                    let line_index = rope.char_to_line(char_index);
+                    let column = char_index - rope.line_to_char(line_index) + 1;
+                    collector.push(line_index + 1, column, bracket, opening);
+                }
+            }
+        }
+
+        if cursor.goto_first_child() || cursor.goto_next_sibling() {
+            continue;
+        }
+        let mut finished = true;
+        while cursor.goto_parent() {
+            if cursor.goto_next_sibling() {
+                finished = false;
+                break;
+            }
+        }
+        if finished {
+            break;
+        }
+    }
+
+    true
+}
+
+fn compute_bracket_depths(
+    rope: &Rope,
+    language: Option<&Language>,
+    start_line: usize,
+    end_line: usize,
+) -> (Vec<BracketDepthEntry>, bool) {
+    let new_collector = || BracketDepthCollector {
+        depth: 0,
+        start_line: start_line.max(1),
+        end_line,
+        entries: Vec::new(),
+    };
+
+    if let Some(language) = language {
+        let mut collector = new_collector();
+        if collect_syntax_bracket_depths(rope, language, &mut collector) {
+            return (collector.entries, true);
+        }
+    }
+
+    let mut collector = new_collector();
+    collect_plain_bracket_depths(rope, &mut collector);
+    (collector.entries, false)
+}
+
+pub(super) async fn get_bracket_depths_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_line: usize,
+    end_line: usize,
+) -> Result<BracketDepthPayload, String> {
+    let (rope, syntax_key, document_version) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (
+            doc.rope.clone(),
+            resolve_document_syntax_key(&doc),
+            doc.document_version,
+        )
+    };
+
+    let (brackets, from_syntax_tree) = tauri::async_runtime::spawn_blocking(move || {
+        let language = tree_sitter_language_for_syntax(&syntax_key);
+        compute_bracket_depths(&rope, language.as_ref(), start_line, end_line)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(BracketDepthPayload {
+        brackets,
+        from_syntax_tree,
+        document_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depths(entries: &[BracketDepthEntry]) -> Vec<(usize, char, usize)> {
+        entries
+            .iter()
+            .map(|entry| (entry.line, entry.bracket, entry.depth))
+            .collect()
+    }
+
+    #[test]
+    fn compute_bracket_depths_should_skip_brackets_inside_json_strings() {
+        let rope = Rope::from_str("{\n  \"a(\": [1, {\"b\": []}]\n}\n");
+        let language = tree_sitter_language_for_syntax("json").expect("json language");
+
+        let (entries, from_syntax_tree) = compute_bracket_depths(&rope, Some(&language), 2, 2);
+
+        assert!(from_syntax_tree);
+        assert_eq!(
+            depths(&entries),
+            vec![
+                (2, '[', 1),
+                (2, '{', 2),
+                (2, '[', 3),
+                (2, ']', 3),
+                (2, '}', 2),
+                (2, ']', 1),
+            ]
+        );
+        assert_eq!(entries[0].column, 9);
+    }
+
+    #[test]
+    fn compute_bracket_depths_should_fall_back_to_plain_scan() {
+        let rope = Rope::from_str("a(b[c)\n)) {x}\n");
+
+        let (entries, from_syntax_tree) = compute_bracket_depths(&rope, None, 1, 10);
+
+        assert!(!from_syntax_tree);
+        assert_eq!(
+            depths(&entries),
+            vec![
+                (1, '(', 0),
+                (1, '[', 1),
+                (1, ')', 1),
+                (2, ')', 0),
+                (2, ')', 0),
+                (2, '{', 0),
+                (2, '}', 0),
+            ]
+        );
+    }
+}
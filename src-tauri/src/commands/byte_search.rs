@@ -0,0 +1,245 @@
+// Raw byte search over a file on disk or the encoded form of an open document.
+//
+// Offsets are byte offsets into what would be written to disk, so markers in
+// mixed binary/text logs can be located regardless of how the text decodes.
+
+use super::*;
+use regex::bytes::RegexBuilder as BytesRegexBuilder;
+use std::path::Path;
+
+const DEFAULT_BYTE_SEARCH_MAX_RESULTS: usize = 10_000;
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ByteSearchResultPayload {
+    pub offsets: Vec<u64>,
+    pub pattern_len: usize,
+    pub total_bytes: u64,
+    pub truncated: bool,
+    // "document" when an open document was encoded, "file" for a disk read.
+    pub source: String,
+}
+
+fn parse_hex_byte_pattern(pattern: &str) -> Result<Vec<u8>, String> {
+    let digits: String = pattern
+        .split(|ch: char| ch.is_whitespace() || ch == ',')
+        .map(|token| {
+            token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .unwrap_or(token)
+        })
+        .collect();
+
+    if !digits.is_ascii() {
+        return Err("Hex pattern may only contain hex digits".to_string());
+    }
+
+    if digits.len() % 2 != 0 {
+        return Err("Hex pattern must contain an even number of digits".to_string());
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&digits[index..index + 2], 16)
+                .map_err(|_| format!("Invalid hex byte: {}", &digits[index..index + 2]))
+        })
+        .collect()
+}
+
+// Supports \xNN, \n, \r, \t, \0 and \\; everything else is taken as UTF-8.
+fn parse_escaped_byte_pattern(pattern: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            let mut buffer = [0u8; 4];
+            bytes.extend_from_slice(ch.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+
+        match chars.next() {
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                if hex.len() != 2 {
+                    return Err("Incomplete \\x escape in byte pattern".to_string());
+                }
+                bytes.push(
+                    u8::from_str_radix(&hex, 16)
+                        .map_err(|_| format!("Invalid hex byte: {}", hex))?,
+                );
+            }
+            Some('n') => bytes.push(b'\n'),
+            Some('r') => bytes.push(b'\r'),
+            Some('t') => bytes.push(b'\t'),
+            Some('0') => bytes.push(0),
+            Some('\\') => bytes.push(b'\\'),
+            Some(other) => return Err(format!("Unsupported escape in byte pattern: \\{}", other)),
+            None => return Err("Trailing backslash in byte pattern".to_string()),
+        }
+    }
+
+    Ok(bytes)
+}
+
+pub(super) fn parse_byte_pattern(pattern: &str, kind: Option<&str>) -> Result<Vec<u8>, String> {
+    let bytes = match kind.unwrap_or("hex") {
+        "hex" => parse_hex_byte_pattern(pattern)?,
+        "escaped" => parse_escaped_byte_pattern(pattern)?,
+        other => return Err(format!("Unsupported byte pattern kind: {}", other)),
+    };
+
+    if bytes.is_empty() {
+        return Err("Byte pattern is empty".to_string());
+    }
+
+    Ok(bytes)
+}
+
+// Start offsets of the first `max_results` matches, and whether more exist.
+// Matches do not overlap: after a match the scan resumes at its end, so `AA`
+// is found once in `AAA`.
+fn find_byte_offsets(
+    haystack: &[u8],
+    needle: &[u8],
+    max_results: usize,
+) -> Result<(Vec<u64>, bool), String> {
+    let source: String = needle
+        .iter()
+        .map(|byte| format!("\\x{:02X}", byte))
+        .collect();
+    let regex = BytesRegexBuilder::new(&source)
+        .unicode(false)
+        .build()
+        .map_err(|error| error.to_string())?;
+
+    let mut offsets = Vec::new();
+    for found in regex.find_iter(haystack) {
+        if offsets.len() >= max_results {
+            return Ok((offsets, true));
+        }
+        offsets.push(found.start() as u64);
+    }
+
+    Ok((offsets, false))
+}
+
+pub(super) async fn search_bytes_in_file_impl(
+    state: State<'_, AppState>,
+    path_or_id: String,
+    pattern: String,
+    pattern_kind: Option<String>,
+    max_results: Option<usize>,
+) -> Result<ByteSearchResultPayload, String> {
+    let needle = parse_byte_pattern(&pattern, pattern_kind.as_deref())?;
+    let max_results = max_results
+        .unwrap_or(DEFAULT_BYTE_SEARCH_MAX_RESULTS)
+        .max(1);
+
+    // Open documents win over paths so unsaved edits are searched as they
+    // would be written.
+    let document_source = state
+        .documents
+        .get(&path_or_id)
+        .map(|doc| file_io::DocumentEncodeSource::capture(&doc));
+
+    if let Some(document_source) = document_source {
+        return tauri::async_runtime::spawn_blocking(move || {
+            let bytes = document_source.encode().0;
+            let (offsets, truncated) = find_byte_offsets(&bytes, &needle, max_results)?;
+            Ok(ByteSearchResultPayload {
+                offsets,
+                pattern_len: needle.len(),
+                total_bytes: bytes.len() as u64,
+                truncated,
+                source: "document".to_string(),
+            })
+        })
+        .await
+        .map_err(|error| error.to_string())?;
+    }
+
+    let share_mode = file_io::resolve_file_share_mode(None)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = Path::new(&path_or_id);
+        let file = file_io::open_file_with_share_mode(path, share_mode, false)
+            .map_err(|error| file_io::describe_file_open_error(path, error))?;
+        let total_bytes = file.metadata().map_err(|e| e.to_string())?.len();
+        if total_bytes == 0 {
+            return Ok(ByteSearchResultPayload {
+                offsets: Vec::new(),
+                pattern_len: needle.len(),
+                total_bytes,
+                truncated: false,
+                source: "file".to_string(),
+            });
+        }
+
+        let mmap = unsafe { Mmap::map(&file).map_err(|e| e.to_string())? };
+        let (offsets, truncated) = find_byte_offsets(&mmap, &needle, max_results)?;
+        Ok(ByteSearchResultPayload {
+            offsets,
+            pattern_len: needle.len(),
+            total_bytes,
+            truncated,
+            source: "file".to_string(),
+        })
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_byte_pattern_should_accept_spaced_and_prefixed_hex() {
+        assert_eq!(
+            parse_byte_pattern("DE ad 0xBE,0xef", None),
+            Ok(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert!(parse_byte_pattern("ABC", Some("hex")).is_err());
+        assert!(parse_byte_pattern("zz", Some("hex")).is_err());
+        assert!(parse_byte_pattern("  ", Some("hex")).is_err());
+    }
+
+    #[test]
+    fn parse_byte_pattern_should_decode_escaped_literals() {
+        assert_eq!(
+            parse_byte_pattern(r"PK\x03\x04\0\\é", Some("escaped")),
+            Ok(vec![b'P', b'K', 0x03, 0x04, 0, b'\\', 0xC3, 0xA9])
+        );
+        assert!(parse_byte_pattern(r"\x4", Some("escaped")).is_err());
+        assert!(parse_byte_pattern(r"\q", Some("escaped")).is_err());
+        assert!(parse_byte_pattern("x", Some("octal")).is_err());
+    }
+
+    #[test]
+    fn find_byte_offsets_should_match_non_utf8_bytes_and_respect_limit() {
+        let haystack = [0x00, 0xFF, 0xFE, 0x41, 0xFF, 0xFE, 0xFF, 0xFE];
+
+        assert_eq!(
+            find_byte_offsets(&haystack, &[0xFF, 0xFE], 10),
+            Ok((vec![1, 4, 6], false))
+        );
+        assert_eq!(
+            find_byte_offsets(&haystack, &[0xFF, 0xFE], 2),
+            Ok((vec![1, 4], true))
+        );
+        assert_eq!(
+            find_byte_offsets(&haystack, &[0x42], 10),
+            Ok((Vec::new(), false))
+        );
+    }
+
+    #[test]
+    fn find_byte_offsets_should_not_report_overlapping_matches() {
+        assert_eq!(
+            find_byte_offsets(b"AAAA", b"AA", 10),
+            Ok((vec![0, 2], false))
+        );
+    }
+}
@@ -0,0 +1,275 @@
+// Clipboard ring: the last snippets copied in the editor, newest first, so the
+// frontend can offer "paste from history". The ring lives in `AppState`. With
+// `persistClipboardHistory` on it is also written to `clipboard_history.json`
+// next to the config file and read back on first use after a restart; saving
+// the setting off deletes the file.
+
+use super::*;
+use crate::state::ClipboardEntry;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+const MAX_CLIPBOARD_HISTORY: usize = 30;
+// Larger snippets are not kept; they would pin too much memory.
+const MAX_CLIPBOARD_ENTRY_BYTES: usize = 1024 * 1024;
+const CLIPBOARD_PREVIEW_CHARS: usize = 120;
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardEntrySummary {
+    // Position in the ring, for `paste_clipboard_entry`.
+    pub index: usize,
+    pub preview: String,
+    pub preview_truncated: bool,
+    pub char_count: usize,
+    pub line_count: usize,
+    pub copied_at_ms: u64,
+}
+
+fn clipboard_history_file_path() -> Result<PathBuf, String> {
+    config::config_dir_path_impl().map(|dir| dir.join("clipboard_history.json"))
+}
+
+fn load_persisted_clipboard_history() -> VecDeque<ClipboardEntry> {
+    if config::is_safe_mode_impl() || !config::is_clipboard_history_persisted_in_config_impl() {
+        return VecDeque::new();
+    }
+
+    let mut entries: VecDeque<ClipboardEntry> = clipboard_history_file_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+    entries.truncate(MAX_CLIPBOARD_HISTORY);
+    entries
+}
+
+// Called when `persistClipboardHistory` is saved off, so snippets copied while
+// it was on do not stay on disk.
+pub(super) fn remove_persisted_clipboard_history_impl() -> Result<(), String> {
+    match fs::remove_file(clipboard_history_file_path()?) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.to_string()),
+        _ => Ok(()),
+    }
+}
+
+// Writes the ring as it is now rather than as a caller saw it. Writers take
+// turns, so the file always ends up with the newest ring.
+fn persist_clipboard_history(state: &AppState) -> Result<(), String> {
+    static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+    if config::is_safe_mode_impl() {
+        return Ok(());
+    }
+    if !config::is_clipboard_history_persisted_in_config_impl() {
+        return remove_persisted_clipboard_history_impl();
+    }
+
+    let _writing = WRITE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let raw = {
+        let history = state.clipboard_history.lock().map_err(|e| e.to_string())?;
+        serde_json::to_string(history.as_ref().unwrap_or(&VecDeque::new()))
+            .map_err(|e| e.to_string())?
+    };
+    let path = clipboard_history_file_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    file_io::write_file_atomically(&path, raw.as_bytes(), None)?;
+    Ok(())
+}
+
+// Runs `update` on the ring and persists it when `update` returns true. The
+// ring is only locked while `update` runs; the file is read and written
+// outside the lock.
+fn update_clipboard_history<T>(
+    state: &AppState,
+    update: impl FnOnce(&mut VecDeque<ClipboardEntry>) -> Result<(T, bool), String>,
+) -> Result<T, String> {
+    let is_loaded = state
+        .clipboard_history
+        .lock()
+        .map_err(|e| e.to_string())?
+        .is_some();
+    let persisted = (!is_loaded).then(load_persisted_clipboard_history);
+
+    let (value, changed) = {
+        let mut history = state.clipboard_history.lock().map_err(|e| e.to_string())?;
+        let entries = history.get_or_insert_with(|| persisted.unwrap_or_default());
+        update(entries)?
+    };
+    if changed {
+        persist_clipboard_history(state)?;
+    }
+    Ok(value)
+}
+
+// Loading and writing the persisted ring is file I/O, so it runs on a
+// blocking thread instead of the command thread.
+async fn run_clipboard_history_update<T: Send + 'static>(
+    app: AppHandle,
+    update: impl FnOnce(&mut VecDeque<ClipboardEntry>) -> Result<(T, bool), String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        update_clipboard_history(&app.state::<AppState>(), update)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn summarize_clipboard_history(entries: &VecDeque<ClipboardEntry>) -> Vec<ClipboardEntrySummary> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let char_count = entry.text.chars().count();
+            ClipboardEntrySummary {
+                index,
+                preview: entry.text.chars().take(CLIPBOARD_PREVIEW_CHARS).collect(),
+                preview_truncated: char_count > CLIPBOARD_PREVIEW_CHARS,
+                char_count,
+                line_count: entry.text.lines().count().max(1),
+                copied_at_ms: entry.copied_at_ms,
+            }
+        })
+        .collect()
+}
+
+// Copying text that is already in the ring moves it to the front. Returns
+// false for text that is not kept: empty or over the size limit.
+fn push_clipboard_text(
+    entries: &mut VecDeque<ClipboardEntry>,
+    text: String,
+    copied_at_ms: u64,
+) -> bool {
+    if text.is_empty() || text.len() > MAX_CLIPBOARD_ENTRY_BYTES {
+        return false;
+    }
+
+    entries.retain(|entry| entry.text != text);
+    entries.push_front(ClipboardEntry { text, copied_at_ms });
+    entries.truncate(MAX_CLIPBOARD_HISTORY);
+    true
+}
+
+pub(super) async fn push_clipboard_entry_impl(
+    app: AppHandle,
+    text: String,
+) -> Result<Vec<ClipboardEntrySummary>, String> {
+    run_clipboard_history_update(app, move |entries| {
+        let changed = push_clipboard_text(entries, text, now_unix_millis());
+        Ok((summarize_clipboard_history(entries), changed))
+    })
+    .await
+}
+
+pub(super) async fn list_clipboard_history_impl(
+    app: AppHandle,
+) -> Result<Vec<ClipboardEntrySummary>, String> {
+    run_clipboard_history_update(app, |entries| {
+        Ok((summarize_clipboard_history(entries), false))
+    })
+    .await
+}
+
+// Returns the full text and moves the entry to the front, the way pasting it
+// from the system clipboard would after copying it again.
+pub(super) async fn paste_clipboard_entry_impl(
+    app: AppHandle,
+    index: usize,
+) -> Result<String, String> {
+    run_clipboard_history_update(app, move |entries| {
+        let entry = entries
+            .remove(index)
+            .ok_or_else(|| "Clipboard entry not found".to_string())?;
+        let text = entry.text.clone();
+        entries.push_front(entry);
+        Ok((text, index > 0))
+    })
+    .await
+}
+
+pub(super) async fn clear_clipboard_history_impl(app: AppHandle) -> Result<(), String> {
+    run_clipboard_history_update(app, |entries| {
+        let changed = !entries.is_empty();
+        entries.clear();
+        Ok(((), changed))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_clipboard_text_should_dedupe_cap_and_skip_empty_text() {
+        let mut entries = VecDeque::new();
+        for index in 0..MAX_CLIPBOARD_HISTORY + 2 {
+            assert!(push_clipboard_text(
+                &mut entries,
+                format!("snippet {index}"),
+                1
+            ));
+        }
+        assert!(push_clipboard_text(
+            &mut entries,
+            "snippet 5".to_string(),
+            2
+        ));
+        assert!(!push_clipboard_text(&mut entries, String::new(), 3));
+
+        assert_eq!(entries.len(), MAX_CLIPBOARD_HISTORY);
+        assert_eq!(entries[0].text, "snippet 5");
+        assert_eq!(entries[0].copied_at_ms, 2);
+        assert_eq!(
+            entries
+                .iter()
+                .filter(|entry| entry.text == "snippet 5")
+                .count(),
+            1
+        );
+        assert_eq!(
+            entries[1].text,
+            format!("snippet {}", MAX_CLIPBOARD_HISTORY + 1)
+        );
+    }
+
+    #[test]
+    fn summarize_clipboard_history_should_truncate_previews() {
+        let long_text = "x".repeat(CLIPBOARD_PREVIEW_CHARS + 1);
+        let entries = VecDeque::from([
+            ClipboardEntry {
+                text: "a\r\nb\n".to_string(),
+                copied_at_ms: 7,
+            },
+            ClipboardEntry {
+                text: long_text,
+                copied_at_ms: 8,
+            },
+        ]);
+
+        let summaries = summarize_clipboard_history(&entries);
+
+        assert_eq!(
+            summaries[0],
+            ClipboardEntrySummary {
+                index: 0,
+                preview: "a\r\nb\n".to_string(),
+                preview_truncated: false,
+                char_count: 5,
+                line_count: 2,
+                copied_at_ms: 7,
+            }
+        );
+        assert_eq!(
+            summaries[1].preview.chars().count(),
+            CLIPBOARD_PREVIEW_CHARS
+        );
+        assert!(summaries[1].preview_truncated);
+    }
+}
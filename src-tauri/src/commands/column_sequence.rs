@@ -0,0 +1,195 @@
+// Column editor: inserts a fixed string or an incrementing number sequence at
+// one column on every line of a range.
+//
+// Lines are 1-based and inclusive; the column is a 1-based UTF-16 column like
+// the rectangular selection commands. Lines shorter than the column are padded
+// with spaces first. The whole insert is one undo step.
+
+use super::editing::{
+    apply_planned_text_edits, plan_char_range_edits, trimmed_line_without_break,
+    utf16_column_to_char_offset, CharRangeTextEdit,
+};
+use super::search::clear_outdated_document_search_caches;
+use crate::state::AppState;
+use ropey::Rope;
+use tauri::State;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SequencePadding {
+    None,
+    Zeros,
+    Spaces,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnSequenceResultPayload {
+    pub inserted_lines: usize,
+    pub line_count: usize,
+    pub document_version: u64,
+}
+
+fn parse_sequence_padding(value: Option<&str>) -> Result<SequencePadding, String> {
+    match value
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some("none") => Ok(SequencePadding::None),
+        Some("zeros") => Ok(SequencePadding::Zeros),
+        Some("spaces") => Ok(SequencePadding::Spaces),
+        Some(other) => Err(format!("Unsupported sequence padding: {other}")),
+    }
+}
+
+// Padding aligns every number to the widest one in the sequence.
+fn build_number_sequence(
+    count: usize,
+    start_number: i64,
+    step: i64,
+    padding: SequencePadding,
+) -> Result<Vec<String>, String> {
+    let mut numbers = Vec::with_capacity(count);
+    let mut value = start_number;
+    for index in 0..count {
+        if index > 0 {
+            value = value
+                .checked_add(step)
+                .ok_or_else(|| "Number sequence is out of range".to_string())?;
+        }
+        numbers.push(value);
+    }
+
+    let width = numbers
+        .iter()
+        .map(|value| value.to_string().len())
+        .max()
+        .unwrap_or(0);
+    Ok(numbers
+        .into_iter()
+        .map(|value| match padding {
+            SequencePadding::None => value.to_string(),
+            SequencePadding::Zeros => format!("{value:0width$}"),
+            SequencePadding::Spaces => format!("{value:>width$}"),
+        })
+        .collect())
+}
+
+fn column_sequence_edits(
+    rope: &Rope,
+    first_line_index: usize,
+    values: &[String],
+    column: usize,
+) -> Vec<CharRangeTextEdit> {
+    let column = column.max(1);
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(offset, value)| {
+            let line_index = first_line_index + offset;
+            let line_text = trimmed_line_without_break(rope, line_index);
+            let line_utf16_len: usize = line_text.chars().map(char::len_utf16).sum();
+            let char_offset = utf16_column_to_char_offset(&line_text, column);
+            let insert_at = rope.line_to_char(line_index) + char_offset;
+            let padding = " ".repeat((column - 1).saturating_sub(line_utf16_len));
+
+            CharRangeTextEdit {
+                start_char: insert_at,
+                end_char: insert_at,
+                text: format!("{padding}{value}"),
+            }
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn insert_column_sequence_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_line: usize,
+    end_line: usize,
+    column: usize,
+    start_number: Option<i64>,
+    step: Option<i64>,
+    padding: Option<String>,
+    text: Option<String>,
+) -> Result<ColumnSequenceResultPayload, String> {
+    let padding = parse_sequence_padding(padding.as_deref())?;
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    let line_count = doc.rope.len_lines();
+    let first_line = start_line.min(end_line).max(1);
+    let last_line = start_line.max(end_line).max(1).min(line_count);
+    if first_line > line_count {
+        return Err("Line range is out of bounds".to_string());
+    }
+
+    let count = last_line - first_line + 1;
+    let values = match text {
+        Some(text) => vec![text; count],
+        None => {
+            build_number_sequence(count, start_number.unwrap_or(1), step.unwrap_or(1), padding)?
+        }
+    };
+    let edits = column_sequence_edits(&doc.rope, first_line - 1, &values, column);
+    let planned = plan_char_range_edits(&doc.rope, &edits)?;
+
+    let mut engine = doc.engine();
+    apply_planned_text_edits(&mut engine, planned.edits, None, None)?;
+
+    let change = engine.finish();
+    if change.changed {
+        drop(doc);
+        clear_outdated_document_search_caches(&id, change.document_version);
+    }
+
+    Ok(ColumnSequenceResultPayload {
+        inserted_lines: count,
+        line_count: change.line_count,
+        document_version: change.document_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::editing::apply_planned_text_edits_to_rope;
+    use super::*;
+
+    fn insert(source: &str, first_line_index: usize, values: &[&str], column: usize) -> String {
+        let rope = Rope::from_str(source);
+        let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        let edits = column_sequence_edits(&rope, first_line_index, &values, column);
+        let planned = plan_char_range_edits(&rope, &edits).expect("edits should plan");
+        apply_planned_text_edits_to_rope(&rope, &planned.edits)
+    }
+
+    #[test]
+    fn build_number_sequence_should_step_and_pad_to_widest_value() {
+        assert_eq!(
+            build_number_sequence(3, 8, 1, SequencePadding::None).expect("sequence"),
+            vec!["8", "9", "10"]
+        );
+        assert_eq!(
+            build_number_sequence(3, 8, 1, SequencePadding::Zeros).expect("sequence"),
+            vec!["08", "09", "10"]
+        );
+        assert_eq!(
+            build_number_sequence(3, 5, -5, SequencePadding::Spaces).expect("sequence"),
+            vec![" 5", " 0", "-5"]
+        );
+        assert!(build_number_sequence(2, i64::MAX, 1, SequencePadding::None).is_err());
+        assert!(parse_sequence_padding(Some("tabs")).is_err());
+    }
+
+    #[test]
+    fn column_sequence_edits_should_insert_at_utf16_column_and_pad_short_lines() {
+        assert_eq!(
+            insert("ab cd\r\nx\n😀yz\nlast\n", 0, &["1", "2", "3"], 3),
+            "ab1 cd\r\nx 2\n😀3yz\nlast\n"
+        );
+        assert_eq!(insert("a\nb\nc\n", 1, &["- ", "- "], 1), "a\n- b\n- c\n");
+    }
+}
@@ -0,0 +1,444 @@
+// Line and block comment toggling by resolved syntax.
+//
+// Syntaxes without line comments (HTML, XML, Markdown, CSS) toggle a block
+// comment around each selected line instead. Each toggle is applied as one
+// grouped batch of edits, so a single undo restores the previous text.
+
+use super::editing::{apply_planned_text_edits, plan_char_range_edits, CharRangeTextEdit};
+use super::search::clear_outdated_document_search_caches;
+use super::syntax::resolve_document_syntax_key;
+use crate::state::AppState;
+use ropey::Rope;
+use tauri::State;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CommentTokens {
+    line: Option<&'static str>,
+    block: Option<(&'static str, &'static str)>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToggleCommentsResultPayload {
+    pub changed: bool,
+    pub line_count: usize,
+    pub document_version: u64,
+    // The selection after the edit, following the text it covered.
+    pub selection_start_char: usize,
+    pub selection_end_char: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct CommentToggle {
+    edits: Vec<CharRangeTextEdit>,
+    selection_start_char: usize,
+    selection_end_char: usize,
+}
+
+fn comment_tokens_for_syntax(syntax_key: &str) -> CommentTokens {
+    let (line, block) = match syntax_key {
+        "javascript" | "typescript" | "rust" | "c" | "cpp" | "go" | "java" | "csharp"
+        | "kotlin" | "swift" | "php" | "jsonc" => (Some("//"), Some(("/*", "*/"))),
+        "hcl" => (Some("#"), Some(("/*", "*/"))),
+        "sql" => (Some("--"), Some(("/*", "*/"))),
+        "lua" => (Some("--"), Some(("--[[", "]]"))),
+        "python" => (Some("#"), Some(("\"\"\"", "\"\"\""))),
+        "powershell" => (Some("#"), Some(("<#", "#>"))),
+        "css" => (None, Some(("/*", "*/"))),
+        "html" | "xml" | "markdown" => (None, Some(("<!--", "-->"))),
+        "batch" => (Some("REM"), None),
+        // None of these formats has comments.
+        "json" | "diff" | "csv" => (None, None),
+        _ => (Some("#"), None),
+    };
+
+    CommentTokens { line, block }
+}
+
+fn line_without_break(rope: &Rope, line_index: usize) -> String {
+    let mut text = rope.line(line_index).to_string();
+    while text.ends_with(['\n', '\r']) {
+        text.pop();
+    }
+    text
+}
+
+fn leading_whitespace_chars(text: &str) -> usize {
+    text.chars()
+        .take_while(|ch| *ch == ' ' || *ch == '\t')
+        .count()
+}
+
+fn edit(start_char: usize, end_char: usize, text: &str) -> CharRangeTextEdit {
+    CharRangeTextEdit {
+        start_char,
+        end_char,
+        text: text.to_string(),
+    }
+}
+
+// Where an offset lands once `edits` are applied. An insertion exactly at the
+// offset moves it only with `bias_after`, so a selection does not swallow a
+// closing token inserted at its end.
+fn map_char_offset(edits: &[CharRangeTextEdit], offset: usize, bias_after: bool) -> usize {
+    let mut mapped = offset as isize;
+    for edit in edits {
+        let inserted = edit.text.chars().count();
+        if edit.end_char < offset
+            || (edit.end_char == offset && (edit.start_char < edit.end_char || bias_after))
+        {
+            mapped += inserted as isize - (edit.end_char - edit.start_char) as isize;
+        } else if edit.start_char < offset {
+            let removed = offset - edit.start_char;
+            mapped += removed.min(inserted) as isize - removed as isize;
+        }
+    }
+
+    mapped.max(0) as usize
+}
+
+fn finish_toggle(
+    edits: Vec<CharRangeTextEdit>,
+    start_char: usize,
+    end_char: usize,
+) -> CommentToggle {
+    let selection_start_char = map_char_offset(&edits, start_char, true);
+    let selection_end_char = if start_char == end_char {
+        selection_start_char
+    } else {
+        map_char_offset(&edits, end_char, false).max(selection_start_char)
+    };
+
+    CommentToggle {
+        edits,
+        selection_start_char,
+        selection_end_char,
+    }
+}
+
+fn toggle_line_comment_edits(
+    rope: &Rope,
+    syntax_key: &str,
+    start_char: usize,
+    end_char: usize,
+) -> Result<CommentToggle, String> {
+    let tokens = comment_tokens_for_syntax(syntax_key);
+    let first_line = rope.char_to_line(start_char);
+    let mut last_line = rope.char_to_line(end_char);
+    // A selection ending at the start of a line does not include that line.
+    if last_line > first_line && rope.line_to_char(last_line) == end_char {
+        last_line -= 1;
+    }
+
+    let lines: Vec<(usize, String)> = (first_line..=last_line)
+        .map(|line_index| (line_index, line_without_break(rope, line_index)))
+        .collect();
+    let mut targets: Vec<&(usize, String)> = lines
+        .iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .collect();
+    if targets.is_empty() {
+        targets = lines.iter().collect();
+    }
+    let indent = targets
+        .iter()
+        .map(|(_, text)| leading_whitespace_chars(text))
+        .min()
+        .unwrap_or(0);
+
+    let mut edits = Vec::new();
+    match (tokens.line, tokens.block) {
+        (Some(token), _) => {
+            let commented = targets
+                .iter()
+                .all(|(_, text)| text.trim_start().starts_with(token));
+            for (line_index, text) in targets {
+                let line_start = rope.line_to_char(*line_index);
+                if commented {
+                    let token_start = line_start + leading_whitespace_chars(text);
+                    let after_token = &text.trim_start()[token.len()..];
+                    let token_chars =
+                        token.chars().count() + usize::from(after_token.starts_with(' '));
+                    edits.push(edit(token_start, token_start + token_chars, ""));
+                } else {
+                    let insert_at = line_start + indent;
+                    edits.push(edit(insert_at, insert_at, &format!("{token} ")));
+                }
+            }
+        }
+        (None, Some((open, close))) => {
+            let commented = targets.iter().all(|(_, text)| {
+                let trimmed = text.trim();
+                trimmed.len() >= open.len() + close.len()
+                    && trimmed.starts_with(open)
+                    && trimmed.ends_with(close)
+            });
+            for (line_index, text) in targets {
+                let line_start = rope.line_to_char(*line_index);
+                let content_start = line_start + leading_whitespace_chars(text);
+                let content_end = line_start + text.trim_end().chars().count();
+                if commented {
+                    let inner = &text.trim()[open.len()..text.trim().len() - close.len()];
+                    let open_chars = open.chars().count() + usize::from(inner.starts_with(' '));
+                    let close_chars = close.chars().count()
+                        + usize::from(inner.len() > 1 && inner.ends_with(' '));
+                    edits.push(edit(content_start, content_start + open_chars, ""));
+                    edits.push(edit(content_end - close_chars, content_end, ""));
+                } else {
+                    let insert_at = line_start + indent;
+                    edits.push(edit(insert_at, insert_at, &format!("{open} ")));
+                    edits.push(edit(content_end, content_end, &format!(" {close}")));
+                }
+            }
+        }
+        (None, None) => return Err("Comments are not supported for this syntax".to_string()),
+    }
+
+    Ok(finish_toggle(edits, start_char, end_char))
+}
+
+// The block comment enclosing a range, as the char ranges of its two tokens.
+fn find_enclosing_block_comment(
+    rope: &Rope,
+    start_char: usize,
+    end_char: usize,
+    open: &str,
+    close: &str,
+) -> Option<((usize, usize), (usize, usize))> {
+    // With identical tokens such as `"""` the enclosing pair is ambiguous.
+    if open == close {
+        return None;
+    }
+
+    let before = rope.slice(..start_char).to_string();
+    let open_byte = before.rfind(open)?;
+    if before[open_byte + open.len()..].contains(close) {
+        return None;
+    }
+
+    let after = rope.slice(end_char..).to_string();
+    let close_byte = after.find(close)?;
+    if after[..close_byte].contains(open) {
+        return None;
+    }
+
+    let open_start = rope.byte_to_char(open_byte);
+    let close_start = rope.byte_to_char(rope.char_to_byte(end_char) + close_byte);
+    Some((
+        (open_start, open_start + open.chars().count()),
+        (close_start, close_start + close.chars().count()),
+    ))
+}
+
+fn toggle_block_comment_edits(
+    rope: &Rope,
+    syntax_key: &str,
+    start_char: usize,
+    end_char: usize,
+) -> Result<CommentToggle, String> {
+    let Some((open, close)) = comment_tokens_for_syntax(syntax_key).block else {
+        return Err("Block comments are not supported for this syntax".to_string());
+    };
+
+    // Without a selection the caret line's content is commented.
+    let (range_start, range_end) = if start_char == end_char {
+        let line_index = rope.char_to_line(start_char);
+        let text = line_without_break(rope, line_index);
+        let line_start = rope.line_to_char(line_index);
+        (
+            line_start + leading_whitespace_chars(&text),
+            line_start + text.trim_end().chars().count(),
+        )
+    } else {
+        (start_char, end_char)
+    };
+
+    let selected = rope.slice(range_start..range_end).to_string();
+    let content_start = range_start + leading_whitespace_chars(&selected);
+    let content_end = range_start + selected.trim_end().chars().count();
+    let content = selected.trim();
+
+    let token_ranges = if content.len() >= open.len() + close.len()
+        && content.starts_with(open)
+        && content.ends_with(close)
+    {
+        Some((
+            (content_start, content_start + open.chars().count()),
+            (content_end - close.chars().count(), content_end),
+        ))
+    } else {
+        find_enclosing_block_comment(rope, start_char, end_char, open, close)
+    };
+
+    if let Some(((open_start, open_end), (close_start, close_end))) = token_ranges {
+        // Drop the padding a toggle inserts along with the tokens.
+        let open_end = if close_start > open_end && rope.char(open_end) == ' ' {
+            open_end + 1
+        } else {
+            open_end
+        };
+        let close_start = if close_start > open_end && rope.char(close_start - 1) == ' ' {
+            close_start - 1
+        } else {
+            close_start
+        };
+        let edits = vec![
+            edit(open_start, open_end, ""),
+            edit(close_start, close_end, ""),
+        ];
+        return Ok(finish_toggle(edits, start_char, end_char));
+    }
+
+    if content_start == content_end {
+        let edits = vec![edit(
+            content_start,
+            content_end,
+            &format!("{open}  {close}"),
+        )];
+        let caret = content_start + open.chars().count() + 1;
+        return Ok(CommentToggle {
+            edits,
+            selection_start_char: caret,
+            selection_end_char: caret,
+        });
+    }
+
+    let edits = vec![
+        edit(content_start, content_start, &format!("{open} ")),
+        edit(content_end, content_end, &format!(" {close}")),
+    ];
+    Ok(finish_toggle(edits, start_char, end_char))
+}
+
+fn apply_comment_toggle(
+    state: State<'_, AppState>,
+    id: String,
+    start_char: usize,
+    end_char: usize,
+    toggle: fn(&Rope, &str, usize, usize) -> Result<CommentToggle, String>,
+) -> Result<ToggleCommentsResultPayload, String> {
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let len_chars = doc.rope.len_chars();
+    let (start_char, end_char) = (
+        start_char.min(end_char).min(len_chars),
+        start_char.max(end_char).min(len_chars),
+    );
+    let syntax_key = resolve_document_syntax_key(&doc);
+    let toggled = toggle(&doc.rope, &syntax_key, start_char, end_char)?;
+    let planned = plan_char_range_edits(&doc.rope, &toggled.edits)?;
+
+    let mut engine = doc.engine();
+    apply_planned_text_edits(&mut engine, planned.edits, None, None)?;
+
+    let change = engine.finish();
+    if change.changed {
+        drop(doc);
+        clear_outdated_document_search_caches(&id, change.document_version);
+    }
+
+    Ok(ToggleCommentsResultPayload {
+        changed: change.changed,
+        line_count: change.line_count,
+        document_version: change.document_version,
+        selection_start_char: toggled.selection_start_char,
+        selection_end_char: toggled.selection_end_char,
+    })
+}
+
+pub(super) fn toggle_line_comments_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_char: usize,
+    end_char: usize,
+) -> Result<ToggleCommentsResultPayload, String> {
+    apply_comment_toggle(state, id, start_char, end_char, toggle_line_comment_edits)
+}
+
+pub(super) fn toggle_block_comment_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_char: usize,
+    end_char: usize,
+) -> Result<ToggleCommentsResultPayload, String> {
+    apply_comment_toggle(state, id, start_char, end_char, toggle_block_comment_edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::editing::apply_planned_text_edits_to_rope;
+    use super::*;
+
+    fn apply(source: &str, toggle: &CommentToggle) -> String {
+        let rope = Rope::from_str(source);
+        let planned = plan_char_range_edits(&rope, &toggle.edits).expect("edits should plan");
+        apply_planned_text_edits_to_rope(&rope, &planned.edits)
+    }
+
+    #[test]
+    fn toggle_line_comment_edits_should_round_trip_at_common_indent() {
+        let source = "fn main() {\n    let a = 1;\n\n        call();\n}\n";
+        let rope = Rope::from_str(source);
+        let start = rope.line_to_char(1);
+        let end = rope.line_to_char(4);
+
+        let commented = toggle_line_comment_edits(&rope, "rust", start, end).expect("toggle");
+        let commented_text = apply(source, &commented);
+        assert_eq!(
+            commented_text,
+            "fn main() {\n    // let a = 1;\n\n    //     call();\n}\n"
+        );
+
+        let rope = Rope::from_str(&commented_text);
+        let uncommented = toggle_line_comment_edits(
+            &rope,
+            "rust",
+            commented.selection_start_char,
+            commented.selection_end_char,
+        )
+        .expect("toggle");
+        assert_eq!(apply(&commented_text, &uncommented), source);
+    }
+
+    #[test]
+    fn toggle_line_comment_edits_should_wrap_lines_without_line_comments() {
+        let source = "<ul>\n  <li>a</li>\n</ul>\n";
+        let rope = Rope::from_str(source);
+        let start = rope.line_to_char(1) + 3;
+
+        let toggle = toggle_line_comment_edits(&rope, "html", start, start).expect("toggle");
+        let commented = apply(source, &toggle);
+        assert_eq!(commented, "<ul>\n  <!-- <li>a</li> -->\n</ul>\n");
+
+        let rope = Rope::from_str(&commented);
+        let toggle = toggle_line_comment_edits(&rope, "html", start, start).expect("toggle");
+        assert_eq!(apply(&commented, &toggle), source);
+        assert!(toggle_line_comment_edits(&rope, "json", 0, 0).is_err());
+    }
+
+    #[test]
+    fn toggle_block_comment_edits_should_wrap_and_unwrap_per_syntax() {
+        let source = "let a = call(x, y);\n";
+        let rope = Rope::from_str(source);
+
+        let toggle = toggle_block_comment_edits(&rope, "typescript", 13, 17).expect("toggle");
+        let commented = apply(source, &toggle);
+        assert_eq!(commented, "let a = call(/* x, y */);\n");
+        assert_eq!(
+            (toggle.selection_start_char, toggle.selection_end_char),
+            (16, 20)
+        );
+
+        // A caret inside the comment removes the enclosing pair.
+        let rope = Rope::from_str(&commented);
+        let toggle = toggle_block_comment_edits(&rope, "typescript", 17, 17).expect("toggle");
+        assert_eq!(apply(&commented, &toggle), source);
+
+        let rope = Rope::from_str("value\n");
+        let toggle = toggle_block_comment_edits(&rope, "python", 0, 5).expect("toggle");
+        assert_eq!(apply("value\n", &toggle), "\"\"\" value \"\"\"\n");
+        assert!(toggle_block_comment_edits(&rope, "yaml", 0, 5).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+// Compares two lists of filter rule groups, typically the local groups and an
+// imported export, so the changes can be reviewed before saving. Groups pair
+// up by name and rules by keyword; both sides are normalized first so that
+// whitespace or spelling differences in modes do not show up as changes.
+
+use super::super::*;
+use super::profile::normalize_filter_rule_groups;
+
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterRuleFieldChange {
+    pub field: String,
+    pub left: String,
+    pub right: String,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterRuleChange {
+    pub keyword: String,
+    pub changes: Vec<FilterRuleFieldChange>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterRuleGroupDiff {
+    pub name: String,
+    // "added", "removed", "modified" or "unchanged", seen from left to right.
+    pub status: String,
+    pub added_rules: Vec<FilterRuleInput>,
+    pub removed_rules: Vec<FilterRuleInput>,
+    pub modified_rules: Vec<FilterRuleChange>,
+    // Same rules on both sides, but evaluated in a different order.
+    pub order_changed: bool,
+}
+
+fn alert_label(alert: &Option<FilterRuleAlertOptions>) -> String {
+    let Some(alert) = alert.as_ref() else {
+        return "off".to_string();
+    };
+    let actions: Vec<&str> = [
+        (alert.notification, "notification"),
+        (alert.sound, "sound"),
+        (alert.flash_taskbar, "flashTaskbar"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, name)| enabled.then_some(name))
+    .collect();
+    if actions.is_empty() {
+        "off".to_string()
+    } else {
+        actions.join("+")
+    }
+}
+
+fn rule_field_changes(
+    left: &FilterRuleInput,
+    right: &FilterRuleInput,
+) -> Vec<FilterRuleFieldChange> {
+    let fields = [
+        (
+            "matchMode",
+            left.match_mode.clone(),
+            right.match_mode.clone(),
+        ),
+        (
+            "backgroundColor",
+            left.background_color.clone(),
+            right.background_color.clone(),
+        ),
+        (
+            "textColor",
+            left.text_color.clone(),
+            right.text_color.clone(),
+        ),
+        ("bold", left.bold.to_string(), right.bold.to_string()),
+        ("italic", left.italic.to_string(), right.italic.to_string()),
+        ("applyTo", left.apply_to.clone(), right.apply_to.clone()),
+        ("alert", alert_label(&left.alert), alert_label(&right.alert)),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, left, right)| left != right)
+        .map(|(field, left, right)| FilterRuleFieldChange {
+            field: field.to_string(),
+            left,
+            right,
+        })
+        .collect()
+}
+
+fn diff_group_rules(
+    name: &str,
+    left: &[FilterRuleInput],
+    right: &[FilterRuleInput],
+) -> FilterRuleGroupDiff {
+    let mut unmatched_right: Vec<Option<&FilterRuleInput>> = right.iter().map(Some).collect();
+    let mut removed_rules = Vec::new();
+    let mut modified_rules = Vec::new();
+    let mut paired_right_indexes = Vec::new();
+
+    // Repeated keywords pair up in order of appearance.
+    for left_rule in left {
+        let paired = unmatched_right.iter().position(|candidate| {
+            candidate.is_some_and(|candidate| candidate.keyword == left_rule.keyword)
+        });
+        match paired {
+            Some(index) => {
+                let right_rule = unmatched_right[index].take().expect("paired rule");
+                paired_right_indexes.push(index);
+                let changes = rule_field_changes(left_rule, right_rule);
+                if !changes.is_empty() {
+                    modified_rules.push(FilterRuleChange {
+                        keyword: left_rule.keyword.clone(),
+                        changes,
+                    });
+                }
+            }
+            None => removed_rules.push(left_rule.clone()),
+        }
+    }
+
+    let added_rules: Vec<FilterRuleInput> =
+        unmatched_right.into_iter().flatten().cloned().collect();
+    let order_changed = paired_right_indexes
+        .windows(2)
+        .any(|pair| pair[0] > pair[1]);
+    let status = if added_rules.is_empty()
+        && removed_rules.is_empty()
+        && modified_rules.is_empty()
+        && !order_changed
+    {
+        "unchanged"
+    } else {
+        "modified"
+    };
+
+    FilterRuleGroupDiff {
+        name: name.to_string(),
+        status: status.to_string(),
+        added_rules,
+        removed_rules,
+        modified_rules,
+        order_changed,
+    }
+}
+
+pub(crate) fn diff_filter_rule_groups_impl(
+    left: Vec<FilterRuleGroupConfig>,
+    right: Vec<FilterRuleGroupConfig>,
+) -> Vec<FilterRuleGroupDiff> {
+    let left = normalize_filter_rule_groups(Some(left)).unwrap_or_default();
+    let right = normalize_filter_rule_groups(Some(right)).unwrap_or_default();
+    let mut diffs = Vec::new();
+
+    for left_group in &left {
+        let diff = match right.iter().find(|group| group.name == left_group.name) {
+            Some(right_group) => {
+                diff_group_rules(&left_group.name, &left_group.rules, &right_group.rules)
+            }
+            None => FilterRuleGroupDiff {
+                status: "removed".to_string(),
+                removed_rules: left_group.rules.clone(),
+                ..diff_group_rules(&left_group.name, &[], &[])
+            },
+        };
+        diffs.push(diff);
+    }
+
+    for right_group in &right {
+        if left.iter().all(|group| group.name != right_group.name) {
+            diffs.push(FilterRuleGroupDiff {
+                status: "added".to_string(),
+                added_rules: right_group.rules.clone(),
+                ..diff_group_rules(&right_group.name, &[], &[])
+            });
+        }
+    }
+
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_rule(keyword: &str, background_color: &str, bold: bool) -> FilterRuleInput {
+        FilterRuleInput {
+            keyword: keyword.to_string(),
+            match_mode: "contains".to_string(),
+            background_color: background_color.to_string(),
+            text_color: "#000000".to_string(),
+            bold,
+            italic: false,
+            apply_to: "line".to_string(),
+            alert: None,
+        }
+    }
+
+    fn make_group(name: &str, rules: Vec<FilterRuleInput>) -> FilterRuleGroupConfig {
+        FilterRuleGroupConfig {
+            name: name.to_string(),
+            rules,
+            shared_source: None,
+        }
+    }
+
+    #[test]
+    fn diff_filter_rule_groups_should_report_rule_and_field_changes() {
+        let left = vec![
+            make_group(
+                "Errors",
+                vec![
+                    make_rule("ERROR", "#ff0000", false),
+                    make_rule("WARN", "#ffff00", false),
+                ],
+            ),
+            make_group("Old", vec![make_rule("legacy", "#cccccc", false)]),
+        ];
+        let right = vec![
+            make_group(
+                "Errors",
+                vec![
+                    make_rule(" ERROR ", "#ff0000", true),
+                    make_rule("FATAL", "#990000", true),
+                ],
+            ),
+            make_group("New", vec![make_rule("trace", "#eeeeee", false)]),
+        ];
+
+        let diffs = diff_filter_rule_groups_impl(left, right);
+        let statuses: Vec<_> = diffs
+            .iter()
+            .map(|diff| (diff.name.as_str(), diff.status.as_str()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![("Errors", "modified"), ("Old", "removed"), ("New", "added")]
+        );
+
+        let errors = &diffs[0];
+        assert_eq!(errors.added_rules[0].keyword, "FATAL");
+        assert_eq!(errors.removed_rules[0].keyword, "WARN");
+        assert_eq!(
+            errors.modified_rules[0].changes,
+            vec![FilterRuleFieldChange {
+                field: "bold".to_string(),
+                left: "false".to_string(),
+                right: "true".to_string(),
+            }]
+        );
+        assert!(!errors.order_changed);
+    }
+
+    #[test]
+    fn diff_filter_rule_groups_should_detect_reordered_rules() {
+        let rules = vec![
+            make_rule("a", "#111111", false),
+            make_rule("b", "#222222", false),
+        ];
+        let reversed = rules.iter().rev().cloned().collect();
+
+        let same = diff_filter_rule_groups_impl(
+            vec![make_group("G", rules.clone())],
+            vec![make_group("G", rules.clone())],
+        );
+        assert_eq!(same[0].status, "unchanged");
+
+        let diffs = diff_filter_rule_groups_impl(
+            vec![make_group("G", rules)],
+            vec![make_group("G", reversed)],
+        );
+        assert_eq!(diffs[0].status, "modified");
+        assert!(diffs[0].order_changed);
+        assert!(diffs[0].modified_rules.is_empty());
+    }
+}
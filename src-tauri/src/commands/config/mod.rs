@@ -2,21 +2,34 @@
 // - `profile`: cross-platform normalization + load/save + filter groups +
 //   tests; also hosts the cross-platform fa\xC3\xA7ade for Windows-shell
 //   integrations.
+// - `filter_rule_diff`: compares two filter rule group lists before an import.
+// - `shared_filter_rules`: read-only groups from a watched shared folder.
 // - `windows_integration`: Windows-only registry + Shell APIs reached via the
 //   `#[cfg(windows)]` branches in `profile`.
 
+mod filter_rule_diff;
 mod profile;
+mod shared_filter_rules;
 #[cfg(windows)]
 mod windows_integration;
 
+pub(super) use filter_rule_diff::{diff_filter_rule_groups_impl, FilterRuleGroupDiff};
 pub(super) use profile::{
-    apply_windows_file_associations_impl, export_filter_rule_groups_impl,
+    add_recent_path_impl, apply_windows_file_associations_impl, clear_recent_paths_impl,
+    config_dir_path_impl, enable_safe_mode_impl, export_filter_rule_groups_impl,
     get_default_windows_file_association_extensions_impl, get_startup_paths_impl,
     get_windows_file_association_status_impl, import_filter_rule_groups_impl,
-    is_remember_window_state_enabled_in_config_impl, is_single_instance_mode_enabled_in_config_impl,
-    is_windows_context_menu_registered_impl, load_config_impl,
-    load_filter_rule_groups_config_impl, load_main_window_state_in_config_impl,
-    register_windows_context_menu_impl, remove_windows_file_associations_impl, save_config_impl,
-    save_filter_rule_groups_config_impl, save_main_window_state_in_config_impl,
+    is_clipboard_history_persisted_in_config_impl, is_remember_window_state_enabled_in_config_impl,
+    is_safe_mode_impl, is_single_instance_mode_enabled_in_config_impl,
+    is_windows_context_menu_registered_impl, load_auto_save_settings_in_config_impl,
+    load_config_impl, load_filter_rule_groups_config_impl,
+    load_fold_min_block_lines_in_config_impl, load_idle_trim_settings_in_config_impl,
+    load_main_window_state_in_config_impl, load_remote_control_settings_in_config_impl,
+    register_windows_context_menu_impl, remove_recent_path_impl,
+    remove_windows_file_associations_impl, save_config_impl, save_filter_rule_groups_config_impl,
+    save_main_window_state_in_config_impl, set_rule_alert_impl,
     unregister_windows_context_menu_impl,
 };
+pub(super) use shared_filter_rules::{
+    handle_shared_filter_rule_groups_watch_batch_impl, sync_shared_filter_rule_groups_watch_impl,
+};
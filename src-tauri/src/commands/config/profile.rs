@@ -5,11 +5,26 @@
 
 use super::super::*;
 
-use std::path::PathBuf;
+use crate::state::{ConfigFileStamp, ConfigManager};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
+use super::shared_filter_rules::load_shared_filter_rule_groups;
 #[cfg(windows)]
 use super::windows_integration;
 
+// Set once from `--safe-mode` before the app is built. The config file is then
+// neither read nor written, so a corrupt one cannot block startup.
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn enable_safe_mode_impl() {
+    SAFE_MODE.store(true, Ordering::Relaxed);
+}
+
+pub(crate) fn is_safe_mode_impl() -> bool {
+    SAFE_MODE.load(Ordering::Relaxed)
+}
+
 pub(crate) fn normalize_filter_rule_input(rule: FilterRuleInput) -> Option<FilterRuleInput> {
     let keyword = rule.keyword.trim().to_string();
     if keyword.is_empty() {
@@ -43,6 +58,7 @@ pub(crate) fn normalize_filter_rule_input(rule: FilterRuleInput) -> Option<Filte
         bold: rule.bold,
         italic: rule.italic,
         apply_to,
+        alert: rule.alert.filter(FilterRuleAlertOptions::is_enabled),
     })
 }
 
@@ -68,7 +84,11 @@ pub(crate) fn normalize_filter_rule_groups(
                 return None;
             }
 
-            Some(FilterRuleGroupConfig { name, rules })
+            Some(FilterRuleGroupConfig {
+                name,
+                rules,
+                shared_source: None,
+            })
         })
         .collect();
 
@@ -269,6 +289,24 @@ pub(crate) fn normalize_translation_settings(
     }
 }
 
+// Privileged ports are never used; the token is compared verbatim apart from
+// surrounding whitespace.
+pub(crate) fn normalize_remote_control_settings(
+    settings: Option<settings::RemoteControlSettingsConfig>,
+) -> settings::RemoteControlSettingsConfig {
+    let settings = settings.unwrap_or_default();
+
+    settings::RemoteControlSettingsConfig {
+        enabled: settings.enabled,
+        port: if settings.port < 1024 {
+            DEFAULT_REMOTE_CONTROL_PORT
+        } else {
+            settings.port
+        },
+        token: settings.token.trim().to_string(),
+    }
+}
+
 pub(crate) fn normalize_window_state(
     window_state: Option<settings::WindowStateConfig>,
 ) -> Option<settings::WindowStateConfig> {
@@ -299,14 +337,33 @@ pub(crate) fn normalize_app_config(config: AppConfig) -> AppConfig {
         new_file_line_ending: settings::normalize_new_file_line_ending(Some(
             config.new_file_line_ending.as_str(),
         )),
+        file_share_mode: settings::normalize_file_share_mode(Some(config.file_share_mode.as_str())),
+        auto_save_mode: settings::normalize_auto_save_mode(Some(config.auto_save_mode.as_str())),
+        auto_save_interval_secs: settings::normalize_auto_save_interval_secs(
+            config.auto_save_interval_secs,
+        ),
+        idle_trim_after_secs: settings::normalize_idle_trim_after_secs(config.idle_trim_after_secs),
+        stale_cache_max_age_mins: settings::normalize_stale_cache_max_age_mins(
+            config.stale_cache_max_age_mins,
+        ),
+        max_undo_history: settings::normalize_max_undo_history(config.max_undo_history),
+        fold_min_block_lines: settings::normalize_fold_min_block_lines(config.fold_min_block_lines),
+        preview_mode_threshold_mb: config.preview_mode_threshold_mb,
+        untrusted_folders: normalize_recent_paths(Some(config.untrusted_folders)),
         word_wrap: config.word_wrap,
         minimap: config.minimap,
         minimap_autohide: config.minimap_autohide,
+        extra_word_chars: settings::normalize_extra_word_chars(&config.extra_word_chars),
+        extra_word_chars_by_syntax: settings::normalize_extra_word_chars_by_syntax(
+            config.extra_word_chars_by_syntax,
+        ),
         double_click_close_tab: config.double_click_close_tab,
         show_line_numbers: config.show_line_numbers,
         highlight_current_line: config.highlight_current_line,
         single_instance_mode: config.single_instance_mode,
         remember_window_state: config.remember_window_state,
+        auto_reload_unmodified_files: config.auto_reload_unmodified_files,
+        persist_clipboard_history: config.persist_clipboard_history,
         recent_files: normalize_recent_paths(Some(config.recent_files)),
         recent_folders: normalize_recent_paths(Some(config.recent_folders)),
         recent_search_keywords: normalize_recent_text_history(Some(config.recent_search_keywords)),
@@ -318,8 +375,13 @@ pub(crate) fn normalize_app_config(config: AppConfig) -> AppConfig {
         mouse_gestures_enabled: config.mouse_gestures_enabled,
         mouse_gestures: normalize_mouse_gestures(Some(config.mouse_gestures)),
         translation: normalize_translation_settings(Some(config.translation)),
+        remote_control: normalize_remote_control_settings(Some(config.remote_control)),
         window_state: normalize_window_state(config.window_state),
         filter_rule_groups: normalize_filter_rule_groups(config.filter_rule_groups),
+        shared_filter_rule_groups_path: config.shared_filter_rule_groups_path.trim().to_string(),
+        syntax_extension_overrides: config
+            .syntax_extension_overrides
+            .map(settings::normalize_syntax_extension_overrides),
     }
 }
 
@@ -332,6 +394,13 @@ fn config_file_path() -> Result<PathBuf, String> {
     )
 }
 
+pub(crate) fn config_dir_path_impl() -> Result<PathBuf, String> {
+    let path = config_file_path()?;
+    path.parent()
+        .map(|dir| dir.to_path_buf())
+        .ok_or_else(|| "Failed to locate config directory".to_string())
+}
+
 fn config_file_path_for_platform(
     platform: &str,
     app_data: Option<std::ffi::OsString>,
@@ -362,7 +431,7 @@ fn config_file_path_for_platform(
     }
 }
 
-pub(crate) fn get_startup_paths_impl(state: State<'_, AppState>) -> Vec<String> {
+pub(crate) fn get_startup_paths_impl(state: State<'_, AppState>) -> Vec<StartupPath> {
     state.take_startup_paths()
 }
 
@@ -394,6 +463,10 @@ pub(crate) fn unregister_windows_context_menu_impl() -> Result<(), String> {
 }
 
 pub(crate) fn is_windows_context_menu_registered_impl() -> bool {
+    if is_safe_mode_impl() {
+        return false;
+    }
+
     #[cfg(not(windows))]
     {
         false
@@ -456,6 +529,13 @@ pub(crate) fn get_windows_file_association_status_impl(
 
     #[cfg(windows)]
     {
+        if is_safe_mode_impl() {
+            return WindowsFileAssociationStatus {
+                enabled: false,
+                extensions: normalized_extensions,
+            };
+        }
+
         WindowsFileAssociationStatus {
             enabled: windows_integration::is_windows_file_association_registered(
                 &normalized_extensions,
@@ -467,13 +547,58 @@ pub(crate) fn get_windows_file_association_status_impl(
 
 // --- Persisted config IO --------------------------------------------------
 
+// Every load and save goes through the shared manager, so startup helpers and
+// commands reuse one parsed config and commands that save together no longer
+// race.
+static CONFIG_MANAGER: ConfigManager =
+    ConfigManager::new(config_file_stamp, load_config_file, write_config_file);
+
 pub(crate) fn load_config_impl() -> Result<AppConfig, String> {
+    if is_safe_mode_impl() {
+        return Ok(AppConfig::default());
+    }
+
+    CONFIG_MANAGER.get()
+}
+
+// Read-modify-write on the shared config, written before it returns.
+fn update_config_impl<T>(
+    edit: impl FnOnce(&mut AppConfig) -> Result<T, String>,
+) -> Result<T, String> {
+    if is_safe_mode_impl() {
+        return Err("Config is not saved in safe mode".to_string());
+    }
+
+    CONFIG_MANAGER.update(edit)
+}
+
+fn config_file_stamp() -> ConfigFileStamp {
+    let metadata = fs::metadata(config_file_path().ok()?).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn load_config_file() -> Result<AppConfig, String> {
+    read_config_file(&config_file_path()?)
+}
+
+fn write_config_file(config: &AppConfig) -> Result<(), String> {
     let path = config_file_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    file_io::write_file_atomically(&path, format!("{}\n", content).as_bytes(), None)?;
+    Ok(())
+}
+
+fn read_config_file(path: &Path) -> Result<AppConfig, String> {
     if !path.exists() {
         return Ok(AppConfig::default());
     }
 
-    let raw = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
     if raw.trim().is_empty() {
         return Ok(AppConfig::default());
     }
@@ -515,6 +640,47 @@ pub(crate) fn load_config_impl() -> Result<AppConfig, String> {
             settings::normalize_new_file_line_ending(Some(new_file_line_ending.as_str()));
     }
 
+    if let Some(file_share_mode) = partial.file_share_mode {
+        config.file_share_mode =
+            settings::normalize_file_share_mode(Some(file_share_mode.as_str()));
+    }
+
+    if let Some(auto_save_mode) = partial.auto_save_mode {
+        config.auto_save_mode = settings::normalize_auto_save_mode(Some(auto_save_mode.as_str()));
+    }
+
+    if let Some(auto_save_interval_secs) = partial.auto_save_interval_secs {
+        config.auto_save_interval_secs =
+            settings::normalize_auto_save_interval_secs(auto_save_interval_secs);
+    }
+
+    if let Some(idle_trim_after_secs) = partial.idle_trim_after_secs {
+        config.idle_trim_after_secs =
+            settings::normalize_idle_trim_after_secs(idle_trim_after_secs);
+    }
+
+    if let Some(stale_cache_max_age_mins) = partial.stale_cache_max_age_mins {
+        config.stale_cache_max_age_mins =
+            settings::normalize_stale_cache_max_age_mins(stale_cache_max_age_mins);
+    }
+
+    if let Some(max_undo_history) = partial.max_undo_history {
+        config.max_undo_history = settings::normalize_max_undo_history(max_undo_history);
+    }
+
+    if let Some(fold_min_block_lines) = partial.fold_min_block_lines {
+        config.fold_min_block_lines =
+            settings::normalize_fold_min_block_lines(fold_min_block_lines);
+    }
+
+    if let Some(preview_mode_threshold_mb) = partial.preview_mode_threshold_mb {
+        config.preview_mode_threshold_mb = preview_mode_threshold_mb;
+    }
+
+    if let Some(untrusted_folders) = partial.untrusted_folders {
+        config.untrusted_folders = normalize_recent_paths(Some(untrusted_folders));
+    }
+
     if let Some(word_wrap) = partial.word_wrap {
         config.word_wrap = word_wrap;
     }
@@ -525,6 +691,13 @@ pub(crate) fn load_config_impl() -> Result<AppConfig, String> {
     if let Some(minimap_autohide) = partial.minimap_autohide {
         config.minimap_autohide = minimap_autohide;
     }
+    if let Some(extra_word_chars) = partial.extra_word_chars {
+        config.extra_word_chars = settings::normalize_extra_word_chars(&extra_word_chars);
+    }
+    if let Some(extra_word_chars_by_syntax) = partial.extra_word_chars_by_syntax {
+        config.extra_word_chars_by_syntax =
+            settings::normalize_extra_word_chars_by_syntax(extra_word_chars_by_syntax);
+    }
     if let Some(double_click_close_tab) = partial.double_click_close_tab {
         config.double_click_close_tab = double_click_close_tab;
     }
@@ -545,6 +718,14 @@ pub(crate) fn load_config_impl() -> Result<AppConfig, String> {
         config.remember_window_state = remember_window_state;
     }
 
+    if let Some(auto_reload_unmodified_files) = partial.auto_reload_unmodified_files {
+        config.auto_reload_unmodified_files = auto_reload_unmodified_files;
+    }
+
+    if let Some(persist_clipboard_history) = partial.persist_clipboard_history {
+        config.persist_clipboard_history = persist_clipboard_history;
+    }
+
     if let Some(recent_files) = partial.recent_files {
         config.recent_files = normalize_recent_paths(Some(recent_files));
     }
@@ -580,10 +761,30 @@ pub(crate) fn load_config_impl() -> Result<AppConfig, String> {
 
     config.translation = normalize_translation_settings(partial.translation);
 
+    if let Some(remote_control) = partial.remote_control {
+        config.remote_control = normalize_remote_control_settings(Some(remote_control));
+    }
+
     config.window_state = normalize_window_state(partial.window_state);
 
     config.filter_rule_groups = normalize_filter_rule_groups(partial.filter_rule_groups);
 
+    if let Some(shared_filter_rule_groups_path) = partial.shared_filter_rule_groups_path {
+        config.shared_filter_rule_groups_path = shared_filter_rule_groups_path.trim().to_string();
+    }
+
+    if let Some(syntax_extension_overrides) = partial.syntax_extension_overrides {
+        config.syntax_extension_overrides = Some(settings::normalize_syntax_extension_overrides(
+            syntax_extension_overrides,
+        ));
+    }
+    syntax::set_syntax_extension_overrides(
+        config
+            .syntax_extension_overrides
+            .clone()
+            .unwrap_or_default(),
+    );
+
     Ok(config)
 }
 
@@ -593,12 +794,89 @@ pub(crate) fn is_single_instance_mode_enabled_in_config_impl() -> bool {
         .unwrap_or(DEFAULT_SINGLE_INSTANCE_MODE)
 }
 
+// Secret holding the remote control token. config.json only keeps it where
+// there is no secret store to move it into.
+pub(crate) const REMOTE_CONTROL_TOKEN_SECRET: &str = "remote-control.token";
+
+// Moves a token found in the config, from a settings payload or written by an
+// older version, into the secret store. Without a usable store the token stays
+// in the settings so remote control keeps working; returns whether it moved.
+fn move_remote_control_token_to_secrets(
+    settings: &mut settings::RemoteControlSettingsConfig,
+) -> bool {
+    if settings.token.is_empty() {
+        return false;
+    }
+
+    match secrets::store_secret_impl(
+        REMOTE_CONTROL_TOKEN_SECRET.to_string(),
+        settings.token.clone(),
+    ) {
+        Ok(()) => {
+            settings.token.clear();
+            true
+        }
+        Err(error) => {
+            eprintln!("failed to move the remote control token to the secret store: {error}");
+            false
+        }
+    }
+}
+
+// (port, token) when the remote control endpoint should be started.
+pub(crate) fn load_remote_control_settings_in_config_impl() -> Result<Option<(u16, String)>, String>
+{
+    let mut settings = load_config_impl()?.remote_control;
+    if move_remote_control_token_to_secrets(&mut settings) {
+        update_config_impl(|config| {
+            config.remote_control.token.clear();
+            Ok(())
+        })?;
+    }
+    if !settings.enabled {
+        return Ok(None);
+    }
+
+    let token = if settings.token.is_empty() {
+        secrets::get_secret_impl(REMOTE_CONTROL_TOKEN_SECRET)?.unwrap_or_default()
+    } else {
+        settings.token
+    };
+    Ok((!token.is_empty()).then_some((settings.port, token)))
+}
+
 pub(crate) fn is_remember_window_state_enabled_in_config_impl() -> bool {
     load_config_impl()
         .map(|config| config.remember_window_state)
         .unwrap_or(true)
 }
 
+pub(crate) fn is_clipboard_history_persisted_in_config_impl() -> bool {
+    load_config_impl()
+        .map(|config| config.persist_clipboard_history)
+        .unwrap_or(false)
+}
+
+pub(crate) fn load_auto_save_settings_in_config_impl() -> (String, u64) {
+    let config = load_config_impl().unwrap_or_default();
+    (config.auto_save_mode, config.auto_save_interval_secs)
+}
+
+pub(crate) fn load_idle_trim_settings_in_config_impl() -> (u64, u64, usize) {
+    let config = load_config_impl().unwrap_or_default();
+    (
+        config.idle_trim_after_secs,
+        config.stale_cache_max_age_mins,
+        config.max_undo_history,
+    )
+}
+
+pub(crate) fn load_fold_min_block_lines_in_config_impl() -> usize {
+    load_config_impl()
+        .map(|config| config.fold_min_block_lines)
+        .unwrap_or(DEFAULT_FOLD_MIN_BLOCK_LINES)
+}
+
 pub(crate) fn load_main_window_state_in_config_impl() -> Option<settings::WindowStateConfig> {
     load_config_impl()
         .ok()
@@ -611,53 +889,154 @@ pub(crate) fn save_main_window_state_in_config_impl(
     height: Option<u32>,
     maximized: bool,
 ) -> Result<(), String> {
-    let mut config = load_config_impl().unwrap_or_default();
-    config.window_state = normalize_window_state(Some(settings::WindowStateConfig {
-        width: if maximized { None } else { width },
-        height: if maximized { None } else { height },
-        maximized,
-    }));
-
-    save_config_impl(config)
+    update_config_impl(|config| {
+        config.window_state = normalize_window_state(Some(settings::WindowStateConfig {
+            width: if maximized { None } else { width },
+            height: if maximized { None } else { height },
+            maximized,
+        }));
+        Ok(())
+    })
 }
 
 pub(crate) fn save_config_impl(config: AppConfig) -> Result<(), String> {
     let mut normalized = normalize_app_config(config);
+    move_remote_control_token_to_secrets(&mut normalized.remote_control);
 
-    if normalized.filter_rule_groups.is_none() {
-        if let Ok(existing) = load_config_impl() {
-            normalized.filter_rule_groups = existing.filter_rule_groups;
+    let saved = update_config_impl(|current| {
+        if normalized.filter_rule_groups.is_none() {
+            normalized.filter_rule_groups = current.filter_rule_groups.clone();
         }
+        if normalized.syntax_extension_overrides.is_none() {
+            normalized.syntax_extension_overrides = current.syntax_extension_overrides.clone();
+        }
+        // Recent lists only change through the recent path commands.
+        normalized.recent_files = current.recent_files.clone();
+        normalized.recent_folders = current.recent_folders.clone();
+        *current = normalized.clone();
+        Ok(normalized)
+    })?;
+    syntax::set_syntax_extension_overrides(
+        saved.syntax_extension_overrides.clone().unwrap_or_default(),
+    );
+    if !saved.persist_clipboard_history {
+        clipboard_history::remove_persisted_clipboard_history_impl()?;
     }
 
-    let path = config_file_path()?;
-
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-
-    let content = serde_json::to_string_pretty(&normalized).map_err(|e| e.to_string())?;
-    fs::write(path, format!("{}\n", content)).map_err(|e| e.to_string())?;
-
     #[cfg(windows)]
     {
-        windows_integration::sync_with_saved_config(&normalized)?;
+        windows_integration::sync_with_saved_config(&saved)?;
     }
 
     Ok(())
 }
 
+fn recent_paths_mut<'a>(
+    config: &'a mut AppConfig,
+    kind: &str,
+) -> Result<&'a mut Vec<String>, String> {
+    match kind {
+        "files" => Ok(&mut config.recent_files),
+        "folders" => Ok(&mut config.recent_folders),
+        _ => Err(format!("Unsupported recent path kind: {kind}")),
+    }
+}
+
+// Moves `path` to the front, dropping the oldest entry past the limit.
+fn push_recent_path(paths: &mut Vec<String>, path: String) {
+    let mut next_paths = vec![path];
+    next_paths.append(paths);
+    *paths = normalize_recent_paths(Some(next_paths));
+}
+
+// Returns the updated list.
+pub(crate) fn add_recent_path_impl(kind: &str, path: String) -> Result<Vec<String>, String> {
+    update_config_impl(|config| {
+        let paths = recent_paths_mut(config, kind)?;
+        push_recent_path(paths, path);
+        Ok(paths.clone())
+    })
+}
+
+pub(crate) fn remove_recent_path_impl(kind: &str, path: String) -> Result<Vec<String>, String> {
+    update_config_impl(|config| {
+        let paths = recent_paths_mut(config, kind)?;
+        paths.retain(|item| item != path.trim());
+        Ok(paths.clone())
+    })
+}
+
+pub(crate) fn clear_recent_paths_impl(kind: &str) -> Result<(), String> {
+    update_config_impl(|config| {
+        recent_paths_mut(config, kind)?.clear();
+        Ok(())
+    })
+}
+
+// Own groups first, then the read-only groups of the shared folder.
 pub(crate) fn load_filter_rule_groups_config_impl() -> Result<Vec<FilterRuleGroupConfig>, String> {
     let config = load_config_impl()?;
-    Ok(config.filter_rule_groups.unwrap_or_default())
+    let mut groups = config.filter_rule_groups.unwrap_or_default();
+    groups.extend(load_shared_filter_rule_groups(
+        config.shared_filter_rule_groups_path.as_str(),
+    ));
+    Ok(groups)
 }
 
 pub(crate) fn save_filter_rule_groups_config_impl(
     groups: Vec<FilterRuleGroupConfig>,
 ) -> Result<(), String> {
-    let mut config = load_config_impl()?;
-    config.filter_rule_groups = normalize_filter_rule_groups(Some(groups));
-    save_config_impl(config)
+    let own_groups = groups
+        .into_iter()
+        .filter(|group| group.shared_source.is_none())
+        .collect();
+    update_config_impl(|config| {
+        config.filter_rule_groups = normalize_filter_rule_groups(Some(own_groups));
+        Ok(())
+    })
+}
+
+// Passing `None`, or options with every action off, removes the alert.
+// Returns all groups as `load_filter_rule_groups_config` would.
+pub(crate) fn set_rule_alert_impl(
+    group: String,
+    rule_index: usize,
+    options: Option<FilterRuleAlertOptions>,
+) -> Result<Vec<FilterRuleGroupConfig>, String> {
+    update_config_impl(|config| {
+        let mut groups = config.filter_rule_groups.take().unwrap_or_default();
+        let Some(target_group) = groups.iter_mut().find(|item| item.name == group) else {
+            let is_shared =
+                load_shared_filter_rule_groups(config.shared_filter_rule_groups_path.as_str())
+                    .iter()
+                    .any(|item| item.name == group);
+            return Err(if is_shared {
+                "Shared filter rule groups are read-only".to_string()
+            } else {
+                "Filter rule group not found".to_string()
+            });
+        };
+        let rule = target_group
+            .rules
+            .get_mut(rule_index)
+            .ok_or_else(|| "Filter rule not found".to_string())?;
+        rule.alert = options.filter(FilterRuleAlertOptions::is_enabled);
+
+        config.filter_rule_groups = normalize_filter_rule_groups(Some(groups));
+        Ok(())
+    })?;
+    load_filter_rule_groups_config_impl()
+}
+
+// Accepts the export format as well as a bare array of groups.
+pub(super) fn parse_filter_rule_groups_file(
+    raw: &str,
+) -> Result<Vec<FilterRuleGroupConfig>, String> {
+    match serde_json::from_str::<FilterRuleGroupsFilePayload>(raw) {
+        Ok(payload) => Ok(payload.filter_rule_groups),
+        Err(_) => serde_json::from_str::<Vec<FilterRuleGroupConfig>>(raw)
+            .map_err(|e| format!("Failed to parse filter groups file: {}", e)),
+    }
 }
 
 pub(crate) fn import_filter_rule_groups_impl(
@@ -668,11 +1047,7 @@ pub(crate) fn import_filter_rule_groups_impl(
         return Err("Import file is empty".to_string());
     }
 
-    let parsed_groups = match serde_json::from_str::<FilterRuleGroupsFilePayload>(&raw) {
-        Ok(payload) => payload.filter_rule_groups,
-        Err(_) => serde_json::from_str::<Vec<FilterRuleGroupConfig>>(&raw)
-            .map_err(|e| format!("Failed to parse filter groups file: {}", e))?,
-    };
+    let parsed_groups = parse_filter_rule_groups_file(&raw)?;
 
     let normalized = normalize_filter_rule_groups(Some(parsed_groups)).unwrap_or_default();
     if normalized.is_empty() {
@@ -709,6 +1084,53 @@ pub(crate) fn export_filter_rule_groups_impl(
 mod tests {
     use super::*;
 
+    static PERSISTED_TEST_CONFIGS: std::sync::Mutex<Vec<AppConfig>> =
+        std::sync::Mutex::new(Vec::new());
+
+    fn unchanged_test_stamp() -> ConfigFileStamp {
+        None
+    }
+
+    fn load_test_config() -> Result<AppConfig, String> {
+        Ok(AppConfig::default())
+    }
+
+    fn persist_test_config(config: &AppConfig) -> Result<(), String> {
+        PERSISTED_TEST_CONFIGS
+            .lock()
+            .expect("persisted configs lock")
+            .push(config.clone());
+        Ok(())
+    }
+
+    // A stand-in for config.json: a version used as the stamp and the result of
+    // parsing it.
+    static TEST_CONFIG_FILE: std::sync::Mutex<(u64, Result<AppConfig, String>)> =
+        std::sync::Mutex::new((0, Err(String::new())));
+
+    fn set_test_config_file(content: Result<AppConfig, String>) {
+        let mut file = TEST_CONFIG_FILE.lock().expect("config file lock");
+        file.0 += 1;
+        file.1 = content;
+    }
+
+    fn test_config_file_stamp() -> ConfigFileStamp {
+        let version = TEST_CONFIG_FILE.lock().expect("config file lock").0;
+        Some((
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(version),
+            0,
+        ))
+    }
+
+    fn load_test_config_file() -> Result<AppConfig, String> {
+        TEST_CONFIG_FILE.lock().expect("config file lock").1.clone()
+    }
+
+    fn write_test_config_file(config: &AppConfig) -> Result<(), String> {
+        set_test_config_file(Ok(config.clone()));
+        Ok(())
+    }
+
     fn make_rule(
         keyword: &str,
         match_mode: &str,
@@ -723,6 +1145,7 @@ mod tests {
             bold: false,
             italic: false,
             apply_to: apply_to.to_string(),
+            alert: None,
         }
     }
 
@@ -825,6 +1248,7 @@ mod tests {
             FilterRuleGroupConfig {
                 name: "  ".to_string(),
                 rules: vec![make_rule("x", "contains", "line", "#fff")],
+                shared_source: None,
             },
             FilterRuleGroupConfig {
                 name: " Main ".to_string(),
@@ -832,6 +1256,7 @@ mod tests {
                     make_rule(" ok ", "contains", "line", "#fff"),
                     make_rule(" ", "contains", "line", "#fff"),
                 ],
+                shared_source: None,
             },
         ];
 
@@ -961,14 +1386,30 @@ mod tests {
             tab_width: 0,
             tab_indent_mode: "invalid".to_string(),
             new_file_line_ending: "bad".to_string(),
+            file_share_mode: "locked".to_string(),
+            auto_save_mode: "sometimes".to_string(),
+            auto_save_interval_secs: 0,
+            idle_trim_after_secs: 5,
+            stale_cache_max_age_mins: 0,
+            max_undo_history: 50,
+            fold_min_block_lines: 0,
+            preview_mode_threshold_mb: 0,
+            untrusted_folders: vec![" /var/log ".to_string()],
             word_wrap: true,
             minimap: false,
             minimap_autohide: false,
+            extra_word_chars: "a-".to_string(),
+            extra_word_chars_by_syntax: std::collections::BTreeMap::from([(
+                "PHP".to_string(),
+                "$$".to_string(),
+            )]),
             double_click_close_tab: true,
             show_line_numbers: true,
             highlight_current_line: true,
             single_instance_mode: true,
             remember_window_state: true,
+            auto_reload_unmodified_files: true,
+            persist_clipboard_history: true,
             recent_files: vec!["  a  ".to_string(), "a".to_string()],
             recent_folders: vec!["  b  ".to_string(), "b".to_string()],
             recent_search_keywords: vec![
@@ -996,6 +1437,11 @@ mod tests {
                     proxy_url: " http://127.0.0.1:7890 ".to_string(),
                 },
             },
+            remote_control: settings::RemoteControlSettingsConfig {
+                enabled: true,
+                port: 80,
+                token: " secret ".to_string(),
+            },
             window_state: Some(settings::WindowStateConfig {
                 width: Some(0),
                 height: Some(1),
@@ -1004,7 +1450,13 @@ mod tests {
             filter_rule_groups: Some(vec![FilterRuleGroupConfig {
                 name: " Group ".to_string(),
                 rules: vec![make_rule(" key ", "contains", "line", "#fff")],
+                shared_source: None,
             }]),
+            shared_filter_rule_groups_path: " /srv/rules ".to_string(),
+            syntax_extension_overrides: Some(std::collections::BTreeMap::from([(
+                ".TPL".to_string(),
+                "html".to_string(),
+            )])),
         };
 
         let normalized = normalize_app_config(config);
@@ -1018,8 +1470,34 @@ mod tests {
             normalized.new_file_line_ending,
             crate::state::default_line_ending().label()
         );
+        assert_eq!(normalized.file_share_mode, "readWrite");
+        assert_eq!(normalized.auto_save_mode, "off");
+        assert_eq!(normalized.auto_save_interval_secs, 1);
+        assert_eq!(normalized.idle_trim_after_secs, 60);
+        assert_eq!(normalized.stale_cache_max_age_mins, 1);
+        assert_eq!(normalized.max_undo_history, 50);
+        assert_eq!(normalized.fold_min_block_lines, 2);
+        assert_eq!(normalized.preview_mode_threshold_mb, 0);
+        assert_eq!(normalized.untrusted_folders, vec!["/var/log".to_string()]);
+        assert_eq!(normalized.shared_filter_rule_groups_path, "/srv/rules");
+        assert_eq!(
+            normalized
+                .syntax_extension_overrides
+                .as_ref()
+                .and_then(|overrides| overrides.get("tpl"))
+                .map(String::as_str),
+            Some("html")
+        );
         assert!(!normalized.minimap);
         assert!(!normalized.minimap_autohide);
+        assert_eq!(normalized.extra_word_chars, "-");
+        assert_eq!(
+            normalized
+                .extra_word_chars_by_syntax
+                .get("php")
+                .map(String::as_str),
+            Some("$")
+        );
         assert_eq!(normalized.recent_files, vec!["a".to_string()]);
         assert_eq!(normalized.recent_folders, vec!["b".to_string()]);
         assert_eq!(
@@ -1047,6 +1525,80 @@ mod tests {
             normalized.translation.microsoft.proxy_server,
             "http://127.0.0.1:7890"
         );
+        assert_eq!(normalized.remote_control.port, DEFAULT_REMOTE_CONTROL_PORT);
+        assert_eq!(normalized.remote_control.token, "secret");
         assert!(normalized.filter_rule_groups.is_some());
     }
+
+    #[test]
+    fn push_recent_path_should_move_path_to_front_and_limit_length() {
+        let mut config = AppConfig::default();
+        let paths = recent_paths_mut(&mut config, "folders").expect("kind");
+        paths.extend((0..MAX_RECENT_PATHS).map(|i| format!("p{i}")));
+
+        push_recent_path(paths, " p3 ".to_string());
+        assert_eq!(paths.len(), MAX_RECENT_PATHS);
+        assert_eq!(paths[..2], ["p3".to_string(), "p0".to_string()]);
+
+        push_recent_path(paths, "new".to_string());
+        assert_eq!(paths.len(), MAX_RECENT_PATHS);
+        assert_eq!(paths[0], "new");
+        assert!(!paths.contains(&format!("p{}", MAX_RECENT_PATHS - 1)));
+        assert_eq!(config.recent_folders[0], "new");
+        assert!(recent_paths_mut(&mut config, "tabs").is_err());
+    }
+
+    #[test]
+    fn config_manager_should_keep_concurrent_updates_and_write_each_one() {
+        static MANAGER: ConfigManager =
+            ConfigManager::new(unchanged_test_stamp, load_test_config, persist_test_config);
+
+        let handles: Vec<_> = (0..8)
+            .map(|index| {
+                std::thread::spawn(move || {
+                    MANAGER.update(|config| {
+                        config.recent_files.push(format!("file-{index}"));
+                        Ok(())
+                    })
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("thread").expect("update");
+        }
+        assert!(MANAGER
+            .update(|_| Err::<(), _>("rejected".to_string()))
+            .is_err());
+        assert_eq!(MANAGER.get().expect("config").recent_files.len(), 8);
+
+        let persisted = PERSISTED_TEST_CONFIGS.lock().expect("lock");
+        assert_eq!(persisted.len(), 8);
+        assert_eq!(persisted.last().expect("write").recent_files.len(), 8);
+    }
+
+    #[test]
+    fn config_manager_should_reload_hand_edits_and_never_overwrite_unparseable_file() {
+        static MANAGER: ConfigManager = ConfigManager::new(
+            test_config_file_stamp,
+            load_test_config_file,
+            write_test_config_file,
+        );
+
+        set_test_config_file(Ok(AppConfig::default()));
+        assert!(MANAGER.get().expect("config").recent_files.is_empty());
+
+        let mut edited = AppConfig::default();
+        edited.recent_files.push("by-hand".to_string());
+        set_test_config_file(Ok(edited));
+        assert_eq!(MANAGER.get().expect("config").recent_files, ["by-hand"]);
+
+        set_test_config_file(Err("Failed to parse config file".to_string()));
+        let result = MANAGER.update(|config| {
+            config.recent_files.clear();
+            Ok(())
+        });
+
+        assert_eq!(result, Err("Failed to parse config file".to_string()));
+        assert!(load_test_config_file().is_err());
+    }
 }
@@ -0,0 +1,155 @@
+// Filter rule groups shared through a folder of JSON files, e.g. on a network
+// drive a team keeps its log-filter library in. Each file uses the export
+// format; its groups are merged read-only, prefixed with the file stem so
+// groups of the same name from different files stay apart.
+
+use super::super::*;
+use super::profile::{
+    load_config_impl, normalize_filter_rule_groups, parse_filter_rule_groups_file,
+};
+
+use crate::state::FileWatchBatch;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Manager};
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SharedFilterRuleGroupsChangedPayload {
+    directory: Option<String>,
+}
+
+fn is_json_file(path: &Path) -> bool {
+    path.extension()
+        .map(|extension| extension.eq_ignore_ascii_case("json"))
+        .unwrap_or(false)
+}
+
+fn read_shared_filter_rule_group_file(path: &Path) -> Vec<FilterRuleGroupConfig> {
+    let (Some(stem), Some(file_name)) = (path.file_stem(), path.file_name()) else {
+        return Vec::new();
+    };
+    let stem = stem.to_string_lossy().to_string();
+    let file_name = file_name.to_string_lossy().to_string();
+
+    let groups = match fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|raw| parse_filter_rule_groups_file(&raw))
+    {
+        Ok(groups) => groups,
+        Err(error) => {
+            eprintln!(
+                "failed to read shared filter rule groups {}: {error}",
+                path.display()
+            );
+            return Vec::new();
+        }
+    };
+
+    normalize_filter_rule_groups(Some(groups))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|mut group| {
+            group.name = format!("{stem}/{}", group.name);
+            group.shared_source = Some(file_name.clone());
+            group
+        })
+        .collect()
+}
+
+// Files are read in name order; unreadable ones are skipped.
+pub(super) fn load_shared_filter_rule_groups(directory: &str) -> Vec<FilterRuleGroupConfig> {
+    if directory.is_empty() {
+        return Vec::new();
+    }
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && is_json_file(path))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .flat_map(|path| read_shared_filter_rule_group_file(path))
+        .collect()
+}
+
+fn emit_shared_filter_rule_groups_changed(app: &AppHandle, directory: Option<&Path>) {
+    let payload = SharedFilterRuleGroupsChangedPayload {
+        directory: directory.map(|directory| directory.to_string_lossy().to_string()),
+    };
+    if let Err(error) = app.emit("rutar://filter-rule-groups-changed", payload) {
+        eprintln!("failed to emit filter rule groups change event: {error}");
+    }
+}
+
+// Called at startup and after the config is saved. The folder itself is
+// watched by the document watcher; see `AppState::sync_document_watches`.
+pub(crate) fn sync_shared_filter_rule_groups_watch_impl(app: &AppHandle) {
+    let wanted = load_config_impl()
+        .ok()
+        .map(|config| config.shared_filter_rule_groups_path)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from);
+
+    let state = app.state::<AppState>();
+    if state.set_shared_filter_rules_directory(wanted.clone()) {
+        emit_shared_filter_rule_groups_changed(app, wanted.as_deref());
+    }
+}
+
+// Called with each settled watch batch. `newly_watched` is set on the tick
+// the folder first became watchable, e.g. once a share is mounted.
+pub(crate) fn handle_shared_filter_rule_groups_watch_batch_impl(
+    app: &AppHandle,
+    batch: Option<&FileWatchBatch>,
+    newly_watched: bool,
+) {
+    let Some(directory) = app.state::<AppState>().shared_filter_rules_directory() else {
+        return;
+    };
+
+    let touched = batch.is_some_and(|batch| {
+        batch
+            .changed_paths
+            .iter()
+            .any(|path| is_json_file(path) && path.parent() == Some(directory.as_path()))
+    });
+    if touched || newly_watched {
+        emit_shared_filter_rule_groups_changed(app, Some(directory.as_path()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn load_shared_filter_rule_groups_should_namespace_groups_by_file() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or(0);
+        let directory = std::env::temp_dir().join(format!("rutar-shared-rules-{unique}"));
+        fs::create_dir_all(&directory).expect("create shared rules directory");
+        fs::write(
+            directory.join("team.json"),
+            r##"{"filterRuleGroups":[{"name":"Errors","rules":[{"keyword":"ERROR","matchMode":"contains","backgroundColor":"#f00","textColor":"","bold":true,"italic":false,"applyTo":"line"}]}]}"##,
+        )
+        .expect("write shared rules");
+        fs::write(directory.join("broken.json"), "{").expect("write broken rules");
+        fs::write(directory.join("notes.txt"), "[]").expect("write notes");
+
+        let groups = load_shared_filter_rule_groups(directory.to_string_lossy().as_ref());
+        let _ = fs::remove_dir_all(&directory);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name, "team/Errors");
+        assert_eq!(groups[0].shared_source.as_deref(), Some("team.json"));
+        assert!(load_shared_filter_rule_groups("").is_empty());
+    }
+}
@@ -29,11 +29,8 @@ pub(crate) const WIN_REGISTERED_APPLICATIONS_KEY: &str = r"Software\RegisteredAp
 pub(crate) const WIN_APP_REGISTRATION_NAME: &str = "Rutar";
 pub(crate) const WIN_APP_CAPABILITIES_KEY: &str = r"Software\Rutar\Capabilities";
 
-pub(crate) fn context_menu_display_name(language: &str) -> &'static str {
-    match settings::normalize_language(Some(language)) {
-        value if value == "zh-CN" => "\u{4f7f}\u{7528} Rutar \u{6253}\u{5f00}",
-        _ => "Open with Rutar",
-    }
+pub(crate) fn context_menu_display_name(language: &str) -> String {
+    locale::locale_text(language, "windows.contextMenuName")
 }
 
 pub(crate) fn executable_path_string() -> Result<String, String> {
@@ -122,11 +119,8 @@ fn expected_registry_command(argument_placeholder: &str) -> Option<String> {
     context_menu_command_line(argument_placeholder).ok()
 }
 
-fn windows_file_association_type_name(language: &str) -> &'static str {
-    match settings::normalize_language(Some(language)) {
-        value if value == "zh-CN" => "Rutar \u{6587}\u{672c}\u{6587}\u{6863}",
-        _ => "Rutar Text Document",
-    }
+fn windows_file_association_type_name(language: &str) -> String {
+    locale::locale_text(language, "windows.fileAssociationTypeName")
 }
 
 pub(crate) fn windows_document_icon_path_string() -> Result<String, String> {
@@ -149,18 +143,12 @@ fn windows_applications_key(executable_name: &str) -> String {
     format!(r"Software\Classes\Applications\{}", executable_name)
 }
 
-fn windows_default_app_name(language: &str) -> &'static str {
-    match settings::normalize_language(Some(language)) {
-        value if value == "zh-CN" => "Rutar",
-        _ => "Rutar",
-    }
+fn windows_default_app_name(language: &str) -> String {
+    locale::locale_text(language, "windows.appName")
 }
 
-fn windows_default_app_description(language: &str) -> &'static str {
-    match settings::normalize_language(Some(language)) {
-        value if value == "zh-CN" => "Rutar \u{6587}\u{672c}\u{7f16}\u{8f91}\u{5668}",
-        _ => "Rutar text editor",
-    }
+fn windows_default_app_description(language: &str) -> String {
+    locale::locale_text(language, "windows.appDescription")
 }
 
 fn windows_file_exts_user_choice_key(extension: &str) -> String {
@@ -510,9 +498,9 @@ pub(crate) fn register_context_menu(language: Option<String>) -> Result<(), Stri
     let normalized_language = settings::normalize_language(language.as_deref());
     let display_name = context_menu_display_name(normalized_language.as_str());
 
-    write_windows_context_shell(&hkcu, WIN_FILE_SHELL_KEY, &icon_path, "%1", display_name)?;
-    write_windows_context_shell(&hkcu, WIN_DIR_SHELL_KEY, &icon_path, "%1", display_name)?;
-    write_windows_context_shell(&hkcu, WIN_DIR_BG_SHELL_KEY, &icon_path, "%V", display_name)?;
+    write_windows_context_shell(&hkcu, WIN_FILE_SHELL_KEY, &icon_path, "%1", &display_name)?;
+    write_windows_context_shell(&hkcu, WIN_DIR_SHELL_KEY, &icon_path, "%1", &display_name)?;
+    write_windows_context_shell(&hkcu, WIN_DIR_BG_SHELL_KEY, &icon_path, "%V", &display_name)?;
 
     Ok(())
 }
@@ -628,9 +616,9 @@ pub(crate) fn sync_with_saved_config(normalized: &AppConfig) -> Result<(), Strin
         let hkcu = RegKey::predef(HKEY_CURRENT_USER);
         let display_name = context_menu_display_name(normalized.language.as_str());
 
-        set_windows_context_shell_display_name(&hkcu, WIN_FILE_SHELL_KEY, display_name)?;
-        set_windows_context_shell_display_name(&hkcu, WIN_DIR_SHELL_KEY, display_name)?;
-        set_windows_context_shell_display_name(&hkcu, WIN_DIR_BG_SHELL_KEY, display_name)?;
+        set_windows_context_shell_display_name(&hkcu, WIN_FILE_SHELL_KEY, &display_name)?;
+        set_windows_context_shell_display_name(&hkcu, WIN_DIR_SHELL_KEY, &display_name)?;
+        set_windows_context_shell_display_name(&hkcu, WIN_DIR_BG_SHELL_KEY, &display_name)?;
     }
 
     if is_windows_file_association_registered(&normalized.windows_file_association_extensions) {
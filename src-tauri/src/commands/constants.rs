@@ -1,5 +1,8 @@
 pub(super) const LARGE_FILE_THRESHOLD_BYTES: usize = 50 * 1024 * 1024;
+pub(super) const STREAMING_FILE_THRESHOLD_BYTES: u64 = 1024 * 1024 * 1024;
 pub(super) const ENCODING_DETECT_SAMPLE_BYTES: usize = 1024 * 1024;
+pub(super) const DEFAULT_ENCODING_PREVIEW_SAMPLE_BYTES: usize = 64 * 1024;
+pub(super) const DEFAULT_ENCODING_PREVIEW_MAX_LINES: usize = 20;
 pub(super) const DEFAULT_LANGUAGE: &str = "zh-CN";
 pub(super) const DEFAULT_THEME: &str = "light";
 pub(super) const DEFAULT_FONT_FAMILY: &str = "Consolas, \"Courier New\", monospace";
@@ -9,6 +12,20 @@ pub(super) const DEFAULT_DOUBLE_CLICK_CLOSE_TAB: bool = true;
 pub(super) const DEFAULT_SHOW_LINE_NUMBERS: bool = true;
 pub(super) const DEFAULT_HIGHLIGHT_CURRENT_LINE: bool = true;
 pub(super) const DEFAULT_SINGLE_INSTANCE_MODE: bool = true;
+pub(super) const DEFAULT_AUTO_SAVE_INTERVAL_SECS: u64 = 30;
+pub(super) const MAX_AUTO_SAVE_INTERVAL_SECS: u64 = 3600;
+pub(super) const DEFAULT_IDLE_TRIM_AFTER_SECS: u64 = 300;
+pub(super) const MIN_IDLE_TRIM_AFTER_SECS: u64 = 60;
+pub(super) const MAX_IDLE_TRIM_AFTER_SECS: u64 = 24 * 3600;
+pub(super) const DEFAULT_STALE_CACHE_MAX_AGE_MINS: u64 = 10;
+pub(super) const MAX_STALE_CACHE_MAX_AGE_MINS: u64 = 24 * 60;
+pub(super) const DEFAULT_MAX_UNDO_HISTORY: usize = 10_000;
+pub(super) const MAX_UNDO_HISTORY: usize = 1_000_000;
+// Lines in a fold including its first line, which stays visible.
+pub(super) const DEFAULT_FOLD_MIN_BLOCK_LINES: usize = 2;
+pub(super) const MAX_FOLD_MIN_BLOCK_LINES: usize = 100;
+pub(super) const DEFAULT_PREVIEW_MODE_THRESHOLD_MB: u64 = 512;
+pub(super) const DEFAULT_REMOTE_CONTROL_PORT: u16 = 17321;
 pub(super) const MAX_RECENT_PATHS: usize = 12;
 pub(super) const MAX_RECENT_TEXT_HISTORY_ITEMS: usize = 10;
 pub(super) const DEFAULT_FILTER_RULE_TEXT: &str = "#1f2937";
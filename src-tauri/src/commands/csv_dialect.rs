@@ -0,0 +1,678 @@
+// Infers the delimiter, quote character and header row of CSV-like documents
+// from a sample of leading lines, so the table view can configure itself, and
+// sorts or filters rows using that dialect.
+
+use super::*;
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+
+const CSV_SAMPLE_MAX_LINES: usize = 200;
+const CSV_PREVIEW_ROWS: usize = 5;
+const CSV_FILTER_MAX_ROWS: usize = 10_000;
+const CSV_DELIMITER_CANDIDATES: [char; 4] = [',', '\t', ';', '|'];
+const CSV_QUOTE_CANDIDATES: [char; 2] = ['"', '\''];
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvDialectPayload {
+    pub delimiter: String,
+    pub quote_char: String,
+    pub has_header: bool,
+    pub column_count: usize,
+    // 0.0-1.0; the share of sampled rows with the detected column count,
+    // lowered when there are too few rows to be sure.
+    pub confidence: f64,
+    pub sample_rows: usize,
+    pub preview: Vec<Vec<String>>,
+}
+
+struct CsvRecord {
+    fields: Vec<String>,
+    // Byte span of the record in the parsed text, without its line break.
+    start: usize,
+    end: usize,
+    // Zero-based line the record starts on.
+    line: usize,
+}
+
+impl CsvRecord {
+    fn is_blank(&self) -> bool {
+        self.fields.len() == 1 && self.fields[0].trim().is_empty()
+    }
+}
+
+// Quote-aware record splitting. Doubled quotes inside a quoted field are read
+// as one literal quote, and line breaks inside quotes stay in the field.
+fn parse_csv_record_spans(text: &str, delimiter: char, quote: char) -> Vec<CsvRecord> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut record_start = 0usize;
+    let mut record_line = 0usize;
+    let mut line = 0usize;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        if ch == '\n' {
+            line += 1;
+        }
+
+        if in_quotes {
+            if ch == quote {
+                if chars.peek().map(|(_, next)| *next) == Some(quote) {
+                    field.push(quote);
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(ch);
+            }
+            continue;
+        }
+
+        match ch {
+            _ if ch == quote && field.is_empty() => in_quotes = true,
+            _ if ch == delimiter => fields.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                records.push(CsvRecord {
+                    fields: std::mem::take(&mut fields),
+                    start: record_start,
+                    end: index,
+                    line: record_line,
+                });
+                record_start = index + 1;
+                record_line = line;
+            }
+            _ => field.push(ch),
+        }
+    }
+
+    if !field.is_empty() || !fields.is_empty() || in_quotes {
+        fields.push(field);
+        records.push(CsvRecord {
+            fields,
+            start: record_start,
+            end: text.len(),
+            line: record_line,
+        });
+    }
+
+    records
+}
+
+pub(super) fn parse_csv_records(sample: &str, delimiter: char, quote: char) -> Vec<Vec<String>> {
+    parse_csv_record_spans(sample, delimiter, quote)
+        .into_iter()
+        .filter(|record| !record.is_blank())
+        .map(|record| record.fields)
+        .collect()
+}
+
+// Returns (consistency, column count) for the most common field count.
+fn score_csv_records(records: &[Vec<String>]) -> (f64, usize) {
+    if records.is_empty() {
+        return (0.0, 0);
+    }
+
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for record in records {
+        *counts.entry(record.len()).or_default() += 1;
+    }
+    let (column_count, rows) = counts
+        .into_iter()
+        .max_by_key(|(column_count, rows)| (*rows, *column_count))
+        .unwrap_or((0, 0));
+
+    (rows as f64 / records.len() as f64, column_count)
+}
+
+pub(super) fn looks_numeric(value: &str) -> bool {
+    let value = value.trim();
+    !value.is_empty() && value.parse::<f64>().is_ok()
+}
+
+// A header row has no numeric or empty cells, and either sits above numeric
+// data or holds names that never reappear in their column.
+fn detect_csv_header(records: &[Vec<String>], column_count: usize) -> bool {
+    let Some((first, rest)) = records.split_first() else {
+        return false;
+    };
+    if rest.is_empty() || first.len() != column_count {
+        return false;
+    }
+    if first
+        .iter()
+        .any(|cell| cell.trim().is_empty() || looks_numeric(cell))
+    {
+        return false;
+    }
+
+    let data_rows: Vec<&Vec<String>> = rest
+        .iter()
+        .filter(|record| record.len() == column_count)
+        .collect();
+    let has_numeric_column = (0..column_count).any(|column| {
+        let numeric = data_rows
+            .iter()
+            .filter(|record| looks_numeric(&record[column]))
+            .count();
+        numeric * 2 > data_rows.len()
+    });
+    let names_are_unique = (0..column_count).all(|column| {
+        data_rows
+            .iter()
+            .all(|record| record[column].trim() != first[column].trim())
+    });
+
+    has_numeric_column || names_are_unique
+}
+
+pub(super) fn detect_csv_dialect_in_text(sample: &str, truncated: bool) -> CsvDialectPayload {
+    let mut best: Option<(f64, usize, char, char, Vec<Vec<String>>)> = None;
+
+    for delimiter in CSV_DELIMITER_CANDIDATES {
+        for quote in CSV_QUOTE_CANDIDATES {
+            let mut records = parse_csv_records(sample, delimiter, quote);
+            // The last record may have been cut off by the sample limit.
+            if truncated && records.len() > 1 {
+                records.pop();
+            }
+
+            let (consistency, column_count) = score_csv_records(&records);
+            if column_count < 2 {
+                continue;
+            }
+
+            let is_better = match &best {
+                None => true,
+                Some((score, columns, ..)) => {
+                    consistency > *score || (consistency == *score && column_count > *columns)
+                }
+            };
+            if is_better {
+                best = Some((consistency, column_count, delimiter, quote, records));
+            }
+        }
+    }
+
+    let Some((consistency, column_count, delimiter, quote, records)) = best else {
+        return CsvDialectPayload {
+            delimiter: ",".to_string(),
+            quote_char: "\"".to_string(),
+            has_header: false,
+            column_count: usize::from(!sample.trim().is_empty()),
+            confidence: 0.0,
+            sample_rows: sample
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .count(),
+            preview: Vec::new(),
+        };
+    };
+
+    let confidence = if records.len() < 2 {
+        consistency * 0.5
+    } else {
+        consistency
+    };
+
+    CsvDialectPayload {
+        delimiter: delimiter.to_string(),
+        quote_char: quote.to_string(),
+        has_header: detect_csv_header(&records, column_count),
+        column_count,
+        confidence,
+        sample_rows: records.len(),
+        preview: records.into_iter().take(CSV_PREVIEW_ROWS).collect(),
+    }
+}
+
+fn csv_sample(rope: &Rope) -> (String, bool) {
+    let line_count = rope.len_lines();
+    let sample_lines = line_count.min(CSV_SAMPLE_MAX_LINES);
+    let end_char = if sample_lines == line_count {
+        rope.len_chars()
+    } else {
+        rope.line_to_char(sample_lines)
+    };
+
+    (
+        rope.slice(..end_char).to_string(),
+        sample_lines < line_count,
+    )
+}
+
+pub(super) fn detect_csv_dialect_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<CsvDialectPayload, String> {
+    let (sample, truncated) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        csv_sample(&doc.rope)
+    };
+
+    Ok(detect_csv_dialect_in_text(&sample, truncated))
+}
+
+// Parses the whole document with the dialect detected from its leading lines.
+// Returns the header (when present) and the data records separately.
+fn parse_csv_document(
+    text: &str,
+    dialect: &CsvDialectPayload,
+    has_header: Option<bool>,
+) -> Result<(Option<CsvRecord>, Vec<CsvRecord>), String> {
+    if dialect.confidence <= 0.0 {
+        return Err("Document does not look like delimited data".to_string());
+    }
+
+    let delimiter = dialect.delimiter.chars().next().unwrap_or(',');
+    let quote = dialect.quote_char.chars().next().unwrap_or('"');
+    let mut records = parse_csv_record_spans(text, delimiter, quote);
+    let header = if has_header.unwrap_or(dialect.has_header) && !records.is_empty() {
+        Some(records.remove(0))
+    } else {
+        None
+    };
+
+    Ok((header, records))
+}
+
+fn validate_csv_column(column: usize, dialect: &CsvDialectPayload) -> Result<(), String> {
+    if column >= dialect.column_count {
+        return Err(format!(
+            "Column {} is out of range; the document has {} columns",
+            column, dialect.column_count
+        ));
+    }
+
+    Ok(())
+}
+
+fn csv_cell(record: &CsvRecord, column: usize) -> &str {
+    record.fields.get(column).map(String::as_str).unwrap_or("")
+}
+
+fn parse_csv_number(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|number| !number.is_nan())
+}
+
+// Stable sort; with `numeric`, cells that are not numbers keep their order
+// after the numeric ones whichever direction is used. Blank records go last.
+fn sort_csv_records(records: &mut [CsvRecord], column: usize, descending: bool, numeric: bool) {
+    records.sort_by(|left, right| {
+        let blank_order = left.is_blank().cmp(&right.is_blank());
+        if blank_order != std::cmp::Ordering::Equal {
+            return blank_order;
+        }
+
+        let (left_cell, right_cell) = (csv_cell(left, column), csv_cell(right, column));
+        let ordering = if numeric {
+            match (parse_csv_number(left_cell), parse_csv_number(right_cell)) {
+                (Some(left_value), Some(right_value)) => left_value.total_cmp(&right_value),
+                (Some(_), None) => return std::cmp::Ordering::Less,
+                (None, Some(_)) => return std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+        } else {
+            left_cell.cmp(right_cell)
+        };
+
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn rebuild_csv_text(text: &str, header: Option<&CsvRecord>, records: &[CsvRecord]) -> String {
+    let mut rebuilt = String::with_capacity(text.len());
+    for record in header.into_iter().chain(records.iter()) {
+        if !rebuilt.is_empty() {
+            rebuilt.push('\n');
+        }
+        rebuilt.push_str(&text[record.start..record.end]);
+    }
+    if text.ends_with('\n') {
+        rebuilt.push('\n');
+    }
+
+    rebuilt
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvSortResultPayload {
+    pub sorted_rows: usize,
+    pub line_count: usize,
+    pub document_version: u64,
+}
+
+// Sorts a snapshot off the command thread, then applies the result only if
+// the document was not edited meanwhile.
+pub(super) async fn sort_csv_by_column_impl(
+    state: State<'_, AppState>,
+    id: String,
+    column: usize,
+    order: Option<String>,
+    numeric: Option<bool>,
+    has_header: Option<bool>,
+) -> Result<CsvSortResultPayload, String> {
+    let descending = match order.as_deref().unwrap_or("asc") {
+        "asc" => false,
+        "desc" => true,
+        other => return Err(format!("Unsupported sort order: {}", other)),
+    };
+
+    let (rope, document_version) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (doc.rope.clone(), doc.document_version)
+    };
+
+    let (source, sorted, sorted_rows) = tauri::async_runtime::spawn_blocking(move || {
+        let (sample, truncated) = csv_sample(&rope);
+        let dialect = detect_csv_dialect_in_text(&sample, truncated);
+        validate_csv_column(column, &dialect)?;
+
+        let source = rope.to_string();
+        let (header, mut records) = parse_csv_document(&source, &dialect, has_header)?;
+        sort_csv_records(&mut records, column, descending, numeric.unwrap_or(false));
+        let sorted = rebuild_csv_text(&source, header.as_ref(), &records);
+        Ok::<_, String>((source, sorted, records.len()))
+    })
+    .await
+    .map_err(|error| error.to_string())??;
+
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    if doc.document_version != document_version {
+        return Err("Document changed while sorting".to_string());
+    }
+    let mut engine = doc.engine();
+    if sorted != source {
+        // One replacement keeps the sort a single undo step.
+        engine.replace(0, source, sorted)?;
+    }
+    let change = engine.finish();
+    drop(doc);
+    if change.changed {
+        clear_outdated_document_search_caches(&id, change.document_version);
+    }
+
+    Ok(CsvSortResultPayload {
+        sorted_rows,
+        line_count: change.line_count,
+        document_version: change.document_version,
+    })
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvRowPredicate {
+    // equals, notEquals, contains, notContains, greaterThan, lessThan or regex.
+    pub op: String,
+    pub value: String,
+    pub case_sensitive: Option<bool>,
+}
+
+enum CsvCellMatcher {
+    Equals(String, bool),
+    Contains(String, bool),
+    Compare(f64, std::cmp::Ordering),
+    Regex(Regex),
+    Not(Box<CsvCellMatcher>),
+}
+
+impl CsvCellMatcher {
+    fn from_predicate(predicate: &CsvRowPredicate) -> Result<Self, String> {
+        let case_sensitive = predicate.case_sensitive.unwrap_or(false);
+        let fold = |value: &str| {
+            if case_sensitive {
+                value.to_string()
+            } else {
+                value.to_lowercase()
+            }
+        };
+        let number = || {
+            parse_csv_number(&predicate.value)
+                .ok_or_else(|| format!("Not a number: {}", predicate.value))
+        };
+
+        Ok(match predicate.op.as_str() {
+            "equals" => Self::Equals(fold(&predicate.value), case_sensitive),
+            "notEquals" => Self::Not(Box::new(Self::Equals(
+                fold(&predicate.value),
+                case_sensitive,
+            ))),
+            "contains" => Self::Contains(fold(&predicate.value), case_sensitive),
+            "notContains" => Self::Not(Box::new(Self::Contains(
+                fold(&predicate.value),
+                case_sensitive,
+            ))),
+            "greaterThan" => Self::Compare(number()?, std::cmp::Ordering::Greater),
+            "lessThan" => Self::Compare(number()?, std::cmp::Ordering::Less),
+            "regex" => Self::Regex(
+                RegexBuilder::new(&predicate.value)
+                    .case_insensitive(!case_sensitive)
+                    .build()
+                    .map_err(|error| error.to_string())?,
+            ),
+            other => return Err(format!("Unsupported filter operator: {}", other)),
+        })
+    }
+
+    fn matches(&self, cell: &str) -> bool {
+        let fold = |case_sensitive: bool| {
+            if case_sensitive {
+                cell.to_string()
+            } else {
+                cell.to_lowercase()
+            }
+        };
+
+        match self {
+            Self::Equals(value, case_sensitive) => fold(*case_sensitive).trim() == value.trim(),
+            Self::Contains(value, case_sensitive) => fold(*case_sensitive).contains(value.as_str()),
+            Self::Compare(value, ordering) => {
+                parse_csv_number(cell).is_some_and(|number| number.total_cmp(value) == *ordering)
+            }
+            Self::Regex(regex) => regex.is_match(cell),
+            Self::Not(inner) => !inner.matches(cell),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvFilteredRow {
+    // 1-based document line the row starts on.
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvFilterResultPayload {
+    pub header: Option<Vec<String>>,
+    pub rows: Vec<CsvFilteredRow>,
+    pub total_rows: usize,
+    pub matched_rows: usize,
+    pub truncated: bool,
+    pub document_version: u64,
+}
+
+// A read-only view: the document is left untouched and matching rows are
+// returned with their line numbers so the table view can jump to them.
+pub(super) async fn filter_csv_rows_impl(
+    state: State<'_, AppState>,
+    id: String,
+    column: usize,
+    predicate: CsvRowPredicate,
+    has_header: Option<bool>,
+    max_rows: Option<usize>,
+) -> Result<CsvFilterResultPayload, String> {
+    let matcher = CsvCellMatcher::from_predicate(&predicate)?;
+    let max_rows = max_rows.unwrap_or(CSV_FILTER_MAX_ROWS).max(1);
+    let (rope, document_version) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (doc.rope.clone(), doc.document_version)
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let (sample, truncated) = csv_sample(&rope);
+        let dialect = detect_csv_dialect_in_text(&sample, truncated);
+        validate_csv_column(column, &dialect)?;
+
+        let source = rope.to_string();
+        let (header, records) = parse_csv_document(&source, &dialect, has_header)?;
+        let data_records: Vec<CsvRecord> = records
+            .into_iter()
+            .filter(|record| !record.is_blank())
+            .collect();
+        let total_rows = data_records.len();
+        let mut rows = Vec::new();
+        let mut matched_rows = 0usize;
+        for record in data_records {
+            if !matcher.matches(csv_cell(&record, column)) {
+                continue;
+            }
+            matched_rows += 1;
+            if rows.len() < max_rows {
+                rows.push(CsvFilteredRow {
+                    line: record.line + 1,
+                    fields: record.fields,
+                });
+            }
+        }
+
+        Ok(CsvFilterResultPayload {
+            header: header.map(|record| record.fields),
+            truncated: matched_rows > rows.len(),
+            rows,
+            total_rows,
+            matched_rows,
+            document_version,
+        })
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_csv_dialect_should_pick_semicolon_and_header() {
+        let dialect = detect_csv_dialect_in_text(
+            "name;price;note\n\"Widget; large\";12.5;a\nGadget;3;\"multi\nline\"\n",
+            false,
+        );
+
+        assert_eq!(dialect.delimiter, ";");
+        assert_eq!(dialect.quote_char, "\"");
+        assert!(dialect.has_header);
+        assert_eq!(dialect.column_count, 3);
+        assert_eq!(dialect.confidence, 1.0);
+        assert_eq!(dialect.preview[1][0], "Widget; large");
+        assert_eq!(dialect.preview[2][2], "multi\nline");
+    }
+
+    #[test]
+    fn detect_csv_dialect_should_handle_tabs_without_header() {
+        let dialect = detect_csv_dialect_in_text("1\t2\t3\n4\t5\t6\n7\t8\t9\n", false);
+
+        assert_eq!(dialect.delimiter, "\t");
+        assert!(!dialect.has_header);
+        assert_eq!(dialect.sample_rows, 3);
+    }
+
+    #[test]
+    fn detect_csv_dialect_should_report_zero_confidence_for_plain_text() {
+        let dialect = detect_csv_dialect_in_text("just a sentence\nand another\n", false);
+
+        assert_eq!(dialect.confidence, 0.0);
+        assert_eq!(dialect.column_count, 1);
+        assert!(dialect.preview.is_empty());
+    }
+
+    #[test]
+    fn parse_csv_records_should_unescape_doubled_quotes_and_drop_truncated_tail() {
+        assert_eq!(
+            parse_csv_records("a,\"say \"\"hi\"\"\"\r\n", ',', '"'),
+            vec![vec!["a".to_string(), "say \"hi\"".to_string()]]
+        );
+
+        let dialect = detect_csv_dialect_in_text("a,b\n1,2\n3,", true);
+        assert_eq!(dialect.sample_rows, 2);
+    }
+
+    fn sample_dialect(text: &str) -> CsvDialectPayload {
+        detect_csv_dialect_in_text(text, false)
+    }
+
+    #[test]
+    fn sort_csv_records_should_keep_header_and_original_quoting() {
+        let text = "name,qty\n\"b, x\",10\na,9\nc,n/a\n";
+        let dialect = sample_dialect(text);
+        let (header, mut records) = parse_csv_document(text, &dialect, None).expect("parse");
+
+        sort_csv_records(&mut records, 1, false, true);
+        assert_eq!(
+            rebuild_csv_text(text, header.as_ref(), &records),
+            "name,qty\na,9\n\"b, x\",10\nc,n/a\n"
+        );
+
+        sort_csv_records(&mut records, 0, true, false);
+        assert_eq!(
+            rebuild_csv_text(text, header.as_ref(), &records),
+            "name,qty\nc,n/a\n\"b, x\",10\na,9\n"
+        );
+    }
+
+    #[test]
+    fn csv_cell_matcher_should_support_text_and_numeric_predicates() {
+        let predicate = |op: &str, value: &str| CsvRowPredicate {
+            op: op.to_string(),
+            value: value.to_string(),
+            case_sensitive: None,
+        };
+
+        let contains = CsvCellMatcher::from_predicate(&predicate("contains", "WID")).expect("ok");
+        assert!(contains.matches("Widget"));
+        let not_equals = CsvCellMatcher::from_predicate(&predicate("notEquals", "a")).expect("ok");
+        assert!(!not_equals.matches(" A "));
+        let greater = CsvCellMatcher::from_predicate(&predicate("greaterThan", "5")).expect("ok");
+        assert!(greater.matches("5.5") && !greater.matches("5") && !greater.matches("x"));
+        assert!(CsvCellMatcher::from_predicate(&predicate("lessThan", "x")).is_err());
+        assert!(CsvCellMatcher::from_predicate(&predicate("between", "1")).is_err());
+    }
+
+    #[test]
+    fn parse_csv_record_spans_should_track_multiline_record_lines() {
+        let records = parse_csv_record_spans("a,\"x\ny\"\nb,z", ',', '"');
+
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].line, records[1].line), (0, 2));
+        assert_eq!(records[1].fields, vec!["b".to_string(), "z".to_string()]);
+    }
+}
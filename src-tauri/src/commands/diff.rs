@@ -60,6 +60,27 @@ impl DiffEditSide {
     }
 }
 
+// One side of a fragment comparison: either literal text or a 1-based,
+// inclusive line range of an open document (the whole document when unset).
+#[derive(serde::Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TextFragment {
+    pub text: Option<String>,
+    pub document_id: Option<String>,
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextFragmentDiffResult {
+    pub line_diff: LineDiffResult,
+    // Document line of the fragment's first line, so relative diff line
+    // numbers can be mapped back; 1 for literal text.
+    pub source_start_line: usize,
+    pub target_start_line: usize,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 struct TextPatch {
     start_char: usize,
@@ -78,7 +99,7 @@ fn normalize_rope_line_text(mut value: String) -> String {
     value
 }
 
-fn collect_rope_lines(rope: &Rope) -> Vec<String> {
+pub(super) fn collect_rope_lines(rope: &Rope) -> Vec<String> {
     let line_count = rope.len_lines();
     let mut lines = Vec::with_capacity(line_count);
 
@@ -93,7 +114,7 @@ fn collect_rope_lines(rope: &Rope) -> Vec<String> {
     lines
 }
 
-fn clone_rope(state: &State<'_, AppState>, id: &str) -> Result<Rope, String> {
+pub(super) fn clone_rope(state: &State<'_, AppState>, id: &str) -> Result<Rope, String> {
     let doc = state
         .documents
         .get(id)
@@ -481,7 +502,7 @@ fn compute_text_patch(previous_text: &str, next_text: &str) -> TextPatch {
     }
 }
 
-fn apply_serialized_text_to_document(
+pub(super) fn apply_serialized_text_to_document(
     doc: &mut Document,
     next_text: String,
 ) -> Result<bool, String> {
@@ -497,12 +518,10 @@ fn apply_serialized_text_to_document(
         return Ok(false);
     }
 
-    let operation = editing::create_edit_operation(doc, patch.start_char, old_text, patch.new_text);
-    editing::apply_operation(doc, &operation)?;
-    doc.undo_stack.push(operation);
-    doc.redo_stack.clear();
+    let mut engine = doc.engine();
+    engine.replace(patch.start_char, old_text, patch.new_text)?;
 
-    Ok(true)
+    Ok(engine.finish().changed)
 }
 
 fn push_aligned_line(
@@ -695,6 +714,94 @@ pub(super) async fn compare_documents_by_line_impl(
     .map_err(|error| error.to_string())
 }
 
+fn compare_rope_with_text(source_rope: &Rope, text: &str) -> LineDiffResult {
+    let source_lines = collect_rope_lines(source_rope);
+    let target_lines = collect_rope_lines(&Rope::from_str(text));
+    build_line_diff_result(source_lines, target_lines)
+}
+
+pub(super) async fn compare_document_with_text_impl(
+    state: State<'_, AppState>,
+    id: String,
+    text: String,
+) -> Result<LineDiffResult, String> {
+    let source_rope = clone_rope(&state, &id)?;
+
+    tauri::async_runtime::spawn_blocking(move || compare_rope_with_text(&source_rope, &text))
+        .await
+        .map_err(|error| error.to_string())
+}
+
+// The snapshot is the source side, so insertions are what changed since then.
+pub(super) async fn compare_with_snapshot_impl(
+    state: State<'_, AppState>,
+    id: String,
+    snapshot_id: String,
+) -> Result<LineDiffResult, String> {
+    let source_rope = snapshots::snapshot_rope(&id, &snapshot_id)?;
+    let target_rope = clone_rope(&state, &id)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let source_lines = collect_rope_lines(&source_rope);
+        let target_lines = collect_rope_lines(&target_rope);
+        build_line_diff_result(source_lines, target_lines)
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+fn slice_fragment_lines(
+    rope: &Rope,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+) -> Result<(Vec<String>, usize), String> {
+    let line_count = rope.len_lines().max(1);
+    let start_line = start_line.unwrap_or(1).max(1);
+    let end_line = end_line.unwrap_or(line_count).min(line_count);
+    if start_line > end_line {
+        return Err(format!(
+            "Invalid fragment line range: {}-{}",
+            start_line, end_line
+        ));
+    }
+
+    let lines = (start_line - 1..end_line)
+        .map(|index| normalize_rope_line_text(rope.line(index).to_string()))
+        .collect();
+    Ok((lines, start_line))
+}
+
+fn resolve_text_fragment(
+    state: &State<'_, AppState>,
+    fragment: TextFragment,
+) -> Result<(Vec<String>, usize), String> {
+    match (fragment.text, fragment.document_id) {
+        (Some(text), None) => Ok((collect_rope_lines(&Rope::from_str(&text)), 1)),
+        (None, Some(id)) => {
+            let rope = clone_rope(state, &id)?;
+            slice_fragment_lines(&rope, fragment.start_line, fragment.end_line)
+        }
+        _ => Err("A text fragment needs either text or a document id".to_string()),
+    }
+}
+
+pub(super) async fn compare_text_fragments_impl(
+    state: State<'_, AppState>,
+    source: TextFragment,
+    target: TextFragment,
+) -> Result<TextFragmentDiffResult, String> {
+    let (source_lines, source_start_line) = resolve_text_fragment(&state, source)?;
+    let (target_lines, target_start_line) = resolve_text_fragment(&state, target)?;
+
+    tauri::async_runtime::spawn_blocking(move || TextFragmentDiffResult {
+        line_diff: build_line_diff_result(source_lines, target_lines),
+        source_start_line,
+        target_start_line,
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
 pub(super) async fn get_unsaved_change_line_numbers_impl(
     state: State<'_, AppState>,
     id: String,
@@ -750,6 +857,139 @@ pub(super) async fn search_diff_panel_aligned_row_matches_impl(
     .map_err(|error| error.to_string())
 }
 
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSearchMatch {
+    pub aligned_row: usize,
+    // "source" or "target".
+    pub side: &'static str,
+    pub line_number: usize,
+    // "added", "removed" or "unchanged", from the point of view of `side`.
+    pub membership: &'static str,
+    // Index of the contiguous run of changed rows the match falls in.
+    pub hunk_index: Option<usize>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSearchResult {
+    pub matches: Vec<DiffSearchMatch>,
+    pub hunk_count: usize,
+}
+
+fn build_hunk_indexes(
+    aligned_diff_kinds: &[Option<AlignedDiffKind>],
+) -> (Vec<Option<usize>>, usize) {
+    let mut hunk_count = 0usize;
+    let mut in_hunk = false;
+    let indexes = aligned_diff_kinds
+        .iter()
+        .map(|kind| {
+            if kind.is_none() {
+                in_hunk = false;
+                return None;
+            }
+            if !in_hunk {
+                in_hunk = true;
+                hunk_count += 1;
+            }
+            Some(hunk_count - 1)
+        })
+        .collect();
+
+    (indexes, hunk_count)
+}
+
+fn search_line_diff_result(
+    diff: &LineDiffResult,
+    normalized_keyword: &str,
+    search_source: bool,
+    search_target: bool,
+) -> DiffSearchResult {
+    let (hunk_indexes, hunk_count) = build_hunk_indexes(diff.aligned_diff_kinds.as_slice());
+    let sides = [
+        (
+            "source",
+            "removed",
+            search_source,
+            &diff.aligned_source_lines,
+            &diff.aligned_source_present,
+            &diff.source_line_numbers_by_aligned_row,
+        ),
+        (
+            "target",
+            "added",
+            search_target,
+            &diff.aligned_target_lines,
+            &diff.aligned_target_present,
+            &diff.target_line_numbers_by_aligned_row,
+        ),
+    ];
+
+    let mut matches = Vec::new();
+    for row in 0..diff.aligned_line_count {
+        for (side, changed_membership, enabled, lines, present, line_numbers) in sides {
+            if !enabled || !present[row] || !lines[row].to_lowercase().contains(normalized_keyword)
+            {
+                continue;
+            }
+
+            let hunk_index = hunk_indexes[row];
+            matches.push(DiffSearchMatch {
+                aligned_row: row,
+                side,
+                line_number: line_numbers[row],
+                membership: if hunk_index.is_some() {
+                    changed_membership
+                } else {
+                    "unchanged"
+                },
+                hunk_index,
+            });
+        }
+    }
+
+    DiffSearchResult {
+        matches,
+        hunk_count,
+    }
+}
+
+pub(super) async fn search_in_diff_impl(
+    state: State<'_, AppState>,
+    source_id: String,
+    target_id: String,
+    keyword: String,
+    side: Option<String>,
+) -> Result<DiffSearchResult, String> {
+    let (search_source, search_target) = match side.as_deref().unwrap_or("both") {
+        "source" => (true, false),
+        "target" => (false, true),
+        "both" => (true, true),
+        other => return Err(format!("Unsupported diff search side: {}", other)),
+    };
+    let normalized_keyword = keyword.trim().to_lowercase();
+    let source_rope = clone_rope(&state, &source_id)?;
+    let target_rope = clone_rope(&state, &target_id)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let diff = build_line_diff_result(
+            collect_rope_lines(&source_rope),
+            collect_rope_lines(&target_rope),
+        );
+        if normalized_keyword.is_empty() {
+            return DiffSearchResult {
+                matches: Vec::new(),
+                hunk_count: build_hunk_indexes(diff.aligned_diff_kinds.as_slice()).1,
+            };
+        }
+
+        search_line_diff_result(&diff, &normalized_keyword, search_source, search_target)
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
 pub(super) async fn preview_aligned_diff_state_impl(
     aligned_source_lines: Vec<String>,
     aligned_target_lines: Vec<String>,
@@ -827,18 +1067,28 @@ pub(super) async fn apply_aligned_diff_edit_impl(
     let actual_lines = extract_actual_lines_from_aligned(edited_lines, edited_present);
     let next_text = serialize_actual_lines(&actual_lines, edited_trailing_newline);
 
-    {
+    let edited_version = {
         let mut doc = state
             .documents
             .get_mut(edited_id)
             .ok_or_else(|| "Document not found".to_string())?;
         let _ = apply_serialized_text_to_document(&mut doc, next_text)?;
-    }
+        doc.document_version
+    };
+    clear_outdated_document_search_caches(edited_id, edited_version);
 
-    let source_rope = clone_rope(&state, &source_id)?;
-    let target_rope = clone_rope(&state, &target_id)?;
-    let source_is_dirty = load_document_is_dirty(&state, &source_id)?;
-    let target_is_dirty = load_document_is_dirty(&state, &target_id)?;
+    build_diff_edit_result(&state, &source_id, &target_id).await
+}
+
+async fn build_diff_edit_result(
+    state: &State<'_, AppState>,
+    source_id: &str,
+    target_id: &str,
+) -> Result<ApplyAlignedDiffEditResult, String> {
+    let source_rope = clone_rope(state, source_id)?;
+    let target_rope = clone_rope(state, target_id)?;
+    let source_is_dirty = load_document_is_dirty(state, source_id)?;
+    let target_is_dirty = load_document_is_dirty(state, target_id)?;
 
     let line_diff = tauri::async_runtime::spawn_blocking(move || {
         let source_lines = collect_rope_lines(&source_rope);
@@ -855,39 +1105,169 @@ pub(super) async fn apply_aligned_diff_edit_impl(
     })
 }
 
+// Target text after copying aligned rows `start_row_index..=end_row_index`
+// of the diff from source to target, or None when those rows already match.
+fn build_hunk_applied_target_text(
+    source_lines: Vec<String>,
+    target_lines: Vec<String>,
+    start_row_index: usize,
+    end_row_index: usize,
+) -> Option<String> {
+    let line_diff = build_line_diff_result(source_lines, target_lines);
+    let copied = apply_aligned_diff_panel_copy(
+        DiffEditSide::Source,
+        DiffEditSide::Target,
+        start_row_index,
+        end_row_index,
+        line_diff.aligned_source_lines,
+        line_diff.aligned_target_lines,
+        line_diff.aligned_source_present,
+        line_diff.aligned_target_present,
+    );
+    if !copied.changed {
+        return None;
+    }
+
+    // Rope lines already end with an empty line when the text ends with a
+    // break, so joining them restores the trailing newline.
+    let actual_lines = extract_actual_lines_from_aligned(
+        &copied.line_diff.aligned_target_lines,
+        &copied.line_diff.aligned_target_present,
+    );
+    Some(serialize_actual_lines(&actual_lines, false))
+}
+
+// Applies one hunk of the current source/target diff to the target document
+// as a single undoable edit. Rows index the aligned diff of the documents as
+// they are now; the call fails if the target changes while it runs.
+pub(super) async fn apply_diff_hunk_impl(
+    state: State<'_, AppState>,
+    source_id: String,
+    target_id: String,
+    start_row_index: usize,
+    end_row_index: usize,
+) -> Result<ApplyAlignedDiffEditResult, String> {
+    let source_rope = clone_rope(&state, &source_id)?;
+    let (target_rope, target_version) = {
+        let doc = state
+            .documents
+            .get(&target_id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (doc.rope.clone(), doc.document_version)
+    };
+
+    let next_text = tauri::async_runtime::spawn_blocking(move || {
+        build_hunk_applied_target_text(
+            collect_rope_lines(&source_rope),
+            collect_rope_lines(&target_rope),
+            start_row_index,
+            end_row_index,
+        )
+    })
+    .await
+    .map_err(|error| error.to_string())?;
+
+    if let Some(next_text) = next_text {
+        let target_version = {
+            let mut doc = state
+                .documents
+                .get_mut(&target_id)
+                .ok_or_else(|| "Document not found".to_string())?;
+            if doc.document_version != target_version {
+                return Err("Target document changed while applying the diff hunk".to_string());
+            }
+            let _ = apply_serialized_text_to_document(&mut doc, next_text)?;
+            doc.document_version
+        };
+        clear_outdated_document_search_caches(&target_id, target_version);
+    }
+
+    build_diff_edit_result(&state, &source_id, &target_id).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_aligned_diff_panel_copy, apply_serialized_text_to_document, build_line_diff_result,
-        build_line_diff_result_from_aligned, build_target_changed_line_numbers, compute_text_patch,
-        extract_actual_lines_from_aligned, find_line_numbers_by_keyword,
-        map_matched_line_numbers_to_aligned_rows, normalize_rope_line_text, serialize_actual_lines,
-        AlignedDiffKind, DiffEditSide,
+        apply_aligned_diff_panel_copy, apply_serialized_text_to_document,
+        build_hunk_applied_target_text, build_line_diff_result,
+        build_line_diff_result_from_aligned, build_target_changed_line_numbers,
+        compare_rope_with_text, compute_text_patch, extract_actual_lines_from_aligned,
+        find_line_numbers_by_keyword, map_matched_line_numbers_to_aligned_rows,
+        normalize_rope_line_text, search_line_diff_result, serialize_actual_lines,
+        slice_fragment_lines, AlignedDiffKind, DiffEditSide,
     };
-    use crate::state::{default_line_ending, Document};
-    use encoding_rs::UTF_8;
+    use crate::state::Document;
     use ropey::Rope;
 
-    fn make_document(text: &str) -> Document {
-        let rope = Rope::from_str(text);
-        Document {
-            rope: rope.clone(),
-            saved_rope: rope,
-            encoding: UTF_8,
-            saved_encoding: UTF_8.name().to_string(),
-            line_ending: default_line_ending(),
-            saved_line_ending: default_line_ending(),
-            path: None,
-            syntax_override: None,
-            document_version: 0,
-            saved_document_version: 0,
-            next_edit_operation_id: 1,
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
-            saved_undo_depth: 0,
-            saved_undo_operation_id: None,
-            saved_file_fingerprint: None,
-        }
+    #[test]
+    fn search_line_diff_result_should_tag_matches_with_hunk_membership() {
+        let diff = build_line_diff_result(
+            vec![
+                "keep x".to_string(),
+                "old x".to_string(),
+                "tail".to_string(),
+            ],
+            vec![
+                "keep x".to_string(),
+                "new x".to_string(),
+                "tail".to_string(),
+                "more x".to_string(),
+            ],
+        );
+
+        let result = search_line_diff_result(&diff, "x", true, true);
+        let summary: Vec<_> = result
+            .matches
+            .iter()
+            .map(|item| {
+                (
+                    item.side,
+                    item.line_number,
+                    item.membership,
+                    item.hunk_index,
+                )
+            })
+            .collect();
+
+        assert_eq!(result.hunk_count, 2);
+        assert_eq!(
+            summary,
+            vec![
+                ("source", 1, "unchanged", None),
+                ("target", 1, "unchanged", None),
+                ("source", 2, "removed", Some(0)),
+                ("target", 2, "added", Some(0)),
+                ("target", 4, "added", Some(1)),
+            ]
+        );
+
+        let target_only = search_line_diff_result(&diff, "x", false, true);
+        assert!(target_only.matches.iter().all(|item| item.side == "target"));
+    }
+
+    #[test]
+    fn slice_fragment_lines_should_clamp_range_and_report_start_line() {
+        let rope = Rope::from_str("a\nb\r\nc\nd");
+
+        assert_eq!(
+            slice_fragment_lines(&rope, Some(2), Some(3)),
+            Ok((vec!["b".to_string(), "c".to_string()], 2))
+        );
+        assert_eq!(
+            slice_fragment_lines(&rope, Some(4), Some(99)),
+            Ok((vec!["d".to_string()], 4))
+        );
+        assert_eq!(
+            slice_fragment_lines(&rope, None, None).map(|(lines, _)| lines.len()),
+            Ok(4)
+        );
+        assert!(slice_fragment_lines(&rope, Some(3), Some(2)).is_err());
+
+        let (source, _) = slice_fragment_lines(&rope, Some(1), Some(2)).expect("source");
+        let (target, _) = slice_fragment_lines(&rope, Some(3), Some(4)).expect("target");
+        let result = build_line_diff_result(source, target);
+        assert_eq!(result.source_line_count, 2);
+        assert!(!result.diff_line_numbers.is_empty());
     }
 
     #[test]
@@ -1006,6 +1386,16 @@ mod tests {
         assert!(result.diff_row_indexes.is_empty());
     }
 
+    #[test]
+    fn compare_rope_with_text_should_treat_text_as_target_and_ignore_crlf() {
+        let rope = Rope::from_str("alpha\nbeta\n");
+        let result = compare_rope_with_text(&rope, "alpha\r\ngamma\r\n");
+
+        assert_eq!(result.source_diff_line_numbers, vec![2]);
+        assert_eq!(result.target_diff_line_numbers, vec![2]);
+        assert_eq!(result.target_line_count, 3);
+    }
+
     #[test]
     fn build_target_changed_line_numbers_should_return_empty_when_same_as_saved_snapshot() {
         let changed = build_target_changed_line_numbers(
@@ -1077,7 +1467,7 @@ mod tests {
 
     #[test]
     fn apply_serialized_text_to_document_should_apply_change_and_record_history() {
-        let mut doc = make_document("alpha\nbeta\n");
+        let mut doc = Document::for_test("alpha\nbeta\n", None);
         let changed = apply_serialized_text_to_document(&mut doc, "alpha\nBETA\n".to_string())
             .expect("diff edit should apply");
 
@@ -1147,6 +1537,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn build_hunk_applied_target_text_should_copy_only_the_selected_rows() {
+        let lines = |text: &str| text.split('\n').map(str::to_string).collect::<Vec<_>>();
+        let source = lines("a\nB\nc\nD\n");
+        let target = lines("a\nb\nc\nd\n");
+
+        assert_eq!(
+            build_hunk_applied_target_text(source.clone(), target.clone(), 1, 2).as_deref(),
+            Some("a\nB\nc\nd\n")
+        );
+        assert_eq!(
+            build_hunk_applied_target_text(source.clone(), source, 0, 3),
+            None
+        );
+
+        let inserted = lines("a\nx\nb\n");
+        assert_eq!(
+            build_hunk_applied_target_text(inserted, lines("a\nb\n"), 1, 1).as_deref(),
+            Some("a\nx\nb\n")
+        );
+    }
+
     #[test]
     fn apply_aligned_diff_panel_copy_should_return_unchanged_when_target_already_matches() {
         let result = apply_aligned_diff_panel_copy(
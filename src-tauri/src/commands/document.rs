@@ -1,4 +1,5 @@
 use super::*;
+use ropey::RopeSlice;
 
 pub(super) fn get_document_version_impl(
     state: State<'_, AppState>,
@@ -10,3 +11,226 @@ pub(super) fn get_document_version_impl(
         Err("Document not found".to_string())
     }
 }
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VisualLineCountPayload {
+    pub visual_line_count: usize,
+    pub logical_line_count: usize,
+    pub word_wrap: bool,
+    pub document_version: u64,
+}
+
+pub(super) fn set_document_word_wrap_impl(
+    state: State<'_, AppState>,
+    id: String,
+    word_wrap: Option<bool>,
+) -> Result<(), String> {
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        doc.word_wrap_override = word_wrap;
+        Ok(())
+    } else {
+        Err("Document not found".to_string())
+    }
+}
+
+// Rough East Asian Wide/Fullwidth ranges; the editor renders these two
+// columns wide, so they wrap sooner than their char count suggests.
+pub(super) fn is_wide_char(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD
+    )
+}
+
+// Wraps at character boundaries, so the result is an estimate of what the
+// editor shows with word-boundary wrapping; close enough to size scrollbars.
+fn count_wrapped_rows(line: RopeSlice<'_>, max_columns: usize, tab_width: usize) -> usize {
+    let char_count = line.len_chars();
+    if char_count.saturating_mul(2.max(tab_width)) <= max_columns {
+        return 1;
+    }
+
+    let mut rows = 1;
+    let mut row_columns = 0;
+    let mut logical_columns = 0;
+    for ch in line.chars() {
+        let width = match ch {
+            '\n' | '\r' => continue,
+            '\t' => tab_width - logical_columns % tab_width,
+            ch if is_wide_char(ch) => 2,
+            _ => 1,
+        };
+
+        if row_columns > 0 && row_columns + width > max_columns {
+            rows += 1;
+            row_columns = 0;
+        }
+        row_columns += width;
+        logical_columns += width;
+    }
+
+    rows
+}
+
+pub(super) fn count_visual_lines(rope: &Rope, max_columns: usize, tab_width: usize) -> usize {
+    rope.lines()
+        .map(|line| count_wrapped_rows(line, max_columns, tab_width))
+        .sum()
+}
+
+pub(super) fn get_visual_line_count_impl(
+    state: State<'_, AppState>,
+    id: String,
+    max_columns: usize,
+    tab_width: Option<u8>,
+) -> Result<VisualLineCountPayload, String> {
+    if max_columns == 0 {
+        return Err("max_columns must be greater than zero".to_string());
+    }
+
+    let (rope, word_wrap_override, document_version) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (
+            doc.rope.clone(),
+            doc.word_wrap_override,
+            doc.document_version,
+        )
+    };
+
+    let word_wrap = word_wrap_override.unwrap_or_else(|| {
+        config::load_config_impl()
+            .map(|config| config.word_wrap)
+            .unwrap_or(false)
+    });
+    let logical_line_count = rope.len_lines();
+    let visual_line_count = if word_wrap {
+        let tab_width = settings::normalize_tab_width(tab_width.unwrap_or(DEFAULT_TAB_WIDTH));
+        count_visual_lines(&rope, max_columns, usize::from(tab_width))
+    } else {
+        logical_line_count
+    };
+
+    Ok(VisualLineCountPayload {
+        visual_line_count,
+        logical_line_count,
+        word_wrap,
+        document_version,
+    })
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordAtPayload {
+    pub word: String,
+    pub start_char: usize,
+    pub end_char: usize,
+}
+
+fn is_word_char(ch: char, extra_word_chars: &str) -> bool {
+    ch.is_alphanumeric() || ch == '_' || extra_word_chars.contains(ch)
+}
+
+// Prefers the word under the offset and falls back to the one ending right
+// before it, so a caret at the end of a word still selects it.
+fn find_word_at(rope: &Rope, char_offset: usize, extra_word_chars: &str) -> Option<WordAtPayload> {
+    let len_chars = rope.len_chars();
+    let char_offset = char_offset.min(len_chars);
+    let anchor =
+        if char_offset < len_chars && is_word_char(rope.char(char_offset), extra_word_chars) {
+            char_offset
+        } else if char_offset > 0 && is_word_char(rope.char(char_offset - 1), extra_word_chars) {
+            char_offset - 1
+        } else {
+            return None;
+        };
+
+    let mut start_char = anchor;
+    while start_char > 0 && is_word_char(rope.char(start_char - 1), extra_word_chars) {
+        start_char -= 1;
+    }
+    let mut end_char = anchor + 1;
+    while end_char < len_chars && is_word_char(rope.char(end_char), extra_word_chars) {
+        end_char += 1;
+    }
+
+    Some(WordAtPayload {
+        word: rope.slice(start_char..end_char).to_string(),
+        start_char,
+        end_char,
+    })
+}
+
+pub(super) fn get_word_at_impl(
+    state: State<'_, AppState>,
+    id: String,
+    char_offset: usize,
+) -> Result<Option<WordAtPayload>, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let syntax_key = syntax::resolve_document_syntax_key(&doc);
+
+    let config = config::load_config_impl().unwrap_or_default();
+    let extra_word_chars = config
+        .extra_word_chars_by_syntax
+        .get(&syntax_key)
+        .unwrap_or(&config.extra_word_chars);
+
+    Ok(find_word_at(&doc.rope, char_offset, extra_word_chars))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_visual_lines_should_wrap_long_lines_only() {
+        let rope = Rope::from_str("short\nabcdefghij\n");
+
+        assert_eq!(count_visual_lines(&rope, 4, 4), 2 + 3 + 1);
+        assert_eq!(count_visual_lines(&rope, 10, 4), 3);
+    }
+
+    #[test]
+    fn count_visual_lines_should_account_for_tabs_and_wide_chars() {
+        assert_eq!(count_visual_lines(&Rope::from_str("\tab"), 4, 4), 2);
+        assert_eq!(count_visual_lines(&Rope::from_str("中文字符"), 4, 4), 2);
+        assert_eq!(count_visual_lines(&Rope::from_str("a中文"), 4, 4), 2);
+        assert_eq!(count_visual_lines(&Rope::from_str("abcd"), 4, 4), 1);
+    }
+
+    #[test]
+    fn find_word_at_should_honor_extra_word_chars() {
+        let rope = Rope::from_str("call my-service.name now");
+        let word = |offset: usize, extra: &str| {
+            find_word_at(&rope, offset, extra).map(|payload| payload.word)
+        };
+
+        assert_eq!(word(6, "").as_deref(), Some("my"));
+        assert_eq!(word(8, "-.").as_deref(), Some("my-service.name"));
+        assert_eq!(word(20, "-.").as_deref(), Some("my-service.name"));
+        assert_eq!(word(4, "").as_deref(), Some("call"));
+        assert_eq!(word(24, "").as_deref(), Some("now"));
+
+        let spaced = Rope::from_str("a  b");
+        assert!(find_word_at(&spaced, 2, "").is_none());
+    }
+}
@@ -0,0 +1,675 @@
+// Exports a whole document with syntax colors, as a standalone HTML page or a
+// PDF for printing and archiving.
+//
+// Colors come from the document's tree-sitter parse. Node kinds are bucketed
+// into a few token classes rather than running per-language highlight
+// queries, so the result is close to, not identical with, the editor's
+// highlighting; documents without a grammar export uncolored.
+//
+// The PDF is written directly with the built-in Courier font, which covers
+// Latin-1 only; other characters print as '?'. HTML keeps every character.
+
+use super::filtered_export::escape_html;
+use super::outline::{parse_source_tree, tree_sitter_language_for_syntax};
+use super::syntax::resolve_document_syntax_key;
+use super::*;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use tree_sitter::Language;
+
+// A4 in points.
+const PDF_PAGE_WIDTH: f32 = 595.0;
+const PDF_PAGE_HEIGHT: f32 = 842.0;
+const PDF_MARGIN: f32 = 36.0;
+const PDF_FONT_SIZE: f32 = 9.0;
+const PDF_LINE_HEIGHT: f32 = 11.0;
+// Every Courier glyph is 0.6 em wide.
+const PDF_CHAR_WIDTH: f32 = PDF_FONT_SIZE * 0.6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DocumentExportFormat {
+    Html,
+    Pdf,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TokenClass {
+    Plain,
+    Comment,
+    String,
+    Number,
+    Keyword,
+    Type,
+}
+
+struct ExportTheme {
+    background: &'static str,
+    foreground: &'static str,
+    line_number: &'static str,
+    comment: &'static str,
+    string: &'static str,
+    number: &'static str,
+    keyword: &'static str,
+    type_name: &'static str,
+}
+
+const LIGHT_EXPORT_THEME: ExportTheme = ExportTheme {
+    background: "#ffffff",
+    foreground: "#1f2937",
+    line_number: "#9ca3af",
+    comment: "#6a737d",
+    string: "#032f62",
+    number: "#005cc5",
+    keyword: "#d73a49",
+    type_name: "#6f42c1",
+};
+
+const DARK_EXPORT_THEME: ExportTheme = ExportTheme {
+    background: "#1e1e1e",
+    foreground: "#d4d4d4",
+    line_number: "#858585",
+    comment: "#6a9955",
+    string: "#ce9178",
+    number: "#b5cea8",
+    keyword: "#569cd6",
+    type_name: "#4ec9b0",
+};
+
+impl ExportTheme {
+    fn color(&self, class: TokenClass) -> &'static str {
+        match class {
+            TokenClass::Plain => self.foreground,
+            TokenClass::Comment => self.comment,
+            TokenClass::String => self.string,
+            TokenClass::Number => self.number,
+            TokenClass::Keyword => self.keyword,
+            TokenClass::Type => self.type_name,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentExportOptions {
+    pub path: String,
+    // Defaults to true.
+    pub line_numbers: Option<bool>,
+    // Wraps long lines; off, HTML scrolls and PDF cuts them at the margin.
+    // Defaults to true.
+    pub wrap: Option<bool>,
+    // "light" (default) or "dark".
+    pub theme: Option<String>,
+    pub tab_width: Option<u8>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentExportPayload {
+    pub path: String,
+    pub exported_lines: usize,
+    pub document_version: u64,
+}
+
+struct RenderOptions<'a> {
+    theme: &'a ExportTheme,
+    line_numbers: bool,
+    wrap: bool,
+    tab_width: usize,
+}
+
+fn parse_document_export_format(format: &str) -> Result<DocumentExportFormat, String> {
+    match format.trim().to_ascii_lowercase().as_str() {
+        "html" | "htm" => Ok(DocumentExportFormat::Html),
+        "pdf" => Ok(DocumentExportFormat::Pdf),
+        _ => Err(format!("Unsupported document export format: {}", format)),
+    }
+}
+
+fn classify_node_kind(kind: &str, is_named: bool) -> Option<TokenClass> {
+    if kind.contains("comment") {
+        Some(TokenClass::Comment)
+    } else if kind.contains("string") || kind.contains("char_literal") {
+        Some(TokenClass::String)
+    } else if kind.contains("number")
+        || kind.contains("integer")
+        || kind.contains("float")
+        || kind.contains("boolean")
+        || matches!(kind, "true" | "false" | "null" | "nil" | "none")
+    {
+        Some(TokenClass::Number)
+    } else if matches!(
+        kind,
+        "primitive_type" | "type_identifier" | "predefined_type" | "builtin_type"
+    ) {
+        Some(TokenClass::Type)
+    } else if !is_named
+        && kind.len() > 1
+        && kind
+            .bytes()
+            .all(|byte| byte.is_ascii_alphabetic() || byte == b'_')
+    {
+        Some(TokenClass::Keyword)
+    } else {
+        None
+    }
+}
+
+// Byte ranges in document order. A classified node is taken whole, so string
+// contents and escapes inside it are not split up.
+fn collect_tokens(source: &str, language: Option<&Language>) -> Vec<(usize, usize, TokenClass)> {
+    let Some(language) = language else {
+        return Vec::new();
+    };
+    let Ok(tree) = parse_source_tree(source, language) else {
+        return Vec::new();
+    };
+
+    let mut tokens = Vec::new();
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        let class = classify_node_kind(node.kind(), node.is_named());
+        if let Some(class) = class {
+            if node.start_byte() < node.end_byte() {
+                tokens.push((node.start_byte(), node.end_byte(), class));
+            }
+        }
+
+        if (class.is_none() && cursor.goto_first_child()) || cursor.goto_next_sibling() {
+            continue;
+        }
+        let mut finished = true;
+        while cursor.goto_parent() {
+            if cursor.goto_next_sibling() {
+                finished = false;
+                break;
+            }
+        }
+        if finished {
+            break;
+        }
+    }
+
+    tokens
+}
+
+// Splits every line into runs of one class; line breaks are dropped. Tokens
+// spanning several lines, like block comments, color each line they cover.
+fn styled_lines<'a>(
+    source: &'a str,
+    tokens: &[(usize, usize, TokenClass)],
+) -> Vec<Vec<(TokenClass, &'a str)>> {
+    let mut lines = Vec::new();
+    let mut token_index = 0;
+    let mut line_start = 0;
+
+    for line in source.split('\n') {
+        let line_end = line_start + line.strip_suffix('\r').unwrap_or(line).len();
+        let mut runs = Vec::new();
+        let mut pos = line_start;
+        while pos < line_end {
+            let Some(&(start, end, class)) = tokens
+                .get(token_index)
+                .filter(|(start, _, _)| *start < line_end)
+            else {
+                runs.push((TokenClass::Plain, &source[pos..line_end]));
+                break;
+            };
+            if end <= pos {
+                token_index += 1;
+                continue;
+            }
+            if start > pos {
+                runs.push((TokenClass::Plain, &source[pos..start]));
+            }
+            let run_end = end.min(line_end);
+            runs.push((class, &source[start.max(pos)..run_end]));
+            pos = run_end;
+            if end <= line_end {
+                token_index += 1;
+            }
+        }
+
+        lines.push(runs);
+        line_start += line.len() + 1;
+    }
+
+    lines
+}
+
+fn html_class_name(class: TokenClass) -> Option<&'static str> {
+    match class {
+        TokenClass::Plain => None,
+        TokenClass::Comment => Some("c"),
+        TokenClass::String => Some("s"),
+        TokenClass::Number => Some("m"),
+        TokenClass::Keyword => Some("k"),
+        TokenClass::Type => Some("t"),
+    }
+}
+
+fn render_document_html(
+    title: &str,
+    lines: &[Vec<(TokenClass, &str)>],
+    options: &RenderOptions,
+) -> String {
+    let theme = options.theme;
+    let title = escape_html(title);
+    let number_width = lines.len().to_string().len();
+    let white_space = if options.wrap { "pre-wrap" } else { "pre" };
+    let mut output = String::new();
+    let _ = write!(
+        output,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>\nbody{{margin:0;background:{};color:{};\
+         -webkit-print-color-adjust:exact;print-color-adjust:exact;}}\n\
+         pre{{margin:0;padding:8px;font-family:Consolas,Menlo,monospace;font-size:13px;\
+         tab-size:{};white-space:{white_space};}}\n\
+         .n{{display:inline-block;min-width:{number_width}ch;margin-right:2ch;text-align:right;\
+         user-select:none;color:{};}}\n",
+        theme.background, theme.foreground, options.tab_width, theme.line_number
+    );
+    for class in [
+        TokenClass::Comment,
+        TokenClass::String,
+        TokenClass::Number,
+        TokenClass::Keyword,
+        TokenClass::Type,
+    ] {
+        if let Some(name) = html_class_name(class) {
+            let _ = writeln!(output, ".{name}{{color:{};}}", theme.color(class));
+        }
+    }
+    output.push_str("</style>\n</head>\n<body>\n<pre>");
+
+    for (index, runs) in lines.iter().enumerate() {
+        if options.line_numbers {
+            let _ = write!(output, "<span class=\"n\">{}</span>", index + 1);
+        }
+        for (class, text) in runs {
+            match html_class_name(*class) {
+                Some(name) => {
+                    let _ = write!(
+                        output,
+                        "<span class=\"{name}\">{}</span>",
+                        escape_html(text)
+                    );
+                }
+                None => output.push_str(&escape_html(text)),
+            }
+        }
+        output.push('\n');
+    }
+
+    output.push_str("</pre>\n</body>\n</html>\n");
+    output
+}
+
+#[derive(Debug, PartialEq)]
+struct PdfRow {
+    // Set on the first row of a line; wrapped continuation rows leave it out.
+    line_number: Option<usize>,
+    runs: Vec<(TokenClass, String)>,
+}
+
+// Courier's WinAnsi encoding matches Latin-1 from 0xA0 up.
+fn pdf_char(ch: char) -> char {
+    if (' '..='~').contains(&ch) || ('\u{A0}'..='\u{FF}').contains(&ch) {
+        ch
+    } else {
+        '?'
+    }
+}
+
+fn layout_pdf_rows(
+    lines: &[Vec<(TokenClass, &str)>],
+    columns: usize,
+    wrap: bool,
+    tab_width: usize,
+) -> Vec<PdfRow> {
+    let columns = columns.max(1);
+    let mut rows = Vec::new();
+
+    for (index, runs) in lines.iter().enumerate() {
+        let mut row = PdfRow {
+            line_number: Some(index + 1),
+            runs: Vec::new(),
+        };
+        let mut row_columns = 0;
+        let mut line_columns = 0;
+        'line: for (class, text) in runs {
+            for ch in text.chars() {
+                let (output, width) = match ch {
+                    '\t' => (' ', tab_width - line_columns % tab_width),
+                    ch => (pdf_char(ch), 1),
+                };
+                for _ in 0..width {
+                    if row_columns == columns {
+                        if !wrap {
+                            break 'line;
+                        }
+                        rows.push(std::mem::replace(
+                            &mut row,
+                            PdfRow {
+                                line_number: None,
+                                runs: Vec::new(),
+                            },
+                        ));
+                        row_columns = 0;
+                    }
+                    match row.runs.last_mut() {
+                        Some((last_class, run)) if *last_class == *class => run.push(output),
+                        _ => row.runs.push((*class, output.to_string())),
+                    }
+                    row_columns += 1;
+                    line_columns += 1;
+                }
+            }
+        }
+        rows.push(row);
+    }
+
+    rows
+}
+
+fn pdf_color(hex: &str) -> String {
+    let channel = |index: usize| {
+        hex.get(index..index + 2)
+            .and_then(|value| u8::from_str_radix(value, 16).ok())
+            .map(|value| f32::from(value) / 255.0)
+            .unwrap_or(0.0)
+    };
+    format!("{:.3} {:.3} {:.3}", channel(1), channel(3), channel(5))
+}
+
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut output = vec![b'('];
+    for ch in text.chars() {
+        match ch {
+            '(' | ')' | '\\' => output.extend([b'\\', ch as u8]),
+            // `pdf_char` already narrowed every char to one byte.
+            ch => output.push(ch as u32 as u8),
+        }
+    }
+    output.push(b')');
+    output
+}
+
+fn render_pdf_page(rows: &[PdfRow], number_width: usize, options: &RenderOptions) -> Vec<u8> {
+    let theme = options.theme;
+    let mut content = Vec::new();
+    if theme.background != LIGHT_EXPORT_THEME.background {
+        content.extend(
+            format!(
+                "{} rg 0 0 {PDF_PAGE_WIDTH} {PDF_PAGE_HEIGHT} re f\n",
+                pdf_color(theme.background)
+            )
+            .as_bytes(),
+        );
+    }
+    content.extend(
+        format!(
+            "BT\n/F1 {PDF_FONT_SIZE} Tf\n{PDF_LINE_HEIGHT} TL\n{PDF_MARGIN} {} Td\n",
+            PDF_PAGE_HEIGHT - PDF_MARGIN - PDF_FONT_SIZE
+        )
+        .as_bytes(),
+    );
+
+    for row in rows {
+        if options.line_numbers {
+            let number = row
+                .line_number
+                .map(|number| number.to_string())
+                .unwrap_or_default();
+            content.extend(format!("{} rg ", pdf_color(theme.line_number)).as_bytes());
+            content.extend(pdf_string(&format!("{number:>number_width$} ")));
+            content.extend(b" Tj\n");
+        }
+        for (class, text) in &row.runs {
+            content.extend(format!("{} rg ", pdf_color(theme.color(*class))).as_bytes());
+            content.extend(pdf_string(text));
+            content.extend(b" Tj\n");
+        }
+        content.extend(b"T*\n");
+    }
+
+    content.extend(b"ET");
+    content
+}
+
+// Objects: catalog, page tree, font, then a page and its content stream for
+// every page.
+fn build_pdf(page_contents: Vec<Vec<u8>>) -> Vec<u8> {
+    let page_count = page_contents.len();
+    let kids = (0..page_count)
+        .map(|index| format!("{} 0 R", 4 + index * 2))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!("<< /Type /Pages /Kids [{kids}] /Count {page_count} >>").into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    for (index, content) in page_contents.into_iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PDF_PAGE_WIDTH} {PDF_PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                5 + index * 2
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend(content);
+        stream.extend(b"\nendstream");
+        objects.push(stream);
+    }
+
+    let mut output = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(output.len());
+        output.extend(format!("{} 0 obj\n", index + 1).as_bytes());
+        output.extend(object);
+        output.extend(b"\nendobj\n");
+    }
+
+    let xref_offset = output.len();
+    output.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        output.extend(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+    output.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .as_bytes(),
+    );
+    output
+}
+
+fn render_document_pdf(lines: &[Vec<(TokenClass, &str)>], options: &RenderOptions) -> Vec<u8> {
+    let number_width = lines.len().to_string().len();
+    let gutter = if options.line_numbers {
+        number_width + 1
+    } else {
+        0
+    };
+    let columns =
+        (((PDF_PAGE_WIDTH - 2.0 * PDF_MARGIN) / PDF_CHAR_WIDTH) as usize).saturating_sub(gutter);
+    let rows_per_page = ((PDF_PAGE_HEIGHT - 2.0 * PDF_MARGIN) / PDF_LINE_HEIGHT) as usize;
+
+    let rows = layout_pdf_rows(lines, columns, options.wrap, options.tab_width);
+    let pages = rows
+        .chunks(rows_per_page)
+        .map(|page_rows| render_pdf_page(page_rows, number_width, options))
+        .collect();
+    build_pdf(pages)
+}
+
+pub(super) async fn export_document_impl(
+    state: State<'_, AppState>,
+    id: String,
+    format: String,
+    options: DocumentExportOptions,
+) -> Result<DocumentExportPayload, String> {
+    let format = parse_document_export_format(&format)?;
+    if options.path.trim().is_empty() {
+        return Err("Export path is empty".to_string());
+    }
+    let theme = match options.theme.as_deref().unwrap_or("light") {
+        "light" => &LIGHT_EXPORT_THEME,
+        "dark" => &DARK_EXPORT_THEME,
+        other => return Err(format!("Unsupported export theme: {}", other)),
+    };
+
+    let (rope, title, syntax_key, document_version) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        let title = doc
+            .path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Untitled".to_string());
+        (
+            doc.rope.clone(),
+            title,
+            resolve_document_syntax_key(&doc),
+            doc.document_version,
+        )
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let source: String = rope.chunks().collect();
+        let language = tree_sitter_language_for_syntax(&syntax_key);
+        let tokens = collect_tokens(&source, language.as_ref());
+        let lines = styled_lines(&source, &tokens);
+        let render_options = RenderOptions {
+            theme,
+            line_numbers: options.line_numbers.unwrap_or(true),
+            wrap: options.wrap.unwrap_or(true),
+            tab_width: usize::from(options.tab_width.unwrap_or(DEFAULT_TAB_WIDTH).max(1)),
+        };
+        let content = match format {
+            DocumentExportFormat::Html => {
+                render_document_html(&title, &lines, &render_options).into_bytes()
+            }
+            DocumentExportFormat::Pdf => render_document_pdf(&lines, &render_options),
+        };
+
+        let output_path = PathBuf::from(&options.path);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&output_path, content).map_err(|e| e.to_string())?;
+
+        Ok(DocumentExportPayload {
+            path: options.path,
+            exported_lines: lines.len(),
+            document_version,
+        })
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_OPTIONS: RenderOptions<'static> = RenderOptions {
+        theme: &LIGHT_EXPORT_THEME,
+        line_numbers: true,
+        wrap: true,
+        tab_width: 4,
+    };
+
+    #[test]
+    fn render_document_html_should_color_syntax_tokens() {
+        let source = "fn main() {\n    // hi <b>\n    let s = \"x\"; 1\n}\n";
+        let language = tree_sitter_language_for_syntax("rust").expect("rust language");
+        let tokens = collect_tokens(source, Some(&language));
+        let lines = styled_lines(source, &tokens);
+
+        let html = render_document_html("main.rs", &lines, &TEST_OPTIONS);
+
+        assert_eq!(lines.len(), 5);
+        assert!(html.contains("<span class=\"n\">1</span><span class=\"k\">fn</span> main() {"));
+        assert!(html.contains("<span class=\"c\">// hi &lt;b&gt;</span>"));
+        assert!(html.contains(
+            "<span class=\"k\">let</span> s = <span class=\"s\">&quot;x&quot;</span>; <span class=\"m\">1</span>"
+        ));
+        assert!(html.contains("white-space:pre-wrap"));
+        assert!(parse_document_export_format("docx").is_err());
+    }
+
+    #[test]
+    fn styled_lines_should_split_multi_line_tokens_and_drop_line_breaks() {
+        let source = "a /* x\r\ny */ b";
+        let lines = styled_lines(source, &[(2, 12, TokenClass::Comment)]);
+
+        assert_eq!(
+            lines,
+            vec![
+                vec![(TokenClass::Plain, "a "), (TokenClass::Comment, "/* x")],
+                vec![(TokenClass::Comment, "y */"), (TokenClass::Plain, " b")],
+            ]
+        );
+    }
+
+    #[test]
+    fn layout_pdf_rows_should_wrap_or_cut_and_expand_tabs() {
+        let lines = vec![
+            vec![(TokenClass::Keyword, "abcdef"), (TokenClass::Plain, "gh")],
+            vec![(TokenClass::Plain, "\tx中")],
+        ];
+
+        let wrapped = layout_pdf_rows(&lines, 4, true, 4);
+        assert_eq!(
+            wrapped
+                .iter()
+                .map(|row| row.line_number)
+                .collect::<Vec<_>>(),
+            vec![Some(1), None, Some(2), None]
+        );
+        assert_eq!(
+            wrapped[1].runs,
+            vec![
+                (TokenClass::Keyword, "ef".to_string()),
+                (TokenClass::Plain, "gh".to_string()),
+            ]
+        );
+        assert_eq!(wrapped[3].runs, vec![(TokenClass::Plain, "x?".to_string())]);
+
+        let cut = layout_pdf_rows(&lines, 4, false, 4);
+        assert_eq!(cut.len(), 2);
+        assert_eq!(cut[0].runs, vec![(TokenClass::Keyword, "abcd".to_string())]);
+    }
+
+    #[test]
+    fn render_document_pdf_should_write_a_valid_skeleton() {
+        let lines = vec![vec![
+            (TokenClass::Keyword, "fn"),
+            (TokenClass::Plain, " f(a)"),
+        ]];
+
+        let pdf = render_document_pdf(&lines, &TEST_OPTIONS);
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.contains("/Count 1"));
+        assert!(text.contains("0.843 0.227 0.286 rg (fn) Tj"));
+        assert!(text.contains("( f\\(a\\)) Tj"));
+        let xref_offset: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|value| value.parse().ok())
+            .expect("xref offset");
+        assert!(text[xref_offset..].starts_with("xref\n0 6\n"));
+    }
+}
@@ -0,0 +1,159 @@
+// Extra views onto an open document, for cloned tabs and side-by-side
+// navigation. Each view keeps its own cursor, scroll position and folds on the
+// document, where edits shift them; edits still go through the document id,
+// so every view shares the text, the undo history and the document version.
+// `AppState` maps view ids to their document. Closing the document closes its
+// views.
+
+use super::*;
+use crate::state::{DocumentView, DocumentViewState};
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentViewPayload {
+    pub view_id: String,
+    pub document_id: String,
+    pub document_version: u64,
+    pub line_count: usize,
+    pub state: DocumentViewState,
+}
+
+// Edits follow stored positions, but a reload can still replace the whole
+// text, so positions are clamped whenever a view is stored or read.
+fn clamp_view_state(state: &DocumentViewState, line_count: usize) -> DocumentViewState {
+    let line_count = line_count.max(1);
+    let mut folded_lines: Vec<usize> = state
+        .folded_lines
+        .iter()
+        .copied()
+        .filter(|line| (1..=line_count).contains(line))
+        .collect();
+    folded_lines.sort_unstable();
+    folded_lines.dedup();
+
+    DocumentViewState {
+        cursor_line: state.cursor_line.clamp(1, line_count),
+        cursor_column: state.cursor_column.max(1),
+        scroll_top_line: state.scroll_top_line.clamp(1, line_count),
+        folded_lines,
+    }
+}
+
+pub(super) fn create_document_view_impl(
+    state: State<'_, AppState>,
+    id: String,
+    initial_state: Option<DocumentViewState>,
+) -> Result<String, String> {
+    let view_id = Uuid::new_v4().to_string();
+    {
+        let mut doc = state
+            .documents
+            .get_mut(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        let view_state = clamp_view_state(&initial_state.unwrap_or_default(), doc.rope.len_lines());
+        doc.views.set(view_id.clone(), view_state);
+    }
+
+    state
+        .document_views
+        .insert(view_id.clone(), DocumentView { document_id: id });
+    Ok(view_id)
+}
+
+fn document_id_for_view(state: &AppState, view_id: &str) -> Result<String, String> {
+    state
+        .document_views
+        .get(view_id)
+        .map(|view| view.document_id.clone())
+        .ok_or_else(|| "Document view not found".to_string())
+}
+
+pub(super) fn get_document_view_impl(
+    state: State<'_, AppState>,
+    view_id: String,
+) -> Result<DocumentViewPayload, String> {
+    let document_id = document_id_for_view(&state, &view_id)?;
+    let doc = state
+        .documents
+        .get(&document_id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let line_count = doc.rope.len_lines();
+    let view_state = doc.views.get(&view_id).cloned().unwrap_or_default();
+
+    Ok(DocumentViewPayload {
+        view_id,
+        document_id,
+        document_version: doc.document_version,
+        line_count,
+        state: clamp_view_state(&view_state, line_count),
+    })
+}
+
+pub(super) fn update_document_view_state_impl(
+    state: State<'_, AppState>,
+    view_id: String,
+    view_state: DocumentViewState,
+) -> Result<(), String> {
+    let document_id = document_id_for_view(&state, &view_id)?;
+    let mut doc = state
+        .documents
+        .get_mut(&document_id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let view_state = clamp_view_state(&view_state, doc.rope.len_lines());
+    doc.views.set(view_id, view_state);
+    Ok(())
+}
+
+pub(super) fn list_document_views_impl(state: State<'_, AppState>, id: String) -> Vec<String> {
+    state
+        .document_views
+        .iter()
+        .filter(|view| view.document_id == id)
+        .map(|view| view.key().clone())
+        .collect()
+}
+
+pub(super) fn close_document_view_impl(state: State<'_, AppState>, view_id: String) -> bool {
+    let Some((_, view)) = state.document_views.remove(&view_id) else {
+        return false;
+    };
+    if let Some(mut doc) = state.documents.get_mut(&view.document_id) {
+        doc.views.remove(&view_id);
+    }
+    true
+}
+
+pub(super) fn close_document_views_for(state: &AppState, id: &str) {
+    state
+        .document_views
+        .retain(|_, view| view.document_id != id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_view_state_should_keep_positions_inside_the_document() {
+        let view_state = DocumentViewState {
+            cursor_line: 40,
+            cursor_column: 0,
+            scroll_top_line: 0,
+            folded_lines: vec![12, 3, 3, 0, 7],
+        };
+
+        assert_eq!(
+            clamp_view_state(&view_state, 10),
+            DocumentViewState {
+                cursor_line: 10,
+                cursor_column: 1,
+                scroll_top_line: 1,
+                folded_lines: vec![3, 7],
+            }
+        );
+        assert_eq!(
+            clamp_view_state(&DocumentViewState::default(), 0),
+            DocumentViewState::default()
+        );
+    }
+}
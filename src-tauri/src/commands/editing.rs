@@ -1,5 +1,5 @@
 use super::*;
-use crate::state::CursorSnapshot;
+use crate::state::{CursorSnapshot, DocumentEngine};
 use base64::Engine as _;
 use std::path::Path;
 use tauri::image::Image;
@@ -12,6 +12,18 @@ pub struct HistoryActionResultPayload {
     pub cursor_column: Option<usize>,
 }
 
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirtyStatePayload {
+    pub is_dirty: bool,
+    pub text_changed: bool,
+    pub encoding_changed: bool,
+    pub line_ending_changed: bool,
+    // Undo steps back to the saved text; negative means redo. None when the
+    // save point has left the history.
+    pub save_point_distance: Option<isize>,
+}
+
 fn build_cursor_snapshot(line: Option<usize>, column: Option<usize>) -> Option<CursorSnapshot> {
     match (line, column) {
         (Some(line), Some(column)) if line > 0 && column > 0 => {
@@ -34,104 +46,250 @@ fn cursor_payload_from_snapshot(
     }
 }
 
-pub(super) fn apply_operation(doc: &mut Document, operation: &EditOperation) -> Result<(), String> {
-    let rope = &mut doc.rope;
-    let start = operation.start_char.min(rope.len_chars());
-    let old_char_len = operation.old_text.chars().count();
-    let end = start
-        .checked_add(old_char_len)
-        .ok_or_else(|| "Edit range overflow".to_string())?;
+pub(super) fn undo_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<HistoryActionResultPayload, String> {
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        let mut engine = doc.engine();
+        let Some(operation) = engine.undo()? else {
+            return Err("No more undo steps".to_string());
+        };
+        let before_cursor = operation.before_cursor.clone();
+        let change = engine.finish();
+        drop(doc);
+        clear_outdated_document_search_caches(&id, change.document_version);
+        Ok(cursor_payload_from_snapshot(
+            before_cursor.as_ref(),
+            change.line_count,
+        ))
+    } else {
+        Err("Document not found".to_string())
+    }
+}
 
-    if end > rope.len_chars() {
-        return Err("Edit range out of bounds".to_string());
+pub(super) fn redo_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<HistoryActionResultPayload, String> {
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        let mut engine = doc.engine();
+        let Some(operation) = engine.redo()? else {
+            return Err("No more redo steps".to_string());
+        };
+        let after_cursor = operation.after_cursor.clone();
+        let change = engine.finish();
+        drop(doc);
+        clear_outdated_document_search_caches(&id, change.document_version);
+        Ok(cursor_payload_from_snapshot(
+            after_cursor.as_ref(),
+            change.line_count,
+        ))
+    } else {
+        Err("Document not found".to_string())
     }
+}
 
-    let current_old = rope.slice(start..end).to_string();
-    if current_old != operation.old_text {
-        return Err("Edit history out of sync".to_string());
+// Edits made until `end_edit_group_impl` undo as one step, so a burst of
+// typing does not take one undo per keystroke.
+pub(super) fn begin_edit_group_impl(state: State<'_, AppState>, id: String) -> Result<u64, String> {
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        Ok(doc.begin_edit_group())
+    } else {
+        Err("Document not found".to_string())
     }
+}
 
-    if start < end {
-        rope.remove(start..end);
+pub(super) fn end_edit_group_impl(state: State<'_, AppState>, id: String) -> Result<bool, String> {
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        Ok(doc.end_edit_group())
+    } else {
+        Err("Document not found".to_string())
     }
+}
+
+pub(super) fn get_edit_history_state_impl(
+    state: State<'_, AppState>,
+    id: String,
+    include_entries: bool,
+) -> Result<EditHistoryState, String> {
+    if let Some(doc) = state.documents.get(&id) {
+        let (entries, current_index) = if include_entries {
+            let mut entries = build_history_entries(doc.undo_stack.iter());
+            let current_index = entries.len();
+            entries.extend(build_history_entries(doc.redo_stack.iter().rev()));
+            (Some(entries), Some(current_index))
+        } else {
+            (None, None)
+        };
 
-    if !operation.new_text.is_empty() {
-        rope.insert(start, &operation.new_text);
+        Ok(EditHistoryState {
+            can_undo: !doc.undo_stack.is_empty(),
+            can_redo: !doc.redo_stack.is_empty(),
+            is_dirty: doc.has_unsaved_text_changes()
+                || doc.encoding.name() != doc.saved_encoding
+                || doc.line_ending != doc.saved_line_ending,
+            entries,
+            current_index,
+        })
+    } else {
+        Err("Document not found".to_string())
     }
+}
 
-    doc.document_version = doc.document_version.saturating_add(1);
-    Ok(())
+const HISTORY_LABEL_PREVIEW_CHARS: usize = 32;
+
+fn build_history_label(inserted: &str, removed: &str) -> String {
+    let (verb, text) = match (inserted.is_empty(), removed.is_empty()) {
+        (false, true) => ("Insert", inserted),
+        (true, false) => ("Delete", removed),
+        _ => ("Replace", inserted),
+    };
+    let first_line = text.lines().next().unwrap_or_default();
+    let mut preview = first_line
+        .chars()
+        .take(HISTORY_LABEL_PREVIEW_CHARS)
+        .collect::<String>();
+    if preview.len() < text.len() {
+        preview.push('…');
+    }
+
+    format!("{verb} \"{preview}\"")
 }
 
-pub(super) fn create_edit_operation(
-    doc: &mut Document,
-    start_char: usize,
-    old_text: String,
-    new_text: String,
-) -> EditOperation {
-    EditOperation {
-        operation_id: doc.allocate_edit_operation_id(),
-        start_char,
-        old_text,
-        new_text,
-        before_cursor: None,
-        after_cursor: None,
+// Folds operations, given in the order they were applied, into one entry per
+// undo step. The label describes the step's first operation.
+fn build_history_entries<'a>(
+    operations: impl Iterator<Item = &'a EditOperation>,
+) -> Vec<EditHistoryEntry> {
+    let mut entries: Vec<EditHistoryEntry> = Vec::new();
+    let mut previous_group: Option<u64> = None;
+
+    for operation in operations {
+        let joins_previous = operation.group_id.is_some() && operation.group_id == previous_group;
+        previous_group = operation.group_id;
+        match entries.last_mut() {
+            Some(entry) if joins_previous => {
+                entry.inserted_bytes += operation.new_text.len();
+                entry.removed_bytes += operation.old_text.len();
+                entry.applied_at_ms = entry.applied_at_ms.max(operation.applied_at_ms);
+            }
+            _ => entries.push(EditHistoryEntry {
+                label: build_history_label(&operation.new_text, &operation.old_text),
+                inserted_bytes: operation.new_text.len(),
+                removed_bytes: operation.old_text.len(),
+                applied_at_ms: operation.applied_at_ms,
+            }),
+        }
     }
+
+    entries
 }
 
-pub(super) fn undo_impl(
+fn count_history_steps(operations: &[EditOperation]) -> usize {
+    build_history_entries(operations.iter()).len()
+}
+
+// Undoes or redoes whole steps until exactly `index` history entries are
+// applied, as listed by `get_edit_history_state`.
+pub(super) fn revert_to_history_index_impl(
     state: State<'_, AppState>,
     id: String,
+    index: usize,
 ) -> Result<HistoryActionResultPayload, String> {
     if let Some(mut doc) = state.documents.get_mut(&id) {
-        if let Some(operation) = doc.undo_stack.pop() {
-            let inverse = operation.inverse();
-            apply_operation(&mut doc, &inverse)?;
-            let result = cursor_payload_from_snapshot(
-                operation.before_cursor.as_ref(),
-                doc.rope.len_lines(),
-            );
-            doc.redo_stack.push(operation);
-            Ok(result)
-        } else {
-            Err("No more undo steps".to_string())
+        let current_index = count_history_steps(&doc.undo_stack);
+        let entry_count = current_index + count_history_steps(&doc.redo_stack);
+        if index > entry_count {
+            return Err("History index out of range".to_string());
+        }
+
+        let mut engine = doc.engine();
+        let mut cursor = None;
+        for _ in index..current_index {
+            cursor = engine
+                .undo()?
+                .and_then(|operation| operation.before_cursor.clone());
         }
+        for _ in current_index..index {
+            cursor = engine
+                .redo()?
+                .and_then(|operation| operation.after_cursor.clone());
+        }
+        let change = engine.finish();
+        drop(doc);
+        if change.changed {
+            clear_outdated_document_search_caches(&id, change.document_version);
+        }
+
+        Ok(cursor_payload_from_snapshot(
+            cursor.as_ref(),
+            change.line_count,
+        ))
     } else {
         Err("Document not found".to_string())
     }
 }
 
-pub(super) fn redo_impl(
+pub(super) fn get_dirty_state_impl(
     state: State<'_, AppState>,
     id: String,
-) -> Result<HistoryActionResultPayload, String> {
-    if let Some(mut doc) = state.documents.get_mut(&id) {
-        if let Some(operation) = doc.redo_stack.pop() {
-            apply_operation(&mut doc, &operation)?;
-            let result =
-                cursor_payload_from_snapshot(operation.after_cursor.as_ref(), doc.rope.len_lines());
-            doc.undo_stack.push(operation);
-            Ok(result)
-        } else {
-            Err("No more redo steps".to_string())
-        }
+) -> Result<DirtyStatePayload, String> {
+    if let Some(is_dirty) = hex_document::is_hex_document_dirty(&id) {
+        return Ok(DirtyStatePayload {
+            is_dirty,
+            text_changed: is_dirty,
+            encoding_changed: false,
+            line_ending_changed: false,
+            save_point_distance: None,
+        });
+    }
+    if let Some(doc) = state.documents.get(&id) {
+        let text_changed = doc.has_unsaved_text_changes();
+        let encoding_changed = doc.encoding.name() != doc.saved_encoding;
+        let line_ending_changed = doc.line_ending != doc.saved_line_ending;
+
+        Ok(DirtyStatePayload {
+            is_dirty: text_changed || encoding_changed || line_ending_changed,
+            text_changed,
+            encoding_changed,
+            line_ending_changed,
+            save_point_distance: doc.save_point_distance(),
+        })
     } else {
         Err("Document not found".to_string())
     }
 }
 
-pub(super) fn get_edit_history_state_impl(
+pub(super) fn undo_to_save_point_impl(
     state: State<'_, AppState>,
     id: String,
-) -> Result<EditHistoryState, String> {
-    if let Some(doc) = state.documents.get(&id) {
-        Ok(EditHistoryState {
-            can_undo: !doc.undo_stack.is_empty(),
-            can_redo: !doc.redo_stack.is_empty(),
-            is_dirty: doc.has_unsaved_text_changes()
-                || doc.encoding.name() != doc.saved_encoding
-                || doc.line_ending != doc.saved_line_ending,
-        })
+) -> Result<HistoryActionResultPayload, String> {
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        let distance = doc.save_point_distance().unwrap_or(0);
+        let mut engine = doc.engine();
+        engine.undo_to_save_point()?;
+        let change = engine.finish();
+        // Land the cursor where the last stepped-over operation left it.
+        let cursor = if distance > 0 {
+            doc.redo_stack
+                .last()
+                .and_then(|operation| operation.before_cursor.clone())
+        } else if distance < 0 {
+            doc.undo_stack
+                .last()
+                .and_then(|operation| operation.after_cursor.clone())
+        } else {
+            None
+        };
+        drop(doc);
+        if change.changed {
+            clear_outdated_document_search_caches(&id, change.document_version);
+        }
+        Ok(cursor_payload_from_snapshot(
+            cursor.as_ref(),
+            change.line_count,
+        ))
     } else {
         Err("Document not found".to_string())
     }
@@ -147,7 +305,7 @@ pub struct LineColumnTextEdit {
     pub text: String,
 }
 
-fn trimmed_line_without_break(rope: &Rope, line_index: usize) -> String {
+pub(super) fn trimmed_line_without_break(rope: &Rope, line_index: usize) -> String {
     let mut line = rope.line(line_index).to_string();
     if line.ends_with('\n') {
         line.pop();
@@ -158,7 +316,7 @@ fn trimmed_line_without_break(rope: &Rope, line_index: usize) -> String {
     line
 }
 
-fn utf16_column_to_char_offset(text: &str, column: usize) -> usize {
+pub(super) fn utf16_column_to_char_offset(text: &str, column: usize) -> usize {
     let target_utf16_offset = column.saturating_sub(1);
     let mut consumed_utf16 = 0usize;
     let mut consumed_chars = 0usize;
@@ -176,7 +334,11 @@ fn utf16_column_to_char_offset(text: &str, column: usize) -> usize {
     consumed_chars
 }
 
-fn line_column_to_char_index_utf16(rope: &Rope, line_number: usize, column: usize) -> usize {
+pub(super) fn line_column_to_char_index_utf16(
+    rope: &Rope,
+    line_number: usize,
+    column: usize,
+) -> usize {
     let line_count = rope.len_lines().max(1);
     let safe_line = line_number.max(1);
 
@@ -251,44 +413,223 @@ pub(super) fn apply_text_edits_by_line_column_impl(
         });
 
         let edit_count = sorted_edits.len();
-        let mut changed = false;
+        let mut engine = doc.engine();
+
+        // Edits apply back to front so earlier offsets stay valid; a failure
+        // part way through reverts the ones already applied.
+        engine.transaction(|engine| {
+            for (index, edit) in sorted_edits.into_iter().rev().enumerate() {
+                let rope = &engine.document().rope;
+                let raw_start = line_column_to_char_index_utf16(
+                    rope,
+                    edit.start_line_number,
+                    edit.start_column,
+                );
+                let raw_end =
+                    line_column_to_char_index_utf16(rope, edit.end_line_number, edit.end_column);
+                let start_char = raw_start.min(raw_end);
+                let end_char = raw_start.max(raw_end);
+
+                let old_text = rope.slice(start_char..end_char).to_string();
+                if old_text == edit.text {
+                    continue;
+                }
 
-        for (index, edit) in sorted_edits.into_iter().rev().enumerate() {
-            let raw_start = line_column_to_char_index_utf16(
-                &doc.rope,
-                edit.start_line_number,
-                edit.start_column,
-            );
-            let raw_end =
-                line_column_to_char_index_utf16(&doc.rope, edit.end_line_number, edit.end_column);
-            let start_char = raw_start.min(raw_end);
-            let end_char = raw_start.max(raw_end);
+                let mut operation = engine.new_operation(start_char, old_text, edit.text);
+                if index + 1 == edit_count {
+                    operation.before_cursor =
+                        build_cursor_snapshot(before_cursor_line, before_cursor_column);
+                }
+                if index == 0 {
+                    operation.after_cursor =
+                        build_cursor_snapshot(after_cursor_line, after_cursor_column);
+                }
 
-            let old_text = doc.rope.slice(start_char..end_char).to_string();
-            if old_text == edit.text {
-                continue;
+                engine.apply(operation)?;
             }
+            Ok(())
+        })?;
 
-            let mut operation = create_edit_operation(&mut doc, start_char, old_text, edit.text);
-            if index + 1 == edit_count {
-                operation.before_cursor =
-                    build_cursor_snapshot(before_cursor_line, before_cursor_column);
-            }
-            if index == 0 {
-                operation.after_cursor =
-                    build_cursor_snapshot(after_cursor_line, after_cursor_column);
+        let change = engine.finish();
+        if change.changed {
+            drop(doc);
+            clear_outdated_document_search_caches(&id, change.document_version);
+        }
+
+        Ok(change.line_count)
+    } else {
+        Err("Document not found".to_string())
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharRangeTextEdit {
+    pub start_char: usize,
+    pub end_char: usize,
+    pub text: String,
+}
+
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharRangeResult {
+    pub start_char: usize,
+    pub end_char: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiTextEditResultPayload {
+    pub line_count: usize,
+    pub document_version: u64,
+    // Where each edit's text landed, in request order.
+    pub ranges: Vec<CharRangeResult>,
+}
+
+// One edit of a planned multi-range edit. Its offset is valid once the edits
+// before it in the plan are applied.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct PlannedTextEdit {
+    pub(super) start_char: usize,
+    pub(super) old_text: String,
+    pub(super) new_text: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct PlannedTextEdits {
+    // Last edit in the text first, so applying one never moves the ones after
+    // it in the list. Edits that change nothing are left out.
+    pub(super) edits: Vec<PlannedTextEdit>,
+    pub(super) ranges: Vec<CharRangeResult>,
+}
+
+// Orders edits given against the current text for application one by one and
+// works out where each one's text ends up. Edits touching the same insertion
+// point keep their request order.
+pub(super) fn plan_char_range_edits(
+    rope: &Rope,
+    edits: &[CharRangeTextEdit],
+) -> Result<PlannedTextEdits, String> {
+    let len_chars = rope.len_chars();
+    let mut sorted = edits
+        .iter()
+        .enumerate()
+        .map(|(index, edit)| {
+            let start_char = edit.start_char.min(edit.end_char);
+            let end_char = edit.start_char.max(edit.end_char);
+            if end_char > len_chars {
+                return Err("Edit range is out of bounds".to_string());
             }
+            Ok((start_char, end_char, edit.text.as_str(), index))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    sorted.sort_by_key(|(start_char, end_char, _, _)| (*start_char, *end_char));
+
+    if sorted.windows(2).any(|pair| pair[0].1 > pair[1].0) {
+        return Err("Edits must not overlap".to_string());
+    }
+
+    let mut shift = 0isize;
+    let mut ranges = vec![CharRangeResult::default(); edits.len()];
+    let mut planned = Vec::with_capacity(sorted.len());
+    for &(start_char, end_char, text, index) in &sorted {
+        let text_chars = text.chars().count();
+        let landed_start = start_char.saturating_add_signed(shift);
+        ranges[index] = CharRangeResult {
+            start_char: landed_start,
+            end_char: landed_start + text_chars,
+        };
+        shift += text_chars as isize - (end_char - start_char) as isize;
+
+        let old_text = rope.slice(start_char..end_char).to_string();
+        if old_text != text {
+            planned.push(PlannedTextEdit {
+                start_char,
+                old_text,
+                new_text: text.to_string(),
+            });
+        }
+    }
+    planned.reverse();
 
-            apply_operation(&mut doc, &operation)?;
-            doc.undo_stack.push(operation);
-            changed = true;
+    Ok(PlannedTextEdits {
+        edits: planned,
+        ranges,
+    })
+}
+
+// Records one operation per edit instead of a replacement spanning all of
+// them, so the history holds only the edited text. The operations share a
+// group and undo and redo as one step; undo restores `before_cursor` and redo
+// `after_cursor`.
+pub(super) fn apply_planned_text_edits(
+    engine: &mut DocumentEngine<'_>,
+    edits: Vec<PlannedTextEdit>,
+    before_cursor: Option<CursorSnapshot>,
+    after_cursor: Option<CursorSnapshot>,
+) -> Result<(), String> {
+    let mut operations: Vec<EditOperation> = edits
+        .into_iter()
+        .map(|edit| engine.new_operation(edit.start_char, edit.old_text, edit.new_text))
+        .collect();
+    if operations.len() > 1 {
+        // Inside an open edit group, or when continuing typed text, the
+        // operations already share a group id.
+        let group_id = operations[0].group_id.unwrap_or(operations[0].operation_id);
+        for operation in &mut operations {
+            operation.group_id = Some(group_id);
         }
+    }
+    if let Some(first) = operations.first_mut() {
+        first.before_cursor = before_cursor;
+    }
+    if let Some(last) = operations.last_mut() {
+        last.after_cursor = after_cursor;
+    }
 
-        if changed {
-            doc.redo_stack.clear();
+    engine.batch(operations)
+}
+
+#[cfg(test)]
+pub(super) fn apply_planned_text_edits_to_rope(rope: &Rope, edits: &[PlannedTextEdit]) -> String {
+    let mut result = rope.clone();
+    for edit in edits {
+        result.remove(edit.start_char..edit.start_char + edit.old_text.chars().count());
+        result.insert(edit.start_char, &edit.new_text);
+    }
+    result.to_string()
+}
+
+pub(super) fn edit_text_multi_impl(
+    state: State<'_, AppState>,
+    id: String,
+    edits: Vec<CharRangeTextEdit>,
+    before_cursor_line: Option<usize>,
+    before_cursor_column: Option<usize>,
+    after_cursor_line: Option<usize>,
+    after_cursor_column: Option<usize>,
+) -> Result<MultiTextEditResultPayload, String> {
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        let planned = plan_char_range_edits(&doc.rope, &edits)?;
+        let mut engine = doc.engine();
+        apply_planned_text_edits(
+            &mut engine,
+            planned.edits,
+            build_cursor_snapshot(before_cursor_line, before_cursor_column),
+            build_cursor_snapshot(after_cursor_line, after_cursor_column),
+        )?;
+
+        let change = engine.finish();
+        if change.changed {
+            drop(doc);
+            clear_outdated_document_search_caches(&id, change.document_version);
         }
 
-        Ok(doc.rope.len_lines())
+        Ok(MultiTextEditResultPayload {
+            line_count: change.line_count,
+            document_version: change.document_version,
+            ranges: planned.ranges,
+        })
     } else {
         Err("Document not found".to_string())
     }
@@ -704,6 +1045,63 @@ pub(super) fn get_rectangular_selection_text_impl(
         .map_err(|error| format!("Failed to convert rectangular selection text result: {error}"))
 }
 
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LineLayoutPayload {
+    pub line: usize,
+    pub line_length: usize,
+    pub first_non_whitespace_column: usize,
+    pub trailing_whitespace_start_column: usize,
+    pub indentation: String,
+    pub is_blank: bool,
+}
+
+// Columns are 1-based UTF-16 columns. For blank lines both the first
+// non-whitespace column and the trailing whitespace start point past the end.
+fn compute_line_layout(line: usize, line_text: &str) -> LineLayoutPayload {
+    let line_length = line_text.encode_utf16().count();
+    let indentation_end = line_text
+        .char_indices()
+        .find(|(_, ch)| !ch.is_whitespace())
+        .map(|(index, _)| index)
+        .unwrap_or(line_text.len());
+    let indentation = &line_text[..indentation_end];
+    let content_end = line_text.trim_end().len().max(indentation_end);
+    let is_blank = indentation_end == line_text.len();
+
+    LineLayoutPayload {
+        line,
+        line_length,
+        first_non_whitespace_column: if is_blank {
+            line_length + 1
+        } else {
+            indentation.encode_utf16().count() + 1
+        },
+        trailing_whitespace_start_column: line_text[..content_end].encode_utf16().count() + 1,
+        indentation: indentation.to_string(),
+        is_blank,
+    }
+}
+
+pub(super) fn get_line_layout_impl(
+    state: State<'_, AppState>,
+    id: String,
+    line: usize,
+) -> Result<LineLayoutPayload, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    let line_count = doc.rope.len_lines().max(1);
+    if line == 0 || line > line_count {
+        return Err("Line out of bounds".to_string());
+    }
+
+    let line_text = trimmed_line_without_break(&doc.rope, line - 1);
+    Ok(compute_line_layout(line, &line_text))
+}
+
 fn encode_base64_utf8(value: &str) -> String {
     base64::engine::general_purpose::STANDARD.encode(value.as_bytes())
 }
@@ -973,13 +1371,13 @@ pub(super) fn cleanup_document_impl(
             return Ok(doc.rope.len_lines());
         }
 
-        let operation = create_edit_operation(&mut doc, 0, source, cleaned);
-
-        apply_operation(&mut doc, &operation)?;
-        doc.undo_stack.push(operation);
-        doc.redo_stack.clear();
+        let mut engine = doc.engine();
+        engine.replace(0, source, cleaned)?;
+        let change = engine.finish();
+        drop(doc);
+        clear_outdated_document_search_caches(&id, change.document_version);
 
-        Ok(doc.rope.len_lines())
+        Ok(change.line_count)
     } else {
         Err("Document not found".to_string())
     }
@@ -1010,13 +1408,13 @@ pub(super) fn format_document_impl(
             return Ok(doc.rope.len_lines());
         }
 
-        let operation = create_edit_operation(&mut doc, 0, source, formatted);
+        let mut engine = doc.engine();
+        engine.replace(0, source, formatted)?;
+        let change = engine.finish();
+        drop(doc);
+        clear_outdated_document_search_caches(&id, change.document_version);
 
-        apply_operation(&mut doc, &operation)?;
-        doc.undo_stack.push(operation);
-        doc.redo_stack.clear();
-
-        Ok(doc.rope.len_lines())
+        Ok(change.line_count)
     } else {
         Err("Document not found".to_string())
     }
@@ -1025,12 +1423,15 @@ pub(super) fn format_document_impl(
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_line_column_edits_to_text_for_test, build_clipboard_image_from_rgba,
-        cleanup_document_lines, decode_image_file_to_clipboard_image, decode_image_file_to_rgba,
-        encode_image_file_as_data_url_impl, find_matching_pair_offsets_impl,
-        line_column_to_char_index_utf16, utf16_column_to_char_offset, DocumentCleanupAction,
-        LineColumnTextEdit,
+        apply_line_column_edits_to_text_for_test, apply_planned_text_edits,
+        apply_planned_text_edits_to_rope, build_clipboard_image_from_rgba, build_history_entries,
+        cleanup_document_lines, compute_line_layout, decode_image_file_to_clipboard_image,
+        decode_image_file_to_rgba, encode_image_file_as_data_url_impl,
+        find_matching_pair_offsets_impl, line_column_to_char_index_utf16, plan_char_range_edits,
+        utf16_column_to_char_offset, CharRangeResult, CharRangeTextEdit, DocumentCleanupAction,
+        LineColumnTextEdit, PlannedTextEdit,
     };
+    use crate::state::{CursorSnapshot, Document, EditOperation};
     use ropey::Rope;
     use std::fs;
 
@@ -1262,6 +1663,190 @@ mod tests {
         assert_eq!(line_column_to_char_index_utf16(&rope, 2, 3), 7);
     }
 
+    fn history_operation(
+        old_text: &str,
+        new_text: &str,
+        group_id: Option<u64>,
+        applied_at_ms: u64,
+    ) -> EditOperation {
+        EditOperation {
+            operation_id: applied_at_ms,
+            start_char: 0,
+            old_text: old_text.to_string(),
+            new_text: new_text.to_string(),
+            before_cursor: None,
+            after_cursor: None,
+            group_id,
+            applied_at_ms,
+        }
+    }
+
+    #[test]
+    fn build_history_entries_should_fold_groups_into_single_steps() {
+        let operations = [
+            history_operation("", "h", Some(7), 10),
+            history_operation("", "i", Some(7), 20),
+            history_operation("old", "", None, 30),
+            history_operation("", "a", None, 40),
+            history_operation(
+                "a",
+                "a very long replacement that keeps going\nnext",
+                None,
+                50,
+            ),
+        ];
+        let entries = build_history_entries(operations.iter());
+
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].label, "Insert \"h\"");
+        assert_eq!(entries[0].inserted_bytes, 2);
+        assert_eq!(entries[0].applied_at_ms, 20);
+        assert_eq!(entries[1].label, "Delete \"old\"");
+        assert_eq!(entries[1].removed_bytes, 3);
+        assert_eq!(
+            entries[3].label,
+            "Replace \"a very long replacement that kee…\""
+        );
+    }
+
+    fn char_range_edit(start_char: usize, end_char: usize, text: &str) -> CharRangeTextEdit {
+        CharRangeTextEdit {
+            start_char,
+            end_char,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn plan_char_range_edits_should_order_edits_back_to_front() {
+        let rope = Rope::from_str("a1\nb2\nc3\n");
+        let planned = plan_char_range_edits(
+            &rope,
+            &[
+                char_range_edit(7, 7, "xyz"),
+                char_range_edit(1, 2, ""),
+                char_range_edit(4, 4, "Z"),
+                char_range_edit(5, 6, "\n"),
+            ],
+        )
+        .expect("plan edits");
+
+        assert_eq!(
+            planned.edits,
+            vec![
+                PlannedTextEdit {
+                    start_char: 7,
+                    old_text: String::new(),
+                    new_text: "xyz".to_string(),
+                },
+                PlannedTextEdit {
+                    start_char: 4,
+                    old_text: String::new(),
+                    new_text: "Z".to_string(),
+                },
+                PlannedTextEdit {
+                    start_char: 1,
+                    old_text: "1".to_string(),
+                    new_text: String::new(),
+                },
+            ]
+        );
+        assert_eq!(
+            planned.ranges,
+            vec![
+                CharRangeResult {
+                    start_char: 7,
+                    end_char: 10,
+                },
+                CharRangeResult {
+                    start_char: 1,
+                    end_char: 1,
+                },
+                CharRangeResult {
+                    start_char: 3,
+                    end_char: 4,
+                },
+                CharRangeResult {
+                    start_char: 5,
+                    end_char: 6,
+                },
+            ]
+        );
+        assert_eq!(
+            apply_planned_text_edits_to_rope(&rope, &planned.edits),
+            "a\nbZ2\ncxyz3\n"
+        );
+    }
+
+    #[test]
+    fn plan_char_range_edits_should_reject_overlapping_or_out_of_bounds_edits() {
+        let rope = Rope::from_str("abcdef");
+
+        assert!(plan_char_range_edits(
+            &rope,
+            &[char_range_edit(1, 4, "x"), char_range_edit(3, 5, "y")]
+        )
+        .is_err());
+        assert!(plan_char_range_edits(&rope, &[char_range_edit(5, 9, "x")]).is_err());
+
+        let same_point = plan_char_range_edits(
+            &rope,
+            &[char_range_edit(2, 2, "x"), char_range_edit(2, 2, "y")],
+        )
+        .expect("plan edits");
+        assert_eq!(
+            apply_planned_text_edits_to_rope(&rope, &same_point.edits),
+            "abxycdef"
+        );
+    }
+
+    #[test]
+    fn apply_planned_text_edits_should_record_small_operations_as_one_undo_step() {
+        let mut doc = Document::for_test(
+            &format!("a{}b{}c", "-".repeat(1000), "-".repeat(1000)),
+            None,
+        );
+        let original = doc.rope.to_string();
+        let planned = plan_char_range_edits(
+            &doc.rope,
+            &[
+                char_range_edit(0, 1, "A"),
+                char_range_edit(1001, 1002, "B"),
+                char_range_edit(2002, 2003, "C"),
+            ],
+        )
+        .expect("plan edits");
+
+        let mut engine = doc.engine();
+        apply_planned_text_edits(
+            &mut engine,
+            planned.edits,
+            Some(CursorSnapshot { line: 1, column: 1 }),
+            Some(CursorSnapshot { line: 1, column: 2 }),
+        )
+        .expect("apply edits");
+        drop(engine);
+
+        assert_eq!(doc.undo_stack.len(), 3);
+        assert!(doc
+            .undo_stack
+            .iter()
+            .all(|operation| operation.old_text.len() == 1 && operation.new_text.len() == 1));
+        assert!(doc.rope.to_string().starts_with('A'));
+
+        let mut engine = doc.engine();
+        let undone = engine
+            .undo()
+            .expect("undo")
+            .cloned()
+            .expect("undone operation");
+        drop(engine);
+        let before_cursor = undone.before_cursor.expect("before cursor");
+        assert_eq!((before_cursor.line, before_cursor.column), (1, 1));
+        assert!(doc.undo_stack.is_empty());
+        assert_eq!(doc.rope.to_string(), original);
+    }
+
     #[test]
     fn apply_line_column_edits_should_handle_cross_line_replace() {
         let source = "alpha\nbeta\ngamma\n";
@@ -1321,4 +1906,31 @@ mod tests {
 
         assert_eq!(result, "0AA23B5\n");
     }
+
+    #[test]
+    fn compute_line_layout_should_report_indentation_and_trailing_whitespace() {
+        let layout = compute_line_layout(3, "\t  let 😀 = 1;  \t");
+
+        assert_eq!(layout.line, 3);
+        assert_eq!(layout.indentation, "\t  ");
+        assert_eq!(layout.first_non_whitespace_column, 4);
+        assert_eq!(layout.trailing_whitespace_start_column, 15);
+        assert_eq!(layout.line_length, 17);
+        assert!(!layout.is_blank);
+    }
+
+    #[test]
+    fn compute_line_layout_should_point_past_end_for_blank_lines() {
+        let layout = compute_line_layout(1, "    ");
+
+        assert_eq!(layout.indentation, "    ");
+        assert_eq!(layout.first_non_whitespace_column, 5);
+        assert_eq!(layout.trailing_whitespace_start_column, 5);
+        assert!(layout.is_blank);
+
+        let empty = compute_line_layout(2, "");
+        assert_eq!(empty.first_non_whitespace_column, 1);
+        assert_eq!(empty.trailing_whitespace_start_column, 1);
+        assert!(empty.is_blank);
+    }
 }
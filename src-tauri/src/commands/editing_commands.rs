@@ -4,7 +4,7 @@ use tauri_plugin_clipboard_manager::ClipboardExt;
 #[tauri::command]
 pub fn undo(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
 ) -> Result<editing::HistoryActionResultPayload, String> {
     editing::undo_impl(state, id)
 }
@@ -12,23 +12,59 @@ pub fn undo(
 #[tauri::command]
 pub fn redo(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
 ) -> Result<editing::HistoryActionResultPayload, String> {
     editing::redo_impl(state, id)
 }
 
+#[tauri::command]
+pub fn begin_edit_group(state: State<'_, AppState>, id: DocumentId) -> Result<u64, String> {
+    editing::begin_edit_group_impl(state, id)
+}
+
+#[tauri::command]
+pub fn end_edit_group(state: State<'_, AppState>, id: DocumentId) -> Result<bool, String> {
+    editing::end_edit_group_impl(state, id)
+}
+
+#[tauri::command]
+pub fn get_dirty_state(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<editing::DirtyStatePayload, String> {
+    editing::get_dirty_state_impl(state, id)
+}
+
+#[tauri::command]
+pub fn undo_to_save_point(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<editing::HistoryActionResultPayload, String> {
+    editing::undo_to_save_point_impl(state, id)
+}
+
 #[tauri::command]
 pub fn get_edit_history_state(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
+    include_entries: Option<bool>,
 ) -> Result<EditHistoryState, String> {
-    editing::get_edit_history_state_impl(state, id)
+    editing::get_edit_history_state_impl(state, id, include_entries.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn revert_to_history_index(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    index: usize,
+) -> Result<editing::HistoryActionResultPayload, String> {
+    editing::revert_to_history_index_impl(state, id, index)
 }
 
 #[tauri::command]
 pub fn apply_text_edits_by_line_column(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     edits: Vec<editing::LineColumnTextEdit>,
     before_cursor_line: Option<usize>,
     before_cursor_column: Option<usize>,
@@ -46,10 +82,31 @@ pub fn apply_text_edits_by_line_column(
     )
 }
 
+#[tauri::command]
+pub fn edit_text_multi(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    edits: Vec<editing::CharRangeTextEdit>,
+    before_cursor_line: Option<usize>,
+    before_cursor_column: Option<usize>,
+    after_cursor_line: Option<usize>,
+    after_cursor_column: Option<usize>,
+) -> Result<editing::MultiTextEditResultPayload, String> {
+    editing::edit_text_multi_impl(
+        state,
+        id,
+        edits,
+        before_cursor_line,
+        before_cursor_column,
+        after_cursor_line,
+        after_cursor_column,
+    )
+}
+
 #[tauri::command]
 pub fn cleanup_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     action: String,
 ) -> Result<usize, String> {
     editing::cleanup_document_impl(state, id, action)
@@ -58,7 +115,7 @@ pub fn cleanup_document(
 #[tauri::command]
 pub fn format_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     mode: String,
     file_syntax: Option<String>,
     file_path: Option<String>,
@@ -81,6 +138,17 @@ pub fn convert_text_base64(text: String, action: String) -> Result<String, Strin
     editing::convert_text_base64_impl(text, action)
 }
 
+#[tauri::command]
+pub fn transform_selection_text(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_char: usize,
+    end_char: usize,
+    action: String,
+) -> Result<text_transform::TransformSelectionResultPayload, String> {
+    text_transform::transform_selection_text_impl(state, id, start_char, end_char, action)
+}
+
 #[tauri::command]
 pub fn encode_image_file_as_data_url(path: String) -> Result<String, String> {
     editing::encode_image_file_as_data_url_impl(path)
@@ -108,6 +176,38 @@ pub fn copy_rgba_image_to_clipboard(
         .map_err(|error| format!("Failed to write image to system clipboard: {error}"))?;
     Ok(())
 }
+
+#[tauri::command]
+pub async fn push_clipboard_entry(
+    app: AppHandle,
+    text: String,
+    _timer: CommandTimer,
+) -> Result<Vec<clipboard_history::ClipboardEntrySummary>, String> {
+    clipboard_history::push_clipboard_entry_impl(app, text).await
+}
+
+#[tauri::command]
+pub async fn list_clipboard_history(
+    app: AppHandle,
+    _timer: CommandTimer,
+) -> Result<Vec<clipboard_history::ClipboardEntrySummary>, String> {
+    clipboard_history::list_clipboard_history_impl(app).await
+}
+
+#[tauri::command]
+pub async fn paste_clipboard_entry(
+    app: AppHandle,
+    index: usize,
+    _timer: CommandTimer,
+) -> Result<String, String> {
+    clipboard_history::paste_clipboard_entry_impl(app, index).await
+}
+
+#[tauri::command]
+pub async fn clear_clipboard_history(app: AppHandle, _timer: CommandTimer) -> Result<(), String> {
+    clipboard_history::clear_clipboard_history_impl(app).await
+}
+
 #[tauri::command]
 pub fn find_matching_pair_offsets(
     text: String,
@@ -154,6 +254,105 @@ pub fn get_rectangular_selection_text(
     )
 }
 
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn insert_column_sequence(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_line: usize,
+    end_line: usize,
+    column: usize,
+    start_number: Option<i64>,
+    step: Option<i64>,
+    padding: Option<String>,
+    text: Option<String>,
+) -> Result<column_sequence::ColumnSequenceResultPayload, String> {
+    column_sequence::insert_column_sequence_impl(
+        state,
+        id,
+        start_line,
+        end_line,
+        column,
+        start_number,
+        step,
+        padding,
+        text,
+    )
+}
+
+#[tauri::command]
+pub fn format_as_table(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_line: usize,
+    end_line: usize,
+    delimiter: Option<String>,
+    style: Option<String>,
+) -> Result<text_table::TextTableResultPayload, String> {
+    text_table::format_as_table_impl(state, id, start_line, end_line, delimiter, style)
+}
+
+#[tauri::command]
+pub fn table_to_delimited(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_line: usize,
+    end_line: usize,
+    delimiter: Option<String>,
+) -> Result<text_table::TextTableResultPayload, String> {
+    text_table::table_to_delimited_impl(state, id, start_line, end_line, delimiter)
+}
+
+#[tauri::command]
+pub fn get_line_layout(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    line: usize,
+) -> Result<editing::LineLayoutPayload, String> {
+    editing::get_line_layout_impl(state, id, line)
+}
+
+#[tauri::command]
+pub async fn should_auto_close_pair(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    char_offset: usize,
+    typed_char: String,
+    _timer: CommandTimer,
+) -> Result<auto_pair::AutoClosePairPayload, String> {
+    auto_pair::should_auto_close_pair_impl(state, id, char_offset, typed_char).await
+}
+
+#[tauri::command]
+pub async fn compute_auto_indent(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    char_offset: usize,
+    _timer: CommandTimer,
+) -> Result<String, String> {
+    auto_indent::compute_auto_indent_impl(state, id, char_offset).await
+}
+
+#[tauri::command]
+pub fn toggle_line_comments(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_char: usize,
+    end_char: usize,
+) -> Result<comments::ToggleCommentsResultPayload, String> {
+    comments::toggle_line_comments_impl(state, id, start_char, end_char)
+}
+
+#[tauri::command]
+pub fn toggle_block_comment(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_char: usize,
+    end_char: usize,
+) -> Result<comments::ToggleCommentsResultPayload, String> {
+    comments::toggle_block_comment_impl(state, id, start_char, end_char)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
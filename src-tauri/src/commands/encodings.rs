@@ -0,0 +1,210 @@
+// Backs the encoding menu so it lists exactly what `convert_encoding` and the
+// reopen dialog accept. `replacement` and `x-user-defined` are left out since
+// they are not useful choices for a text file.
+
+use super::*;
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportedEncodingPayload {
+    // Canonical encoding_rs name; pass it back to `convert_encoding`.
+    pub name: String,
+    pub label: String,
+    // False for UTF-16, which encoding_rs can decode but only saves as UTF-8.
+    pub can_encode: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodingFamilyPayload {
+    pub key: String,
+    pub label: String,
+    pub encodings: Vec<SupportedEncodingPayload>,
+}
+
+struct EncodingFamily {
+    key: &'static str,
+    label_key: &'static str,
+    encodings: &'static [&'static encoding_rs::Encoding],
+}
+
+const ENCODING_FAMILIES: &[EncodingFamily] = &[
+    EncodingFamily {
+        key: "unicode",
+        label_key: "encodingFamily.unicode",
+        encodings: &[
+            &encoding_rs::UTF_8,
+            &encoding_rs::UTF_16LE,
+            &encoding_rs::UTF_16BE,
+        ],
+    },
+    EncodingFamily {
+        key: "chinese_simplified",
+        label_key: "encodingFamily.chineseSimplified",
+        encodings: &[&encoding_rs::GBK, &encoding_rs::GB18030],
+    },
+    EncodingFamily {
+        key: "chinese_traditional",
+        label_key: "encodingFamily.chineseTraditional",
+        encodings: &[&encoding_rs::BIG5],
+    },
+    EncodingFamily {
+        key: "japanese",
+        label_key: "encodingFamily.japanese",
+        encodings: &[
+            &encoding_rs::SHIFT_JIS,
+            &encoding_rs::EUC_JP,
+            &encoding_rs::ISO_2022_JP,
+        ],
+    },
+    EncodingFamily {
+        key: "korean",
+        label_key: "encodingFamily.korean",
+        encodings: &[&encoding_rs::EUC_KR],
+    },
+    EncodingFamily {
+        key: "western",
+        label_key: "encodingFamily.western",
+        encodings: &[
+            &encoding_rs::WINDOWS_1252,
+            &encoding_rs::ISO_8859_15,
+            &encoding_rs::MACINTOSH,
+            &encoding_rs::ISO_8859_3,
+            &encoding_rs::ISO_8859_10,
+            &encoding_rs::ISO_8859_14,
+        ],
+    },
+    EncodingFamily {
+        key: "central_european",
+        label_key: "encodingFamily.centralEuropean",
+        encodings: &[
+            &encoding_rs::WINDOWS_1250,
+            &encoding_rs::ISO_8859_2,
+            &encoding_rs::ISO_8859_16,
+        ],
+    },
+    EncodingFamily {
+        key: "cyrillic",
+        label_key: "encodingFamily.cyrillic",
+        encodings: &[
+            &encoding_rs::WINDOWS_1251,
+            &encoding_rs::ISO_8859_5,
+            &encoding_rs::KOI8_R,
+            &encoding_rs::KOI8_U,
+            &encoding_rs::IBM866,
+            &encoding_rs::X_MAC_CYRILLIC,
+        ],
+    },
+    EncodingFamily {
+        key: "greek",
+        label_key: "encodingFamily.greek",
+        encodings: &[&encoding_rs::WINDOWS_1253, &encoding_rs::ISO_8859_7],
+    },
+    EncodingFamily {
+        key: "turkish",
+        label_key: "encodingFamily.turkish",
+        encodings: &[&encoding_rs::WINDOWS_1254],
+    },
+    EncodingFamily {
+        key: "baltic",
+        label_key: "encodingFamily.baltic",
+        encodings: &[
+            &encoding_rs::WINDOWS_1257,
+            &encoding_rs::ISO_8859_4,
+            &encoding_rs::ISO_8859_13,
+        ],
+    },
+    EncodingFamily {
+        key: "hebrew",
+        label_key: "encodingFamily.hebrew",
+        encodings: &[
+            &encoding_rs::WINDOWS_1255,
+            &encoding_rs::ISO_8859_8,
+            &encoding_rs::ISO_8859_8_I,
+        ],
+    },
+    EncodingFamily {
+        key: "arabic",
+        label_key: "encodingFamily.arabic",
+        encodings: &[&encoding_rs::WINDOWS_1256, &encoding_rs::ISO_8859_6],
+    },
+    EncodingFamily {
+        key: "thai",
+        label_key: "encodingFamily.thai",
+        encodings: &[&encoding_rs::WINDOWS_874],
+    },
+    EncodingFamily {
+        key: "vietnamese",
+        label_key: "encodingFamily.vietnamese",
+        encodings: &[&encoding_rs::WINDOWS_1258],
+    },
+];
+
+pub(super) fn list_supported_encodings_impl(
+    language: Option<String>,
+) -> Vec<EncodingFamilyPayload> {
+    let localizer = locale::Localizer::for_request(language);
+
+    ENCODING_FAMILIES
+        .iter()
+        .map(|family| {
+            let label = localizer.text(family.label_key);
+            let encodings = family
+                .encodings
+                .iter()
+                .map(|encoding| SupportedEncodingPayload {
+                    name: encoding.name().to_string(),
+                    label: if family.key == "unicode" {
+                        encoding.name().to_string()
+                    } else {
+                        format!("{} ({})", label, encoding.name())
+                    },
+                    can_encode: encoding.output_encoding() == *encoding,
+                })
+                .collect();
+
+            EncodingFamilyPayload {
+                key: family.key.to_string(),
+                label,
+                encodings,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn supported_encodings_should_round_trip_through_labels_without_duplicates() {
+        let families = list_supported_encodings_impl(Some("en-US".to_string()));
+        let mut seen = HashSet::new();
+
+        for encoding in families.iter().flat_map(|family| family.encodings.iter()) {
+            assert!(seen.insert(encoding.name.clone()), "{}", encoding.name);
+            let resolved = encoding_rs::Encoding::for_label(encoding.name.as_bytes())
+                .expect("listed encoding should resolve");
+            assert_eq!(resolved.name(), encoding.name);
+        }
+
+        assert_eq!(seen.len(), 38);
+    }
+
+    #[test]
+    fn supported_encodings_should_localize_family_labels() {
+        let english = list_supported_encodings_impl(Some("en-US".to_string()));
+        let chinese = list_supported_encodings_impl(Some("zh-CN".to_string()));
+
+        assert_eq!(english[1].label, "Chinese Simplified");
+        assert_eq!(english[1].encodings[0].label, "Chinese Simplified (GBK)");
+        assert_eq!(chinese[1].label, "简体中文");
+        assert!(chinese
+            .iter()
+            .all(|family| !family.label.starts_with("encodingFamily.")));
+        assert_eq!(chinese[0].encodings[0].label, "UTF-8");
+        assert!(english[0].encodings[0].can_encode);
+        assert!(!english[0].encodings[1].can_encode);
+    }
+}
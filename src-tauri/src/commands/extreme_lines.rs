@@ -0,0 +1,217 @@
+// Report of the lines that stand out by a metric: the longest lines by char
+// count or by rendered width, or the lines with the most matches of a
+// pattern. Meant for finding the one huge minified line that makes a file
+// slow to render.
+
+use super::document::is_wide_char;
+use super::search::{escape_regex_literal, wildcard_to_regex_source};
+use super::*;
+use regex::{Regex, RegexBuilder};
+use ropey::RopeSlice;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+const EXTREME_LINES_MAX_TOP_N: usize = 1000;
+const EXTREME_LINE_PREVIEW_CHARS: usize = 200;
+
+enum LineMetric {
+    Chars,
+    // Tabs expand to the next stop and wide chars take two columns.
+    Width { tab_width: usize },
+    Matches(Regex),
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtremeLine {
+    // 1-based.
+    pub line: usize,
+    pub value: usize,
+    // The start of the line; the full text may be megabytes long.
+    pub preview: String,
+    pub preview_truncated: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtremeLinesPayload {
+    pub lines: Vec<ExtremeLine>,
+    pub document_version: u64,
+}
+
+fn build_match_regex(keyword: &str, mode: &str, case_sensitive: bool) -> Result<Regex, String> {
+    if keyword.is_empty() {
+        return Err("A pattern is required for the matches metric".to_string());
+    }
+
+    let source = match mode {
+        "literal" => escape_regex_literal(keyword),
+        "wildcard" => wildcard_to_regex_source(keyword),
+        "regex" => keyword.to_string(),
+        _ => return Err("Unsupported search mode".to_string()),
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+fn line_content(line: RopeSlice<'_>) -> RopeSlice<'_> {
+    let mut end = line.len_chars();
+    while end > 0 && matches!(line.char(end - 1), '\n' | '\r') {
+        end -= 1;
+    }
+    line.slice(..end)
+}
+
+fn measure_line(line: RopeSlice<'_>, metric: &LineMetric) -> usize {
+    match metric {
+        LineMetric::Chars => line.len_chars(),
+        LineMetric::Width { tab_width } => line.chars().fold(0, |columns, ch| {
+            columns
+                + match ch {
+                    '\t' => tab_width - columns % tab_width,
+                    ch if is_wide_char(ch) => 2,
+                    _ => 1,
+                }
+        }),
+        LineMetric::Matches(regex) => regex.find_iter(&line.to_string()).count(),
+    }
+}
+
+// Highest value first; ties keep document order. Lines without any match are
+// left out of the matches report. Only the best `top_n` lines are kept while
+// scanning: the heap's minimum is the weakest line still in the report.
+fn find_extreme_lines(rope: &Rope, metric: &LineMetric, top_n: usize) -> Vec<ExtremeLine> {
+    let mut ranked = BinaryHeap::with_capacity(top_n.saturating_add(1));
+    for (index, line) in rope.lines().enumerate() {
+        let value = measure_line(line_content(line), metric);
+        if value == 0 && matches!(metric, LineMetric::Matches(_)) {
+            continue;
+        }
+
+        // Larger values rank higher, then earlier lines.
+        ranked.push(Reverse((value, Reverse(index))));
+        if ranked.len() > top_n {
+            ranked.pop();
+        }
+    }
+
+    ranked
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((value, Reverse(index)))| {
+            let content = line_content(rope.line(index));
+            let preview_end = content.len_chars().min(EXTREME_LINE_PREVIEW_CHARS);
+            ExtremeLine {
+                line: index + 1,
+                value,
+                preview: content.slice(..preview_end).to_string(),
+                preview_truncated: preview_end < content.len_chars(),
+            }
+        })
+        .collect()
+}
+
+// `metric` is "chars", "width" or "matches"; the last one needs `keyword`.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn find_extreme_lines_impl(
+    state: State<'_, AppState>,
+    id: String,
+    metric: String,
+    top_n: usize,
+    keyword: Option<String>,
+    mode: Option<String>,
+    case_sensitive: Option<bool>,
+    tab_width: Option<u8>,
+) -> Result<ExtremeLinesPayload, String> {
+    let metric = match metric.as_str() {
+        "chars" => LineMetric::Chars,
+        "width" => LineMetric::Width {
+            tab_width: usize::from(tab_width.unwrap_or(DEFAULT_TAB_WIDTH).max(1)),
+        },
+        "matches" => LineMetric::Matches(build_match_regex(
+            keyword.as_deref().unwrap_or_default(),
+            mode.as_deref().unwrap_or("literal"),
+            case_sensitive.unwrap_or(false),
+        )?),
+        _ => return Err("Unsupported line metric".to_string()),
+    };
+    let (rope, document_version) = state
+        .documents
+        .get(&id)
+        .map(|doc| (doc.rope.clone(), doc.document_version))
+        .ok_or_else(|| "Document not found".to_string())?;
+    let top_n = top_n.clamp(1, EXTREME_LINES_MAX_TOP_N);
+
+    tauri::async_runtime::spawn_blocking(move || ExtremeLinesPayload {
+        lines: find_extreme_lines(&rope, &metric, top_n),
+        document_version,
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked_lines(text: &str, metric: &LineMetric, top_n: usize) -> Vec<(usize, usize)> {
+        find_extreme_lines(&Rope::from_str(text), metric, top_n)
+            .into_iter()
+            .map(|line| (line.line, line.value))
+            .collect()
+    }
+
+    #[test]
+    fn find_extreme_lines_should_rank_by_chars_and_width() {
+        let text = "abcd\r\n\tx\n中文中\nabcd\n";
+
+        assert_eq!(
+            ranked_lines(text, &LineMetric::Chars, 3),
+            vec![(1, 4), (4, 4), (3, 3)]
+        );
+        assert_eq!(
+            ranked_lines(text, &LineMetric::Width { tab_width: 4 }, 2),
+            vec![(3, 6), (2, 5)]
+        );
+    }
+
+    #[test]
+    fn find_extreme_lines_should_keep_earliest_ties_when_trimming_to_top_n() {
+        let text = "bb
+a
+cc
+dddd
+ee
+";
+
+        assert_eq!(
+            ranked_lines(text, &LineMetric::Chars, 3),
+            vec![(4, 4), (1, 2), (3, 2)]
+        );
+        assert_eq!(ranked_lines(text, &LineMetric::Chars, 1), vec![(4, 4)]);
+    }
+
+    #[test]
+    fn find_extreme_lines_should_count_matches_and_skip_lines_without_any() {
+        let regex = build_match_regex("a?c", "wildcard", false).expect("pattern");
+        let lines = find_extreme_lines(
+            &Rope::from_str("abc\nnone\nABC abc aXc\n"),
+            &LineMetric::Matches(regex),
+            10,
+        );
+
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| (line.line, line.value))
+                .collect::<Vec<_>>(),
+            vec![(3, 3), (1, 1)]
+        );
+        assert_eq!(lines[1].preview, "abc");
+        assert!(!lines[1].preview_truncated);
+        assert!(build_match_regex("", "literal", true).is_err());
+        assert!(build_match_regex("(", "regex", true).is_err());
+    }
+}
@@ -1,11 +1,16 @@
+use super::file_sniff::SniffedContent;
+use super::types::{FileDecodeStats, LineEndingCounts};
 use super::*;
-use crate::state::FileFingerprint;
+use crate::state::{FileFingerprint, SavePoint};
 use notify::{
     event::{ModifyKind, RenameMode},
     Event, EventKind, RecursiveMode, Watcher,
 };
+use regex::Regex;
+use ropey::RopeSlice;
 use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 
 #[derive(Clone, serde::Serialize)]
@@ -22,11 +27,13 @@ struct DiskFileSnapshot {
     line_count: usize,
     large_file_mode: bool,
     fingerprint: FileFingerprint,
+    decode_stats: FileDecodeStats,
+    raw_bytes: Option<Arc<[u8]>>,
 }
 
 const DOCUMENT_TEXT_SNAPSHOT_CHUNK_BYTES: usize = 64 * 1024;
 
-fn build_file_fingerprint(metadata: &std::fs::Metadata) -> FileFingerprint {
+pub(super) fn build_file_fingerprint(metadata: &std::fs::Metadata) -> FileFingerprint {
     let modified_unix_millis = metadata
         .modified()
         .ok()
@@ -52,8 +59,97 @@ pub(super) fn has_external_file_change_by_snapshot(
     saved_fingerprint != Some(current_fingerprint)
 }
 
-fn read_disk_file_snapshot(path: &PathBuf) -> Result<DiskFileSnapshot, String> {
-    let file = File::open(path).map_err(|e| e.to_string())?;
+#[cfg(windows)]
+fn windows_share_mode_flags(share_mode: FileShareMode) -> u32 {
+    const FILE_SHARE_READ: u32 = 0x0000_0001;
+    const FILE_SHARE_WRITE: u32 = 0x0000_0002;
+    const FILE_SHARE_DELETE: u32 = 0x0000_0004;
+
+    match share_mode {
+        FileShareMode::ReadWrite => FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+        FileShareMode::Read => FILE_SHARE_READ,
+        FileShareMode::Exclusive => 0,
+    }
+}
+
+pub(super) fn open_file_with_share_mode(
+    path: &Path,
+    share_mode: FileShareMode,
+    write: bool,
+) -> std::io::Result<File> {
+    let mut options = fs::OpenOptions::new();
+    if write {
+        options.write(true).create(true).truncate(true);
+    } else {
+        options.read(true);
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        options.share_mode(windows_share_mode_flags(share_mode));
+    }
+
+    #[cfg(not(windows))]
+    let _ = share_mode;
+
+    options.open(path)
+}
+
+fn is_file_lock_violation(error: &std::io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION / ERROR_LOCK_VIOLATION
+    cfg!(windows) && matches!(error.raw_os_error(), Some(32) | Some(33))
+}
+
+pub(super) fn describe_file_open_error(path: &Path, error: std::io::Error) -> String {
+    if is_file_lock_violation(&error) {
+        return format!(
+            "File is locked by another process: {}",
+            path.to_string_lossy()
+        );
+    }
+
+    error.to_string()
+}
+
+pub(super) fn resolve_file_share_mode(label: Option<&str>) -> Result<FileShareMode, String> {
+    match label.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) => FileShareMode::from_label(value)
+            .ok_or_else(|| format!("Unsupported file share mode: {}", value)),
+        None => Ok(config::load_config_impl()
+            .ok()
+            .and_then(|config| FileShareMode::from_label(&config.file_share_mode))
+            .unwrap_or_default()),
+    }
+}
+
+// Returns the encoding together with how it was settled: "bom", "heuristic"
+// for BOM-less UTF-16, or the detector's "high"/"low" confidence.
+pub(super) fn detect_mapped_encoding(
+    bytes: &[u8],
+    sniffed: SniffedContent,
+) -> (&'static Encoding, &'static str) {
+    if let Some((enc, _size)) = Encoding::for_bom(bytes) {
+        return (enc, "bom");
+    }
+    if let SniffedContent::Utf16(enc) = sniffed {
+        return (enc, "heuristic");
+    }
+
+    let sample = &bytes[..bytes.len().min(ENCODING_DETECT_SAMPLE_BYTES)];
+    let mut detector = EncodingDetector::new();
+    detector.feed(sample, true);
+    let (enc, confident) = detector.guess_assess(None, true);
+    (enc, if confident { "high" } else { "low" })
+}
+
+fn read_disk_file_snapshot(
+    path: &Path,
+    share_mode: FileShareMode,
+    allow_binary: bool,
+) -> Result<DiskFileSnapshot, String> {
+    let file = open_file_with_share_mode(path, share_mode, false)
+        .map_err(|error| describe_file_open_error(path, error))?;
     let metadata = file.metadata().map_err(|e| e.to_string())?;
     let size = metadata.len();
     let large_file_mode = size > LARGE_FILE_THRESHOLD_BYTES as u64;
@@ -61,23 +157,30 @@ fn read_disk_file_snapshot(path: &PathBuf) -> Result<DiskFileSnapshot, String> {
 
     let mmap = unsafe { Mmap::map(&file).map_err(|e| e.to_string())? };
 
-    let encoding = if let Some((enc, _size)) = Encoding::for_bom(&mmap) {
-        enc
-    } else {
-        let mut detector = EncodingDetector::new();
-        if size as usize > ENCODING_DETECT_SAMPLE_BYTES {
-            detector.feed(&mmap[..ENCODING_DETECT_SAMPLE_BYTES], true);
-        } else {
-            detector.feed(&mmap, true);
+    let sniffed = file_sniff::sniff_content(&mmap);
+    let detected_file_type = match sniffed {
+        SniffedContent::Binary(kind) if !allow_binary => {
+            return Err(format!(
+                "Not a text file ({}): {}",
+                kind,
+                path.to_string_lossy()
+            ));
         }
-        detector.guess(None, true)
+        SniffedContent::Binary(kind) => Some(kind.to_string()),
+        _ => None,
     };
 
-    let (cow, _, _malformed) = encoding.decode(&mmap);
-    let line_ending = detect_line_ending(&cow);
+    let (encoding, encoding_confidence) = detect_mapped_encoding(&mmap, sniffed);
+    let has_bom = encoding_confidence == "bom";
+
+    let (cow, _, malformed) = encoding.decode(&mmap);
+    let line_ending_counts = count_line_endings(&cow);
+    let line_ending = detect_line_ending(line_ending_counts);
     let normalized_content = text_utils::normalize_to_lf(&cow);
     let rope = Rope::from_str(&normalized_content);
     let line_count = rope.len_lines();
+    let raw_bytes = raw_bytes::should_preserve_raw_bytes(encoding, malformed)
+        .then(|| Arc::<[u8]>::from(&mmap[..]));
 
     Ok(DiskFileSnapshot {
         rope,
@@ -86,6 +189,15 @@ fn read_disk_file_snapshot(path: &PathBuf) -> Result<DiskFileSnapshot, String> {
         line_count,
         large_file_mode,
         fingerprint,
+        decode_stats: FileDecodeStats {
+            encoding_confidence: encoding_confidence.to_string(),
+            line_ending_counts,
+            has_bom,
+            had_malformed_sequences: malformed,
+            raw_bytes_preserved: raw_bytes.is_some(),
+            detected_file_type,
+        },
+        raw_bytes,
     })
 }
 
@@ -100,26 +212,24 @@ pub struct OpenFileBatchResultItem {
     pub error: Option<String>,
 }
 
-fn detect_line_ending(text: &str) -> LineEnding {
+pub(super) fn count_line_endings(text: &str) -> LineEndingCounts {
     let bytes = text.as_bytes();
-    let mut crlf_count = 0usize;
-    let mut lf_count = 0usize;
-    let mut cr_count = 0usize;
+    let mut counts = LineEndingCounts::default();
 
     let mut index = 0usize;
     while index < bytes.len() {
         match bytes[index] {
             b'\r' => {
                 if index + 1 < bytes.len() && bytes[index + 1] == b'\n' {
-                    crlf_count += 1;
+                    counts.crlf += 1;
                     index += 2;
                 } else {
-                    cr_count += 1;
+                    counts.cr += 1;
                     index += 1;
                 }
             }
             b'\n' => {
-                lf_count += 1;
+                counts.lf += 1;
                 index += 1;
             }
             _ => {
@@ -128,6 +238,16 @@ fn detect_line_ending(text: &str) -> LineEnding {
         }
     }
 
+    counts
+}
+
+pub(super) fn detect_line_ending(counts: LineEndingCounts) -> LineEnding {
+    let LineEndingCounts {
+        crlf: crlf_count,
+        lf: lf_count,
+        cr: cr_count,
+    } = counts;
+
     if crlf_count >= lf_count && crlf_count >= cr_count && crlf_count > 0 {
         LineEnding::CrLf
     } else if lf_count >= cr_count && lf_count > 0 {
@@ -139,11 +259,11 @@ fn detect_line_ending(text: &str) -> LineEnding {
     }
 }
 
-fn build_persist_content(doc: &Document) -> String {
-    let utf8_content: String = doc.rope.chunks().collect();
+pub(super) fn build_persist_text(rope: &Rope, line_ending: LineEnding) -> String {
+    let utf8_content: String = rope.chunks().collect();
     let normalized = text_utils::normalize_to_lf(&utf8_content);
 
-    match doc.line_ending {
+    match line_ending {
         LineEnding::CrLf => normalized.replace('\n', "\r\n"),
         LineEnding::Lf => normalized,
         LineEnding::Cr => normalized.replace('\n', "\r"),
@@ -204,6 +324,10 @@ fn is_latin_like_word_char(ch: char) -> bool {
 }
 
 fn count_word_stats(rope: &Rope) -> WordCountInfo {
+    count_word_stats_in_slice(rope.slice(..))
+}
+
+fn count_word_stats_in_slice(text: RopeSlice<'_>) -> WordCountInfo {
     let mut word_count = 0usize;
     let mut character_count = 0usize;
     let mut character_count_no_spaces = 0usize;
@@ -212,7 +336,7 @@ fn count_word_stats(rope: &Rope) -> WordCountInfo {
     let mut in_latin_word = false;
     let mut in_paragraph = false;
 
-    for chunk in rope.chunks() {
+    for chunk in text.chunks() {
         for ch in chunk.chars() {
             character_count = character_count.saturating_add(1);
 
@@ -261,7 +385,7 @@ fn count_word_stats(rope: &Rope) -> WordCountInfo {
         word_count,
         character_count,
         character_count_no_spaces,
-        line_count: rope.len_lines(),
+        line_count: text.len_lines(),
         paragraph_count,
     }
 }
@@ -393,6 +517,7 @@ fn register_disk_snapshot_as_document(
     path: String,
     path_buf: PathBuf,
     snapshot: DiskFileSnapshot,
+    share_mode: FileShareMode,
 ) -> FileInfo {
     let size_bytes =
         measure_document_size_bytes(&snapshot.rope, snapshot.encoding, snapshot.line_ending);
@@ -400,29 +525,29 @@ fn register_disk_snapshot_as_document(
     let line_ending_label = snapshot.line_ending.label().to_string();
     let line_count = snapshot.line_count;
     let large_file_mode = snapshot.large_file_mode;
+    let snapshot_decode_stats = snapshot.decode_stats;
+    let preview_mode =
+        preview_mode::should_open_in_preview_mode(&path_buf, snapshot.fingerprint.size_bytes);
+    let suggested_syntax =
+        syntax_suggestion::suggest_syntax_for_document(&path_buf, &snapshot.rope);
 
     let id = Uuid::new_v4().to_string();
 
     let doc = Document {
-        rope: snapshot.rope.clone(),
-        saved_rope: snapshot.rope,
-        encoding: snapshot.encoding,
-        saved_encoding: encoding_name.clone(),
-        line_ending: snapshot.line_ending,
-        saved_line_ending: snapshot.line_ending,
-        path: Some(path_buf.clone()),
-        syntax_override: None,
-        document_version: 0,
-        saved_document_version: 0,
-        next_edit_operation_id: 1,
-        undo_stack: Vec::new(),
-        redo_stack: Vec::new(),
-        saved_undo_depth: 0,
-        saved_undo_operation_id: None,
         saved_file_fingerprint: Some(snapshot.fingerprint),
+        share_mode,
+        raw_bytes: snapshot.raw_bytes,
+        preview_mode,
+        ..Document::new(
+            snapshot.rope,
+            snapshot.encoding,
+            snapshot.line_ending,
+            Some(path_buf.clone()),
+        )
     };
 
     state.documents.insert(id.clone(), doc);
+    state.mark_document_watches_stale();
 
     FileInfo {
         id,
@@ -438,12 +563,20 @@ fn register_disk_snapshot_as_document(
         size_bytes,
         large_file_mode,
         syntax_override: None,
+        suggested_syntax,
+        decode_stats: Some(snapshot_decode_stats),
+        preview_mode,
+        streaming: false,
+        hex_view: false,
     }
 }
 
 async fn open_file_by_path_async(
     state: &State<'_, AppState>,
     path: String,
+    share_mode: FileShareMode,
+    allow_binary: bool,
+    hex_view: bool,
 ) -> Result<FileInfo, String> {
     let path_buf = PathBuf::from(&path);
 
@@ -470,35 +603,87 @@ async fn open_file_by_path_async(
             ),
             large_file_mode: existing.rope.len_bytes() > LARGE_FILE_THRESHOLD_BYTES,
             syntax_override: existing.syntax_override.clone(),
+            suggested_syntax: syntax_suggestion::suggest_syntax_for_document(
+                &path_buf,
+                &existing.rope,
+            ),
+            decode_stats: None,
+            preview_mode: existing.preview_mode,
+            streaming: false,
+            hex_view: false,
         });
     }
+    if let Some(existing) = streaming_document::find_streaming_document_by_path(&path_buf) {
+        return Ok(existing);
+    }
 
     let path_for_io = path_buf.clone();
-    let snapshot =
-        tauri::async_runtime::spawn_blocking(move || read_disk_file_snapshot(&path_for_io))
-            .await
-            .map_err(|error| error.to_string())??;
+    let streaming = tauri::async_runtime::spawn_blocking(move || {
+        streaming_document::map_streaming_document(&path_for_io, share_mode)
+    })
+    .await
+    .map_err(|error| error.to_string())??;
+    if let Some(document) = streaming {
+        return Ok(streaming_document::register_streaming_document(document));
+    }
+
+    // Binary files open in the hex view only when the caller can show one;
+    // otherwise the snapshot read below reports them as not text, as before.
+    if hex_view && !allow_binary {
+        if let Some(existing) = hex_document::find_hex_document_by_path(&path_buf) {
+            return Ok(existing);
+        }
+
+        let path_for_io = path_buf.clone();
+        let hex = tauri::async_runtime::spawn_blocking(move || {
+            hex_document::map_hex_document(&path_for_io, share_mode)
+        })
+        .await
+        .map_err(|error| error.to_string())??;
+        if let Some(document) = hex {
+            return Ok(hex_document::register_hex_document(document));
+        }
+    }
+
+    let path_for_io = path_buf.clone();
+    let snapshot = tauri::async_runtime::spawn_blocking(move || {
+        read_disk_file_snapshot(&path_for_io, share_mode, allow_binary)
+    })
+    .await
+    .map_err(|error| error.to_string())??;
 
     Ok(register_disk_snapshot_as_document(
-        state, path, path_buf, snapshot,
+        state, path, path_buf, snapshot, share_mode,
     ))
 }
 
 pub(super) async fn open_file_impl(
     state: State<'_, AppState>,
     path: String,
+    share_mode: Option<String>,
+    allow_binary: Option<bool>,
+    hex_view: Option<bool>,
 ) -> Result<FileInfo, String> {
-    open_file_by_path_async(&state, path).await
+    let share_mode = resolve_file_share_mode(share_mode.as_deref())?;
+    open_file_by_path_async(
+        &state,
+        path,
+        share_mode,
+        allow_binary.unwrap_or(false),
+        hex_view.unwrap_or(false),
+    )
+    .await
 }
 
 pub(super) async fn open_files_impl(
     state: State<'_, AppState>,
     paths: Vec<String>,
 ) -> Vec<OpenFileBatchResultItem> {
+    let share_mode = resolve_file_share_mode(None).unwrap_or_default();
     let mut results = Vec::with_capacity(paths.len());
     for path in paths {
         let path_for_result = path.clone();
-        match open_file_by_path_async(&state, path).await {
+        match open_file_by_path_async(&state, path, share_mode, false, false).await {
             Ok(file_info) => results.push(OpenFileBatchResultItem {
                 path: path_for_result,
                 success: true,
@@ -572,6 +757,10 @@ pub(super) fn get_visible_lines_impl(
         let end_char = rope.line_to_char(end);
 
         Ok(rope.slice(start_char..end_char).to_string())
+    } else if let Some(lines) =
+        streaming_document::get_streaming_visible_lines(&id, start_line, end_line)
+    {
+        lines
     } else {
         Err("Document not found".to_string())
     }
@@ -690,17 +879,48 @@ pub(super) async fn render_markdown_preview_impl(
     .map_err(|error| error.to_string())?
 }
 
-pub(super) fn close_file_impl(state: State<'_, AppState>, id: String) {
-    state.documents.remove(&id);
+// `reading_line` is the top visible line, kept for large files so reading
+// can resume there next time.
+pub(super) fn close_file_impl(
+    state: State<'_, AppState>,
+    id: String,
+    reading_line: Option<usize>,
+    cursor_line: Option<usize>,
+    cursor_column: Option<usize>,
+) {
+    if let Some(line) = reading_line {
+        if let Err(error) = reading_positions::record_reading_position_impl(&state, &id, line) {
+            eprintln!("failed to record reading position: {error}");
+        }
+    }
+    if let Some((_, doc)) = state.documents.remove(&id) {
+        recently_closed::remember_closed_document(&doc, cursor_line, cursor_column);
+        state.mark_document_watches_stale();
+    }
+    streaming_document::close_streaming_document(&id);
+    streams::close_stream_for_document(&id);
+    hex_document::close_hex_document(&id);
+    document_views::close_document_views_for(&state, &id);
+    clear_document_search_caches(&id);
+    recovery::discard_document_recovery_backup(&id);
 }
 
 pub(super) fn close_files_impl(state: State<'_, AppState>, ids: Vec<String>) {
     for id in ids {
-        state.documents.remove(&id);
+        if let Some((_, doc)) = state.documents.remove(&id) {
+            recently_closed::remember_closed_document(&doc, None, None);
+            state.mark_document_watches_stale();
+        }
+        streaming_document::close_streaming_document(&id);
+        streams::close_stream_for_document(&id);
+        hex_document::close_hex_document(&id);
+        document_views::close_document_views_for(&state, &id);
+        clear_document_search_caches(&id);
+        recovery::discard_document_recovery_backup(&id);
     }
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveFileBatchResultItem {
     pub id: String,
@@ -716,23 +936,127 @@ struct SaveSnapshot {
     document_version: u64,
     encoding_name: String,
     line_ending: LineEnding,
-    saved_undo_depth: usize,
-    saved_undo_operation_id: Option<u64>,
+    save_point: SavePoint,
+    share_mode: FileShareMode,
+    raw_bytes_preserved: bool,
+}
+
+// What encoding a document for save reads, detached from the document so the
+// encoding itself can run after its guard is released. Capturing is cheap:
+// the rope and raw bytes are shared, not copied.
+pub(super) struct DocumentEncodeSource {
+    rope: Rope,
+    encoding: &'static Encoding,
+    line_ending: LineEnding,
+    preserved_raw_bytes: Option<Arc<[u8]>>,
+}
+
+impl DocumentEncodeSource {
+    pub(super) fn capture(doc: &Document) -> Self {
+        // Raw bytes only line up with the current text while no encoding or
+        // line-ending conversion is pending.
+        let preserved_raw_bytes = doc.raw_bytes.clone().filter(|_| {
+            doc.encoding.name() == doc.saved_encoding && doc.line_ending == doc.saved_line_ending
+        });
+
+        Self {
+            rope: doc.rope.clone(),
+            encoding: doc.encoding,
+            line_ending: doc.line_ending,
+            preserved_raw_bytes,
+        }
+    }
+
+    // Like `capture`, but encodes to `encoding` and `line_ending` instead of
+    // the document's own; raw bytes only survive when neither changes.
+    pub(super) fn capture_converted(
+        doc: &Document,
+        encoding: &'static Encoding,
+        line_ending: LineEnding,
+    ) -> Self {
+        let mut source = Self::capture(doc);
+        if encoding != doc.encoding || line_ending != doc.line_ending {
+            source.encoding = encoding;
+            source.line_ending = line_ending;
+            source.preserved_raw_bytes = None;
+        }
+        source
+    }
+
+    // Returns the bytes to persist and whether they were built from the
+    // document's preserved raw bytes.
+    pub(super) fn encode(&self) -> (Vec<u8>, bool) {
+        if let Some(raw) = self.preserved_raw_bytes.as_ref() {
+            let bytes =
+                raw_bytes::encode_with_raw_bytes(raw, self.encoding, &self.rope, self.line_ending);
+            return (bytes, true);
+        }
+
+        let persist_content = build_persist_text(&self.rope, self.line_ending);
+        let (bytes_cow, _, _malformed) = self.encoding.encode(&persist_content);
+        (bytes_cow.into_owned(), false)
+    }
+}
+
+pub(super) fn encode_document_for_save(doc: &Document) -> (Vec<u8>, bool) {
+    DocumentEncodeSource::capture(doc).encode()
 }
 
 fn snapshot_for_save(doc: &Document, path: PathBuf) -> SaveSnapshot {
-    let persist_content = build_persist_content(doc);
-    let (bytes_cow, _, _malformed) = doc.encoding.encode(&persist_content);
+    let (bytes, raw_bytes_preserved) = encode_document_for_save(doc);
+
     SaveSnapshot {
         path,
-        bytes: bytes_cow.into_owned(),
+        bytes,
         rope: doc.rope.clone(),
         document_version: doc.document_version,
         encoding_name: doc.encoding.name().to_string(),
         line_ending: doc.line_ending,
-        saved_undo_depth: doc.undo_stack.len(),
-        saved_undo_operation_id: doc.undo_stack.last().map(|op| op.operation_id),
+        save_point: doc.current_save_point(),
+        share_mode: doc.share_mode,
+        raw_bytes_preserved,
+    }
+}
+
+// Writes a temp file next to `path` and renames it over the target, so a
+// failed write never leaves a half-written file. With `backup_suffix` the old
+// content is copied to `<name><suffix>` first; returns that backup path.
+pub(super) fn write_file_atomically(
+    path: &Path,
+    bytes: &[u8],
+    backup_suffix: Option<&str>,
+) -> Result<Option<PathBuf>, String> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| "Invalid file path".to_string())?
+        .to_string_lossy()
+        .to_string();
+    let temp_path = parent.join(format!(".{}.{}.tmp", file_name, Uuid::new_v4()));
+
+    fs::write(&temp_path, bytes).map_err(|e| e.to_string())?;
+    if let Ok(metadata) = fs::metadata(path) {
+        let _ = fs::set_permissions(&temp_path, metadata.permissions());
+    }
+
+    let backup_path = if let Some(backup_suffix) = backup_suffix {
+        let backup_path = parent.join(format!("{}{}", file_name, backup_suffix));
+        if let Err(error) = fs::copy(path, &backup_path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(format!("Failed to write backup: {error}"));
+        }
+        Some(backup_path)
+    } else {
+        None
+    };
+
+    if let Err(error) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(error.to_string());
     }
+    Ok(backup_path)
 }
 
 async fn write_snapshot_to_disk(
@@ -740,9 +1064,11 @@ async fn write_snapshot_to_disk(
 ) -> Result<(SaveSnapshot, Option<FileFingerprint>), String> {
     let path_for_io = snapshot.path.clone();
     let bytes_for_io = snapshot.bytes.clone();
+    let share_mode = snapshot.share_mode;
     let fingerprint =
         tauri::async_runtime::spawn_blocking(move || -> Result<Option<FileFingerprint>, String> {
-            let mut file = File::create(&path_for_io).map_err(|e| e.to_string())?;
+            let mut file = open_file_with_share_mode(&path_for_io, share_mode, true)
+                .map_err(|error| describe_file_open_error(&path_for_io, error))?;
             use std::io::Write;
             file.write_all(&bytes_for_io).map_err(|e| e.to_string())?;
             Ok(fs::metadata(&path_for_io)
@@ -763,9 +1089,11 @@ fn apply_save_snapshot_to_doc(
     doc.saved_document_version = snapshot.document_version;
     doc.saved_encoding = snapshot.encoding_name;
     doc.saved_line_ending = snapshot.line_ending;
-    doc.saved_undo_depth = snapshot.saved_undo_depth;
-    doc.saved_undo_operation_id = snapshot.saved_undo_operation_id;
+    doc.mark_save_point(snapshot.save_point);
     doc.saved_file_fingerprint = fingerprint;
+    doc.raw_bytes = snapshot
+        .raw_bytes_preserved
+        .then(|| Arc::<[u8]>::from(snapshot.bytes));
 }
 
 async fn save_file_by_id_async(state: &State<'_, AppState>, id: &str) -> Result<(), String> {
@@ -785,6 +1113,11 @@ async fn save_file_by_id_async(state: &State<'_, AppState>, id: &str) -> Result<
 
     if let Some(mut doc) = state.documents.get_mut(id) {
         apply_save_snapshot_to_doc(&mut doc, snapshot, fingerprint);
+        let saved_clean = !doc.has_unsaved_text_changes();
+        drop(doc);
+        if saved_clean {
+            recovery::discard_document_recovery_backup(id);
+        }
     }
     Ok(())
 }
@@ -795,7 +1128,12 @@ pub(super) async fn save_files_impl(
 ) -> Vec<SaveFileBatchResultItem> {
     let mut results = Vec::with_capacity(ids.len());
     for id in ids {
-        match save_file_by_id_async(&state, id.as_str()).await {
+        let saved = if hex_document::is_hex_document(&id) {
+            hex_document::save_hex_document_impl(id.clone()).await
+        } else {
+            save_file_by_id_async(&state, id.as_str()).await
+        };
+        match saved {
             Ok(()) => results.push(SaveFileBatchResultItem {
                 id,
                 success: true,
@@ -812,6 +1150,9 @@ pub(super) async fn save_files_impl(
 }
 
 pub(super) async fn save_file_impl(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    if hex_document::is_hex_document(&id) {
+        return hex_document::save_hex_document_impl(id).await;
+    }
     save_file_by_id_async(&state, id.as_str()).await
 }
 
@@ -835,12 +1176,161 @@ pub(super) async fn save_file_as_impl(
     if let Some(mut doc) = state.documents.get_mut(&id) {
         doc.path = Some(path_buf);
         apply_save_snapshot_to_doc(&mut doc, snapshot, fingerprint);
+        let saved_clean = !doc.has_unsaved_text_changes();
+        drop(doc);
+        state.mark_document_watches_stale();
+        if saved_clean {
+            recovery::discard_document_recovery_backup(&id);
+        }
         Ok(())
     } else {
         Err("Document not found".to_string())
     }
 }
 
+pub(super) async fn save_copy_as_impl(
+    state: State<'_, AppState>,
+    id: String,
+    path: String,
+    encoding: Option<String>,
+    line_ending: Option<String>,
+) -> Result<(), String> {
+    let path_buf = PathBuf::from(&path);
+
+    let source = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+
+        let target_encoding = match encoding.as_deref() {
+            Some(label) => resolve_encoding_label(label)?,
+            None => doc.encoding,
+        };
+        let target_line_ending = match line_ending.as_deref() {
+            Some(label) => LineEnding::from_label(label)
+                .ok_or_else(|| format!("Unsupported line ending: {}", label))?,
+            None => doc.line_ending,
+        };
+
+        DocumentEncodeSource::capture_converted(&doc, target_encoding, target_line_ending)
+    };
+
+    // Unlike save_file_as, the document keeps its path, encoding and saved
+    // state. The copy may replace an existing file, so it is written
+    // atomically.
+    tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
+        let (bytes, _) = source.encode();
+        write_file_atomically(&path_buf, &bytes, None).map(|_| ())
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractSelectionResultPayload {
+    pub file_info: FileInfo,
+    pub source_changed: bool,
+    pub source_line_count: usize,
+    pub source_document_version: u64,
+}
+
+// `{name}` and `{path}` in the reference template stand for the new file.
+fn expand_extract_reference(template: &str, path: &Path) -> String {
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    template
+        .replace("{name}", &name)
+        .replace("{path}", &path.to_string_lossy())
+}
+
+// Writes the selection to a new file in the source's encoding and line ending,
+// opens it, and then optionally swaps the selection for a reference as one
+// undo step. The source is only touched once the new file is open.
+pub(super) async fn extract_selection_to_file_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_char: usize,
+    end_char: usize,
+    path: String,
+    remove_from_source: bool,
+    reference: Option<String>,
+) -> Result<ExtractSelectionResultPayload, String> {
+    let path_buf = PathBuf::from(&path);
+    if path_buf.exists() {
+        return Err("File already exists".to_string());
+    }
+
+    let (selected_text, bytes) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        if start_char >= end_char || end_char > doc.rope.len_chars() {
+            return Err("Invalid selection range".to_string());
+        }
+
+        let selected_text = doc.rope.slice(start_char..end_char).to_string();
+        let persist_content = build_persist_text(&Rope::from_str(&selected_text), doc.line_ending);
+        let (bytes_cow, _, _malformed) = doc.encoding.encode(&persist_content);
+        (selected_text, bytes_cow.into_owned())
+    };
+
+    let path_for_io = path_buf.clone();
+    tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path_for_io)
+            .map_err(|error| describe_file_open_error(&path_for_io, error))?;
+        use std::io::Write;
+        file.write_all(&bytes).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|error| error.to_string())??;
+
+    let share_mode = resolve_file_share_mode(None).unwrap_or_default();
+    let file_info = open_file_by_path_async(&state, path, share_mode, false, false).await?;
+
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let current_text = doc
+        .rope
+        .get_slice(start_char..end_char)
+        .map(|slice| slice.to_string());
+    if !remove_from_source || current_text.as_deref() != Some(selected_text.as_str()) {
+        // Nothing to replace, or the source was edited while the file was written.
+        return Ok(ExtractSelectionResultPayload {
+            file_info,
+            source_changed: false,
+            source_line_count: doc.rope.len_lines(),
+            source_document_version: doc.document_version,
+        });
+    }
+
+    let replacement = reference
+        .map(|template| expand_extract_reference(&template, &path_buf))
+        .unwrap_or_default();
+    let mut engine = doc.engine();
+    engine.replace(start_char, selected_text, replacement)?;
+    let change = engine.finish();
+    drop(doc);
+    clear_outdated_document_search_caches(&id, change.document_version);
+
+    Ok(ExtractSelectionResultPayload {
+        file_info,
+        source_changed: change.changed,
+        source_line_count: change.line_count,
+        source_document_version: change.document_version,
+    })
+}
+
 fn normalize_encoding_label(label: &str) -> &str {
     if label.eq_ignore_ascii_case("ansi") {
         return "windows-1252";
@@ -849,23 +1339,120 @@ fn normalize_encoding_label(label: &str) -> &str {
     label
 }
 
+pub(super) fn resolve_encoding_label(label: &str) -> Result<&'static Encoding, String> {
+    let normalized_label = normalize_encoding_label(label.trim());
+    Encoding::for_label(normalized_label.as_bytes())
+        .ok_or_else(|| format!("Unsupported encoding: {}", label))
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodingPreviewItem {
+    pub requested_label: String,
+    pub encoding: Option<String>,
+    pub lines: Vec<String>,
+    pub had_malformed_sequences: bool,
+    pub error: Option<String>,
+}
+
+// The sample usually ends mid-file, so decoding runs with `last = false` to
+// keep a split multi-byte sequence at the end from counting as malformed.
+fn decode_encoding_preview(
+    sample: &[u8],
+    label: &str,
+    max_lines: usize,
+    truncated: bool,
+) -> EncodingPreviewItem {
+    let encoding = match resolve_encoding_label(label) {
+        Ok(encoding) => encoding,
+        Err(error) => {
+            return EncodingPreviewItem {
+                requested_label: label.to_string(),
+                encoding: None,
+                lines: Vec::new(),
+                had_malformed_sequences: false,
+                error: Some(error),
+            };
+        }
+    };
+
+    let mut decoder = encoding.new_decoder_with_bom_removal();
+    let capacity = decoder
+        .max_utf8_buffer_length(sample.len())
+        .unwrap_or(sample.len().saturating_mul(3));
+    let mut decoded = String::with_capacity(capacity);
+    let (_, _, had_malformed_sequences) =
+        decoder.decode_to_string(sample, &mut decoded, !truncated);
+
+    let normalized = text_utils::normalize_to_lf(&decoded);
+    let mut lines: Vec<String> = normalized.lines().map(str::to_string).collect();
+    // A cut-off final line would be misleading next to complete ones.
+    if truncated && lines.len() > 1 {
+        lines.pop();
+    }
+    lines.truncate(max_lines);
+
+    EncodingPreviewItem {
+        requested_label: label.to_string(),
+        encoding: Some(encoding.name().to_string()),
+        lines,
+        had_malformed_sequences,
+        error: None,
+    }
+}
+
+pub(super) async fn preview_file_encodings_impl(
+    path: String,
+    encodings: Vec<String>,
+    sample_bytes: Option<usize>,
+    max_lines: Option<usize>,
+) -> Result<Vec<EncodingPreviewItem>, String> {
+    let sample_limit = sample_bytes
+        .unwrap_or(DEFAULT_ENCODING_PREVIEW_SAMPLE_BYTES)
+        .clamp(1, ENCODING_DETECT_SAMPLE_BYTES);
+    let max_lines = max_lines
+        .unwrap_or(DEFAULT_ENCODING_PREVIEW_MAX_LINES)
+        .max(1);
+    let share_mode = resolve_file_share_mode(None)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        use std::io::Read;
+
+        let path = PathBuf::from(path);
+        let file = open_file_with_share_mode(&path, share_mode, false)
+            .map_err(|error| describe_file_open_error(&path, error))?;
+        let file_size = file.metadata().map_err(|e| e.to_string())?.len();
+
+        let mut sample = Vec::with_capacity(sample_limit);
+        file.take(sample_limit as u64)
+            .read_to_end(&mut sample)
+            .map_err(|e| e.to_string())?;
+        let truncated = (sample.len() as u64) < file_size;
+
+        Ok(encodings
+            .iter()
+            .map(|label| decode_encoding_preview(&sample, label, max_lines, truncated))
+            .collect())
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
 pub(super) fn convert_encoding_impl(
     state: State<'_, AppState>,
     id: String,
     new_encoding: String,
 ) -> Result<(), String> {
     if let Some(mut doc) = state.documents.get_mut(&id) {
-        let normalized_label = normalize_encoding_label(new_encoding.trim());
-        let label = normalized_label.as_bytes();
-        let encoding = Encoding::for_label(label)
-            .ok_or_else(|| format!("Unsupported encoding: {}", new_encoding))?;
-
-        if doc.encoding.name() == encoding.name() {
-            return Ok(());
+        let encoding = resolve_encoding_label(&new_encoding)?;
+
+        let mut engine = doc.engine();
+        engine.set_encoding(encoding);
+        let change = engine.finish();
+        drop(doc);
+        if change.changed {
+            clear_outdated_document_search_caches(&id, change.document_version);
         }
-
-        doc.encoding = encoding;
-        doc.document_version = doc.document_version.saturating_add(1);
         Ok(())
     } else {
         Err("Document not found".to_string())
@@ -881,18 +1468,100 @@ pub(super) fn set_line_ending_impl(
         let line_ending = LineEnding::from_label(&new_line_ending)
             .ok_or_else(|| format!("Unsupported line ending: {}", new_line_ending))?;
 
-        if doc.line_ending == line_ending {
-            return Ok(());
+        let mut engine = doc.engine();
+        engine.set_line_ending(line_ending);
+        let change = engine.finish();
+        drop(doc);
+        if change.changed {
+            clear_outdated_document_search_caches(&id, change.document_version);
         }
-
-        doc.line_ending = line_ending;
-        doc.document_version = doc.document_version.saturating_add(1);
         Ok(())
     } else {
         Err("Document not found".to_string())
     }
 }
 
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineEndingConversionResultPayload {
+    // Line breaks written differently on the next save.
+    pub changed_lines: usize,
+    pub line_count: usize,
+    pub document_version: u64,
+}
+
+// Byte span from the first to the last CR in `text`, widened to cover the LF
+// of a trailing CRLF so the pair is normalized together.
+fn stray_line_break_span(text: &str) -> Option<(usize, usize)> {
+    let start = text.find('\r')?;
+    let last = text.rfind('\r')?;
+    let end = if text.as_bytes().get(last + 1) == Some(&b'\n') {
+        last + 2
+    } else {
+        last + 1
+    };
+    Some((start, end))
+}
+
+// Every break, stray CR or CRLF included, is written with the save ending, so
+// switching it changes either all breaks on disk or none.
+fn count_changed_persisted_line_breaks(
+    text: &str,
+    current: LineEnding,
+    target: LineEnding,
+) -> usize {
+    if current == target {
+        return 0;
+    }
+
+    let counts = count_line_endings(text);
+    counts.lf + counts.crlf + counts.cr
+}
+
+// The buffer stores LF breaks and the target ending is applied on save, so
+// converting means folding any CR/CRLF breaks left by pasted or inserted text
+// into LF (one undo step) and then switching the save ending.
+pub(super) fn convert_line_endings_in_buffer_impl(
+    state: State<'_, AppState>,
+    id: String,
+    target: String,
+) -> Result<LineEndingConversionResultPayload, String> {
+    let line_ending = LineEnding::from_label(&target)
+        .ok_or_else(|| format!("Unsupported line ending: {}", target))?;
+
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        let text = doc.rope.to_string();
+        let changed_lines =
+            count_changed_persisted_line_breaks(&text, doc.line_ending, line_ending);
+        let mut engine = doc.engine();
+
+        if let Some((start_byte, end_byte)) = stray_line_break_span(&text) {
+            let stray = &text[start_byte..end_byte];
+            let start_char = engine.document().rope.byte_to_char(start_byte);
+            engine.replace(
+                start_char,
+                stray.to_string(),
+                text_utils::normalize_to_lf(stray),
+            )?;
+        }
+
+        engine.set_line_ending(line_ending);
+        let change = engine.finish();
+        drop(doc);
+        if change.changed {
+            clear_outdated_document_search_caches(&id, change.document_version);
+        }
+
+        Ok(LineEndingConversionResultPayload {
+            changed_lines,
+            line_count: change.line_count,
+            document_version: change.document_version,
+        })
+    } else {
+        Err("Document not found".to_string())
+    }
+}
+
 pub(super) fn set_document_syntax_impl(
     state: State<'_, AppState>,
     id: String,
@@ -916,22 +1585,8 @@ pub(super) fn new_file_impl(
     let line_ending = resolve_new_file_line_ending(new_file_line_ending.as_deref());
 
     let doc = Document {
-        rope: Rope::new(),
-        saved_rope: Rope::new(),
-        encoding,
-        saved_encoding: encoding.name().to_string(),
-        line_ending,
-        saved_line_ending: line_ending,
-        path: None,
-        syntax_override: None,
-        document_version: 0,
-        saved_document_version: 0,
-        next_edit_operation_id: 1,
-        undo_stack: Vec::new(),
-        redo_stack: Vec::new(),
-        saved_undo_depth: 0,
-        saved_undo_operation_id: None,
-        saved_file_fingerprint: None,
+        share_mode: resolve_file_share_mode(None)?,
+        ..Document::new(Rope::new(), encoding, line_ending, None)
     };
 
     state.documents.insert(id.clone(), doc);
@@ -946,6 +1601,11 @@ pub(super) fn new_file_impl(
         size_bytes: 0,
         large_file_mode: false,
         syntax_override: None,
+        suggested_syntax: None,
+        decode_stats: None,
+        preview_mode: false,
+        streaming: false,
+        hex_view: false,
     })
 }
 
@@ -987,19 +1647,40 @@ pub(super) fn acknowledge_external_file_change_impl(
     }
 }
 
+pub(super) fn is_file_locked_impl(path: String) -> Result<bool, String> {
+    let path_buf = PathBuf::from(&path);
+    if !path_buf.is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+
+    // Probing with an exclusive handle fails only while another process holds
+    // the file open, which is exactly what users editing live logs care about.
+    match open_file_with_share_mode(&path_buf, FileShareMode::Exclusive, false) {
+        Ok(_) => Ok(false),
+        Err(error) if is_file_lock_violation(&error) => Ok(true),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
 pub(super) fn reload_file_from_disk_impl(
     state: State<'_, AppState>,
     id: String,
 ) -> Result<FileInfo, String> {
-    let path = if let Some(doc) = state.documents.get(&id) {
-        doc.path
-            .clone()
-            .ok_or_else(|| "No path associated with this file".to_string())?
+    let (path, share_mode) = if let Some(doc) = state.documents.get(&id) {
+        (
+            doc.path
+                .clone()
+                .ok_or_else(|| "No path associated with this file".to_string())?,
+            doc.share_mode,
+        )
     } else {
         return Err("Document not found".to_string());
     };
 
-    let snapshot = read_disk_file_snapshot(&path)?;
+    // The document is already open, so a binary result should not block reload.
+    let snapshot = read_disk_file_snapshot(&path, share_mode, true)?;
+    // The version restarts at zero, so version-keyed entries could collide.
+    clear_document_search_caches(&id);
 
     if let Some(mut doc) = state.documents.get_mut(&id) {
         doc.rope = snapshot.rope.clone();
@@ -1010,12 +1691,16 @@ pub(super) fn reload_file_from_disk_impl(
         doc.saved_line_ending = snapshot.line_ending;
         doc.document_version = 0;
         doc.saved_document_version = 0;
+        doc.text_snapshot.clear();
         doc.next_edit_operation_id = 1;
         doc.undo_stack.clear();
         doc.redo_stack.clear();
-        doc.saved_undo_depth = 0;
+        doc.open_edit_group = None;
+        doc.saved_undo_depth = Some(0);
         doc.saved_undo_operation_id = None;
         doc.saved_file_fingerprint = Some(snapshot.fingerprint);
+        doc.raw_bytes = snapshot.raw_bytes;
+        doc.line_annotations.clear(None);
 
         Ok(FileInfo {
             id,
@@ -1035,6 +1720,11 @@ pub(super) fn reload_file_from_disk_impl(
             ),
             large_file_mode: snapshot.large_file_mode,
             syntax_override: doc.syntax_override.clone(),
+            suggested_syntax: syntax_suggestion::suggest_syntax_for_document(&path, &doc.rope),
+            decode_stats: Some(snapshot.decode_stats),
+            preview_mode: doc.preview_mode,
+            streaming: false,
+            hex_view: false,
         })
     } else {
         Err("Document not found".to_string())
@@ -1171,6 +1861,7 @@ fn update_open_document_paths_after_rename(
             doc.path = Some(target_path.join(relative_path));
         }
     }
+    state.mark_document_watches_stale();
 }
 
 pub(super) async fn rename_path_impl(
@@ -1307,6 +1998,16 @@ fn collect_folder_refresh_directories(root_path: &std::path::Path, event: &Event
         return Vec::new();
     }
 
+    collect_folder_changed_directories(root_path, event)
+}
+
+// Directories under the root whose entries changed in any way, content
+// writes included; feeds the batched `rutar://directory-changed` event.
+fn collect_folder_changed_directories(root_path: &std::path::Path, event: &Event) -> Vec<PathBuf> {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return Vec::new();
+    }
+
     let mut directories = BTreeSet::new();
 
     for changed_path in &event.paths {
@@ -1333,6 +2034,11 @@ pub(super) fn watch_folder_tree_impl(
     state: State<'_, AppState>,
     path: String,
 ) -> Result<(), String> {
+    // Safe mode runs without file watchers; the tree is still browsable.
+    if config::is_safe_mode_impl() {
+        return Ok(());
+    }
+
     let root_path = PathBuf::from(&path);
 
     if !root_path.is_dir() {
@@ -1358,6 +2064,11 @@ pub(super) fn watch_folder_tree_impl(
             }
         };
 
+        let changed_directories =
+            collect_folder_changed_directories(root_path_for_callback.as_path(), &event);
+        app.state::<AppState>()
+            .record_changed_directories(changed_directories);
+
         let refresh_directories =
             collect_folder_refresh_directories(root_path_for_callback.as_path(), &event);
         if refresh_directories.is_empty() {
@@ -1409,6 +2120,94 @@ pub(super) async fn get_word_count_info_impl(
         .map_err(|error| error.to_string())
 }
 
+#[derive(serde::Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionRange {
+    pub start_line_number: usize,
+    pub start_column: usize,
+    pub end_line_number: usize,
+    pub end_column: usize,
+}
+
+#[derive(serde::Serialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionStatistics {
+    pub range_count: usize,
+    pub character_count: usize,
+    pub character_count_no_spaces: usize,
+    pub word_count: usize,
+    pub line_count: usize,
+    pub number_count: usize,
+    pub number_sum: f64,
+}
+
+fn selection_number_regex() -> &'static Regex {
+    static NUMBER_REGEX: OnceLock<Regex> = OnceLock::new();
+    NUMBER_REGEX.get_or_init(|| {
+        Regex::new(r"[-+]?(?:\d+(?:\.\d*)?|\.\d+)(?:[eE][-+]?\d+)?")
+            .expect("number regex should compile")
+    })
+}
+
+// Columns are 1-based UTF-16 offsets, matching the editor's selections. Empty
+// ranges are skipped so a bare cursor does not count as a selected line.
+fn compute_selection_statistics(rope: &Rope, ranges: &[SelectionRange]) -> SelectionStatistics {
+    let mut stats = SelectionStatistics::default();
+
+    for range in ranges {
+        let raw_start = editing::line_column_to_char_index_utf16(
+            rope,
+            range.start_line_number,
+            range.start_column,
+        );
+        let raw_end =
+            editing::line_column_to_char_index_utf16(rope, range.end_line_number, range.end_column);
+        let (start_char, end_char) = (raw_start.min(raw_end), raw_start.max(raw_end));
+        if start_char == end_char {
+            continue;
+        }
+
+        let slice = rope.slice(start_char..end_char);
+        let words = count_word_stats_in_slice(slice);
+        stats.range_count += 1;
+        stats.character_count += words.character_count;
+        stats.character_count_no_spaces += words.character_count_no_spaces;
+        stats.word_count += words.word_count;
+
+        // A selection ending right after a line break does not touch the next line.
+        let start_line = rope.char_to_line(start_char);
+        let end_line = rope.char_to_line(end_char);
+        let ends_at_line_start = end_line > start_line && rope.line_to_char(end_line) == end_char;
+        stats.line_count += end_line - start_line + usize::from(!ends_at_line_start);
+
+        let text = slice.to_string();
+        for found in selection_number_regex().find_iter(&text) {
+            if let Ok(value) = found.as_str().parse::<f64>() {
+                stats.number_count += 1;
+                stats.number_sum += value;
+            }
+        }
+    }
+
+    stats
+}
+
+pub(super) async fn get_selection_statistics_impl(
+    state: State<'_, AppState>,
+    id: String,
+    ranges: Vec<SelectionRange>,
+) -> Result<SelectionStatistics, String> {
+    let rope = state
+        .documents
+        .get(&id)
+        .map(|doc| doc.rope.clone())
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || compute_selection_statistics(&rope, &ranges))
+        .await
+        .map_err(|error| error.to_string())
+}
+
 pub(super) async fn get_document_size_bytes_impl(
     state: State<'_, AppState>,
     id: String,
@@ -1442,16 +2241,39 @@ pub(super) fn detect_document_indentation_impl(
 #[cfg(test)]
 mod tests {
     use super::{
-        build_document_text_chunks, collect_folder_refresh_directories, count_word_stats,
-        detect_indentation_from_rope, measure_document_size_bytes, normalize_encoding_label,
-        render_markdown_preview_html, DOCUMENT_TEXT_SNAPSHOT_CHUNK_BYTES,
+        build_document_text_chunks, build_persist_text, collect_folder_refresh_directories,
+        compute_selection_statistics, count_changed_persisted_line_breaks, count_line_endings,
+        count_word_stats, decode_encoding_preview, detect_indentation_from_rope,
+        detect_line_ending, expand_extract_reference, is_file_locked_impl,
+        measure_document_size_bytes, normalize_encoding_label, read_disk_file_snapshot,
+        render_markdown_preview_html, resolve_encoding_label, resolve_file_share_mode,
+        stray_line_break_span, text_utils, DocumentEncodeSource, SelectionRange,
+        DOCUMENT_TEXT_SNAPSHOT_CHUNK_BYTES,
     };
-    use crate::state::LineEnding;
+    use crate::state::{Document, FileShareMode, LineEnding};
     use encoding_rs::Encoding;
     use notify::{event::CreateKind, event::DataChange, event::ModifyKind, Event, EventKind};
     use ropey::Rope;
     use std::path::Path;
 
+    #[test]
+    fn capture_converted_should_drop_raw_bytes_only_when_converting() {
+        let mut doc = Document::for_test("a\nb", None);
+        doc.line_ending = LineEnding::Lf;
+        doc.saved_line_ending = LineEnding::Lf;
+        doc.raw_bytes = Some(std::sync::Arc::from(&b"a\nb"[..]));
+
+        let (bytes, preserved) =
+            DocumentEncodeSource::capture_converted(&doc, doc.encoding, LineEnding::Lf).encode();
+        assert_eq!(bytes, b"a\nb");
+        assert!(preserved);
+
+        let (bytes, preserved) =
+            DocumentEncodeSource::capture_converted(&doc, doc.encoding, LineEnding::CrLf).encode();
+        assert_eq!(bytes, b"a\r\nb");
+        assert!(!preserved);
+    }
+
     #[test]
     fn word_count_should_treat_cjk_characters_individually() {
         let rope = Rope::from_str("你好 world");
@@ -1718,5 +2540,208 @@ $$
         let directories = collect_folder_refresh_directories(root_path, &event);
 
         assert!(directories.is_empty());
+        assert_eq!(
+            collect_folder_changed_directories(root_path, &event),
+            vec![root_path.join("src")]
+        );
+    }
+
+    #[test]
+    fn resolve_file_share_mode_should_reject_unknown_labels() {
+        assert_eq!(
+            resolve_file_share_mode(Some("exclusive")),
+            Ok(FileShareMode::Exclusive)
+        );
+        assert!(resolve_file_share_mode(Some("deny-all")).is_err());
+    }
+
+    #[test]
+    fn read_disk_file_snapshot_should_honor_share_mode_for_unlocked_files() {
+        let path =
+            std::env::temp_dir().join(format!("rutar-share-mode-{}.log", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "first\nsecond\n").expect("write temp file");
+
+        let snapshot =
+            read_disk_file_snapshot(&path, FileShareMode::Exclusive, false).expect("read snapshot");
+        assert_eq!(snapshot.line_count, 3);
+        assert_eq!(
+            is_file_locked_impl(path.to_string_lossy().to_string()),
+            Ok(false)
+        );
+
+        let _ = std::fs::remove_file(&path);
+        assert!(is_file_locked_impl(path.to_string_lossy().to_string()).is_err());
+    }
+
+    #[test]
+    fn compute_selection_statistics_should_sum_ranges_and_numbers() {
+        let rope = Rope::from_str("price 12 and 3.5\n中文 -2\nlast");
+        let range = |start_line, start_column, end_line, end_column| SelectionRange {
+            start_line_number: start_line,
+            start_column,
+            end_line_number: end_line,
+            end_column,
+        };
+
+        let stats = compute_selection_statistics(
+            &rope,
+            &[range(1, 7, 2, 1), range(2, 1, 2, 6), range(3, 2, 3, 2)],
+        );
+
+        assert_eq!(stats.range_count, 2);
+        assert_eq!(stats.character_count, 11 + 5);
+        assert_eq!(stats.word_count, 4 + 3);
+        assert_eq!(stats.line_count, 2);
+        assert_eq!(stats.number_count, 3);
+        assert!((stats.number_sum - 13.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn expand_extract_reference_should_fill_name_and_path() {
+        let path = std::path::Path::new("/tmp/part.sql");
+        assert_eq!(
+            expand_extract_reference("\\i {name} -- {path}", path),
+            "\\i part.sql -- /tmp/part.sql"
+        );
+        assert_eq!(expand_extract_reference("plain", path), "plain");
+    }
+
+    #[test]
+    fn count_changed_persisted_line_breaks_should_count_all_breaks_only_when_switching() {
+        let text = "a\nb\r\nc\rd\n";
+        assert_eq!(
+            count_changed_persisted_line_breaks(text, LineEnding::Lf, LineEnding::CrLf),
+            4
+        );
+        assert_eq!(
+            count_changed_persisted_line_breaks(text, LineEnding::Lf, LineEnding::Lf),
+            0
+        );
+    }
+
+    #[test]
+    fn stray_line_break_span_should_cover_first_to_last_carriage_return() {
+        assert_eq!(stray_line_break_span("a\nb\nc"), None);
+        assert_eq!(stray_line_break_span("a\r\nb\nc\rd"), Some((1, 7)));
+        assert_eq!(stray_line_break_span("a\nb\r\n"), Some((3, 5)));
+
+        let text = "a\r\nb\nc\rd";
+        let (start, end) = stray_line_break_span(text).expect("span");
+        assert_eq!(
+            format!(
+                "{}{}{}",
+                &text[..start],
+                text_utils::normalize_to_lf(&text[start..end]),
+                &text[end..]
+            ),
+            "a\nb\nc\nd"
+        );
+    }
+
+    #[test]
+    fn count_line_endings_should_count_each_style_and_pick_dominant() {
+        let counts = count_line_endings("a\r\nb\r\nc\nd\re");
+
+        assert_eq!((counts.crlf, counts.lf, counts.cr), (2, 1, 1));
+        assert!(matches!(detect_line_ending(counts), LineEnding::CrLf));
+    }
+
+    #[test]
+    fn read_disk_file_snapshot_should_report_bom_and_malformed_sequences() {
+        let dir = std::env::temp_dir().join(format!("rutar-decode-stats-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+
+        let bom_path = dir.join("bom.txt");
+        std::fs::write(&bom_path, b"\xEF\xBB\xBFalpha\r\nbeta").expect("write bom file");
+        let snapshot = read_disk_file_snapshot(&bom_path, FileShareMode::ReadWrite, false)
+            .expect("read bom file");
+        assert!(snapshot.decode_stats.has_bom);
+        assert_eq!(snapshot.decode_stats.encoding_confidence, "bom");
+        assert!(!snapshot.decode_stats.had_malformed_sequences);
+        assert!(snapshot.raw_bytes.is_none());
+        assert_eq!(snapshot.decode_stats.line_ending_counts.crlf, 1);
+
+        let malformed_path = dir.join("malformed.txt");
+        std::fs::write(&malformed_path, b"\xEF\xBB\xBFok \xFF\xFE done")
+            .expect("write malformed file");
+        let snapshot = read_disk_file_snapshot(&malformed_path, FileShareMode::ReadWrite, false)
+            .expect("read malformed file");
+        assert!(snapshot.decode_stats.had_malformed_sequences);
+        assert!(snapshot.decode_stats.raw_bytes_preserved);
+        assert_eq!(
+            snapshot.raw_bytes.as_deref(),
+            Some(&b"\xEF\xBB\xBFok \xFF\xFE done"[..])
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn read_disk_file_snapshot_should_reject_binary_unless_allowed() {
+        let dir = std::env::temp_dir().join(format!("rutar-sniff-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+
+        let png_path = dir.join("image.txt");
+        std::fs::write(&png_path, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").expect("write png file");
+        let error = read_disk_file_snapshot(&png_path, FileShareMode::ReadWrite, false)
+            .err()
+            .expect("binary file should be rejected");
+        assert!(error.starts_with("Not a text file (png)"));
+
+        let snapshot = read_disk_file_snapshot(&png_path, FileShareMode::ReadWrite, true)
+            .expect("binary file should open when allowed");
+        assert_eq!(
+            snapshot.decode_stats.detected_file_type.as_deref(),
+            Some("png")
+        );
+
+        let utf16_path = dir.join("utf16.txt");
+        let utf16: Vec<u8> = "alpha\nbeta\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        std::fs::write(&utf16_path, utf16).expect("write utf16 file");
+        let snapshot = read_disk_file_snapshot(&utf16_path, FileShareMode::ReadWrite, false)
+            .expect("read utf16 file");
+        assert_eq!(snapshot.encoding, encoding_rs::UTF_16LE);
+        assert_eq!(snapshot.decode_stats.encoding_confidence, "heuristic");
+        assert_eq!(snapshot.rope.to_string(), "alpha\nbeta\n");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn save_copy_conversion_helpers_should_resolve_targets() {
+        let rope = Rope::from_str("a\nb");
+
+        assert_eq!(build_persist_text(&rope, LineEnding::CrLf), "a\r\nb");
+        assert_eq!(build_persist_text(&rope, LineEnding::Cr), "a\rb");
+        assert_eq!(
+            resolve_encoding_label(" ansi ").map(|encoding| encoding.name()),
+            Ok("windows-1252")
+        );
+        assert_eq!(
+            resolve_encoding_label("bogus").map(|encoding| encoding.name()),
+            Err("Unsupported encoding: bogus".to_string())
+        );
+    }
+
+    #[test]
+    fn decode_encoding_preview_should_decode_per_encoding_and_ignore_split_tail() {
+        // "中文\r\n" in GBK followed by the first byte of another GBK character.
+        let sample = b"\xD6\xD0\xCE\xC4\r\nnext \xD6";
+
+        let gbk = decode_encoding_preview(sample, "GBK", 10, true);
+        assert_eq!(gbk.encoding.as_deref(), Some("GBK"));
+        assert_eq!(gbk.lines, vec!["中文".to_string()]);
+        assert!(!gbk.had_malformed_sequences);
+
+        let utf8 = decode_encoding_preview(sample, "utf-8", 10, false);
+        assert!(utf8.had_malformed_sequences);
+        assert_eq!(utf8.lines.len(), 2);
+
+        let unknown = decode_encoding_preview(sample, "bogus", 10, true);
+        assert!(unknown.encoding.is_none());
+        assert!(unknown.error.is_some());
     }
 }
@@ -1,14 +1,23 @@
 use super::*;
+use crate::state::DocumentViewState;
 
 #[tauri::command]
-pub async fn open_file(state: State<'_, AppState>, path: String) -> Result<FileInfo, String> {
-    file_io::open_file_impl(state, path).await
+pub async fn open_file(
+    state: State<'_, AppState>,
+    path: String,
+    share_mode: Option<String>,
+    allow_binary: Option<bool>,
+    hex_view: Option<bool>,
+    _timer: CommandTimer,
+) -> Result<FileInfo, String> {
+    file_io::open_file_impl(state, path, share_mode, allow_binary, hex_view).await
 }
 
 #[tauri::command]
 pub async fn open_files(
     state: State<'_, AppState>,
     paths: Vec<String>,
+    _timer: CommandTimer,
 ) -> Result<Vec<file_io::OpenFileBatchResultItem>, String> {
     Ok(file_io::open_files_impl(state, paths).await)
 }
@@ -16,7 +25,7 @@ pub async fn open_files(
 #[tauri::command]
 pub fn get_visible_lines(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     start_line: usize,
     end_line: usize,
 ) -> Result<String, String> {
@@ -24,14 +33,19 @@ pub fn get_visible_lines(
 }
 
 #[tauri::command]
-pub async fn get_document_text(state: State<'_, AppState>, id: String) -> Result<String, String> {
+pub async fn get_document_text(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    _timer: CommandTimer,
+) -> Result<String, String> {
     file_io::get_document_text_impl(state, id).await
 }
 
 #[tauri::command]
 pub async fn get_document_text_chunks(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
+    _timer: CommandTimer,
 ) -> Result<Vec<String>, String> {
     file_io::get_document_text_chunks_impl(state, id).await
 }
@@ -39,23 +53,59 @@ pub async fn get_document_text_chunks(
 #[tauri::command]
 pub async fn render_markdown_preview(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
+    _timer: CommandTimer,
 ) -> Result<String, String> {
     file_io::render_markdown_preview_impl(state, id).await
 }
 
+#[tauri::command]
+pub fn list_supported_encodings(language: Option<String>) -> Vec<encodings::EncodingFamilyPayload> {
+    encodings::list_supported_encodings_impl(language)
+}
+
+#[tauri::command]
+pub async fn preview_file_encodings(
+    path: String,
+    encodings: Vec<String>,
+    sample_bytes: Option<usize>,
+    max_lines: Option<usize>,
+    _timer: CommandTimer,
+) -> Result<Vec<file_io::EncodingPreviewItem>, String> {
+    file_io::preview_file_encodings_impl(path, encodings, sample_bytes, max_lines).await
+}
+
+#[tauri::command]
+pub async fn search_bytes_in_file(
+    state: State<'_, AppState>,
+    path_or_id: String,
+    pattern: String,
+    pattern_kind: Option<String>,
+    max_results: Option<usize>,
+    _timer: CommandTimer,
+) -> Result<byte_search::ByteSearchResultPayload, String> {
+    byte_search::search_bytes_in_file_impl(state, path_or_id, pattern, pattern_kind, max_results)
+        .await
+}
+
 #[tauri::command]
 pub fn get_bookmark_line_previews(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     lines: Vec<usize>,
 ) -> Result<Vec<String>, String> {
     file_io::get_bookmark_line_previews_impl(state, id, lines)
 }
 
 #[tauri::command]
-pub fn close_file(state: State<'_, AppState>, id: String) {
-    file_io::close_file_impl(state, id)
+pub fn close_file(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    reading_line: Option<usize>,
+    cursor_line: Option<usize>,
+    cursor_column: Option<usize>,
+) {
+    file_io::close_file_impl(state, id, reading_line, cursor_line, cursor_column)
 }
 
 #[tauri::command]
@@ -64,7 +114,60 @@ pub fn close_files(state: State<'_, AppState>, ids: Vec<String>) {
 }
 
 #[tauri::command]
-pub async fn save_file(state: State<'_, AppState>, id: String) -> Result<(), String> {
+pub fn create_document_view(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    initial_state: Option<DocumentViewState>,
+) -> Result<String, String> {
+    document_views::create_document_view_impl(state, id, initial_state)
+}
+
+#[tauri::command]
+pub fn get_document_view(
+    state: State<'_, AppState>,
+    view_id: String,
+) -> Result<document_views::DocumentViewPayload, String> {
+    document_views::get_document_view_impl(state, view_id)
+}
+
+#[tauri::command]
+pub fn update_document_view_state(
+    state: State<'_, AppState>,
+    view_id: String,
+    view_state: DocumentViewState,
+) -> Result<(), String> {
+    document_views::update_document_view_state_impl(state, view_id, view_state)
+}
+
+#[tauri::command]
+pub fn list_document_views(state: State<'_, AppState>, id: DocumentId) -> Vec<String> {
+    document_views::list_document_views_impl(state, id)
+}
+
+#[tauri::command]
+pub fn close_document_view(state: State<'_, AppState>, view_id: String) -> bool {
+    document_views::close_document_view_impl(state, view_id)
+}
+
+#[tauri::command]
+pub async fn reopen_last_closed(
+    state: State<'_, AppState>,
+    _timer: CommandTimer,
+) -> Result<Option<recently_closed::ReopenedDocumentPayload>, String> {
+    recently_closed::reopen_last_closed_impl(state).await
+}
+
+#[tauri::command]
+pub fn list_recently_closed() -> Vec<recently_closed::ClosedDocumentSummary> {
+    recently_closed::list_recently_closed_impl()
+}
+
+#[tauri::command]
+pub async fn save_file(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    _timer: CommandTimer,
+) -> Result<(), String> {
     file_io::save_file_impl(state, id).await
 }
 
@@ -72,6 +175,7 @@ pub async fn save_file(state: State<'_, AppState>, id: String) -> Result<(), Str
 pub async fn save_files(
     state: State<'_, AppState>,
     ids: Vec<String>,
+    _timer: CommandTimer,
 ) -> Result<Vec<file_io::SaveFileBatchResultItem>, String> {
     Ok(file_io::save_files_impl(state, ids).await)
 }
@@ -79,25 +183,70 @@ pub async fn save_files(
 #[tauri::command]
 pub async fn save_file_as(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     path: String,
+    _timer: CommandTimer,
 ) -> Result<(), String> {
     file_io::save_file_as_impl(state, id, path).await
 }
 
+#[tauri::command]
+pub async fn save_copy_as(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    path: String,
+    encoding: Option<String>,
+    line_ending: Option<String>,
+    _timer: CommandTimer,
+) -> Result<(), String> {
+    file_io::save_copy_as_impl(state, id, path, encoding, line_ending).await
+}
+
+#[tauri::command]
+pub async fn extract_selection_to_file(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    start_char: usize,
+    end_char: usize,
+    path: String,
+    remove_from_source: bool,
+    reference: Option<String>,
+    _timer: CommandTimer,
+) -> Result<file_io::ExtractSelectionResultPayload, String> {
+    file_io::extract_selection_to_file_impl(
+        state,
+        id,
+        start_char,
+        end_char,
+        path,
+        remove_from_source,
+        reference,
+    )
+    .await
+}
+
 #[tauri::command]
 pub fn convert_encoding(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     new_encoding: String,
 ) -> Result<(), String> {
     file_io::convert_encoding_impl(state, id, new_encoding)
 }
 
+#[tauri::command]
+pub fn convert_line_endings_in_buffer(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    target: String,
+) -> Result<file_io::LineEndingConversionResultPayload, String> {
+    file_io::convert_line_endings_in_buffer_impl(state, id, target)
+}
+
 #[tauri::command]
 pub fn set_line_ending(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     new_line_ending: String,
 ) -> Result<(), String> {
     file_io::set_line_ending_impl(state, id, new_line_ending)
@@ -106,7 +255,7 @@ pub fn set_line_ending(
 #[tauri::command]
 pub fn set_document_syntax(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     syntax_override: Option<String>,
 ) -> Result<(), String> {
     file_io::set_document_syntax_impl(state, id, syntax_override)
@@ -152,12 +301,13 @@ pub async fn rename_path(
     state: State<'_, AppState>,
     path: String,
     new_name: String,
+    _timer: CommandTimer,
 ) -> Result<DirEntry, String> {
     file_io::rename_path_impl(state, path, new_name).await
 }
 
 #[tauri::command]
-pub async fn delete_path(path: String) -> Result<(), String> {
+pub async fn delete_path(path: String, _timer: CommandTimer) -> Result<(), String> {
     file_io::delete_path_impl(path).await
 }
 
@@ -166,10 +316,21 @@ pub fn open_in_file_manager(path: String) -> Result<(), String> {
     file_io::open_in_file_manager_impl(path)
 }
 
+#[tauri::command]
+pub async fn get_selection_statistics(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    ranges: Vec<file_io::SelectionRange>,
+    _timer: CommandTimer,
+) -> Result<file_io::SelectionStatistics, String> {
+    file_io::get_selection_statistics_impl(state, id, ranges).await
+}
+
 #[tauri::command]
 pub async fn get_word_count_info(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
+    _timer: CommandTimer,
 ) -> Result<WordCountInfo, String> {
     file_io::get_word_count_info_impl(state, id).await
 }
@@ -177,7 +338,8 @@ pub async fn get_word_count_info(
 #[tauri::command]
 pub async fn get_document_size_bytes(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
+    _timer: CommandTimer,
 ) -> Result<u64, String> {
     file_io::get_document_size_bytes_impl(state, id).await
 }
@@ -185,27 +347,38 @@ pub async fn get_document_size_bytes(
 #[tauri::command]
 pub fn detect_document_indentation(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     max_lines: Option<usize>,
 ) -> Result<Option<file_io::DetectedIndentation>, String> {
     file_io::detect_document_indentation_impl(state, id, max_lines)
 }
 
 #[tauri::command]
-pub fn has_external_file_change(state: State<'_, AppState>, id: String) -> Result<bool, String> {
+pub fn has_external_file_change(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<bool, String> {
     file_io::has_external_file_change_impl(state, id)
 }
 
 #[tauri::command]
 pub fn acknowledge_external_file_change(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
 ) -> Result<(), String> {
     file_io::acknowledge_external_file_change_impl(state, id)
 }
 
 #[tauri::command]
-pub fn reload_file_from_disk(state: State<'_, AppState>, id: String) -> Result<FileInfo, String> {
+pub fn is_file_locked(path: String) -> Result<bool, String> {
+    file_io::is_file_locked_impl(path)
+}
+
+#[tauri::command]
+pub fn reload_file_from_disk(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<FileInfo, String> {
     file_io::reload_file_from_disk_impl(state, id)
 }
 
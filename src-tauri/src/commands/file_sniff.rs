@@ -0,0 +1,169 @@
+// Lightweight content sniffing run before decoding a file from disk.
+//
+// Only the first few kilobytes are inspected: well-known binary signatures are
+// reported so the frontend can refuse a garbled decode, and BOM-less UTF-16 is
+// recognised because chardetng never guesses it.
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE};
+
+pub(super) const SNIFF_SAMPLE_BYTES: usize = 8 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum SniffedContent {
+    Text,
+    Utf16(&'static Encoding),
+    Binary(&'static str),
+}
+
+fn sniff_magic_number(sample: &[u8]) -> Option<&'static str> {
+    if sample.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some("png");
+    }
+    if sample.starts_with(b"PK\x03\x04")
+        || sample.starts_with(b"PK\x05\x06")
+        || sample.starts_with(b"PK\x07\x08")
+    {
+        return Some("zip");
+    }
+    if sample.starts_with(b"%PDF-") {
+        return Some("pdf");
+    }
+    if sample.starts_with(b"\x7fELF") {
+        return Some("elf");
+    }
+    if sample.starts_with(b"MZ") {
+        // Plain "MZ" is too short to trust; require the PE header it points to.
+        let pe_offset = sample
+            .get(0x3c..0x40)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)?;
+        if sample.get(pe_offset..pe_offset + 4) == Some(b"PE\0\0") {
+            return Some("pe");
+        }
+    }
+
+    None
+}
+
+// Mostly-ASCII UTF-16 leaves a zero in every other byte; which half holds the
+// zeros gives away the byte order.
+fn sniff_utf16_without_bom(sample: &[u8]) -> Option<&'static Encoding> {
+    let pairs = sample.len() / 2;
+    if pairs < 4 {
+        return None;
+    }
+
+    let (mut even_zeros, mut odd_zeros) = (0usize, 0usize);
+    for pair in sample.chunks_exact(2) {
+        even_zeros += usize::from(pair[0] == 0);
+        odd_zeros += usize::from(pair[1] == 0);
+    }
+
+    let mostly = |count: usize| count * 10 >= pairs * 4;
+    let rarely = |count: usize| count * 10 <= pairs;
+    if mostly(odd_zeros) && rarely(even_zeros) {
+        Some(UTF_16LE)
+    } else if mostly(even_zeros) && rarely(odd_zeros) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+// Text can carry the odd NUL or escape byte (logs written by crashed
+// processes, terminal captures), so one control byte is not enough; binary
+// formats without a known signature are dense with them.
+fn is_control_dense(sample: &[u8]) -> bool {
+    let control_bytes = sample
+        .iter()
+        .filter(|&&byte| byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    control_bytes * 10 > sample.len()
+}
+
+pub(super) fn sniff_content(sample: &[u8]) -> SniffedContent {
+    let sample = &sample[..sample.len().min(SNIFF_SAMPLE_BYTES)];
+
+    // A BOM already settles the encoding.
+    if Encoding::for_bom(sample).is_some() {
+        return SniffedContent::Text;
+    }
+
+    if let Some(kind) = sniff_magic_number(sample) {
+        return SniffedContent::Binary(kind);
+    }
+
+    if let Some(encoding) = sniff_utf16_without_bom(sample) {
+        return SniffedContent::Utf16(encoding);
+    }
+
+    if is_control_dense(sample) {
+        return SniffedContent::Binary("binary");
+    }
+
+    SniffedContent::Text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff_content_should_recognize_magic_numbers() {
+        assert_eq!(
+            sniff_content(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            SniffedContent::Binary("png")
+        );
+        assert_eq!(
+            sniff_content(b"PK\x03\x04\x14\0"),
+            SniffedContent::Binary("zip")
+        );
+        assert_eq!(sniff_content(b"%PDF-1.7\n"), SniffedContent::Binary("pdf"));
+        assert_eq!(
+            sniff_content(b"\x7fELF\x02\x01\x01"),
+            SniffedContent::Binary("elf")
+        );
+
+        let mut pe = vec![0u8; 0x48];
+        pe[..2].copy_from_slice(b"MZ");
+        pe[0x3c] = 0x40;
+        pe[0x40..0x44].copy_from_slice(b"PE\0\0");
+        assert_eq!(sniff_content(&pe), SniffedContent::Binary("pe"));
+    }
+
+    #[test]
+    fn sniff_content_should_keep_plain_text_including_mz_prefix() {
+        assert_eq!(
+            sniff_content(b"MZ is just text here\n"),
+            SniffedContent::Text
+        );
+        assert_eq!(
+            sniff_content("中文 text\n".as_bytes()),
+            SniffedContent::Text
+        );
+        assert_eq!(sniff_content(b""), SniffedContent::Text);
+        assert_eq!(
+            sniff_content(b"2024-01-01 worker crashed\0\0\n2024-01-01 restarted after \x1b[1mfailure\x1b[0m\n"),
+            SniffedContent::Text
+        );
+    }
+
+    #[test]
+    fn sniff_content_should_detect_bomless_utf16_and_generic_binary() {
+        let le: Vec<u8> = "hello world\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        assert_eq!(sniff_content(&le), SniffedContent::Utf16(UTF_16LE));
+
+        let be: Vec<u8> = "hello world\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_be_bytes())
+            .collect();
+        assert_eq!(sniff_content(&be), SniffedContent::Utf16(UTF_16BE));
+
+        assert_eq!(
+            sniff_content(b"\x01\x02\0\x03\xff\xfe\x10\x11\x12"),
+            SniffedContent::Binary("binary")
+        );
+    }
+}
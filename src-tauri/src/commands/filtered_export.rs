@@ -0,0 +1,298 @@
+// Writes the lines a set of filter rules matches to a file that keeps the
+// rule categorization: an HTML page with the rule colors, or CSV with the
+// rule and its colors in their own columns. Rules are named by keyword.
+
+use super::*;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilteredViewExportPayload {
+    pub path: String,
+    pub exported_lines: usize,
+    pub document_version: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FilteredViewExportFormat {
+    Html,
+    Csv,
+}
+
+fn parse_filtered_view_export_format(format: &str) -> Result<FilteredViewExportFormat, String> {
+    match format.trim().to_ascii_lowercase().as_str() {
+        "html" | "htm" => Ok(FilteredViewExportFormat::Html),
+        "csv" => Ok(FilteredViewExportFormat::Csv),
+        _ => Err(format!(
+            "Unsupported filtered view export format: {}",
+            format
+        )),
+    }
+}
+
+pub(super) fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+// Colors come from user rules and end up inside a style attribute, so only
+// characters that occur in CSS color values are kept.
+fn sanitize_css_color(value: &str) -> String {
+    value
+        .trim()
+        .chars()
+        .filter(|ch| {
+            ch.is_ascii_alphanumeric() || matches!(ch, '#' | '(' | ')' | ',' | '.' | '%' | ' ')
+        })
+        .collect()
+}
+
+fn build_rule_style(style: &FilterRuleStyleResult) -> String {
+    let mut css = String::new();
+    let background_color = sanitize_css_color(&style.background_color);
+    if !background_color.is_empty() {
+        let _ = write!(css, "background-color:{background_color};");
+    }
+    let text_color = sanitize_css_color(&style.text_color);
+    if !text_color.is_empty() {
+        let _ = write!(css, "color:{text_color};");
+    }
+    if style.bold {
+        css.push_str("font-weight:bold;");
+    }
+    if style.italic {
+        css.push_str("font-style:italic;");
+    }
+    css
+}
+
+fn render_match_text_html(item: &FilterLineMatchResult, style: &str) -> String {
+    if item.style.apply_to != "match" || item.ranges.is_empty() {
+        return escape_html(&item.line_text);
+    }
+
+    let chars: Vec<char> = item.line_text.chars().collect();
+    let mut output = String::new();
+    let mut cursor = 0usize;
+    for range in &item.ranges {
+        let start = range.start_char.clamp(cursor, chars.len());
+        let end = range.end_char.clamp(start, chars.len());
+        let before: String = chars[cursor..start].iter().collect();
+        let matched: String = chars[start..end].iter().collect();
+        output.push_str(&escape_html(&before));
+        let _ = write!(
+            output,
+            "<span style=\"{style}\">{}</span>",
+            escape_html(&matched)
+        );
+        cursor = end;
+    }
+    let rest: String = chars[cursor..].iter().collect();
+    output.push_str(&escape_html(&rest));
+    output
+}
+
+fn render_filtered_view_html(
+    title: &str,
+    rules: &[CompiledFilterRule],
+    matches: &[FilterLineMatchResult],
+) -> String {
+    let mut output = String::new();
+    let title = escape_html(title);
+    let _ = write!(
+        output,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>\nbody{{font-family:Consolas,Menlo,monospace;font-size:13px;}}\n\
+         table{{border-collapse:collapse;}}\ntd{{padding:0 8px;white-space:pre;vertical-align:top;}}\n\
+         td.line{{color:#888;text-align:right;}}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+
+    output.push_str("<ul class=\"rules\">\n");
+    for rule in rules {
+        let _ = writeln!(
+            output,
+            "<li><span style=\"{}\">{}</span></li>",
+            build_rule_style(&rule.style),
+            escape_html(&rule.keyword)
+        );
+    }
+    output.push_str("</ul>\n<table>\n<tr><th>Line</th><th>Rule</th><th>Text</th></tr>\n");
+
+    for item in matches {
+        let keyword = rules
+            .iter()
+            .find(|rule| rule.rule_index == item.rule_index)
+            .map(|rule| rule.keyword.as_str())
+            .unwrap_or_default();
+        let style = build_rule_style(&item.style);
+        let line_style = if item.style.apply_to == "match" {
+            String::new()
+        } else {
+            format!(" style=\"{style}\"")
+        };
+        let _ = writeln!(
+            output,
+            "<tr{line_style}><td class=\"line\">{}</td><td>{}</td><td>{}</td></tr>",
+            item.line,
+            escape_html(keyword),
+            render_match_text_html(item, &style)
+        );
+    }
+
+    output.push_str("</table>\n</body>\n</html>\n");
+    output
+}
+
+fn escape_csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_filtered_view_csv(
+    rules: &[CompiledFilterRule],
+    matches: &[FilterLineMatchResult],
+) -> String {
+    let mut output = String::from("line,rule,background_color,text_color,text\n");
+    for item in matches {
+        let keyword = rules
+            .iter()
+            .find(|rule| rule.rule_index == item.rule_index)
+            .map(|rule| rule.keyword.as_str())
+            .unwrap_or_default();
+        let _ = writeln!(
+            output,
+            "{},{},{},{},{}",
+            item.line,
+            escape_csv_field(keyword),
+            escape_csv_field(&item.style.background_color),
+            escape_csv_field(&item.style.text_color),
+            escape_csv_field(&item.line_text)
+        );
+    }
+    output
+}
+
+pub(super) async fn export_filtered_view_impl(
+    state: State<'_, AppState>,
+    id: String,
+    rules: Vec<FilterRuleInput>,
+    format: String,
+    path: String,
+) -> Result<FilteredViewExportPayload, String> {
+    let format = parse_filtered_view_export_format(&format)?;
+    let compiled_rules = search::compile_filter_rules(rules)?;
+    if compiled_rules.is_empty() {
+        return Err("No filter rules to export".to_string());
+    }
+    if path.trim().is_empty() {
+        return Err("Export path is empty".to_string());
+    }
+
+    let (rope, title, document_version) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        let title = doc
+            .path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Untitled".to_string());
+        (doc.rope.clone(), title, doc.document_version)
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let matches =
+            search::collect_filter_rule_line_matches(&rope, 0, &compiled_rules, None, true);
+        let content = match format {
+            FilteredViewExportFormat::Html => {
+                render_filtered_view_html(&title, &compiled_rules, &matches)
+            }
+            FilteredViewExportFormat::Csv => render_filtered_view_csv(&compiled_rules, &matches),
+        };
+
+        let output_path = PathBuf::from(&path);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&output_path, content).map_err(|e| e.to_string())?;
+
+        Ok(FilteredViewExportPayload {
+            path,
+            exported_lines: matches.len(),
+            document_version,
+        })
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_rule(keyword: &str, apply_to: &str, background_color: &str) -> FilterRuleInput {
+        FilterRuleInput {
+            keyword: keyword.to_string(),
+            match_mode: "contains".to_string(),
+            background_color: background_color.to_string(),
+            text_color: "#000000".to_string(),
+            bold: true,
+            italic: false,
+            apply_to: apply_to.to_string(),
+            alert: None,
+        }
+    }
+
+    fn collect(text: &str, rules: &[CompiledFilterRule]) -> Vec<FilterLineMatchResult> {
+        search::collect_filter_rule_line_matches(&Rope::from_str(text), 0, rules, None, true)
+    }
+
+    #[test]
+    fn render_filtered_view_html_should_color_lines_and_matches() {
+        let rules = search::compile_filter_rules(vec![
+            make_rule("ERROR", "line", "#ff0000\"><script>"),
+            make_rule("warn", "match", "#ffff00"),
+        ])
+        .expect("compile rules");
+        let matches = collect("ERROR <boom>\nok\na warn here\n", &rules);
+
+        let html = render_filtered_view_html("app.log", &rules, &matches);
+
+        assert!(html.contains(
+            "<tr style=\"background-color:#ff0000script;color:#000000;font-weight:bold;\">"
+        ));
+        assert!(html.contains("ERROR &lt;boom&gt;"));
+        assert!(html.contains("a <span style=\"background-color:#ffff00;color:#000000;font-weight:bold;\">warn</span> here"));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains(">ok<"));
+    }
+
+    #[test]
+    fn render_filtered_view_csv_should_quote_fields() {
+        let rules =
+            search::compile_filter_rules(vec![make_rule("a,b", "line", "#fff")]).expect("compile");
+        let matches = collect("x a,b \"y\"\nnone\n", &rules);
+
+        assert_eq!(
+            render_filtered_view_csv(&rules, &matches),
+            "line,rule,background_color,text_color,text\n1,\"a,b\",#fff,#000000,\"x a,b \"\"y\"\"\"\n"
+        );
+        assert!(parse_filtered_view_export_format("xlsx").is_err());
+    }
+}
@@ -0,0 +1,307 @@
+// Foldable line ranges for a document.
+//
+// With a tree-sitter grammar every multi-line named node is a fold. Plain text,
+// logs and other documents without a parser fall back to indentation blocks
+// plus multi-line bracket pairs, so YAML-ish configs and indented log dumps
+// can still be folded.
+
+use super::outline::{parse_source_tree, tree_sitter_language_for_syntax};
+use super::syntax::resolve_document_syntax_key;
+use crate::state::AppState;
+use ropey::Rope;
+use std::collections::BTreeMap;
+use tauri::State;
+use tree_sitter::Language;
+
+const FOLD_TAB_WIDTH: usize = 4;
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FoldRange {
+    // 1-based, inclusive.
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoldRangesPayload {
+    pub ranges: Vec<FoldRange>,
+    pub from_syntax_tree: bool,
+    pub document_version: u64,
+}
+
+// Keeps the longest range per start line, which is the one an editor shows.
+struct FoldRangeCollector {
+    min_block_lines: usize,
+    ranges: BTreeMap<usize, usize>,
+}
+
+impl FoldRangeCollector {
+    fn push(&mut self, start_line: usize, end_line: usize) {
+        if end_line < start_line || end_line - start_line + 1 < self.min_block_lines {
+            return;
+        }
+
+        let entry = self.ranges.entry(start_line).or_insert(end_line);
+        *entry = (*entry).max(end_line);
+    }
+
+    fn finish(self) -> Vec<FoldRange> {
+        self.ranges
+            .into_iter()
+            .map(|(start_line, end_line)| FoldRange {
+                start_line,
+                end_line,
+            })
+            .collect()
+    }
+}
+
+// Indent width in columns, or None for a blank line.
+fn line_indent_width(line: ropey::RopeSlice<'_>) -> Option<usize> {
+    let mut width = 0;
+    for ch in line.chars() {
+        match ch {
+            ' ' => width += 1,
+            '\t' => width += FOLD_TAB_WIDTH - width % FOLD_TAB_WIDTH,
+            '\r' | '\n' => return None,
+            _ => return Some(width),
+        }
+    }
+
+    None
+}
+
+// Starts with a closing bracket or an XML end tag, so the line stays visible
+// below the fold.
+fn line_starts_with_closer(line: ropey::RopeSlice<'_>) -> bool {
+    let mut chars = line.chars().skip_while(|ch| *ch == ' ' || *ch == '\t');
+    match chars.next() {
+        Some(')' | ']' | '}') => true,
+        Some('<') => chars.next() == Some('/'),
+        _ => false,
+    }
+}
+
+// A line opens a block when the following non-blank lines are indented deeper;
+// trailing blank lines are left outside the block.
+fn collect_indent_fold_ranges(rope: &Rope, collector: &mut FoldRangeCollector) {
+    let mut open_blocks: Vec<(usize, usize)> = Vec::new();
+    let mut last_content_line = 0;
+
+    for line_index in 0..rope.len_lines() {
+        let Some(indent) = line_indent_width(rope.line(line_index)) else {
+            continue;
+        };
+        let line_number = line_index + 1;
+
+        while let Some(&(start_line, start_indent)) = open_blocks.last() {
+            if start_indent < indent {
+                break;
+            }
+            open_blocks.pop();
+            collector.push(start_line, last_content_line);
+        }
+
+        open_blocks.push((line_number, indent));
+        last_content_line = line_number;
+    }
+
+    for (start_line, _) in open_blocks {
+        collector.push(start_line, last_content_line);
+    }
+}
+
+fn collect_bracket_fold_ranges(rope: &Rope, collector: &mut FoldRangeCollector) {
+    let mut open_brackets: Vec<(char, usize)> = Vec::new();
+
+    for line_index in 0..rope.len_lines() {
+        let line = rope.line(line_index);
+        let line_number = line_index + 1;
+        for ch in line.chars() {
+            let opener = match ch {
+                '(' | '[' | '{' => {
+                    open_brackets.push((ch, line_number));
+                    continue;
+                }
+                ')' => '(',
+                ']' => '[',
+                '}' => '{',
+                _ => continue,
+            };
+
+            // Unbalanced closers in free text are ignored.
+            let Some(position) = open_brackets.iter().rposition(|(open, _)| *open == opener) else {
+                continue;
+            };
+            let (_, start_line) = open_brackets[position];
+            open_brackets.truncate(position);
+            if line_number > start_line {
+                let end_line = if line_starts_with_closer(line) {
+                    line_number - 1
+                } else {
+                    line_number
+                };
+                collector.push(start_line, end_line);
+            }
+        }
+    }
+}
+
+fn collect_syntax_fold_ranges(
+    rope: &Rope,
+    language: &Language,
+    collector: &mut FoldRangeCollector,
+) -> bool {
+    let source: String = rope.chunks().collect();
+    let Ok(tree) = parse_source_tree(&source, language) else {
+        return false;
+    };
+
+    // Iterative walk: deeply nested JSON would overflow a recursive one. The
+    // root spans the whole document and is never a useful fold.
+    let mut cursor = tree.walk();
+    if !cursor.goto_first_child() {
+        return true;
+    }
+    loop {
+        let node = cursor.node();
+        if node.is_named() {
+            let start_row = node.start_position().row;
+            let mut end_row = node.end_position().row;
+            // Nodes that swallow their trailing newline end at column 0.
+            if node.end_position().column == 0 && end_row > start_row {
+                end_row -= 1;
+            }
+            if end_row > start_row {
+                let end_line = if line_starts_with_closer(rope.line(end_row)) {
+                    end_row
+                } else {
+                    end_row + 1
+                };
+                collector.push(start_row + 1, end_line);
+            }
+        }
+
+        if cursor.goto_first_child() || cursor.goto_next_sibling() {
+            continue;
+        }
+        let mut finished = true;
+        while cursor.goto_parent() {
+            if cursor.goto_next_sibling() {
+                finished = false;
+                break;
+            }
+        }
+        if finished {
+            break;
+        }
+    }
+
+    true
+}
+
+fn compute_fold_ranges(
+    rope: &Rope,
+    language: Option<&Language>,
+    min_block_lines: usize,
+) -> (Vec<FoldRange>, bool) {
+    let new_collector = || FoldRangeCollector {
+        min_block_lines: min_block_lines.max(2),
+        ranges: BTreeMap::new(),
+    };
+
+    if let Some(language) = language {
+        let mut collector = new_collector();
+        if collect_syntax_fold_ranges(rope, language, &mut collector) {
+            return (collector.finish(), true);
+        }
+    }
+
+    let mut collector = new_collector();
+    collect_indent_fold_ranges(rope, &mut collector);
+    collect_bracket_fold_ranges(rope, &mut collector);
+    (collector.finish(), false)
+}
+
+pub(super) async fn get_fold_ranges_impl(
+    state: State<'_, AppState>,
+    id: String,
+    min_block_lines: Option<usize>,
+) -> Result<FoldRangesPayload, String> {
+    let (rope, syntax_key, document_version) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (
+            doc.rope.clone(),
+            resolve_document_syntax_key(&doc),
+            doc.document_version,
+        )
+    };
+    let min_block_lines = min_block_lines
+        .map(super::settings::normalize_fold_min_block_lines)
+        .unwrap_or_else(super::config::load_fold_min_block_lines_in_config_impl);
+
+    let (ranges, from_syntax_tree) = tauri::async_runtime::spawn_blocking(move || {
+        let language = tree_sitter_language_for_syntax(&syntax_key);
+        compute_fold_ranges(&rope, language.as_ref(), min_block_lines)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(FoldRangesPayload {
+        ranges,
+        from_syntax_tree,
+        document_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(ranges: &[FoldRange]) -> Vec<(usize, usize)> {
+        ranges
+            .iter()
+            .map(|range| (range.start_line, range.end_line))
+            .collect()
+    }
+
+    #[test]
+    fn compute_fold_ranges_should_fold_indented_blocks_without_parser() {
+        let rope = Rope::from_str(
+            "server:\n  host: a\n  tls:\n    cert: x\n    key: y\n\nclient:\n  retries: 3\n\n",
+        );
+
+        let (ranges, from_syntax_tree) = compute_fold_ranges(&rope, None, 2);
+
+        assert!(!from_syntax_tree);
+        assert_eq!(lines(&ranges), vec![(1, 5), (3, 5), (7, 8)]);
+
+        let (ranges, _) = compute_fold_ranges(&rope, None, 3);
+        assert_eq!(lines(&ranges), vec![(1, 5), (3, 5)]);
+    }
+
+    #[test]
+    fn compute_fold_ranges_should_fold_multi_line_brackets_without_parser() {
+        let rope = Rope::from_str("ERROR payload {\n\"id\": 7,\n\"tags\": [a,\nb]\n}\ndone)\n");
+
+        let (ranges, _) = compute_fold_ranges(&rope, None, 2);
+
+        assert_eq!(lines(&ranges), vec![(1, 4), (3, 4)]);
+    }
+
+    #[test]
+    fn compute_fold_ranges_should_use_syntax_tree_when_available() {
+        let rope = Rope::from_str("{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": 3\n}\n");
+        let language = tree_sitter_language_for_syntax("json").expect("json language");
+
+        let (ranges, from_syntax_tree) = compute_fold_ranges(&rope, Some(&language), 2);
+
+        assert!(from_syntax_tree);
+        assert_eq!(lines(&ranges), vec![(1, 6), (2, 4)]);
+    }
+}
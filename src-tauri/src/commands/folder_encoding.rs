@@ -0,0 +1,218 @@
+// Converts the encoding of every matching file under a folder, e.g. to move a
+// legacy GBK/Big5 tree to UTF-8. Each file is sniffed and detected the same way
+// as when opening it; binaries, uncertain guesses and text that would not
+// round-trip are skipped and reported instead of converted. Progress is sent
+// as `rutar://folder-encoding-progress` events.
+
+use super::file_sniff::{self, SniffedContent};
+use super::folder_walk::{self, FolderBatchFileResult};
+use super::*;
+use chardetng::EncodingDetector;
+use encoding_rs::Encoding;
+use std::path::{Path, PathBuf};
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderEncodingFileResult {
+    pub path: String,
+    // "converted", "wouldConvert", "unchanged", "skipped" or "failed".
+    pub status: String,
+    pub detected_encoding: Option<String>,
+    // "bom", "heuristic", "high", "low", or "specified" when `from` was given.
+    pub confidence: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl FolderBatchFileResult for FolderEncodingFileResult {
+    fn unprocessed(path: &Path, status: &str, reason: String) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            status: status.to_string(),
+            detected_encoding: None,
+            confidence: None,
+            reason: Some(reason),
+        }
+    }
+
+    fn mark_failed(&mut self, reason: String) {
+        self.status = "failed".to_string();
+        self.reason = Some(reason);
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderEncodingReport {
+    pub dry_run: bool,
+    pub target_encoding: String,
+    pub converted_count: usize,
+    pub unchanged_count: usize,
+    pub skipped_count: usize,
+    pub failed_count: usize,
+    pub files: Vec<FolderEncodingFileResult>,
+}
+
+// Returns the encoding to decode with and how it was chosen.
+fn detect_file_encoding(
+    bytes: &[u8],
+    sniffed: SniffedContent,
+) -> (&'static Encoding, &'static str) {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return (encoding, "bom");
+    }
+    if let SniffedContent::Utf16(encoding) = sniffed {
+        return (encoding, "heuristic");
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(
+        &bytes[..bytes.len().min(ENCODING_DETECT_SAMPLE_BYTES)],
+        true,
+    );
+    let (encoding, confident) = detector.guess_assess(None, true);
+    (encoding, if confident { "high" } else { "low" })
+}
+
+// Decides what to do with one file; returns the result and, when the file
+// should be rewritten, the converted bytes.
+fn plan_file_conversion(
+    path: &Path,
+    bytes: &[u8],
+    from: Option<&'static Encoding>,
+    to: &'static Encoding,
+    dry_run: bool,
+) -> (FolderEncodingFileResult, Option<Vec<u8>>) {
+    let mut result = FolderEncodingFileResult {
+        path: path.to_string_lossy().to_string(),
+        status: "skipped".to_string(),
+        detected_encoding: None,
+        confidence: None,
+        reason: None,
+    };
+
+    let sniffed = file_sniff::sniff_content(bytes);
+    if let SniffedContent::Binary(kind) = sniffed {
+        result.reason = Some(format!("binary ({})", kind));
+        return (result, None);
+    }
+
+    let (encoding, confidence) = match from {
+        Some(encoding) => (encoding, "specified"),
+        None => detect_file_encoding(bytes, sniffed),
+    };
+    result.detected_encoding = Some(encoding.name().to_string());
+    result.confidence = Some(confidence.to_string());
+
+    if confidence == "low" {
+        result.reason = Some("uncertain detection".to_string());
+        return (result, None);
+    }
+    let has_bom = Encoding::for_bom(bytes).is_some();
+    if encoding == to && !has_bom {
+        result.status = "unchanged".to_string();
+        return (result, None);
+    }
+
+    let (text, malformed) = encoding.decode_with_bom_removal(bytes);
+    if malformed {
+        result.reason = Some(format!("not valid {}", encoding.name()));
+        return (result, None);
+    }
+    let (converted, _, unmappable) = to.encode(&text);
+    if unmappable {
+        result.reason = Some(format!("characters not representable in {}", to.name()));
+        return (result, None);
+    }
+
+    if dry_run {
+        result.status = "wouldConvert".to_string();
+        return (result, None);
+    }
+    result.status = "converted".to_string();
+    (result, Some(converted.into_owned()))
+}
+
+pub(super) async fn convert_folder_encoding_impl(
+    app: AppHandle,
+    root: String,
+    from: Option<String>,
+    to: String,
+    extensions: Vec<String>,
+    dry_run: bool,
+) -> Result<FolderEncodingReport, String> {
+    let root = PathBuf::from(root.trim());
+    if !root.is_dir() {
+        return Err("Folder not found".to_string());
+    }
+    let from = from
+        .filter(|label| !label.trim().is_empty())
+        .map(|label| file_io::resolve_encoding_label(&label))
+        .transpose()?;
+    let to = file_io::resolve_encoding_label(&to)?;
+    // encoding_rs only decodes UTF-16; encoding to it would silently yield UTF-8.
+    if to.output_encoding() != to {
+        return Err(format!("Unsupported target encoding: {}", to.name()));
+    }
+    let extensions = folder_walk::normalize_extension_filter(extensions);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let files = folder_walk::collect_folder_files(&root, &extensions, false);
+        let results = folder_walk::run_folder_batch(
+            &app,
+            "rutar://folder-encoding-progress",
+            &files,
+            |path, bytes| plan_file_conversion(path, bytes, from, to, dry_run),
+        );
+
+        let count = |status: &str| results.iter().filter(|item| item.status == status).count();
+        FolderEncodingReport {
+            dry_run,
+            target_encoding: to.name().to_string(),
+            converted_count: count("converted") + count("wouldConvert"),
+            unchanged_count: count("unchanged"),
+            skipped_count: count("skipped"),
+            failed_count: count("failed"),
+            files: results,
+        }
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{GBK, UTF_8};
+
+    #[test]
+    fn plan_file_conversion_should_convert_specified_source_and_skip_binaries() {
+        let path = Path::new("a.txt");
+        let (gbk_bytes, _, _) = GBK.encode("中文内容");
+
+        let (result, converted) = plan_file_conversion(path, &gbk_bytes, Some(GBK), UTF_8, false);
+        assert_eq!(result.status, "converted");
+        assert_eq!(converted.as_deref(), Some("中文内容".as_bytes()));
+
+        let (dry_run, converted) = plan_file_conversion(path, &gbk_bytes, Some(GBK), UTF_8, true);
+        assert_eq!(dry_run.status, "wouldConvert");
+        assert!(converted.is_none());
+
+        let (binary, _) = plan_file_conversion(path, b"\x89PNG\r\n\x1a\n", None, UTF_8, false);
+        assert_eq!(binary.status, "skipped");
+        assert_eq!(binary.reason.as_deref(), Some("binary (png)"));
+    }
+
+    #[test]
+    fn plan_file_conversion_should_strip_bom_and_keep_plain_utf8() {
+        let path = Path::new("a.txt");
+
+        let (plain, converted) = plan_file_conversion(path, b"plain", Some(UTF_8), UTF_8, false);
+        assert_eq!(plain.status, "unchanged");
+        assert!(converted.is_none());
+
+        let (with_bom, converted) =
+            plan_file_conversion(path, b"\xEF\xBB\xBFtext", None, UTF_8, false);
+        assert_eq!(with_bom.confidence.as_deref(), Some("bom"));
+        assert_eq!(converted.as_deref(), Some(b"text".as_slice()));
+    }
+}
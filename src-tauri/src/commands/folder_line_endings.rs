@@ -0,0 +1,214 @@
+// Rewrites every line break under a folder to one ending, the folder-wide
+// counterpart of converting a single document. Files are handled as bytes, so
+// any ASCII-compatible encoding keeps its content untouched; binaries and
+// UTF-16 files are skipped. Paths matched by `.gitignore` are left alone and
+// progress is sent as `rutar://folder-line-endings-progress` events.
+
+use super::file_sniff::{self, SniffedContent};
+use super::folder_walk::{self, FolderBatchFileResult};
+use super::*;
+use std::path::{Path, PathBuf};
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderLineEndingsFileResult {
+    pub path: String,
+    // "converted", "wouldConvert", "unchanged", "skipped" or "failed".
+    pub status: String,
+    pub changed_line_endings: usize,
+    pub reason: Option<String>,
+}
+
+impl FolderBatchFileResult for FolderLineEndingsFileResult {
+    fn unprocessed(path: &Path, status: &str, reason: String) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            status: status.to_string(),
+            changed_line_endings: 0,
+            reason: Some(reason),
+        }
+    }
+
+    fn mark_failed(&mut self, reason: String) {
+        self.status = "failed".to_string();
+        self.reason = Some(reason);
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderLineEndingsReport {
+    pub dry_run: bool,
+    pub target_line_ending: String,
+    pub converted_count: usize,
+    pub unchanged_count: usize,
+    pub skipped_count: usize,
+    pub failed_count: usize,
+    pub changed_line_endings: usize,
+    pub files: Vec<FolderLineEndingsFileResult>,
+}
+
+// Returns the rewritten bytes and how many breaks differed from the target.
+fn convert_line_ending_bytes(bytes: &[u8], target: LineEnding) -> (Vec<u8>, usize) {
+    let target_bytes: &[u8] = match target {
+        LineEnding::CrLf => b"\r\n",
+        LineEnding::Lf => b"\n",
+        LineEnding::Cr => b"\r",
+    };
+    let mut converted = Vec::with_capacity(bytes.len());
+    let mut changed = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let line_break: &[u8] = match bytes[index] {
+            b'\r' if bytes.get(index + 1) == Some(&b'\n') => b"\r\n",
+            b'\r' => b"\r",
+            b'\n' => b"\n",
+            byte => {
+                converted.push(byte);
+                index += 1;
+                continue;
+            }
+        };
+        if line_break != target_bytes {
+            changed += 1;
+        }
+        converted.extend_from_slice(target_bytes);
+        index += line_break.len();
+    }
+
+    (converted, changed)
+}
+
+// Decides what to do with one file; returns the result and, when the file
+// should be rewritten, the converted bytes.
+fn plan_file_line_endings(
+    path: &Path,
+    bytes: &[u8],
+    target: LineEnding,
+    dry_run: bool,
+) -> (FolderLineEndingsFileResult, Option<Vec<u8>>) {
+    let mut result = FolderLineEndingsFileResult {
+        path: path.to_string_lossy().to_string(),
+        status: "skipped".to_string(),
+        changed_line_endings: 0,
+        reason: None,
+    };
+
+    match file_sniff::sniff_content(bytes) {
+        SniffedContent::Binary(kind) => {
+            result.reason = Some(format!("binary ({})", kind));
+            return (result, None);
+        }
+        SniffedContent::Utf16(encoding) => {
+            result.reason = Some(format!("{} is not supported", encoding.name()));
+            return (result, None);
+        }
+        _ => {}
+    }
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        if encoding != encoding_rs::UTF_8 {
+            result.reason = Some(format!("{} is not supported", encoding.name()));
+            return (result, None);
+        }
+    }
+
+    let (converted, changed) = convert_line_ending_bytes(bytes, target);
+    result.changed_line_endings = changed;
+    if changed == 0 {
+        result.status = "unchanged".to_string();
+        return (result, None);
+    }
+    if dry_run {
+        result.status = "wouldConvert".to_string();
+        return (result, None);
+    }
+    result.status = "converted".to_string();
+    (result, Some(converted))
+}
+
+pub(super) async fn normalize_folder_line_endings_impl(
+    app: AppHandle,
+    root: String,
+    target: String,
+    extensions: Vec<String>,
+    dry_run: bool,
+) -> Result<FolderLineEndingsReport, String> {
+    let root = PathBuf::from(root.trim());
+    if !root.is_dir() {
+        return Err("Folder not found".to_string());
+    }
+    let target = LineEnding::from_label(&target)
+        .ok_or_else(|| format!("Unsupported line ending: {}", target))?;
+    let extensions = folder_walk::normalize_extension_filter(extensions);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let files = folder_walk::collect_folder_files(&root, &extensions, true);
+        let results = folder_walk::run_folder_batch(
+            &app,
+            "rutar://folder-line-endings-progress",
+            &files,
+            |path, bytes| plan_file_line_endings(path, bytes, target, dry_run),
+        );
+
+        let count = |status: &str| results.iter().filter(|item| item.status == status).count();
+        FolderLineEndingsReport {
+            dry_run,
+            target_line_ending: target.label().to_string(),
+            converted_count: count("converted") + count("wouldConvert"),
+            unchanged_count: count("unchanged"),
+            skipped_count: count("skipped"),
+            failed_count: count("failed"),
+            changed_line_endings: results
+                .iter()
+                .filter(|item| item.status != "failed")
+                .map(|item| item.changed_line_endings)
+                .sum(),
+            files: results,
+        }
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_line_ending_bytes_should_count_only_differing_breaks() {
+        assert_eq!(
+            convert_line_ending_bytes(b"a\r\nb\nc\rd\n", LineEnding::Lf),
+            (b"a\nb\nc\nd\n".to_vec(), 2)
+        );
+        assert_eq!(
+            convert_line_ending_bytes(b"a\nb\r\n", LineEnding::CrLf),
+            (b"a\r\nb\r\n".to_vec(), 1)
+        );
+        assert_eq!(
+            convert_line_ending_bytes(b"no breaks", LineEnding::Cr),
+            (b"no breaks".to_vec(), 0)
+        );
+    }
+
+    #[test]
+    fn plan_file_line_endings_should_skip_binary_and_utf16_files() {
+        let path = Path::new("a.txt");
+
+        let (binary, _) = plan_file_line_endings(path, b"\x89PNG\r\n\x1a\n", LineEnding::Lf, false);
+        assert_eq!(binary.status, "skipped");
+        assert_eq!(binary.reason.as_deref(), Some("binary (png)"));
+
+        let (utf16, _) =
+            plan_file_line_endings(path, b"\xFF\xFEa\x00\r\x00\n\x00", LineEnding::Lf, false);
+        assert_eq!(utf16.status, "skipped");
+
+        let (dry_run, converted) = plan_file_line_endings(path, b"a\r\nb", LineEnding::Lf, true);
+        assert_eq!(dry_run.status, "wouldConvert");
+        assert_eq!(dry_run.changed_line_endings, 1);
+        assert!(converted.is_none());
+
+        let (unchanged, _) = plan_file_line_endings(path, b"a\nb\n", LineEnding::Lf, false);
+        assert_eq!(unchanged.status, "unchanged");
+    }
+}
@@ -0,0 +1,240 @@
+// File collection and the per-file loop shared by the folder-wide batch
+// tools. Tool metadata directories are always skipped; `.gitignore` files can
+// optionally be honored, including nested ones, by git's own rules. The
+// folder does not need to be inside a git repository for that.
+
+use super::constants::LARGE_FILE_THRESHOLD_BYTES;
+use super::file_io;
+use ignore::WalkBuilder;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+// Directories that hold tool metadata rather than project text.
+const SKIPPED_DIRECTORY_NAMES: &[&str] = &[".git", ".svn", ".hg", "node_modules"];
+
+pub(super) fn normalize_extension_filter(extensions: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = extensions
+        .iter()
+        .map(|extension| {
+            extension
+                .trim()
+                .trim_start_matches("*.")
+                .trim_start_matches('.')
+        })
+        .filter(|extension| !extension.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+fn has_listed_extension(path: &Path, extensions: &[String]) -> bool {
+    extensions.is_empty()
+        || path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .is_some_and(|extension| extensions.contains(&extension))
+}
+
+// Sorted files under `root` whose extension is listed (any when empty).
+// Hidden files are included; only the metadata directories are left out.
+pub(super) fn collect_folder_files(
+    root: &Path,
+    extensions: &[String],
+    respect_gitignore: bool,
+) -> Vec<PathBuf> {
+    let walker = WalkBuilder::new(root)
+        .standard_filters(false)
+        .git_ignore(respect_gitignore)
+        .require_git(false)
+        .filter_entry(|entry| {
+            !(entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir())
+                && SKIPPED_DIRECTORY_NAMES
+                    .iter()
+                    .any(|name| entry.file_name() == *name))
+        })
+        .build();
+
+    let mut files: Vec<PathBuf> = walker
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+        })
+        .map(|entry| entry.into_path())
+        .filter(|path| has_listed_extension(path, extensions))
+        .collect();
+    files.sort();
+    files
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FolderBatchProgressEventPayload {
+    processed: usize,
+    total: usize,
+    path: String,
+}
+
+// A batch tool's report entry for one file.
+pub(super) trait FolderBatchFileResult {
+    // An entry for a file that was never handed to the tool; `status` is
+    // "skipped" or "failed".
+    fn unprocessed(path: &Path, status: &str, reason: String) -> Self;
+    fn mark_failed(&mut self, reason: String);
+}
+
+// Runs `plan` on each file and writes back the bytes it returns, sending
+// `progress_event` after every file. Files over the large-file threshold are
+// skipped before being read; rewrites go through a temp file and a rename.
+pub(super) fn run_folder_batch<R: FolderBatchFileResult>(
+    app: &AppHandle,
+    progress_event: &str,
+    files: &[PathBuf],
+    plan: impl Fn(&Path, &[u8]) -> (R, Option<Vec<u8>>),
+) -> Vec<R> {
+    let total = files.len();
+    let mut results = Vec::with_capacity(total);
+
+    for (index, path) in files.iter().enumerate() {
+        let result = match fs::metadata(path) {
+            Ok(metadata) if metadata.len() > LARGE_FILE_THRESHOLD_BYTES as u64 => {
+                R::unprocessed(path, "skipped", "file too large".to_string())
+            }
+            Ok(_) => match fs::read(path) {
+                Ok(bytes) => {
+                    let (mut result, rewritten) = plan(path, &bytes);
+                    if let Some(rewritten) = rewritten {
+                        if let Err(error) = file_io::write_file_atomically(path, &rewritten, None) {
+                            result.mark_failed(error);
+                        }
+                    }
+                    result
+                }
+                Err(error) => R::unprocessed(path, "failed", error.to_string()),
+            },
+            Err(error) => R::unprocessed(path, "failed", error.to_string()),
+        };
+        results.push(result);
+
+        if let Err(error) = app.emit(
+            progress_event,
+            FolderBatchProgressEventPayload {
+                processed: index + 1,
+                total,
+                path: path.to_string_lossy().to_string(),
+            },
+        ) {
+            eprintln!("failed to emit {progress_event} event: {error}");
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_extension_filter_should_strip_prefixes_and_dedup() {
+        assert_eq!(
+            normalize_extension_filter(vec![
+                "*.TXT".to_string(),
+                ".txt".to_string(),
+                " md ".to_string(),
+                "".to_string(),
+            ]),
+            vec!["md".to_string(), "txt".to_string()]
+        );
+    }
+
+    #[test]
+    fn collect_folder_files_should_apply_anchoring_negation_and_directory_rules() {
+        let root =
+            std::env::temp_dir().join(format!("rutar-folder-rules-{}", uuid::Uuid::new_v4()));
+        for directory in ["a", "build", "src/build", "src/target", "docs/sub"] {
+            fs::create_dir_all(root.join(directory)).expect("create dirs");
+        }
+        fs::write(
+            root.join(".gitignore"),
+            "*.log\n!keep.log\n/build\ntarget/\ndocs/*.tmp\n",
+        )
+        .expect("write gitignore");
+        for file in [
+            "a/b.log",
+            "a/keep.log",
+            "build/x.txt",
+            "src/build/x.txt",
+            "src/target/x.txt",
+            "target",
+            "docs/a.tmp",
+            "docs/sub/a.tmp",
+        ] {
+            fs::write(root.join(file), "x").expect("write file");
+        }
+
+        let names: Vec<String> = collect_folder_files(&root, &[], true)
+            .iter()
+            .map(|path| {
+                path.strip_prefix(&root)
+                    .expect("inside root")
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ".gitignore",
+                "a/keep.log",
+                "docs/sub/a.tmp",
+                "src/build/x.txt",
+                "target"
+            ]
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn collect_folder_files_should_honor_nested_gitignore_files() {
+        let root = std::env::temp_dir().join(format!("rutar-folder-walk-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("src/generated")).expect("create dirs");
+        fs::create_dir_all(root.join(".git")).expect("create git dir");
+        fs::write(root.join(".gitignore"), "generated/\n").expect("write gitignore");
+        fs::write(root.join("src/.gitignore"), "*.bak\n").expect("write nested gitignore");
+        fs::write(root.join("src/a.txt"), "a").expect("write file");
+        fs::write(root.join("src/a.bak"), "a").expect("write file");
+        fs::write(root.join("src/generated/b.txt"), "b").expect("write file");
+        fs::write(root.join(".git/config"), "c").expect("write file");
+
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|path| {
+                    path.strip_prefix(&root)
+                        .expect("inside root")
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            names(collect_folder_files(&root, &[], true)),
+            vec![".gitignore", "src/.gitignore", "src/a.txt"]
+        );
+        assert_eq!(
+            names(collect_folder_files(&root, &["txt".to_string()], false)),
+            vec!["src/a.txt", "src/generated/b.txt"]
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,3 +1,4 @@
+use super::ini::format_ini;
 use quick_xml::events::Event;
 use quick_xml::{Reader, Writer};
 use regex::Regex;
@@ -12,6 +13,7 @@ enum StructuredFormat {
     Xml,
     Html,
     Toml,
+    Ini,
 }
 
 fn parse_structured_format_from_syntax_key(syntax_key: &str) -> Option<StructuredFormat> {
@@ -21,6 +23,7 @@ fn parse_structured_format_from_syntax_key(syntax_key: &str) -> Option<Structure
         "xml" => Some(StructuredFormat::Xml),
         "html" => Some(StructuredFormat::Html),
         "toml" => Some(StructuredFormat::Toml),
+        "ini" => Some(StructuredFormat::Ini),
         _ => None,
     }
 }
@@ -140,6 +143,10 @@ fn parse_structured_format_from_name(name: &str) -> Option<StructuredFormat> {
         return Some(StructuredFormat::Toml);
     }
 
+    if lower.ends_with(".ini") || lower.ends_with(".properties") {
+        return Some(StructuredFormat::Ini);
+    }
+
     None
 }
 
@@ -504,6 +511,7 @@ fn format_structured_text(
                 format_toml(source, mode, indent_width)
             }
         }
+        StructuredFormat::Ini => Ok(format_ini(source, matches!(mode, FormatMode::Beautify))),
     }
 }
 
@@ -520,7 +528,7 @@ pub(super) fn format_document_text(
         .ok_or_else(|| "Unsupported format mode. Use beautify or minify".to_string())?;
     let file_format = resolve_structured_format(file_syntax, file_path, file_name, document_path)
         .ok_or_else(|| {
-        "Only JSON, YAML, XML, HTML, and TOML files are supported".to_string()
+        "Only JSON, YAML, XML, HTML, TOML, and INI files are supported".to_string()
     })?;
     let preserve_comments = should_preserve_comments(source, file_format);
 
@@ -620,4 +628,21 @@ mod tests {
         assert!(formatted.contains("\n"));
         assert!(formatted.contains("\"a\": 1"));
     }
+
+    #[test]
+    fn format_should_resolve_properties_files_by_name() {
+        let source = "b.key=2\na.key = 1\n";
+        let result = format_document_text(
+            source,
+            "beautify",
+            None,
+            None,
+            Some("app.properties"),
+            &None,
+            2,
+        );
+
+        let formatted = result.expect("expected properties formatting to succeed");
+        assert_eq!(formatted, "a.key = 1\nb.key = 2\n");
+    }
 }
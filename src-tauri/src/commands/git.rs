@@ -0,0 +1,354 @@
+// Line and file status against git HEAD, read by running the `git` binary.
+// A missing git install and a path outside any repository look the same to
+// callers: no repository root and nothing to mark.
+
+use super::*;
+use similar::{Algorithm, DiffOp, TextDiff};
+use std::path::{Path, PathBuf};
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GitLineChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitLineRange {
+    pub kind: GitLineChangeKind,
+    // 1-based and inclusive. Deleted lines have no range of their own; they
+    // are reported on the line now at their position, or the last line.
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitLineStatusPayload {
+    pub repository_root: Option<String>,
+    pub tracked: bool,
+    pub ranges: Vec<GitLineRange>,
+    pub document_version: u64,
+}
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GitFileStatusKind {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Untracked,
+    Conflicted,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileStatusEntry {
+    pub path: String,
+    pub status: GitFileStatusKind,
+    // Whether the index differs from HEAD, not just the working tree.
+    pub staged: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileStatusPayload {
+    pub repository_root: Option<String>,
+    pub entries: Vec<GitFileStatusEntry>,
+}
+
+fn run_git(directory: &Path, args: &[&str]) -> Option<Vec<u8>> {
+    let mut command = Command::new("git");
+    command
+        .arg("--no-optional-locks")
+        .arg("-C")
+        .arg(directory)
+        .args(args);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command.output().ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+fn find_repository_root(directory: &Path) -> Option<PathBuf> {
+    let stdout = run_git(directory, &["rev-parse", "--show-toplevel"])?;
+    let root = String::from_utf8(stdout).ok()?;
+    let root = root.trim_end();
+    (!root.is_empty()).then(|| PathBuf::from(root))
+}
+
+fn build_git_line_ranges(head_lines: &[String], current_lines: &[String]) -> Vec<GitLineRange> {
+    let head_refs: Vec<&str> = head_lines.iter().map(String::as_str).collect();
+    let current_refs: Vec<&str> = current_lines.iter().map(String::as_str).collect();
+    let diff = TextDiff::configure()
+        .algorithm(Algorithm::Myers)
+        .diff_slices(&head_refs, &current_refs);
+    let last_line = current_lines.len().max(1);
+
+    diff.ops()
+        .iter()
+        .filter_map(|op| match *op {
+            DiffOp::Equal { .. } => None,
+            DiffOp::Insert {
+                new_index, new_len, ..
+            } => Some(GitLineRange {
+                kind: GitLineChangeKind::Added,
+                start_line: new_index + 1,
+                end_line: new_index + new_len,
+            }),
+            DiffOp::Delete { new_index, .. } => {
+                let line = (new_index + 1).min(last_line);
+                Some(GitLineRange {
+                    kind: GitLineChangeKind::Deleted,
+                    start_line: line,
+                    end_line: line,
+                })
+            }
+            DiffOp::Replace {
+                new_index, new_len, ..
+            } => Some(GitLineRange {
+                kind: GitLineChangeKind::Modified,
+                start_line: new_index + 1,
+                end_line: new_index + new_len,
+            }),
+        })
+        .collect()
+}
+
+// Compares the document text, unsaved edits included, with the file at HEAD.
+// Files git does not know about are all added; ignored files are unmarked.
+fn compute_git_line_status(
+    path: &Path,
+    encoding: &'static Encoding,
+    current_lines: &[String],
+) -> (Option<String>, bool, Vec<GitLineRange>) {
+    let (Some(directory), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return (None, false, Vec::new());
+    };
+    let Some(root) = find_repository_root(directory) else {
+        return (None, false, Vec::new());
+    };
+    let repository_root = Some(root.to_string_lossy().to_string());
+    let file_name = file_name.to_string_lossy();
+
+    let head_spec = format!("HEAD:./{file_name}");
+    if let Some(head_bytes) = run_git(directory, &["show", head_spec.as_str()]) {
+        let (head_text, _, _) = encoding.decode(&head_bytes);
+        let head_lines = diff::collect_rope_lines(&Rope::from_str(&head_text));
+        return (
+            repository_root,
+            true,
+            build_git_line_ranges(&head_lines, current_lines),
+        );
+    }
+
+    if run_git(directory, &["check-ignore", "-q", "--", file_name.as_ref()]).is_some() {
+        return (repository_root, false, Vec::new());
+    }
+
+    let ranges = vec![GitLineRange {
+        kind: GitLineChangeKind::Added,
+        start_line: 1,
+        end_line: current_lines.len().max(1),
+    }];
+    (repository_root, false, ranges)
+}
+
+pub(super) async fn get_git_line_status_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<GitLineStatusPayload, String> {
+    let (path, encoding, rope, document_version) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (
+            doc.path.clone(),
+            doc.encoding,
+            doc.rope.clone(),
+            doc.document_version,
+        )
+    };
+
+    let Some(path) = path else {
+        return Ok(GitLineStatusPayload {
+            repository_root: None,
+            tracked: false,
+            ranges: Vec::new(),
+            document_version,
+        });
+    };
+
+    let (repository_root, tracked, ranges) = tauri::async_runtime::spawn_blocking(move || {
+        compute_git_line_status(&path, encoding, &diff::collect_rope_lines(&rope))
+    })
+    .await
+    .map_err(|error| error.to_string())?;
+
+    Ok(GitLineStatusPayload {
+        repository_root,
+        tracked,
+        ranges,
+        document_version,
+    })
+}
+
+fn resolve_git_file_status_kind(index: u8, worktree: u8) -> GitFileStatusKind {
+    match (index, worktree) {
+        (b'?', _) => GitFileStatusKind::Untracked,
+        (b'U', _) | (_, b'U') | (b'A', b'A') | (b'D', b'D') => GitFileStatusKind::Conflicted,
+        (b'R', _) | (b'C', _) => GitFileStatusKind::Renamed,
+        (b'A', _) => GitFileStatusKind::Added,
+        (b'D', _) | (_, b'D') => GitFileStatusKind::Deleted,
+        _ => GitFileStatusKind::Modified,
+    }
+}
+
+// Parses `git status --porcelain=v1 -z`. Paths stay relative to the
+// repository root with `/` separators.
+fn parse_git_status_output(stdout: &[u8]) -> Vec<(String, GitFileStatusKind, bool)> {
+    let text = String::from_utf8_lossy(stdout);
+    let mut fields = text.split('\0');
+    let mut entries = Vec::new();
+
+    while let Some(field) = fields.next() {
+        let bytes = field.as_bytes();
+        let Some(path) = field.get(3..).filter(|path| !path.is_empty()) else {
+            continue;
+        };
+        let (index, worktree) = (bytes[0], bytes[1]);
+        if matches!(index, b'R' | b'C') {
+            // The source path of a rename or copy follows as its own field.
+            fields.next();
+        }
+
+        let staged = !matches!(index, b' ' | b'?');
+        entries.push((
+            path.to_string(),
+            resolve_git_file_status_kind(index, worktree),
+            staged,
+        ));
+    }
+
+    entries
+}
+
+// Status of every changed file under `path`, a folder or file inside the
+// repository, with absolute paths so the folder tree can match its nodes.
+pub(super) async fn get_git_file_status_impl(path: String) -> Result<GitFileStatusPayload, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let target = PathBuf::from(path);
+        let directory = if target.is_dir() {
+            target.as_path()
+        } else {
+            target
+                .parent()
+                .ok_or_else(|| "Failed to resolve parent directory".to_string())?
+        };
+        let Some(root) = find_repository_root(directory) else {
+            return Ok(GitFileStatusPayload {
+                repository_root: None,
+                entries: Vec::new(),
+            });
+        };
+
+        let stdout = run_git(
+            directory,
+            &[
+                "status",
+                "--porcelain=v1",
+                "-z",
+                "--untracked-files=all",
+                "--",
+                ".",
+            ],
+        )
+        .ok_or_else(|| "Failed to read git status".to_string())?;
+
+        let entries = parse_git_status_output(&stdout)
+            .into_iter()
+            .map(|(relative_path, status, staged)| {
+                let mut absolute_path = root.clone();
+                absolute_path.extend(relative_path.split('/'));
+                GitFileStatusEntry {
+                    path: absolute_path.to_string_lossy().to_string(),
+                    status,
+                    staged,
+                }
+            })
+            .collect();
+
+        Ok(GitFileStatusPayload {
+            repository_root: Some(root.to_string_lossy().to_string()),
+            entries,
+        })
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn build_git_line_ranges_should_classify_added_modified_and_deleted_lines() {
+        let head = lines(&["a", "b", "c", "d", ""]);
+        let current = lines(&["a", "B", "c", "new", ""]);
+        let ranges = build_git_line_ranges(&head, &current);
+        let kinds: Vec<_> = ranges
+            .iter()
+            .map(|range| (range.kind, range.start_line, range.end_line))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (GitLineChangeKind::Modified, 2, 2),
+                (GitLineChangeKind::Modified, 4, 4),
+            ]
+        );
+
+        let ranges = build_git_line_ranges(&head, &lines(&["a", "c", "d", "e", ""]));
+        let kinds: Vec<_> = ranges
+            .iter()
+            .map(|range| (range.kind, range.start_line, range.end_line))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (GitLineChangeKind::Deleted, 2, 2),
+                (GitLineChangeKind::Added, 4, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_git_status_output_should_skip_rename_sources() {
+        let stdout = b" M src/a.rs\0R  new.rs\0old.rs\0?? notes.txt\0UU both.rs\0";
+        let entries = parse_git_status_output(stdout);
+
+        assert_eq!(
+            entries,
+            vec![
+                ("src/a.rs".to_string(), GitFileStatusKind::Modified, false),
+                ("new.rs".to_string(), GitFileStatusKind::Renamed, true),
+                ("notes.txt".to_string(), GitFileStatusKind::Untracked, false),
+                ("both.rs".to_string(), GitFileStatusKind::Conflicted, true),
+            ]
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use super::*;
+
+#[tauri::command]
+pub async fn get_git_line_status(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    _timer: CommandTimer,
+) -> Result<git::GitLineStatusPayload, String> {
+    git::get_git_line_status_impl(state, id).await
+}
+
+#[tauri::command]
+pub async fn get_git_file_status(
+    path: String,
+    _timer: CommandTimer,
+) -> Result<git::GitFileStatusPayload, String> {
+    git::get_git_file_status_impl(path).await
+}
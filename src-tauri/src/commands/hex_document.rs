@@ -0,0 +1,509 @@
+// Hex view for binary files. When content sniffing reports a binary file and
+// the caller passes `hexView`, `open_file` maps it here instead of failing
+// with "Not a text file", and
+// `get_hex_view_chunk` formats rows of offset, hex and ASCII columns straight
+// from the mapping. Like streaming documents, hex documents live outside the
+// text document map, so text editing commands reject their ids.
+//
+// `edit_bytes` patches the raw bytes with its own undo history. The first
+// edit copies the file into memory and releases the mapping, so the file can
+// be written back by `save_file` without going through any text decoding.
+// Saving replaces the file atomically and refuses when it changed on disk
+// since it was opened or last saved.
+
+use super::file_sniff::SniffedContent;
+use super::streaming_document::ensure_mapping_not_truncated;
+use super::types::{FileDecodeStats, LineEndingCounts};
+use super::*;
+use crate::state::FileFingerprint;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+const HEX_VIEW_BYTES_PER_ROW: usize = 16;
+const HEX_VIEW_MAX_BYTES_PER_REQUEST: usize = 64 * 1024;
+
+pub(super) struct HexDocument {
+    path: PathBuf,
+    // What sniffing recognised, e.g. "png", or "binary" for unknown formats.
+    detected_file_type: &'static str,
+    data: RwLock<HexData>,
+}
+
+enum HexSource {
+    // The file is kept open so a truncation on disk is noticed before the
+    // mapping is read.
+    Mapped { file: File, mmap: Mmap },
+    Owned(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ByteEdit {
+    offset: usize,
+    old_bytes: Vec<u8>,
+    new_bytes: Vec<u8>,
+}
+
+impl ByteEdit {
+    fn inverse(&self) -> Self {
+        Self {
+            offset: self.offset,
+            old_bytes: self.new_bytes.clone(),
+            new_bytes: self.old_bytes.clone(),
+        }
+    }
+}
+
+struct HexData {
+    source: HexSource,
+    // Oldest edit first; trimmed from the front past the history limit.
+    undo_stack: VecDeque<ByteEdit>,
+    redo_stack: Vec<ByteEdit>,
+    version: u64,
+    saved_version: u64,
+    // Size and mtime on disk when the file was opened or last saved.
+    saved_fingerprint: Option<FileFingerprint>,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HexViewRow {
+    pub offset: u64,
+    // Space-separated bytes, with an extra space after the eighth.
+    pub hex: String,
+    // Printable ASCII as is, anything else as '.'.
+    pub ascii: String,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexViewChunkPayload {
+    pub rows: Vec<HexViewRow>,
+    pub total_bytes: u64,
+    pub version: u64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexEditResultPayload {
+    pub total_bytes: u64,
+    pub version: u64,
+    pub is_dirty: bool,
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+fn hex_documents() -> &'static DashMap<String, Arc<HexDocument>> {
+    static MAP: OnceLock<DashMap<String, Arc<HexDocument>>> = OnceLock::new();
+    MAP.get_or_init(DashMap::new)
+}
+
+fn hex_document(id: &str) -> Result<Arc<HexDocument>, String> {
+    hex_documents()
+        .get(id)
+        .map(|entry| Arc::clone(entry.value()))
+        .ok_or_else(|| "Hex document not found".to_string())
+}
+
+pub(super) fn is_hex_document(id: &str) -> bool {
+    hex_documents().contains_key(id)
+}
+
+// Returns None for files that sniff as text.
+pub(super) fn map_hex_document(
+    path: &Path,
+    share_mode: FileShareMode,
+) -> Result<Option<HexDocument>, String> {
+    let file = file_io::open_file_with_share_mode(path, share_mode, false)
+        .map_err(|error| file_io::describe_file_open_error(path, error))?;
+    let mmap = unsafe { Mmap::map(&file).map_err(|e| e.to_string())? };
+    let SniffedContent::Binary(detected_file_type) = file_sniff::sniff_content(&mmap) else {
+        return Ok(None);
+    };
+    let fingerprint = file
+        .metadata()
+        .ok()
+        .map(|metadata| file_io::build_file_fingerprint(&metadata));
+
+    let mut data = HexData::new(HexSource::Mapped { file, mmap });
+    data.saved_fingerprint = fingerprint;
+    Ok(Some(HexDocument {
+        path: path.to_path_buf(),
+        detected_file_type,
+        data: RwLock::new(data),
+    }))
+}
+
+fn build_hex_file_info(id: String, document: &HexDocument) -> FileInfo {
+    let size_bytes = document.read_data().bytes().len();
+    FileInfo {
+        id,
+        path: document.path.to_string_lossy().to_string(),
+        name: document
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        encoding: "binary".to_string(),
+        line_ending: default_line_ending().label().to_string(),
+        // One line per hex view row.
+        line_count: size_bytes.div_ceil(HEX_VIEW_BYTES_PER_ROW).max(1),
+        size_bytes: size_bytes as u64,
+        large_file_mode: size_bytes > LARGE_FILE_THRESHOLD_BYTES,
+        syntax_override: None,
+        suggested_syntax: None,
+        decode_stats: Some(FileDecodeStats {
+            encoding_confidence: "high".to_string(),
+            line_ending_counts: LineEndingCounts::default(),
+            has_bom: false,
+            had_malformed_sequences: false,
+            raw_bytes_preserved: false,
+            detected_file_type: Some(document.detected_file_type.to_string()),
+        }),
+        preview_mode: true,
+        streaming: false,
+        hex_view: true,
+    }
+}
+
+pub(super) fn register_hex_document(document: HexDocument) -> FileInfo {
+    let id = Uuid::new_v4().to_string();
+    let file_info = build_hex_file_info(id.clone(), &document);
+    hex_documents().insert(id, Arc::new(document));
+    file_info
+}
+
+pub(super) fn find_hex_document_by_path(path: &Path) -> Option<FileInfo> {
+    hex_documents()
+        .iter()
+        .find(|entry| entry.value().path == path)
+        .map(|entry| build_hex_file_info(entry.key().clone(), entry.value()))
+}
+
+fn format_hex_row(offset: usize, bytes: &[u8]) -> HexViewRow {
+    let mut hex = String::with_capacity(HEX_VIEW_BYTES_PER_ROW * 3 + 1);
+    for (index, byte) in bytes.iter().enumerate() {
+        if index > 0 {
+            hex.push(' ');
+        }
+        if index == HEX_VIEW_BYTES_PER_ROW / 2 {
+            hex.push(' ');
+        }
+        hex.push_str(&format!("{byte:02X}"));
+    }
+
+    let ascii = bytes
+        .iter()
+        .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+
+    HexViewRow {
+        offset: offset as u64,
+        hex,
+        ascii,
+    }
+}
+
+// `start_offset` is rounded down to a row boundary so rows always line up.
+fn hex_rows(bytes: &[u8], start_offset: u64, length: usize) -> Vec<HexViewRow> {
+    let start = usize::try_from(start_offset)
+        .unwrap_or(usize::MAX)
+        .min(bytes.len());
+    let start = start - start % HEX_VIEW_BYTES_PER_ROW;
+    let end = start
+        .saturating_add(length.min(HEX_VIEW_MAX_BYTES_PER_REQUEST))
+        .min(bytes.len());
+
+    bytes[start..end]
+        .chunks(HEX_VIEW_BYTES_PER_ROW)
+        .enumerate()
+        .map(|(index, row)| format_hex_row(start + index * HEX_VIEW_BYTES_PER_ROW, row))
+        .collect()
+}
+
+impl HexData {
+    fn new(source: HexSource) -> Self {
+        Self {
+            source,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            version: 0,
+            saved_version: 0,
+            saved_fingerprint: None,
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match &self.source {
+            HexSource::Mapped { mmap, .. } => mmap,
+            HexSource::Owned(bytes) => bytes,
+        }
+    }
+
+    fn ensure_readable(&self, path: &Path) -> Result<(), String> {
+        match &self.source {
+            HexSource::Mapped { file, mmap } => ensure_mapping_not_truncated(file, mmap, path),
+            HexSource::Owned(_) => Ok(()),
+        }
+    }
+
+    // Copies the mapped bytes into memory; dropping the mapping also lets the
+    // file be overwritten on save.
+    fn owned_bytes(&mut self) -> &mut Vec<u8> {
+        if let HexSource::Mapped { mmap, .. } = &self.source {
+            self.source = HexSource::Owned(mmap.to_vec());
+        }
+        match &mut self.source {
+            HexSource::Owned(bytes) => bytes,
+            HexSource::Mapped { .. } => unreachable!("source was just made owned"),
+        }
+    }
+
+    fn apply(&mut self, edit: &ByteEdit) -> Result<(), String> {
+        let end = edit
+            .offset
+            .checked_add(edit.old_bytes.len())
+            .ok_or_else(|| "Edit range overflow".to_string())?;
+        if end > self.bytes().len() {
+            return Err("Edit range out of bounds".to_string());
+        }
+        if self.bytes()[edit.offset..end] != edit.old_bytes[..] {
+            return Err("Bytes at the edit offset have changed".to_string());
+        }
+
+        self.owned_bytes()
+            .splice(edit.offset..end, edit.new_bytes.iter().copied());
+        self.version += 1;
+        Ok(())
+    }
+
+    fn edit(&mut self, edit: ByteEdit) -> Result<(), String> {
+        self.apply(&edit)?;
+        self.undo_stack.push_back(edit);
+        if self.undo_stack.len() > DEFAULT_MAX_UNDO_HISTORY {
+            self.undo_stack.pop_front();
+        }
+        self.redo_stack.clear();
+        Ok(())
+    }
+
+    // Returns false when there was nothing to undo.
+    fn undo(&mut self) -> Result<bool, String> {
+        let Some(edit) = self.undo_stack.pop_back() else {
+            return Ok(false);
+        };
+        if let Err(error) = self.apply(&edit.inverse()) {
+            self.undo_stack.push_back(edit);
+            return Err(error);
+        }
+        self.redo_stack.push(edit);
+        Ok(true)
+    }
+
+    fn redo(&mut self) -> Result<bool, String> {
+        let Some(edit) = self.redo_stack.pop() else {
+            return Ok(false);
+        };
+        if let Err(error) = self.apply(&edit) {
+            self.redo_stack.push(edit);
+            return Err(error);
+        }
+        self.undo_stack.push_back(edit);
+        Ok(true)
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.version != self.saved_version
+    }
+
+    fn result_payload(&self) -> HexEditResultPayload {
+        HexEditResultPayload {
+            total_bytes: self.bytes().len() as u64,
+            version: self.version,
+            is_dirty: self.is_dirty(),
+            can_undo: !self.undo_stack.is_empty(),
+            can_redo: !self.redo_stack.is_empty(),
+        }
+    }
+}
+
+impl HexDocument {
+    fn read_data(&self) -> std::sync::RwLockReadGuard<'_, HexData> {
+        self.data
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write_data(&self) -> std::sync::RwLockWriteGuard<'_, HexData> {
+        self.data
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub(super) fn get_hex_view_chunk_impl(
+    id: String,
+    start_offset: u64,
+    length: usize,
+) -> Result<HexViewChunkPayload, String> {
+    let document = hex_document(&id)?;
+    let data = document.read_data();
+    data.ensure_readable(&document.path)?;
+
+    Ok(HexViewChunkPayload {
+        rows: hex_rows(data.bytes(), start_offset, length),
+        total_bytes: data.bytes().len() as u64,
+        version: data.version,
+    })
+}
+
+// `old_bytes` must match what is at `offset`, like the old text of a text
+// edit; the lengths may differ, so bytes can be inserted or removed too.
+pub(super) fn edit_bytes_impl(
+    id: String,
+    offset: u64,
+    old_bytes: Vec<u8>,
+    new_bytes: Vec<u8>,
+) -> Result<HexEditResultPayload, String> {
+    let document = hex_document(&id)?;
+    let mut data = document.write_data();
+    data.ensure_readable(&document.path)?;
+    if old_bytes != new_bytes {
+        data.edit(ByteEdit {
+            offset: usize::try_from(offset).map_err(|_| "Edit range out of bounds".to_string())?,
+            old_bytes,
+            new_bytes,
+        })?;
+    }
+
+    Ok(data.result_payload())
+}
+
+pub(super) fn undo_byte_edit_impl(id: String) -> Result<HexEditResultPayload, String> {
+    let document = hex_document(&id)?;
+    let mut data = document.write_data();
+    data.undo()?;
+    Ok(data.result_payload())
+}
+
+pub(super) fn redo_byte_edit_impl(id: String) -> Result<HexEditResultPayload, String> {
+    let document = hex_document(&id)?;
+    let mut data = document.write_data();
+    data.redo()?;
+    Ok(data.result_payload())
+}
+
+// None when `id` is not a hex document.
+pub(super) fn is_hex_document_dirty(id: &str) -> Option<bool> {
+    hex_documents()
+        .get(id)
+        .map(|document| document.read_data().is_dirty())
+}
+
+// Reached through `save_file` and `save_files`. An unedited file is left
+// alone.
+pub(super) async fn save_hex_document_impl(id: String) -> Result<(), String> {
+    let document = hex_document(&id)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut data = document.write_data();
+        if matches!(data.source, HexSource::Mapped { .. }) {
+            return Ok(());
+        }
+
+        let current_fingerprint = fs::metadata(&document.path)
+            .ok()
+            .map(|metadata| file_io::build_file_fingerprint(&metadata));
+        if current_fingerprint != data.saved_fingerprint {
+            return Err(format!(
+                "{} was changed on disk; reopen it before saving",
+                document.path.to_string_lossy()
+            ));
+        }
+
+        file_io::write_file_atomically(&document.path, data.bytes(), None)?;
+        data.saved_fingerprint = fs::metadata(&document.path)
+            .ok()
+            .map(|metadata| file_io::build_file_fingerprint(&metadata));
+        data.saved_version = data.version;
+        Ok(())
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+pub(super) fn close_hex_document(id: &str) -> bool {
+    hex_documents().remove(id).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn byte_edit(offset: usize, old_bytes: &[u8], new_bytes: &[u8]) -> ByteEdit {
+        ByteEdit {
+            offset,
+            old_bytes: old_bytes.to_vec(),
+            new_bytes: new_bytes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn hex_rows_should_align_to_rows_and_mask_unprintable_bytes() {
+        let bytes: Vec<u8> = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"
+            .iter()
+            .copied()
+            .chain(b"tail ~".iter().copied())
+            .collect();
+
+        let rows = hex_rows(&bytes, 3, 64);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            HexViewRow {
+                offset: 0,
+                hex: "89 50 4E 47 0D 0A 1A 0A  00 00 00 0D 49 48 44 52".to_string(),
+                ascii: ".PNG........IHDR".to_string(),
+            }
+        );
+        assert_eq!(rows[1].offset, 16);
+        assert_eq!(rows[1].hex, "74 61 69 6C 20 7E");
+        assert_eq!(rows[1].ascii, "tail ~");
+
+        assert_eq!(hex_rows(&bytes, 17, 1).len(), 1);
+        assert!(hex_rows(&bytes, 1000, 16).is_empty());
+    }
+
+    #[test]
+    fn hex_data_should_edit_undo_and_redo_bytes() {
+        let mut data = HexData::new(HexSource::Owned(b"\xEF\xBB\xBFabc\0".to_vec()));
+
+        data.edit(byte_edit(0, b"\xEF\xBB\xBF", b""))
+            .expect("remove BOM");
+        data.edit(byte_edit(2, b"c", b"C")).expect("patch byte");
+        assert_eq!(data.bytes(), b"abC\0");
+        assert!(data.result_payload().is_dirty);
+
+        assert!(data.undo().expect("undo"));
+        assert!(data.undo().expect("undo"));
+        assert!(!data.undo().expect("nothing to undo"));
+        assert_eq!(data.bytes(), b"\xEF\xBB\xBFabc\0");
+
+        assert!(data.redo().expect("redo"));
+        assert_eq!(data.bytes(), b"abc\0");
+        let payload = data.result_payload();
+        assert_eq!((payload.can_undo, payload.can_redo), (true, true));
+
+        assert!(data.edit(byte_edit(1, b"x", b"y")).is_err());
+        assert!(data.edit(byte_edit(3, b"\0\0", b"")).is_err());
+        assert_eq!(data.bytes(), b"abc\0");
+        assert!(data.result_payload().can_redo);
+    }
+}
@@ -0,0 +1,268 @@
+use super::search::evict_stale_search_caches;
+use crate::state::{AppState, Document, EditOperation};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct IdleTrimSettings {
+    // Zero disables idle trimming entirely.
+    pub(super) idle_after: Duration,
+    pub(super) stale_cache_max_age: Duration,
+    // Zero keeps the full undo history.
+    pub(super) max_undo_history: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleMaintenanceReport {
+    pub evicted_cache_entries: usize,
+    pub trimmed_undo_operations: usize,
+    pub reclaimed_bytes: usize,
+}
+
+// Tracks how long the open documents have gone without edits, opens or
+// closes, so trimming runs once per idle period rather than on every tick.
+pub struct IdleActivityTracker {
+    fingerprint: Option<(usize, u64)>,
+    idle_since: Instant,
+    trimmed: bool,
+}
+
+impl Default for IdleActivityTracker {
+    fn default() -> Self {
+        Self {
+            fingerprint: None,
+            idle_since: Instant::now(),
+            trimmed: false,
+        }
+    }
+}
+
+impl IdleActivityTracker {
+    fn observe(&mut self, fingerprint: (usize, u64), now: Instant) -> Duration {
+        if self.fingerprint != Some(fingerprint) {
+            self.fingerprint = Some(fingerprint);
+            self.idle_since = now;
+            self.trimmed = false;
+        }
+
+        now.saturating_duration_since(self.idle_since)
+    }
+
+    fn should_trim(
+        &mut self,
+        fingerprint: (usize, u64),
+        now: Instant,
+        idle_after: Duration,
+    ) -> bool {
+        let idle_for = self.observe(fingerprint, now);
+        !idle_after.is_zero() && !self.trimmed && idle_for >= idle_after
+    }
+}
+
+pub(super) fn load_idle_trim_settings() -> IdleTrimSettings {
+    let (idle_secs, stale_cache_mins, max_undo_history) =
+        super::config::load_idle_trim_settings_in_config_impl();
+    IdleTrimSettings {
+        idle_after: Duration::from_secs(idle_secs),
+        stale_cache_max_age: Duration::from_secs(stale_cache_mins.saturating_mul(60)),
+        max_undo_history,
+    }
+}
+
+fn documents_activity_fingerprint(state: &AppState) -> (usize, u64) {
+    let version_sum = state
+        .documents
+        .iter()
+        .fold(0u64, |sum, entry| sum.wrapping_add(entry.document_version));
+    (state.documents.len(), version_sum)
+}
+
+fn approximate_edit_operation_bytes(operation: &EditOperation) -> usize {
+    std::mem::size_of::<EditOperation>()
+        + operation.old_text.capacity()
+        + operation.new_text.capacity()
+}
+
+// Drops the oldest undo operations beyond `max_undo_history`. When the saved
+// checkpoint falls off the front the document can no longer undo back to its
+// saved state, so the checkpoint is cleared to keep it dirty.
+fn trim_undo_history(doc: &mut Document, max_undo_history: usize) -> (usize, usize) {
+    if max_undo_history == 0 || doc.undo_stack.len() <= max_undo_history {
+        return (0, 0);
+    }
+
+    let excess = doc.undo_stack.len() - max_undo_history;
+    let reclaimed_bytes = doc
+        .undo_stack
+        .drain(..excess)
+        .map(|operation| approximate_edit_operation_bytes(&operation))
+        .sum();
+    doc.undo_stack.shrink_to_fit();
+
+    // The saved state is the one after the operation at `saved_depth - 1`. When
+    // that was the last dropped operation the state is the new bottom of the
+    // stack, which has no operation to identify it.
+    match doc
+        .saved_undo_depth
+        .and_then(|saved_depth| saved_depth.checked_sub(excess))
+    {
+        Some(0) => {
+            doc.saved_undo_depth = Some(0);
+            doc.saved_undo_operation_id = None;
+        }
+        Some(saved_depth) => doc.saved_undo_depth = Some(saved_depth),
+        None => {
+            doc.saved_undo_depth = None;
+            doc.saved_undo_operation_id = None;
+        }
+    }
+
+    (excess, reclaimed_bytes)
+}
+
+pub(super) fn run_idle_maintenance_impl(
+    state: &AppState,
+    settings: IdleTrimSettings,
+) -> IdleMaintenanceReport {
+    let cache_stats = evict_stale_search_caches(settings.stale_cache_max_age);
+    let mut report = IdleMaintenanceReport {
+        evicted_cache_entries: cache_stats.removed_entries,
+        trimmed_undo_operations: 0,
+        reclaimed_bytes: cache_stats.reclaimed_bytes,
+    };
+
+    for mut entry in state.documents.iter_mut() {
+        let (removed, reclaimed_bytes) =
+            trim_undo_history(entry.value_mut(), settings.max_undo_history);
+        report.trimmed_undo_operations += removed;
+        report.reclaimed_bytes += reclaimed_bytes;
+
+        let snapshot_bytes = entry
+            .text_snapshot
+            .clear_if_unused_for(settings.stale_cache_max_age);
+        if snapshot_bytes > 0 {
+            report.evicted_cache_entries += 1;
+            report.reclaimed_bytes += snapshot_bytes;
+        }
+    }
+
+    report
+}
+
+// Called periodically from the maintenance thread; trims and notifies the
+// frontend once the documents have been idle for the configured time.
+pub(super) fn idle_maintenance_tick_impl(app: &AppHandle, tracker: &mut IdleActivityTracker) {
+    let settings = load_idle_trim_settings();
+    let state = app.state::<AppState>();
+    let fingerprint = documents_activity_fingerprint(&state);
+    if !tracker.should_trim(fingerprint, Instant::now(), settings.idle_after) {
+        return;
+    }
+
+    tracker.trimmed = true;
+    let report = run_idle_maintenance_impl(&state, settings);
+
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(error) = window.emit("rutar://idle-maintenance", report) {
+            eprintln!("failed to emit idle-maintenance event: {error}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_insert(doc: &mut Document, text: &str) {
+        let operation_id = doc.allocate_edit_operation_id();
+        doc.undo_stack.push(EditOperation {
+            operation_id,
+            start_char: 0,
+            old_text: String::new(),
+            new_text: text.to_string(),
+            before_cursor: None,
+            after_cursor: None,
+            group_id: None,
+            applied_at_ms: 0,
+        });
+    }
+
+    #[test]
+    fn trim_undo_history_should_keep_saved_checkpoint_when_still_reachable() {
+        let mut doc = Document::for_test("", None);
+        for text in ["a", "b", "c", "d"] {
+            push_insert(&mut doc, text);
+        }
+        doc.mark_saved_undo_checkpoint();
+
+        let (removed, reclaimed_bytes) = trim_undo_history(&mut doc, 2);
+
+        assert_eq!(removed, 2);
+        assert!(reclaimed_bytes > 0);
+        assert_eq!(doc.undo_stack.len(), 2);
+        assert_eq!(doc.undo_stack[0].new_text, "c");
+        assert!(!doc.has_unsaved_text_changes());
+    }
+
+    #[test]
+    fn trim_undo_history_should_keep_document_dirty_when_checkpoint_is_dropped() {
+        let mut doc = Document::for_test("", None);
+        doc.mark_saved_undo_checkpoint();
+        for text in ["a", "b", "c"] {
+            push_insert(&mut doc, text);
+        }
+
+        assert_eq!(trim_undo_history(&mut doc, 1).0, 2);
+        assert_eq!(doc.saved_undo_depth, None);
+        assert!(doc.has_unsaved_text_changes());
+        assert_eq!(doc.save_point_distance(), None);
+
+        doc.undo_stack.clear();
+        assert!(doc.has_unsaved_text_changes());
+    }
+
+    #[test]
+    fn trim_undo_history_should_keep_checkpoint_at_new_bottom_of_stack() {
+        let mut doc = Document::for_test("", None);
+        for text in ["a", "b"] {
+            push_insert(&mut doc, text);
+        }
+        doc.mark_saved_undo_checkpoint();
+        push_insert(&mut doc, "c");
+
+        assert_eq!(trim_undo_history(&mut doc, 1).0, 2);
+        assert_eq!(doc.saved_undo_depth, Some(0));
+        assert!(doc.has_unsaved_text_changes());
+        assert_eq!(doc.save_point_distance(), Some(1));
+
+        doc.undo_stack.clear();
+        assert!(!doc.has_unsaved_text_changes());
+    }
+
+    #[test]
+    fn trim_undo_history_should_ignore_unlimited_setting() {
+        let mut doc = Document::for_test("", None);
+        push_insert(&mut doc, "a");
+
+        assert_eq!(trim_undo_history(&mut doc, 0), (0, 0));
+        assert_eq!(doc.undo_stack.len(), 1);
+    }
+
+    #[test]
+    fn idle_activity_tracker_should_trim_once_per_idle_period() {
+        let mut tracker = IdleActivityTracker::default();
+        let start = Instant::now();
+        let idle_after = Duration::from_secs(60);
+
+        assert!(!tracker.should_trim((1, 0), start, idle_after));
+        assert!(tracker.should_trim((1, 0), start + idle_after, idle_after));
+
+        tracker.trimmed = true;
+        assert!(!tracker.should_trim((1, 0), start + idle_after * 2, idle_after));
+
+        assert!(!tracker.should_trim((1, 1), start + idle_after * 2, idle_after));
+        assert!(tracker.should_trim((1, 1), start + idle_after * 3, idle_after));
+        assert!(!tracker.should_trim((1, 1), start + idle_after * 3, Duration::ZERO));
+    }
+}
@@ -0,0 +1,401 @@
+// Line model of INI and Java `.properties` files, shared by formatting, key
+// paths and duplicate-key validation. Both formats use the `ini` syntax key.
+// `.properties` syntax (`key: value`, `!` comments, `\` continuations) is not
+// INI, so lines are classified by hand rather than with the INI grammar.
+
+use super::syntax::resolve_document_syntax_key;
+use crate::state::AppState;
+use std::collections::HashMap;
+use tauri::State;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum IniLineKind<'a> {
+    Blank,
+    Comment,
+    Section {
+        name: &'a str,
+    },
+    // Offsets are bytes into the line. Keys without a separator are flags such
+    // as `skip-networking` and have an empty value.
+    Entry {
+        key: &'a str,
+        key_start: usize,
+        separator: Option<char>,
+        value: &'a str,
+        value_start: usize,
+    },
+    // Follows a line ending in an unescaped `\`.
+    Continuation,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct IniLine<'a> {
+    // Without the line break.
+    pub text: &'a str,
+    pub kind: IniLineKind<'a>,
+}
+
+// An entry with its section and the line its value ends on.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct IniEntry<'a> {
+    pub section: Option<&'a str>,
+    pub key: &'a str,
+    pub line_index: usize,
+    pub key_start: usize,
+    pub value_start: usize,
+    pub end_line_index: usize,
+    pub value_end: usize,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IniDuplicateKey {
+    pub section: Option<String>,
+    pub key: String,
+    // 1-based; the column counts UTF-16 units like the editor.
+    pub line: usize,
+    pub column: usize,
+    pub first_line: usize,
+}
+
+fn ends_with_line_continuation(text: &str) -> bool {
+    let trailing = text
+        .trim_end()
+        .chars()
+        .rev()
+        .take_while(|ch| *ch == '\\')
+        .count();
+    trailing % 2 == 1
+}
+
+// First `=` or `:` that is not escaped with `\`, as `.properties` allows.
+fn find_ini_separator(text: &str) -> Option<(usize, char)> {
+    let mut escaped = false;
+    for (index, ch) in text.char_indices() {
+        match ch {
+            '\\' if !escaped => escaped = true,
+            '=' | ':' if !escaped => return Some((index, ch)),
+            _ => escaped = false,
+        }
+    }
+
+    None
+}
+
+fn classify_ini_line(text: &str) -> IniLineKind<'_> {
+    let trimmed_start = text.trim_start();
+    let indent = text.len() - trimmed_start.len();
+    let trimmed = trimmed_start.trim_end();
+
+    if trimmed.is_empty() {
+        return IniLineKind::Blank;
+    }
+    if trimmed.starts_with([';', '#', '!']) {
+        return IniLineKind::Comment;
+    }
+    if let Some(rest) = trimmed.strip_prefix('[') {
+        if let Some(end) = rest.find(']') {
+            return IniLineKind::Section {
+                name: rest[..end].trim(),
+            };
+        }
+    }
+
+    match find_ini_separator(trimmed) {
+        Some((position, separator)) => {
+            let raw_value = &trimmed[position + separator.len_utf8()..];
+            let value = raw_value.trim_start();
+            IniLineKind::Entry {
+                key: trimmed[..position].trim_end(),
+                key_start: indent,
+                separator: Some(separator),
+                value,
+                value_start: indent + trimmed.len() - value.len(),
+            }
+        }
+        None => IniLineKind::Entry {
+            key: trimmed,
+            key_start: indent,
+            separator: None,
+            value: "",
+            value_start: indent + trimmed.len(),
+        },
+    }
+}
+
+// Line indices match the document's, as long as it has no lone `\r` breaks.
+pub(super) fn parse_ini_lines(source: &str) -> Vec<IniLine<'_>> {
+    let mut lines = Vec::new();
+    let mut continues = false;
+
+    for text in source.lines() {
+        let kind = if continues {
+            IniLineKind::Continuation
+        } else {
+            classify_ini_line(text)
+        };
+        continues = matches!(kind, IniLineKind::Entry { .. } | IniLineKind::Continuation)
+            && ends_with_line_continuation(text);
+        lines.push(IniLine { text, kind });
+    }
+
+    lines
+}
+
+pub(super) fn collect_ini_entries<'a>(lines: &[IniLine<'a>]) -> Vec<IniEntry<'a>> {
+    let mut entries: Vec<IniEntry<'a>> = Vec::new();
+    let mut section = None;
+
+    for (line_index, line) in lines.iter().enumerate() {
+        match line.kind {
+            IniLineKind::Section { name } => section = Some(name),
+            IniLineKind::Entry {
+                key,
+                key_start,
+                value_start,
+                ..
+            } => entries.push(IniEntry {
+                section,
+                key,
+                line_index,
+                key_start,
+                value_start,
+                end_line_index: line_index,
+                value_end: line.text.trim_end().len(),
+            }),
+            IniLineKind::Continuation => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.end_line_index = line_index;
+                    entry.value_end = line.text.trim_end().len();
+                }
+            }
+            IniLineKind::Blank | IniLineKind::Comment => {}
+        }
+    }
+
+    entries
+}
+
+fn find_ini_duplicate_keys(source: &str) -> Vec<IniDuplicateKey> {
+    let lines = parse_ini_lines(source);
+    let mut first_lines: HashMap<(Option<&str>, &str), usize> = HashMap::new();
+    let mut duplicates = Vec::new();
+
+    // Repeated section headers continue the same section.
+    for entry in collect_ini_entries(&lines) {
+        if entry.key.is_empty() {
+            continue;
+        }
+
+        let line = entry.line_index + 1;
+        let first_line = *first_lines
+            .entry((entry.section, entry.key))
+            .or_insert(line);
+        if first_line == line {
+            continue;
+        }
+
+        let text = lines[entry.line_index].text;
+        duplicates.push(IniDuplicateKey {
+            section: entry.section.map(str::to_string),
+            key: entry.key.to_string(),
+            line,
+            column: text[..entry.key_start]
+                .chars()
+                .map(char::len_utf16)
+                .sum::<usize>()
+                + 1,
+            first_line,
+        });
+    }
+
+    duplicates
+}
+
+fn push_formatted_entry(output: &mut Vec<String>, line: &IniLine<'_>, key_width: usize) {
+    let IniLineKind::Entry {
+        key,
+        separator,
+        value,
+        ..
+    } = line.kind
+    else {
+        return;
+    };
+
+    let formatted = match separator {
+        Some(separator) if key_width == 0 => format!("{key}{separator}{value}"),
+        Some(separator) => format!("{key:<key_width$} {separator} {value}"),
+        None => key.to_string(),
+    };
+    output.push(formatted.trim_end().to_string());
+}
+
+// Beautify sorts the entries of each section by key, keeping the comments
+// above an entry with it, aligns the separators and leaves one blank
+// line between sections. Minify drops blank lines and separator padding.
+pub(super) fn format_ini(source: &str, beautify: bool) -> String {
+    let lines = parse_ini_lines(source);
+    let mut output: Vec<String> = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        // A block is an optional section header and the lines up to the next.
+        let mut block_end = index + 1;
+        while block_end < lines.len()
+            && !matches!(lines[block_end].kind, IniLineKind::Section { .. })
+        {
+            block_end += 1;
+        }
+        let (header, body) = match lines[index].kind {
+            IniLineKind::Section { .. } => (Some(&lines[index]), &lines[index + 1..block_end]),
+            _ => (None, &lines[index..block_end]),
+        };
+        index = block_end;
+
+        // Entries with their leading comments and continuation lines.
+        let mut items: Vec<(&str, Vec<&IniLine<'_>>)> = Vec::new();
+        let mut pending_comments: Vec<&IniLine<'_>> = Vec::new();
+        for line in body {
+            match line.kind {
+                IniLineKind::Blank => {}
+                IniLineKind::Comment => pending_comments.push(line),
+                IniLineKind::Entry { key, .. } => {
+                    let mut item = std::mem::take(&mut pending_comments);
+                    item.push(line);
+                    items.push((key, item));
+                }
+                IniLineKind::Continuation => {
+                    if let Some((_, item)) = items.last_mut() {
+                        item.push(line);
+                    } else {
+                        pending_comments.push(line);
+                    }
+                }
+                IniLineKind::Section { .. } => {}
+            }
+        }
+        if header.is_none() && items.is_empty() && pending_comments.is_empty() {
+            continue;
+        }
+
+        let key_width = if beautify {
+            items.sort_by_cached_key(|(key, _)| (key.to_lowercase(), key.to_string()));
+            items
+                .iter()
+                .map(|(key, _)| key.chars().count())
+                .max()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        if beautify && !output.is_empty() {
+            output.push(String::new());
+        }
+        if let Some(header) = header {
+            output.push(header.text.trim().to_string());
+        }
+        for (_, item) in &items {
+            for line in item {
+                match line.kind {
+                    IniLineKind::Entry { .. } => push_formatted_entry(&mut output, line, key_width),
+                    IniLineKind::Continuation => output.push(line.text.trim_end().to_string()),
+                    _ => output.push(line.text.trim().to_string()),
+                }
+            }
+        }
+        for line in pending_comments {
+            output.push(line.text.trim_end().to_string());
+        }
+    }
+
+    let mut formatted = output.join("\n");
+    if source.ends_with('\n') && !formatted.is_empty() {
+        formatted.push('\n');
+    }
+    formatted
+}
+
+pub(super) fn validate_ini_document_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<IniDuplicateKey>, String> {
+    let source = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        if resolve_document_syntax_key(&doc) != "ini" {
+            return Err("Only INI and properties files can be validated".to_string());
+        }
+        doc.rope.to_string()
+    };
+
+    Ok(find_ini_duplicate_keys(&source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ini_lines_should_classify_properties_syntax() {
+        let lines = parse_ini_lines("! note\nurl: http\\://a\nlist = a, \\\n  b\nflag\n");
+        let kinds: Vec<_> = lines.iter().map(|line| line.kind).collect();
+
+        assert_eq!(kinds[0], IniLineKind::Comment);
+        assert_eq!(
+            kinds[1],
+            IniLineKind::Entry {
+                key: "url",
+                key_start: 0,
+                separator: Some(':'),
+                value: "http\\://a",
+                value_start: 5,
+            }
+        );
+        assert!(matches!(kinds[2], IniLineKind::Entry { key: "list", .. }));
+        assert_eq!(kinds[3], IniLineKind::Continuation);
+        assert!(matches!(
+            kinds[4],
+            IniLineKind::Entry {
+                key: "flag",
+                separator: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn find_ini_duplicate_keys_should_report_repeats_per_section() {
+        let source = "name=a\n[db]\nhost=x\nport=1\n[web]\nhost=y\n[db]\n  host = z\nname=b\n";
+
+        let duplicates = find_ini_duplicate_keys(source);
+
+        assert_eq!(
+            duplicates,
+            vec![IniDuplicateKey {
+                section: Some("db".to_string()),
+                key: "host".to_string(),
+                line: 8,
+                column: 3,
+                first_line: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn format_ini_should_sort_and_align_keys_per_section() {
+        let source = "; global\n[server]\nport=80\n\n# bind address\nhost  :  0.0.0.0\ntimeout_ms=5\n[client]\nretries = 3\n";
+
+        assert_eq!(
+            format_ini(source, true),
+            "; global\n\n[server]\n# bind address\nhost       : 0.0.0.0\nport       = 80\ntimeout_ms = 5\n\n[client]\nretries = 3\n"
+        );
+        assert_eq!(
+            format_ini(source, false),
+            "; global\n[server]\nport=80\n# bind address\nhost:0.0.0.0\ntimeout_ms=5\n[client]\nretries=3\n"
+        );
+    }
+}
@@ -0,0 +1,504 @@
+// Field-aware filtering for JSON-lines logs: each line is parsed on its own
+// and compared by a dotted field path instead of a text pattern. Also builds
+// the pretty-printed, read-only view of a JSON-lines document.
+
+use super::*;
+use crate::state::DerivedView;
+use regex::Regex;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+const JSON_LINES_DEFAULT_MAX_RESULTS: usize = 10_000;
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonLineMatch {
+    // 1-based document line.
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    // Requested fields keyed by path; set only when a projection was asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projection: Option<serde_json::Map<String, Value>>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonLinesFilterResultPayload {
+    pub matches: Vec<JsonLineMatch>,
+    pub matched_lines: usize,
+    pub total_lines: usize,
+    // Non-empty lines that were not valid JSON.
+    pub invalid_lines: usize,
+    pub truncated: bool,
+    pub document_version: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum FieldPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+// Accepts `a.b[0].c`; a leading `$.` or `.` is ignored.
+fn parse_field_path(path: &str) -> Result<Vec<FieldPathSegment>, String> {
+    let trimmed = path.trim();
+    let trimmed = trimmed
+        .strip_prefix("$.")
+        .or_else(|| trimmed.strip_prefix('.'))
+        .unwrap_or(trimmed);
+    if trimmed.is_empty() {
+        return Err("Field path is empty".to_string());
+    }
+
+    let mut segments = Vec::new();
+    for part in trimmed.split('.') {
+        let (key, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !key.is_empty() {
+            segments.push(FieldPathSegment::Key(key.to_string()));
+        }
+        while !rest.is_empty() {
+            let close = rest
+                .find(']')
+                .filter(|_| rest.starts_with('['))
+                .ok_or_else(|| format!("Invalid field path: {}", path))?;
+            let index = rest[1..close]
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid array index in field path: {}", path))?;
+            segments.push(FieldPathSegment::Index(index));
+            rest = &rest[close + 1..];
+        }
+        if key.is_empty() && part.is_empty() {
+            return Err(format!("Invalid field path: {}", path));
+        }
+    }
+
+    Ok(segments)
+}
+
+fn resolve_field<'a>(value: &'a Value, segments: &[FieldPathSegment]) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(value, |current, segment| match segment {
+            FieldPathSegment::Key(key) => current.get(key),
+            FieldPathSegment::Index(index) => current.get(index),
+        })
+}
+
+enum JsonFieldOperator {
+    Equals(Value),
+    NotEquals(Value),
+    Compare(Value, &'static [Ordering]),
+    Contains(Value),
+    Exists,
+    Regex(Regex),
+}
+
+// The value is read as JSON when it parses (`500`, `"error"`, `true`) and as a
+// plain string otherwise, so `level == error` works without quoting.
+fn parse_operand(value: Option<&str>) -> Value {
+    let raw = value.unwrap_or("").trim();
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+impl JsonFieldOperator {
+    fn parse(operator: &str, value: Option<&str>) -> Result<Self, String> {
+        Ok(match operator.trim() {
+            "==" | "eq" => Self::Equals(parse_operand(value)),
+            "!=" | "ne" => Self::NotEquals(parse_operand(value)),
+            ">" | "gt" => Self::Compare(parse_operand(value), &[Ordering::Greater]),
+            ">=" | "gte" => {
+                Self::Compare(parse_operand(value), &[Ordering::Greater, Ordering::Equal])
+            }
+            "<" | "lt" => Self::Compare(parse_operand(value), &[Ordering::Less]),
+            "<=" | "lte" => Self::Compare(parse_operand(value), &[Ordering::Less, Ordering::Equal]),
+            "contains" => Self::Contains(parse_operand(value)),
+            "exists" => Self::Exists,
+            "regex" => {
+                Self::Regex(Regex::new(value.unwrap_or("")).map_err(|error| error.to_string())?)
+            }
+            other => return Err(format!("Unsupported JSON field operator: {}", other)),
+        })
+    }
+
+    fn matches(&self, field: Option<&Value>) -> bool {
+        let Some(field) = field else {
+            return matches!(self, Self::NotEquals(_));
+        };
+
+        match self {
+            Self::Equals(expected) => json_values_equal(field, expected),
+            Self::NotEquals(expected) => !json_values_equal(field, expected),
+            Self::Compare(expected, accepted) => compare_json_values(field, expected)
+                .is_some_and(|ordering| accepted.contains(&ordering)),
+            Self::Contains(expected) => match (field, expected) {
+                (Value::String(text), Value::String(needle)) => text.contains(needle.as_str()),
+                (Value::String(text), other) => text.contains(&other.to_string()),
+                (Value::Array(items), expected) => {
+                    items.iter().any(|item| json_values_equal(item, expected))
+                }
+                _ => false,
+            },
+            Self::Exists => true,
+            Self::Regex(regex) => match field {
+                Value::String(text) => regex.is_match(text),
+                other => regex.is_match(&other.to_string()),
+            },
+        }
+    }
+}
+
+fn json_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+// Numbers compare numerically even when one side is a numeric string, since
+// loggers are inconsistent about quoting them.
+fn json_values_equal(field: &Value, expected: &Value) -> bool {
+    if field == expected {
+        return true;
+    }
+
+    match (field, expected) {
+        (Value::Number(_), _) | (_, Value::Number(_)) => {
+            matches!((json_number(field), json_number(expected)), (Some(left), Some(right)) if left == right)
+        }
+        (Value::String(text), other) => *text == other.to_string(),
+        _ => false,
+    }
+}
+
+fn compare_json_values(field: &Value, expected: &Value) -> Option<Ordering> {
+    if let (Some(left), Some(right)) = (json_number(field), json_number(expected)) {
+        return left.partial_cmp(&right);
+    }
+
+    match (field, expected) {
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        _ => None,
+    }
+}
+
+fn filter_json_lines_in_rope(
+    rope: &Rope,
+    segments: &[FieldPathSegment],
+    operator: &JsonFieldOperator,
+    projection: &[(String, Vec<FieldPathSegment>)],
+    include_text: bool,
+    max_results: usize,
+) -> (Vec<JsonLineMatch>, usize, usize, usize) {
+    let mut matches = Vec::new();
+    let mut matched_lines = 0usize;
+    let mut total_lines = 0usize;
+    let mut invalid_lines = 0usize;
+
+    for index in 0..rope.len_lines() {
+        let text = editing::trimmed_line_without_break(rope, index);
+        if text.trim().is_empty() {
+            continue;
+        }
+        total_lines += 1;
+
+        let Ok(value) = serde_json::from_str::<Value>(&text) else {
+            invalid_lines += 1;
+            continue;
+        };
+        if !operator.matches(resolve_field(&value, segments)) {
+            continue;
+        }
+
+        matched_lines += 1;
+        if matches.len() >= max_results {
+            continue;
+        }
+
+        let projection = (!projection.is_empty()).then(|| {
+            projection
+                .iter()
+                .map(|(path, segments)| {
+                    (
+                        path.clone(),
+                        resolve_field(&value, segments)
+                            .cloned()
+                            .unwrap_or(Value::Null),
+                    )
+                })
+                .collect()
+        });
+        matches.push(JsonLineMatch {
+            line: index + 1,
+            text: include_text.then_some(text),
+            projection,
+        });
+    }
+
+    (matches, matched_lines, total_lines, invalid_lines)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn filter_json_lines_impl(
+    state: State<'_, AppState>,
+    id: String,
+    field_path: String,
+    operator: String,
+    value: Option<String>,
+    project_fields: Option<Vec<String>>,
+    include_line_text: Option<bool>,
+    max_results: Option<usize>,
+) -> Result<JsonLinesFilterResultPayload, String> {
+    let segments = parse_field_path(&field_path)?;
+    let operator = JsonFieldOperator::parse(&operator, value.as_deref())?;
+    let projection = project_fields
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| parse_field_path(&path).map(|segments| (path, segments)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let include_text = include_line_text.unwrap_or(projection.is_empty());
+    let max_results = max_results.unwrap_or(JSON_LINES_DEFAULT_MAX_RESULTS).max(1);
+
+    let (rope, document_version) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        (doc.rope.clone(), doc.document_version)
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let (matches, matched_lines, total_lines, invalid_lines) = filter_json_lines_in_rope(
+            &rope,
+            &segments,
+            &operator,
+            &projection,
+            include_text,
+            max_results,
+        );
+
+        JsonLinesFilterResultPayload {
+            truncated: matched_lines > matches.len(),
+            matches,
+            matched_lines,
+            total_lines,
+            invalid_lines,
+            document_version,
+        }
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+// Pretty-prints each non-empty line under a `// line N` header; lines that are
+// not valid JSON are copied as-is. Returns the text and, for every view line,
+// the 1-based source line it came from.
+fn build_json_lines_view(rope: &Rope) -> (String, Vec<usize>) {
+    let mut text = String::new();
+    let mut source_lines = Vec::new();
+
+    for index in 0..rope.len_lines() {
+        let line = editing::trimmed_line_without_break(rope, index);
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let body = serde_json::from_str::<Value>(&line)
+            .ok()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+            .unwrap_or(line);
+        if !text.is_empty() {
+            text.push('\n');
+            source_lines.push(index + 1);
+        }
+        text.push_str(&format!("// line {}\n", index + 1));
+        text.push_str(&body);
+        text.push('\n');
+        source_lines.extend(std::iter::repeat(index + 1).take(body.lines().count() + 1));
+    }
+
+    // The trailing newline leaves one more, empty rope line.
+    source_lines.push(source_lines.last().copied().unwrap_or(1));
+    (text, source_lines)
+}
+
+pub(super) async fn expand_json_lines_view_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<FileInfo, String> {
+    let (rope, name, line_ending) = {
+        let doc = state
+            .documents
+            .get(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        let name = doc
+            .path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Untitled".to_string());
+        (doc.rope.clone(), name, doc.line_ending)
+    };
+
+    let (text, source_lines) =
+        tauri::async_runtime::spawn_blocking(move || build_json_lines_view(&rope))
+            .await
+            .map_err(|error| error.to_string())?;
+
+    let view_rope = Rope::from_str(&text);
+    let line_count = view_rope.len_lines();
+    let view_id = Uuid::new_v4().to_string();
+    let encoding = encoding_rs::UTF_8;
+    state.documents.insert(
+        view_id.clone(),
+        Document {
+            syntax_override: Some("json".to_string()),
+            derived_view: Some(DerivedView {
+                source_id: id,
+                source_lines,
+            }),
+            ..Document::new(view_rope, encoding, line_ending, None)
+        },
+    );
+
+    Ok(FileInfo {
+        id: view_id,
+        path: String::new(),
+        name: format!("{} (JSON view)", name),
+        encoding: encoding.name().to_string(),
+        line_ending: line_ending.label().to_string(),
+        line_count,
+        size_bytes: text.len() as u64,
+        large_file_mode: text.len() > LARGE_FILE_THRESHOLD_BYTES,
+        syntax_override: Some("json".to_string()),
+        suggested_syntax: None,
+        decode_stats: None,
+        preview_mode: false,
+        streaming: false,
+        hex_view: false,
+    })
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivedViewLinePayload {
+    pub source_id: String,
+    pub source_line: usize,
+}
+
+pub(super) fn map_derived_view_line_impl(
+    state: State<'_, AppState>,
+    id: String,
+    line: usize,
+) -> Result<DerivedViewLinePayload, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let view = doc
+        .derived_view
+        .as_ref()
+        .ok_or_else(|| "Document is not a derived view".to_string())?;
+    let source_line = view
+        .source_lines
+        .get(line.max(1) - 1)
+        .or(view.source_lines.last())
+        .copied()
+        .unwrap_or(1);
+
+    Ok(DerivedViewLinePayload {
+        source_id: view.source_id.clone(),
+        source_line,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = concat!(
+        "{\"level\":\"error\",\"latency\":812,\"req\":{\"path\":\"/a\",\"tags\":[\"x\"]}}\n",
+        "{\"level\":\"info\",\"latency\":\"120\",\"req\":{\"path\":\"/b\"}}\n",
+        "not json\n",
+        "\n",
+        "{\"level\":\"error\",\"latency\":40}\n",
+    );
+
+    fn run(path: &str, operator: &str, value: &str) -> Vec<usize> {
+        let rope = Rope::from_str(LOG);
+        let segments = parse_field_path(path).expect("path");
+        let operator = JsonFieldOperator::parse(operator, Some(value)).expect("operator");
+        filter_json_lines_in_rope(&rope, &segments, &operator, &[], false, 100)
+            .0
+            .iter()
+            .map(|item| item.line)
+            .collect()
+    }
+
+    #[test]
+    fn parse_field_path_should_split_keys_and_indexes() {
+        assert_eq!(
+            parse_field_path("$.req.tags[0]"),
+            Ok(vec![
+                FieldPathSegment::Key("req".to_string()),
+                FieldPathSegment::Key("tags".to_string()),
+                FieldPathSegment::Index(0),
+            ])
+        );
+        assert!(parse_field_path("a..b").is_err());
+        assert!(parse_field_path("a[x]").is_err());
+        assert!(parse_field_path(" ").is_err());
+    }
+
+    #[test]
+    fn filter_json_lines_should_compare_strings_numbers_and_nested_fields() {
+        assert_eq!(run("level", "==", "error"), vec![1, 5]);
+        assert_eq!(run("level", "==", "\"info\""), vec![2]);
+        assert_eq!(run("latency", ">", "100"), vec![1, 2]);
+        assert_eq!(run("latency", "<=", "120"), vec![2, 5]);
+        assert_eq!(run("req.path", "!=", "/a"), vec![2, 5]);
+        assert_eq!(run("req.tags", "contains", "x"), vec![1]);
+        assert_eq!(run("req", "exists", ""), vec![1, 2]);
+        assert_eq!(run("req.path", "regex", "^/b$"), vec![2]);
+    }
+
+    #[test]
+    fn filter_json_lines_should_project_fields_and_count_invalid_lines() {
+        let rope = Rope::from_str(LOG);
+        let segments = parse_field_path("level").expect("path");
+        let operator = JsonFieldOperator::parse("==", Some("error")).expect("operator");
+        let projection = vec![(
+            "req.path".to_string(),
+            parse_field_path("req.path").expect("path"),
+        )];
+
+        let (matches, matched_lines, total_lines, invalid_lines) =
+            filter_json_lines_in_rope(&rope, &segments, &operator, &projection, false, 1);
+
+        assert_eq!((matched_lines, total_lines, invalid_lines), (2, 4, 1));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0]
+                .projection
+                .as_ref()
+                .and_then(|fields| fields.get("req.path")),
+            Some(&Value::String("/a".to_string()))
+        );
+        assert!(matches[0].text.is_none());
+    }
+
+    #[test]
+    fn build_json_lines_view_should_pretty_print_and_map_lines_back() {
+        let rope = Rope::from_str("{\"a\":1}\n\nplain text\n");
+        let (text, source_lines) = build_json_lines_view(&rope);
+
+        assert_eq!(
+            text,
+            "// line 1\n{\n  \"a\": 1\n}\n\n// line 3\nplain text\n"
+        );
+        assert_eq!(source_lines.len(), Rope::from_str(&text).len_lines());
+        assert_eq!(source_lines, vec![1, 1, 1, 1, 3, 3, 3, 3]);
+    }
+}
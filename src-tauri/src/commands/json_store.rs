@@ -0,0 +1,68 @@
+// An in-memory value mirrored to a JSON file next to the config file. The file
+// is read on first use and rewritten atomically after each update; safe mode
+// neither reads nor writes it.
+
+use super::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+pub(super) struct JsonStore<T> {
+    file_name: &'static str,
+    // Error returned when the lock is poisoned, e.g. "Line notes are unavailable".
+    unavailable_message: &'static str,
+    value: OnceLock<Mutex<T>>,
+}
+
+impl<T: Default + Serialize + DeserializeOwned> JsonStore<T> {
+    pub(super) const fn new(file_name: &'static str, unavailable_message: &'static str) -> Self {
+        Self {
+            file_name,
+            unavailable_message,
+            value: OnceLock::new(),
+        }
+    }
+
+    fn file_path(&self) -> Result<PathBuf, String> {
+        config::config_dir_path_impl().map(|dir| dir.join(self.file_name))
+    }
+
+    pub(super) fn lock(&self) -> Result<MutexGuard<'_, T>, String> {
+        self.value
+            .get_or_init(|| {
+                let value = if config::is_safe_mode_impl() {
+                    T::default()
+                } else {
+                    self.file_path()
+                        .ok()
+                        .and_then(|path| fs::read_to_string(path).ok())
+                        .and_then(|raw| serde_json::from_str(&raw).ok())
+                        .unwrap_or_default()
+                };
+                Mutex::new(value)
+            })
+            .lock()
+            .map_err(|_| self.unavailable_message.to_string())
+    }
+
+    // Runs `update` under the lock and writes the value out when it succeeds.
+    pub(super) fn update<R>(
+        &self,
+        update: impl FnOnce(&mut T) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let mut value = self.lock()?;
+        let result = update(&mut value)?;
+        if config::is_safe_mode_impl() {
+            return Ok(result);
+        }
+
+        let path = self.file_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let raw = serde_json::to_string(&*value).map_err(|e| e.to_string())?;
+        file_io::write_file_atomically(&path, raw.as_bytes(), None)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,248 @@
+// Reviewer notes attached to lines of a file without touching the file.
+//
+// Each note keeps the text of the line it was written on. When the file
+// changes the note follows that text to the nearest line still holding it and
+// is reported as orphaned once the text is gone. Notes for all files live in
+// `line_notes.json` next to the config file.
+
+use super::file_sniff::SniffedContent;
+use super::json_store::JsonStore;
+use super::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+static LINE_NOTES: JsonStore<BTreeMap<String, Vec<LineNote>>> =
+    JsonStore::new("line_notes.json", "Line notes are unavailable");
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineNote {
+    pub id: String,
+    // 1-based line the note was written on.
+    pub line: usize,
+    pub anchor: String,
+    pub text: String,
+    pub created_at_ms: u64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineNotePayload {
+    pub id: String,
+    // Current 1-based line; None when the anchored text no longer exists.
+    pub line: Option<usize>,
+    pub original_line: usize,
+    pub anchor: String,
+    pub text: String,
+    pub created_at_ms: u64,
+}
+
+fn note_key(path: &str) -> String {
+    fs::canonicalize(path)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+// Only documents sharing the file name are canonicalized to confirm a match,
+// so a lookup does not touch the filesystem once per open document.
+fn find_open_document_rope(state: &AppState, path: &str) -> Option<Rope> {
+    let target = Path::new(path);
+    let file_name = target.file_name()?;
+    let mut key = None;
+    state.documents.iter().find_map(|entry| {
+        let doc = entry.value();
+        let doc_path = doc.path.as_ref()?;
+        if doc_path.file_name() != Some(file_name) {
+            return None;
+        }
+        let key = key.get_or_insert_with(|| note_key(path));
+        (doc_path == target || note_key(&doc_path.to_string_lossy()) == *key)
+            .then(|| doc.rope.clone())
+    })
+}
+
+// Prefers the open document so unsaved edits are taken into account; a file
+// on disk is decoded with its detected encoding, as when it is opened.
+fn load_note_target_rope(state: &AppState, path: &str) -> Result<Rope, String> {
+    if let Some(rope) = find_open_document_rope(state, path) {
+        return Ok(rope);
+    }
+
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let sniffed = file_sniff::sniff_content(&bytes);
+    if let SniffedContent::Binary(kind) = sniffed {
+        return Err(format!("Not a text file ({}): {}", kind, path));
+    }
+    let (encoding, _) = file_io::detect_mapped_encoding(&bytes, sniffed);
+    let (text, _, _) = encoding.decode(&bytes);
+    Ok(Rope::from_str(&text_utils::normalize_to_lf(&text)))
+}
+
+fn resolve_note_line(rope: &Rope, note: &LineNote) -> Option<usize> {
+    let line_count = rope.len_lines();
+    let matches_anchor = |line: usize| {
+        line >= 1
+            && line <= line_count
+            && editing::trimmed_line_without_break(rope, line - 1) == note.anchor
+    };
+
+    if matches_anchor(note.line) {
+        return Some(note.line);
+    }
+    (1..line_count.max(note.line)).find_map(|distance| {
+        if matches_anchor(note.line.saturating_sub(distance)) {
+            Some(note.line - distance)
+        } else if matches_anchor(note.line + distance) {
+            Some(note.line + distance)
+        } else {
+            None
+        }
+    })
+}
+
+fn note_payload(rope: &Rope, note: &LineNote) -> LineNotePayload {
+    LineNotePayload {
+        id: note.id.clone(),
+        line: resolve_note_line(rope, note),
+        original_line: note.line,
+        anchor: note.anchor.clone(),
+        text: note.text.clone(),
+        created_at_ms: note.created_at_ms,
+    }
+}
+
+pub(super) fn add_line_note_impl(
+    state: State<'_, AppState>,
+    path: String,
+    line: usize,
+    text: String,
+) -> Result<LineNotePayload, String> {
+    if text.trim().is_empty() {
+        return Err("Note text must not be empty".to_string());
+    }
+
+    let rope = load_note_target_rope(&state, &path)?;
+    if line == 0 || line > rope.len_lines() {
+        return Err("Line is out of range".to_string());
+    }
+
+    let note = LineNote {
+        id: Uuid::new_v4().to_string(),
+        line,
+        anchor: editing::trimmed_line_without_break(&rope, line - 1),
+        text,
+        created_at_ms: now_unix_millis(),
+    };
+    let payload = note_payload(&rope, &note);
+
+    LINE_NOTES.update(|notes| {
+        notes.entry(note_key(&path)).or_default().push(note);
+        Ok(())
+    })?;
+
+    Ok(payload)
+}
+
+pub(super) fn list_line_notes_impl(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<Vec<LineNotePayload>, String> {
+    let notes = LINE_NOTES
+        .lock()?
+        .get(&note_key(&path))
+        .cloned()
+        .unwrap_or_default();
+    if notes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rope = load_note_target_rope(&state, &path).unwrap_or_else(|_| Rope::new());
+    let mut payloads: Vec<LineNotePayload> =
+        notes.iter().map(|note| note_payload(&rope, note)).collect();
+    payloads.sort_by_key(|note| (note.line.is_none(), note.line, note.created_at_ms));
+    Ok(payloads)
+}
+
+fn render_notes_markdown(path: &str, notes: &[LineNotePayload]) -> String {
+    let mut output = format!("# Notes: {}\n", path);
+    for note in notes {
+        let location = note
+            .line
+            .map(|line| format!("Line {}", line))
+            .unwrap_or_else(|| format!("Line {} (orphaned)", note.original_line));
+        output.push_str(&format!(
+            "\n## {}\n\n> {}\n\n{}\n",
+            location, note.anchor, note.text
+        ));
+    }
+    output
+}
+
+pub(super) fn export_notes_impl(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<String, String> {
+    let notes = list_line_notes_impl(state, path.clone())?;
+    Ok(render_notes_markdown(&path, &notes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_note(line: usize, anchor: &str) -> LineNote {
+        LineNote {
+            id: "note".to_string(),
+            line,
+            anchor: anchor.to_string(),
+            text: "check this".to_string(),
+            created_at_ms: 0,
+        }
+    }
+
+    #[test]
+    fn resolve_note_line_should_follow_anchor_to_nearest_line() {
+        let rope = Rope::from_str("new\nheader\nerror 42\nbody\nerror 42\n");
+
+        assert_eq!(resolve_note_line(&rope, &make_note(2, "header")), Some(2));
+        assert_eq!(resolve_note_line(&rope, &make_note(1, "header")), Some(2));
+        assert_eq!(resolve_note_line(&rope, &make_note(4, "error 42")), Some(3));
+        assert_eq!(resolve_note_line(&rope, &make_note(9, "body")), Some(4));
+        assert_eq!(resolve_note_line(&rope, &make_note(2, "gone")), None);
+    }
+
+    #[test]
+    fn load_note_target_rope_should_decode_files_with_detected_encoding() {
+        let path = std::env::temp_dir().join(format!("rutar-line-notes-{}.txt", Uuid::new_v4()));
+        let mut content = vec![0xFF, 0xFE];
+        content.extend(
+            "first\r\nzweite Zeile\r\n"
+                .encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes()),
+        );
+        fs::write(&path, &content).expect("write UTF-16 file");
+
+        let state = AppState::new(Vec::new());
+        let rope = load_note_target_rope(&state, &path.to_string_lossy());
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            rope.expect("decode file").to_string(),
+            "first\nzweite Zeile\n"
+        );
+    }
+
+    #[test]
+    fn render_notes_markdown_should_mark_orphaned_notes() {
+        let rope = Rope::from_str("a\nb\n");
+        let notes = vec![
+            note_payload(&rope, &make_note(2, "b")),
+            note_payload(&rope, &make_note(5, "z")),
+        ];
+
+        let markdown = render_notes_markdown("log.txt", &notes);
+        assert!(markdown.starts_with("# Notes: log.txt\n"));
+        assert!(markdown.contains("## Line 2\n\n> b\n\ncheck this\n"));
+        assert!(markdown.contains("## Line 5 (orphaned)"));
+    }
+}
@@ -0,0 +1,506 @@
+// User-facing backend strings and their translations.
+//
+// zh-CN and en-US are built in. Other languages come from
+// `<config dir>/locales/<language>.json`, a flat object keyed like the table
+// below; missing keys fall back to English. Each file is read once per run.
+// Commands still fail with English messages, so `get_locale_strings` also
+// maps every raw `error.*` message to its translation for the frontend.
+
+use super::*;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+type LocaleOverrides = Arc<HashMap<String, String>>;
+
+const BUILTIN_LANGUAGES: &[&str] = &["zh-CN", "en-US"];
+
+struct LocaleString {
+    key: &'static str,
+    en: &'static str,
+    zh: &'static str,
+}
+
+const LOCALE_STRINGS: &[LocaleString] = &[
+    LocaleString {
+        key: "windows.contextMenuName",
+        en: "Open with Rutar",
+        zh: "\u{4f7f}\u{7528} Rutar \u{6253}\u{5f00}",
+    },
+    LocaleString {
+        key: "windows.fileAssociationTypeName",
+        en: "Rutar Text Document",
+        zh: "Rutar \u{6587}\u{672c}\u{6587}\u{6863}",
+    },
+    LocaleString {
+        key: "windows.appName",
+        en: "Rutar",
+        zh: "Rutar",
+    },
+    LocaleString {
+        key: "windows.appDescription",
+        en: "Rutar text editor",
+        zh: "Rutar \u{6587}\u{672c}\u{7f16}\u{8f91}\u{5668}",
+    },
+    LocaleString {
+        key: "document.untitled",
+        en: "Untitled",
+        zh: "\u{672a}\u{547d}\u{540d}",
+    },
+    LocaleString {
+        key: "encodingFamily.unicode",
+        en: "Unicode",
+        zh: "Unicode",
+    },
+    LocaleString {
+        key: "encodingFamily.chineseSimplified",
+        en: "Chinese Simplified",
+        zh: "\u{7b80}\u{4f53}\u{4e2d}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.chineseTraditional",
+        en: "Chinese Traditional",
+        zh: "\u{7e41}\u{4f53}\u{4e2d}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.japanese",
+        en: "Japanese",
+        zh: "\u{65e5}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.korean",
+        en: "Korean",
+        zh: "\u{97e9}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.western",
+        en: "Western European",
+        zh: "\u{897f}\u{6b27}",
+    },
+    LocaleString {
+        key: "encodingFamily.centralEuropean",
+        en: "Central European",
+        zh: "\u{4e2d}\u{6b27}",
+    },
+    LocaleString {
+        key: "encodingFamily.cyrillic",
+        en: "Cyrillic",
+        zh: "\u{897f}\u{91cc}\u{5c14}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.greek",
+        en: "Greek",
+        zh: "\u{5e0c}\u{814a}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.turkish",
+        en: "Turkish",
+        zh: "\u{571f}\u{8033}\u{5176}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.baltic",
+        en: "Baltic",
+        zh: "\u{6ce2}\u{7f57}\u{7684}\u{6d77}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.hebrew",
+        en: "Hebrew",
+        zh: "\u{5e0c}\u{4f2f}\u{6765}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.arabic",
+        en: "Arabic",
+        zh: "\u{963f}\u{62c9}\u{4f2f}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.thai",
+        en: "Thai",
+        zh: "\u{6cf0}\u{6587}",
+    },
+    LocaleString {
+        key: "encodingFamily.vietnamese",
+        en: "Vietnamese",
+        zh: "\u{8d8a}\u{5357}\u{6587}",
+    },
+    LocaleString {
+        key: "palette.newFile",
+        en: "New File",
+        zh: "\u{65b0}\u{5efa}\u{6587}\u{4ef6}",
+    },
+    LocaleString {
+        key: "palette.reloadFileFromDisk",
+        en: "Reload From Disk",
+        zh: "\u{4ece}\u{78c1}\u{76d8}\u{91cd}\u{65b0}\u{52a0}\u{8f7d}",
+    },
+    LocaleString {
+        key: "palette.setLineEnding",
+        en: "Set Line Ending",
+        zh: "\u{8bbe}\u{7f6e}\u{6362}\u{884c}\u{7b26}",
+    },
+    LocaleString {
+        key: "palette.convertLineEndingsInBuffer",
+        en: "Convert Line Endings",
+        zh: "\u{8f6c}\u{6362}\u{6362}\u{884c}\u{7b26}",
+    },
+    LocaleString {
+        key: "palette.undo",
+        en: "Undo",
+        zh: "\u{64a4}\u{9500}",
+    },
+    LocaleString {
+        key: "palette.redo",
+        en: "Redo",
+        zh: "\u{91cd}\u{505a}",
+    },
+    LocaleString {
+        key: "palette.cleanupDocument",
+        en: "Clean Up Lines",
+        zh: "\u{6574}\u{7406}\u{884c}",
+    },
+    LocaleString {
+        key: "palette.formatDocument",
+        en: "Format Document",
+        zh: "\u{683c}\u{5f0f}\u{5316}\u{6587}\u{6863}",
+    },
+    LocaleString {
+        key: "palette.setDocumentWordWrap",
+        en: "Set Word Wrap for Document",
+        zh: "\u{8bbe}\u{7f6e}\u{5f53}\u{524d}\u{6587}\u{6863}\u{81ea}\u{52a8}\u{6362}\u{884c}",
+    },
+    LocaleString {
+        key: "palette.expandJsonLinesView",
+        en: "Open Pretty JSON Lines View",
+        zh: "\u{6253}\u{5f00}\u{683c}\u{5f0f}\u{5316} JSON Lines \u{89c6}\u{56fe}",
+    },
+    LocaleString {
+        key: "palette.clearTempHighlights",
+        en: "Clear Temporary Highlights",
+        zh: "\u{6e05}\u{9664}\u{4e34}\u{65f6}\u{9ad8}\u{4eae}",
+    },
+    LocaleString {
+        key: "palette.getWordCountInfo",
+        en: "Word Count",
+        zh: "\u{5b57}\u{6570}\u{7edf}\u{8ba1}",
+    },
+    LocaleString {
+        key: "palette.detectDocumentIndentation",
+        en: "Detect Indentation",
+        zh: "\u{68c0}\u{6d4b}\u{7f29}\u{8fdb}",
+    },
+    LocaleString {
+        key: "palette.detectCsvDialect",
+        en: "Detect CSV Dialect",
+        zh: "\u{68c0}\u{6d4b} CSV \u{683c}\u{5f0f}",
+    },
+    LocaleString {
+        key: "palette.compareDocumentWithClipboard",
+        en: "Compare With Clipboard",
+        zh: "\u{4e0e}\u{526a}\u{8d34}\u{677f}\u{6bd4}\u{8f83}",
+    },
+    LocaleString {
+        key: "palette.runIdleMaintenance",
+        en: "Free Memory Now",
+        zh: "\u{7acb}\u{5373}\u{91ca}\u{653e}\u{5185}\u{5b58}",
+    },
+    LocaleString {
+        key: "error.documentNotFound",
+        en: "Document not found",
+        zh: "\u{672a}\u{627e}\u{5230}\u{6587}\u{6863}",
+    },
+    LocaleString {
+        key: "error.readOnlyView",
+        en: "Document is a read-only view",
+        zh: "\u{6587}\u{6863}\u{662f}\u{53ea}\u{8bfb}\u{89c6}\u{56fe}",
+    },
+    LocaleString {
+        key: "error.noMoreUndo",
+        en: "No more undo steps",
+        zh: "\u{6ca1}\u{6709}\u{53ef}\u{64a4}\u{9500}\u{7684}\u{64cd}\u{4f5c}",
+    },
+    LocaleString {
+        key: "error.noMoreRedo",
+        en: "No more redo steps",
+        zh: "\u{6ca1}\u{6709}\u{53ef}\u{91cd}\u{505a}\u{7684}\u{64cd}\u{4f5c}",
+    },
+    LocaleString {
+        key: "error.pathNotFound",
+        en: "Path does not exist",
+        zh: "\u{8def}\u{5f84}\u{4e0d}\u{5b58}\u{5728}",
+    },
+    LocaleString {
+        key: "error.notADirectory",
+        en: "Path is not a directory",
+        zh: "\u{8def}\u{5f84}\u{4e0d}\u{662f}\u{6587}\u{4ef6}\u{5939}",
+    },
+    LocaleString {
+        key: "error.nameEmpty",
+        en: "Name cannot be empty",
+        zh: "\u{540d}\u{79f0}\u{4e0d}\u{80fd}\u{4e3a}\u{7a7a}",
+    },
+    LocaleString {
+        key: "error.nameInvalidCharacters",
+        en: "Name contains invalid characters",
+        zh: "\u{540d}\u{79f0}\u{5305}\u{542b}\u{65e0}\u{6548}\u{5b57}\u{7b26}",
+    },
+    LocaleString {
+        key: "error.nameReserved",
+        en: "Name is reserved",
+        zh: "\u{540d}\u{79f0}\u{4e3a}\u{7cfb}\u{7edf}\u{4fdd}\u{7559}\u{540d}\u{79f0}",
+    },
+    LocaleString {
+        key: "error.nameTrailingDot",
+        en: "Name cannot end with a space or period",
+        zh: "\u{540d}\u{79f0}\u{4e0d}\u{80fd}\u{4ee5}\u{7a7a}\u{683c}\u{6216}\u{53e5}\u{70b9}\u{7ed3}\u{5c3e}",
+    },
+    LocaleString {
+        key: "error.unsupportedSearchMode",
+        en: "Unsupported search mode",
+        zh: "\u{4e0d}\u{652f}\u{6301}\u{7684}\u{641c}\u{7d22}\u{6a21}\u{5f0f}",
+    },
+    LocaleString {
+        key: "error.searchSessionExpired",
+        en: "Search session expired due to document changes",
+        zh: "\u{6587}\u{6863}\u{5df2}\u{66f4}\u{6539}\u{ff0c}\u{641c}\u{7d22}\u{4f1a}\u{8bdd}\u{5df2}\u{5931}\u{6548}",
+    },
+    LocaleString {
+        key: "error.unsupportedOutlineType",
+        en: "Unsupported outline type",
+        zh: "\u{4e0d}\u{652f}\u{6301}\u{7684}\u{5927}\u{7eb2}\u{7c7b}\u{578b}",
+    },
+    LocaleString {
+        key: "error.terminalNotFound",
+        en: "Terminal not found",
+        zh: "\u{672a}\u{627e}\u{5230}\u{7ec8}\u{7aef}",
+    },
+    LocaleString {
+        key: "error.configNotSavedInSafeMode",
+        en: "Config is not saved in safe mode",
+        zh: "\u{5b89}\u{5168}\u{6a21}\u{5f0f}\u{4e0b}\u{4e0d}\u{4f1a}\u{4fdd}\u{5b58}\u{914d}\u{7f6e}",
+    },
+    LocaleString {
+        key: "error.contextMenuWindowsOnly",
+        en: "Windows context menu is only supported on Windows",
+        zh: "\u{53f3}\u{952e}\u{83dc}\u{5355}\u{4ec5}\u{5728} Windows \u{4e0a}\u{53d7}\u{652f}\u{6301}",
+    },
+    LocaleString {
+        key: "error.fileAssociationWindowsOnly",
+        en: "Windows file association is only supported on Windows",
+        zh: "\u{6587}\u{4ef6}\u{5173}\u{8054}\u{4ec5}\u{5728} Windows \u{4e0a}\u{53d7}\u{652f}\u{6301}",
+    },
+    LocaleString {
+        key: "error.fileManagerUnsupported",
+        en: "Opening file manager is not supported on this platform",
+        zh: "\u{5f53}\u{524d}\u{5e73}\u{53f0}\u{4e0d}\u{652f}\u{6301}\u{6253}\u{5f00}\u{6587}\u{4ef6}\u{7ba1}\u{7406}\u{5668}",
+    },
+];
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleStringsPayload {
+    pub language: String,
+    pub strings: BTreeMap<String, String>,
+    // Raw English error message -> localized message.
+    pub errors: BTreeMap<String, String>,
+}
+
+// Only plain `language-REGION` tags like `ja-JP` may name a locale file.
+pub(super) fn is_valid_language_tag(language: &str) -> bool {
+    let Some((language, region)) = language.split_once('-') else {
+        return false;
+    };
+
+    (2..=3).contains(&language.len())
+        && language.chars().all(|ch| ch.is_ascii_lowercase())
+        && region.len() == 2
+        && region.chars().all(|ch| ch.is_ascii_uppercase())
+}
+
+fn locale_file_path(language: &str) -> Option<PathBuf> {
+    if !is_valid_language_tag(language) {
+        return None;
+    }
+
+    config::config_dir_path_impl()
+        .ok()
+        .map(|dir| dir.join("locales").join(format!("{}.json", language)))
+}
+
+fn load_locale_file(language: &str) -> HashMap<String, String> {
+    locale_file_path(language)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn builtin_text(entry: &LocaleString, language: &str) -> &'static str {
+    if language == "zh-CN" {
+        entry.zh
+    } else {
+        entry.en
+    }
+}
+
+fn resolve_locale_strings(
+    language: &str,
+    overrides: &HashMap<String, String>,
+) -> Vec<(&'static LocaleString, String)> {
+    LOCALE_STRINGS
+        .iter()
+        .map(|entry| {
+            let text = overrides
+                .get(entry.key)
+                .filter(|text| !text.trim().is_empty())
+                .cloned()
+                .unwrap_or_else(|| builtin_text(entry, language).to_string());
+            (entry, text)
+        })
+        .collect()
+}
+
+fn locale_overrides(language: &str) -> LocaleOverrides {
+    static CACHE: OnceLock<Mutex<HashMap<String, LocaleOverrides>>> = OnceLock::new();
+
+    if BUILTIN_LANGUAGES.contains(&language) {
+        return LocaleOverrides::default();
+    }
+
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(overrides) = cache
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(language).cloned())
+    {
+        return overrides;
+    }
+
+    let overrides = Arc::new(load_locale_file(language));
+    match cache.lock() {
+        Ok(mut cache) => cache
+            .entry(language.to_string())
+            .or_insert(overrides)
+            .clone(),
+        Err(_) => overrides,
+    }
+}
+
+// Resolves keys for one language; build it once when looking up many keys.
+pub(super) struct Localizer {
+    language: String,
+    overrides: LocaleOverrides,
+}
+
+impl Localizer {
+    pub(super) fn new(language: &str) -> Self {
+        let language = settings::normalize_language(Some(language));
+        let overrides = locale_overrides(&language);
+        Self {
+            language,
+            overrides,
+        }
+    }
+
+    // Loads the configured language when none is given.
+    pub(super) fn for_request(language: Option<String>) -> Self {
+        let language = language.unwrap_or_else(|| {
+            config::load_config_impl()
+                .map(|config| config.language)
+                .unwrap_or_else(|_| settings::normalize_language(None))
+        });
+        Self::new(&language)
+    }
+
+    pub(super) fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub(super) fn text(&self, key: &str) -> String {
+        self.optional_text(key).unwrap_or_else(|| key.to_string())
+    }
+
+    // None for keys missing from the table.
+    pub(super) fn optional_text(&self, key: &str) -> Option<String> {
+        let entry = LOCALE_STRINGS.iter().find(|entry| entry.key == key)?;
+
+        Some(
+            self.overrides
+                .get(key)
+                .filter(|text| !text.trim().is_empty())
+                .cloned()
+                .unwrap_or_else(|| builtin_text(entry, &self.language).to_string()),
+        )
+    }
+}
+
+pub(super) fn locale_text(language: &str, key: &str) -> String {
+    Localizer::new(language).text(key)
+}
+
+pub(super) fn get_locale_strings_impl(language: Option<String>) -> LocaleStringsPayload {
+    let localizer = Localizer::for_request(language);
+
+    let mut strings = BTreeMap::new();
+    let mut errors = BTreeMap::new();
+    for (entry, text) in resolve_locale_strings(localizer.language(), &localizer.overrides) {
+        if entry.key.starts_with("error.") {
+            errors.insert(entry.en.to_string(), text.clone());
+        }
+        strings.insert(entry.key.to_string(), text);
+    }
+
+    LocaleStringsPayload {
+        language: localizer.language,
+        strings,
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_strings_should_have_unique_keys_and_error_messages() {
+        let mut keys = HashSet::new();
+        let mut messages = HashSet::new();
+        for entry in LOCALE_STRINGS {
+            assert!(keys.insert(entry.key), "duplicate key {}", entry.key);
+            if entry.key.starts_with("error.") {
+                assert!(messages.insert(entry.en), "duplicate message {}", entry.en);
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_locale_strings_should_prefer_overrides_and_fall_back_to_builtin() {
+        let overrides = HashMap::from([
+            (
+                "error.documentNotFound".to_string(),
+                "Document introuvable".to_string(),
+            ),
+            ("windows.appName".to_string(), "  ".to_string()),
+        ]);
+        let resolved = resolve_locale_strings("fr-FR", &overrides);
+        let text = |key: &str| {
+            resolved
+                .iter()
+                .find(|(entry, _)| entry.key == key)
+                .map(|(_, text)| text.as_str())
+        };
+
+        assert_eq!(text("error.documentNotFound"), Some("Document introuvable"));
+        assert_eq!(text("windows.appName"), Some("Rutar"));
+        assert_eq!(text("error.nameEmpty"), Some("Name cannot be empty"));
+        assert_eq!(
+            resolve_locale_strings("zh-CN", &HashMap::new())[0].1,
+            "使用 Rutar 打开"
+        );
+    }
+
+    #[test]
+    fn is_valid_language_tag_should_reject_paths() {
+        assert!(is_valid_language_tag("ja-JP"));
+        assert!(!is_valid_language_tag("../config"));
+        assert!(!is_valid_language_tag("unknown"));
+        assert!(!is_valid_language_tag(""));
+    }
+}
@@ -0,0 +1,415 @@
+// Opens a rotated log together with its siblings, e.g. `app.log`,
+// `app.log.1` and `app.log.2.gz`. Larger rotation numbers are older, so the
+// series runs from the highest number up to the live file. Members open as
+// separate tabs or as one joined document. The joined document remembers
+// which file each of its lines came from.
+
+use super::*;
+use flate2::read::GzDecoder;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const LOG_SERIES_MAX_MEMBERS: usize = 64;
+// Guards against archives that expand far beyond their size on disk.
+const MAX_DECOMPRESSED_BYTES: u64 = 512 * 1024 * 1024;
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSeriesMember {
+    pub path: String,
+    // 0 for the live file.
+    pub rotation: u32,
+    pub compressed: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSeriesOpenResultPayload {
+    // Oldest first.
+    pub members: Vec<LogSeriesMember>,
+    // One entry per member for "tabs", a single joined document for "join".
+    pub files: Vec<FileInfo>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone)]
+struct LogSeriesSegment {
+    path: String,
+    // 1-based first line in the joined document.
+    start_line: usize,
+    line_count: usize,
+}
+
+struct JoinedLogSeries {
+    // Version right after joining; any later edit moves it.
+    document_version: u64,
+    segments: Vec<LogSeriesSegment>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSeriesLinePayload {
+    pub path: String,
+    pub line: usize,
+}
+
+fn joined_series() -> &'static DashMap<String, JoinedLogSeries> {
+    static MAP: OnceLock<DashMap<String, JoinedLogSeries>> = OnceLock::new();
+    MAP.get_or_init(DashMap::new)
+}
+
+// Splits `app.log.2.gz` into ("app.log", 2, true); the live file has rotation 0.
+fn parse_rotated_name(name: &str) -> (String, u32, bool) {
+    let (name, compressed) = match name.strip_suffix(".gz") {
+        Some(stripped) => (stripped, true),
+        None => (name, false),
+    };
+    if let Some((base, suffix)) = name.rsplit_once('.') {
+        if !base.is_empty() && !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit()) {
+            if let Ok(rotation) = suffix.parse::<u32>() {
+                return (base.to_string(), rotation, compressed);
+            }
+        }
+    }
+    (name.to_string(), 0, compressed)
+}
+
+fn find_log_series_members(path: &Path) -> Result<Vec<LogSeriesMember>, String> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| "Invalid log path".to_string())?
+        .to_string_lossy()
+        .to_string();
+    let directory = path.parent().unwrap_or_else(|| Path::new("."));
+    let (base, _, _) = parse_rotated_name(&file_name);
+
+    let mut members: Vec<LogSeriesMember> = fs::read_dir(directory)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let (member_base, rotation, compressed) = parse_rotated_name(&name);
+            (member_base == base).then(|| LogSeriesMember {
+                path: entry.path().to_string_lossy().to_string(),
+                rotation,
+                compressed,
+            })
+        })
+        .collect();
+
+    // Oldest first; a plain file sorts after its compressed twin.
+    members.sort_by(|left, right| {
+        right
+            .rotation
+            .cmp(&left.rotation)
+            .then(right.compressed.cmp(&left.compressed))
+    });
+    if members.len() > LOG_SERIES_MAX_MEMBERS {
+        members.drain(..members.len() - LOG_SERIES_MAX_MEMBERS);
+    }
+    Ok(members)
+}
+
+fn read_log_member_text(member: &LogSeriesMember) -> Result<String, String> {
+    read_series_file_text(&member.path, member.compressed)
+}
+
+// Decodes a plain or gzip-compressed file to LF-normalized text; shared with
+// virtual documents, which are built from the same kind of file lists.
+pub(super) fn read_series_file_text(path: &str, compressed: bool) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    if compressed {
+        GzDecoder::new(file)
+            .take(MAX_DECOMPRESSED_BYTES + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("{}: {}", path, e))?;
+        if bytes.len() as u64 > MAX_DECOMPRESSED_BYTES {
+            return Err(format!(
+                "{}: decompressed size exceeds {} MB",
+                path,
+                MAX_DECOMPRESSED_BYTES / (1024 * 1024)
+            ));
+        }
+    } else {
+        let mut file = file;
+        file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    }
+
+    let encoding = match Encoding::for_bom(&bytes) {
+        Some((encoding, _)) => encoding,
+        None => {
+            let mut detector = EncodingDetector::new();
+            detector.feed(
+                &bytes[..bytes.len().min(ENCODING_DETECT_SAMPLE_BYTES)],
+                true,
+            );
+            detector.guess(None, true)
+        }
+    };
+    let (text, _) = encoding.decode_with_bom_removal(&bytes);
+    Ok(text_utils::normalize_to_lf(&text))
+}
+
+// Joins member texts so each starts on a fresh line, recording where each
+// member's lines landed.
+fn join_log_texts(texts: Vec<(String, String)>) -> (String, Vec<LogSeriesSegment>) {
+    let mut joined = String::new();
+    let mut segments = Vec::with_capacity(texts.len());
+    let mut next_line = 1usize;
+
+    for (path, text) in texts {
+        if text.is_empty() {
+            continue;
+        }
+        let body = text.strip_suffix('\n').unwrap_or(&text);
+        let line_count = body.split('\n').count();
+        if !joined.is_empty() {
+            joined.push('\n');
+        }
+        joined.push_str(body);
+        segments.push(LogSeriesSegment {
+            path,
+            start_line: next_line,
+            line_count,
+        });
+        next_line += line_count;
+    }
+
+    (joined, segments)
+}
+
+// Returns the new tab and its document version once the text is in.
+fn create_text_document(
+    state: &State<'_, AppState>,
+    name: String,
+    text: &str,
+) -> Result<(FileInfo, u64), String> {
+    let mut file_info = file_io::new_file_impl(state.clone(), Some("LF".to_string()))?;
+    let mut doc = state
+        .documents
+        .get_mut(&file_info.id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    // The text is the tab's starting content, not an edit to undo.
+    let mut engine = doc.engine();
+    engine.append_unrecorded(text)?;
+    let document_version = engine.finish().document_version;
+    doc.saved_rope = doc.rope.clone();
+    doc.saved_document_version = document_version;
+    drop(doc);
+
+    file_info.name = name;
+    file_info.line_count = text.split('\n').count();
+    file_info.size_bytes = text.len() as u64;
+    file_info.large_file_mode = text.len() > LARGE_FILE_THRESHOLD_BYTES;
+    Ok((file_info, document_version))
+}
+
+fn member_file_name(member: &LogSeriesMember) -> String {
+    Path::new(&member.path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| member.path.clone())
+}
+
+pub(super) async fn open_log_series_impl(
+    state: State<'_, AppState>,
+    path: String,
+    mode: String,
+) -> Result<LogSeriesOpenResultPayload, String> {
+    if mode != "tabs" && mode != "join" {
+        return Err("Unsupported log series mode. Use tabs or join".to_string());
+    }
+    let path_buf = PathBuf::from(path.trim());
+    let members = find_log_series_members(&path_buf)?;
+    if members.is_empty() {
+        return Err("No log files found".to_string());
+    }
+    joined_series().retain(|id, _| state.documents.contains_key(id));
+
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+
+    if mode == "tabs" {
+        let compressed_members: Vec<LogSeriesMember> = members
+            .iter()
+            .filter(|member| member.compressed)
+            .cloned()
+            .collect();
+        let mut compressed_texts = tauri::async_runtime::spawn_blocking(move || {
+            compressed_members
+                .iter()
+                .map(read_log_member_text)
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|error| error.to_string())?
+        .into_iter();
+        let plain_paths: Vec<String> = members
+            .iter()
+            .filter(|member| !member.compressed)
+            .map(|member| member.path.clone())
+            .collect();
+        let mut opened = file_io::open_files_impl(state.clone(), plain_paths)
+            .await
+            .into_iter();
+
+        for member in &members {
+            if member.compressed {
+                let Some(text) = compressed_texts.next() else {
+                    continue;
+                };
+                let loaded = text
+                    .and_then(|text| create_text_document(&state, member_file_name(member), &text));
+                match loaded {
+                    Ok((file_info, _)) => files.push(file_info),
+                    Err(error) => errors.push(error),
+                }
+            } else if let Some(result) = opened.next() {
+                match (result.file_info, result.error) {
+                    (Some(file_info), _) => files.push(file_info),
+                    (None, error) => {
+                        errors.push(format!("{}: {}", result.path, error.unwrap_or_default()))
+                    }
+                }
+            }
+        }
+    } else {
+        let members_for_io = members.clone();
+        let (texts, read_errors) = tauri::async_runtime::spawn_blocking(move || {
+            let mut texts = Vec::new();
+            let mut errors = Vec::new();
+            for member in &members_for_io {
+                match read_log_member_text(member) {
+                    Ok(text) => texts.push((member.path.clone(), text)),
+                    Err(error) => errors.push(error),
+                }
+            }
+            (texts, errors)
+        })
+        .await
+        .map_err(|error| error.to_string())?;
+        errors.extend(read_errors);
+
+        let (joined, segments) = join_log_texts(texts);
+        let name = format!(
+            "{} (series)",
+            parse_rotated_name(&member_file_name(&members[0])).0
+        );
+        let (file_info, document_version) = create_text_document(&state, name, &joined)?;
+        joined_series().insert(
+            file_info.id.clone(),
+            JoinedLogSeries {
+                document_version,
+                segments,
+            },
+        );
+        files.push(file_info);
+    }
+
+    Ok(LogSeriesOpenResultPayload {
+        members,
+        files,
+        errors,
+    })
+}
+
+// Maps a joined document line back to its file; refused once the joined text
+// has been edited, since the recorded offsets no longer hold.
+pub(super) fn map_log_series_line_impl(
+    state: State<'_, AppState>,
+    id: String,
+    line: usize,
+) -> Result<LogSeriesLinePayload, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let series = joined_series()
+        .get(&id)
+        .ok_or_else(|| "Document is not a joined log series".to_string())?;
+    if doc.document_version != series.document_version {
+        return Err("Log series was edited after it was joined".to_string());
+    }
+
+    locate_series_line(&series.segments, line)
+        .ok_or_else(|| "Line is outside the log series".to_string())
+}
+
+fn locate_series_line(segments: &[LogSeriesSegment], line: usize) -> Option<LogSeriesLinePayload> {
+    segments
+        .iter()
+        .find(|segment| {
+            line >= segment.start_line && line < segment.start_line + segment.line_count
+        })
+        .map(|segment| LogSeriesLinePayload {
+            path: segment.path.clone(),
+            line: line - segment.start_line + 1,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rotated_name_should_split_rotation_and_compression() {
+        assert_eq!(
+            parse_rotated_name("app.log"),
+            ("app.log".to_string(), 0, false)
+        );
+        assert_eq!(
+            parse_rotated_name("app.log.1"),
+            ("app.log".to_string(), 1, false)
+        );
+        assert_eq!(
+            parse_rotated_name("app.log.12.gz"),
+            ("app.log".to_string(), 12, true)
+        );
+        assert_eq!(
+            parse_rotated_name("app.log.gz"),
+            ("app.log".to_string(), 0, true)
+        );
+    }
+
+    #[test]
+    fn find_log_series_members_should_order_oldest_first() {
+        let root = std::env::temp_dir().join(format!("rutar-log-series-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create dir");
+        for name in [
+            "app.log",
+            "app.log.1",
+            "app.log.10",
+            "app.log.2.gz",
+            "other.log",
+        ] {
+            fs::write(root.join(name), "x").expect("write file");
+        }
+
+        let members = find_log_series_members(&root.join("app.log.1")).expect("members");
+        let names: Vec<String> = members.iter().map(member_file_name).collect();
+        assert_eq!(
+            names,
+            vec!["app.log.10", "app.log.2.gz", "app.log.1", "app.log"]
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn join_log_texts_should_track_source_lines() {
+        let (joined, segments) = join_log_texts(vec![
+            ("old".to_string(), "a\nb\n".to_string()),
+            ("empty".to_string(), String::new()),
+            ("new".to_string(), "c".to_string()),
+        ]);
+
+        assert_eq!(joined, "a\nb\nc");
+        let located = locate_series_line(&segments, 3).expect("line");
+        assert_eq!((located.path.as_str(), located.line), ("new", 1));
+        let located = locate_series_line(&segments, 2).expect("line");
+        assert_eq!((located.path.as_str(), located.line), ("old", 2));
+        assert!(locate_series_line(&segments, 4).is_none());
+    }
+}
@@ -0,0 +1,423 @@
+// Three-way merge of open documents. The merged text is written to a result
+// document with git-style conflict markers, so the same listing and resolving
+// also works for files that another tool left conflicted.
+
+use super::*;
+use similar::{Algorithm, DiffOp, TextDiff};
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflictHunk {
+    pub index: usize,
+    // 1-based, inclusive, and covering the marker lines.
+    pub start_line: usize,
+    pub end_line: usize,
+    pub ours_lines: Vec<String>,
+    // None when the block has no `|||||||` base section.
+    pub base_lines: Option<Vec<String>>,
+    pub theirs_lines: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflictsPayload {
+    pub conflicts: Vec<MergeConflictHunk>,
+    pub line_count: usize,
+    pub document_version: u64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeDocumentsResult {
+    pub conflicts: Vec<MergeConflictHunk>,
+    // Regions changed on one side only, or identically on both.
+    pub auto_merged_count: usize,
+    pub line_count: usize,
+    pub document_version: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MergeResolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+impl MergeResolution {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "ours" => Ok(Self::Ours),
+            "theirs" => Ok(Self::Theirs),
+            "both" => Ok(Self::Both),
+            _ => Err("Invalid merge resolution".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum MergeChunk {
+    Stable(Vec<String>),
+    Merged(Vec<String>),
+    Conflict {
+        base: Vec<String>,
+        ours: Vec<String>,
+        theirs: Vec<String>,
+    },
+}
+
+// For each base line, the index of the equal line on the other side.
+fn build_base_line_matches(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; base.len()];
+    let diff = TextDiff::configure()
+        .algorithm(Algorithm::Myers)
+        .diff_slices(base, other);
+
+    for op in diff.ops() {
+        if let DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } = *op
+        {
+            for offset in 0..len {
+                matches[old_index + offset] = Some(new_index + offset);
+            }
+        }
+    }
+
+    matches
+}
+
+fn to_owned_lines(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+fn resolve_unstable_chunk(base: &[&str], ours: &[&str], theirs: &[&str]) -> MergeChunk {
+    if ours == theirs || theirs == base {
+        MergeChunk::Merged(to_owned_lines(ours))
+    } else if ours == base {
+        MergeChunk::Merged(to_owned_lines(theirs))
+    } else {
+        MergeChunk::Conflict {
+            base: to_owned_lines(base),
+            ours: to_owned_lines(ours),
+            theirs: to_owned_lines(theirs),
+        }
+    }
+}
+
+// diff3: runs of base lines kept by both sides are stable; everything between
+// two stable runs is merged on its own or becomes a conflict.
+fn merge_lines(base: &[String], ours: &[String], theirs: &[String]) -> Vec<MergeChunk> {
+    let base: Vec<&str> = base.iter().map(String::as_str).collect();
+    let ours: Vec<&str> = ours.iter().map(String::as_str).collect();
+    let theirs: Vec<&str> = theirs.iter().map(String::as_str).collect();
+    let ours_matches = build_base_line_matches(&base, &ours);
+    let theirs_matches = build_base_line_matches(&base, &theirs);
+
+    let mut chunks = Vec::new();
+    let (mut base_pos, mut ours_pos, mut theirs_pos) = (0usize, 0usize, 0usize);
+
+    while base_pos < base.len() || ours_pos < ours.len() || theirs_pos < theirs.len() {
+        let mut run = 0usize;
+        while base_pos + run < base.len()
+            && ours_matches[base_pos + run] == Some(ours_pos + run)
+            && theirs_matches[base_pos + run] == Some(theirs_pos + run)
+        {
+            run += 1;
+        }
+
+        if run > 0 {
+            chunks.push(MergeChunk::Stable(to_owned_lines(
+                &base[base_pos..base_pos + run],
+            )));
+            base_pos += run;
+            ours_pos += run;
+            theirs_pos += run;
+            continue;
+        }
+
+        let (base_end, ours_end, theirs_end) = (base_pos..base.len())
+            .find_map(|index| Some((index, ours_matches[index]?, theirs_matches[index]?)))
+            .unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        chunks.push(resolve_unstable_chunk(
+            &base[base_pos..base_end],
+            &ours[ours_pos..ours_end],
+            &theirs[theirs_pos..theirs_end],
+        ));
+        base_pos = base_end;
+        ours_pos = ours_end;
+        theirs_pos = theirs_end;
+    }
+
+    chunks
+}
+
+fn render_merge_chunks(chunks: Vec<MergeChunk>) -> (Vec<String>, usize) {
+    let mut lines = Vec::new();
+    let mut auto_merged_count = 0usize;
+
+    for chunk in chunks {
+        match chunk {
+            MergeChunk::Stable(stable) => lines.extend(stable),
+            MergeChunk::Merged(merged) => {
+                auto_merged_count += 1;
+                lines.extend(merged);
+            }
+            MergeChunk::Conflict { base, ours, theirs } => {
+                lines.push(format!("{OURS_MARKER} ours"));
+                lines.extend(ours);
+                lines.push(format!("{BASE_MARKER} base"));
+                lines.extend(base);
+                lines.push(SEPARATOR_MARKER.to_string());
+                lines.extend(theirs);
+                lines.push(format!("{THEIRS_MARKER} theirs"));
+            }
+        }
+    }
+
+    (lines, auto_merged_count)
+}
+
+fn is_marker_line(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+// Unterminated or nested blocks are skipped rather than reported.
+fn parse_merge_conflicts(lines: &[String]) -> Vec<MergeConflictHunk> {
+    let mut conflicts = Vec::new();
+    let mut index = 0usize;
+
+    while index < lines.len() {
+        if !is_marker_line(&lines[index], OURS_MARKER) {
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        let mut ours_lines = Vec::new();
+        let mut base_lines: Option<Vec<String>> = None;
+        let mut theirs_lines: Option<Vec<String>> = None;
+        let mut end = None;
+        let mut cursor = start + 1;
+
+        while cursor < lines.len() {
+            let line = &lines[cursor];
+            if is_marker_line(line, OURS_MARKER) {
+                break;
+            }
+            if let Some(theirs) = theirs_lines.as_mut() {
+                if is_marker_line(line, THEIRS_MARKER) {
+                    end = Some(cursor);
+                    break;
+                }
+                theirs.push(line.clone());
+            } else if line == SEPARATOR_MARKER {
+                theirs_lines = Some(Vec::new());
+            } else if let Some(base) = base_lines.as_mut() {
+                base.push(line.clone());
+            } else if is_marker_line(line, BASE_MARKER) {
+                base_lines = Some(Vec::new());
+            } else {
+                ours_lines.push(line.clone());
+            }
+            cursor += 1;
+        }
+
+        match end {
+            Some(end) => {
+                conflicts.push(MergeConflictHunk {
+                    index: conflicts.len(),
+                    start_line: start + 1,
+                    end_line: end + 1,
+                    ours_lines,
+                    base_lines,
+                    theirs_lines: theirs_lines.unwrap_or_default(),
+                });
+                index = end + 1;
+            }
+            None => index = start + 1,
+        }
+    }
+
+    conflicts
+}
+
+fn resolve_conflict_lines(
+    lines: &[String],
+    conflict: &MergeConflictHunk,
+    resolution: MergeResolution,
+) -> Vec<String> {
+    let mut next_lines = Vec::with_capacity(lines.len());
+    next_lines.extend_from_slice(&lines[..conflict.start_line - 1]);
+    match resolution {
+        MergeResolution::Ours => next_lines.extend_from_slice(&conflict.ours_lines),
+        MergeResolution::Theirs => next_lines.extend_from_slice(&conflict.theirs_lines),
+        MergeResolution::Both => {
+            next_lines.extend_from_slice(&conflict.ours_lines);
+            next_lines.extend_from_slice(&conflict.theirs_lines);
+        }
+    }
+    next_lines.extend_from_slice(&lines[conflict.end_line..]);
+    next_lines
+}
+
+// Replaces the whole text of the result document as one undoable edit. The
+// result may be one of the inputs, usually `ours`.
+pub(super) async fn merge_documents_impl(
+    state: State<'_, AppState>,
+    base_id: String,
+    ours_id: String,
+    theirs_id: String,
+    result_id: String,
+) -> Result<MergeDocumentsResult, String> {
+    let base_rope = diff::clone_rope(&state, &base_id)?;
+    let ours_rope = diff::clone_rope(&state, &ours_id)?;
+    let theirs_rope = diff::clone_rope(&state, &theirs_id)?;
+
+    let (merged_lines, auto_merged_count) = tauri::async_runtime::spawn_blocking(move || {
+        render_merge_chunks(merge_lines(
+            &diff::collect_rope_lines(&base_rope),
+            &diff::collect_rope_lines(&ours_rope),
+            &diff::collect_rope_lines(&theirs_rope),
+        ))
+    })
+    .await
+    .map_err(|error| error.to_string())?;
+
+    let conflicts = parse_merge_conflicts(&merged_lines);
+    let (line_count, document_version) = {
+        let mut doc = state
+            .documents
+            .get_mut(&result_id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        let _ = diff::apply_serialized_text_to_document(&mut doc, merged_lines.join("\n"))?;
+        (doc.rope.len_lines(), doc.document_version)
+    };
+    clear_outdated_document_search_caches(&result_id, document_version);
+
+    Ok(MergeDocumentsResult {
+        conflicts,
+        auto_merged_count,
+        line_count,
+        document_version,
+    })
+}
+
+pub(super) fn list_merge_conflicts_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<MergeConflictsPayload, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    Ok(MergeConflictsPayload {
+        conflicts: parse_merge_conflicts(&diff::collect_rope_lines(&doc.rope)),
+        line_count: doc.rope.len_lines(),
+        document_version: doc.document_version,
+    })
+}
+
+// `conflict_index` refers to the listing at `expected_document_version`;
+// the remaining conflicts are returned renumbered.
+pub(super) fn resolve_merge_conflict_impl(
+    state: State<'_, AppState>,
+    id: String,
+    conflict_index: usize,
+    resolution: String,
+    expected_document_version: u64,
+) -> Result<MergeConflictsPayload, String> {
+    let resolution = MergeResolution::parse(resolution.as_str())?;
+
+    let (conflicts, line_count, document_version) = {
+        let mut doc = state
+            .documents
+            .get_mut(&id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        if doc.document_version != expected_document_version {
+            return Err("Document changed since the merge conflicts were listed".to_string());
+        }
+
+        let lines = diff::collect_rope_lines(&doc.rope);
+        let conflicts = parse_merge_conflicts(&lines);
+        let conflict = conflicts
+            .get(conflict_index)
+            .ok_or_else(|| "Merge conflict index out of range".to_string())?;
+        let next_lines = resolve_conflict_lines(&lines, conflict, resolution);
+        let _ = diff::apply_serialized_text_to_document(&mut doc, next_lines.join("\n"))?;
+
+        (
+            parse_merge_conflicts(&next_lines),
+            doc.rope.len_lines(),
+            doc.document_version,
+        )
+    };
+    clear_outdated_document_search_caches(&id, document_version);
+
+    Ok(MergeConflictsPayload {
+        conflicts,
+        line_count,
+        document_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn merge_lines_should_take_one_sided_changes_and_report_conflicts() {
+        let base = lines(&["a", "b", "c", "d", ""]);
+        let ours = lines(&["a", "B", "c", "d", ""]);
+        let theirs = lines(&["a", "b", "c", "D", ""]);
+        let (merged, auto_merged_count) = render_merge_chunks(merge_lines(&base, &ours, &theirs));
+        assert_eq!(merged, lines(&["a", "B", "c", "D", ""]));
+        assert_eq!(auto_merged_count, 2);
+        assert!(parse_merge_conflicts(&merged).is_empty());
+
+        let theirs = lines(&["a", "x", "c", "d", ""]);
+        let (merged, _) = render_merge_chunks(merge_lines(&base, &ours, &theirs));
+        let conflicts = parse_merge_conflicts(&merged);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].start_line, 2);
+        assert_eq!(conflicts[0].end_line, 8);
+        assert_eq!(conflicts[0].ours_lines, lines(&["B"]));
+        assert_eq!(conflicts[0].base_lines, Some(lines(&["b"])));
+        assert_eq!(conflicts[0].theirs_lines, lines(&["x"]));
+
+        let resolved = resolve_conflict_lines(&merged, &conflicts[0], MergeResolution::Both);
+        assert_eq!(resolved, lines(&["a", "B", "x", "c", "d", ""]));
+    }
+
+    #[test]
+    fn parse_merge_conflicts_should_skip_unterminated_blocks() {
+        let text = lines(&[
+            "<<<<<<< HEAD",
+            "left",
+            "=======",
+            "right",
+            ">>>>>>> branch",
+            "<<<<<<< HEAD",
+            "dangling",
+        ]);
+        let conflicts = parse_merge_conflicts(&text);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].base_lines, None);
+        assert_eq!(conflicts[0].theirs_lines, lines(&["right"]));
+        assert!(MergeResolution::parse("base").is_err());
+    }
+}
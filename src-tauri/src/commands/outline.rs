@@ -1,11 +1,66 @@
 use crate::state::AppState;
 use ropey::Rope;
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 use tauri::State;
 use tree_sitter::{Language, Parser, Tree};
 
 thread_local! {
-    static OUTLINE_PARSER: RefCell<Parser> = RefCell::new(Parser::new());
+    // One parser per thread for every tree-sitter based command; see
+    // `parse_source_tree`.
+    static TREE_SITTER_PARSER: RefCell<Parser> = RefCell::new(Parser::new());
+}
+
+// Text parsed around a caret by `caret_context_source`. Commands that only
+// need the syntax at one position use it so large documents are not reparsed
+// whole on every keystroke; a string or comment opened further back than this
+// is missed.
+const CARET_CONTEXT_BYTES_BEFORE: usize = 64 * 1024;
+const CARET_CONTEXT_BYTES_AFTER: usize = 4 * 1024;
+
+// Parses with the calling thread's parser. Commands run on the blocking pool,
+// so this keeps one parser per pool thread instead of one per feature.
+pub(super) fn parse_source_tree(source: &str, language: &Language) -> Result<Tree, String> {
+    TREE_SITTER_PARSER.with(|cell| {
+        let mut parser = cell.borrow_mut();
+        parser
+            .set_language(language)
+            .map_err(|error| format!("Failed to configure parser: {}", error))?;
+        parser
+            .parse(source, None)
+            .ok_or_else(|| "Failed to parse source".to_string())
+    })
+}
+
+// Start of the caret context: the first full line within
+// `CARET_CONTEXT_BYTES_BEFORE` of the caret, or a plain byte cut when the
+// caret's own line is longer than that.
+fn caret_context_start(rope: &Rope, byte_offset: usize) -> usize {
+    let Some(budget_start) = byte_offset.checked_sub(CARET_CONTEXT_BYTES_BEFORE) else {
+        return 0;
+    };
+    let caret_line_start = rope.line_to_byte(rope.byte_to_line(byte_offset));
+    if caret_line_start <= budget_start {
+        return rope.char_to_byte(rope.byte_to_char(budget_start));
+    }
+
+    rope.line_to_byte(rope.byte_to_line(budget_start) + 1)
+        .min(caret_line_start)
+}
+
+// The text around `byte_offset` worth parsing for its syntax context, with
+// the byte offset the window starts at.
+pub(super) fn caret_context_source(rope: &Rope, byte_offset: usize) -> (String, usize) {
+    let window_start = caret_context_start(rope, byte_offset);
+    let window_end = rope.char_to_byte(
+        rope.byte_to_char((byte_offset + CARET_CONTEXT_BYTES_AFTER).min(rope.len_bytes())),
+    );
+
+    (
+        rope.byte_slice(window_start..window_end).chunks().collect(),
+        window_start,
+    )
 }
 
 fn rope_to_string(rope: &Rope) -> String {
@@ -81,6 +136,9 @@ enum OutlineFileType {
     Php,
     Kotlin,
     Swift,
+    Ruby,
+    Lua,
+    Sql,
 }
 
 fn truncate_preview(value: &str, max_len: usize) -> String {
@@ -114,30 +172,90 @@ fn parse_outline_file_type(file_type: &str) -> Option<OutlineFileType> {
         "php" => Some(OutlineFileType::Php),
         "kotlin" | "kt" | "kts" => Some(OutlineFileType::Kotlin),
         "swift" => Some(OutlineFileType::Swift),
+        "ruby" | "rb" => Some(OutlineFileType::Ruby),
+        "lua" => Some(OutlineFileType::Lua),
+        "sql" => Some(OutlineFileType::Sql),
         _ => None,
     }
 }
 
 fn get_outline_language(file_type: OutlineFileType) -> Option<Language> {
-    match file_type {
-        OutlineFileType::Markdown => None,
-        OutlineFileType::Json => Some(tree_sitter_json::LANGUAGE.into()),
-        OutlineFileType::Yaml => Some(tree_sitter_yaml::LANGUAGE.into()),
-        OutlineFileType::Xml => Some(tree_sitter_xml::LANGUAGE_XML.into()),
-        OutlineFileType::Toml => Some(tree_sitter_toml_ng::LANGUAGE.into()),
-        OutlineFileType::Python => Some(tree_sitter_python::LANGUAGE.into()),
-        OutlineFileType::Javascript => Some(tree_sitter_javascript::LANGUAGE.into()),
-        OutlineFileType::Typescript => Some(tree_sitter_typescript::LANGUAGE_TSX.into()),
-        OutlineFileType::C => Some(tree_sitter_c::LANGUAGE.into()),
-        OutlineFileType::Cpp => Some(tree_sitter_cpp::LANGUAGE.into()),
-        OutlineFileType::Go => Some(tree_sitter_go::LANGUAGE.into()),
-        OutlineFileType::Java => Some(tree_sitter_java::LANGUAGE.into()),
-        OutlineFileType::Rust => Some(tree_sitter_rust::LANGUAGE.into()),
-        OutlineFileType::Csharp => Some(tree_sitter_c_sharp::LANGUAGE.into()),
-        OutlineFileType::Php => Some(tree_sitter_php::LANGUAGE_PHP.into()),
-        OutlineFileType::Kotlin => Some(tree_sitter_kotlin_ng::LANGUAGE.into()),
-        OutlineFileType::Swift => Some(tree_sitter_swift::LANGUAGE.into()),
-        OutlineFileType::Ini => None,
+    let syntax_key = match file_type {
+        OutlineFileType::Markdown | OutlineFileType::Ini => return None,
+        OutlineFileType::Json => "json",
+        OutlineFileType::Yaml => "yaml",
+        OutlineFileType::Xml => "xml",
+        OutlineFileType::Toml => "toml",
+        OutlineFileType::Python => "python",
+        OutlineFileType::Javascript => "javascript",
+        OutlineFileType::Typescript => "typescript",
+        OutlineFileType::C => "c",
+        OutlineFileType::Cpp => "cpp",
+        OutlineFileType::Go => "go",
+        OutlineFileType::Java => "java",
+        OutlineFileType::Rust => "rust",
+        OutlineFileType::Csharp => "csharp",
+        OutlineFileType::Php => "php",
+        OutlineFileType::Kotlin => "kotlin",
+        OutlineFileType::Swift => "swift",
+        OutlineFileType::Ruby => "ruby",
+        OutlineFileType::Lua => "lua",
+        OutlineFileType::Sql => "sql",
+    };
+
+    tree_sitter_language_for_syntax(syntax_key)
+}
+
+// Grammar for a syntax key from `syntax.rs`; the one mapping every
+// tree-sitter based feature goes through. Each grammar is loaded on first use
+// and cloned from the cache afterwards.
+pub(super) fn tree_sitter_language_for_syntax(syntax_key: &str) -> Option<Language> {
+    static LANGUAGES: OnceLock<Mutex<HashMap<String, Language>>> = OnceLock::new();
+
+    let mut languages = LANGUAGES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(language) = languages.get(syntax_key) {
+        return Some(language.clone());
+    }
+
+    let language = load_tree_sitter_language(syntax_key)?;
+    languages.insert(syntax_key.to_string(), language.clone());
+    Some(language)
+}
+
+fn load_tree_sitter_language(syntax_key: &str) -> Option<Language> {
+    match syntax_key {
+        "javascript" => Some(tree_sitter_javascript::LANGUAGE.into()),
+        "typescript" => Some(tree_sitter_typescript::LANGUAGE_TSX.into()),
+        "rust" => Some(tree_sitter_rust::LANGUAGE.into()),
+        "python" => Some(tree_sitter_python::LANGUAGE.into()),
+        "json" | "jsonc" => Some(tree_sitter_json::LANGUAGE.into()),
+        "dockerfile" => Some(tree_sitter_dockerfile::language()),
+        "makefile" => Some(tree_sitter_make::LANGUAGE.into()),
+        "ini" => Some(tree_sitter_ini::LANGUAGE.into()),
+        "html" => Some(tree_sitter_html::LANGUAGE.into()),
+        "css" => Some(tree_sitter_css::LANGUAGE.into()),
+        "bash" => Some(tree_sitter_bash::LANGUAGE.into()),
+        "zsh" => Some(tree_sitter_zsh::LANGUAGE.into()),
+        "toml" => Some(tree_sitter_toml_ng::LANGUAGE.into()),
+        "yaml" => Some(tree_sitter_yaml::LANGUAGE.into()),
+        "xml" => Some(tree_sitter_xml::LANGUAGE_XML.into()),
+        "c" => Some(tree_sitter_c::LANGUAGE.into()),
+        "cpp" => Some(tree_sitter_cpp::LANGUAGE.into()),
+        "go" => Some(tree_sitter_go::LANGUAGE.into()),
+        "java" => Some(tree_sitter_java::LANGUAGE.into()),
+        "csharp" => Some(tree_sitter_c_sharp::LANGUAGE.into()),
+        "hcl" => Some(tree_sitter_hcl::LANGUAGE.into()),
+        "lua" => Some(tree_sitter_lua::LANGUAGE.into()),
+        "php" => Some(tree_sitter_php::LANGUAGE_PHP.into()),
+        "kotlin" => Some(tree_sitter_kotlin_ng::LANGUAGE.into()),
+        "powershell" => Some(tree_sitter_powershell::LANGUAGE.into()),
+        "ruby" => Some(tree_sitter_ruby::LANGUAGE.into()),
+        "sql" => Some(tree_sitter_sequel::LANGUAGE.into()),
+        "swift" => Some(tree_sitter_swift::LANGUAGE.into()),
+        _ => None,
     }
 }
 
@@ -501,7 +619,10 @@ fn is_container_kind(file_type: OutlineFileType, kind: &str) -> bool {
         | OutlineFileType::Csharp
         | OutlineFileType::Php
         | OutlineFileType::Kotlin
-        | OutlineFileType::Swift => false,
+        | OutlineFileType::Swift
+        | OutlineFileType::Ruby
+        | OutlineFileType::Lua
+        | OutlineFileType::Sql => false,
     }
 }
 
@@ -671,7 +792,10 @@ fn format_outline_label(
         | OutlineFileType::Csharp
         | OutlineFileType::Php
         | OutlineFileType::Kotlin
-        | OutlineFileType::Swift => {
+        | OutlineFileType::Swift
+        | OutlineFileType::Ruby
+        | OutlineFileType::Lua
+        | OutlineFileType::Sql => {
             if has_named_children {
                 kind.to_string()
             } else {
@@ -840,9 +964,12 @@ mod go;
 mod java;
 mod javascript;
 mod kotlin;
+mod lua;
 mod php;
 mod python;
+mod ruby;
 mod rust_lang;
+mod sql;
 mod swift;
 mod typescript;
 
@@ -865,6 +992,9 @@ fn build_symbol_outline_node(
         OutlineFileType::Php => php::build_php_outline_node(node, source),
         OutlineFileType::Kotlin => kotlin::build_kotlin_outline_node(node, source),
         OutlineFileType::Swift => swift::build_swift_outline_node(node, source),
+        OutlineFileType::Ruby => ruby::build_ruby_outline_node(node, source),
+        OutlineFileType::Lua => lua::build_lua_outline_node(node, source),
+        OutlineFileType::Sql => sql::build_sql_outline_node(node, source),
         OutlineFileType::Markdown
         | OutlineFileType::Json
         | OutlineFileType::Yaml
@@ -1149,8 +1279,12 @@ pub async fn get_outline_impl(
     let rope = state
         .documents
         .get(&id)
-        .map(|doc| doc.rope.clone())
+        .map(|doc| (!doc.preview_mode).then(|| doc.rope.clone()))
         .ok_or_else(|| "Document not found".to_string())?;
+    // Preview documents get no outline until promoted to full mode.
+    let Some(rope) = rope else {
+        return Ok(Vec::new());
+    };
 
     tauri::async_runtime::spawn_blocking(move || -> Result<Vec<OutlineNode>, String> {
         let source = rope_to_string(&rope);
@@ -1167,15 +1301,7 @@ pub async fn get_outline_impl(
         let language = get_outline_language(outline_type)
             .ok_or_else(|| "Unsupported outline type".to_string())?;
 
-        let tree: Tree = OUTLINE_PARSER.with(|cell| -> Result<Tree, String> {
-            let mut parser = cell.borrow_mut();
-            parser
-                .set_language(&language)
-                .map_err(|error| format!("Failed to configure outline parser: {}", error))?;
-            parser
-                .parse(&source, None)
-                .ok_or_else(|| "Failed to parse outline".to_string())
-        })?;
+        let tree = parse_source_tree(&source, &language)?;
 
         let root_node = tree.root_node();
 
@@ -1193,6 +1319,9 @@ pub async fn get_outline_impl(
                 | OutlineFileType::Php
                 | OutlineFileType::Kotlin
                 | OutlineFileType::Swift
+                | OutlineFileType::Ruby
+                | OutlineFileType::Lua
+                | OutlineFileType::Sql
         ) {
             let mut symbols = Vec::new();
             collect_symbol_outline_nodes(root_node, &source, outline_type, &mut symbols);
@@ -1225,12 +1354,65 @@ pub async fn get_outline_impl(
     .map_err(|error| error.to_string())?
 }
 
+fn push_outline_markdown_lines(node: &OutlineNode, depth: usize, output: &mut String) {
+    output.push_str(&"  ".repeat(depth));
+    output.push_str(&format!(
+        "- {} `{}` (line {})\n",
+        node.label.replace('\n', " "),
+        node.node_type,
+        node.line
+    ));
+    for child in &node.children {
+        push_outline_markdown_lines(child, depth + 1, output);
+    }
+}
+
+fn render_outline_markdown(nodes: &[OutlineNode]) -> String {
+    let mut output = String::new();
+    for node in nodes {
+        push_outline_markdown_lines(node, 0, &mut output);
+    }
+    output
+}
+
+fn render_outline_export(nodes: &[OutlineNode], format: &str) -> Result<String, String> {
+    match format.trim().to_ascii_lowercase().as_str() {
+        "json" => serde_json::to_string_pretty(nodes)
+            .map(|content| format!("{}\n", content))
+            .map_err(|error| error.to_string()),
+        "markdown" | "md" => Ok(render_outline_markdown(nodes)),
+        _ => Err(format!("Unsupported outline export format: {}", format)),
+    }
+}
+
+// Returns the rendered outline and, when a path is given, also writes it there.
+pub async fn export_outline_impl(
+    state: State<'_, AppState>,
+    id: String,
+    file_type: String,
+    format: String,
+    path: Option<String>,
+) -> Result<String, String> {
+    let nodes = get_outline_impl(state, id, file_type).await?;
+    let content = render_outline_export(&nodes, &format)?;
+
+    if let Some(path) = path.filter(|value| !value.trim().is_empty()) {
+        let output_path = std::path::PathBuf::from(path);
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(output_path, &content).map_err(|e| e.to_string())?;
+    }
+
+    Ok(content)
+}
+
 #[cfg(test)]
 mod outline_tests {
     use super::{
         build_symbol_outline_node, build_tree_sitter_outline_node, parse_ini_outline,
-        parse_markdown_outline, parse_outline_file_type, parse_xml_outline, OutlineFileType,
-        Parser,
+        parse_markdown_outline, parse_outline_file_type, parse_xml_outline, render_outline_export,
+        OutlineFileType, Parser,
     };
 
     fn collect_outline_labels(node: &super::OutlineNode, labels: &mut Vec<String>) {
@@ -1306,6 +1488,18 @@ mod outline_tests {
             parse_outline_file_type("swift"),
             Some(OutlineFileType::Swift)
         ));
+        assert!(matches!(
+            parse_outline_file_type("rb"),
+            Some(OutlineFileType::Ruby)
+        ));
+        assert!(matches!(
+            parse_outline_file_type("lua"),
+            Some(OutlineFileType::Lua)
+        ));
+        assert!(matches!(
+            parse_outline_file_type("sql"),
+            Some(OutlineFileType::Sql)
+        ));
     }
 
     #[test]
@@ -2338,6 +2532,126 @@ object Globals {}
             .any(|node| node.node_type == "function" && node.label == "fun execute()"));
     }
 
+    #[test]
+    fn ruby_outline_should_detect_modules_classes_and_methods() {
+        let source = r#"
+module Billing
+  class Invoice < Base
+    def self.build(attrs)
+    end
+
+    def total
+    end
+  end
+end
+"#;
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_ruby::LANGUAGE.into())
+            .expect("set ruby parser");
+        let tree = parser.parse(source, None).expect("parse ruby");
+
+        let mut symbols = Vec::new();
+        collect_symbol_outline_nodes(
+            tree.root_node(),
+            source,
+            OutlineFileType::Ruby,
+            &mut symbols,
+        );
+
+        let module_node = symbols
+            .iter()
+            .find(|node| node.node_type == "module" && node.label == "module Billing")
+            .expect("module Billing should exist");
+        let class_node = module_node
+            .children
+            .iter()
+            .find(|node| node.node_type == "class" && node.label == "class Invoice")
+            .expect("class Invoice should exist");
+        assert!(class_node
+            .children
+            .iter()
+            .any(|node| node.node_type == "method" && node.label == "def self.build()"));
+        assert!(class_node
+            .children
+            .iter()
+            .any(|node| node.node_type == "method" && node.label == "def total()"));
+    }
+
+    #[test]
+    fn lua_outline_should_detect_global_local_and_nested_functions() {
+        let source = r#"
+local M = {}
+
+function M.setup(opts)
+  local function merge(a, b)
+  end
+end
+
+local function helper()
+end
+
+return M
+"#;
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_lua::LANGUAGE.into())
+            .expect("set lua parser");
+        let tree = parser.parse(source, None).expect("parse lua");
+
+        let mut symbols = Vec::new();
+        collect_symbol_outline_nodes(tree.root_node(), source, OutlineFileType::Lua, &mut symbols);
+
+        let setup_node = symbols
+            .iter()
+            .find(|node| node.label == "function M.setup()")
+            .expect("M.setup should exist");
+        assert!(setup_node
+            .children
+            .iter()
+            .any(|node| node.label == "local function merge()"));
+        assert!(symbols
+            .iter()
+            .any(|node| node.node_type == "function" && node.label == "local function helper()"));
+    }
+
+    #[test]
+    fn sql_outline_should_detect_created_objects_and_table_columns() {
+        let source = r#"
+CREATE TABLE users (
+  id INT PRIMARY KEY,
+  email VARCHAR(255)
+);
+
+CREATE VIEW active_users AS SELECT id FROM users;
+"#;
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_sequel::LANGUAGE.into())
+            .expect("set sql parser");
+        let tree = parser.parse(source, None).expect("parse sql");
+
+        let mut symbols = Vec::new();
+        collect_symbol_outline_nodes(tree.root_node(), source, OutlineFileType::Sql, &mut symbols);
+
+        let table_node = symbols
+            .iter()
+            .find(|node| node.node_type == "table" && node.label == "table users")
+            .expect("table users should exist");
+        let columns: Vec<_> = table_node
+            .children
+            .iter()
+            .map(|node| node.label.as_str())
+            .collect();
+        assert_eq!(columns, vec!["id", "email"]);
+        assert!(symbols
+            .iter()
+            .any(|node| node.node_type == "view" && node.label == "view active_users"));
+    }
+
     #[test]
     fn swift_outline_should_detect_protocol_type_and_members() {
         let source = r#"
@@ -2392,4 +2706,23 @@ typealias UserId = String
             .iter()
             .any(|node| node.node_type == "function" && node.label == "func execute()"));
     }
+
+    #[test]
+    fn render_outline_export_should_nest_markdown_and_keep_json_tree() {
+        let nodes = parse_ini_outline("[server]\nport=8080\n[client]\n");
+
+        let markdown = render_outline_export(&nodes, "Markdown").expect("markdown export");
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert!(lines[0].starts_with("- [server]"));
+        assert!(lines[0].ends_with("(line 1)"));
+        assert!(lines.iter().any(|line| line.starts_with("  - port")));
+        assert!(lines.iter().any(|line| line.starts_with("- [client]")));
+
+        let json = render_outline_export(&nodes, "json").expect("json export");
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("valid json");
+        assert_eq!(parsed.as_array().map(Vec::len), Some(nodes.len()));
+        assert!(parsed[0]["children"].is_array());
+
+        assert!(render_outline_export(&nodes, "yaml").is_err());
+    }
 }
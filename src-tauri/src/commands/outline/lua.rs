@@ -0,0 +1,40 @@
+use super::*;
+
+pub(super) fn build_lua_outline_node(
+    node: tree_sitter::Node<'_>,
+    source: &str,
+) -> Option<OutlineNode> {
+    match node.kind() {
+        "function_declaration" | "local_function_declaration" => {
+            // Names keep their table path, e.g. `M.helpers.parse` or `Stack:push`.
+            let name = node
+                .child_by_field_name("name")
+                .map(|name_node| get_node_text_preview(name_node, source, 80))
+                .or_else(|| find_first_identifier_text(node, source))
+                .filter(|value| !value.trim().is_empty())
+                .unwrap_or_else(|| "anonymous".to_string());
+            let is_local = node.kind() == "local_function_declaration"
+                || source
+                    .get(node.start_byte()..node.end_byte())
+                    .unwrap_or("")
+                    .starts_with("local ");
+
+            let mut children = Vec::new();
+            if let Some(body) = node.child_by_field_name("body") {
+                collect_symbol_outline_nodes(body, source, OutlineFileType::Lua, &mut children);
+            }
+
+            Some(build_outline_node(
+                format!(
+                    "{}function {}()",
+                    if is_local { "local " } else { "" },
+                    name
+                ),
+                "function",
+                node,
+                children,
+            ))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+fn ruby_name(node: tree_sitter::Node<'_>, source: &str) -> String {
+    node.child_by_field_name("name")
+        .map(|name_node| get_node_text_preview(name_node, source, 80))
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "anonymous".to_string())
+}
+
+pub(super) fn build_ruby_outline_node(
+    node: tree_sitter::Node<'_>,
+    source: &str,
+) -> Option<OutlineNode> {
+    match node.kind() {
+        "class" | "module" => {
+            let keyword = node.kind();
+            let name = ruby_name(node, source);
+
+            // Members sit in the body statement; the name and superclass hold
+            // nothing that becomes a symbol.
+            let mut children = Vec::new();
+            collect_symbol_outline_nodes(node, source, OutlineFileType::Ruby, &mut children);
+
+            Some(build_outline_node(
+                format!("{} {}", keyword, name),
+                keyword,
+                node,
+                children,
+            ))
+        }
+        "singleton_class" => {
+            let target = node
+                .child_by_field_name("value")
+                .map(|value_node| get_node_text_preview(value_node, source, 80))
+                .filter(|value| !value.trim().is_empty())
+                .unwrap_or_else(|| "self".to_string());
+
+            let mut children = Vec::new();
+            collect_symbol_outline_nodes(node, source, OutlineFileType::Ruby, &mut children);
+
+            Some(build_outline_node(
+                format!("class << {}", target),
+                "class",
+                node,
+                children,
+            ))
+        }
+        "method" => Some(build_outline_node(
+            format!("def {}()", ruby_name(node, source)),
+            "method",
+            node,
+            Vec::new(),
+        )),
+        "singleton_method" => {
+            let object = node
+                .child_by_field_name("object")
+                .map(|object_node| get_node_text_preview(object_node, source, 80))
+                .filter(|value| !value.trim().is_empty())
+                .unwrap_or_else(|| "self".to_string());
+
+            Some(build_outline_node(
+                format!("def {}.{}()", object, ruby_name(node, source)),
+                "method",
+                node,
+                Vec::new(),
+            ))
+        }
+        _ => None,
+    }
+}
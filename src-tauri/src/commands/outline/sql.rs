@@ -0,0 +1,56 @@
+use super::*;
+
+fn build_sql_column_outline_nodes(
+    table_node: tree_sitter::Node<'_>,
+    source: &str,
+) -> Vec<OutlineNode> {
+    let Some(columns_node) = named_child_by_kind(table_node, "column_definitions") else {
+        return Vec::new();
+    };
+
+    let mut columns = Vec::new();
+    let mut cursor = columns_node.walk();
+    for column in columns_node.children(&mut cursor) {
+        if column.kind() != "column_definition" {
+            continue;
+        }
+
+        let name = column
+            .child_by_field_name("name")
+            .map(|name_node| get_node_text_preview(name_node, source, 80))
+            .or_else(|| find_first_identifier_text(column, source))
+            .filter(|value| !value.trim().is_empty());
+        if let Some(name) = name {
+            columns.push(build_outline_node(name, "column", column, Vec::new()));
+        }
+    }
+
+    columns
+}
+
+// Every `CREATE ...` statement becomes a symbol named after the object it
+// creates; tables also list their columns.
+pub(super) fn build_sql_outline_node(
+    node: tree_sitter::Node<'_>,
+    source: &str,
+) -> Option<OutlineNode> {
+    let object_kind = node.kind().strip_prefix("create_")?;
+    let name = named_child_by_kind(node, "object_reference")
+        .map(|name_node| get_node_text_preview(name_node, source, 80))
+        .or_else(|| find_first_identifier_text(node, source))
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "anonymous".to_string());
+
+    let children = if object_kind == "table" {
+        build_sql_column_outline_nodes(node, source)
+    } else {
+        Vec::new()
+    };
+
+    Some(build_outline_node(
+        format!("{} {}", object_kind.replace('_', " "), name),
+        object_kind,
+        node,
+        children,
+    ))
+}
@@ -0,0 +1,253 @@
+// Registry behind the command palette. The build script derives it from the
+// `generate_handler!` list in lib.rs, so every registered command is listed
+// and `invoke_palette_command` can only reach commands the frontend could
+// invoke itself. Calls are routed through the webview's regular IPC path.
+
+use super::*;
+use serde_json::Value;
+use tauri::ipc::{CallbackFn, InvokeBody, InvokeResponse, InvokeResponseBody};
+use tauri::webview::InvokeRequest;
+use tauri::{Manager, Webview};
+
+struct RegisteredCommand {
+    name: &'static str,
+    // Command module the handler is declared in; empty for commands.rs.
+    module: &'static str,
+    needs_document: bool,
+    // Async commands are timed by their `CommandTimer` argument rather than by
+    // the invoke handler.
+    is_async: bool,
+    // Required camelCase arguments other than `id`.
+    params: &'static [&'static str],
+}
+
+include!(concat!(env!("OUT_DIR"), "/registered_commands.rs"));
+
+// The palette's own commands are not offered inside the palette.
+const HIDDEN_COMMANDS: &[&str] = &["list_palette_commands", "invoke_palette_command"];
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteCommandPayload {
+    pub name: String,
+    pub category: String,
+    pub title: String,
+    // The active document id is passed as `id` when set.
+    pub needs_document: bool,
+    // Extra camelCase arguments the palette has to ask for.
+    pub params: Vec<String>,
+}
+
+fn command_category(module: &str) -> &'static str {
+    match module {
+        "file_io_commands" => "file",
+        "editing_commands" => "edit",
+        "search_commands" | "path_search_commands" => "search",
+        "git_commands" => "git",
+        "session_commands" => "session",
+        "terminal_commands" => "terminal",
+        _ => "tools",
+    }
+}
+
+// `sort_csv_by_column` -> `palette.sortCsvByColumn`.
+fn command_title_key(name: &str) -> String {
+    let mut key = String::from("palette.");
+    let mut upper_next = false;
+    for ch in name.chars() {
+        if ch == '_' {
+            upper_next = true;
+        } else if upper_next {
+            key.push(ch.to_ascii_uppercase());
+            upper_next = false;
+        } else {
+            key.push(ch);
+        }
+    }
+    key
+}
+
+// Fallback title for commands without a translation: `sort_csv_by_column`
+// -> `Sort csv by column`.
+fn humanize_command_name(name: &str) -> String {
+    let words = name.replace('_', " ");
+    let mut chars = words.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+fn palette_commands() -> impl Iterator<Item = &'static RegisteredCommand> {
+    REGISTERED_COMMANDS
+        .iter()
+        .filter(|command| !HIDDEN_COMMANDS.contains(&command.name))
+}
+
+pub(super) fn is_async_command_impl(name: &str) -> bool {
+    REGISTERED_COMMANDS
+        .iter()
+        .any(|command| command.is_async && command.name == name)
+}
+
+pub(super) fn list_palette_commands_impl(language: Option<String>) -> Vec<PaletteCommandPayload> {
+    let localizer = locale::Localizer::for_request(language);
+
+    palette_commands()
+        .map(|command| PaletteCommandPayload {
+            name: command.name.to_string(),
+            category: command_category(command.module).to_string(),
+            title: localizer
+                .optional_text(&command_title_key(command.name))
+                .unwrap_or_else(|| humanize_command_name(command.name)),
+            needs_document: command.needs_document,
+            params: command
+                .params
+                .iter()
+                .map(|param| param.to_string())
+                .collect(),
+        })
+        .collect()
+}
+
+fn palette_response_value(response: InvokeResponse) -> Result<Value, String> {
+    match response {
+        InvokeResponse::Ok(InvokeResponseBody::Json(json)) => {
+            serde_json::from_str(&json).map_err(|error| error.to_string())
+        }
+        InvokeResponse::Ok(InvokeResponseBody::Raw(bytes)) => {
+            serde_json::to_value(bytes).map_err(|error| error.to_string())
+        }
+        InvokeResponse::Err(error) => Err(match error.0 {
+            Value::String(message) => message,
+            other => other.to_string(),
+        }),
+    }
+}
+
+pub(super) async fn invoke_palette_command_impl(
+    webview: Webview,
+    name: String,
+    args: Option<Value>,
+) -> Result<Value, String> {
+    let command = palette_commands()
+        .find(|command| command.name == name)
+        .ok_or_else(|| format!("Unknown palette command: {}", name))?;
+    let args = match args {
+        None | Some(Value::Null) => Value::Object(Default::default()),
+        Some(Value::Object(args)) => Value::Object(args),
+        Some(_) => return Err("Palette command arguments must be an object".to_string()),
+    };
+    if command.needs_document && args.get("id").and_then(Value::as_str).is_none() {
+        return Err("Missing argument id".to_string());
+    }
+
+    let request = InvokeRequest {
+        cmd: command.name.to_string(),
+        callback: CallbackFn(0),
+        error: CallbackFn(1),
+        url: webview.url().map_err(|error| error.to_string())?,
+        body: InvokeBody::Json(args),
+        headers: Default::default(),
+        invoke_key: webview.app_handle().invoke_key().to_string(),
+    };
+    let (sender, receiver) = std::sync::mpsc::channel();
+    webview.clone().on_message(
+        request,
+        Box::new(move |_webview, _command, response, _callback, _error| {
+            let _ = sender.send(response);
+        }),
+    );
+
+    let response = tauri::async_runtime::spawn_blocking(move || receiver.recv())
+        .await
+        .map_err(|error| error.to_string())?
+        .map_err(|_| format!("Palette command {} did not respond", name))?;
+    palette_response_value(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_commands_should_match_the_handler_list() {
+        let handlers = include_str!("../lib.rs");
+        let mut names = HashSet::new();
+
+        for command in REGISTERED_COMMANDS {
+            assert!(names.insert(command.name), "duplicate {}", command.name);
+            assert!(
+                handlers.contains(&format!("::{},", command.name)),
+                "{} is not registered",
+                command.name
+            );
+        }
+        assert!(names.contains("open_file"));
+        assert!(names.contains("run_idle_maintenance"));
+    }
+
+    #[test]
+    fn registered_commands_should_describe_document_and_required_arguments() {
+        let find = |name: &str| {
+            REGISTERED_COMMANDS
+                .iter()
+                .find(|command| command.name == name)
+                .expect("registered command")
+        };
+
+        assert!(find("undo").needs_document);
+        assert!(find("undo").params.is_empty());
+        assert!(!find("open_file").needs_document);
+        assert!(!find("close_terminal").needs_document);
+        assert_eq!(find("close_terminal").params, &["id"]);
+        assert!(!find("discard_recovery_file").needs_document);
+        assert!(find("open_file").is_async);
+        assert!(!find("undo").is_async);
+        assert!(is_async_command_impl("search_first_in_document"));
+        assert!(!is_async_command_impl("get_visible_lines"));
+        assert_eq!(find("format_document").params, &["mode"]);
+        assert_eq!(find("format_document").module, "editing_commands");
+    }
+
+    #[test]
+    fn list_palette_commands_should_localize_titles_and_hide_palette_commands() {
+        let english = list_palette_commands_impl(Some("en-US".to_string()));
+        let chinese = list_palette_commands_impl(Some("zh-CN".to_string()));
+        let title = |commands: &[PaletteCommandPayload], name: &str| {
+            commands
+                .iter()
+                .find(|command| command.name == name)
+                .map(|command| command.title.clone())
+        };
+
+        assert_eq!(
+            english.len(),
+            REGISTERED_COMMANDS.len() - HIDDEN_COMMANDS.len()
+        );
+        assert_eq!(title(&english, "new_file").as_deref(), Some("New File"));
+        assert_eq!(title(&chinese, "new_file").as_deref(), Some("新建文件"));
+        assert_eq!(
+            title(&english, "sort_csv_by_column").as_deref(),
+            Some("Sort csv by column")
+        );
+        assert_eq!(title(&english, "invoke_palette_command"), None);
+    }
+
+    #[test]
+    fn palette_response_value_should_unwrap_json_and_errors() {
+        let ok = InvokeResponse::Ok(InvokeResponseBody::Json("{\"lineCount\":3}".to_string()));
+        let error = InvokeResponse::Err(tauri::ipc::InvokeError(Value::String(
+            "Document not found".to_string(),
+        )));
+
+        assert_eq!(
+            palette_response_value(ok),
+            Ok(serde_json::json!({ "lineCount": 3 }))
+        );
+        assert_eq!(
+            palette_response_value(error),
+            Err("Document not found".to_string())
+        );
+    }
+}
@@ -0,0 +1,360 @@
+// Hunks of unified diffs (`.diff` / `.patch` documents): their boundaries and
+// the files they change, for jumping between hunks and for opening the patched
+// file at the line a hunk touches. Paths in the patch are resolved against the
+// folder of the patch file and its ancestors, with and without the `a/` / `b/`
+// prefixes git adds.
+
+use super::*;
+use std::path::{Path, PathBuf};
+
+// How many folders above the patch file are searched for patched files.
+const MAX_PATCH_SOURCE_ANCESTORS: usize = 4;
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchHunk {
+    // 1-based lines of the `@@` header and of the hunk's last line.
+    pub start_line: usize,
+    pub end_line: usize,
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    // Text after the closing `@@`, usually the enclosing function.
+    pub section: String,
+}
+
+impl PatchHunk {
+    // The file the hunk applies to; deleted files only have the old side.
+    fn target_path(&self) -> Option<&str> {
+        self.new_path.as_deref().or(self.old_path.as_deref())
+    }
+}
+
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchHunkSourceLocation {
+    pub path: String,
+    pub line: usize,
+}
+
+fn parse_hunk_range(value: &str) -> Option<(usize, usize)> {
+    let (start, count) = match value.split_once(',') {
+        Some((start, count)) => (start, count.parse().ok()?),
+        None => (value, 1),
+    };
+
+    Some((start.parse().ok()?, count))
+}
+
+// `@@ -12,7 +12,8 @@ fn main() {`
+fn parse_hunk_header(line: &str) -> Option<((usize, usize), (usize, usize), String)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old_range, new_range) = ranges.split_once(" +")?;
+
+    Some((
+        parse_hunk_range(old_range)?,
+        parse_hunk_range(new_range)?,
+        section.trim().to_string(),
+    ))
+}
+
+// Drops the timestamp some tools append after a tab; `/dev/null` stands for
+// the missing side of an added or deleted file.
+fn parse_file_header_path(value: &str) -> Option<String> {
+    let path = value
+        .split('\t')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_matches('"');
+    if path.is_empty() || path == "/dev/null" {
+        None
+    } else {
+        Some(path.to_string())
+    }
+}
+
+// `diff --git a/old b/new`, used until `---` / `+++` headers say otherwise
+// and on their own for renames without content changes.
+fn parse_git_diff_paths(value: &str) -> (Option<String>, Option<String>) {
+    match value.rsplit_once(" b/") {
+        Some((old_path, new_path)) => (
+            Some(old_path.trim().to_string()),
+            Some(format!("b/{}", new_path.trim())),
+        ),
+        None => (None, None),
+    }
+}
+
+fn parse_patch_hunks(text: &str) -> Vec<PatchHunk> {
+    let mut hunks: Vec<PatchHunk> = Vec::new();
+    let mut old_path = None;
+    let mut new_path = None;
+    // Lines the open hunk still expects on the old and new side.
+    let mut open_hunk: Option<(usize, usize)> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+
+        if let Some((old_left, new_left)) = open_hunk {
+            let pending = old_left > 0 || new_left > 0;
+            // Some tools strip the leading space of blank context lines.
+            let next = match line.as_bytes().first() {
+                Some(b'\\') => Some((old_left, new_left)),
+                Some(b' ') | None if pending => {
+                    Some((old_left.saturating_sub(1), new_left.saturating_sub(1)))
+                }
+                Some(b'-') if old_left > 0 => Some((old_left - 1, new_left)),
+                Some(b'+') if new_left > 0 => Some((old_left, new_left - 1)),
+                _ => None,
+            };
+            if let Some(next) = next {
+                if let Some(hunk) = hunks.last_mut() {
+                    hunk.end_line = line_number;
+                }
+                open_hunk = Some(next);
+                continue;
+            }
+            open_hunk = None;
+        }
+
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            (old_path, new_path) = parse_git_diff_paths(rest);
+        } else if let Some(rest) = line.strip_prefix("--- ") {
+            old_path = parse_file_header_path(rest);
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            new_path = parse_file_header_path(rest);
+        } else if let Some(((old_start, old_lines), (new_start, new_lines), section)) =
+            parse_hunk_header(line)
+        {
+            hunks.push(PatchHunk {
+                start_line: line_number,
+                end_line: line_number,
+                old_path: old_path.clone(),
+                new_path: new_path.clone(),
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                section,
+            });
+            open_hunk = Some((old_lines, new_lines));
+        }
+    }
+
+    hunks
+}
+
+// The hunk a cursor line belongs to; lines between hunks go with the hunk
+// above them.
+fn hunk_at_line(hunks: &[PatchHunk], line: usize) -> Option<&PatchHunk> {
+    hunks.iter().rev().find(|hunk| hunk.start_line <= line)
+}
+
+fn adjacent_patch_hunk(hunks: &[PatchHunk], line: usize, forward: bool) -> Option<&PatchHunk> {
+    if forward {
+        hunks.iter().find(|hunk| hunk.start_line > line)
+    } else {
+        hunks.iter().rev().find(|hunk| hunk.start_line < line)
+    }
+}
+
+// Line in the patched file for a cursor line inside a hunk: the line itself
+// for context and added lines, the next surviving line for removed ones.
+fn hunk_target_line(hunk: &PatchHunk, rope: &Rope, line: usize) -> usize {
+    let use_new_side = hunk.new_path.is_some() || hunk.old_path.is_none();
+    let (start, side_marker) = if use_new_side {
+        (hunk.new_start, '+')
+    } else {
+        (hunk.old_start, '-')
+    };
+
+    // Zero-based indexes of the hunk lines between the header and the cursor.
+    let end_index = line
+        .saturating_sub(1)
+        .min(hunk.end_line)
+        .min(rope.len_lines());
+    let offset = (hunk.start_line..end_index)
+        .filter(|line_index| {
+            let marker = rope.line(*line_index).chars().next();
+            matches!(marker, None | Some(' ' | '\n' | '\r')) || marker == Some(side_marker)
+        })
+        .count();
+
+    start.max(1) + offset
+}
+
+fn strip_git_path_prefix(path: &str) -> Option<&str> {
+    path.strip_prefix("a/").or_else(|| path.strip_prefix("b/"))
+}
+
+fn resolve_patched_file_path(patch_dir: &Path, patch_path: &str) -> Option<PathBuf> {
+    let candidate = Path::new(patch_path);
+    if candidate.is_absolute() {
+        return candidate.is_file().then(|| candidate.to_path_buf());
+    }
+
+    let relative_paths: Vec<&str> = strip_git_path_prefix(patch_path)
+        .into_iter()
+        .chain(std::iter::once(patch_path))
+        .collect();
+    patch_dir
+        .ancestors()
+        .take(MAX_PATCH_SOURCE_ANCESTORS + 1)
+        .flat_map(|dir| relative_paths.iter().map(move |path| dir.join(path)))
+        .find(|path| path.is_file())
+}
+
+fn document_patch_hunks(
+    state: &State<'_, AppState>,
+    id: &str,
+) -> Result<(Vec<PatchHunk>, Rope, Option<PathBuf>), String> {
+    let doc = state
+        .documents
+        .get(id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let rope = doc.rope.clone();
+    let path = doc.path.clone();
+    drop(doc);
+
+    Ok((parse_patch_hunks(&rope.to_string()), rope, path))
+}
+
+pub(super) fn get_patch_hunks_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<PatchHunk>, String> {
+    document_patch_hunks(&state, &id).map(|(hunks, ..)| hunks)
+}
+
+pub(super) fn get_adjacent_patch_hunk_impl(
+    state: State<'_, AppState>,
+    id: String,
+    line: usize,
+    forward: bool,
+) -> Result<Option<PatchHunk>, String> {
+    let (hunks, ..) = document_patch_hunks(&state, &id)?;
+    Ok(adjacent_patch_hunk(&hunks, line, forward).cloned())
+}
+
+pub(super) fn resolve_patch_hunk_source_impl(
+    state: State<'_, AppState>,
+    id: String,
+    line: usize,
+) -> Result<Option<PatchHunkSourceLocation>, String> {
+    let (hunks, rope, patch_path) = document_patch_hunks(&state, &id)?;
+    let Some(hunk) = hunk_at_line(&hunks, line) else {
+        return Ok(None);
+    };
+    let Some(target_path) = hunk.target_path() else {
+        return Ok(None);
+    };
+    let patch_dir = patch_path
+        .as_deref()
+        .and_then(Path::parent)
+        .ok_or_else(|| "Save the patch to open the files it changes".to_string())?;
+    let source_path = resolve_patched_file_path(patch_dir, target_path)
+        .ok_or_else(|| format!("Patched file not found: {}", target_path))?;
+
+    Ok(Some(PatchHunkSourceLocation {
+        path: source_path.to_string_lossy().to_string(),
+        line: hunk_target_line(hunk, &rope, line),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_PATCH: &str = "diff --git a/src/main.rs b/src/main.rs
+index 1111111..2222222 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -10,3 +10,4 @@ fn main() {
+     let a = 1;
+-    let b = 2;
++    let b = 3;
++    let c = 4;
+     run(a, b);
+
+@@ -40 +41 @@ fn run() {
+-old
++new
+\\ No newline at end of file
+diff --git a/notes.txt b/notes.txt
+deleted file mode 100644
+--- a/notes.txt\t2024-05-01 10:00:00
++++ /dev/null
+@@ -1,2 +0,0 @@
+-first
+-second
+";
+
+    #[test]
+    fn parse_patch_hunks_should_track_boundaries_and_files() {
+        let hunks = parse_patch_hunks(SAMPLE_PATCH);
+
+        assert_eq!(hunks.len(), 3);
+        assert_eq!((hunks[0].start_line, hunks[0].end_line), (5, 10));
+        assert_eq!(hunks[0].new_path.as_deref(), Some("b/src/main.rs"));
+        assert_eq!((hunks[0].new_start, hunks[0].new_lines), (10, 4));
+        assert_eq!(hunks[0].section, "fn main() {");
+        assert_eq!((hunks[1].start_line, hunks[1].end_line), (12, 15));
+        assert_eq!((hunks[1].old_lines, hunks[1].new_lines), (1, 1));
+        assert_eq!(hunks[2].old_path.as_deref(), Some("a/notes.txt"));
+        assert_eq!(hunks[2].new_path, None);
+        assert_eq!(hunks[2].target_path(), Some("a/notes.txt"));
+
+        assert_eq!(
+            adjacent_patch_hunk(&hunks, 7, true).map(|h| h.start_line),
+            Some(12)
+        );
+        assert_eq!(
+            adjacent_patch_hunk(&hunks, 14, false).map(|h| h.start_line),
+            Some(12)
+        );
+        assert_eq!(
+            adjacent_patch_hunk(&hunks, 12, false).map(|h| h.start_line),
+            Some(5)
+        );
+        assert_eq!(adjacent_patch_hunk(&hunks, 5, false), None);
+        assert_eq!(adjacent_patch_hunk(&hunks, 20, true), None);
+    }
+
+    #[test]
+    fn hunk_target_line_should_follow_new_side_lines() {
+        let rope = Rope::from_str(SAMPLE_PATCH);
+        let hunks = parse_patch_hunks(SAMPLE_PATCH);
+
+        // Header, context line, removed line, second added line.
+        assert_eq!(hunk_target_line(&hunks[0], &rope, 5), 10);
+        assert_eq!(hunk_target_line(&hunks[0], &rope, 6), 10);
+        assert_eq!(hunk_target_line(&hunks[0], &rope, 7), 11);
+        assert_eq!(hunk_target_line(&hunks[0], &rope, 9), 12);
+        assert_eq!(hunk_target_line(&hunks[2], &rope, 22), 2);
+    }
+
+    #[test]
+    fn resolve_patched_file_path_should_strip_git_prefix_and_search_ancestors() {
+        let root = std::env::temp_dir().join(format!("rutar-patch-hunks-{}", Uuid::new_v4()));
+        let patch_dir = root.join("patches");
+        fs::create_dir_all(root.join("src")).expect("create src");
+        fs::create_dir_all(&patch_dir).expect("create patches");
+        fs::write(root.join("src").join("main.rs"), "fn main() {}\n").expect("write source");
+
+        assert_eq!(
+            resolve_patched_file_path(&patch_dir, "b/src/main.rs"),
+            Some(root.join("src").join("main.rs"))
+        );
+        assert_eq!(
+            resolve_patched_file_path(&patch_dir, "b/src/missing.rs"),
+            None
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
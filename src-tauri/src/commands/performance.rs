@@ -0,0 +1,266 @@
+// Command timing for diagnosing freezes. Each command's duration is kept,
+// together with a sanitized summary of its arguments, in a fixed-size ring
+// buffer. The invoke handler times sync commands around their dispatch. Async
+// commands only get spawned there, so they take a `CommandTimer` argument that
+// records them when their future finishes.
+
+use super::*;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::{CommandArg, CommandItem, InvokeBody, InvokeError};
+use tauri::Runtime;
+
+const PERFORMANCE_SAMPLE_CAPACITY: usize = 512;
+const DEFAULT_PERFORMANCE_REPORT_LIMIT: usize = 20;
+const PARAM_STRING_MAX_CHARS: usize = 64;
+const PARAM_ARRAY_MAX_ITEMS: usize = 8;
+const PARAM_MAX_DEPTH: usize = 3;
+// Argument names whose values are never recorded, compared case-insensitively.
+const REDACTED_PARAM_KEYS: &[&str] = &["token", "password", "secret", "apikey"];
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceSample {
+    pub command: String,
+    pub params: String,
+    pub duration_ms: f64,
+    pub finished_at_ms: u64,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandPerformanceSummary {
+    pub command: String,
+    pub count: usize,
+    pub total_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceReport {
+    pub sample_count: usize,
+    pub capacity: usize,
+    // Slowest first.
+    pub slowest: Vec<PerformanceSample>,
+    // Busiest commands first, by total time.
+    pub by_command: Vec<CommandPerformanceSummary>,
+}
+
+fn performance_samples() -> &'static Mutex<VecDeque<PerformanceSample>> {
+    static SAMPLES: OnceLock<Mutex<VecDeque<PerformanceSample>>> = OnceLock::new();
+    SAMPLES.get_or_init(|| Mutex::new(VecDeque::with_capacity(PERFORMANCE_SAMPLE_CAPACITY)))
+}
+
+// Keeps the shape of the arguments while dropping their bulk: long strings and
+// arrays are reduced to their size, secrets are redacted and nesting is cut.
+fn sanitize_param_value(value: &Value, depth: usize) -> Value {
+    match value {
+        Value::String(text) => {
+            let char_count = text.chars().count();
+            if char_count > PARAM_STRING_MAX_CHARS {
+                Value::String(format!("<{} chars>", char_count))
+            } else {
+                value.clone()
+            }
+        }
+        Value::Array(items) => {
+            if depth >= PARAM_MAX_DEPTH || items.len() > PARAM_ARRAY_MAX_ITEMS {
+                Value::String(format!("<{} items>", items.len()))
+            } else {
+                Value::Array(
+                    items
+                        .iter()
+                        .map(|item| sanitize_param_value(item, depth + 1))
+                        .collect(),
+                )
+            }
+        }
+        Value::Object(fields) => {
+            if depth >= PARAM_MAX_DEPTH {
+                return Value::String(format!("<{} fields>", fields.len()));
+            }
+            let sanitized: Map<String, Value> = fields
+                .iter()
+                .map(|(key, field)| {
+                    let lower_key = key.to_ascii_lowercase();
+                    let sanitized = if REDACTED_PARAM_KEYS.contains(&lower_key.as_str()) {
+                        Value::String("<redacted>".to_string())
+                    } else {
+                        sanitize_param_value(field, depth + 1)
+                    };
+                    (key.clone(), sanitized)
+                })
+                .collect();
+            Value::Object(sanitized)
+        }
+        _ => value.clone(),
+    }
+}
+
+pub(super) fn summarize_command_params_impl(params: &Value) -> String {
+    sanitize_param_value(params, 0).to_string()
+}
+
+pub(super) fn summarize_invoke_body_impl(body: &InvokeBody) -> String {
+    match body {
+        InvokeBody::Json(value) => summarize_command_params_impl(value),
+        InvokeBody::Raw(bytes) => format!("<{} bytes>", bytes.len()),
+    }
+}
+
+// Injected by Tauri like `State`; the frontend never passes it. It is built
+// when the command's future starts running and records the command when the
+// future drops it, i.e. once the command has finished.
+pub struct CommandTimer {
+    command: String,
+    params: String,
+    started: Instant,
+}
+
+impl<'de, R: Runtime> CommandArg<'de, R> for CommandTimer {
+    fn from_command(item: CommandItem<'de, R>) -> Result<Self, InvokeError> {
+        Ok(Self {
+            command: item.message.command().to_string(),
+            params: summarize_invoke_body_impl(item.message.payload()),
+            started: Instant::now(),
+        })
+    }
+}
+
+impl Drop for CommandTimer {
+    fn drop(&mut self) {
+        record_command_timing_impl(
+            &self.command,
+            std::mem::take(&mut self.params),
+            self.started.elapsed(),
+        );
+    }
+}
+
+pub(super) fn record_command_timing_impl(command: &str, params: String, elapsed: Duration) {
+    let sample = PerformanceSample {
+        command: command.to_string(),
+        params,
+        duration_ms: elapsed.as_secs_f64() * 1000.0,
+        finished_at_ms: now_unix_millis(),
+    };
+
+    let mut samples = performance_samples()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if samples.len() >= PERFORMANCE_SAMPLE_CAPACITY {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+fn build_performance_report(
+    samples: &VecDeque<PerformanceSample>,
+    limit: usize,
+) -> PerformanceReport {
+    let mut slowest: Vec<PerformanceSample> = samples.iter().cloned().collect();
+    slowest.sort_by(|left, right| right.duration_ms.total_cmp(&left.duration_ms));
+    slowest.truncate(limit);
+
+    let mut totals: HashMap<&str, CommandPerformanceSummary> = HashMap::new();
+    for sample in samples {
+        let summary =
+            totals
+                .entry(sample.command.as_str())
+                .or_insert_with(|| CommandPerformanceSummary {
+                    command: sample.command.clone(),
+                    count: 0,
+                    total_ms: 0.0,
+                    max_ms: 0.0,
+                });
+        summary.count += 1;
+        summary.total_ms += sample.duration_ms;
+        summary.max_ms = summary.max_ms.max(sample.duration_ms);
+    }
+    let mut by_command: Vec<CommandPerformanceSummary> = totals.into_values().collect();
+    by_command.sort_by(|left, right| {
+        right
+            .total_ms
+            .total_cmp(&left.total_ms)
+            .then_with(|| left.command.cmp(&right.command))
+    });
+    by_command.truncate(limit);
+
+    PerformanceReport {
+        sample_count: samples.len(),
+        capacity: PERFORMANCE_SAMPLE_CAPACITY,
+        slowest,
+        by_command,
+    }
+}
+
+pub(super) fn get_performance_report_impl(limit: Option<usize>) -> PerformanceReport {
+    let samples = performance_samples()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    build_performance_report(
+        &samples,
+        limit.unwrap_or(DEFAULT_PERFORMANCE_REPORT_LIMIT).max(1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_sample(command: &str, duration_ms: f64) -> PerformanceSample {
+        PerformanceSample {
+            command: command.to_string(),
+            params: String::new(),
+            duration_ms,
+            finished_at_ms: 0,
+        }
+    }
+
+    #[test]
+    fn summarize_command_params_should_shrink_bulk_and_redact_secrets() {
+        let params = json!({
+            "id": "doc-1",
+            "text": "x".repeat(500),
+            "lines": [1, 2, 3, 4, 5, 6, 7, 8, 9],
+            "token": "s3cret",
+            "keyword": "error",
+        });
+
+        let summary: Value =
+            serde_json::from_str(&summarize_command_params_impl(&params)).expect("valid json");
+
+        assert_eq!(summary["id"], "doc-1");
+        assert_eq!(summary["text"], "<500 chars>");
+        assert_eq!(summary["lines"], "<9 items>");
+        assert_eq!(summary["token"], "<redacted>");
+        assert_eq!(summary["keyword"], "error");
+    }
+
+    #[test]
+    fn build_performance_report_should_rank_slowest_and_busiest() {
+        let samples: VecDeque<PerformanceSample> = vec![
+            make_sample("get_visible_lines", 2.0),
+            make_sample("search_count_in_document", 340.0),
+            make_sample("get_visible_lines", 3.0),
+            make_sample("format_document", 120.0),
+        ]
+        .into();
+
+        let report = build_performance_report(&samples, 2);
+
+        let slowest: Vec<&str> = report
+            .slowest
+            .iter()
+            .map(|sample| sample.command.as_str())
+            .collect();
+        assert_eq!(slowest, vec!["search_count_in_document", "format_document"]);
+        assert_eq!(report.by_command.len(), 2);
+        assert_eq!(report.by_command[0].command, "search_count_in_document");
+        assert_eq!(report.sample_count, 4);
+    }
+}
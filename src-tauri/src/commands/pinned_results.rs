@@ -0,0 +1,270 @@
+// Named snapshots of search/filter session results. A pin copies the matched
+// lines out of the session so later edits cannot change it; on open each entry
+// is compared with the current line text and flagged stale when it differs.
+// Pins are kept in `pinned_results.json` next to the config file.
+
+use super::json_store::JsonStore;
+use super::*;
+use std::path::Path;
+
+const MAX_PINNED_RESULT_SETS: usize = 50;
+const MAX_PINNED_RESULT_ENTRIES: usize = 10_000;
+
+static PINNED_RESULTS: JsonStore<Vec<PinnedResultSet>> =
+    JsonStore::new("pinned_results.json", "Pinned results are unavailable");
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedResultEntry {
+    // 1-based line and char column, as in search and filter results.
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+    pub line_text: String,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedResultSet {
+    pub name: String,
+    // "search" or "filter".
+    pub kind: String,
+    pub document_id: String,
+    pub document_path: Option<String>,
+    pub document_version: u64,
+    pub pinned_at_ms: u64,
+    pub truncated: bool,
+    pub entries: Vec<PinnedResultEntry>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedResultSummary {
+    pub name: String,
+    pub kind: String,
+    pub document_path: Option<String>,
+    pub match_count: usize,
+    pub pinned_at_ms: u64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedResultEntryPayload {
+    #[serde(flatten)]
+    pub entry: PinnedResultEntry,
+    // The line no longer holds the pinned text, or the document is not open.
+    pub stale: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenPinnedResultsPayload {
+    pub name: String,
+    pub kind: String,
+    // Open document the pin was resolved against, matched by id then path.
+    pub document_id: Option<String>,
+    pub document_path: Option<String>,
+    pub truncated: bool,
+    pub stale_count: usize,
+    pub entries: Vec<PinnedResultEntryPayload>,
+}
+
+// (line, column, length) of every match, in session order.
+fn session_match_positions(
+    session_id: &str,
+) -> Option<(String, String, Vec<(usize, usize, usize)>)> {
+    if let Some(session) = search_session_cache().get(session_id) {
+        let positions = session
+            .matches
+            .iter()
+            .map(|item| (item.line, item.column, item.end_char - item.start_char))
+            .collect();
+        return Some(("search".to_string(), session.document_id.clone(), positions));
+    }
+
+    filter_session_cache().get(session_id).map(|session| {
+        let positions = session
+            .matches
+            .iter()
+            .map(|item| (item.line, item.column, item.length))
+            .collect();
+        ("filter".to_string(), session.document_id.clone(), positions)
+    })
+}
+
+fn build_pinned_entries(
+    rope: &Rope,
+    positions: &[(usize, usize, usize)],
+) -> (Vec<PinnedResultEntry>, bool) {
+    let entries = positions
+        .iter()
+        .take(MAX_PINNED_RESULT_ENTRIES)
+        .filter(|(line, _, _)| *line >= 1 && *line <= rope.len_lines())
+        .map(|&(line, column, length)| PinnedResultEntry {
+            line,
+            column,
+            length,
+            line_text: editing::trimmed_line_without_break(rope, line - 1),
+        })
+        .collect();
+
+    (entries, positions.len() > MAX_PINNED_RESULT_ENTRIES)
+}
+
+fn mark_stale_entries(
+    rope: Option<&Rope>,
+    entries: &[PinnedResultEntry],
+) -> Vec<PinnedResultEntryPayload> {
+    entries
+        .iter()
+        .map(|entry| {
+            let stale = match rope {
+                Some(rope) if entry.line <= rope.len_lines() => {
+                    editing::trimmed_line_without_break(rope, entry.line - 1) != entry.line_text
+                }
+                _ => true,
+            };
+            PinnedResultEntryPayload {
+                entry: entry.clone(),
+                stale,
+            }
+        })
+        .collect()
+}
+
+pub(super) fn pin_search_results_impl(
+    state: State<'_, AppState>,
+    session_id: String,
+    name: String,
+) -> Result<PinnedResultSummary, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Pinned result name must not be empty".to_string());
+    }
+
+    let (kind, document_id, positions) = session_match_positions(&session_id)
+        .ok_or_else(|| "Search session not found".to_string())?;
+    let (entries, truncated, document_path, document_version) = {
+        let doc = state
+            .documents
+            .get(&document_id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        let (entries, truncated) = build_pinned_entries(&doc.rope, &positions);
+        (
+            entries,
+            truncated,
+            doc.path
+                .as_ref()
+                .map(|path| path.to_string_lossy().to_string()),
+            doc.document_version,
+        )
+    };
+
+    let pinned_at_ms = now_unix_millis();
+    let set = PinnedResultSet {
+        name: name.clone(),
+        kind,
+        document_id,
+        document_path,
+        document_version,
+        pinned_at_ms,
+        truncated,
+        entries,
+    };
+    let summary = PinnedResultSummary {
+        name,
+        kind: set.kind.clone(),
+        document_path: set.document_path.clone(),
+        match_count: set.entries.len(),
+        pinned_at_ms,
+    };
+
+    PINNED_RESULTS.update(|sets| {
+        sets.retain(|existing| existing.name != set.name);
+        sets.push(set);
+        if sets.len() > MAX_PINNED_RESULT_SETS {
+            let overflow = sets.len() - MAX_PINNED_RESULT_SETS;
+            sets.drain(..overflow);
+        }
+        Ok(())
+    })?;
+
+    Ok(summary)
+}
+
+pub(super) fn list_pinned_results_impl() -> Result<Vec<PinnedResultSummary>, String> {
+    let sets = PINNED_RESULTS.lock()?;
+
+    Ok(sets
+        .iter()
+        .map(|set| PinnedResultSummary {
+            name: set.name.clone(),
+            kind: set.kind.clone(),
+            document_path: set.document_path.clone(),
+            match_count: set.entries.len(),
+            pinned_at_ms: set.pinned_at_ms,
+        })
+        .collect())
+}
+
+pub(super) fn open_pinned_results_impl(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<OpenPinnedResultsPayload, String> {
+    let set = PINNED_RESULTS
+        .lock()?
+        .iter()
+        .find(|set| set.name == name)
+        .cloned()
+        .ok_or_else(|| "Pinned results not found".to_string())?;
+
+    // Document ids do not survive a restart, so fall back to the path.
+    let document = state
+        .documents
+        .get(&set.document_id)
+        .map(|doc| (set.document_id.clone(), doc.rope.clone()))
+        .or_else(|| {
+            let path = set.document_path.as_deref()?;
+            state.documents.iter().find_map(|entry| {
+                let doc = entry.value();
+                (doc.path.as_deref() == Some(Path::new(path)))
+                    .then(|| (entry.key().clone(), doc.rope.clone()))
+            })
+        });
+
+    let entries = mark_stale_entries(document.as_ref().map(|(_, rope)| rope), &set.entries);
+    let stale_count = entries.iter().filter(|entry| entry.stale).count();
+
+    Ok(OpenPinnedResultsPayload {
+        name: set.name,
+        kind: set.kind,
+        document_id: document.map(|(id, _)| id),
+        document_path: set.document_path,
+        truncated: set.truncated,
+        stale_count,
+        entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_entries_should_be_marked_stale_after_edits() {
+        let rope = Rope::from_str("alpha\nbeta\ngamma\n");
+        let (entries, truncated) = build_pinned_entries(&rope, &[(1, 1, 5), (3, 2, 3), (9, 1, 1)]);
+
+        assert!(!truncated);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].line_text, "gamma");
+
+        let edited = Rope::from_str("alpha\nbeta\ngamma ray\n");
+        let marked = mark_stale_entries(Some(&edited), &entries);
+        assert!(!marked[0].stale);
+        assert!(marked[1].stale);
+        assert!(mark_stale_entries(None, &entries)
+            .iter()
+            .all(|entry| entry.stale));
+    }
+}
@@ -0,0 +1,74 @@
+// Opening policy for files that are risky to treat as ordinary documents:
+// anything over `previewModeThresholdMb` or under one of `untrustedFolders`
+// opens in preview mode. Preview documents reject edits, resolve to plain text
+// so nothing parses them, and get no outline until the user promotes them.
+
+use super::*;
+use std::path::Path;
+
+fn is_over_preview_threshold(size_bytes: u64, threshold_mb: u64) -> bool {
+    threshold_mb > 0 && size_bytes > threshold_mb.saturating_mul(1024 * 1024)
+}
+
+// Compares whole path components, so `/var/log` does not cover `/var/logs`.
+fn is_under_untrusted_folder(path: &Path, untrusted_folders: &[String]) -> bool {
+    let canonical_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    untrusted_folders.iter().any(|folder| {
+        let folder = Path::new(folder);
+        let canonical_folder = fs::canonicalize(folder).unwrap_or_else(|_| folder.to_path_buf());
+        path.starts_with(folder) || canonical_path.starts_with(&canonical_folder)
+    })
+}
+
+pub(super) fn should_open_in_preview_mode(path: &Path, size_bytes: u64) -> bool {
+    let config = config::load_config_impl().unwrap_or_default();
+    is_over_preview_threshold(size_bytes, config.preview_mode_threshold_mb)
+        || is_under_untrusted_folder(path, &config.untrusted_folders)
+}
+
+// Returns whether the document was in preview mode.
+pub(super) fn promote_document_to_full_mode_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<bool, String> {
+    if streaming_document::is_streaming_document(&id) {
+        return Err("Streamed files are read-only; they cannot be promoted".to_string());
+    }
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let was_preview = doc.preview_mode;
+    doc.preview_mode = false;
+    Ok(was_preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_over_preview_threshold_should_treat_zero_as_disabled() {
+        assert!(!is_over_preview_threshold(u64::MAX, 0));
+        assert!(!is_over_preview_threshold(1024 * 1024, 1));
+        assert!(is_over_preview_threshold(1024 * 1024 + 1, 1));
+    }
+
+    #[test]
+    fn is_under_untrusted_folder_should_match_whole_components() {
+        let untrusted = vec!["/srv/prod/logs".to_string()];
+
+        assert!(is_under_untrusted_folder(
+            Path::new("/srv/prod/logs/app.log"),
+            &untrusted
+        ));
+        assert!(!is_under_untrusted_folder(
+            Path::new("/srv/prod/logs2/app.log"),
+            &untrusted
+        ));
+        assert!(!is_under_untrusted_folder(
+            Path::new("/srv/prod/app.log"),
+            &[]
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+// Lossless save support for files whose bytes could not be fully decoded.
+//
+// Decoding replaces malformed sequences with U+FFFD, so a plain re-encode on
+// save would silently corrupt binary-ish logs. When a document keeps its
+// original bytes, unchanged lines are copied back verbatim and only edited
+// lines are re-encoded.
+
+use super::*;
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+use std::ops::Range;
+
+struct RawLine {
+    content: Range<usize>,
+    terminator: Range<usize>,
+}
+
+pub(super) fn should_preserve_raw_bytes(encoding: &'static Encoding, malformed: bool) -> bool {
+    // Byte-level line splitting is only sound when CR/LF can never appear
+    // inside a multi-byte sequence.
+    malformed && encoding.is_ascii_compatible()
+}
+
+fn split_raw_lines(bytes: &[u8], start: usize) -> Vec<RawLine> {
+    let mut lines = Vec::new();
+    let mut line_start = start;
+    let mut index = start;
+
+    while index < bytes.len() {
+        let terminator_len = match bytes[index] {
+            b'\r' if bytes.get(index + 1) == Some(&b'\n') => 2,
+            b'\r' | b'\n' => 1,
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+
+        lines.push(RawLine {
+            content: line_start..index,
+            terminator: index..index + terminator_len,
+        });
+        index += terminator_len;
+        line_start = index;
+    }
+
+    lines.push(RawLine {
+        content: line_start..bytes.len(),
+        terminator: bytes.len()..bytes.len(),
+    });
+    lines
+}
+
+pub(super) fn encode_with_raw_bytes(
+    raw_bytes: &[u8],
+    encoding: &'static Encoding,
+    rope: &Rope,
+    line_ending: LineEnding,
+) -> Vec<u8> {
+    let bom_len = Encoding::for_bom(raw_bytes)
+        .map(|(_, length)| length)
+        .unwrap_or(0);
+    let raw_lines = split_raw_lines(raw_bytes, bom_len);
+    let decoded_raw_lines: Vec<String> = raw_lines
+        .iter()
+        .map(|line| {
+            encoding
+                .decode_without_bom_handling(&raw_bytes[line.content.clone()])
+                .0
+                .into_owned()
+        })
+        .collect();
+    let old_lines: Vec<&str> = decoded_raw_lines.iter().map(String::as_str).collect();
+
+    let utf8_content: String = rope.chunks().collect();
+    let normalized = text_utils::normalize_to_lf(&utf8_content);
+    let new_lines: Vec<&str> = normalized.split('\n').collect();
+
+    let line_ending_bytes: &[u8] = match line_ending {
+        LineEnding::CrLf => b"\r\n",
+        LineEnding::Lf => b"\n",
+        LineEnding::Cr => b"\r",
+    };
+    let last_new_index = new_lines.len().saturating_sub(1);
+
+    let mut output = Vec::with_capacity(raw_bytes.len());
+    output.extend_from_slice(&raw_bytes[..bom_len]);
+
+    for op in capture_diff_slices(Algorithm::Myers, &old_lines, &new_lines) {
+        match op {
+            DiffOp::Equal {
+                old_index,
+                new_index,
+                len,
+            } => {
+                for offset in 0..len {
+                    let raw_line = &raw_lines[old_index + offset];
+                    output.extend_from_slice(&raw_bytes[raw_line.content.clone()]);
+
+                    if new_index + offset < last_new_index {
+                        if raw_line.terminator.is_empty() {
+                            output.extend_from_slice(line_ending_bytes);
+                        } else {
+                            output.extend_from_slice(&raw_bytes[raw_line.terminator.clone()]);
+                        }
+                    }
+                }
+            }
+            DiffOp::Delete { .. } => {}
+            DiffOp::Insert {
+                new_index, new_len, ..
+            }
+            | DiffOp::Replace {
+                new_index, new_len, ..
+            } => {
+                for (offset, line) in new_lines[new_index..new_index + new_len].iter().enumerate() {
+                    let (encoded, _, _) = encoding.encode(line);
+                    output.extend_from_slice(&encoded);
+
+                    if new_index + offset < last_new_index {
+                        output.extend_from_slice(line_ending_bytes);
+                    }
+                }
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_to_rope(bytes: &[u8], encoding: &'static Encoding) -> Rope {
+        let (decoded, _, _) = encoding.decode(bytes);
+        Rope::from_str(&text_utils::normalize_to_lf(&decoded))
+    }
+
+    #[test]
+    fn encode_with_raw_bytes_should_round_trip_unchanged_content() {
+        let raw = b"\xEF\xBB\xBFok \xFF\xFE\r\nnext\rlast\n".to_vec();
+        let rope = decode_to_rope(&raw, encoding_rs::UTF_8);
+
+        let output = encode_with_raw_bytes(&raw, encoding_rs::UTF_8, &rope, LineEnding::CrLf);
+
+        assert_eq!(output, raw);
+    }
+
+    #[test]
+    fn encode_with_raw_bytes_should_only_reencode_edited_lines() {
+        let raw = b"bad \xC3(\r\nold\r\ntail".to_vec();
+        let mut rope = decode_to_rope(&raw, encoding_rs::UTF_8);
+        let line_start = rope.line_to_char(1);
+        rope.remove(line_start..line_start + 3);
+        rope.insert(line_start, "new");
+        rope.insert(rope.len_chars(), "\nadded");
+
+        let output = encode_with_raw_bytes(&raw, encoding_rs::UTF_8, &rope, LineEnding::CrLf);
+
+        assert_eq!(output, b"bad \xC3(\r\nnew\r\ntail\r\nadded".to_vec());
+    }
+
+    #[test]
+    fn should_preserve_raw_bytes_should_require_malformed_ascii_compatible_encoding() {
+        assert!(should_preserve_raw_bytes(encoding_rs::GBK, true));
+        assert!(!should_preserve_raw_bytes(encoding_rs::GBK, false));
+        assert!(!should_preserve_raw_bytes(encoding_rs::UTF_16LE, true));
+    }
+}
@@ -0,0 +1,159 @@
+// Where the reader stopped in large files, kept per path and separate from the
+// cursor. Only documents at least READING_POSITION_MIN_BYTES long and without
+// unsaved edits are tracked; positions live in `reading_positions.json` next
+// to the config file.
+
+use super::json_store::JsonStore;
+use super::*;
+use std::collections::BTreeMap;
+
+const READING_POSITION_MIN_BYTES: usize = 1024 * 1024;
+const MAX_READING_POSITIONS: usize = 200;
+
+static READING_POSITIONS: JsonStore<BTreeMap<String, ReadingPosition>> = JsonStore::new(
+    "reading_positions.json",
+    "Reading positions are unavailable",
+);
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingPosition {
+    // 1-based top visible line.
+    pub line: usize,
+    pub line_count: usize,
+    pub file_size: u64,
+    pub updated_at_ms: u64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingPositionPayload {
+    pub path: String,
+    #[serde(flatten)]
+    pub position: ReadingPosition,
+    // The file is now smaller than when the position was saved, e.g. a
+    // rotated log, so the line may point at different content.
+    pub file_shrunk: bool,
+}
+
+fn reading_position_key(path: &str) -> String {
+    fs::canonicalize(path)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+// Keeps the most recently updated entries.
+fn trim_reading_positions(positions: &mut BTreeMap<String, ReadingPosition>) {
+    while positions.len() > MAX_READING_POSITIONS {
+        let Some(oldest) = positions
+            .iter()
+            .min_by_key(|(_, position)| position.updated_at_ms)
+            .map(|(key, _)| key.clone())
+        else {
+            break;
+        };
+        positions.remove(&oldest);
+    }
+}
+
+fn reading_position_payload(key: String, position: ReadingPosition) -> ReadingPositionPayload {
+    let file_shrunk = fs::metadata(&key)
+        .map(|metadata| metadata.len() < position.file_size)
+        .unwrap_or(false);
+
+    ReadingPositionPayload {
+        path: key,
+        position,
+        file_shrunk,
+    }
+}
+
+// Returns whether the document was eligible and the position stored.
+pub(super) fn record_reading_position_impl(
+    state: &AppState,
+    id: &str,
+    line: usize,
+) -> Result<bool, String> {
+    let (path, line_count) = {
+        let doc = state
+            .documents
+            .get(id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        let Some(path) = doc.path.as_ref() else {
+            return Ok(false);
+        };
+        if doc.rope.len_bytes() < READING_POSITION_MIN_BYTES || doc.has_unsaved_text_changes() {
+            return Ok(false);
+        }
+        (path.to_string_lossy().to_string(), doc.rope.len_lines())
+    };
+
+    let position = ReadingPosition {
+        line: line.clamp(1, line_count.max(1)),
+        line_count,
+        file_size: fs::metadata(&path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0),
+        updated_at_ms: now_unix_millis(),
+    };
+
+    READING_POSITIONS.update(|positions| {
+        positions.insert(reading_position_key(&path), position);
+        trim_reading_positions(positions);
+        Ok(true)
+    })
+}
+
+pub(super) fn get_reading_position_impl(
+    path: String,
+) -> Result<Option<ReadingPositionPayload>, String> {
+    let key = reading_position_key(&path);
+    let position = READING_POSITIONS.lock()?.get(&key).cloned();
+
+    Ok(position.map(|position| reading_position_payload(key, position)))
+}
+
+pub(super) fn list_reading_positions_impl() -> Result<Vec<ReadingPositionPayload>, String> {
+    let positions = READING_POSITIONS.lock()?.clone();
+
+    let mut payloads: Vec<ReadingPositionPayload> = positions
+        .into_iter()
+        .map(|(key, position)| reading_position_payload(key, position))
+        .collect();
+    payloads.sort_by(|left, right| {
+        right
+            .position
+            .updated_at_ms
+            .cmp(&left.position.updated_at_ms)
+    });
+    Ok(payloads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_reading_positions_should_drop_oldest_entries() {
+        let mut positions: BTreeMap<String, ReadingPosition> = (0..MAX_READING_POSITIONS + 2)
+            .map(|index| {
+                (
+                    format!("file-{index}"),
+                    ReadingPosition {
+                        line: 1,
+                        line_count: 1,
+                        file_size: 0,
+                        updated_at_ms: index as u64,
+                    },
+                )
+            })
+            .collect();
+
+        trim_reading_positions(&mut positions);
+
+        assert_eq!(positions.len(), MAX_READING_POSITIONS);
+        assert!(!positions.contains_key("file-0"));
+        assert!(!positions.contains_key("file-1"));
+        assert!(positions.contains_key("file-2"));
+    }
+}
@@ -0,0 +1,194 @@
+// Documents closed during this session, newest first, so a closed tab can be
+// brought back. Files are reopened from disk by path; untitled documents keep
+// their text here because it exists nowhere else.
+
+use super::*;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+const MAX_RECENTLY_CLOSED: usize = 20;
+// The text of larger untitled buffers is not kept, as it would pin too much
+// memory; they are still listed, with `content_too_large` set.
+const MAX_RECENTLY_CLOSED_CONTENT_BYTES: usize = 8 * 1024 * 1024;
+
+static RECENTLY_CLOSED: OnceLock<Mutex<VecDeque<ClosedDocument>>> = OnceLock::new();
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosedDocumentSummary {
+    pub path: Option<String>,
+    pub name: String,
+    pub cursor_line: Option<usize>,
+    pub cursor_column: Option<usize>,
+    pub closed_at_ms: u64,
+    pub has_unsaved_content: bool,
+    pub content_too_large: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReopenedDocumentPayload {
+    pub file: FileInfo,
+    pub cursor_line: Option<usize>,
+    pub cursor_column: Option<usize>,
+}
+
+struct ClosedDocument {
+    summary: ClosedDocumentSummary,
+    content: Option<String>,
+    line_ending: LineEnding,
+}
+
+fn recently_closed() -> &'static Mutex<VecDeque<ClosedDocument>> {
+    RECENTLY_CLOSED.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+fn build_closed_document(
+    doc: &Document,
+    cursor_line: Option<usize>,
+    cursor_column: Option<usize>,
+) -> Option<ClosedDocument> {
+    if doc.derived_view.is_some() {
+        return None;
+    }
+
+    let mut content_too_large = false;
+    let (path, name, content) = match doc.path.as_ref() {
+        Some(path) => (
+            Some(path.to_string_lossy().to_string()),
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            None,
+        ),
+        None => {
+            let len_bytes = doc.rope.len_bytes();
+            if len_bytes == 0 {
+                return None;
+            }
+            content_too_large = len_bytes > MAX_RECENTLY_CLOSED_CONTENT_BYTES;
+            let content = (!content_too_large).then(|| doc.rope.to_string());
+            (None, "Untitled".to_string(), content)
+        }
+    };
+
+    Some(ClosedDocument {
+        summary: ClosedDocumentSummary {
+            path,
+            name,
+            cursor_line,
+            cursor_column,
+            closed_at_ms: now_unix_millis(),
+            has_unsaved_content: content.is_some(),
+            content_too_large,
+        },
+        content,
+        line_ending: doc.line_ending,
+    })
+}
+
+pub(super) fn remember_closed_document(
+    doc: &Document,
+    cursor_line: Option<usize>,
+    cursor_column: Option<usize>,
+) {
+    let Some(closed) = build_closed_document(doc, cursor_line, cursor_column) else {
+        return;
+    };
+
+    let Ok(mut entries) = recently_closed().lock() else {
+        return;
+    };
+    if let Some(path) = closed.summary.path.as_ref() {
+        entries.retain(|entry| entry.summary.path.as_ref() != Some(path));
+    }
+    entries.push_front(closed);
+    entries.truncate(MAX_RECENTLY_CLOSED);
+}
+
+pub(super) fn list_recently_closed_impl() -> Vec<ClosedDocumentSummary> {
+    recently_closed()
+        .lock()
+        .map(|entries| entries.iter().map(|entry| entry.summary.clone()).collect())
+        .unwrap_or_default()
+}
+
+// Takes the newest entry off the stack even if reopening fails, so a file
+// that has since been deleted does not block the ones behind it.
+pub(super) async fn reopen_last_closed_impl(
+    state: State<'_, AppState>,
+) -> Result<Option<ReopenedDocumentPayload>, String> {
+    let closed = {
+        let mut entries = recently_closed().lock().map_err(|e| e.to_string())?;
+        entries.pop_front()
+    };
+    let Some(closed) = closed else {
+        return Ok(None);
+    };
+
+    let file = match (closed.summary.path, closed.content) {
+        (Some(path), _) => file_io::open_file_impl(state, path, None, None, None).await?,
+        (None, Some(content)) => restore_untitled_document(&state, content, closed.line_ending)?,
+        (None, None) if closed.summary.content_too_large => {
+            return Err("Closed untitled document was too large to keep".to_string())
+        }
+        (None, None) => return Err("Closed document has nothing to restore".to_string()),
+    };
+
+    Ok(Some(ReopenedDocumentPayload {
+        file,
+        cursor_line: closed.summary.cursor_line,
+        cursor_column: closed.summary.cursor_column,
+    }))
+}
+
+// The text comes back as an edit, so the new tab is dirty like the one that
+// was closed.
+fn restore_untitled_document(
+    state: &State<'_, AppState>,
+    content: String,
+    line_ending: LineEnding,
+) -> Result<FileInfo, String> {
+    let mut file = file_io::new_file_impl(state.clone(), Some(line_ending.label().to_string()))?;
+    let mut doc = state
+        .documents
+        .get_mut(&file.id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let mut engine = doc.engine();
+    engine.replace(0, String::new(), content)?;
+    let change = engine.finish();
+    file.line_count = change.line_count;
+    file.size_bytes = doc.rope.len_bytes() as u64;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_closed_document_should_keep_text_only_for_untitled_documents() {
+        let file = build_closed_document(
+            &Document::for_test("abc", Some("/tmp/a.txt")),
+            Some(3),
+            None,
+        )
+        .expect("file entry");
+        assert_eq!(file.summary.name, "a.txt");
+        assert_eq!(file.summary.cursor_line, Some(3));
+        assert!(file.content.is_none());
+
+        let scratch =
+            build_closed_document(&Document::for_test("draft", None), None, None).expect("scratch");
+        assert_eq!(scratch.content.as_deref(), Some("draft"));
+        assert!(scratch.summary.has_unsaved_content);
+
+        assert!(build_closed_document(&Document::for_test("", None), None, None).is_none());
+
+        let large_text = "x".repeat(MAX_RECENTLY_CLOSED_CONTENT_BYTES + 1);
+        let large = build_closed_document(&Document::for_test(&large_text, None), None, None)
+            .expect("large scratch");
+        assert!(large.content.is_none());
+        assert!(large.summary.content_too_large);
+    }
+}
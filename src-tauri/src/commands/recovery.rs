@@ -0,0 +1,345 @@
+// Crash recovery: the text of every document with unsaved edits, untitled ones
+// included, is copied to a `recovery` folder next to the config file. Each
+// document gets `<id>.txt` with its LF text and `<id>.json` describing where
+// it came from. A backup is rewritten only when the document changed since the
+// last one and removed once the document is saved or closed. Backups left
+// behind by a crash are offered again at the next startup.
+
+use super::*;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryFileSummary {
+    pub id: String,
+    // Empty for untitled documents.
+    #[serde(default)]
+    pub path: String,
+    pub name: String,
+    #[serde(default)]
+    pub line_ending: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub syntax_override: Option<String>,
+    #[serde(default)]
+    pub saved_at_ms: u64,
+    #[serde(default)]
+    pub line_count: usize,
+    #[serde(default)]
+    pub size_bytes: u64,
+}
+
+// Document version last written for each document backed up by this process.
+fn backed_up_versions() -> &'static DashMap<String, u64> {
+    static MAP: OnceLock<DashMap<String, u64>> = OnceLock::new();
+    MAP.get_or_init(DashMap::new)
+}
+
+fn recovery_dir_path() -> Result<PathBuf, String> {
+    config::config_dir_path_impl().map(|dir| dir.join("recovery"))
+}
+
+// Ids become file names, so anything but a plain uuid-like token is refused.
+fn is_valid_recovery_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+}
+
+fn recovery_text_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.txt", id))
+}
+
+fn recovery_meta_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+fn remove_recovery_files(dir: &Path, id: &str) -> bool {
+    let removed_text = fs::remove_file(recovery_text_path(dir, id)).is_ok();
+    let removed_meta = fs::remove_file(recovery_meta_path(dir, id)).is_ok();
+    removed_text || removed_meta
+}
+
+// Both files are replaced through a temp file and a rename, so a crash mid-write
+// leaves the previous backup rather than a truncated one. The text goes first
+// so metadata never points at a missing or older text file.
+fn write_recovery_files(
+    dir: &Path,
+    summary: &RecoveryFileSummary,
+    text: &str,
+) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    file_io::write_file_atomically(&recovery_text_path(dir, &summary.id), text.as_bytes(), None)?;
+    let raw = serde_json::to_string_pretty(summary).map_err(|e| e.to_string())?;
+    file_io::write_file_atomically(&recovery_meta_path(dir, &summary.id), raw.as_bytes(), None)
+        .map(|_| ())
+}
+
+fn needs_recovery_backup(doc: &Document) -> bool {
+    if doc.derived_view.is_some() {
+        return false;
+    }
+    match doc.path {
+        Some(_) => doc.has_unsaved_text_changes(),
+        None => doc.rope.len_bytes() > 0,
+    }
+}
+
+fn recovery_summary(id: &str, doc: &Document, saved_at_ms: u64) -> RecoveryFileSummary {
+    let path = doc
+        .path
+        .as_ref()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = doc
+        .path
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Untitled".to_string());
+
+    RecoveryFileSummary {
+        id: id.to_string(),
+        path,
+        name,
+        line_ending: doc.line_ending.label().to_string(),
+        syntax_override: doc.syntax_override.clone(),
+        saved_at_ms,
+        line_count: doc.rope.len_lines(),
+        size_bytes: doc.rope.len_bytes() as u64,
+    }
+}
+
+// Saving and closing call this so a backup does not outlive its reason until
+// the next backup tick. Documents never backed up cost no file system access.
+pub(super) fn discard_document_recovery_backup(id: &str) {
+    if backed_up_versions().remove(id).is_none() {
+        return;
+    }
+    if let Ok(dir) = recovery_dir_path() {
+        remove_recovery_files(&dir, id);
+    }
+}
+
+// On a clean exit every remaining edit was saved or deliberately dropped in
+// the close prompt, so no backup is left for the next startup to offer.
+pub(super) fn discard_recovery_backups_on_exit_impl() {
+    let ids: Vec<String> = backed_up_versions()
+        .iter()
+        .map(|entry| entry.key().clone())
+        .collect();
+    for id in ids {
+        discard_document_recovery_backup(&id);
+    }
+}
+
+// Run periodically: backs up documents that changed since their last backup
+// and drops backups of documents that were saved or closed since. Files are
+// only touched after the walk over the documents, never under their locks.
+pub(super) fn write_recovery_backups_impl(app: &AppHandle) {
+    if config::is_safe_mode_impl() {
+        return;
+    }
+    let dir = match recovery_dir_path() {
+        Ok(dir) => dir,
+        Err(error) => {
+            eprintln!("failed to locate recovery directory: {error}");
+            return;
+        }
+    };
+
+    let state = app.state::<AppState>();
+    let saved_at_ms = now_unix_millis();
+    let mut pending = Vec::new();
+    let mut stale_ids = Vec::new();
+    for entry in state.documents.iter() {
+        let doc = entry.value();
+        let id = entry.key();
+        if !needs_recovery_backup(doc) {
+            if backed_up_versions().contains_key(id) {
+                stale_ids.push(id.clone());
+            }
+            continue;
+        }
+        if backed_up_versions().get(id).map(|version| *version) == Some(doc.document_version) {
+            continue;
+        }
+        // Ropes share chunks, so the copy is cheap; the text is written out
+        // after the document lock is released.
+        pending.push((
+            recovery_summary(id, doc, saved_at_ms),
+            doc.rope.clone(),
+            doc.document_version,
+        ));
+    }
+
+    for id in stale_ids {
+        backed_up_versions().remove(&id);
+        remove_recovery_files(&dir, &id);
+    }
+
+    for (summary, rope, document_version) in pending {
+        match write_recovery_files(&dir, &summary, &rope.to_string()) {
+            Ok(()) => {
+                backed_up_versions().insert(summary.id, document_version);
+            }
+            Err(error) => eprintln!("failed to write recovery backup: {error}"),
+        }
+    }
+
+    let closed_ids: Vec<String> = backed_up_versions()
+        .iter()
+        .map(|entry| entry.key().clone())
+        .filter(|id| !state.documents.contains_key(id))
+        .collect();
+    for id in closed_ids {
+        backed_up_versions().remove(&id);
+        remove_recovery_files(&dir, &id);
+    }
+}
+
+fn read_recovery_summary(dir: &Path, id: &str) -> Option<RecoveryFileSummary> {
+    let raw = fs::read_to_string(recovery_meta_path(dir, id)).ok()?;
+    let summary: RecoveryFileSummary = serde_json::from_str(&raw).ok()?;
+    (summary.id == id && recovery_text_path(dir, id).is_file()).then_some(summary)
+}
+
+// Backups whose document is open in this session are current, not leftovers,
+// so only the others are listed. Newest first.
+pub(super) fn list_recovery_files_impl(
+    state: State<'_, AppState>,
+) -> Result<Vec<RecoveryFileSummary>, String> {
+    if config::is_safe_mode_impl() {
+        return Ok(Vec::new());
+    }
+    let dir = recovery_dir_path()?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut summaries: Vec<RecoveryFileSummary> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let id = file_name.strip_suffix(".json")?.to_string();
+            if !is_valid_recovery_id(&id) || state.documents.contains_key(&id) {
+                return None;
+            }
+            read_recovery_summary(&dir, &id)
+        })
+        .collect();
+    summaries.sort_by(|left, right| right.saved_at_ms.cmp(&left.saved_at_ms));
+    Ok(summaries)
+}
+
+// Reopens the original file, or a new untitled document when there was none
+// or it is gone, and applies the recovered text as an undoable edit.
+pub(super) async fn restore_recovery_file_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<FileInfo, String> {
+    if !is_valid_recovery_id(&id) {
+        return Err("Invalid recovery id".to_string());
+    }
+    let dir = recovery_dir_path()?;
+    let summary =
+        read_recovery_summary(&dir, &id).ok_or_else(|| "Recovery file not found".to_string())?;
+    let text = fs::read_to_string(recovery_text_path(&dir, &id)).map_err(|e| e.to_string())?;
+
+    let mut file_info = if !summary.path.is_empty() && Path::new(&summary.path).is_file() {
+        file_io::open_file_impl(state.clone(), summary.path.clone(), None, None, None).await?
+    } else {
+        let mut file_info =
+            file_io::new_file_impl(state.clone(), Some(summary.line_ending.clone()))?;
+        file_info.name = format!("{} (recovered)", summary.name);
+        file_info
+    };
+
+    let changed_version = {
+        let mut doc = state
+            .documents
+            .get_mut(&file_info.id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        let current = doc.rope.to_string();
+        let mut engine = doc.engine();
+        if current != text {
+            engine.replace(0, current, text)?;
+        }
+        let change = engine.finish();
+        if let Some(syntax_override) = summary.syntax_override.as_deref() {
+            if let Ok(normalized) = syntax::normalize_syntax_override(Some(syntax_override)) {
+                doc.syntax_override = normalized;
+            }
+        }
+        file_info.line_count = doc.rope.len_lines();
+        file_info.size_bytes = doc.rope.len_bytes() as u64;
+        file_info.large_file_mode = doc.rope.len_bytes() > LARGE_FILE_THRESHOLD_BYTES;
+        file_info.syntax_override = doc.syntax_override.clone();
+        change.changed.then_some(change.document_version)
+    };
+    if let Some(document_version) = changed_version {
+        clear_outdated_document_search_caches(&file_info.id, document_version);
+    }
+
+    remove_recovery_files(&dir, &id);
+    Ok(file_info)
+}
+
+pub(super) fn discard_recovery_file_impl(id: String) -> Result<bool, String> {
+    if !is_valid_recovery_id(&id) {
+        return Err("Invalid recovery id".to_string());
+    }
+    Ok(remove_recovery_files(&recovery_dir_path()?, &id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_valid_recovery_id_should_reject_path_like_ids() {
+        assert!(is_valid_recovery_id(&Uuid::new_v4().to_string()));
+        assert!(!is_valid_recovery_id(""));
+        assert!(!is_valid_recovery_id("../config"));
+        assert!(!is_valid_recovery_id("a/b"));
+    }
+
+    #[test]
+    fn recovery_files_should_round_trip_through_disk() {
+        let dir = std::env::temp_dir().join(format!("rutar-recovery-{}", Uuid::new_v4()));
+        let summary = RecoveryFileSummary {
+            id: Uuid::new_v4().to_string(),
+            path: String::new(),
+            name: "Untitled".to_string(),
+            line_ending: "LF".to_string(),
+            syntax_override: None,
+            saved_at_ms: 1,
+            line_count: 2,
+            size_bytes: 4,
+        };
+
+        write_recovery_files(&dir, &summary, "a\nbc").expect("write recovery files");
+        assert_eq!(
+            read_recovery_summary(&dir, &summary.id),
+            Some(summary.clone())
+        );
+        assert_eq!(
+            fs::read_to_string(recovery_text_path(&dir, &summary.id)).expect("read text"),
+            "a\nbc"
+        );
+
+        write_recovery_files(&dir, &summary, "xyz").expect("rewrite recovery files");
+        assert_eq!(
+            fs::read_to_string(recovery_text_path(&dir, &summary.id)).expect("read text"),
+            "xyz"
+        );
+        assert_eq!(fs::read_dir(&dir).expect("read dir").count(), 2);
+
+        assert!(remove_recovery_files(&dir, &summary.id));
+        assert!(read_recovery_summary(&dir, &summary.id).is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
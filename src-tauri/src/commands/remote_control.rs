@@ -0,0 +1,576 @@
+// Optional localhost HTTP endpoint that lets scripts and browser extensions
+// push content into a running instance:
+//
+//   POST /open   {"path": "..."}
+//   POST /goto   {"path": "...", "line": 12, "column": 3}
+//   POST /paste  {"text": "...", "name": "..."}
+//
+// It binds 127.0.0.1 only and every request must carry the configured token,
+// either as `Authorization: Bearer <token>` or `X-Rutar-Token: <token>`. The
+// token lives in the `remote-control.token` secret where the platform has a
+// secret store and in the config elsewhere.
+
+use super::*;
+use crate::state::GotoPosition;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+const MAX_REMOTE_CONTROL_BODY_BYTES: usize = 16 * 1024 * 1024;
+const MAX_REMOTE_CONTROL_HEADER_LINES: usize = 64;
+const MAX_REMOTE_CONTROL_CONNECTIONS: usize = 8;
+// Covers the whole request, so a client trickling bytes cannot hold a
+// connection slot by staying just under a per-read timeout.
+const REMOTE_CONTROL_REQUEST_DEADLINE: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq)]
+struct RemoteControlRequest {
+    method: String,
+    target: String,
+    body: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+enum RemoteControlAction {
+    Open {
+        path: String,
+    },
+    Goto {
+        path: String,
+        line: usize,
+        column: usize,
+    },
+    Paste {
+        text: String,
+        name: Option<String>,
+    },
+}
+
+#[derive(serde::Deserialize)]
+struct RemoteOpenBody {
+    path: String,
+}
+
+#[derive(serde::Deserialize)]
+struct RemoteGotoBody {
+    path: String,
+    line: usize,
+    #[serde(default)]
+    column: Option<usize>,
+}
+
+#[derive(serde::Deserialize)]
+struct RemotePasteBody {
+    text: String,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+// Errors carry the HTTP status they are reported with.
+type RemoteControlError = (u16, String);
+
+// Longer request or header lines are rejected rather than buffered.
+const MAX_REMOTE_CONTROL_LINE_BYTES: u64 = 8 * 1024;
+
+fn read_remote_control_line(
+    reader: &mut impl BufRead,
+    line: &mut String,
+) -> Result<(), RemoteControlError> {
+    let read = reader
+        .by_ref()
+        .take(MAX_REMOTE_CONTROL_LINE_BYTES)
+        .read_line(line)
+        .map_err(|_| (400, "Malformed request".to_string()))?;
+    if read as u64 == MAX_REMOTE_CONTROL_LINE_BYTES && !line.ends_with('\n') {
+        return Err((431, "Request line is too long".to_string()));
+    }
+    Ok(())
+}
+
+// The token is checked once the headers are in, so an unauthenticated client
+// never gets a body read or buffered.
+fn read_remote_control_request(
+    reader: &mut impl BufRead,
+    expected_token: &str,
+) -> Result<RemoteControlRequest, RemoteControlError> {
+    let bad_request = |message: &str| (400, message.to_string());
+
+    let mut request_line = String::new();
+    read_remote_control_line(reader, &mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request("Malformed request"));
+    };
+
+    let mut token = None;
+    let mut content_length = 0usize;
+    for _ in 0..MAX_REMOTE_CONTROL_HEADER_LINES {
+        let mut header = String::new();
+        read_remote_control_line(reader, &mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            if !is_remote_control_token_valid(expected_token, token.as_deref()) {
+                return Err((401, "Invalid token".to_string()));
+            }
+            if content_length > MAX_REMOTE_CONTROL_BODY_BYTES {
+                return Err((413, "Request body is too large".to_string()));
+            }
+            let mut body = Vec::new();
+            reader
+                .by_ref()
+                .take(content_length as u64)
+                .read_to_end(&mut body)
+                .map_err(|_| bad_request("Incomplete request body"))?;
+            if body.len() != content_length {
+                return Err(bad_request("Incomplete request body"));
+            }
+            return Ok(RemoteControlRequest {
+                method: method.to_string(),
+                target: target.to_string(),
+                body,
+            });
+        }
+
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(bad_request("Malformed header"));
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => {
+                content_length = value
+                    .parse()
+                    .map_err(|_| bad_request("Invalid Content-Length"))?;
+            }
+            "authorization" => {
+                if let Some(bearer) = value.strip_prefix("Bearer ") {
+                    token = Some(bearer.trim().to_string());
+                }
+            }
+            "x-rutar-token" => token = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    Err(bad_request("Too many headers"))
+}
+
+// Compares every byte so the response time does not reveal the matching prefix.
+fn is_remote_control_token_valid(expected: &str, provided: Option<&str>) -> bool {
+    let Some(provided) = provided else {
+        return false;
+    };
+    if expected.len() != provided.len() {
+        return false;
+    }
+
+    expected
+        .bytes()
+        .zip(provided.bytes())
+        .fold(0u8, |diff, (left, right)| diff | (left ^ right))
+        == 0
+}
+
+fn parse_remote_control_action(
+    request: &RemoteControlRequest,
+) -> Result<RemoteControlAction, RemoteControlError> {
+    fn parse_body<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, RemoteControlError> {
+        serde_json::from_slice(body).map_err(|error| (400, format!("Invalid JSON body: {}", error)))
+    }
+
+    let route = request.target.split('?').next().unwrap_or_default();
+    if !matches!(route, "/open" | "/goto" | "/paste") {
+        return Err((404, "Unknown endpoint".to_string()));
+    }
+    if request.method != "POST" {
+        return Err((405, "Only POST is supported".to_string()));
+    }
+
+    match route {
+        "/open" => {
+            let body: RemoteOpenBody = parse_body(&request.body)?;
+            Ok(RemoteControlAction::Open { path: body.path })
+        }
+        "/goto" => {
+            let body: RemoteGotoBody = parse_body(&request.body)?;
+            if body.line == 0 {
+                return Err((400, "Line must be at least 1".to_string()));
+            }
+            Ok(RemoteControlAction::Goto {
+                path: body.path,
+                line: body.line,
+                column: body.column.unwrap_or(1).max(1),
+            })
+        }
+        _ => {
+            let body: RemotePasteBody = parse_body(&request.body)?;
+            Ok(RemoteControlAction::Paste {
+                text: body.text,
+                name: body
+                    .name
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty()),
+            })
+        }
+    }
+}
+
+fn resolve_remote_file_path(path: &str) -> Result<String, RemoteControlError> {
+    let path = std::path::Path::new(path.trim());
+    if !path.is_file() {
+        return Err((404, "File not found".to_string()));
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+fn create_pasted_document(
+    app: &AppHandle,
+    text: &str,
+    name: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let state = app.state::<AppState>();
+    let mut file_info = file_io::new_file_impl(app.state::<AppState>(), None)?;
+    let text = text_utils::normalize_to_lf(text);
+
+    if !text.is_empty() {
+        let mut doc = state
+            .documents
+            .get_mut(&file_info.id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        let mut engine = doc.engine();
+        engine.replace(0, String::new(), text.clone())?;
+        let change = engine.finish();
+        file_info.line_count = change.line_count;
+        file_info.size_bytes = text.len() as u64;
+    }
+    if let Some(name) = name {
+        file_info.name = name;
+    }
+
+    serde_json::to_value(file_info).map_err(|error| error.to_string())
+}
+
+fn run_remote_control_action(
+    app: &AppHandle,
+    action: RemoteControlAction,
+    open_paths: fn(&AppHandle, Vec<StartupPath>),
+) -> Result<serde_json::Value, RemoteControlError> {
+    let internal = |error: String| (500, error);
+
+    match action {
+        RemoteControlAction::Open { path } => {
+            let path = resolve_remote_file_path(&path)?;
+            open_paths(app, vec![StartupPath::new(path)]);
+            Ok(serde_json::json!({}))
+        }
+        RemoteControlAction::Goto { path, line, column } => {
+            // Goes the way of `rutar file:line`, so the jump waits for the tab
+            // and is queued like any open until the editor is up.
+            let path = resolve_remote_file_path(&path)?;
+            open_paths(
+                app,
+                vec![StartupPath {
+                    path,
+                    goto_position: Some(GotoPosition { line, column }),
+                }],
+            );
+            Ok(serde_json::json!({}))
+        }
+        RemoteControlAction::Paste { text, name } => {
+            if !app.state::<AppState>().is_frontend_ready() {
+                return Err((503, "Editor is not ready yet".to_string()));
+            }
+            let file_info = create_pasted_document(app, &text, name).map_err(internal)?;
+            emit_to_main_window(app, "rutar://remote-open-document", file_info.clone())
+                .map_err(internal)?;
+            Ok(serde_json::json!({ "id": file_info["id"] }))
+        }
+    }
+}
+
+fn emit_to_main_window<S: serde::Serialize + Clone>(
+    app: &AppHandle,
+    event: &str,
+    payload: S,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    window
+        .emit(event, payload)
+        .map_err(|error| error.to_string())
+}
+
+fn write_remote_control_response(
+    stream: &mut TcpStream,
+    result: Result<serde_json::Value, RemoteControlError>,
+) {
+    let (status, body) = match result {
+        Ok(mut value) => {
+            value["ok"] = serde_json::Value::Bool(true);
+            (200, value)
+        }
+        Err((status, error)) => (status, serde_json::json!({ "ok": false, "error": error })),
+    };
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+// Shrinks the socket read timeout to whatever is left of the request deadline
+// before every read.
+struct DeadlineReader {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(std::io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+fn handle_remote_control_connection(
+    app: &AppHandle,
+    mut stream: TcpStream,
+    token: &str,
+    open_paths: fn(&AppHandle, Vec<StartupPath>),
+) {
+    let deadline = Instant::now() + REMOTE_CONTROL_REQUEST_DEADLINE;
+    let result = stream
+        .try_clone()
+        .map_err(|error| (500, error.to_string()))
+        .and_then(|read_half| {
+            let reader = DeadlineReader {
+                stream: read_half,
+                deadline,
+            };
+            read_remote_control_request(&mut BufReader::new(reader), token)
+        })
+        .and_then(|request| parse_remote_control_action(&request))
+        .and_then(|action| run_remote_control_action(app, action, open_paths));
+
+    write_remote_control_response(&mut stream, result);
+}
+
+// Releases a connection slot when its handler thread finishes.
+struct RemoteControlConnectionSlot(Arc<AtomicUsize>);
+
+impl Drop for RemoteControlConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// Each connection gets its own thread, up to MAX_REMOTE_CONTROL_CONNECTIONS at
+// once; further connections are answered with 503 without reading them.
+pub(super) fn start_remote_control_server_impl(
+    app: &AppHandle,
+    open_paths: fn(&AppHandle, Vec<StartupPath>),
+) {
+    let (port, token) = match config::load_remote_control_settings_in_config_impl() {
+        Ok(Some(settings)) => settings,
+        Ok(None) => return,
+        Err(error) => {
+            eprintln!("failed to load remote control settings: {error}");
+            return;
+        }
+    };
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("failed to start remote control server on port {port}: {error}");
+            return;
+        }
+    };
+
+    let app_handle = app.clone();
+    let token: Arc<str> = token.into();
+    let active_connections = Arc::new(AtomicUsize::new(0));
+    let _ = std::thread::Builder::new()
+        .name("rutar-remote-control".to_string())
+        .spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                if active_connections.fetch_add(1, Ordering::SeqCst)
+                    >= MAX_REMOTE_CONTROL_CONNECTIONS
+                {
+                    active_connections.fetch_sub(1, Ordering::SeqCst);
+                    write_remote_control_response(
+                        &mut stream,
+                        Err((503, "Too many concurrent requests".to_string())),
+                    );
+                    continue;
+                }
+
+                let slot = RemoteControlConnectionSlot(active_connections.clone());
+                let app_handle = app_handle.clone();
+                let token = token.clone();
+                let spawned = std::thread::Builder::new()
+                    .name("rutar-remote-control-connection".to_string())
+                    .spawn(move || {
+                        let _slot = slot;
+                        handle_remote_control_connection(&app_handle, stream, &token, open_paths);
+                    });
+                if let Err(error) = spawned {
+                    eprintln!("failed to spawn remote control connection thread: {error}");
+                }
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_request(raw: &str) -> Result<RemoteControlRequest, RemoteControlError> {
+        read_remote_control_request(&mut BufReader::new(raw.as_bytes()), "abc")
+    }
+
+    #[test]
+    fn read_remote_control_request_should_parse_token_and_body() {
+        let request = read_request(
+            "POST /goto HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer abc\r\nContent-Length: 25\r\n\r\n{\"path\":\"a.txt\",\"line\":3}",
+        )
+        .expect("request");
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(
+            parse_remote_control_action(&request),
+            Ok(RemoteControlAction::Goto {
+                path: "a.txt".to_string(),
+                line: 3,
+                column: 1,
+            })
+        );
+
+        let header_token =
+            read_request("POST /paste HTTP/1.1\r\nX-Rutar-Token: abc\r\n\r\n").expect("request");
+        assert!(header_token.body.is_empty());
+    }
+
+    #[test]
+    fn read_remote_control_request_should_authenticate_before_the_body() {
+        // The body is never sent; a read would fail as incomplete instead.
+        let raw = "POST /paste HTTP/1.1\r\nX-Rutar-Token: xyz\r\nContent-Length: 100\r\n\r\n";
+        assert_eq!(read_request(raw).unwrap_err().0, 401);
+        assert_eq!(
+            read_request("POST /paste HTTP/1.1\r\n\r\n").unwrap_err().0,
+            401
+        );
+    }
+
+    #[test]
+    fn read_remote_control_request_should_reject_oversized_input() {
+        let raw = format!(
+            "POST /paste HTTP/1.1\r\nX-Rutar-Token: abc\r\nContent-Length: {}\r\n\r\n",
+            MAX_REMOTE_CONTROL_BODY_BYTES + 1
+        );
+        assert_eq!(read_request(&raw).unwrap_err().0, 413);
+
+        let long_header = format!(
+            "POST /paste HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+            "x".repeat(MAX_REMOTE_CONTROL_LINE_BYTES as usize)
+        );
+        assert_eq!(read_request(&long_header).unwrap_err().0, 431);
+    }
+
+    #[test]
+    fn parse_remote_control_action_should_check_route_method_and_body() {
+        let make_request = |method: &str, target: &str, body: &str| RemoteControlRequest {
+            method: method.to_string(),
+            target: target.to_string(),
+            body: body.as_bytes().to_vec(),
+        };
+
+        assert_eq!(
+            parse_remote_control_action(&make_request(
+                "POST",
+                "/paste",
+                "{\"text\":\"hi\",\"name\":\" \"}"
+            )),
+            Ok(RemoteControlAction::Paste {
+                text: "hi".to_string(),
+                name: None,
+            })
+        );
+        assert_eq!(
+            parse_remote_control_action(&make_request("GET", "/open", "{\"path\":\"a\"}"))
+                .unwrap_err()
+                .0,
+            405
+        );
+        assert_eq!(
+            parse_remote_control_action(&make_request("POST", "/close", ""))
+                .unwrap_err()
+                .0,
+            404
+        );
+        assert_eq!(
+            parse_remote_control_action(&make_request(
+                "POST",
+                "/goto",
+                "{\"path\":\"a\",\"line\":0}"
+            ))
+            .unwrap_err()
+            .0,
+            400
+        );
+    }
+
+    #[test]
+    fn is_remote_control_token_valid_should_require_exact_match() {
+        assert!(is_remote_control_token_valid("secret", Some("secret")));
+        assert!(!is_remote_control_token_valid("secret", Some("secreT")));
+        assert!(!is_remote_control_token_valid("secret", Some("secret2")));
+        assert!(!is_remote_control_token_valid("secret", None));
+    }
+
+    #[test]
+    fn deadline_reader_should_fail_once_the_request_deadline_passes() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).expect("bind");
+        let client = TcpStream::connect(listener.local_addr().expect("addr")).expect("connect");
+        let (server, _) = listener.accept().expect("accept");
+
+        // The client sends a byte but never finishes the request.
+        (&client).write_all(b"P").expect("write");
+        let mut reader = DeadlineReader {
+            stream: server,
+            deadline: Instant::now() + Duration::from_millis(200),
+        };
+        let mut buf = [0u8; 8];
+        assert_eq!(reader.read(&mut buf).expect("first byte"), 1);
+
+        let started = Instant::now();
+        assert!(reader.read(&mut buf).is_err());
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(
+            reader.read(&mut buf).unwrap_err().kind(),
+            std::io::ErrorKind::TimedOut
+        );
+    }
+}
@@ -0,0 +1,183 @@
+// Alerts for filter rules hit by text appended to a follow-mode document.
+// The taskbar is flashed from here; notification and sound are left to the
+// frontend through `rutar://filter-rule-alert`. Each rule alerts at most once
+// per interval and document, with the hits in between counted into the next.
+
+use super::*;
+use dashmap::DashMap;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager, UserAttentionType};
+
+const DEFAULT_RULE_ALERT_INTERVAL: Duration = Duration::from_secs(10);
+// Throttle state older than this no longer suppresses anything.
+const RULE_ALERT_THROTTLE_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+static RULE_ALERT_THROTTLES: OnceLock<DashMap<String, RuleAlertThrottle>> = OnceLock::new();
+
+struct RuleAlertThrottle {
+    last_fired_at: Instant,
+    suppressed_hits: usize,
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FilterRuleAlertEventPayload {
+    document_id: String,
+    rule_index: usize,
+    keyword: String,
+    line: usize,
+    line_text: String,
+    // Hits since the previous alert of the rule, this append included.
+    hit_count: usize,
+    notification: bool,
+    sound: bool,
+}
+
+fn rule_alert_throttles() -> &'static DashMap<String, RuleAlertThrottle> {
+    RULE_ALERT_THROTTLES.get_or_init(DashMap::new)
+}
+
+fn rule_alert_interval(options: &FilterRuleAlertOptions) -> Duration {
+    if options.min_interval_seconds == 0 {
+        DEFAULT_RULE_ALERT_INTERVAL
+    } else {
+        Duration::from_secs(options.min_interval_seconds)
+    }
+}
+
+// Returns the hit count to report when the alert fires, or `None` while the
+// rule is still inside its interval.
+fn take_rule_alert_slot(
+    throttle: Option<&mut RuleAlertThrottle>,
+    now: Instant,
+    interval: Duration,
+    hit_count: usize,
+) -> Option<usize> {
+    let Some(throttle) = throttle else {
+        return Some(hit_count);
+    };
+    if now.saturating_duration_since(throttle.last_fired_at) < interval {
+        throttle.suppressed_hits += hit_count;
+        return None;
+    }
+
+    let total = throttle.suppressed_hits + hit_count;
+    throttle.last_fired_at = now;
+    throttle.suppressed_hits = 0;
+    Some(total)
+}
+
+pub(super) fn dispatch_filter_rule_alerts(
+    app: &AppHandle,
+    document_id: &str,
+    hits: Vec<FilterRuleAlertHit>,
+) {
+    if hits.is_empty() {
+        return;
+    }
+
+    let now = Instant::now();
+    let throttles = rule_alert_throttles();
+    throttles.retain(|_, throttle| {
+        now.saturating_duration_since(throttle.last_fired_at) < RULE_ALERT_THROTTLE_RETENTION
+    });
+
+    for hit in hits {
+        let key = format!("{document_id}\u{0}{}\u{0}{}", hit.rule_index, hit.keyword);
+        let interval = rule_alert_interval(&hit.options);
+        let fired = match throttles.get_mut(&key) {
+            Some(mut throttle) => {
+                take_rule_alert_slot(Some(&mut *throttle), now, interval, hit.hit_count)
+            }
+            None => {
+                throttles.insert(
+                    key,
+                    RuleAlertThrottle {
+                        last_fired_at: now,
+                        suppressed_hits: 0,
+                    },
+                );
+                take_rule_alert_slot(None, now, interval, hit.hit_count)
+            }
+        };
+        let Some(hit_count) = fired else {
+            continue;
+        };
+
+        fire_filter_rule_alert(app, document_id, hit, hit_count);
+    }
+}
+
+fn fire_filter_rule_alert(
+    app: &AppHandle,
+    document_id: &str,
+    hit: FilterRuleAlertHit,
+    hit_count: usize,
+) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    if hit.options.flash_taskbar {
+        if let Err(error) = window.request_user_attention(Some(UserAttentionType::Informational)) {
+            eprintln!("failed to flash taskbar for filter rule alert: {error}");
+        }
+    }
+
+    if !hit.options.notification && !hit.options.sound {
+        return;
+    }
+    let payload = FilterRuleAlertEventPayload {
+        document_id: document_id.to_string(),
+        rule_index: hit.rule_index,
+        keyword: hit.keyword,
+        line: hit.line,
+        line_text: hit.line_text,
+        hit_count,
+        notification: hit.options.notification,
+        sound: hit.options.sound,
+    };
+    if let Err(error) = window.emit("rutar://filter-rule-alert", payload) {
+        eprintln!("failed to emit filter rule alert event: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_rule_alert_slot_should_count_hits_suppressed_within_interval() {
+        let start = Instant::now();
+        let interval = Duration::from_secs(10);
+        let mut throttle = RuleAlertThrottle {
+            last_fired_at: start,
+            suppressed_hits: 0,
+        };
+
+        assert_eq!(take_rule_alert_slot(None, start, interval, 2), Some(2));
+        assert_eq!(
+            take_rule_alert_slot(
+                Some(&mut throttle),
+                start + Duration::from_secs(3),
+                interval,
+                4
+            ),
+            None
+        );
+        assert_eq!(
+            take_rule_alert_slot(
+                Some(&mut throttle),
+                start + Duration::from_secs(11),
+                interval,
+                1
+            ),
+            Some(5)
+        );
+        assert_eq!(throttle.suppressed_hits, 0);
+        assert_eq!(
+            rule_alert_interval(&FilterRuleAlertOptions::default()),
+            DEFAULT_RULE_ALERT_INTERVAL
+        );
+    }
+}
@@ -1,24 +1,49 @@
 use dashmap::DashMap;
 use regex::RegexBuilder;
 use ropey::Rope;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-use super::editing::{apply_operation, create_edit_operation};
+use super::editing;
 use super::FILTER_MAX_RANGES_PER_LINE;
 use crate::state::AppState;
-use crate::state::Document;
-use tauri::State;
+use crate::state::{Document, DocumentChange, DocumentTextSnapshot};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 // Bound helpers for the session DashMaps.
 mod session_cache;
-pub(super) use session_cache::enforce_dashmap_bound;
+pub(super) use session_cache::{
+    enforce_dashmap_bound, evict_entries_older_than, CacheEvictionStats, SessionCacheEntry,
+};
 
 // Pure index and offset helpers live in their own submodule.
 mod byte_index;
 pub(super) use byte_index::{
-    build_byte_to_char_map, build_line_starts, find_line_index_by_offset, get_line_text,
+    build_byte_to_char_map, build_char_checkpoints, build_line_starts, find_line_index_by_offset,
+    get_line_text, ByteToChar,
+};
+
+// Find-in-files over a folder, streamed back as events.
+mod directory;
+pub(super) use directory::{
+    cancel_directory_search_impl, search_in_directory_start_impl, DirectorySearchStartPayload,
+};
+
+// Preview-then-apply replace over unopened files in a folder.
+mod directory_replace;
+pub(super) use directory_replace::{
+    preview_replace_in_directory_impl, replace_in_directory_impl, DirectoryReplaceApplyPayload,
+    DirectoryReplacePreviewPayload,
+};
+
+// Search and replace scoped to a rectangular column region.
+mod rectangle;
+pub(super) use rectangle::{
+    replace_in_rectangle_impl, search_in_rectangle_impl, RectangleRegion,
+    RectangleReplaceResultPayload, RectangleSearchResultPayload,
 };
 
 #[derive(serde::Serialize, Clone)]
@@ -69,6 +94,18 @@ pub struct SearchSessionStartResultPayload {
     pub(super) next_offset: Option<usize>,
     pub(super) total_matches: usize,
     pub(super) total_matched_lines: usize,
+    // Set when `cancel_search_session` stopped the scan; nothing is returned.
+    pub(super) cancelled: bool,
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SearchProgressEventPayload {
+    session_id: String,
+    document_id: String,
+    bytes_scanned: usize,
+    total_bytes: usize,
+    matches_so_far: usize,
 }
 
 #[derive(serde::Serialize)]
@@ -79,6 +116,26 @@ pub struct SearchSessionNextResultPayload {
     pub(super) next_offset: Option<usize>,
 }
 
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSessionPreviousResultPayload {
+    pub(super) matches: Vec<SearchMatchResult>,
+    pub(super) document_version: u64,
+    pub(super) start_index: usize,
+    pub(super) has_previous: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSessionSeekResultPayload {
+    pub(super) matches: Vec<SearchMatchResult>,
+    pub(super) document_version: u64,
+    // Index of the first returned match, after clamping to the session.
+    pub(super) start_index: usize,
+    pub(super) next_offset: Option<usize>,
+    pub(super) total_matches: usize,
+}
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchSessionRestoreResultPayload {
@@ -90,6 +147,18 @@ pub struct SearchSessionRestoreResultPayload {
     pub(super) total_matched_lines: usize,
 }
 
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinePreviewResultPayload {
+    pub(super) text: String,
+    // 0-based char offset of `text` within the full line.
+    pub(super) start_char: usize,
+    pub(super) line_char_count: usize,
+    pub(super) truncated_start: bool,
+    pub(super) truncated_end: bool,
+    pub(super) document_version: u64,
+}
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchCountResultPayload {
@@ -110,6 +179,45 @@ pub struct ReplaceAllAndSearchChunkResultPayload {
     pub(super) total_matched_lines: usize,
 }
 
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacePreviewItemResult {
+    // Position in the full match list; pass it back to
+    // `replace_selected_in_document` to apply this replacement.
+    pub(super) index: usize,
+    pub(super) start_char: usize,
+    pub(super) end_char: usize,
+    pub(super) line: usize,
+    pub(super) column: usize,
+    pub(super) match_text: String,
+    pub(super) replacement_text: String,
+    pub(super) line_text: String,
+    pub(super) replaced_line_text: String,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacePreviewResultPayload {
+    pub(super) items: Vec<ReplacePreviewItemResult>,
+    pub(super) total_matches: usize,
+    pub(super) document_version: u64,
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectedMatchRange {
+    pub(super) start_char: usize,
+    pub(super) end_char: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceSelectedResultPayload {
+    pub(super) replaced_count: usize,
+    pub(super) line_count: usize,
+    pub(super) document_version: u64,
+}
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReplaceCurrentAndSearchChunkResultPayload {
@@ -153,6 +261,29 @@ pub struct FilterRuleInput {
     pub(super) bold: bool,
     pub(super) italic: bool,
     pub(super) apply_to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) alert: Option<FilterRuleAlertOptions>,
+}
+
+// What to do when appended text of a follow-mode document hits the rule.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterRuleAlertOptions {
+    #[serde(default)]
+    pub(super) notification: bool,
+    #[serde(default)]
+    pub(super) sound: bool,
+    #[serde(default)]
+    pub(super) flash_taskbar: bool,
+    // Shortest gap between two alerts of the rule; 0 uses the default.
+    #[serde(default)]
+    pub(super) min_interval_seconds: u64,
+}
+
+impl FilterRuleAlertOptions {
+    pub(super) fn is_enabled(&self) -> bool {
+        self.notification || self.sound || self.flash_taskbar
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
@@ -160,6 +291,10 @@ pub struct FilterRuleInput {
 pub struct FilterRuleGroupConfig {
     pub(super) name: String,
     pub(super) rules: Vec<FilterRuleInput>,
+    // File name in the shared rule-group folder the group was read from.
+    // Shared groups are read-only and never written to the config file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) shared_source: Option<String>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -225,6 +360,15 @@ pub struct FilterSessionNextResultPayload {
     pub(super) next_line: Option<usize>,
 }
 
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterSessionPreviousResultPayload {
+    pub(super) matches: Vec<FilterLineMatchResult>,
+    pub(super) document_version: u64,
+    pub(super) start_index: usize,
+    pub(super) has_previous: bool,
+}
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FilterSessionRestoreResultPayload {
@@ -260,6 +404,7 @@ pub struct SearchResultFilterStepCacheEntry {
     pub(super) matches: Arc<Vec<SearchMatchResult>>,
     pub(super) total_matches: usize,
     pub(super) total_matched_lines: usize,
+    pub(super) touched_at: Instant,
 }
 
 #[derive(Clone)]
@@ -267,6 +412,7 @@ pub struct FilterResultFilterStepCacheEntry {
     pub(super) document_version: u64,
     pub(super) matches: Arc<Vec<FilterLineMatchResult>>,
     pub(super) total_matched_lines: usize,
+    pub(super) touched_at: Instant,
 }
 
 #[derive(Clone)]
@@ -276,7 +422,11 @@ pub struct SearchSessionEntry {
     pub(super) result_filter_keyword: Option<String>,
     pub(super) result_filter_case_sensitive: bool,
     pub(super) matches: Arc<Vec<SearchMatchResult>>,
+    // Cursor shared by next and previous: next serves the chunk starting here
+    // and previous the chunk ending here, each moving it past what it served.
     pub(super) next_index: usize,
+    pub(super) include_line_text: bool,
+    pub(super) touched_at: Instant,
 }
 
 #[derive(Clone)]
@@ -286,15 +436,52 @@ pub struct FilterSessionEntry {
     pub(super) result_filter_keyword: Option<String>,
     pub(super) result_filter_case_sensitive: bool,
     pub(super) matches: Arc<Vec<FilterLineMatchResult>>,
+    // Shared by next and previous, as in `SearchSessionEntry`.
     pub(super) next_index: usize,
+    pub(super) touched_at: Instant,
+    pub(super) follow: Option<FilterSessionFollowState>,
 }
 
+// Kept for sessions started on a streamed document in follow mode, so text
+// appended to it is matched line by line instead of expiring the session.
 #[derive(Clone)]
-pub struct SearchCursorContextCacheEntry {
-    pub(super) document_id: String,
-    pub(super) source_text: Arc<String>,
-    pub(super) line_starts: Arc<Vec<usize>>,
-    pub(super) byte_to_char: Arc<Vec<usize>>,
+pub struct FilterSessionFollowState {
+    pub(super) rules: Arc<Vec<CompiledFilterRule>>,
+    // Lines before this index are final; the last line may still grow.
+    pub(super) scanned_line_count: usize,
+    // Start order; only the newest follow session of a document keeps
+    // following, so two panels never alert on the same append.
+    pub(super) generation: u64,
+}
+
+static FILTER_FOLLOW_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterMatchesAppendedEventPayload {
+    pub session_id: String,
+    pub document_id: String,
+    // Earlier matches from this 1-based line on are superseded by `matches`.
+    pub replace_from_line: usize,
+    pub matches: Vec<FilterLineMatchResult>,
+    pub document_version: u64,
+    pub total_matched_lines: usize,
+}
+
+// Appended lines that hit a rule with alert options, folded per rule.
+pub struct FilterRuleAlertHit {
+    pub(super) rule_index: usize,
+    pub(super) keyword: String,
+    pub(super) options: FilterRuleAlertOptions,
+    // First matching line of this append, 1-based.
+    pub(super) line: usize,
+    pub(super) line_text: String,
+    pub(super) hit_count: usize,
+}
+
+pub struct FollowFilterSessionUpdate {
+    pub(super) payload: FilterMatchesAppendedEventPayload,
+    pub(super) alert_hits: Vec<FilterRuleAlertHit>,
 }
 
 pub(super) static SEARCH_RESULT_FILTER_STEP_CACHE: OnceLock<
@@ -307,9 +494,13 @@ pub(super) static SEARCH_SESSION_CACHE: OnceLock<DashMap<String, SearchSessionEn
     OnceLock::new();
 pub(super) static FILTER_SESSION_CACHE: OnceLock<DashMap<String, FilterSessionEntry>> =
     OnceLock::new();
-pub(super) static SEARCH_CURSOR_CONTEXT_CACHE: OnceLock<
-    DashMap<String, SearchCursorContextCacheEntry>,
-> = OnceLock::new();
+// Cancellation tokens of search sessions whose scan is still running, keyed
+// by session id. An entry only lives as long as its scan.
+pub(super) static SEARCH_SESSION_CANCELLATIONS: OnceLock<DashMap<String, Arc<AtomicBool>>> =
+    OnceLock::new();
+
+const SEARCH_PROGRESS_EVENT_INTERVAL: Duration = Duration::from_millis(100);
+const SEARCH_CANCEL_CHECK_INTERVAL_BYTES: usize = 1024 * 1024;
 
 pub(super) fn search_result_filter_step_cache(
 ) -> &'static DashMap<String, SearchResultFilterStepCacheEntry> {
@@ -329,9 +520,88 @@ pub(super) fn filter_session_cache() -> &'static DashMap<String, FilterSessionEn
     FILTER_SESSION_CACHE.get_or_init(DashMap::new)
 }
 
-pub(super) fn search_cursor_context_cache(
-) -> &'static DashMap<String, SearchCursorContextCacheEntry> {
-    SEARCH_CURSOR_CONTEXT_CACHE.get_or_init(DashMap::new)
+fn search_session_cancellations() -> &'static DashMap<String, Arc<AtomicBool>> {
+    SEARCH_SESSION_CANCELLATIONS.get_or_init(DashMap::new)
+}
+
+fn approximate_search_matches_bytes(matches: &[SearchMatchResult]) -> usize {
+    matches
+        .iter()
+        .map(|item| {
+            std::mem::size_of::<SearchMatchResult>()
+                + item.text.capacity()
+                + item.line_text.capacity()
+                + approximate_preview_segments_bytes(item.preview_segments.as_deref())
+        })
+        .sum()
+}
+
+fn approximate_filter_matches_bytes(matches: &[FilterLineMatchResult]) -> usize {
+    matches
+        .iter()
+        .map(|item| {
+            std::mem::size_of::<FilterLineMatchResult>()
+                + item.line_text.capacity()
+                + item.ranges.capacity() * std::mem::size_of::<FilterMatchRangeResult>()
+                + approximate_preview_segments_bytes(item.preview_segments.as_deref())
+        })
+        .sum()
+}
+
+fn approximate_preview_segments_bytes(segments: Option<&[PreviewSegmentResult]>) -> usize {
+    segments
+        .unwrap_or_default()
+        .iter()
+        .map(|segment| std::mem::size_of::<PreviewSegmentResult>() + segment.text.capacity())
+        .sum()
+}
+
+impl SessionCacheEntry for SearchSessionEntry {
+    fn touched_at(&self) -> Instant {
+        self.touched_at
+    }
+
+    fn approximate_bytes(&self) -> usize {
+        approximate_search_matches_bytes(&self.matches)
+    }
+}
+
+impl SessionCacheEntry for FilterSessionEntry {
+    fn touched_at(&self) -> Instant {
+        self.touched_at
+    }
+
+    fn approximate_bytes(&self) -> usize {
+        approximate_filter_matches_bytes(&self.matches)
+    }
+}
+
+impl SessionCacheEntry for SearchResultFilterStepCacheEntry {
+    fn touched_at(&self) -> Instant {
+        self.touched_at
+    }
+
+    fn approximate_bytes(&self) -> usize {
+        approximate_search_matches_bytes(&self.matches)
+    }
+}
+
+impl SessionCacheEntry for FilterResultFilterStepCacheEntry {
+    fn touched_at(&self) -> Instant {
+        self.touched_at
+    }
+
+    fn approximate_bytes(&self) -> usize {
+        approximate_filter_matches_bytes(&self.matches)
+    }
+}
+
+pub(super) fn evict_stale_search_caches(max_age: Duration) -> CacheEvictionStats {
+    let mut stats = evict_entries_older_than(search_session_cache(), max_age);
+    stats += evict_entries_older_than(filter_session_cache(), max_age);
+    stats += evict_entries_older_than(search_result_filter_step_cache(), max_age);
+    stats += evict_entries_older_than(filter_result_filter_step_cache(), max_age);
+    stats
 }
 
 fn remove_search_sessions_by_document(document_id: &str) {
@@ -358,48 +628,61 @@ fn remove_filter_sessions_by_document(document_id: &str) {
     }
 }
 
-fn build_search_cursor_context_cache_key(document_id: &str, document_version: u64) -> String {
-    format!("{document_id}\u{1f}{document_version}")
+// Step cache keys start with the owning document id.
+fn is_document_cache_key(cache_key: &str, document_id: &str) -> bool {
+    cache_key
+        .strip_prefix(document_id)
+        .is_some_and(|rest| rest.starts_with('\u{1f}'))
 }
 
-fn obtain_search_context_owned(id: &str, doc: &Document) -> (String, Vec<usize>, Vec<usize>) {
-    let cache_key = build_search_cursor_context_cache_key(id, doc.document_version);
-    if let Some(entry) = search_cursor_context_cache().get(&cache_key) {
-        return (
-            entry.source_text.as_ref().clone(),
-            entry.line_starts.as_ref().clone(),
-            entry.byte_to_char.as_ref().clone(),
-        );
-    }
+// Single entry point for dropping per-document search artifacts. `None` drops
+// everything the document owns; `Some(version)` keeps only what was computed
+// for that version. Edit paths call this after releasing their document guard,
+// since session lookups lock the session cache first.
+fn retain_document_search_caches(document_id: &str, current_version: Option<u64>) {
+    let is_current = |document_version: u64| Some(document_version) == current_version;
+
+    search_session_cache()
+        .retain(|_, entry| entry.document_id != document_id || is_current(entry.document_version));
+    filter_session_cache()
+        .retain(|_, entry| entry.document_id != document_id || is_current(entry.document_version));
+    search_result_filter_step_cache().retain(|key, entry| {
+        !is_document_cache_key(key, document_id) || is_current(entry.document_version)
+    });
+    filter_result_filter_step_cache().retain(|key, entry| {
+        !is_document_cache_key(key, document_id) || is_current(entry.document_version)
+    });
+}
 
-    let source_text: String = doc.rope.chunks().collect();
-    let line_starts = build_line_starts(&source_text);
-    let byte_to_char = build_byte_to_char_map(&source_text);
+// Called when a document is closed or replaced wholesale from disk.
+pub(super) fn clear_document_search_caches(document_id: &str) {
+    retain_document_search_caches(document_id, None);
+}
 
-    remove_search_cursor_context_cache_by_document(id);
-    enforce_dashmap_bound(search_cursor_context_cache());
-    search_cursor_context_cache().insert(
-        cache_key,
-        SearchCursorContextCacheEntry {
-            document_id: id.to_string(),
-            source_text: Arc::new(source_text.clone()),
-            line_starts: Arc::new(line_starts.clone()),
-            byte_to_char: Arc::new(byte_to_char.clone()),
-        },
-    );
+// Called after every document version bump so artifacts built for older
+// versions do not linger until the next search on that document.
+pub(super) fn clear_outdated_document_search_caches(document_id: &str, document_version: u64) {
+    retain_document_search_caches(document_id, Some(document_version));
+}
 
-    (source_text, line_starts, byte_to_char)
+// The text copy and offset tables shared by every search over the current
+// document version; see `TextSnapshotCell`.
+pub(super) fn document_text_snapshot(doc: &Document) -> Arc<DocumentTextSnapshot> {
+    doc.text_snapshot.get_or_build(doc.document_version, || {
+        let text: String = doc.rope.chunks().collect();
+        let line_starts = build_line_starts(&text);
+        let char_checkpoints = build_char_checkpoints(&text);
+        DocumentTextSnapshot {
+            document_version: doc.document_version,
+            text,
+            line_starts,
+            char_checkpoints,
+        }
+    })
 }
-fn remove_search_cursor_context_cache_by_document(document_id: &str) {
-    let stale_cache_keys = search_cursor_context_cache()
-        .iter()
-        .filter(|entry| entry.value().document_id == document_id)
-        .map(|entry| entry.key().clone())
-        .collect::<Vec<String>>();
 
-    for cache_key in stale_cache_keys {
-        search_cursor_context_cache().remove(&cache_key);
-    }
+fn snapshot_byte_to_char(snapshot: &DocumentTextSnapshot) -> ByteToChar<'_> {
+    ByteToChar::new(&snapshot.text, &snapshot.char_checkpoints)
 }
 
 pub(super) fn dispose_search_session_impl(session_id: String) -> bool {
@@ -681,6 +964,63 @@ fn resolve_replace_value(value: &str, parse_escape_sequences: bool) -> String {
     }
 }
 
+fn compile_replace_regex(
+    keyword: &str,
+    mode: &str,
+    case_sensitive: bool,
+) -> Result<Option<regex::Regex>, String> {
+    if mode != "regex" {
+        return Ok(None);
+    }
+
+    RegexBuilder::new(keyword)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+// Regex replacements expand capture groups from the match found in the whole
+// text, so anchors and word boundaries see the same context as the search
+// did. `haystack` is the text the match offsets refer to.
+fn build_match_replacement(
+    regex: Option<&regex::Regex>,
+    haystack: &str,
+    item: &SearchMatchResult,
+    replace_value: &str,
+) -> String {
+    let Some(regex) = regex else {
+        return replace_value.to_string();
+    };
+
+    let captures = regex.captures_at(haystack, item.start).filter(|captures| {
+        captures
+            .get(0)
+            .is_some_and(|found| found.start() == item.start && found.end() == item.end)
+    });
+    match captures {
+        Some(captures) => {
+            let mut replacement = String::new();
+            captures.expand(replace_value, &mut replacement);
+            replacement
+        }
+        None => regex.replace(&item.text, replace_value).to_string(),
+    }
+}
+
+// The match's line with only this match replaced. Matches running past the
+// end of the line keep just the part before them.
+fn build_replaced_line_text(item: &SearchMatchResult, replacement_text: &str) -> String {
+    let line_chars = item.line_text.chars().collect::<Vec<char>>();
+    let start = item.column.saturating_sub(1).min(line_chars.len());
+    let end = (start + item.end_char.saturating_sub(item.start_char)).min(line_chars.len());
+
+    let mut replaced = line_chars[..start].iter().collect::<String>();
+    replaced.push_str(replacement_text);
+    replaced.extend(&line_chars[end..]);
+    replaced
+}
+
 pub(super) fn wildcard_to_regex_source(keyword: &str) -> String {
     let mut source = String::new();
 
@@ -716,6 +1056,7 @@ pub struct CompiledFilterRule {
     pub(super) regex: Option<regex::Regex>,
     pub(super) style: FilterRuleStyleResult,
     pub(super) apply_to: FilterApplyTo,
+    pub(super) alert: Option<FilterRuleAlertOptions>,
 }
 
 pub(super) fn parse_filter_match_mode(mode: &str) -> Result<FilterMatchMode, String> {
@@ -735,6 +1076,55 @@ pub(super) fn parse_filter_apply_to(value: &str) -> Result<FilterApplyTo, String
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum SearchResultSortOrder {
+    Document,
+    LineText,
+    MatchText,
+    MatchFrequency,
+}
+
+pub(super) fn parse_search_result_sort_order(
+    value: Option<&str>,
+) -> Result<SearchResultSortOrder, String> {
+    let Some(value) = value else {
+        return Ok(SearchResultSortOrder::Document);
+    };
+
+    match value.trim().to_lowercase().as_str() {
+        "" | "document" => Ok(SearchResultSortOrder::Document),
+        "linetext" | "line" => Ok(SearchResultSortOrder::LineText),
+        "matchtext" | "match" => Ok(SearchResultSortOrder::MatchText),
+        "frequency" => Ok(SearchResultSortOrder::MatchFrequency),
+        _ => Err("Unsupported search result sort order".to_string()),
+    }
+}
+
+// Sorts are stable, so equal keys keep document order. Frequency puts the
+// most common match text first and keeps identical texts together.
+fn sort_search_matches(matches: &mut [SearchMatchResult], sort_order: SearchResultSortOrder) {
+    match sort_order {
+        SearchResultSortOrder::Document => {}
+        SearchResultSortOrder::LineText => {
+            matches.sort_by(|left, right| left.line_text.cmp(&right.line_text))
+        }
+        SearchResultSortOrder::MatchText => {
+            matches.sort_by(|left, right| left.text.cmp(&right.text))
+        }
+        SearchResultSortOrder::MatchFrequency => {
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for item in matches.iter() {
+                *counts.entry(item.text.clone()).or_default() += 1;
+            }
+            matches.sort_by(|left, right| {
+                counts[&right.text]
+                    .cmp(&counts[&left.text])
+                    .then_with(|| left.text.cmp(&right.text))
+            });
+        }
+    }
+}
+
 pub(super) fn compile_filter_rules(
     rules: Vec<FilterRuleInput>,
 ) -> Result<Vec<CompiledFilterRule>, String> {
@@ -781,6 +1171,7 @@ pub(super) fn compile_filter_rules(
                 },
             },
             apply_to,
+            alert: rule.alert.filter(FilterRuleAlertOptions::is_enabled),
         });
     }
 
@@ -941,6 +1332,16 @@ pub(super) fn compute_kmp_lps(pattern: &[u8]) -> Vec<usize> {
 }
 
 pub(super) fn kmp_find_all(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    kmp_find_all_until(haystack, needle, &mut |_, _| true)
+}
+
+// `keep_going` is asked every `SEARCH_CANCEL_CHECK_INTERVAL_BYTES` with the
+// bytes scanned and matches found so far; returning false stops the scan.
+fn kmp_find_all_until(
+    haystack: &str,
+    needle: &str,
+    keep_going: &mut dyn FnMut(usize, usize) -> bool,
+) -> Vec<(usize, usize)> {
     if needle.is_empty() {
         return Vec::new();
     }
@@ -954,7 +1355,14 @@ pub(super) fn kmp_find_all(haystack: &str, needle: &str) -> Vec<(usize, usize)>
     let mut haystack_index = 0usize;
     let mut needle_index = 0usize;
 
+    let mut next_check = SEARCH_CANCEL_CHECK_INTERVAL_BYTES;
     while haystack_index < haystack_bytes.len() {
+        if haystack_index >= next_check {
+            if !keep_going(haystack_index, matches.len()) {
+                break;
+            }
+            next_check = haystack_index + SEARCH_CANCEL_CHECK_INTERVAL_BYTES;
+        }
         if haystack_bytes[haystack_index] == needle_bytes[needle_index] {
             haystack_index += 1;
             needle_index += 1;
@@ -974,34 +1382,93 @@ pub(super) fn kmp_find_all(haystack: &str, needle: &str) -> Vec<(usize, usize)>
     matches
 }
 
+// End of the regex chunk starting at `start`: just past the first line break
+// at least `SEARCH_CANCEL_CHECK_INTERVAL_BYTES` further on, or the end of the
+// text.
+fn regex_chunk_end(text: &str, start: usize) -> usize {
+    let target = start.saturating_add(SEARCH_CANCEL_CHECK_INTERVAL_BYTES);
+    if target >= text.len() {
+        return text.len();
+    }
+
+    memchr::memchr(b'\n', &text.as_bytes()[target..])
+        .map(|index| target + index + 1)
+        .unwrap_or(text.len())
+}
+
+fn next_char_boundary(text: &str, offset: usize) -> usize {
+    offset + text[offset..].chars().next().map_or(1, char::len_utf8)
+}
+
+// The regex engine cannot be interrupted mid-scan, so the text is searched in
+// line-aligned chunks of about `SEARCH_CANCEL_CHECK_INTERVAL_BYTES` and
+// `keep_going` is asked before each one. A match cannot span two chunks.
 fn collect_regex_matches(
     text: &str,
     regex: &regex::Regex,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
+    keep_going: &mut dyn FnMut(usize, usize) -> bool,
 ) -> Vec<SearchMatchResult> {
     let mut matches = Vec::new();
+    let mut chunk_start = 0usize;
+    // Like `find_iter`, an empty match right where the previous one ended is
+    // skipped.
+    let mut last_match_end = None;
 
-    for capture in regex.find_iter(text) {
-        let start = capture.start();
-        let end = capture.end();
-        let line_index = find_line_index_by_offset(line_starts, start);
-        let line_start = *line_starts.get(line_index).unwrap_or(&0usize);
-        let start_char = *byte_to_char.get(start).unwrap_or(&0usize);
-        let end_char = *byte_to_char.get(end).unwrap_or(&start_char);
-        let line_start_char = *byte_to_char.get(line_start).unwrap_or(&0usize);
-
-        matches.push(SearchMatchResult {
-            start,
-            end,
-            start_char,
-            end_char,
-            text: capture.as_str().to_string(),
-            line: line_index + 1,
-            column: start_char.saturating_sub(line_start_char) + 1,
-            line_text: get_line_text(text, line_starts, line_index),
-            preview_segments: None,
-        });
+    loop {
+        if !keep_going(chunk_start, matches.len()) {
+            break;
+        }
+        let chunk_end = regex_chunk_end(text, chunk_start);
+        let is_last_chunk = chunk_end == text.len();
+        // Searching a prefix of the text keeps the context before each match.
+        let haystack = &text[..chunk_end];
+        let mut position = chunk_start;
+
+        while position <= chunk_end {
+            let Some(capture) = regex.find_at(haystack, position) else {
+                break;
+            };
+            let start = capture.start();
+            let end = capture.end();
+            if start == chunk_end && !is_last_chunk {
+                break;
+            }
+            if start == end && last_match_end == Some(start) {
+                position = next_char_boundary(text, start);
+                continue;
+            }
+
+            let line_index = find_line_index_by_offset(line_starts, start);
+            let line_start = *line_starts.get(line_index).unwrap_or(&0usize);
+            let start_char = byte_to_char.get(start).unwrap_or(0usize);
+            let end_char = byte_to_char.get(end).unwrap_or(start_char);
+            let line_start_char = byte_to_char.get(line_start).unwrap_or(0usize);
+
+            matches.push(SearchMatchResult {
+                start,
+                end,
+                start_char,
+                end_char,
+                text: capture.as_str().to_string(),
+                line: line_index + 1,
+                column: start_char.saturating_sub(line_start_char) + 1,
+                line_text: get_line_text(text, line_starts, line_index),
+                preview_segments: None,
+            });
+            last_match_end = Some(end);
+            position = if start == end {
+                next_char_boundary(text, end)
+            } else {
+                end
+            };
+        }
+
+        if is_last_chunk {
+            break;
+        }
+        chunk_start = chunk_end;
     }
 
     matches
@@ -1011,13 +1478,14 @@ fn collect_literal_matches(
     text: &str,
     needle: &str,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
+    keep_going: &mut dyn FnMut(usize, usize) -> bool,
 ) -> Vec<SearchMatchResult> {
     if needle.is_empty() {
         return Vec::new();
     }
 
-    let raw_matches = kmp_find_all(text, needle);
+    let raw_matches = kmp_find_all_until(text, needle, keep_going);
     let needle_len_bytes = needle.len();
 
     raw_matches
@@ -1027,9 +1495,9 @@ fn collect_literal_matches(
             let matched_text = text.get(start..end)?.to_string();
             let line_index = find_line_index_by_offset(line_starts, start);
             let line_start = *line_starts.get(line_index).unwrap_or(&0usize);
-            let start_char = *byte_to_char.get(start).unwrap_or(&0usize);
-            let end_char = *byte_to_char.get(end).unwrap_or(&start_char);
-            let line_start_char = *byte_to_char.get(line_start).unwrap_or(&0usize);
+            let start_char = byte_to_char.get(start).unwrap_or(0usize);
+            let end_char = byte_to_char.get(end).unwrap_or(start_char);
+            let line_start_char = byte_to_char.get(line_start).unwrap_or(0usize);
 
             Some(SearchMatchResult {
                 start,
@@ -1158,7 +1626,7 @@ fn normalize_search_offset(text: &str, start_offset: usize) -> usize {
 fn build_match_result_from_offsets(
     text: &str,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
     start: usize,
     end: usize,
 ) -> Option<SearchMatchResult> {
@@ -1172,9 +1640,9 @@ fn build_match_result_from_offsets(
 
     let line_index = find_line_index_by_offset(line_starts, start);
     let line_start = *line_starts.get(line_index).unwrap_or(&0usize);
-    let start_char = *byte_to_char.get(start).unwrap_or(&0usize);
-    let end_char = *byte_to_char.get(end).unwrap_or(&start_char);
-    let line_start_char = *byte_to_char.get(line_start).unwrap_or(&0usize);
+    let start_char = byte_to_char.get(start).unwrap_or(0usize);
+    let end_char = byte_to_char.get(end).unwrap_or(start_char);
+    let line_start_char = byte_to_char.get(line_start).unwrap_or(0usize);
 
     Some(SearchMatchResult {
         start,
@@ -1193,7 +1661,7 @@ fn collect_regex_matches_chunk(
     text: &str,
     regex: &regex::Regex,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
     start_offset: usize,
     max_results: usize,
     result_filter_keyword: Option<&str>,
@@ -1244,7 +1712,7 @@ fn collect_literal_matches_chunk(
     text: &str,
     needle: &str,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
     start_offset: usize,
     max_results: usize,
     result_filter_keyword: Option<&str>,
@@ -1431,7 +1899,7 @@ fn find_previous_regex_match_filtered(
     text: &str,
     regex: &regex::Regex,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
     before_offset: usize,
     result_filter_keyword: Option<&str>,
     result_filter_case_sensitive: bool,
@@ -1473,7 +1941,7 @@ fn find_next_regex_match_filtered(
     text: &str,
     regex: &regex::Regex,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
     start_offset: usize,
     result_filter_keyword: Option<&str>,
     result_filter_case_sensitive: bool,
@@ -1510,7 +1978,7 @@ fn find_next_literal_match_filtered(
     text: &str,
     keyword: &str,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
     start_offset: usize,
     result_filter_keyword: Option<&str>,
     result_filter_case_sensitive: bool,
@@ -1553,7 +2021,7 @@ fn find_next_filtered_search_match(
     mode: &str,
     case_sensitive: bool,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
     start_offset: usize,
     result_filter_keyword: Option<&str>,
     result_filter_case_sensitive: bool,
@@ -1636,7 +2104,7 @@ fn find_previous_filtered_search_match(
     mode: &str,
     case_sensitive: bool,
     line_starts: &[usize],
-    byte_to_char: &[usize],
+    byte_to_char: ByteToChar<'_>,
     before_offset: usize,
     result_filter_keyword: Option<&str>,
     result_filter_case_sensitive: bool,
@@ -1774,26 +2242,50 @@ fn build_filter_result_filter_step_cache_key(
     )
 }
 
-fn build_search_step_filtered_matches(
-    doc: &Document,
+// All matches of `keyword` in `source_text` for the given search mode.
+pub(super) fn collect_search_matches_by_mode(
+    source_text: &str,
     keyword: &str,
     mode: &str,
     case_sensitive: bool,
-    result_filter_keyword: Option<&str>,
-    result_filter_case_sensitive: bool,
 ) -> Result<Vec<SearchMatchResult>, String> {
     if keyword.is_empty() {
         return Ok(Vec::new());
     }
 
-    let source_text: String = doc.rope.chunks().collect();
-    let line_starts = build_line_starts(&source_text);
-    let byte_to_char = build_byte_to_char_map(&source_text);
+    let line_starts = build_line_starts(source_text);
+    let char_checkpoints = build_char_checkpoints(source_text);
+    collect_search_matches_with_index(
+        source_text,
+        &line_starts,
+        ByteToChar::new(source_text, &char_checkpoints),
+        keyword,
+        mode,
+        case_sensitive,
+        &mut |_, _| true,
+    )
+}
+
+// Same as `collect_search_matches_by_mode` for callers that already hold the
+// offset tables, such as a `DocumentTextSnapshot`. `keep_going` can stop the
+// scan early; see `collect_regex_matches` for how often it is asked.
+fn collect_search_matches_with_index(
+    source_text: &str,
+    line_starts: &[usize],
+    byte_to_char: ByteToChar<'_>,
+    keyword: &str,
+    mode: &str,
+    case_sensitive: bool,
+    keep_going: &mut dyn FnMut(usize, usize) -> bool,
+) -> Result<Vec<SearchMatchResult>, String> {
+    if keyword.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    let mut matches = match mode {
+    let matches = match mode {
         "literal" => {
             if case_sensitive {
-                collect_literal_matches(&source_text, keyword, &line_starts, &byte_to_char)
+                collect_literal_matches(source_text, keyword, line_starts, byte_to_char, keep_going)
             } else {
                 let escaped = escape_regex_literal(keyword);
                 let regex = RegexBuilder::new(&escaped)
@@ -1801,7 +2293,7 @@ fn build_search_step_filtered_matches(
                     .build()
                     .map_err(|e| e.to_string())?;
 
-                collect_regex_matches(&source_text, &regex, &line_starts, &byte_to_char)
+                collect_regex_matches(source_text, &regex, line_starts, byte_to_char, keep_going)
             }
         }
         "wildcard" => {
@@ -1811,7 +2303,7 @@ fn build_search_step_filtered_matches(
                 .build()
                 .map_err(|e| e.to_string())?;
 
-            collect_regex_matches(&source_text, &regex, &line_starts, &byte_to_char)
+            collect_regex_matches(source_text, &regex, line_starts, byte_to_char, keep_going)
         }
         "regex" => {
             let regex = RegexBuilder::new(keyword)
@@ -1819,30 +2311,133 @@ fn build_search_step_filtered_matches(
                 .build()
                 .map_err(|e| e.to_string())?;
 
-            collect_regex_matches(&source_text, &regex, &line_starts, &byte_to_char)
+            collect_regex_matches(source_text, &regex, line_starts, byte_to_char, keep_going)
         }
         _ => {
             return Err("Unsupported search mode".to_string());
         }
     };
 
-    if result_filter_keyword.is_some() {
-        matches.retain(|item| {
-            matches_result_filter(
-                &item.line_text,
-                result_filter_keyword,
-                result_filter_case_sensitive,
-            )
-        });
-    }
-
     Ok(matches)
 }
 
-fn build_search_matches_chunk_with_preview(
-    matches: &[SearchMatchResult],
-    start_index: usize,
-    max_results: usize,
+fn build_search_step_filtered_matches(
+    doc: &Document,
+    keyword: &str,
+    mode: &str,
+    case_sensitive: bool,
+    result_filter_keyword: Option<&str>,
+    result_filter_case_sensitive: bool,
+) -> Result<Vec<SearchMatchResult>, String> {
+    if keyword.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    build_snapshot_filtered_matches(
+        &document_text_snapshot(doc),
+        keyword,
+        mode,
+        case_sensitive,
+        result_filter_keyword,
+        result_filter_case_sensitive,
+        &mut |_, _| true,
+    )
+}
+
+fn build_snapshot_filtered_matches(
+    snapshot: &DocumentTextSnapshot,
+    keyword: &str,
+    mode: &str,
+    case_sensitive: bool,
+    result_filter_keyword: Option<&str>,
+    result_filter_case_sensitive: bool,
+    keep_going: &mut dyn FnMut(usize, usize) -> bool,
+) -> Result<Vec<SearchMatchResult>, String> {
+    let mut matches = collect_search_matches_with_index(
+        &snapshot.text,
+        &snapshot.line_starts,
+        snapshot_byte_to_char(&snapshot),
+        keyword,
+        mode,
+        case_sensitive,
+        keep_going,
+    )?;
+
+    if result_filter_keyword.is_some() {
+        matches.retain(|item| {
+            matches_result_filter(
+                &item.line_text,
+                result_filter_keyword,
+                result_filter_case_sensitive,
+            )
+        });
+    }
+
+    Ok(matches)
+}
+
+// Minified files can repeat a multi-megabyte line in every match. Callers that
+// opt out of line text fetch trimmed windows through `get_line_preview`.
+fn strip_search_match_line_text(matches: &mut [SearchMatchResult]) {
+    for item in matches {
+        item.line_text = String::new();
+        item.preview_segments = None;
+    }
+}
+
+fn build_line_preview_window(
+    line_text: &str,
+    max_chars: usize,
+    around_char: usize,
+    document_version: u64,
+) -> LinePreviewResultPayload {
+    let chars: Vec<char> = line_text.chars().collect();
+    let line_char_count = chars.len();
+    let window = max_chars.max(1).min(line_char_count);
+    let start_char = around_char
+        .saturating_sub(window / 2)
+        .min(line_char_count - window);
+    let end_char = start_char + window;
+
+    LinePreviewResultPayload {
+        text: chars[start_char..end_char].iter().collect(),
+        start_char,
+        line_char_count,
+        truncated_start: start_char > 0,
+        truncated_end: end_char < line_char_count,
+        document_version,
+    }
+}
+
+pub(super) fn get_line_preview_impl(
+    state: State<'_, AppState>,
+    id: String,
+    line: usize,
+    max_chars: usize,
+    around_char: usize,
+) -> Result<LinePreviewResultPayload, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    if line == 0 || line > doc.rope.len_lines() {
+        return Err("Line out of range".to_string());
+    }
+
+    let line_text = normalize_rope_line_text(&doc.rope.line(line - 1).to_string());
+    Ok(build_line_preview_window(
+        &line_text,
+        max_chars,
+        around_char,
+        doc.document_version,
+    ))
+}
+
+fn build_search_matches_chunk_with_preview(
+    matches: &[SearchMatchResult],
+    start_index: usize,
+    max_results: usize,
     result_filter_keyword: Option<&str>,
     result_filter_case_sensitive: bool,
 ) -> (Vec<SearchMatchResult>, Option<usize>, usize) {
@@ -1865,14 +2460,24 @@ fn build_search_matches_chunk_with_preview(
     (chunk, next_offset, end_index)
 }
 
+// Sorted sessions are not in offset order, so they resume at the match that
+// starts exactly at `next_offset`.
 fn find_search_session_next_index_by_offset(
     matches: &[SearchMatchResult],
     next_offset: Option<usize>,
+    sort_order: SearchResultSortOrder,
 ) -> usize {
     let Some(offset) = next_offset else {
         return matches.len();
     };
 
+    if sort_order != SearchResultSortOrder::Document {
+        return matches
+            .iter()
+            .position(|item| item.start == offset)
+            .unwrap_or(matches.len());
+    }
+
     matches
         .iter()
         .position(|item| item.start >= offset)
@@ -1907,6 +2512,10 @@ fn build_filter_matches_chunk_with_preview(
     (chunk, next_line, end_index)
 }
 
+fn previous_chunk_start_index(end_index: usize, max_results: usize) -> usize {
+    end_index.saturating_sub(max_results.max(1))
+}
+
 fn find_filter_session_next_index_by_line(
     matches: &[FilterLineMatchResult],
     next_line: Option<usize>,
@@ -1926,15 +2535,27 @@ fn replace_matches_by_char_ranges(
     matches: &[SearchMatchResult],
     replace_value: &str,
 ) -> String {
-    if matches.is_empty() {
+    replace_matches_with_texts(
+        source_text,
+        matches.iter().map(|item| (item, replace_value)),
+    )
+}
+
+// Like `replace_matches_by_char_ranges`, with a replacement text per match.
+fn replace_matches_with_texts<'a>(
+    source_text: &str,
+    replacements: impl IntoIterator<Item = (&'a SearchMatchResult, &'a str)>,
+) -> String {
+    let mut replacements = replacements.into_iter().peekable();
+    if replacements.peek().is_none() {
         return source_text.to_string();
     }
 
     let mut rope = Rope::from_str(source_text);
-    let replacement_char_count = replace_value.chars().count() as isize;
     let mut char_delta: isize = 0;
 
-    for item in matches {
+    for (item, replace_value) in replacements {
+        let replacement_char_count = replace_value.chars().count() as isize;
         let adjusted_start = (item.start_char as isize + char_delta).max(0) as usize;
         let adjusted_end =
             (item.end_char as isize + char_delta).max(adjusted_start as isize) as usize;
@@ -1965,16 +2586,32 @@ fn build_filter_step_filtered_matches(
     result_filter_case_sensitive: bool,
 ) -> Result<Vec<FilterLineMatchResult>, String> {
     let compiled_rules = compile_filter_rules(rules)?;
+    Ok(collect_filter_rule_line_matches(
+        &doc.rope,
+        0,
+        &compiled_rules,
+        result_filter_keyword,
+        result_filter_case_sensitive,
+    ))
+}
+
+pub(super) fn collect_filter_rule_line_matches(
+    rope: &Rope,
+    start_line_index: usize,
+    compiled_rules: &[CompiledFilterRule],
+    result_filter_keyword: Option<&str>,
+    result_filter_case_sensitive: bool,
+) -> Vec<FilterLineMatchResult> {
     if compiled_rules.is_empty() {
-        return Ok(Vec::new());
+        return Vec::new();
     }
 
-    let total_lines = doc.rope.len_lines();
+    let total_lines = rope.len_lines();
     let mut results: Vec<FilterLineMatchResult> = Vec::new();
 
-    for line_index in 0..total_lines {
+    for line_index in start_line_index..total_lines {
         let line_number = line_index + 1;
-        let line_slice = doc.rope.line(line_index);
+        let line_slice = rope.line(line_index);
         let line_text = normalize_rope_line_text(&line_slice.to_string());
 
         if !matches_result_filter(
@@ -1985,12 +2622,12 @@ fn build_filter_step_filtered_matches(
             continue;
         }
 
-        if let Some(item) = match_line_with_filter_rules(line_number, &line_text, &compiled_rules) {
+        if let Some(item) = match_line_with_filter_rules(line_number, &line_text, compiled_rules) {
             results.push(item);
         }
     }
 
-    Ok(results)
+    results
 }
 
 fn lower_bound_search_matches(matches: &[SearchMatchResult], target_start: usize) -> usize {
@@ -2077,7 +2714,20 @@ fn find_exact_filter_match_index(
     None
 }
 
-pub(super) fn search_first_in_document_impl(
+// The scan runs on the shared text snapshot, so the document is not held
+// while it does.
+fn document_search_snapshot(
+    state: &AppState,
+    id: &str,
+) -> Result<Arc<DocumentTextSnapshot>, String> {
+    let doc = state
+        .documents
+        .get(id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    Ok(document_text_snapshot(&doc))
+}
+
+pub(super) async fn search_first_in_document_impl(
     state: State<'_, AppState>,
     id: String,
     keyword: String,
@@ -2085,30 +2735,31 @@ pub(super) fn search_first_in_document_impl(
     case_sensitive: bool,
     reverse: bool,
 ) -> Result<SearchFirstResultPayload, String> {
-    if let Some(doc) = state.documents.get(&id) {
-        let (source_text, line_starts, byte_to_char) = obtain_search_context_owned(&id, &doc);
-
-        let first_match = find_match_edge(&source_text, &keyword, &mode, case_sensitive, reverse)?
-            .and_then(|(start, end)| {
-                build_match_result_from_offsets(
-                    &source_text,
-                    &line_starts,
-                    &byte_to_char,
-                    start,
-                    end,
-                )
-            });
+    let snapshot = document_search_snapshot(&state, &id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let first_match =
+            find_match_edge(&snapshot.text, &keyword, &mode, case_sensitive, reverse)?.and_then(
+                |(start, end)| {
+                    build_match_result_from_offsets(
+                        &snapshot.text,
+                        &snapshot.line_starts,
+                        snapshot_byte_to_char(&snapshot),
+                        start,
+                        end,
+                    )
+                },
+            );
 
         Ok(SearchFirstResultPayload {
             first_match,
-            document_version: doc.document_version,
+            document_version: snapshot.document_version,
         })
-    } else {
-        Err("Document not found".to_string())
-    }
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
-pub(super) fn search_in_document_chunk_impl(
+pub(super) async fn search_in_document_chunk_impl(
     state: State<'_, AppState>,
     id: String,
     keyword: String,
@@ -2117,14 +2768,14 @@ pub(super) fn search_in_document_chunk_impl(
     result_filter_keyword: Option<String>,
     start_offset: usize,
     max_results: usize,
+    include_line_text: bool,
 ) -> Result<SearchChunkResultPayload, String> {
-    if let Some(doc) = state.documents.get(&id) {
-        let (source_text, line_starts, byte_to_char) = obtain_search_context_owned(&id, &doc);
-
+    let snapshot = document_search_snapshot(&state, &id)?;
+    tauri::async_runtime::spawn_blocking(move || {
         if keyword.is_empty() {
             return Ok(SearchChunkResultPayload {
                 matches: Vec::new(),
-                document_version: doc.document_version,
+                document_version: snapshot.document_version,
                 next_offset: None,
             });
         }
@@ -2137,10 +2788,10 @@ pub(super) fn search_in_document_chunk_impl(
             "literal" => {
                 if case_sensitive {
                     collect_literal_matches_chunk(
-                        &source_text,
+                        &snapshot.text,
                         &keyword,
-                        &line_starts,
-                        &byte_to_char,
+                        &snapshot.line_starts,
+                        snapshot_byte_to_char(&snapshot),
                         start_offset,
                         effective_max,
                         result_filter_keyword_ref,
@@ -2154,10 +2805,10 @@ pub(super) fn search_in_document_chunk_impl(
                         .map_err(|e| e.to_string())?;
 
                     collect_regex_matches_chunk(
-                        &source_text,
+                        &snapshot.text,
                         &regex,
-                        &line_starts,
-                        &byte_to_char,
+                        &snapshot.line_starts,
+                        snapshot_byte_to_char(&snapshot),
                         start_offset,
                         effective_max,
                         result_filter_keyword_ref,
@@ -2173,10 +2824,10 @@ pub(super) fn search_in_document_chunk_impl(
                     .map_err(|e| e.to_string())?;
 
                 collect_regex_matches_chunk(
-                    &source_text,
+                    &snapshot.text,
                     &regex,
-                    &line_starts,
-                    &byte_to_char,
+                    &snapshot.line_starts,
+                    snapshot_byte_to_char(&snapshot),
                     start_offset,
                     effective_max,
                     result_filter_keyword_ref,
@@ -2190,10 +2841,10 @@ pub(super) fn search_in_document_chunk_impl(
                     .map_err(|e| e.to_string())?;
 
                 collect_regex_matches_chunk(
-                    &source_text,
+                    &snapshot.text,
                     &regex,
-                    &line_starts,
-                    &byte_to_char,
+                    &snapshot.line_starts,
+                    snapshot_byte_to_char(&snapshot),
                     start_offset,
                     effective_max,
                     result_filter_keyword_ref,
@@ -2206,109 +2857,231 @@ pub(super) fn search_in_document_chunk_impl(
         };
 
         let mut matches_with_preview = matches;
-        for item in matches_with_preview.iter_mut() {
-            item.preview_segments = Some(build_search_match_preview_segments(
-                item,
-                result_filter_keyword_ref,
-                case_sensitive,
-            ));
+        if include_line_text {
+            for item in matches_with_preview.iter_mut() {
+                item.preview_segments = Some(build_search_match_preview_segments(
+                    item,
+                    result_filter_keyword_ref,
+                    case_sensitive,
+                ));
+            }
+        } else {
+            strip_search_match_line_text(&mut matches_with_preview);
         }
 
         Ok(SearchChunkResultPayload {
             matches: matches_with_preview,
-            document_version: doc.document_version,
+            document_version: snapshot.document_version,
             next_offset,
         })
-    } else {
-        Err("Document not found".to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn empty_search_session_start_payload(
+    document_version: u64,
+    cancelled: bool,
+) -> SearchSessionStartResultPayload {
+    SearchSessionStartResultPayload {
+        session_id: None,
+        matches: Vec::new(),
+        document_version,
+        next_offset: None,
+        total_matches: 0,
+        total_matched_lines: 0,
+        cancelled,
     }
 }
 
-pub(super) fn search_session_start_in_document_impl(
-    state: State<'_, AppState>,
-    id: String,
-    keyword: String,
-    mode: String,
+fn emit_search_progress(app: &AppHandle, payload: SearchProgressEventPayload) {
+    if let Err(error) = app.emit("rutar://search-progress", payload) {
+        eprintln!("failed to emit search-progress event: {error}");
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_search_session_start(
+    app: &AppHandle,
+    id: &str,
+    session_id: &str,
+    keyword: &str,
+    mode: &str,
     case_sensitive: bool,
     result_filter_keyword: Option<String>,
     result_filter_case_sensitive: Option<bool>,
     max_results: usize,
+    include_line_text: bool,
+    sort_order: SearchResultSortOrder,
+    cancelled: &AtomicBool,
 ) -> Result<SearchSessionStartResultPayload, String> {
-    if let Some(doc) = state.documents.get(&id) {
-        remove_search_sessions_by_document(&id);
+    let state = app.state::<AppState>();
+    let (snapshot, document_version) = {
+        let doc = state
+            .documents
+            .get(id)
+            .ok_or_else(|| "Document not found".to_string())?;
+        remove_search_sessions_by_document(id);
         if keyword.is_empty() {
-            return Ok(SearchSessionStartResultPayload {
-                session_id: None,
-                matches: Vec::new(),
-                document_version: doc.document_version,
-                next_offset: None,
-                total_matches: 0,
-                total_matched_lines: 0,
-            });
+            return Ok(empty_search_session_start_payload(
+                doc.document_version,
+                false,
+            ));
         }
+        (document_text_snapshot(&doc), doc.document_version)
+    };
 
-        let normalized_result_filter_keyword =
-            normalize_result_filter_keyword(result_filter_keyword);
-        let effective_result_filter_case_sensitive =
-            result_filter_case_sensitive.unwrap_or(case_sensitive);
-        let result_filter_keyword_ref = normalized_result_filter_keyword.as_deref();
-        let all_matches = build_search_step_filtered_matches(
-            &doc,
-            &keyword,
-            &mode,
-            case_sensitive,
-            result_filter_keyword_ref,
-            effective_result_filter_case_sensitive,
-        )?;
-        let total_matches = all_matches.len();
-        let total_matched_lines = all_matches
-            .iter()
-            .map(|item| item.line)
-            .collect::<BTreeSet<usize>>()
-            .len();
-        let (matches, next_offset, next_index) = build_search_matches_chunk_with_preview(
-            &all_matches,
-            0,
-            max_results,
-            result_filter_keyword_ref,
-            effective_result_filter_case_sensitive,
-        );
-
-        if total_matches == 0 {
-            return Ok(SearchSessionStartResultPayload {
-                session_id: None,
-                matches,
-                document_version: doc.document_version,
-                next_offset,
-                total_matches,
-                total_matched_lines,
-            });
-        }
+    let normalized_result_filter_keyword = normalize_result_filter_keyword(result_filter_keyword);
+    let effective_result_filter_case_sensitive =
+        result_filter_case_sensitive.unwrap_or(case_sensitive);
+    let result_filter_keyword_ref = normalized_result_filter_keyword.as_deref();
+    let total_bytes = snapshot.text.len();
+    let mut last_progress_at = Instant::now();
+    let mut all_matches = build_snapshot_filtered_matches(
+        &snapshot,
+        keyword,
+        mode,
+        case_sensitive,
+        result_filter_keyword_ref,
+        effective_result_filter_case_sensitive,
+        &mut |bytes_scanned, matches_so_far| {
+            if cancelled.load(Ordering::Relaxed) {
+                return false;
+            }
+            if last_progress_at.elapsed() >= SEARCH_PROGRESS_EVENT_INTERVAL {
+                last_progress_at = Instant::now();
+                emit_search_progress(
+                    app,
+                    SearchProgressEventPayload {
+                        session_id: session_id.to_string(),
+                        document_id: id.to_string(),
+                        bytes_scanned,
+                        total_bytes,
+                        matches_so_far,
+                    },
+                );
+            }
+            true
+        },
+    )?;
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(empty_search_session_start_payload(document_version, true));
+    }
+    sort_search_matches(&mut all_matches, sort_order);
 
-        let session_id = Uuid::new_v4().to_string();
-        enforce_dashmap_bound(search_session_cache());
-        search_session_cache().insert(
-            session_id.clone(),
-            SearchSessionEntry {
-                document_id: id,
-                document_version: doc.document_version,
-                result_filter_keyword: normalized_result_filter_keyword,
-                result_filter_case_sensitive: effective_result_filter_case_sensitive,
-                matches: Arc::new(all_matches),
-                next_index,
-            },
-        );
+    let total_matches = all_matches.len();
+    let total_matched_lines = all_matches
+        .iter()
+        .map(|item| item.line)
+        .collect::<BTreeSet<usize>>()
+        .len();
+    let (mut matches, next_offset, next_index) = build_search_matches_chunk_with_preview(
+        &all_matches,
+        0,
+        max_results,
+        result_filter_keyword_ref,
+        effective_result_filter_case_sensitive,
+    );
+    if !include_line_text {
+        strip_search_match_line_text(&mut matches);
+    }
 
-        Ok(SearchSessionStartResultPayload {
-            session_id: Some(session_id),
+    if total_matches == 0 {
+        return Ok(SearchSessionStartResultPayload {
+            session_id: None,
             matches,
-            document_version: doc.document_version,
+            document_version,
             next_offset,
             total_matches,
             total_matched_lines,
-        })
-    } else {
-        Err("Document not found".to_string())
+            cancelled: false,
+        });
+    }
+
+    enforce_dashmap_bound(search_session_cache());
+    search_session_cache().insert(
+        session_id.to_string(),
+        SearchSessionEntry {
+            document_id: id.to_string(),
+            document_version,
+            result_filter_keyword: normalized_result_filter_keyword,
+            result_filter_case_sensitive: effective_result_filter_case_sensitive,
+            matches: Arc::new(all_matches),
+            next_index,
+            include_line_text,
+            touched_at: Instant::now(),
+        },
+    );
+
+    Ok(SearchSessionStartResultPayload {
+        session_id: Some(session_id.to_string()),
+        matches,
+        document_version,
+        next_offset,
+        total_matches,
+        total_matched_lines,
+        cancelled: false,
+    })
+}
+
+// Scans on a blocking thread, sending `rutar://search-progress` events as it
+// goes. Callers that may cancel pass their own `session_id`, so
+// `cancel_search_session` can reach the scan before this returns.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn search_session_start_in_document_impl(
+    app: AppHandle,
+    id: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    result_filter_keyword: Option<String>,
+    result_filter_case_sensitive: Option<bool>,
+    max_results: usize,
+    include_line_text: bool,
+    session_id: Option<String>,
+    sort_order: Option<String>,
+) -> Result<SearchSessionStartResultPayload, String> {
+    let sort_order = parse_search_result_sort_order(sort_order.as_deref())?;
+    let session_id = session_id
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let cancelled = Arc::new(AtomicBool::new(false));
+    search_session_cancellations().insert(session_id.clone(), cancelled.clone());
+
+    let worker_session_id = session_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        run_search_session_start(
+            &app,
+            &id,
+            &worker_session_id,
+            &keyword,
+            &mode,
+            case_sensitive,
+            result_filter_keyword,
+            result_filter_case_sensitive,
+            max_results,
+            include_line_text,
+            sort_order,
+            &cancelled,
+        )
+    })
+    .await
+    .map_err(|error| error.to_string());
+
+    search_session_cancellations().remove(&session_id);
+    result?
+}
+
+// Stops the scan of a session that is still starting; returns false when no
+// scan with that id is running.
+pub(super) fn cancel_search_session_impl(session_id: String) -> bool {
+    match search_session_cancellations().get(&session_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
     }
 }
 
@@ -2321,6 +3094,7 @@ pub(super) fn search_session_next_in_document_impl(
         let mut entry = search_session_cache()
             .get_mut(&session_id)
             .ok_or_else(|| "Search session not found".to_string())?;
+        entry.touched_at = Instant::now();
 
         let Some(doc) = state.documents.get(&entry.document_id) else {
             return Err("Search session document not found".to_string());
@@ -2330,13 +3104,16 @@ pub(super) fn search_session_next_in_document_impl(
             return Err("Search session expired due to document changes".to_string());
         }
 
-        let (matches, next_offset, next_index) = build_search_matches_chunk_with_preview(
+        let (mut matches, next_offset, next_index) = build_search_matches_chunk_with_preview(
             entry.matches.as_slice(),
             entry.next_index,
             max_results,
             entry.result_filter_keyword.as_deref(),
             entry.result_filter_case_sensitive,
         );
+        if !entry.include_line_text {
+            strip_search_match_line_text(&mut matches);
+        }
         entry.next_index = next_index;
         let should_remove = next_index >= entry.matches.len();
 
@@ -2354,6 +3131,106 @@ pub(super) fn search_session_next_in_document_impl(
     })
 }
 
+// Returns up to `max_results` matches ending at the session cursor and moves
+// the cursor back to their start, so a virtualized list can page back after
+// dropping rows.
+pub(super) fn search_session_previous_in_document_impl(
+    state: State<'_, AppState>,
+    session_id: String,
+    max_results: usize,
+) -> Result<SearchSessionPreviousResultPayload, String> {
+    let mut entry = search_session_cache()
+        .get_mut(&session_id)
+        .ok_or_else(|| "Search session not found".to_string())?;
+    entry.touched_at = Instant::now();
+
+    let Some(doc) = state.documents.get(&entry.document_id) else {
+        return Err("Search session document not found".to_string());
+    };
+
+    if doc.document_version != entry.document_version {
+        return Err("Search session expired due to document changes".to_string());
+    }
+
+    let end_index = entry.next_index.min(entry.matches.len());
+    let start_index = previous_chunk_start_index(end_index, max_results);
+    let (mut matches, _, _) = build_search_matches_chunk_with_preview(
+        &entry.matches[..end_index],
+        start_index,
+        end_index - start_index,
+        entry.result_filter_keyword.as_deref(),
+        entry.result_filter_case_sensitive,
+    );
+    if !entry.include_line_text {
+        strip_search_match_line_text(&mut matches);
+    }
+    entry.next_index = start_index;
+
+    Ok(SearchSessionPreviousResultPayload {
+        matches,
+        document_version: entry.document_version,
+        start_index,
+        has_previous: start_index > 0,
+    })
+}
+
+// Jumps to `match_index` and returns the chunk starting there; later
+// `search_session_next_in_document` calls continue from the end of it. Unlike
+// next, reaching the end keeps the session so the list can seek back.
+pub(super) fn search_session_seek_in_document_impl(
+    state: State<'_, AppState>,
+    session_id: String,
+    match_index: usize,
+    max_results: usize,
+) -> Result<SearchSessionSeekResultPayload, String> {
+    let mut entry = search_session_cache()
+        .get_mut(&session_id)
+        .ok_or_else(|| "Search session not found".to_string())?;
+    entry.touched_at = Instant::now();
+
+    let Some(doc) = state.documents.get(&entry.document_id) else {
+        return Err("Search session document not found".to_string());
+    };
+
+    if doc.document_version != entry.document_version {
+        return Err("Search session expired due to document changes".to_string());
+    }
+
+    Ok(seek_search_session_entry(
+        &mut entry,
+        match_index,
+        max_results,
+    ))
+}
+
+fn seek_search_session_entry(
+    entry: &mut SearchSessionEntry,
+    match_index: usize,
+    max_results: usize,
+) -> SearchSessionSeekResultPayload {
+    let total_matches = entry.matches.len();
+    let start_index = match_index.min(total_matches.saturating_sub(1));
+    let (mut matches, next_offset, next_index) = build_search_matches_chunk_with_preview(
+        entry.matches.as_slice(),
+        start_index,
+        max_results,
+        entry.result_filter_keyword.as_deref(),
+        entry.result_filter_case_sensitive,
+    );
+    if !entry.include_line_text {
+        strip_search_match_line_text(&mut matches);
+    }
+    entry.next_index = next_index;
+
+    SearchSessionSeekResultPayload {
+        matches,
+        document_version: entry.document_version,
+        start_index,
+        next_offset,
+        total_matches,
+    }
+}
+
 pub(super) fn search_session_restore_in_document_impl(
     state: State<'_, AppState>,
     id: String,
@@ -2364,7 +3241,10 @@ pub(super) fn search_session_restore_in_document_impl(
     result_filter_case_sensitive: Option<bool>,
     expected_document_version: Option<u64>,
     next_offset: Option<usize>,
+    include_line_text: bool,
+    sort_order: Option<String>,
 ) -> Result<SearchSessionRestoreResultPayload, String> {
+    let sort_order = parse_search_result_sort_order(sort_order.as_deref())?;
     if let Some(doc) = state.documents.get(&id) {
         remove_search_sessions_by_document(&id);
 
@@ -2397,7 +3277,7 @@ pub(super) fn search_session_restore_in_document_impl(
         let effective_result_filter_case_sensitive =
             result_filter_case_sensitive.unwrap_or(case_sensitive);
         let result_filter_keyword_ref = normalized_result_filter_keyword.as_deref();
-        let all_matches = build_search_step_filtered_matches(
+        let mut all_matches = build_search_step_filtered_matches(
             &doc,
             &keyword,
             &mode,
@@ -2405,13 +3285,15 @@ pub(super) fn search_session_restore_in_document_impl(
             result_filter_keyword_ref,
             effective_result_filter_case_sensitive,
         )?;
+        sort_search_matches(&mut all_matches, sort_order);
         let total_matches = all_matches.len();
         let total_matched_lines = all_matches
             .iter()
             .map(|item| item.line)
             .collect::<BTreeSet<usize>>()
             .len();
-        let next_index = find_search_session_next_index_by_offset(&all_matches, next_offset);
+        let next_index =
+            find_search_session_next_index_by_offset(&all_matches, next_offset, sort_order);
         let resolved_next_offset = all_matches.get(next_index).map(|item| item.start);
 
         if total_matches == 0 || next_index >= all_matches.len() {
@@ -2436,6 +3318,8 @@ pub(super) fn search_session_restore_in_document_impl(
                 result_filter_case_sensitive: effective_result_filter_case_sensitive,
                 matches: Arc::new(all_matches),
                 next_index,
+                include_line_text,
+                touched_at: Instant::now(),
             },
         );
 
@@ -2452,7 +3336,7 @@ pub(super) fn search_session_restore_in_document_impl(
     }
 }
 
-pub(super) fn search_count_in_document_impl(
+pub(super) async fn search_count_in_document_impl(
     state: State<'_, AppState>,
     id: String,
     keyword: String,
@@ -2460,14 +3344,13 @@ pub(super) fn search_count_in_document_impl(
     case_sensitive: bool,
     result_filter_keyword: Option<String>,
 ) -> Result<SearchCountResultPayload, String> {
-    if let Some(doc) = state.documents.get(&id) {
-        let (source_text, line_starts, _byte_to_char) = obtain_search_context_owned(&id, &doc);
-
+    let snapshot = document_search_snapshot(&state, &id)?;
+    tauri::async_runtime::spawn_blocking(move || {
         if keyword.is_empty() {
             return Ok(SearchCountResultPayload {
                 total_matches: 0,
                 matched_lines: 0,
-                document_version: doc.document_version,
+                document_version: snapshot.document_version,
             });
         }
 
@@ -2477,9 +3360,9 @@ pub(super) fn search_count_in_document_impl(
             "literal" => {
                 if case_sensitive {
                     count_literal_matches(
-                        &source_text,
+                        &snapshot.text,
                         &keyword,
-                        &line_starts,
+                        &snapshot.line_starts,
                         normalized_result_filter_keyword.as_deref(),
                         case_sensitive,
                     )
@@ -2491,9 +3374,9 @@ pub(super) fn search_count_in_document_impl(
                         .map_err(|e| e.to_string())?;
 
                     count_regex_matches(
-                        &source_text,
+                        &snapshot.text,
                         &regex,
-                        &line_starts,
+                        &snapshot.line_starts,
                         normalized_result_filter_keyword.as_deref(),
                         case_sensitive,
                     )
@@ -2507,9 +3390,9 @@ pub(super) fn search_count_in_document_impl(
                     .map_err(|e| e.to_string())?;
 
                 count_regex_matches(
-                    &source_text,
+                    &snapshot.text,
                     &regex,
-                    &line_starts,
+                    &snapshot.line_starts,
                     normalized_result_filter_keyword.as_deref(),
                     case_sensitive,
                 )
@@ -2521,9 +3404,9 @@ pub(super) fn search_count_in_document_impl(
                     .map_err(|e| e.to_string())?;
 
                 count_regex_matches(
-                    &source_text,
+                    &snapshot.text,
                     &regex,
-                    &line_starts,
+                    &snapshot.line_starts,
                     normalized_result_filter_keyword.as_deref(),
                     case_sensitive,
                 )
@@ -2536,11 +3419,11 @@ pub(super) fn search_count_in_document_impl(
         Ok(SearchCountResultPayload {
             total_matches,
             matched_lines,
-            document_version: doc.document_version,
+            document_version: snapshot.document_version,
         })
-    } else {
-        Err("Document not found".to_string())
-    }
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 pub(super) fn search_step_from_cursor_in_document_impl(
@@ -2571,39 +3454,13 @@ pub(super) fn search_step_from_cursor_in_document_impl(
         });
     }
 
-    let cache_key = build_search_cursor_context_cache_key(&id, doc.document_version);
-    let (source_text, line_starts, byte_to_char) =
-        if let Some(entry) = search_cursor_context_cache().get(&cache_key) {
-            (
-                entry.source_text.clone(),
-                entry.line_starts.clone(),
-                entry.byte_to_char.clone(),
-            )
-        } else {
-            let source_text = Arc::new(doc.rope.chunks().collect::<String>());
-            let line_starts = Arc::new(build_line_starts(source_text.as_str()));
-            let byte_to_char = Arc::new(build_byte_to_char_map(source_text.as_str()));
-
-            remove_search_cursor_context_cache_by_document(&id);
-            enforce_dashmap_bound(search_cursor_context_cache());
-            search_cursor_context_cache().insert(
-                cache_key,
-                SearchCursorContextCacheEntry {
-                    document_id: id.clone(),
-                    source_text: source_text.clone(),
-                    line_starts: line_starts.clone(),
-                    byte_to_char: byte_to_char.clone(),
-                },
-            );
-
-            (source_text, line_starts, byte_to_char)
-        };
+    let snapshot = document_text_snapshot(&doc);
     let normalized_result_filter_keyword = normalize_result_filter_keyword(result_filter_keyword);
     let filter_case_sensitive = result_filter_case_sensitive.unwrap_or(case_sensitive);
     let result_filter_keyword_ref = normalized_result_filter_keyword.as_deref();
     let cursor_offset = resolve_search_cursor_offset(
-        source_text.as_str(),
-        &line_starts,
+        snapshot.text.as_str(),
+        &snapshot.line_starts,
         cursor_line,
         cursor_column,
         step,
@@ -2611,12 +3468,12 @@ pub(super) fn search_step_from_cursor_in_document_impl(
 
     let mut target_match = if step > 0 {
         let forward_match = find_next_filtered_search_match(
-            source_text.as_str(),
+            snapshot.text.as_str(),
             &keyword,
             &mode,
             case_sensitive,
-            &line_starts,
-            &byte_to_char,
+            &snapshot.line_starts,
+            snapshot_byte_to_char(&snapshot),
             cursor_offset.saturating_add(1),
             result_filter_keyword_ref,
             filter_case_sensitive,
@@ -2626,12 +3483,12 @@ pub(super) fn search_step_from_cursor_in_document_impl(
             forward_match
         } else {
             find_next_filtered_search_match(
-                source_text.as_str(),
+                snapshot.text.as_str(),
                 &keyword,
                 &mode,
                 case_sensitive,
-                &line_starts,
-                &byte_to_char,
+                &snapshot.line_starts,
+                snapshot_byte_to_char(&snapshot),
                 0,
                 result_filter_keyword_ref,
                 filter_case_sensitive,
@@ -2639,12 +3496,12 @@ pub(super) fn search_step_from_cursor_in_document_impl(
         }
     } else {
         let backward_match = find_previous_filtered_search_match(
-            source_text.as_str(),
+            snapshot.text.as_str(),
             &keyword,
             &mode,
             case_sensitive,
-            &line_starts,
-            &byte_to_char,
+            &snapshot.line_starts,
+            snapshot_byte_to_char(&snapshot),
             cursor_offset,
             result_filter_keyword_ref,
             filter_case_sensitive,
@@ -2654,13 +3511,13 @@ pub(super) fn search_step_from_cursor_in_document_impl(
             backward_match
         } else {
             find_previous_filtered_search_match(
-                source_text.as_str(),
+                snapshot.text.as_str(),
                 &keyword,
                 &mode,
                 case_sensitive,
-                &line_starts,
-                &byte_to_char,
-                source_text.len(),
+                &snapshot.line_starts,
+                snapshot_byte_to_char(&snapshot),
+                snapshot.text.len(),
                 result_filter_keyword_ref,
                 filter_case_sensitive,
             )?
@@ -2769,6 +3626,7 @@ pub(super) fn step_result_filter_search_in_document_impl(
                         matches: matches_arc.clone(),
                         total_matches,
                         total_matched_lines,
+                        touched_at: Instant::now(),
                     },
                 );
                 (
@@ -2802,6 +3660,7 @@ pub(super) fn step_result_filter_search_in_document_impl(
                     matches: matches_arc.clone(),
                     total_matches,
                     total_matched_lines,
+                    touched_at: Instant::now(),
                 },
             );
             (
@@ -3047,18 +3906,30 @@ pub(super) fn filter_session_start_in_document_impl(
     result_filter_keyword: Option<String>,
     result_filter_case_sensitive: Option<bool>,
     max_results: usize,
+    follow_appended: Option<bool>,
 ) -> Result<FilterSessionStartResultPayload, String> {
     if let Some(doc) = state.documents.get(&id) {
         remove_filter_sessions_by_document(&id);
         let normalized_result_filter_keyword =
             normalize_result_filter_keyword(result_filter_keyword);
         let effective_result_filter_case_sensitive = result_filter_case_sensitive.unwrap_or(true);
-        let all_matches = build_filter_step_filtered_matches(
-            &doc,
-            rules,
+        let compiled_rules = Arc::new(compile_filter_rules(rules)?);
+        let all_matches = collect_filter_rule_line_matches(
+            &doc.rope,
+            0,
+            &compiled_rules,
             normalized_result_filter_keyword.as_deref(),
             effective_result_filter_case_sensitive,
-        )?;
+        );
+        // A live session is kept even with no matches yet; they may arrive
+        // with the next append.
+        let follow = (follow_appended.unwrap_or(false) && !compiled_rules.is_empty()).then(|| {
+            FilterSessionFollowState {
+                rules: compiled_rules,
+                scanned_line_count: doc.rope.len_lines().saturating_sub(1),
+                generation: FILTER_FOLLOW_GENERATION.fetch_add(1, Ordering::Relaxed),
+            }
+        });
         let total_matched_lines = all_matches.len();
         let (matches, next_line, next_index) = build_filter_matches_chunk_with_preview(
             &all_matches,
@@ -3068,7 +3939,7 @@ pub(super) fn filter_session_start_in_document_impl(
             effective_result_filter_case_sensitive,
         );
 
-        if total_matched_lines == 0 {
+        if total_matched_lines == 0 && follow.is_none() {
             return Ok(FilterSessionStartResultPayload {
                 session_id: None,
                 matches,
@@ -3089,6 +3960,8 @@ pub(super) fn filter_session_start_in_document_impl(
                 result_filter_case_sensitive: effective_result_filter_case_sensitive,
                 matches: Arc::new(all_matches),
                 next_index,
+                touched_at: Instant::now(),
+                follow,
             },
         );
 
@@ -3104,6 +3977,136 @@ pub(super) fn filter_session_start_in_document_impl(
     }
 }
 
+// Called after text is appended to the end of a streamed document, before
+// outdated caches are cleared. Follow sessions on it re-evaluate the line that
+// was still open plus the new ones and move to the new version; each returned
+// payload is meant for `rutar://filter-matches-appended`. Takes a rope snapshot
+// rather than the document: the session commands lock a session before the
+// document, so the document guard must already be released here.
+pub(super) fn advance_follow_filter_sessions(
+    rope: &Rope,
+    document_version: u64,
+    document_id: &str,
+) -> Vec<FollowFilterSessionUpdate> {
+    let total_lines = rope.len_lines();
+    let mut payloads = Vec::new();
+    let newest_generation = filter_session_cache()
+        .iter()
+        .filter(|entry| entry.document_id == document_id)
+        .filter_map(|entry| entry.follow.as_ref().map(|follow| follow.generation))
+        .max();
+    // A line is alerted on once per rule keyword, however many sessions see it.
+    let mut alerted_lines: HashSet<(usize, String)> = HashSet::new();
+
+    for mut session in filter_session_cache().iter_mut() {
+        let session_id = session.key().clone();
+        let entry = session.value_mut();
+        if entry.document_id != document_id {
+            continue;
+        }
+        if entry
+            .follow
+            .as_ref()
+            .is_some_and(|follow| Some(follow.generation) != newest_generation)
+        {
+            entry.follow = None;
+        }
+        let Some(follow) = entry.follow.as_mut() else {
+            continue;
+        };
+
+        let start_line_index = follow.scanned_line_count.min(total_lines);
+        let appended = collect_filter_rule_line_matches(
+            rope,
+            start_line_index,
+            &follow.rules,
+            entry.result_filter_keyword.as_deref(),
+            entry.result_filter_case_sensitive,
+        );
+        // The line left open by the previous append was not alerted on, so
+        // re-evaluating it cannot alert twice.
+        let alert_hits = collect_filter_rule_alert_hits(
+            &appended,
+            &follow.rules,
+            total_lines,
+            &mut alerted_lines,
+        );
+        follow.scanned_line_count = total_lines.saturating_sub(1);
+
+        let replace_from_line = start_line_index + 1;
+        let matches = Arc::make_mut(&mut entry.matches);
+        let kept = matches.partition_point(|item| item.line < replace_from_line);
+        matches.truncate(kept);
+        matches.extend(appended.iter().cloned());
+        entry.next_index = entry.next_index.min(matches.len());
+        entry.document_version = document_version;
+
+        let (chunk, _, _) = build_filter_matches_chunk_with_preview(
+            &appended,
+            0,
+            appended.len(),
+            entry.result_filter_keyword.as_deref(),
+            entry.result_filter_case_sensitive,
+        );
+        payloads.push(FollowFilterSessionUpdate {
+            payload: FilterMatchesAppendedEventPayload {
+                session_id,
+                document_id: document_id.to_string(),
+                replace_from_line,
+                matches: chunk,
+                document_version,
+                total_matched_lines: entry.matches.len(),
+            },
+            alert_hits,
+        });
+    }
+
+    payloads
+}
+
+// Only complete lines count: the last line may still be cut mid-write and is
+// evaluated again with the next append.
+// Lines already in `alerted_lines` for the rule's keyword are skipped, and the
+// rest are added to it.
+fn collect_filter_rule_alert_hits(
+    matches: &[FilterLineMatchResult],
+    rules: &[CompiledFilterRule],
+    total_lines: usize,
+    alerted_lines: &mut HashSet<(usize, String)>,
+) -> Vec<FilterRuleAlertHit> {
+    let mut hits: Vec<FilterRuleAlertHit> = Vec::new();
+
+    for item in matches.iter().filter(|item| item.line < total_lines) {
+        let Some((rule, options)) = rules
+            .iter()
+            .find(|rule| rule.rule_index == item.rule_index)
+            .and_then(|rule| rule.alert.as_ref().map(|options| (rule, options)))
+        else {
+            continue;
+        };
+        if !alerted_lines.insert((item.line, rule.keyword.clone())) {
+            continue;
+        }
+        if let Some(hit) = hits
+            .iter_mut()
+            .find(|hit| hit.rule_index == item.rule_index)
+        {
+            hit.hit_count += 1;
+            continue;
+        }
+        hits.push(FilterRuleAlertHit {
+            rule_index: item.rule_index,
+            keyword: rule.keyword.clone(),
+            options: options.clone(),
+            line: item.line,
+            line_text: item.line_text.clone(),
+            hit_count: 1,
+        });
+    }
+
+    hits
+}
+
 pub(super) fn filter_session_next_in_document_impl(
     state: State<'_, AppState>,
     session_id: String,
@@ -3113,6 +4116,7 @@ pub(super) fn filter_session_next_in_document_impl(
         let mut entry = filter_session_cache()
             .get_mut(&session_id)
             .ok_or_else(|| "Filter session not found".to_string())?;
+        entry.touched_at = Instant::now();
 
         let Some(doc) = state.documents.get(&entry.document_id) else {
             return Err("Filter session document not found".to_string());
@@ -3130,7 +4134,8 @@ pub(super) fn filter_session_next_in_document_impl(
             entry.result_filter_case_sensitive,
         );
         entry.next_index = next_index;
-        let should_remove = next_index >= entry.matches.len();
+        // A follow session keeps growing as text is appended.
+        let should_remove = next_index >= entry.matches.len() && entry.follow.is_none();
 
         (matches, next_line, entry.document_version, should_remove)
     };
@@ -3146,6 +4151,43 @@ pub(super) fn filter_session_next_in_document_impl(
     })
 }
 
+pub(super) fn filter_session_previous_in_document_impl(
+    state: State<'_, AppState>,
+    session_id: String,
+    max_results: usize,
+) -> Result<FilterSessionPreviousResultPayload, String> {
+    let mut entry = filter_session_cache()
+        .get_mut(&session_id)
+        .ok_or_else(|| "Filter session not found".to_string())?;
+    entry.touched_at = Instant::now();
+
+    let Some(doc) = state.documents.get(&entry.document_id) else {
+        return Err("Filter session document not found".to_string());
+    };
+
+    if doc.document_version != entry.document_version {
+        return Err("Filter session expired due to document changes".to_string());
+    }
+
+    let end_index = entry.next_index.min(entry.matches.len());
+    let start_index = previous_chunk_start_index(end_index, max_results);
+    let (matches, _, _) = build_filter_matches_chunk_with_preview(
+        &entry.matches[..end_index],
+        start_index,
+        end_index - start_index,
+        entry.result_filter_keyword.as_deref(),
+        entry.result_filter_case_sensitive,
+    );
+    entry.next_index = start_index;
+
+    Ok(FilterSessionPreviousResultPayload {
+        matches,
+        document_version: entry.document_version,
+        start_index,
+        has_previous: start_index > 0,
+    })
+}
+
 pub(super) fn filter_session_restore_in_document_impl(
     state: State<'_, AppState>,
     id: String,
@@ -3206,6 +4248,8 @@ pub(super) fn filter_session_restore_in_document_impl(
                 result_filter_case_sensitive: effective_result_filter_case_sensitive,
                 matches: Arc::new(all_matches),
                 next_index,
+                touched_at: Instant::now(),
+                follow: None,
             },
         );
 
@@ -3277,6 +4321,7 @@ pub(super) fn step_result_filter_search_in_filter_document_impl(
                         document_version,
                         matches: matches_arc.clone(),
                         total_matched_lines,
+                        touched_at: Instant::now(),
                     },
                 );
                 (document_version, matches_arc, total_matched_lines)
@@ -3297,6 +4342,7 @@ pub(super) fn step_result_filter_search_in_filter_document_impl(
                     document_version,
                     matches: matches_arc.clone(),
                     total_matched_lines,
+                    touched_at: Instant::now(),
                 },
             );
             (document_version, matches_arc, total_matched_lines)
@@ -3474,96 +4520,287 @@ pub(super) fn replace_current_and_search_chunk_in_document_impl(
         };
 
         let target_match = previous_matches[target_index].clone();
-        let replacement_text = if mode == "regex" {
-            let regex = RegexBuilder::new(&keyword)
-                .case_insensitive(!case_sensitive)
-                .build()
-                .map_err(|e| e.to_string())?;
+        let replace_regex = compile_replace_regex(&keyword, &mode, case_sensitive)?;
+        let replacement_text = build_match_replacement(
+            replace_regex.as_ref(),
+            &document_text_snapshot(&doc).text,
+            &target_match,
+            &effective_replace_value,
+        );
+
+        if replacement_text == target_match.text {
+            return Ok(ReplaceCurrentAndSearchChunkResultPayload {
+                replaced: false,
+                line_count: doc.rope.len_lines(),
+                document_version: doc.document_version,
+                matches: Vec::new(),
+                next_offset: None,
+                preferred_match: None,
+                total_matches: previous_matches.len(),
+                total_matched_lines: previous_matches
+                    .iter()
+                    .map(|item| item.line)
+                    .collect::<BTreeSet<usize>>()
+                    .len(),
+            });
+        }
 
-            regex
-                .replace(&target_match.text, effective_replace_value.as_str())
-                .to_string()
+        let mut engine = doc.engine();
+        engine.replace(target_match.start_char, target_match.text, replacement_text)?;
+        let change = engine.finish();
+
+        let refreshed_matches = build_search_step_filtered_matches(
+            &doc,
+            &keyword,
+            &mode,
+            case_sensitive,
+            result_filter_keyword_ref,
+            effective_result_filter_case_sensitive,
+        )?;
+
+        let total_matches = refreshed_matches.len();
+        let total_matched_lines = refreshed_matches
+            .iter()
+            .map(|item| item.line)
+            .collect::<BTreeSet<usize>>()
+            .len();
+        let effective_max_results = max_results.max(1);
+        let next_offset = refreshed_matches
+            .get(effective_max_results)
+            .map(|item| item.start);
+
+        let mut matches = refreshed_matches
+            .into_iter()
+            .take(effective_max_results)
+            .collect::<Vec<SearchMatchResult>>();
+        for item in &mut matches {
+            item.preview_segments = Some(build_search_match_preview_segments(
+                item,
+                result_filter_keyword_ref,
+                effective_result_filter_case_sensitive,
+            ));
+        }
+        let preferred_chunk_index = select_replace_current_preferred_chunk_index(
+            total_matches,
+            target_index,
+            effective_max_results,
+        );
+        let preferred_match = preferred_chunk_index.and_then(|index| matches.get(index).cloned());
+        drop(doc);
+        clear_outdated_document_search_caches(&id, change.document_version);
+
+        Ok(ReplaceCurrentAndSearchChunkResultPayload {
+            replaced: true,
+            line_count: change.line_count,
+            document_version: change.document_version,
+            matches,
+            next_offset,
+            preferred_match,
+            total_matches,
+            total_matched_lines,
+        })
+    } else {
+        Err("Document not found".to_string())
+    }
+}
+
+// Lists what replacing each match would produce without touching the
+// document, for a confirm list ahead of `replace_selected_in_document`.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn preview_replace_in_document_impl(
+    state: State<'_, AppState>,
+    id: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+    max_results: usize,
+) -> Result<ReplacePreviewResultPayload, String> {
+    if let Some(doc) = state.documents.get(&id) {
+        if keyword.is_empty() {
+            return Ok(ReplacePreviewResultPayload {
+                items: Vec::new(),
+                total_matches: 0,
+                document_version: doc.document_version,
+            });
+        }
+
+        let effective_replace_value =
+            resolve_replace_value(&replace_value, parse_escape_sequences.unwrap_or(false));
+        let replace_regex = compile_replace_regex(&keyword, &mode, case_sensitive)?;
+        let matches = build_search_step_filtered_matches(
+            &doc,
+            &keyword,
+            &mode,
+            case_sensitive,
+            None,
+            case_sensitive,
+        )?;
+
+        let snapshot = document_text_snapshot(&doc);
+        let items = matches
+            .iter()
+            .take(max_results.max(1))
+            .enumerate()
+            .map(|(index, item)| {
+                let replacement_text = build_match_replacement(
+                    replace_regex.as_ref(),
+                    &snapshot.text,
+                    item,
+                    &effective_replace_value,
+                );
+                ReplacePreviewItemResult {
+                    index,
+                    start_char: item.start_char,
+                    end_char: item.end_char,
+                    line: item.line,
+                    column: item.column,
+                    match_text: item.text.clone(),
+                    replaced_line_text: build_replaced_line_text(item, &replacement_text),
+                    replacement_text,
+                    line_text: item.line_text.clone(),
+                }
+            })
+            .collect();
+
+        Ok(ReplacePreviewResultPayload {
+            items,
+            total_matches: matches.len(),
+            document_version: doc.document_version,
+        })
+    } else {
+        Err("Document not found".to_string())
+    }
+}
+
+// Applies the previewed replacements at `match_indices` as one undo step. The
+// indices are only meaningful for the document version the preview saw.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn replace_selected_in_document_impl(
+    state: State<'_, AppState>,
+    id: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+    match_indices: Vec<usize>,
+    expected_document_version: u64,
+) -> Result<ReplaceSelectedResultPayload, String> {
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        if doc.document_version != expected_document_version {
+            return Err("Document changed since the replace preview".to_string());
+        }
+
+        let effective_replace_value =
+            resolve_replace_value(&replace_value, parse_escape_sequences.unwrap_or(false));
+        let replace_regex = compile_replace_regex(&keyword, &mode, case_sensitive)?;
+        let matches = if keyword.is_empty() {
+            Vec::new()
         } else {
-            effective_replace_value
+            build_search_step_filtered_matches(
+                &doc,
+                &keyword,
+                &mode,
+                case_sensitive,
+                None,
+                case_sensitive,
+            )?
         };
 
-        if replacement_text == target_match.text {
-            return Ok(ReplaceCurrentAndSearchChunkResultPayload {
-                replaced: false,
-                line_count: doc.rope.len_lines(),
-                document_version: doc.document_version,
-                matches: Vec::new(),
-                next_offset: None,
-                preferred_match: None,
-                total_matches: previous_matches.len(),
-                total_matched_lines: previous_matches
-                    .iter()
-                    .map(|item| item.line)
-                    .collect::<BTreeSet<usize>>()
-                    .len(),
-            });
+        let mut selected = match_indices;
+        selected.sort_unstable();
+        selected.dedup();
+        if selected.last().is_some_and(|index| *index >= matches.len()) {
+            return Err("Replace match index out of range".to_string());
+        }
+
+        let snapshot = document_text_snapshot(&doc);
+        let edits = selected
+            .iter()
+            .map(|index| {
+                let item = &matches[*index];
+                editing::CharRangeTextEdit {
+                    start_char: item.start_char,
+                    end_char: item.end_char,
+                    text: build_match_replacement(
+                        replace_regex.as_ref(),
+                        &snapshot.text,
+                        item,
+                        &effective_replace_value,
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+        let planned = editing::plan_char_range_edits(&doc.rope, &edits)?;
+
+        let mut engine = doc.engine();
+        editing::apply_planned_text_edits(&mut engine, planned.edits, None, None)?;
+        let change = engine.finish();
+        if change.changed {
+            drop(doc);
+            clear_outdated_document_search_caches(&id, change.document_version);
         }
 
-        let operation = create_edit_operation(
-            &mut doc,
-            target_match.start_char,
-            target_match.text,
-            replacement_text,
-        );
+        Ok(ReplaceSelectedResultPayload {
+            replaced_count: selected.len(),
+            line_count: change.line_count,
+            document_version: change.document_version,
+        })
+    } else {
+        Err("Document not found".to_string())
+    }
+}
 
-        apply_operation(&mut doc, &operation)?;
-        doc.undo_stack.push(operation);
-        doc.redo_stack.clear();
-        remove_search_sessions_by_document(&id);
-        remove_filter_sessions_by_document(&id);
+fn replace_selected_ranges(
+    doc: &mut Document,
+    matches: &[SelectedMatchRange],
+    replace_value: &str,
+) -> Result<DocumentChange, String> {
+    let edits = matches
+        .iter()
+        .map(|item| editing::CharRangeTextEdit {
+            start_char: item.start_char,
+            end_char: item.end_char,
+            text: replace_value.to_string(),
+        })
+        .collect::<Vec<_>>();
+    let planned = editing::plan_char_range_edits(&doc.rope, &edits)?;
 
-        let refreshed_matches = build_search_step_filtered_matches(
-            &doc,
-            &keyword,
-            &mode,
-            case_sensitive,
-            result_filter_keyword_ref,
-            effective_result_filter_case_sensitive,
-        )?;
+    let mut engine = doc.engine();
+    editing::apply_planned_text_edits(&mut engine, planned.edits, None, None)?;
+    Ok(engine.finish())
+}
 
-        let total_matches = refreshed_matches.len();
-        let total_matched_lines = refreshed_matches
-            .iter()
-            .map(|item| item.line)
-            .collect::<BTreeSet<usize>>()
-            .len();
-        let effective_max_results = max_results.max(1);
-        let next_offset = refreshed_matches
-            .get(effective_max_results)
-            .map(|item| item.start);
+// Replaces explicit ranges, e.g. ones the user ticked in a result list, with
+// the same text as one undo step. Nothing is applied if the document moved on
+// from `expected_document_version` or any ranges overlap.
+pub(super) fn replace_selected_matches_impl(
+    state: State<'_, AppState>,
+    id: String,
+    matches: Vec<SelectedMatchRange>,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+    expected_document_version: u64,
+) -> Result<ReplaceSelectedResultPayload, String> {
+    if let Some(mut doc) = state.documents.get_mut(&id) {
+        if doc.document_version != expected_document_version {
+            return Err("Document changed since the matches were found".to_string());
+        }
 
-        let mut matches = refreshed_matches
-            .into_iter()
-            .take(effective_max_results)
-            .collect::<Vec<SearchMatchResult>>();
-        for item in &mut matches {
-            item.preview_segments = Some(build_search_match_preview_segments(
-                item,
-                result_filter_keyword_ref,
-                effective_result_filter_case_sensitive,
-            ));
+        let effective_replace_value =
+            resolve_replace_value(&replace_value, parse_escape_sequences.unwrap_or(false));
+        let change = replace_selected_ranges(&mut doc, &matches, &effective_replace_value)?;
+        if change.changed {
+            drop(doc);
+            clear_outdated_document_search_caches(&id, change.document_version);
         }
-        let preferred_chunk_index = select_replace_current_preferred_chunk_index(
-            total_matches,
-            target_index,
-            effective_max_results,
-        );
-        let preferred_match = preferred_chunk_index.and_then(|index| matches.get(index).cloned());
 
-        Ok(ReplaceCurrentAndSearchChunkResultPayload {
-            replaced: true,
-            line_count: doc.rope.len_lines(),
-            document_version: doc.document_version,
-            matches,
-            next_offset,
-            preferred_match,
-            total_matches,
-            total_matched_lines,
+        Ok(ReplaceSelectedResultPayload {
+            replaced_count: matches.len(),
+            line_count: change.line_count,
+            document_version: change.document_version,
         })
     } else {
         Err("Document not found".to_string())
@@ -3625,20 +4862,17 @@ pub(super) fn replace_all_and_search_chunk_in_document_impl(
             });
         }
 
-        let source_text = doc.rope.to_string();
+        let source_text = document_text_snapshot(&doc).text.clone();
         let next_text = replace_matches_by_char_ranges(
             &source_text,
             &matches_before_replace,
             &effective_replace_value,
         );
+        let mut engine = doc.engine();
         if source_text != next_text {
-            let operation = create_edit_operation(&mut doc, 0, source_text, next_text);
-            apply_operation(&mut doc, &operation)?;
-            doc.undo_stack.push(operation);
-            doc.redo_stack.clear();
-            remove_search_sessions_by_document(&id);
-            remove_filter_sessions_by_document(&id);
+            engine.replace(0, source_text, next_text)?;
         }
+        let change = engine.finish();
 
         let refreshed_matches = build_search_step_filtered_matches(
             &doc,
@@ -3670,10 +4904,15 @@ pub(super) fn replace_all_and_search_chunk_in_document_impl(
             ));
         }
 
+        drop(doc);
+        if change.changed {
+            clear_outdated_document_search_caches(&id, change.document_version);
+        }
+
         Ok(ReplaceAllAndSearchChunkResultPayload {
             replaced_count,
-            line_count: doc.rope.len_lines(),
-            document_version: doc.document_version,
+            line_count: change.line_count,
+            document_version: change.document_version,
             matches,
             next_offset,
             total_matches,
@@ -3698,6 +4937,7 @@ mod tests {
             bold: false,
             italic: false,
             apply_to: apply_to.to_string(),
+            alert: None,
         }
     }
 
@@ -3767,6 +5007,79 @@ mod tests {
         assert_eq!(cache.len(), 50);
     }
 
+    #[test]
+    fn kmp_find_all_until_should_stop_when_keep_going_returns_false() {
+        let haystack = "ab".repeat(SEARCH_CANCEL_CHECK_INTERVAL_BYTES);
+        let mut checks = 0usize;
+        let matches = kmp_find_all_until(&haystack, "ab", &mut |_, _| {
+            checks += 1;
+            false
+        });
+
+        assert_eq!(checks, 1);
+        assert!(matches.len() < SEARCH_CANCEL_CHECK_INTERVAL_BYTES);
+        assert_eq!(
+            kmp_find_all(&haystack, "ab").len(),
+            SEARCH_CANCEL_CHECK_INTERVAL_BYTES
+        );
+    }
+
+    #[test]
+    fn collect_regex_matches_should_match_find_iter_across_chunks_and_stop_between_them() {
+        let line = "alpha beta42 gamma\n";
+        let text = line.repeat(SEARCH_CANCEL_CHECK_INTERVAL_BYTES * 2 / line.len() + 7);
+        let line_starts = build_line_starts(&text);
+        let checkpoints = build_char_checkpoints(&text);
+        let byte_to_char = ByteToChar::new(&text, &checkpoints);
+
+        for pattern in [r"beta\d+", r"a*", r"(?m)^"] {
+            let regex = regex::Regex::new(pattern).expect("regex");
+            let mut chunks = 0usize;
+            let matches =
+                collect_regex_matches(&text, &regex, &line_starts, byte_to_char, &mut |_, _| {
+                    chunks += 1;
+                    true
+                });
+
+            assert_eq!(chunks, 3);
+            assert_eq!(
+                matches
+                    .iter()
+                    .map(|item| (item.start, item.end))
+                    .collect::<Vec<_>>(),
+                regex
+                    .find_iter(&text)
+                    .map(|item| (item.start(), item.end()))
+                    .collect::<Vec<_>>(),
+                "{pattern}"
+            );
+        }
+
+        let regex = regex::Regex::new("beta").expect("regex");
+        let matches = collect_regex_matches(
+            &text,
+            &regex,
+            &line_starts,
+            byte_to_char,
+            &mut |bytes_scanned, _| bytes_scanned == 0,
+        );
+        assert!(!matches.is_empty());
+        assert!(matches.len() < regex.find_iter(&text).count());
+    }
+
+    #[test]
+    fn cancel_search_session_impl_should_flag_only_running_sessions() {
+        let session_id = format!("cancel-test-{}", Uuid::new_v4());
+        assert!(!cancel_search_session_impl(session_id.clone()));
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        search_session_cancellations().insert(session_id.clone(), cancelled.clone());
+        assert!(cancel_search_session_impl(session_id.clone()));
+        assert!(cancelled.load(Ordering::Relaxed));
+
+        search_session_cancellations().remove(&session_id);
+    }
+
     #[test]
     fn find_line_index_by_offset_should_return_last_line_start_not_greater_than_offset() {
         let starts = vec![0usize, 3, 8];
@@ -3806,6 +5119,19 @@ mod tests {
         assert_eq!(mapping[5], 3);
     }
 
+    #[test]
+    fn byte_to_char_should_agree_with_the_per_byte_map_across_checkpoints() {
+        let text = format!("{}a你b\n{}😀", "x".repeat(4094), "你".repeat(3000));
+        let mapping = build_byte_to_char_map(&text);
+        let char_checkpoints = build_char_checkpoints(&text);
+        let byte_to_char = ByteToChar::new(&text, &char_checkpoints);
+
+        for (byte_offset, expected) in mapping.iter().enumerate() {
+            assert_eq!(byte_to_char.get(byte_offset), Some(*expected));
+        }
+        assert_eq!(byte_to_char.get(text.len() + 1), None);
+    }
+
     #[test]
     fn get_line_text_should_strip_line_break_and_trailing_carriage_return() {
         let text = "a\r\nb\n";
@@ -3851,6 +5177,44 @@ mod tests {
         assert_eq!(decoded, r"keep\q\z");
     }
 
+    #[test]
+    fn build_match_replacement_should_expand_capture_groups_for_regex_mode() {
+        let regex = compile_replace_regex(r"(\w+)@(\w+)", "regex", true)
+            .expect("compile regex")
+            .expect("regex mode");
+
+        let haystack = "mail user@host now";
+        let item = make_search_match(5, 14, 1, 6, haystack);
+        assert_eq!(
+            build_match_replacement(Some(&regex), haystack, &item, "$2:$1"),
+            "host:user"
+        );
+        assert_eq!(
+            build_match_replacement(None, haystack, &item, "$2:$1"),
+            "$2:$1"
+        );
+
+        // `\B` only holds inside the word; the match text alone has none.
+        let inner = compile_replace_regex(r"\B(\w)", "regex", true)
+            .expect("compile regex")
+            .expect("regex mode");
+        let item = make_search_match(1, 2, 1, 2, "abc");
+        assert_eq!(
+            build_match_replacement(Some(&inner), "abc", &item, "[$1]"),
+            "[b]"
+        );
+        assert!(compile_replace_regex("a(", "literal", true)
+            .expect("literal mode")
+            .is_none());
+    }
+
+    #[test]
+    fn build_replaced_line_text_should_replace_only_the_given_match() {
+        let item = make_search_match(9, 12, 2, 5, "let foo = foo;");
+
+        assert_eq!(build_replaced_line_text(&item, "bar"), "let bar = foo;");
+    }
+
     #[test]
     fn resolve_replace_value_should_keep_literal_when_escape_parsing_disabled() {
         let resolved = resolve_replace_value(r"\n", false);
@@ -3926,6 +5290,59 @@ mod tests {
         assert_eq!(ranges, vec![(0, 2), (4, 6)]);
     }
 
+    #[test]
+    fn collect_filter_rule_line_matches_should_start_at_given_line() {
+        let compiled = compile_filter_rules(vec![make_rule("error", "contains", "line")])
+            .expect("rule compile should succeed");
+        let rope = Rope::from_str("error one\nok\nerror two\nerr");
+
+        let all = collect_filter_rule_line_matches(&rope, 0, &compiled, None, true);
+        let tail = collect_filter_rule_line_matches(&rope, 2, &compiled, None, true);
+
+        assert_eq!(
+            all.iter().map(|item| item.line).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            tail.iter().map(|item| item.line).collect::<Vec<_>>(),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn collect_filter_rule_alert_hits_should_fold_complete_lines_per_flagged_rule() {
+        let mut flagged = make_rule("error", "contains", "line");
+        flagged.alert = Some(FilterRuleAlertOptions {
+            sound: true,
+            ..FilterRuleAlertOptions::default()
+        });
+        let compiled = compile_filter_rules(vec![flagged, make_rule("warn", "contains", "line")])
+            .expect("rule compile should succeed");
+        let rope = Rope::from_str("error a\nwarn b\nerror c\nerror d");
+        let matches = collect_filter_rule_line_matches(&rope, 0, &compiled, None, true);
+
+        let mut alerted_lines = HashSet::new();
+        let hits = collect_filter_rule_alert_hits(
+            &matches,
+            &compiled,
+            rope.len_lines(),
+            &mut alerted_lines,
+        );
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].rule_index, 0);
+        assert_eq!(hits[0].line, 1);
+        assert_eq!(hits[0].hit_count, 2);
+
+        let repeated = collect_filter_rule_alert_hits(
+            &matches,
+            &compiled,
+            rope.len_lines(),
+            &mut alerted_lines,
+        );
+        assert!(repeated.is_empty());
+    }
+
     #[test]
     fn select_replace_current_preferred_chunk_index_should_clamp_to_chunk_tail() {
         assert_eq!(
@@ -3972,11 +5389,64 @@ mod tests {
         assert_eq!(next_index, 2);
     }
 
+    #[test]
+    fn seek_search_session_entry_should_jump_to_match_and_move_cursor() {
+        let mut entry = make_search_session_entry("seek-doc");
+        entry.matches = Arc::new(
+            (0..10)
+                .map(|index| make_search_match(index * 10, index * 10 + 4, index + 1, 1, "todo"))
+                .collect(),
+        );
+
+        let payload = seek_search_session_entry(&mut entry, 5, 3);
+
+        assert_eq!(payload.start_index, 5);
+        assert_eq!(payload.total_matches, 10);
+        assert_eq!(
+            payload
+                .matches
+                .iter()
+                .map(|item| item.line)
+                .collect::<Vec<_>>(),
+            vec![6, 7, 8]
+        );
+        assert!(payload
+            .matches
+            .iter()
+            .all(|item| item.preview_segments.is_some()));
+        assert_eq!(payload.next_offset, Some(80));
+        assert_eq!(entry.next_index, 8);
+    }
+
+    #[test]
+    fn seek_search_session_entry_should_clamp_past_the_end_and_strip_line_text() {
+        let mut entry = make_search_session_entry("seek-doc");
+        entry.include_line_text = false;
+        entry.matches = Arc::new(vec![
+            make_search_match(0, 4, 1, 1, "todo one"),
+            make_search_match(10, 14, 2, 1, "todo two"),
+        ]);
+
+        let payload = seek_search_session_entry(&mut entry, 500, 10);
+
+        assert_eq!(payload.start_index, 1);
+        assert_eq!(payload.matches.len(), 1);
+        assert!(payload.matches[0].line_text.is_empty());
+        assert_eq!(payload.next_offset, None);
+        assert_eq!(entry.next_index, 2);
+
+        let mut empty = make_search_session_entry("seek-doc");
+        let payload = seek_search_session_entry(&mut empty, 3, 10);
+        assert_eq!(payload.start_index, 0);
+        assert!(payload.matches.is_empty());
+        assert_eq!(payload.total_matches, 0);
+    }
+
     #[test]
     fn find_next_filtered_search_match_should_return_first_match_after_offset() {
         let text = "todo one\nskip\ntodo two";
         let line_starts = build_line_starts(text);
-        let byte_to_char = build_byte_to_char_map(text);
+        let char_checkpoints = build_char_checkpoints(text);
 
         let match_result = find_next_filtered_search_match(
             text,
@@ -3984,7 +5454,7 @@ mod tests {
             "literal",
             true,
             &line_starts,
-            &byte_to_char,
+            ByteToChar::new(text, &char_checkpoints),
             1,
             None,
             true,
@@ -4000,7 +5470,7 @@ mod tests {
     fn find_previous_filtered_search_match_should_return_last_match_before_offset() {
         let text = "todo one\nskip\ntodo two";
         let line_starts = build_line_starts(text);
-        let byte_to_char = build_byte_to_char_map(text);
+        let char_checkpoints = build_char_checkpoints(text);
         let second_match_start = text.find("todo two").expect("second match should exist");
 
         let match_result = find_previous_filtered_search_match(
@@ -4009,7 +5479,7 @@ mod tests {
             "literal",
             true,
             &line_starts,
-            &byte_to_char,
+            ByteToChar::new(text, &char_checkpoints),
             second_match_start,
             None,
             true,
@@ -4030,22 +5500,79 @@ mod tests {
         ];
 
         assert_eq!(
-            find_search_session_next_index_by_offset(&matches, Some(0)),
+            find_search_session_next_index_by_offset(
+                &matches,
+                Some(0),
+                SearchResultSortOrder::Document
+            ),
             0
         );
         assert_eq!(
-            find_search_session_next_index_by_offset(&matches, Some(10)),
+            find_search_session_next_index_by_offset(
+                &matches,
+                Some(10),
+                SearchResultSortOrder::Document
+            ),
             1
         );
         assert_eq!(
-            find_search_session_next_index_by_offset(&matches, Some(15)),
+            find_search_session_next_index_by_offset(
+                &matches,
+                Some(15),
+                SearchResultSortOrder::Document
+            ),
             2
         );
         assert_eq!(
-            find_search_session_next_index_by_offset(&matches, Some(30)),
+            find_search_session_next_index_by_offset(
+                &matches,
+                Some(30),
+                SearchResultSortOrder::Document
+            ),
+            3
+        );
+        assert_eq!(
+            find_search_session_next_index_by_offset(
+                &matches,
+                None,
+                SearchResultSortOrder::Document
+            ),
+            3
+        );
+    }
+
+    #[test]
+    fn sort_search_matches_should_group_by_frequency_then_keep_document_order() {
+        let mut matches = vec![
+            make_search_match(0, 3, 1, 1, "bar x"),
+            make_search_match(10, 13, 2, 1, "foo y"),
+            make_search_match(20, 23, 3, 1, "bar z"),
+            make_search_match(30, 33, 4, 1, "abc w"),
+            make_search_match(40, 43, 5, 1, "foo v"),
+            make_search_match(50, 53, 6, 1, "bar u"),
+        ];
+
+        sort_search_matches(&mut matches, SearchResultSortOrder::MatchFrequency);
+        let starts = matches.iter().map(|item| item.start).collect::<Vec<_>>();
+        assert_eq!(starts, vec![0, 20, 50, 10, 40, 30]);
+
+        sort_search_matches(&mut matches, SearchResultSortOrder::LineText);
+        let starts = matches.iter().map(|item| item.start).collect::<Vec<_>>();
+        assert_eq!(starts, vec![30, 50, 0, 20, 40, 10]);
+
+        assert_eq!(
+            find_search_session_next_index_by_offset(
+                &matches,
+                Some(20),
+                SearchResultSortOrder::LineText
+            ),
             3
         );
-        assert_eq!(find_search_session_next_index_by_offset(&matches, None), 3);
+        assert_eq!(
+            parse_search_result_sort_order(Some("Frequency")),
+            Ok(SearchResultSortOrder::MatchFrequency)
+        );
+        assert!(parse_search_result_sort_order(Some("random")).is_err());
     }
 
     #[test]
@@ -4077,6 +5604,14 @@ mod tests {
         assert_eq!(next_index, 3);
     }
 
+    #[test]
+    fn previous_chunk_start_index_should_stop_at_first_match() {
+        assert_eq!(previous_chunk_start_index(10, 4), 6);
+        assert_eq!(previous_chunk_start_index(3, 4), 0);
+        assert_eq!(previous_chunk_start_index(0, 4), 0);
+        assert_eq!(previous_chunk_start_index(5, 0), 4);
+    }
+
     #[test]
     fn find_filter_session_next_index_by_line_should_return_expected_position() {
         let matches = vec![
@@ -4109,6 +5644,8 @@ mod tests {
                 result_filter_case_sensitive: true,
                 matches: Arc::new(Vec::new()),
                 next_index: 0,
+                include_line_text: true,
+                touched_at: Instant::now(),
             },
         );
 
@@ -4129,6 +5666,8 @@ mod tests {
                 result_filter_case_sensitive: true,
                 matches: Arc::new(Vec::new()),
                 next_index: 0,
+                touched_at: Instant::now(),
+                follow: None,
             },
         );
 
@@ -4144,6 +5683,8 @@ mod tests {
             result_filter_case_sensitive: true,
             matches: Arc::new(Vec::new()),
             next_index: 0,
+            include_line_text: true,
+            touched_at: Instant::now(),
         }
     }
 
@@ -4155,7 +5696,40 @@ mod tests {
             result_filter_case_sensitive: true,
             matches: Arc::new(Vec::new()),
             next_index: 0,
+            touched_at: Instant::now(),
+            follow: None,
+        }
+    }
+
+    #[test]
+    fn advance_follow_filter_sessions_should_only_follow_newest_session_of_document() {
+        let mut flagged = make_rule("error", "contains", "line");
+        flagged.alert = Some(FilterRuleAlertOptions {
+            sound: true,
+            ..FilterRuleAlertOptions::default()
+        });
+        let rules =
+            Arc::new(compile_filter_rules(vec![flagged]).expect("rule compile should succeed"));
+        for (session_id, generation) in [("follow-old-session", 1), ("follow-new-session", 2)] {
+            let mut entry = make_filter_session_entry("follow-doc");
+            entry.follow = Some(FilterSessionFollowState {
+                rules: rules.clone(),
+                scanned_line_count: 0,
+                generation,
+            });
+            filter_session_cache().insert(session_id.to_string(), entry);
         }
+
+        let rope = Rope::from_str("error a\nerror b\n");
+        let updates = advance_follow_filter_sessions(&rope, 2, "follow-doc");
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].payload.session_id, "follow-new-session");
+        assert_eq!(updates[0].alert_hits[0].hit_count, 2);
+        assert!(filter_session_cache()
+            .get("follow-old-session")
+            .is_some_and(|entry| entry.follow.is_none()));
+        remove_filter_sessions_by_document("follow-doc");
     }
 
     #[test]
@@ -4198,4 +5772,161 @@ mod tests {
         assert!(filter_session_cache().contains_key("doc-x-session"));
         assert!(!filter_session_cache().contains_key("doc-y-session"));
     }
+
+    fn make_search_step_cache_entry(document_version: u64) -> SearchResultFilterStepCacheEntry {
+        SearchResultFilterStepCacheEntry {
+            document_version,
+            matches: Arc::new(Vec::new()),
+            total_matches: 0,
+            total_matched_lines: 0,
+            touched_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn clear_outdated_document_search_caches_should_keep_current_version_entries() {
+        let mut outdated_session = make_search_session_entry("doc-registry");
+        outdated_session.document_version = 0;
+        search_session_cache().insert("doc-registry-old".to_string(), outdated_session);
+        search_session_cache().insert(
+            "doc-registry-current".to_string(),
+            make_search_session_entry("doc-registry"),
+        );
+        search_result_filter_step_cache().insert(
+            "doc-registry\u{1f}0\u{1f}".to_string(),
+            make_search_step_cache_entry(0),
+        );
+        search_result_filter_step_cache().insert(
+            "doc-registry\u{1f}1\u{1f}".to_string(),
+            make_search_step_cache_entry(1),
+        );
+        search_result_filter_step_cache().insert(
+            "doc-registry-other\u{1f}0\u{1f}".to_string(),
+            make_search_step_cache_entry(0),
+        );
+
+        clear_outdated_document_search_caches("doc-registry", 1);
+
+        assert!(!search_session_cache().contains_key("doc-registry-old"));
+        assert!(search_session_cache().contains_key("doc-registry-current"));
+        assert!(!search_result_filter_step_cache().contains_key("doc-registry\u{1f}0\u{1f}"));
+        assert!(search_result_filter_step_cache().contains_key("doc-registry\u{1f}1\u{1f}"));
+        assert!(search_result_filter_step_cache().contains_key("doc-registry-other\u{1f}0\u{1f}"));
+
+        clear_document_search_caches("doc-registry");
+
+        assert!(!search_session_cache().contains_key("doc-registry-current"));
+        assert!(!search_result_filter_step_cache().contains_key("doc-registry\u{1f}1\u{1f}"));
+    }
+
+    #[test]
+    fn build_line_preview_window_should_center_on_match_and_clamp_to_line() {
+        let line_text = "0123456789abcdefghij";
+
+        let centered = build_line_preview_window(line_text, 6, 10, 3);
+        assert_eq!(centered.text, "789abc");
+        assert_eq!(centered.start_char, 7);
+        assert_eq!(centered.line_char_count, 20);
+        assert!(centered.truncated_start);
+        assert!(centered.truncated_end);
+        assert_eq!(centered.document_version, 3);
+
+        let tail = build_line_preview_window(line_text, 6, 19, 3);
+        assert_eq!(tail.text, "efghij");
+        assert!(!tail.truncated_end);
+
+        let whole = build_line_preview_window("短行", 100, 50, 0);
+        assert_eq!(whole.text, "短行");
+        assert_eq!(whole.start_char, 0);
+        assert!(!whole.truncated_start && !whole.truncated_end);
+    }
+
+    #[test]
+    fn strip_search_match_line_text_should_drop_line_text_and_preview_segments() {
+        let mut matches = vec![make_search_match(0, 4, 1, 1, "todo one")];
+        matches[0].preview_segments = Some(Vec::new());
+
+        strip_search_match_line_text(&mut matches);
+
+        assert!(matches[0].line_text.is_empty());
+        assert!(matches[0].preview_segments.is_none());
+        assert_eq!(matches[0].text, "todo");
+    }
+
+    #[test]
+    fn replace_selected_ranges_should_undo_every_replacement_in_one_step() {
+        let mut doc = Document::for_test("foo bar foo baz foo", None);
+
+        let change = replace_selected_ranges(
+            &mut doc,
+            &[
+                SelectedMatchRange {
+                    start_char: 16,
+                    end_char: 19,
+                },
+                SelectedMatchRange {
+                    start_char: 0,
+                    end_char: 3,
+                },
+            ],
+            "qux",
+        )
+        .expect("replace selected ranges");
+
+        assert!(change.changed);
+        assert_eq!(doc.rope.to_string(), "qux bar foo baz qux");
+        assert_eq!(doc.undo_stack.len(), 2);
+        assert!(doc
+            .undo_stack
+            .iter()
+            .all(|operation| operation.old_text == "foo" && operation.new_text == "qux"));
+
+        let mut engine = doc.engine();
+        engine.undo().expect("undo");
+        drop(engine);
+        assert!(doc.undo_stack.is_empty());
+        assert_eq!(doc.rope.to_string(), "foo bar foo baz foo");
+    }
+
+    #[test]
+    fn replace_selected_ranges_should_reject_overlapping_ranges() {
+        let mut doc = Document::for_test("foo bar foo", None);
+
+        let result = replace_selected_ranges(
+            &mut doc,
+            &[
+                SelectedMatchRange {
+                    start_char: 0,
+                    end_char: 5,
+                },
+                SelectedMatchRange {
+                    start_char: 4,
+                    end_char: 7,
+                },
+            ],
+            "x",
+        );
+
+        assert!(result.is_err());
+        assert_eq!(doc.rope.to_string(), "foo bar foo");
+        assert!(doc.undo_stack.is_empty());
+    }
+
+    #[test]
+    fn replace_selected_ranges_should_leave_identical_text_unchanged() {
+        let mut doc = Document::for_test("foo bar", None);
+
+        let change = replace_selected_ranges(
+            &mut doc,
+            &[SelectedMatchRange {
+                start_char: 0,
+                end_char: 3,
+            }],
+            "foo",
+        )
+        .expect("replace selected ranges");
+
+        assert!(!change.changed);
+        assert!(doc.undo_stack.is_empty());
+    }
 }
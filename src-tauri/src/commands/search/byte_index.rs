@@ -57,6 +57,61 @@ pub(crate) fn build_byte_to_char_map(text: &str) -> Vec<usize> {
     mapping
 }
 
+// Bytes between two entries of `build_char_checkpoints`, which bounds the scan
+// behind each `ByteToChar` lookup.
+const CHAR_CHECKPOINT_BYTES: usize = 4096;
+
+// Char count before every `CHAR_CHECKPOINT_BYTES`-th byte. Costs one entry per
+// block where `build_byte_to_char_map` costs one per byte, which matters for
+// snapshots kept alongside whole documents.
+pub(crate) fn build_char_checkpoints(text: &str) -> Vec<usize> {
+    let bytes = text.as_bytes();
+    let mut checkpoints = Vec::with_capacity(bytes.len() / CHAR_CHECKPOINT_BYTES + 2);
+    let mut char_count = 0usize;
+    checkpoints.push(char_count);
+    for block in bytes.chunks(CHAR_CHECKPOINT_BYTES) {
+        char_count += count_char_starts(block);
+        checkpoints.push(char_count);
+    }
+
+    checkpoints
+}
+
+fn count_char_starts(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|byte| (**byte & 0xC0) != 0x80).count()
+}
+
+// Byte-to-char lookups over a text and its `build_char_checkpoints` table.
+// Answers like indexing the table from `build_byte_to_char_map`: a byte
+// inside a multibyte char maps to that char, past the end gives `None`.
+#[derive(Clone, Copy)]
+pub(crate) struct ByteToChar<'a> {
+    text: &'a str,
+    checkpoints: &'a [usize],
+}
+
+impl<'a> ByteToChar<'a> {
+    pub(crate) fn new(text: &'a str, checkpoints: &'a [usize]) -> Self {
+        Self { text, checkpoints }
+    }
+
+    pub(crate) fn get(&self, byte_offset: usize) -> Option<usize> {
+        if byte_offset > self.text.len() {
+            return None;
+        }
+
+        let block = byte_offset / CHAR_CHECKPOINT_BYTES;
+        let block_start = block * CHAR_CHECKPOINT_BYTES;
+        let starts_before = *self.checkpoints.get(block)?
+            + count_char_starts(&self.text.as_bytes()[block_start..byte_offset]);
+        if self.text.is_char_boundary(byte_offset) {
+            Some(starts_before)
+        } else {
+            Some(starts_before - 1)
+        }
+    }
+}
+
 pub(crate) fn get_line_text(text: &str, line_starts: &[usize], line_index: usize) -> String {
     if line_starts.is_empty() {
         return String::new();
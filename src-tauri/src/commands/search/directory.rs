@@ -0,0 +1,392 @@
+// Find-in-files across a folder from the file tree.
+//
+// The walk runs on a background thread and honors `.gitignore`. Files that are
+// too large, binary or unreadable are counted as skipped. Matches are sent in
+// `rutar://directory-search-chunk` events. A single
+// `rutar://directory-search-finished` event closes the search, whether it
+// completed, was cancelled or hit its result cap.
+
+use dashmap::DashMap;
+use encoding_rs::Encoding;
+use regex::{Regex, RegexBuilder};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use super::super::file_sniff::{self, SniffedContent};
+use super::super::folder_walk;
+use super::super::ENCODING_DETECT_SAMPLE_BYTES;
+use super::{
+    build_preview_segments_by_char, escape_regex_literal, wildcard_to_regex_source,
+    PreviewSegmentResult,
+};
+
+const DIRECTORY_SEARCH_MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+const DIRECTORY_SEARCH_CHUNK_MATCHES: usize = 200;
+const DIRECTORY_SEARCH_DEFAULT_MAX_RESULTS: usize = 10_000;
+const DIRECTORY_SEARCH_PREVIEW_MAX_CHARS: usize = 240;
+const DIRECTORY_SEARCH_MAX_MATCHES_PER_LINE: usize = 256;
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectorySearchMatch {
+    pub(super) file_path: String,
+    pub(super) line: usize,
+    // 1-based char columns; the end is exclusive.
+    pub(super) column: usize,
+    pub(super) end_column: usize,
+    // Clipped around the match for very long lines.
+    pub(super) line_text: String,
+    pub(super) preview_segments: Vec<PreviewSegmentResult>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectorySearchStartPayload {
+    pub(super) search_id: String,
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DirectorySearchChunkEventPayload {
+    search_id: String,
+    matches: Vec<DirectorySearchMatch>,
+    scanned_files: usize,
+    total_files: usize,
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DirectorySearchFinishedEventPayload {
+    search_id: String,
+    scanned_files: usize,
+    total_files: usize,
+    skipped_files: usize,
+    match_count: usize,
+    cancelled: bool,
+    truncated: bool,
+}
+
+fn active_directory_searches() -> &'static DashMap<String, Arc<AtomicBool>> {
+    static MAP: OnceLock<DashMap<String, Arc<AtomicBool>>> = OnceLock::new();
+    MAP.get_or_init(DashMap::new)
+}
+
+fn build_directory_search_regex(
+    keyword: &str,
+    mode: &str,
+    case_sensitive: bool,
+) -> Result<Regex, String> {
+    if keyword.is_empty() {
+        return Err("Search keyword must not be empty".to_string());
+    }
+
+    let source = match mode {
+        "literal" => escape_regex_literal(keyword),
+        "wildcard" => wildcard_to_regex_source(keyword),
+        "regex" => keyword.to_string(),
+        _ => return Err("Unsupported search mode".to_string()),
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+pub(super) struct DirectoryTextFile {
+    pub(super) text: String,
+    pub(super) encoding: &'static Encoding,
+    pub(super) has_bom: bool,
+}
+
+// Decodes a file the way it would be opened, or returns None when it is too
+// large, binary or unreadable.
+pub(super) fn read_directory_text_file(path: &Path) -> Option<DirectoryTextFile> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if metadata.len() > DIRECTORY_SEARCH_MAX_FILE_BYTES {
+        return None;
+    }
+    let bytes = std::fs::read(path).ok()?;
+
+    let bom_encoding = Encoding::for_bom(&bytes).map(|(encoding, _)| encoding);
+    let encoding = match file_sniff::sniff_content(&bytes) {
+        SniffedContent::Binary(_) => return None,
+        SniffedContent::Utf16(encoding) => encoding,
+        SniffedContent::Text => match bom_encoding {
+            Some(encoding) => encoding,
+            None => {
+                let mut detector = chardetng::EncodingDetector::new();
+                detector.feed(
+                    &bytes[..bytes.len().min(ENCODING_DETECT_SAMPLE_BYTES)],
+                    true,
+                );
+                detector.guess(None, true)
+            }
+        },
+    };
+    let (text, _) = encoding.decode_with_bom_removal(&bytes);
+    Some(DirectoryTextFile {
+        text: text.into_owned(),
+        encoding,
+        has_bom: bom_encoding.is_some(),
+    })
+}
+
+// Keeps long lines readable by cutting a window that starts a little before
+// the match; returns the window text and the char offset it starts at.
+pub(super) fn clip_preview_window(
+    chars: &[char],
+    start_char: usize,
+    end_char: usize,
+) -> (String, usize) {
+    if chars.len() <= DIRECTORY_SEARCH_PREVIEW_MAX_CHARS {
+        return (chars.iter().collect(), 0);
+    }
+
+    let lead = DIRECTORY_SEARCH_PREVIEW_MAX_CHARS / 4;
+    let window_start = start_char
+        .saturating_sub(lead)
+        .min(chars.len() - DIRECTORY_SEARCH_PREVIEW_MAX_CHARS);
+    let window_end =
+        (window_start + DIRECTORY_SEARCH_PREVIEW_MAX_CHARS).max(end_char.min(chars.len()));
+    (
+        chars[window_start..window_end].iter().collect(),
+        window_start,
+    )
+}
+
+fn collect_directory_matches_in_text(
+    file_path: &str,
+    text: &str,
+    regex: &Regex,
+    capacity: usize,
+) -> Vec<DirectorySearchMatch> {
+    let mut matches = Vec::new();
+
+    for (line_index, raw_line) in text.split('\n').enumerate() {
+        if matches.len() >= capacity {
+            break;
+        }
+        let line_text = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+        let mut line_chars: Option<Vec<char>> = None;
+
+        for found in regex
+            .find_iter(line_text)
+            .filter(|found| found.end() > found.start())
+            .take(DIRECTORY_SEARCH_MAX_MATCHES_PER_LINE)
+        {
+            if matches.len() >= capacity {
+                break;
+            }
+            let chars = line_chars.get_or_insert_with(|| line_text.chars().collect());
+            let start_char = line_text[..found.start()].chars().count();
+            let end_char = start_char + found.as_str().chars().count();
+            let (preview, window_start) = clip_preview_window(chars, start_char, end_char);
+            let preview_segments = build_preview_segments_by_char(
+                &preview,
+                &[(start_char - window_start, end_char - window_start)],
+                &[],
+                &[],
+            );
+
+            matches.push(DirectorySearchMatch {
+                file_path: file_path.to_string(),
+                line: line_index + 1,
+                column: start_char + 1,
+                end_column: end_char + 1,
+                line_text: preview,
+                preview_segments,
+            });
+        }
+    }
+
+    matches
+}
+
+fn emit_directory_search_event<P: serde::Serialize + Clone>(
+    app: &AppHandle,
+    event: &str,
+    payload: P,
+) {
+    if let Err(error) = app.emit(event, payload) {
+        eprintln!("failed to emit {event} event: {error}");
+    }
+}
+
+fn run_directory_search(
+    app: &AppHandle,
+    search_id: &str,
+    root: &Path,
+    regex: &Regex,
+    max_results: usize,
+    cancelled: &AtomicBool,
+) {
+    let files: Vec<PathBuf> = folder_walk::collect_folder_files(root, &[], true);
+    let total_files = files.len();
+    let mut pending: Vec<DirectorySearchMatch> = Vec::new();
+    let mut scanned_files = 0usize;
+    let mut skipped_files = 0usize;
+    let mut match_count = 0usize;
+
+    for path in &files {
+        if cancelled.load(Ordering::Relaxed) || match_count >= max_results {
+            break;
+        }
+        scanned_files += 1;
+
+        let Some(file) = read_directory_text_file(path) else {
+            skipped_files += 1;
+            continue;
+        };
+        let file_matches = collect_directory_matches_in_text(
+            &path.to_string_lossy(),
+            &file.text,
+            regex,
+            max_results - match_count,
+        );
+        match_count += file_matches.len();
+        pending.extend(file_matches);
+
+        if pending.len() >= DIRECTORY_SEARCH_CHUNK_MATCHES {
+            emit_directory_search_event(
+                app,
+                "rutar://directory-search-chunk",
+                DirectorySearchChunkEventPayload {
+                    search_id: search_id.to_string(),
+                    matches: std::mem::take(&mut pending),
+                    scanned_files,
+                    total_files,
+                },
+            );
+        }
+    }
+
+    let was_cancelled = cancelled.load(Ordering::Relaxed);
+    if !pending.is_empty() && !was_cancelled {
+        emit_directory_search_event(
+            app,
+            "rutar://directory-search-chunk",
+            DirectorySearchChunkEventPayload {
+                search_id: search_id.to_string(),
+                matches: pending,
+                scanned_files,
+                total_files,
+            },
+        );
+    }
+    emit_directory_search_event(
+        app,
+        "rutar://directory-search-finished",
+        DirectorySearchFinishedEventPayload {
+            search_id: search_id.to_string(),
+            scanned_files,
+            total_files,
+            skipped_files,
+            match_count,
+            cancelled: was_cancelled,
+            truncated: !was_cancelled && match_count >= max_results,
+        },
+    );
+}
+
+pub(crate) fn search_in_directory_start_impl(
+    app: AppHandle,
+    root: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    max_results: Option<usize>,
+) -> Result<DirectorySearchStartPayload, String> {
+    let root = PathBuf::from(root.trim());
+    if !root.is_dir() {
+        return Err("Folder not found".to_string());
+    }
+    let regex = build_directory_search_regex(&keyword, &mode, case_sensitive)?;
+    let max_results = max_results
+        .unwrap_or(DIRECTORY_SEARCH_DEFAULT_MAX_RESULTS)
+        .max(1);
+
+    let search_id = Uuid::new_v4().to_string();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    active_directory_searches().insert(search_id.clone(), cancelled.clone());
+
+    let thread_search_id = search_id.clone();
+    std::thread::Builder::new()
+        .name("rutar-directory-search".to_string())
+        .spawn(move || {
+            run_directory_search(
+                &app,
+                &thread_search_id,
+                &root,
+                &regex,
+                max_results,
+                &cancelled,
+            );
+            active_directory_searches().remove(&thread_search_id);
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(DirectorySearchStartPayload { search_id })
+}
+
+// The worker stops before its next file and still sends the finished event.
+pub(crate) fn cancel_directory_search_impl(search_id: String) -> bool {
+    match active_directory_searches().get(&search_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collect_directory_matches_in_text_should_report_lines_columns_and_segments() {
+        let regex = build_directory_search_regex("b*d", "wildcard", false).expect("regex");
+        let matches =
+            collect_directory_matches_in_text("a.txt", "first\r\n中abcD xyz\nnone", &regex, 10);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, 2);
+        assert_eq!(matches[0].column, 3);
+        assert_eq!(matches[0].end_column, 6);
+        assert_eq!(matches[0].line_text, "中abcD xyz");
+        let primary: Vec<&str> = matches[0]
+            .preview_segments
+            .iter()
+            .filter(|segment| segment.is_primary_match)
+            .map(|segment| segment.text.as_str())
+            .collect();
+        assert_eq!(primary, vec!["bcD"]);
+    }
+
+    #[test]
+    fn collect_directory_matches_in_text_should_respect_capacity() {
+        let regex = build_directory_search_regex("x", "literal", true).expect("regex");
+        assert_eq!(
+            collect_directory_matches_in_text("a.txt", "xx\nx", &regex, 2).len(),
+            2
+        );
+        assert!(build_directory_search_regex("x", "fuzzy", true).is_err());
+    }
+
+    #[test]
+    fn clip_preview_window_should_keep_match_inside_long_lines() {
+        let line: Vec<char> = "a".repeat(1000).chars().collect();
+        let (preview, window_start) = clip_preview_window(&line, 500, 505);
+
+        assert_eq!(preview.chars().count(), DIRECTORY_SEARCH_PREVIEW_MAX_CHARS);
+        assert!(window_start <= 500 && window_start + DIRECTORY_SEARCH_PREVIEW_MAX_CHARS >= 505);
+    }
+
+    #[test]
+    fn cancel_directory_search_should_report_unknown_ids() {
+        assert!(!cancel_directory_search_impl("missing-search".to_string()));
+    }
+}
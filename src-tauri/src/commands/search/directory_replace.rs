@@ -0,0 +1,545 @@
+// Replace across unopened files in a folder, in two steps. The preview lists
+// the files that would change, with before/after snippets. The apply step
+// rewrites the files the user confirmed. Each file goes through the same
+// matching and replace-all logic as an open document: its text is normalized
+// to LF, replaced, and saved back with its dominant line ending and encoding.
+// Writes land in a temporary sibling that is renamed over the original, so a
+// failure never leaves a half-written file.
+
+use ropey::Rope;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::super::{file_io, folder_walk, text_utils};
+use super::directory::{clip_preview_window, read_directory_text_file, DirectoryTextFile};
+use super::{
+    build_match_replacement, collect_search_matches_by_mode, compile_replace_regex,
+    replace_matches_with_texts, resolve_replace_value, SearchMatchResult,
+};
+use crate::state::{AppState, LineEnding};
+use tauri::State;
+
+const DIRECTORY_REPLACE_PREVIEW_LINES_PER_FILE: usize = 20;
+const DIRECTORY_REPLACE_BACKUP_SUFFIX: &str = ".bak";
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryReplaceLinePreview {
+    pub(super) line: usize,
+    pub(super) before: String,
+    pub(super) after: String,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryReplaceFilePreview {
+    pub(super) file_path: String,
+    pub(super) match_count: usize,
+    pub(super) lines: Vec<DirectoryReplaceLinePreview>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryReplacePreviewPayload {
+    pub(super) files: Vec<DirectoryReplaceFilePreview>,
+    pub(super) total_matches: usize,
+    pub(super) scanned_files: usize,
+    pub(super) skipped_files: usize,
+    // "path: error" for files that could not be searched.
+    pub(super) errors: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryReplaceFileResult {
+    pub(super) file_path: String,
+    // "replaced", "unchanged", "skipped" or "failed".
+    pub(super) status: String,
+    pub(super) replaced_count: usize,
+    pub(super) backup_path: Option<String>,
+    pub(super) error: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryReplaceApplyPayload {
+    pub(super) files: Vec<DirectoryReplaceFileResult>,
+    pub(super) replaced_files: usize,
+    pub(super) total_replaced: usize,
+    pub(super) failed_files: usize,
+}
+
+struct DirectoryReplaceQuery {
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    regex: Option<regex::Regex>,
+}
+
+struct PlannedFileReplacement {
+    matches: Vec<SearchMatchResult>,
+    // Replacement text of each match, with regex captures expanded.
+    replacements: Vec<String>,
+    next_text: String,
+    line_ending: LineEnding,
+}
+
+fn plan_file_replacement(
+    file: &DirectoryTextFile,
+    query: &DirectoryReplaceQuery,
+) -> Result<Option<PlannedFileReplacement>, String> {
+    let source_text = text_utils::normalize_to_lf(&file.text);
+    let matches = collect_search_matches_by_mode(
+        &source_text,
+        &query.keyword,
+        &query.mode,
+        query.case_sensitive,
+    )?;
+    if matches.is_empty() {
+        return Ok(None);
+    }
+
+    let replacements: Vec<String> = matches
+        .iter()
+        .map(|item| {
+            build_match_replacement(
+                query.regex.as_ref(),
+                &source_text,
+                item,
+                &query.replace_value,
+            )
+        })
+        .collect();
+    let next_text = replace_matches_with_texts(
+        &source_text,
+        matches.iter().zip(replacements.iter().map(String::as_str)),
+    );
+    if next_text == source_text {
+        return Ok(None);
+    }
+
+    let line_ending = file_io::detect_line_ending(file_io::count_line_endings(&file.text));
+    Ok(Some(PlannedFileReplacement {
+        matches,
+        replacements,
+        next_text,
+        line_ending,
+    }))
+}
+
+// Replaces the matches of each affected line on its own, so snippets stay
+// one line even when the replacement adds line breaks.
+fn build_line_previews(
+    matches: &[SearchMatchResult],
+    replacements: &[String],
+) -> Vec<DirectoryReplaceLinePreview> {
+    let mut previews = Vec::new();
+    let mut index = 0;
+
+    while index < matches.len() && previews.len() < DIRECTORY_REPLACE_PREVIEW_LINES_PER_FILE {
+        let line = matches[index].line;
+        let line_text = matches[index].line_text.as_str();
+        let line_chars: Vec<char> = line_text.chars().collect();
+        let first_replacement = replacements[index].as_str();
+        let mut local_matches = Vec::new();
+
+        while index < matches.len() && matches[index].line == line {
+            let mut local = matches[index].clone();
+            let length = local.end_char.saturating_sub(local.start_char);
+            local.start_char = local.column.saturating_sub(1).min(line_chars.len());
+            local.end_char = (local.start_char + length).min(line_chars.len());
+            local_matches.push((local, replacements[index].as_str()));
+            index += 1;
+        }
+
+        let first_start = local_matches[0].0.start_char;
+        let first_end = local_matches[0].0.end_char;
+        let after_text = replace_matches_with_texts(
+            line_text,
+            local_matches.iter().map(|(local, text)| (local, *text)),
+        );
+        let after_chars: Vec<char> = after_text.chars().collect();
+        let replacement_end = first_start + first_replacement.chars().count();
+
+        previews.push(DirectoryReplaceLinePreview {
+            line,
+            before: clip_preview_window(&line_chars, first_start, first_end).0,
+            after: clip_preview_window(&after_chars, first_start, replacement_end).0,
+        });
+    }
+
+    previews
+}
+
+fn encode_replaced_text(
+    file: &DirectoryTextFile,
+    text: &str,
+    line_ending: LineEnding,
+) -> Result<Vec<u8>, String> {
+    let persist_text = file_io::build_persist_text(&Rope::from_str(text), line_ending);
+    let (encoded, _, unmappable) = file.encoding.encode(&persist_text);
+    if unmappable {
+        return Err(format!(
+            "Replacement is not representable in {}",
+            file.encoding.name()
+        ));
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len() + 3);
+    if file.has_bom {
+        bytes.extend_from_slice(b"\xEF\xBB\xBF");
+    }
+    bytes.extend_from_slice(&encoded);
+    Ok(bytes)
+}
+
+fn build_replace_query(
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: bool,
+) -> Result<DirectoryReplaceQuery, String> {
+    if keyword.is_empty() {
+        return Err("Search keyword must not be empty".to_string());
+    }
+    // Validates the mode and pattern before any file is read.
+    collect_search_matches_by_mode("", &keyword, &mode, case_sensitive)?;
+
+    let regex = compile_replace_regex(&keyword, &mode, case_sensitive)?;
+
+    Ok(DirectoryReplaceQuery {
+        keyword,
+        mode,
+        case_sensitive,
+        replace_value: resolve_replace_value(&replace_value, parse_escape_sequences),
+        regex,
+    })
+}
+
+fn resolve_replace_root(root: &str) -> Result<PathBuf, String> {
+    let root = PathBuf::from(root.trim());
+    if !root.is_dir() {
+        return Err("Folder not found".to_string());
+    }
+    fs::canonicalize(&root).map_err(|e| e.to_string())
+}
+
+pub(crate) async fn preview_replace_in_directory_impl(
+    root: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: bool,
+) -> Result<DirectoryReplacePreviewPayload, String> {
+    let root = resolve_replace_root(&root)?;
+    let query = build_replace_query(
+        keyword,
+        mode,
+        case_sensitive,
+        replace_value,
+        parse_escape_sequences,
+    )?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let files = folder_walk::collect_folder_files(&root, &[], true);
+        let mut previews = Vec::new();
+        let mut total_matches = 0usize;
+        let mut skipped_files = 0usize;
+        let mut errors = Vec::new();
+
+        for path in &files {
+            let Some(file) = read_directory_text_file(path).filter(|file| !is_utf16(file)) else {
+                skipped_files += 1;
+                continue;
+            };
+            let planned = match plan_file_replacement(&file, &query) {
+                Ok(Some(planned)) => planned,
+                Ok(None) => continue,
+                Err(error) => {
+                    errors.push(format!("{}: {}", path.to_string_lossy(), error));
+                    continue;
+                }
+            };
+
+            total_matches += planned.matches.len();
+            previews.push(DirectoryReplaceFilePreview {
+                file_path: path.to_string_lossy().to_string(),
+                match_count: planned.matches.len(),
+                lines: build_line_previews(&planned.matches, &planned.replacements),
+            });
+        }
+
+        DirectoryReplacePreviewPayload {
+            files: previews,
+            total_matches,
+            scanned_files: files.len(),
+            skipped_files,
+            errors,
+        }
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+// encoding_rs cannot encode UTF-16, so such files are left alone.
+fn is_utf16(file: &DirectoryTextFile) -> bool {
+    file.encoding.output_encoding() != file.encoding
+}
+
+fn apply_file_replacement(
+    path: &Path,
+    query: &DirectoryReplaceQuery,
+    create_backup: bool,
+) -> DirectoryReplaceFileResult {
+    let mut result = DirectoryReplaceFileResult {
+        file_path: path.to_string_lossy().to_string(),
+        status: "skipped".to_string(),
+        replaced_count: 0,
+        backup_path: None,
+        error: None,
+    };
+
+    let Some(file) = read_directory_text_file(path) else {
+        result.error = Some("File is binary, too large or unreadable".to_string());
+        return result;
+    };
+    if is_utf16(&file) {
+        result.error = Some(format!("{} is not supported", file.encoding.name()));
+        return result;
+    }
+
+    let planned = match plan_file_replacement(&file, query) {
+        Ok(Some(planned)) => planned,
+        Ok(None) => {
+            result.status = "unchanged".to_string();
+            return result;
+        }
+        Err(error) => {
+            result.status = "failed".to_string();
+            result.error = Some(error);
+            return result;
+        }
+    };
+
+    let written =
+        encode_replaced_text(&file, &planned.next_text, planned.line_ending).and_then(|bytes| {
+            file_io::write_file_atomically(
+                path,
+                &bytes,
+                create_backup.then_some(DIRECTORY_REPLACE_BACKUP_SUFFIX),
+            )
+        });
+    match written {
+        Ok(backup_path) => {
+            result.status = "replaced".to_string();
+            result.replaced_count = planned.matches.len();
+            result.backup_path = backup_path.map(|path| path.to_string_lossy().to_string());
+        }
+        Err(error) => {
+            result.status = "failed".to_string();
+            result.error = Some(error);
+        }
+    }
+    result
+}
+
+// Only the confirmed `file_paths` are touched. Files open in the editor are
+// skipped so the replacement cannot be overwritten by a later save.
+pub(crate) async fn replace_in_directory_impl(
+    state: State<'_, AppState>,
+    root: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: bool,
+    file_paths: Vec<String>,
+    create_backup: bool,
+) -> Result<DirectoryReplaceApplyPayload, String> {
+    let root = resolve_replace_root(&root)?;
+    let query = build_replace_query(
+        keyword,
+        mode,
+        case_sensitive,
+        replace_value,
+        parse_escape_sequences,
+    )?;
+    let open_paths: HashSet<PathBuf> = state
+        .documents
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .path
+                .as_ref()
+                .and_then(|path| fs::canonicalize(path).ok())
+        })
+        .collect();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut results = Vec::with_capacity(file_paths.len());
+
+        for file_path in file_paths {
+            let path = PathBuf::from(&file_path);
+            let canonical = fs::canonicalize(&path).ok();
+            let skip_reason = match canonical.as_ref() {
+                None => Some("File not found"),
+                Some(canonical) if !canonical.starts_with(&root) => {
+                    Some("File is outside the folder")
+                }
+                Some(canonical) if open_paths.contains(canonical) => {
+                    Some("File is open in the editor")
+                }
+                Some(_) => None,
+            };
+
+            let result = match skip_reason {
+                Some(reason) => DirectoryReplaceFileResult {
+                    file_path,
+                    status: "skipped".to_string(),
+                    replaced_count: 0,
+                    backup_path: None,
+                    error: Some(reason.to_string()),
+                },
+                None => apply_file_replacement(&path, &query, create_backup),
+            };
+            results.push(result);
+        }
+
+        let count = |status: &str| results.iter().filter(|item| item.status == status).count();
+        DirectoryReplaceApplyPayload {
+            replaced_files: count("replaced"),
+            total_replaced: results.iter().map(|item| item.replaced_count).sum(),
+            failed_files: count("failed"),
+            files: results,
+        }
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn make_file(text: &str) -> DirectoryTextFile {
+        DirectoryTextFile {
+            text: text.to_string(),
+            encoding: encoding_rs::UTF_8,
+            has_bom: false,
+        }
+    }
+
+    fn make_query(keyword: &str, mode: &str, replace_value: &str) -> DirectoryReplaceQuery {
+        build_replace_query(
+            keyword.to_string(),
+            mode.to_string(),
+            true,
+            replace_value.to_string(),
+            false,
+        )
+        .expect("query")
+    }
+
+    #[test]
+    fn plan_file_replacement_should_keep_crlf_and_report_line_previews() {
+        let file = make_file("foo bar\r\nbaz\r\nfoo foo\r\n");
+        let query = make_query("foo", "literal", "qux");
+        let planned = plan_file_replacement(&file, &query)
+            .expect("plan")
+            .expect("changes");
+
+        assert_eq!(planned.matches.len(), 3);
+        let bytes =
+            encode_replaced_text(&file, &planned.next_text, planned.line_ending).expect("encode");
+        assert_eq!(bytes, b"qux bar\r\nbaz\r\nqux qux\r\n".to_vec());
+
+        assert_eq!(
+            build_line_previews(&planned.matches, &planned.replacements),
+            vec![
+                DirectoryReplaceLinePreview {
+                    line: 1,
+                    before: "foo bar".to_string(),
+                    after: "qux bar".to_string(),
+                },
+                DirectoryReplaceLinePreview {
+                    line: 3,
+                    before: "foo foo".to_string(),
+                    after: "qux qux".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_file_replacement_should_expand_captures_in_text_and_previews() {
+        let file = make_file("key=value\nname=rutar x\n");
+        let query = make_query(r"(\w+)=(\w+)", "regex", "$2:$1");
+        let planned = plan_file_replacement(&file, &query)
+            .expect("plan")
+            .expect("changes");
+
+        assert_eq!(planned.next_text, "value:key\nrutar:name x\n");
+        assert_eq!(
+            build_line_previews(&planned.matches, &planned.replacements),
+            vec![
+                DirectoryReplaceLinePreview {
+                    line: 1,
+                    before: "key=value".to_string(),
+                    after: "value:key".to_string(),
+                },
+                DirectoryReplaceLinePreview {
+                    line: 2,
+                    before: "name=rutar x".to_string(),
+                    after: "rutar:name x".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_file_replacement_should_skip_files_without_changes() {
+        let file = make_file("same same");
+        assert!(
+            plan_file_replacement(&file, &make_query("same", "literal", "same"))
+                .expect("plan")
+                .is_none()
+        );
+        assert!(
+            plan_file_replacement(&file, &make_query("none", "literal", "x"))
+                .expect("plan")
+                .is_none()
+        );
+        assert!(build_replace_query(
+            "(".to_string(),
+            "regex".to_string(),
+            true,
+            String::new(),
+            false
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn write_file_atomically_should_replace_content_and_keep_backup() {
+        let root = std::env::temp_dir().join(format!("rutar-directory-replace-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create dir");
+        let path = root.join("a.txt");
+        fs::write(&path, "old").expect("write file");
+
+        let backup =
+            file_io::write_file_atomically(&path, b"new", Some(DIRECTORY_REPLACE_BACKUP_SUFFIX))
+                .expect("write")
+                .expect("backup");
+
+        assert_eq!(fs::read_to_string(&path).expect("read"), "new");
+        assert_eq!(fs::read_to_string(&backup).expect("read backup"), "old");
+        assert_eq!(fs::read_dir(&root).expect("list").count(), 2);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
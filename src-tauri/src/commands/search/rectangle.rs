@@ -0,0 +1,356 @@
+// Search and replace restricted to a rectangular (column) region.
+//
+// Lines are 1-based and inclusive; columns are 1-based UTF-16 columns with an
+// exclusive end, matching the rectangular selection commands in editing.rs.
+
+use regex::{Regex, RegexBuilder};
+use ropey::Rope;
+
+use super::super::editing::{trimmed_line_without_break, utf16_column_to_char_offset};
+use super::{
+    clear_outdated_document_search_caches, escape_regex_literal, resolve_replace_value,
+    wildcard_to_regex_source,
+};
+use crate::state::AppState;
+use tauri::State;
+
+#[derive(serde::Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct RectangleRegion {
+    pub(super) start_line: usize,
+    pub(super) end_line: usize,
+    pub(super) start_column: usize,
+    pub(super) end_column: usize,
+}
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RectangleSearchMatch {
+    pub(super) line: usize,
+    pub(super) start_column: usize,
+    pub(super) end_column: usize,
+    pub(super) start_char: usize,
+    pub(super) end_char: usize,
+    pub(super) text: String,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RectangleSearchResultPayload {
+    pub(super) matches: Vec<RectangleSearchMatch>,
+    pub(super) document_version: u64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RectangleReplaceResultPayload {
+    pub(super) replaced_count: usize,
+    pub(super) line_count: usize,
+    pub(super) document_version: u64,
+}
+
+struct RectangleLineSegment {
+    line_index: usize,
+    line_start_char: usize,
+    line_text: String,
+    // Byte range of the segment inside `line_text`.
+    start_byte: usize,
+    end_byte: usize,
+}
+
+fn build_rectangle_regex(keyword: &str, mode: &str, case_sensitive: bool) -> Result<Regex, String> {
+    let source = match mode {
+        "literal" => escape_regex_literal(keyword),
+        "wildcard" => wildcard_to_regex_source(keyword),
+        "regex" => keyword.to_string(),
+        _ => return Err("Unsupported search mode".to_string()),
+    };
+
+    RegexBuilder::new(&source)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+fn char_to_byte_offset(text: &str, char_offset: usize) -> usize {
+    text.char_indices()
+        .nth(char_offset)
+        .map(|(byte_offset, _)| byte_offset)
+        .unwrap_or(text.len())
+}
+
+fn collect_rectangle_segments(rope: &Rope, region: RectangleRegion) -> Vec<RectangleLineSegment> {
+    let line_count = rope.len_lines().max(1);
+    let start_line = region.start_line.min(region.end_line).max(1);
+    let end_line = region
+        .start_line
+        .max(region.end_line)
+        .max(1)
+        .min(line_count);
+    let start_column = region.start_column.min(region.end_column).max(1);
+    let end_column = region.start_column.max(region.end_column).max(1);
+
+    (start_line..=end_line)
+        .map(|line_number| {
+            let line_index = line_number - 1;
+            let line_text = trimmed_line_without_break(rope, line_index);
+            let start_char = utf16_column_to_char_offset(&line_text, start_column);
+            let end_char = utf16_column_to_char_offset(&line_text, end_column);
+
+            RectangleLineSegment {
+                line_index,
+                line_start_char: rope.line_to_char(line_index),
+                start_byte: char_to_byte_offset(&line_text, start_char),
+                end_byte: char_to_byte_offset(&line_text, end_char),
+                line_text,
+            }
+        })
+        .collect()
+}
+
+fn find_rectangle_matches(
+    rope: &Rope,
+    region: RectangleRegion,
+    regex: &Regex,
+) -> Vec<RectangleSearchMatch> {
+    let mut matches = Vec::new();
+
+    for segment in collect_rectangle_segments(rope, region) {
+        let segment_text = &segment.line_text[segment.start_byte..segment.end_byte];
+        for found in regex.find_iter(segment_text) {
+            if found.start() == found.end() {
+                continue;
+            }
+
+            let match_start_byte = segment.start_byte + found.start();
+            let prefix = &segment.line_text[..match_start_byte];
+            let start_column = prefix.encode_utf16().count() + 1;
+            let start_char = segment.line_start_char + prefix.chars().count();
+
+            matches.push(RectangleSearchMatch {
+                line: segment.line_index + 1,
+                start_column,
+                end_column: start_column + found.as_str().encode_utf16().count(),
+                start_char,
+                end_char: start_char + found.as_str().chars().count(),
+                text: found.as_str().to_string(),
+            });
+        }
+    }
+
+    matches
+}
+
+// Replaces matches inside each line's segment and returns the rewritten text of
+// the covered lines (line breaks included) plus the number of replacements.
+fn replace_rectangle_segments(
+    rope: &Rope,
+    region: RectangleRegion,
+    regex: &Regex,
+    replacement: &str,
+    expand_captures: bool,
+) -> (usize, usize, String, usize) {
+    let segments = collect_rectangle_segments(rope, region);
+    let Some(first) = segments.first() else {
+        return (0, 0, String::new(), 0);
+    };
+    let region_start_char = first.line_start_char;
+    let region_end_char = segments
+        .last()
+        .map(|segment| {
+            let next_line = segment.line_index + 1;
+            if next_line < rope.len_lines() {
+                rope.line_to_char(next_line)
+            } else {
+                rope.len_chars()
+            }
+        })
+        .unwrap_or(region_start_char);
+
+    let mut next_text = String::new();
+    let mut replaced_count = 0usize;
+
+    for segment in &segments {
+        let line = rope.line(segment.line_index).to_string();
+        let line_break = &line[segment.line_text.len()..];
+        let segment_text = &segment.line_text[segment.start_byte..segment.end_byte];
+
+        next_text.push_str(&segment.line_text[..segment.start_byte]);
+        let mut last_end = 0usize;
+        for captures in regex.captures_iter(segment_text) {
+            let found = captures.get(0).expect("capture group 0 always exists");
+            if found.start() == found.end() {
+                continue;
+            }
+
+            next_text.push_str(&segment_text[last_end..found.start()]);
+            if expand_captures {
+                captures.expand(replacement, &mut next_text);
+            } else {
+                next_text.push_str(replacement);
+            }
+            last_end = found.end();
+            replaced_count += 1;
+        }
+        next_text.push_str(&segment_text[last_end..]);
+        next_text.push_str(&segment.line_text[segment.end_byte..]);
+        next_text.push_str(line_break);
+    }
+
+    (
+        region_start_char,
+        region_end_char,
+        next_text,
+        replaced_count,
+    )
+}
+
+pub(crate) fn search_in_rectangle_impl(
+    state: State<'_, AppState>,
+    id: String,
+    region: RectangleRegion,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+) -> Result<RectangleSearchResultPayload, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    if keyword.is_empty() {
+        return Ok(RectangleSearchResultPayload {
+            matches: Vec::new(),
+            document_version: doc.document_version,
+        });
+    }
+
+    let regex = build_rectangle_regex(&keyword, &mode, case_sensitive)?;
+    Ok(RectangleSearchResultPayload {
+        matches: find_rectangle_matches(&doc.rope, region, &regex),
+        document_version: doc.document_version,
+    })
+}
+
+pub(crate) fn replace_in_rectangle_impl(
+    state: State<'_, AppState>,
+    id: String,
+    region: RectangleRegion,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+) -> Result<RectangleReplaceResultPayload, String> {
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    if keyword.is_empty() {
+        return Ok(RectangleReplaceResultPayload {
+            replaced_count: 0,
+            line_count: doc.rope.len_lines(),
+            document_version: doc.document_version,
+        });
+    }
+
+    let regex = build_rectangle_regex(&keyword, &mode, case_sensitive)?;
+    let replacement =
+        resolve_replace_value(&replace_value, parse_escape_sequences.unwrap_or(false));
+    let (start_char, end_char, next_text, replaced_count) =
+        replace_rectangle_segments(&doc.rope, region, &regex, &replacement, mode == "regex");
+
+    let previous_text = doc.rope.slice(start_char..end_char).to_string();
+    let mut engine = doc.engine();
+    if replaced_count > 0 && previous_text != next_text {
+        // One operation for the whole block keeps the replacement a single undo step.
+        engine.replace(start_char, previous_text, next_text)?;
+    }
+
+    let change = engine.finish();
+    drop(doc);
+    if change.changed {
+        clear_outdated_document_search_caches(&id, change.document_version);
+    }
+
+    Ok(RectangleReplaceResultPayload {
+        replaced_count,
+        line_count: change.line_count,
+        document_version: change.document_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(
+        start_line: usize,
+        end_line: usize,
+        start_column: usize,
+        end_column: usize,
+    ) -> RectangleRegion {
+        RectangleRegion {
+            start_line,
+            end_line,
+            start_column,
+            end_column,
+        }
+    }
+
+    #[test]
+    fn find_rectangle_matches_should_only_report_matches_inside_columns() {
+        let rope = Rope::from_str("ERR 10 ERR\n😀ERR ok\nshort\n");
+        let regex = build_rectangle_regex("err", "literal", false).expect("regex");
+
+        let matches = find_rectangle_matches(&rope, region(1, 3, 1, 6), &regex);
+
+        assert_eq!(
+            matches,
+            vec![
+                RectangleSearchMatch {
+                    line: 1,
+                    start_column: 1,
+                    end_column: 4,
+                    start_char: 0,
+                    end_char: 3,
+                    text: "ERR".to_string(),
+                },
+                RectangleSearchMatch {
+                    line: 2,
+                    start_column: 3,
+                    end_column: 6,
+                    start_char: 12,
+                    end_char: 15,
+                    text: "ERR".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn replace_rectangle_segments_should_rewrite_only_the_column_block() {
+        let rope = Rope::from_str("a-1 a-1\r\nb-2 b-2\r\nc-3 c-3");
+        let regex = build_rectangle_regex(r"(\w)-(\d)", "regex", true).expect("regex");
+
+        let (start_char, end_char, next_text, replaced_count) =
+            replace_rectangle_segments(&rope, region(1, 2, 1, 4), &regex, "$2$1", true);
+
+        assert_eq!((start_char, end_char), (0, 18));
+        assert_eq!(next_text, "1a a-1\r\n2b b-2\r\n");
+        assert_eq!(replaced_count, 2);
+    }
+
+    #[test]
+    fn replace_rectangle_segments_should_not_expand_captures_for_literal_mode() {
+        let rope = Rope::from_str("x.y x.y");
+        let regex = build_rectangle_regex("x.y", "literal", true).expect("regex");
+
+        let (_, _, next_text, replaced_count) =
+            replace_rectangle_segments(&rope, region(1, 1, 5, 8), &regex, "$0", false);
+
+        assert_eq!(next_text, "x.y $0");
+        assert_eq!(replaced_count, 1);
+    }
+}
@@ -3,6 +3,7 @@
 // depending on a specific value type.
 
 use dashmap::DashMap;
+use std::time::{Duration, Instant};
 
 // Upper bound for any single session DashMap; older entries are pruned on insert.
 pub(crate) const MAX_SESSION_CACHE_ENTRIES: usize = 200;
@@ -31,3 +32,101 @@ where
         cache.remove(&key);
     }
 }
+
+// Implemented by session cache entries so idle maintenance can age them out
+// and report roughly how much memory was released.
+pub(crate) trait SessionCacheEntry {
+    fn touched_at(&self) -> Instant;
+    fn approximate_bytes(&self) -> usize;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct CacheEvictionStats {
+    pub(crate) removed_entries: usize,
+    pub(crate) reclaimed_bytes: usize,
+}
+
+impl std::ops::AddAssign for CacheEvictionStats {
+    fn add_assign(&mut self, other: Self) {
+        self.removed_entries += other.removed_entries;
+        self.reclaimed_bytes += other.reclaimed_bytes;
+    }
+}
+
+// Drops entries that have not been touched for at least `max_age`.
+pub(crate) fn evict_entries_older_than<K, V, S>(
+    cache: &DashMap<K, V, S>,
+    max_age: Duration,
+) -> CacheEvictionStats
+where
+    K: Eq + std::hash::Hash + Clone,
+    V: SessionCacheEntry,
+    S: std::hash::BuildHasher + Clone,
+{
+    let stale_keys: Vec<K> = cache
+        .iter()
+        .filter(|entry| entry.value().touched_at().elapsed() >= max_age)
+        .map(|entry| entry.key().clone())
+        .collect();
+
+    let mut stats = CacheEvictionStats::default();
+    for key in stale_keys {
+        if let Some((_, value)) = cache.remove(&key) {
+            stats.removed_entries += 1;
+            stats.reclaimed_bytes += value.approximate_bytes();
+        }
+    }
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestEntry {
+        touched_at: Instant,
+        bytes: usize,
+    }
+
+    impl SessionCacheEntry for TestEntry {
+        fn touched_at(&self) -> Instant {
+            self.touched_at
+        }
+
+        fn approximate_bytes(&self) -> usize {
+            self.bytes
+        }
+    }
+
+    #[test]
+    fn evict_entries_older_than_should_only_drop_stale_entries() {
+        let cache: DashMap<String, TestEntry> = DashMap::new();
+        let now = Instant::now();
+        cache.insert(
+            "fresh".to_string(),
+            TestEntry {
+                touched_at: now,
+                bytes: 10,
+            },
+        );
+        cache.insert(
+            "stale".to_string(),
+            TestEntry {
+                touched_at: now - Duration::from_secs(120),
+                bytes: 32,
+            },
+        );
+
+        let stats = evict_entries_older_than(&cache, Duration::from_secs(60));
+
+        assert_eq!(
+            stats,
+            CacheEvictionStats {
+                removed_entries: 1,
+                reclaimed_bytes: 32,
+            }
+        );
+        assert!(cache.contains_key("fresh"));
+    }
+}
@@ -1,27 +1,30 @@
 use super::*;
 
 #[tauri::command]
-pub fn search_first_in_document(
+pub async fn search_first_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     keyword: String,
     mode: String,
     case_sensitive: bool,
     reverse: bool,
+    _timer: CommandTimer,
 ) -> Result<SearchFirstResultPayload, String> {
-    search::search_first_in_document_impl(state, id, keyword, mode, case_sensitive, reverse)
+    search::search_first_in_document_impl(state, id, keyword, mode, case_sensitive, reverse).await
 }
 
 #[tauri::command]
-pub fn search_in_document_chunk(
+pub async fn search_in_document_chunk(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     keyword: String,
     mode: String,
     case_sensitive: bool,
     result_filter_keyword: Option<String>,
     start_offset: usize,
     max_results: usize,
+    include_line_text: Option<bool>,
+    _timer: CommandTimer,
 ) -> Result<SearchChunkResultPayload, String> {
     search::search_in_document_chunk_impl(
         state,
@@ -32,22 +35,28 @@ pub fn search_in_document_chunk(
         result_filter_keyword,
         start_offset,
         max_results,
+        include_line_text.unwrap_or(true),
     )
+    .await
 }
 
 #[tauri::command]
-pub fn search_session_start_in_document(
-    state: State<'_, AppState>,
-    id: String,
+pub async fn search_session_start_in_document(
+    app: AppHandle,
+    id: DocumentId,
     keyword: String,
     mode: String,
     case_sensitive: bool,
     result_filter_keyword: Option<String>,
     result_filter_case_sensitive: Option<bool>,
     max_results: usize,
+    include_line_text: Option<bool>,
+    session_id: Option<String>,
+    sort_order: Option<String>,
+    _timer: CommandTimer,
 ) -> Result<SearchSessionStartResultPayload, String> {
     search::search_session_start_in_document_impl(
-        state,
+        app,
         id,
         keyword,
         mode,
@@ -55,7 +64,16 @@ pub fn search_session_start_in_document(
         result_filter_keyword,
         result_filter_case_sensitive,
         max_results,
+        include_line_text.unwrap_or(true),
+        session_id,
+        sort_order,
     )
+    .await
+}
+
+#[tauri::command]
+pub fn cancel_search_session(session_id: String) -> bool {
+    search::cancel_search_session_impl(session_id)
 }
 
 #[tauri::command]
@@ -67,10 +85,29 @@ pub fn search_session_next_in_document(
     search::search_session_next_in_document_impl(state, session_id, max_results)
 }
 
+#[tauri::command]
+pub fn search_session_previous_in_document(
+    state: State<'_, AppState>,
+    session_id: String,
+    max_results: usize,
+) -> Result<SearchSessionPreviousResultPayload, String> {
+    search::search_session_previous_in_document_impl(state, session_id, max_results)
+}
+
+#[tauri::command]
+pub fn search_session_seek(
+    state: State<'_, AppState>,
+    session_id: String,
+    match_index: usize,
+    max_results: usize,
+) -> Result<SearchSessionSeekResultPayload, String> {
+    search::search_session_seek_in_document_impl(state, session_id, match_index, max_results)
+}
+
 #[tauri::command]
 pub fn search_session_restore_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     keyword: String,
     mode: String,
     case_sensitive: bool,
@@ -78,6 +115,8 @@ pub fn search_session_restore_in_document(
     result_filter_case_sensitive: Option<bool>,
     expected_document_version: Option<u64>,
     next_offset: Option<usize>,
+    include_line_text: Option<bool>,
+    sort_order: Option<String>,
 ) -> Result<SearchSessionRestoreResultPayload, String> {
     search::search_session_restore_in_document_impl(
         state,
@@ -89,22 +128,36 @@ pub fn search_session_restore_in_document(
         result_filter_case_sensitive,
         expected_document_version,
         next_offset,
+        include_line_text.unwrap_or(true),
+        sort_order,
     )
 }
 
+#[tauri::command]
+pub fn get_line_preview(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    line: usize,
+    max_chars: usize,
+    around_char: usize,
+) -> Result<LinePreviewResultPayload, String> {
+    search::get_line_preview_impl(state, id, line, max_chars, around_char)
+}
+
 #[tauri::command]
 pub fn dispose_search_session(session_id: String) -> bool {
     search::dispose_search_session_impl(session_id)
 }
 
 #[tauri::command]
-pub fn search_count_in_document(
+pub async fn search_count_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     keyword: String,
     mode: String,
     case_sensitive: bool,
     result_filter_keyword: Option<String>,
+    _timer: CommandTimer,
 ) -> Result<SearchCountResultPayload, String> {
     search::search_count_in_document_impl(
         state,
@@ -114,12 +167,13 @@ pub fn search_count_in_document(
         case_sensitive,
         result_filter_keyword,
     )
+    .await
 }
 
 #[tauri::command]
 pub fn search_step_from_cursor_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     keyword: String,
     mode: String,
     case_sensitive: bool,
@@ -146,7 +200,7 @@ pub fn search_step_from_cursor_in_document(
 #[tauri::command]
 pub fn replace_all_and_search_chunk_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     keyword: String,
     mode: String,
     case_sensitive: bool,
@@ -170,10 +224,77 @@ pub fn replace_all_and_search_chunk_in_document(
     )
 }
 
+#[tauri::command]
+pub fn preview_replace_in_document(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+    max_results: usize,
+) -> Result<ReplacePreviewResultPayload, String> {
+    search::preview_replace_in_document_impl(
+        state,
+        id,
+        keyword,
+        mode,
+        case_sensitive,
+        replace_value,
+        parse_escape_sequences,
+        max_results,
+    )
+}
+
+#[tauri::command]
+pub fn replace_selected_in_document(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+    match_indices: Vec<usize>,
+    expected_document_version: u64,
+) -> Result<ReplaceSelectedResultPayload, String> {
+    search::replace_selected_in_document_impl(
+        state,
+        id,
+        keyword,
+        mode,
+        case_sensitive,
+        replace_value,
+        parse_escape_sequences,
+        match_indices,
+        expected_document_version,
+    )
+}
+
+#[tauri::command]
+pub fn replace_selected_matches(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    matches: Vec<SelectedMatchRange>,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+    expected_document_version: u64,
+) -> Result<ReplaceSelectedResultPayload, String> {
+    search::replace_selected_matches_impl(
+        state,
+        id,
+        matches,
+        replace_value,
+        parse_escape_sequences,
+        expected_document_version,
+    )
+}
+
 #[tauri::command]
 pub fn replace_current_and_search_chunk_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     keyword: String,
     mode: String,
     case_sensitive: bool,
@@ -204,7 +325,7 @@ pub fn replace_current_and_search_chunk_in_document(
 #[tauri::command]
 pub fn step_result_filter_search_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     keyword: String,
     mode: String,
     case_sensitive: bool,
@@ -233,7 +354,7 @@ pub fn step_result_filter_search_in_document(
 #[tauri::command]
 pub fn filter_count_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     rules: Vec<FilterRuleInput>,
     result_filter_keyword: Option<String>,
     result_filter_case_sensitive: Option<bool>,
@@ -250,7 +371,7 @@ pub fn filter_count_in_document(
 #[tauri::command]
 pub fn filter_in_document_chunk(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     rules: Vec<FilterRuleInput>,
     result_filter_keyword: Option<String>,
     result_filter_case_sensitive: Option<bool>,
@@ -271,11 +392,12 @@ pub fn filter_in_document_chunk(
 #[tauri::command]
 pub fn filter_session_start_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     rules: Vec<FilterRuleInput>,
     result_filter_keyword: Option<String>,
     result_filter_case_sensitive: Option<bool>,
     max_results: usize,
+    follow_appended: Option<bool>,
 ) -> Result<FilterSessionStartResultPayload, String> {
     search::filter_session_start_in_document_impl(
         state,
@@ -284,6 +406,7 @@ pub fn filter_session_start_in_document(
         result_filter_keyword,
         result_filter_case_sensitive,
         max_results,
+        follow_appended,
     )
 }
 
@@ -296,10 +419,19 @@ pub fn filter_session_next_in_document(
     search::filter_session_next_in_document_impl(state, session_id, max_results)
 }
 
+#[tauri::command]
+pub fn filter_session_previous_in_document(
+    state: State<'_, AppState>,
+    session_id: String,
+    max_results: usize,
+) -> Result<FilterSessionPreviousResultPayload, String> {
+    search::filter_session_previous_in_document_impl(state, session_id, max_results)
+}
+
 #[tauri::command]
 pub fn filter_session_restore_in_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     rules: Vec<FilterRuleInput>,
     result_filter_keyword: Option<String>,
     result_filter_case_sensitive: Option<bool>,
@@ -325,7 +457,7 @@ pub fn dispose_filter_session(session_id: String) -> bool {
 #[tauri::command]
 pub fn step_result_filter_search_in_filter_document(
     state: State<'_, AppState>,
-    id: String,
+    id: DocumentId,
     rules: Vec<FilterRuleInput>,
     result_filter_keyword: Option<String>,
     result_filter_case_sensitive: Option<bool>,
@@ -346,3 +478,103 @@ pub fn step_result_filter_search_in_filter_document(
         max_results,
     )
 }
+
+#[tauri::command]
+pub fn search_in_rectangle(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    rect: RectangleRegion,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+) -> Result<RectangleSearchResultPayload, String> {
+    search::search_in_rectangle_impl(state, id, rect, keyword, mode, case_sensitive)
+}
+
+#[tauri::command]
+pub fn replace_in_rectangle(
+    state: State<'_, AppState>,
+    id: DocumentId,
+    rect: RectangleRegion,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+) -> Result<RectangleReplaceResultPayload, String> {
+    search::replace_in_rectangle_impl(
+        state,
+        id,
+        rect,
+        keyword,
+        mode,
+        case_sensitive,
+        replace_value,
+        parse_escape_sequences,
+    )
+}
+
+#[tauri::command]
+pub fn search_in_directory(
+    app: AppHandle,
+    root: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    max_results: Option<usize>,
+) -> Result<DirectorySearchStartPayload, String> {
+    search::search_in_directory_start_impl(app, root, keyword, mode, case_sensitive, max_results)
+}
+
+#[tauri::command]
+pub fn cancel_directory_search(search_id: String) -> bool {
+    search::cancel_directory_search_impl(search_id)
+}
+
+#[tauri::command]
+pub async fn preview_replace_in_directory(
+    root: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+    _timer: CommandTimer,
+) -> Result<DirectoryReplacePreviewPayload, String> {
+    search::preview_replace_in_directory_impl(
+        root,
+        keyword,
+        mode,
+        case_sensitive,
+        replace_value,
+        parse_escape_sequences.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn replace_in_directory(
+    state: State<'_, AppState>,
+    root: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    replace_value: String,
+    parse_escape_sequences: Option<bool>,
+    file_paths: Vec<String>,
+    create_backup: Option<bool>,
+    _timer: CommandTimer,
+) -> Result<DirectoryReplaceApplyPayload, String> {
+    search::replace_in_directory_impl(
+        state,
+        root,
+        keyword,
+        mode,
+        case_sensitive,
+        replace_value,
+        parse_escape_sequences.unwrap_or(false),
+        file_paths,
+        create_backup.unwrap_or(true),
+    )
+    .await
+}
@@ -0,0 +1,208 @@
+// Secrets such as remote credentials, kept out of `config.json`. Values are
+// stored as generic credentials in the Windows Credential Manager under
+// `Rutar:<name>`, so they are protected by the user's Windows logon. Backend
+// modules read them with `get_secret_impl`; the frontend can only store,
+// check and delete, never read a value back. Other platforms have no store
+// yet and report an error; callers decide whether to fall back, like the
+// remote control token staying in the config.
+
+const SECRET_TARGET_PREFIX: &str = "Rutar:";
+const MAX_SECRET_NAME_LEN: usize = 128;
+// CRED_MAX_CREDENTIAL_BLOB_SIZE
+const MAX_SECRET_VALUE_BYTES: usize = 5 * 512;
+
+// Names are dotted identifiers like `sftp.example-host.password`.
+fn normalize_secret_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Secret name is empty".to_string());
+    }
+    if name.len() > MAX_SECRET_NAME_LEN {
+        return Err(format!(
+            "Secret name is longer than {} characters",
+            MAX_SECRET_NAME_LEN
+        ));
+    }
+    if !name
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-'))
+    {
+        return Err(format!("Invalid secret name: {}", name));
+    }
+
+    Ok(name.to_string())
+}
+
+fn secret_target_name(name: &str) -> Result<String, String> {
+    normalize_secret_name(name).map(|name| format!("{SECRET_TARGET_PREFIX}{name}"))
+}
+
+pub(super) fn store_secret_impl(name: String, value: String) -> Result<(), String> {
+    let target = secret_target_name(&name)?;
+    if value.len() > MAX_SECRET_VALUE_BYTES {
+        return Err(format!(
+            "Secret value is larger than {} bytes",
+            MAX_SECRET_VALUE_BYTES
+        ));
+    }
+
+    credential_store::write(&target, value.as_bytes())
+}
+
+pub(crate) fn get_secret_impl(name: &str) -> Result<Option<String>, String> {
+    let target = secret_target_name(name)?;
+    let Some(bytes) = credential_store::read(&target)? else {
+        return Ok(None);
+    };
+
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|_| format!("Secret {} is not valid UTF-8", name.trim()))
+}
+
+pub(super) fn has_secret_impl(name: String) -> Result<bool, String> {
+    get_secret_impl(&name).map(|value| value.is_some())
+}
+
+pub(super) fn delete_secret_impl(name: String) -> Result<bool, String> {
+    let target = secret_target_name(&name)?;
+    credential_store::delete(&target)
+}
+
+#[cfg(windows)]
+mod credential_store {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::ERROR_NOT_FOUND;
+    use windows_sys::Win32::Security::Credentials::{
+        CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE,
+        CRED_TYPE_GENERIC,
+    };
+
+    fn to_wide(value: &str) -> Vec<u16> {
+        OsStr::new(value)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect()
+    }
+
+    fn last_error() -> std::io::Error {
+        std::io::Error::last_os_error()
+    }
+
+    fn is_not_found(error: &std::io::Error) -> bool {
+        error.raw_os_error() == Some(ERROR_NOT_FOUND as i32)
+    }
+
+    pub(super) fn write(target: &str, value: &[u8]) -> Result<(), String> {
+        let mut target_name = to_wide(target);
+        let mut blob = value.to_vec();
+        // SAFETY: CREDENTIALW is a plain C struct for which all-zero is a valid
+        // empty value; the pointers set below outlive the CredWriteW call.
+        let mut credential: CREDENTIALW = unsafe { std::mem::zeroed() };
+        credential.Type = CRED_TYPE_GENERIC;
+        credential.TargetName = target_name.as_mut_ptr();
+        credential.CredentialBlobSize = blob.len() as u32;
+        credential.CredentialBlob = blob.as_mut_ptr();
+        credential.Persist = CRED_PERSIST_LOCAL_MACHINE;
+
+        // SAFETY: `credential` is fully initialized, and `target_name` (NUL
+        // terminated) and `blob` are alive and unmoved for the whole call;
+        // CredWriteW copies what it needs before returning.
+        let written = unsafe { CredWriteW(&credential, 0) };
+        blob.fill(0);
+        if written == 0 {
+            return Err(format!("Failed to store secret: {}", last_error()));
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn read(target: &str) -> Result<Option<Vec<u8>>, String> {
+        let target_name = to_wide(target);
+        let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+        // SAFETY: `target_name` is a NUL-terminated UTF-16 string that outlives
+        // the call, and `credential` is a valid out pointer CredReadW only
+        // writes on success.
+        let found =
+            unsafe { CredReadW(target_name.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) };
+        if found == 0 {
+            let error = last_error();
+            if is_not_found(&error) {
+                return Ok(None);
+            }
+            return Err(format!("Failed to read secret: {}", error));
+        }
+
+        // SAFETY: on success CredReadW hands back a credential whose blob stays
+        // valid until CredFree.
+        let value = unsafe {
+            let credential_ref = &*credential;
+            let value = if credential_ref.CredentialBlob.is_null() {
+                Vec::new()
+            } else {
+                std::slice::from_raw_parts(
+                    credential_ref.CredentialBlob,
+                    credential_ref.CredentialBlobSize as usize,
+                )
+                .to_vec()
+            };
+            CredFree(credential.cast());
+            value
+        };
+
+        Ok(Some(value))
+    }
+
+    pub(super) fn delete(target: &str) -> Result<bool, String> {
+        let target_name = to_wide(target);
+        // SAFETY: `target_name` is a NUL-terminated UTF-16 string that outlives
+        // the call.
+        let deleted = unsafe { CredDeleteW(target_name.as_ptr(), CRED_TYPE_GENERIC, 0) };
+        if deleted == 0 {
+            let error = last_error();
+            if is_not_found(&error) {
+                return Ok(false);
+            }
+            return Err(format!("Failed to delete secret: {}", error));
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(not(windows))]
+mod credential_store {
+    const UNSUPPORTED: &str = "Secret storage is only available on Windows";
+
+    pub(super) fn write(_target: &str, _value: &[u8]) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub(super) fn read(_target: &str) -> Result<Option<Vec<u8>>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub(super) fn delete(_target: &str) -> Result<bool, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_target_name_should_prefix_valid_names_and_reject_others() {
+        assert_eq!(
+            secret_target_name("  sftp.example-host.password ").unwrap(),
+            "Rutar:sftp.example-host.password"
+        );
+        assert!(secret_target_name("").is_err());
+        assert!(secret_target_name("a/b").is_err());
+        assert!(secret_target_name(&"x".repeat(MAX_SECRET_NAME_LEN + 1)).is_err());
+        assert!(
+            store_secret_impl("ok".to_string(), "v".repeat(MAX_SECRET_VALUE_BYTES + 1)).is_err()
+        );
+    }
+}
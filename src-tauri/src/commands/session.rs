@@ -0,0 +1,450 @@
+// Open tabs, kept in `session.json` next to the config file so a restart can
+// bring them back. Cursor and scroll positions are reported by the frontend;
+// paths and syntax overrides are read from the documents themselves. Untitled
+// documents have nothing to reopen and are left out.
+//
+// Each tab can also carry the search and panel state around it (see
+// `SessionDocumentExtras`). The backend only stores it; a restored tab asks
+// for it with `get_document_session_extras`.
+
+use super::*;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const SESSION_FORMAT_VERSION: u32 = 1;
+
+// Last session reported by the frontend; the close handler refreshes it from
+// the open documents so tabs opened since then are not lost.
+static LAST_SESSION: OnceLock<Mutex<Option<SessionFile>>> = OnceLock::new();
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTab {
+    pub path: String,
+    // 1-based, like the status bar.
+    #[serde(default = "default_session_position")]
+    pub cursor_line: usize,
+    #[serde(default = "default_session_position")]
+    pub cursor_column: usize,
+    #[serde(default = "default_session_position")]
+    pub scroll_top_line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub syntax_override: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extras: Option<SessionDocumentExtras>,
+}
+
+// The working context of a tab beyond its cursor: the last search, the
+// selected filter rule group and the result panel.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDocumentExtras {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_keyword: Option<String>,
+    // "literal", "regex" or "wildcard", as the search commands take it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_mode: Option<String>,
+    #[serde(default)]
+    pub search_case_sensitive: bool,
+    // Name of the filter rule group applied to the tab.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter_rule_group: Option<String>,
+    #[serde(default)]
+    pub result_panel_open: bool,
+    // "search" or "filter".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_panel_tab: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_panel_height: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFile {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub saved_at_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_path: Option<String>,
+    #[serde(default)]
+    pub tabs: Vec<SessionTab>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTabInput {
+    pub id: String,
+    #[serde(default = "default_session_position")]
+    pub cursor_line: usize,
+    #[serde(default = "default_session_position")]
+    pub cursor_column: usize,
+    #[serde(default = "default_session_position")]
+    pub scroll_top_line: usize,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub extras: Option<SessionDocumentExtras>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredSessionTab {
+    pub file_info: FileInfo,
+    pub cursor_line: usize,
+    pub cursor_column: usize,
+    pub scroll_top_line: usize,
+    pub pinned: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRestorePayload {
+    // In saved tab order.
+    pub tabs: Vec<RestoredSessionTab>,
+    pub active_id: Option<String>,
+    // Saved paths that no longer exist on disk.
+    pub missing_paths: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+fn default_session_position() -> usize {
+    1
+}
+
+fn session_file_path() -> Result<PathBuf, String> {
+    config::config_dir_path_impl().map(|dir| dir.join("session.json"))
+}
+
+fn last_session() -> &'static Mutex<Option<SessionFile>> {
+    LAST_SESSION.get_or_init(|| Mutex::new(None))
+}
+
+fn read_session_file() -> Option<SessionFile> {
+    session_file_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+fn persist_session_file(session: &SessionFile) -> Result<(), String> {
+    if config::is_safe_mode_impl() {
+        return Ok(());
+    }
+
+    let path = session_file_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let raw = serde_json::to_string_pretty(session).map_err(|e| e.to_string())?;
+    fs::write(path, raw).map_err(|e| e.to_string())
+}
+
+// Brings a saved session in line with the documents that are open now, given
+// as (path, syntax override) pairs: closed tabs are dropped, newly opened ones
+// are appended and syntax overrides follow the documents.
+fn merge_session_with_documents(
+    session: SessionFile,
+    documents: &[(String, Option<String>)],
+) -> SessionFile {
+    let mut tabs: Vec<SessionTab> = session
+        .tabs
+        .into_iter()
+        .filter_map(|mut tab| {
+            let (_, syntax_override) = documents.iter().find(|(path, _)| path == &tab.path)?;
+            tab.syntax_override = syntax_override.clone();
+            Some(tab)
+        })
+        .collect();
+
+    for (path, syntax_override) in documents {
+        if tabs.iter().any(|tab| &tab.path == path) {
+            continue;
+        }
+        tabs.push(SessionTab {
+            path: path.clone(),
+            cursor_line: 1,
+            cursor_column: 1,
+            scroll_top_line: 1,
+            syntax_override: syntax_override.clone(),
+            pinned: false,
+            extras: None,
+        });
+    }
+
+    let active_path = session
+        .active_path
+        .filter(|active_path| tabs.iter().any(|tab| &tab.path == active_path));
+
+    SessionFile {
+        version: SESSION_FORMAT_VERSION,
+        saved_at_ms: now_unix_millis(),
+        active_path,
+        tabs,
+    }
+}
+
+fn open_document_paths(state: &AppState) -> Vec<(String, Option<String>)> {
+    let mut documents: Vec<(String, Option<String>)> = state
+        .documents
+        .iter()
+        .filter_map(|doc| {
+            doc.path.as_ref().map(|path| {
+                (
+                    path.to_string_lossy().to_string(),
+                    doc.syntax_override.clone(),
+                )
+            })
+        })
+        .collect();
+    documents.sort();
+    documents.dedup_by(|left, right| left.0 == right.0);
+    documents
+}
+
+// Returns how many tabs were written.
+pub(super) fn save_session_impl(
+    state: State<'_, AppState>,
+    tabs: Vec<SessionTabInput>,
+    active_id: Option<String>,
+) -> Result<usize, String> {
+    let mut session_tabs = Vec::with_capacity(tabs.len());
+    let mut active_path = None;
+
+    for tab in tabs {
+        let Some(doc) = state.documents.get(&tab.id) else {
+            continue;
+        };
+        let Some(path) = doc.path.as_ref() else {
+            continue;
+        };
+        let path = path.to_string_lossy().to_string();
+        if session_tabs
+            .iter()
+            .any(|existing: &SessionTab| existing.path == path)
+        {
+            continue;
+        }
+        if active_id.as_deref() == Some(tab.id.as_str()) {
+            active_path = Some(path.clone());
+        }
+        session_tabs.push(SessionTab {
+            path,
+            cursor_line: tab.cursor_line.max(1),
+            cursor_column: tab.cursor_column.max(1),
+            scroll_top_line: tab.scroll_top_line.max(1),
+            syntax_override: doc.syntax_override.clone(),
+            pinned: tab.pinned,
+            extras: tab
+                .extras
+                .filter(|extras| *extras != SessionDocumentExtras::default()),
+        });
+    }
+
+    let session = SessionFile {
+        version: SESSION_FORMAT_VERSION,
+        saved_at_ms: now_unix_millis(),
+        active_path,
+        tabs: session_tabs,
+    };
+    persist_session_file(&session)?;
+
+    let tab_count = session.tabs.len();
+    *last_session()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(session);
+    Ok(tab_count)
+}
+
+pub(super) fn persist_session_on_close_impl(state: &AppState) -> Result<(), String> {
+    let base = last_session()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .or_else(read_session_file)
+        .unwrap_or_default();
+    let session = merge_session_with_documents(base, &open_document_paths(state));
+    persist_session_file(&session)
+}
+
+// Looked up by path in the session saved last, so it also answers for a file
+// reopened by hand after the restore.
+pub(super) fn get_document_session_extras_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Option<SessionDocumentExtras>, String> {
+    let path = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?
+        .path
+        .as_ref()
+        .map(|path| path.to_string_lossy().to_string());
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    if config::is_safe_mode_impl() {
+        return Ok(None);
+    }
+
+    let mut last = last_session()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if last.is_none() {
+        *last = read_session_file();
+    }
+    Ok(last
+        .as_ref()
+        .and_then(|session| session.tabs.iter().find(|tab| tab.path == path))
+        .and_then(|tab| tab.extras.clone()))
+}
+
+pub(super) async fn load_session_impl(
+    state: State<'_, AppState>,
+) -> Result<SessionRestorePayload, String> {
+    // Safe mode starts with no tabs, like it skips every other persisted state.
+    if config::is_safe_mode_impl() {
+        return Ok(SessionRestorePayload::default());
+    }
+    let Some(session) = read_session_file() else {
+        return Ok(SessionRestorePayload::default());
+    };
+
+    let (tabs, missing): (Vec<SessionTab>, Vec<SessionTab>) = session
+        .tabs
+        .into_iter()
+        .partition(|tab| Path::new(&tab.path).is_file());
+    let opened = file_io::open_files_impl(
+        state.clone(),
+        tabs.iter().map(|tab| tab.path.clone()).collect(),
+    )
+    .await;
+
+    let mut payload = SessionRestorePayload {
+        missing_paths: missing.into_iter().map(|tab| tab.path).collect(),
+        ..SessionRestorePayload::default()
+    };
+
+    for (tab, result) in tabs.into_iter().zip(opened) {
+        let Some(mut file_info) = result.file_info else {
+            payload.errors.push(format!(
+                "{}: {}",
+                result.path,
+                result.error.unwrap_or_default()
+            ));
+            continue;
+        };
+
+        if tab.syntax_override.is_some() {
+            // A syntax that is no longer supported falls back to detection.
+            if let Ok(syntax_override) =
+                syntax::normalize_syntax_override(tab.syntax_override.as_deref())
+            {
+                if let Some(mut doc) = state.documents.get_mut(&file_info.id) {
+                    doc.syntax_override = syntax_override.clone();
+                }
+                file_info.syntax_override = syntax_override;
+            }
+        }
+
+        if session.active_path.as_deref() == Some(tab.path.as_str()) {
+            payload.active_id = Some(file_info.id.clone());
+        }
+        payload.tabs.push(RestoredSessionTab {
+            file_info,
+            cursor_line: tab.cursor_line.max(1),
+            cursor_column: tab.cursor_column.max(1),
+            scroll_top_line: tab.scroll_top_line.max(1),
+            pinned: tab.pinned,
+        });
+    }
+
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_tab(path: &str, cursor_line: usize) -> SessionTab {
+        SessionTab {
+            path: path.to_string(),
+            cursor_line,
+            cursor_column: 1,
+            scroll_top_line: 1,
+            syntax_override: None,
+            pinned: false,
+            extras: None,
+        }
+    }
+
+    #[test]
+    fn merge_session_with_documents_should_keep_order_and_append_new_tabs() {
+        let mut searched = make_tab("c", 7);
+        searched.extras = Some(SessionDocumentExtras {
+            search_keyword: Some("error".to_string()),
+            ..SessionDocumentExtras::default()
+        });
+        let session = SessionFile {
+            version: SESSION_FORMAT_VERSION,
+            saved_at_ms: 0,
+            active_path: Some("b".to_string()),
+            tabs: vec![searched, make_tab("b", 3), make_tab("a", 2)],
+        };
+        let documents = vec![
+            ("a".to_string(), Some("json".to_string())),
+            ("c".to_string(), None),
+            ("d".to_string(), None),
+        ];
+
+        let merged = merge_session_with_documents(session, &documents);
+
+        let paths: Vec<&str> = merged.tabs.iter().map(|tab| tab.path.as_str()).collect();
+        assert_eq!(paths, vec!["c", "a", "d"]);
+        assert_eq!(merged.tabs[0].cursor_line, 7);
+        assert_eq!(
+            merged.tabs[0]
+                .extras
+                .as_ref()
+                .and_then(|extras| extras.search_keyword.as_deref()),
+            Some("error")
+        );
+        assert_eq!(merged.tabs[1].syntax_override.as_deref(), Some("json"));
+        assert_eq!(merged.tabs[2].cursor_line, 1);
+        // The active tab was closed.
+        assert!(merged.active_path.is_none());
+    }
+
+    #[test]
+    fn session_file_should_fill_missing_positions_with_defaults() {
+        let session: SessionFile =
+            serde_json::from_str(r#"{"tabs":[{"path":"a.txt","pinned":true}]}"#)
+                .expect("session should parse");
+
+        assert_eq!(session.tabs.len(), 1);
+        assert_eq!(session.tabs[0].cursor_line, 1);
+        assert_eq!(session.tabs[0].scroll_top_line, 1);
+        assert!(session.tabs[0].pinned);
+        assert!(session.tabs[0].extras.is_none());
+        assert!(session.active_path.is_none());
+    }
+
+    #[test]
+    fn session_document_extras_should_round_trip_and_fill_defaults() {
+        let extras: SessionDocumentExtras = serde_json::from_str(
+            r#"{"searchKeyword":"timeout","filterRuleGroup":"Errors","resultPanelOpen":true}"#,
+        )
+        .expect("extras should parse");
+
+        assert_eq!(extras.search_keyword.as_deref(), Some("timeout"));
+        assert!(extras.search_mode.is_none());
+        assert!(!extras.search_case_sensitive);
+        assert!(extras.result_panel_open);
+        assert_eq!(
+            serde_json::to_string(&extras).expect("extras should serialize"),
+            r#"{"searchKeyword":"timeout","searchCaseSensitive":false,"filterRuleGroup":"Errors","resultPanelOpen":true}"#
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use super::session::{self, SessionDocumentExtras, SessionRestorePayload, SessionTabInput};
+use super::*;
+
+#[tauri::command]
+pub fn save_session(
+    state: State<'_, AppState>,
+    tabs: Vec<SessionTabInput>,
+    active_id: Option<String>,
+) -> Result<usize, String> {
+    session::save_session_impl(state, tabs, active_id)
+}
+
+#[tauri::command]
+pub async fn load_session(
+    state: State<'_, AppState>,
+    _timer: CommandTimer,
+) -> Result<SessionRestorePayload, String> {
+    session::load_session_impl(state).await
+}
+
+#[tauri::command]
+pub fn get_document_session_extras(
+    state: State<'_, AppState>,
+    id: DocumentId,
+) -> Result<Option<SessionDocumentExtras>, String> {
+    session::get_document_session_extras_impl(state, id)
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::BTreeMap;
 
 pub(super) fn default_windows_file_association_extensions() -> Vec<String> {
     DEFAULT_WINDOWS_FILE_ASSOCIATION_EXTENSIONS
@@ -60,6 +61,10 @@ fn default_translation_settings() -> TranslationSettingsConfig {
     TranslationSettingsConfig::default()
 }
 
+fn default_remote_control_port() -> u16 {
+    DEFAULT_REMOTE_CONTROL_PORT
+}
+
 fn default_new_file_line_ending() -> String {
     default_line_ending().label().to_string()
 }
@@ -67,6 +72,39 @@ fn default_new_file_line_ending() -> String {
 fn default_tab_indent_mode() -> String {
     "tabs".to_string()
 }
+
+fn default_file_share_mode() -> String {
+    FileShareMode::default().label().to_string()
+}
+
+fn default_auto_save_mode() -> String {
+    "off".to_string()
+}
+
+fn default_auto_save_interval_secs() -> u64 {
+    DEFAULT_AUTO_SAVE_INTERVAL_SECS
+}
+
+fn default_idle_trim_after_secs() -> u64 {
+    DEFAULT_IDLE_TRIM_AFTER_SECS
+}
+
+fn default_stale_cache_max_age_mins() -> u64 {
+    DEFAULT_STALE_CACHE_MAX_AGE_MINS
+}
+
+fn default_max_undo_history() -> usize {
+    DEFAULT_MAX_UNDO_HISTORY
+}
+
+fn default_fold_min_block_lines() -> usize {
+    DEFAULT_FOLD_MIN_BLOCK_LINES
+}
+
+fn default_preview_mode_threshold_mb() -> u64 {
+    DEFAULT_PREVIEW_MODE_THRESHOLD_MB
+}
+
 fn default_minimap() -> bool {
     true
 }
@@ -75,6 +113,8 @@ fn default_minimap_autohide() -> bool {
     true
 }
 
+const MAX_EXTRA_WORD_CHARS: usize = 32;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct TranslationEngineConfig {
@@ -107,6 +147,31 @@ impl Default for TranslationSettingsConfig {
     }
 }
 
+// Localhost HTTP endpoint for scripts and browser extensions. It only starts
+// when enabled with a non-empty token; changes apply on the next launch.
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteControlSettingsConfig {
+    #[serde(default)]
+    pub(super) enabled: bool,
+    #[serde(default = "default_remote_control_port")]
+    pub(super) port: u16,
+    // Moved into the secret store when one is available; it only stays in
+    // saved configs on platforms without one.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(super) token: String,
+}
+
+impl Default for RemoteControlSettingsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_remote_control_port(),
+            token: String::new(),
+        }
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowStateConfig {
@@ -137,11 +202,37 @@ pub struct AppConfig {
     pub(super) tab_indent_mode: String,
     #[serde(default = "default_new_file_line_ending")]
     pub(super) new_file_line_ending: String,
+    #[serde(default = "default_file_share_mode")]
+    pub(super) file_share_mode: String,
+    #[serde(default = "default_auto_save_mode")]
+    pub(super) auto_save_mode: String,
+    #[serde(default = "default_auto_save_interval_secs")]
+    pub(super) auto_save_interval_secs: u64,
+    #[serde(default = "default_idle_trim_after_secs")]
+    pub(super) idle_trim_after_secs: u64,
+    #[serde(default = "default_stale_cache_max_age_mins")]
+    pub(super) stale_cache_max_age_mins: u64,
+    #[serde(default = "default_max_undo_history")]
+    pub(super) max_undo_history: usize,
+    #[serde(default = "default_fold_min_block_lines")]
+    pub(super) fold_min_block_lines: usize,
+    // Files larger than this open in read-only preview mode; 0 disables it.
+    #[serde(default = "default_preview_mode_threshold_mb")]
+    pub(super) preview_mode_threshold_mb: u64,
+    // Files under these folders always open in preview mode.
+    #[serde(default = "default_recent_paths")]
+    pub(super) untrusted_folders: Vec<String>,
     pub(super) word_wrap: bool,
     #[serde(default = "default_minimap")]
     pub(super) minimap: bool,
     #[serde(default = "default_minimap_autohide")]
     pub(super) minimap_autohide: bool,
+    // Punctuation treated as part of a word, e.g. "-$" for CSS or PHP.
+    #[serde(default)]
+    pub(super) extra_word_chars: String,
+    // Per syntax key; an entry replaces the global set for that file type.
+    #[serde(default)]
+    pub(super) extra_word_chars_by_syntax: BTreeMap<String, String>,
     pub(super) double_click_close_tab: bool,
     pub(super) show_line_numbers: bool,
     pub(super) highlight_current_line: bool,
@@ -149,6 +240,13 @@ pub struct AppConfig {
     pub(super) single_instance_mode: bool,
     #[serde(default = "default_remember_window_state")]
     pub(super) remember_window_state: bool,
+    // Reload files changed on disk without asking when they have no local
+    // edits; tabs can override it.
+    #[serde(default)]
+    pub(super) auto_reload_unmodified_files: bool,
+    // Keeps the clipboard history in `clipboard_history.json` across restarts.
+    #[serde(default)]
+    pub(super) persist_clipboard_history: bool,
     #[serde(default = "default_recent_paths")]
     pub(super) recent_files: Vec<String>,
     #[serde(default = "default_recent_paths")]
@@ -167,10 +265,19 @@ pub struct AppConfig {
     pub(super) mouse_gestures: Vec<MouseGestureConfig>,
     #[serde(default = "default_translation_settings")]
     pub(super) translation: TranslationSettingsConfig,
+    #[serde(default)]
+    pub(super) remote_control: RemoteControlSettingsConfig,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(super) window_state: Option<WindowStateConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(super) filter_rule_groups: Option<Vec<FilterRuleGroupConfig>>,
+    // Folder of rule-group JSON files maintained by a team; empty disables it.
+    #[serde(default)]
+    pub(super) shared_filter_rule_groups_path: String,
+    // File extension, without the dot, to syntax key. Checked before the
+    // built-in extension table; `None` on save keeps the saved map.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) syntax_extension_overrides: Option<BTreeMap<String, String>>,
 }
 
 #[derive(serde::Deserialize)]
@@ -183,14 +290,27 @@ pub struct PartialAppConfig {
     pub(super) tab_width: Option<u8>,
     pub(super) tab_indent_mode: Option<String>,
     pub(super) new_file_line_ending: Option<String>,
+    pub(super) file_share_mode: Option<String>,
+    pub(super) auto_save_mode: Option<String>,
+    pub(super) auto_save_interval_secs: Option<u64>,
+    pub(super) idle_trim_after_secs: Option<u64>,
+    pub(super) stale_cache_max_age_mins: Option<u64>,
+    pub(super) max_undo_history: Option<usize>,
+    pub(super) fold_min_block_lines: Option<usize>,
+    pub(super) preview_mode_threshold_mb: Option<u64>,
+    pub(super) untrusted_folders: Option<Vec<String>>,
     pub(super) word_wrap: Option<bool>,
     pub(super) minimap: Option<bool>,
     pub(super) minimap_autohide: Option<bool>,
+    pub(super) extra_word_chars: Option<String>,
+    pub(super) extra_word_chars_by_syntax: Option<BTreeMap<String, String>>,
     pub(super) double_click_close_tab: Option<bool>,
     pub(super) show_line_numbers: Option<bool>,
     pub(super) highlight_current_line: Option<bool>,
     pub(super) single_instance_mode: Option<bool>,
     pub(super) remember_window_state: Option<bool>,
+    pub(super) auto_reload_unmodified_files: Option<bool>,
+    pub(super) persist_clipboard_history: Option<bool>,
     pub(super) recent_files: Option<Vec<String>>,
     pub(super) recent_folders: Option<Vec<String>>,
     pub(super) recent_search_keywords: Option<Vec<String>>,
@@ -200,8 +320,11 @@ pub struct PartialAppConfig {
     pub(super) mouse_gestures_enabled: Option<bool>,
     pub(super) mouse_gestures: Option<Vec<MouseGestureConfig>>,
     pub(super) translation: Option<TranslationSettingsConfig>,
+    pub(super) remote_control: Option<RemoteControlSettingsConfig>,
     pub(super) window_state: Option<WindowStateConfig>,
     pub(super) filter_rule_groups: Option<Vec<FilterRuleGroupConfig>>,
+    pub(super) shared_filter_rule_groups_path: Option<String>,
+    pub(super) syntax_extension_overrides: Option<BTreeMap<String, String>>,
 }
 
 impl Default for AppConfig {
@@ -214,14 +337,27 @@ impl Default for AppConfig {
             tab_width: DEFAULT_TAB_WIDTH,
             tab_indent_mode: default_tab_indent_mode(),
             new_file_line_ending: default_new_file_line_ending(),
+            file_share_mode: default_file_share_mode(),
+            auto_save_mode: default_auto_save_mode(),
+            auto_save_interval_secs: default_auto_save_interval_secs(),
+            idle_trim_after_secs: default_idle_trim_after_secs(),
+            stale_cache_max_age_mins: default_stale_cache_max_age_mins(),
+            max_undo_history: default_max_undo_history(),
+            fold_min_block_lines: default_fold_min_block_lines(),
+            preview_mode_threshold_mb: default_preview_mode_threshold_mb(),
+            untrusted_folders: default_recent_paths(),
             word_wrap: false,
             minimap: default_minimap(),
             minimap_autohide: default_minimap_autohide(),
+            extra_word_chars: String::new(),
+            extra_word_chars_by_syntax: BTreeMap::new(),
             double_click_close_tab: DEFAULT_DOUBLE_CLICK_CLOSE_TAB,
             show_line_numbers: DEFAULT_SHOW_LINE_NUMBERS,
             highlight_current_line: DEFAULT_HIGHLIGHT_CURRENT_LINE,
             single_instance_mode: DEFAULT_SINGLE_INSTANCE_MODE,
             remember_window_state: default_remember_window_state(),
+            auto_reload_unmodified_files: false,
+            persist_clipboard_history: false,
             recent_files: default_recent_paths(),
             recent_folders: default_recent_paths(),
             recent_search_keywords: default_recent_text_history(),
@@ -231,15 +367,21 @@ impl Default for AppConfig {
             mouse_gestures_enabled: default_mouse_gestures_enabled(),
             mouse_gestures: default_mouse_gestures(),
             translation: default_translation_settings(),
+            remote_control: RemoteControlSettingsConfig::default(),
             window_state: None,
             filter_rule_groups: None,
+            shared_filter_rule_groups_path: String::new(),
+            syntax_extension_overrides: None,
         }
     }
 }
 
+// Besides the built-in languages, any well-formed tag is kept so a locale
+// file can provide it; this is a pure check that never touches the disk.
 pub(super) fn normalize_language(language: Option<&str>) -> String {
     match language {
         Some("en-US") => "en-US".to_string(),
+        Some(other) if locale::is_valid_language_tag(other) => other.to_string(),
         _ => DEFAULT_LANGUAGE.to_string(),
     }
 }
@@ -270,6 +412,97 @@ pub(super) fn normalize_new_file_line_ending(label: Option<&str>) -> String {
         .to_string()
 }
 
+pub(super) fn normalize_file_share_mode(label: Option<&str>) -> String {
+    label
+        .and_then(FileShareMode::from_label)
+        .unwrap_or_default()
+        .label()
+        .to_string()
+}
+
+// Accepts the documented kebab-case spellings as aliases.
+pub(super) fn normalize_auto_save_mode(mode: Option<&str>) -> String {
+    match mode {
+        Some("afterDelay" | "after-delay") => "afterDelay".to_string(),
+        Some("onFocusChange" | "on-focus-change") => "onFocusChange".to_string(),
+        _ => default_auto_save_mode(),
+    }
+}
+
+pub(super) fn normalize_auto_save_interval_secs(interval_secs: u64) -> u64 {
+    interval_secs.clamp(1, MAX_AUTO_SAVE_INTERVAL_SECS)
+}
+
+// Zero disables idle trimming; anything else waits at least a minute so
+// trimming never races an active editing burst.
+pub(super) fn normalize_idle_trim_after_secs(idle_secs: u64) -> u64 {
+    if idle_secs == 0 {
+        0
+    } else {
+        idle_secs.clamp(MIN_IDLE_TRIM_AFTER_SECS, MAX_IDLE_TRIM_AFTER_SECS)
+    }
+}
+
+pub(super) fn normalize_stale_cache_max_age_mins(max_age_mins: u64) -> u64 {
+    max_age_mins.clamp(1, MAX_STALE_CACHE_MAX_AGE_MINS)
+}
+
+// 0 keeps the undo history unlimited.
+pub(super) fn normalize_max_undo_history(max_undo_history: usize) -> usize {
+    max_undo_history.min(MAX_UNDO_HISTORY)
+}
+
+pub(super) fn normalize_fold_min_block_lines(min_block_lines: usize) -> usize {
+    min_block_lines.clamp(2, MAX_FOLD_MIN_BLOCK_LINES)
+}
+
+// Letters, digits and `_` are always word characters, so only other visible
+// characters are kept, once each.
+pub(super) fn normalize_extra_word_chars(chars: &str) -> String {
+    let mut normalized = String::new();
+    for ch in chars.chars() {
+        if ch.is_alphanumeric() || ch == '_' || ch.is_whitespace() || ch.is_control() {
+            continue;
+        }
+        if !normalized.contains(ch) && normalized.chars().count() < MAX_EXTRA_WORD_CHARS {
+            normalized.push(ch);
+        }
+    }
+    normalized
+}
+
+pub(super) fn normalize_extra_word_chars_by_syntax(
+    entries: BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    entries
+        .into_iter()
+        .filter_map(|(syntax_key, chars)| {
+            let syntax_key = syntax_key.trim().to_lowercase();
+            (!syntax_key.is_empty()).then(|| (syntax_key, normalize_extra_word_chars(&chars)))
+        })
+        .collect()
+}
+
+// Entries whose syntax key is unknown are dropped rather than failing the
+// whole config.
+pub(super) fn normalize_syntax_extension_overrides(
+    entries: BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    entries
+        .into_iter()
+        .filter_map(|(extension, syntax_key)| {
+            let extension = extension.trim().trim_start_matches('.').to_lowercase();
+            if extension.is_empty() {
+                return None;
+            }
+            let syntax_key = syntax::normalize_syntax_override(Some(syntax_key.as_str()))
+                .ok()
+                .flatten()?;
+            Some((extension, syntax_key))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,6 +512,8 @@ mod tests {
         assert_eq!(normalize_language(Some("en-US")), "en-US");
         assert_eq!(normalize_language(Some("zh-CN")), DEFAULT_LANGUAGE);
         assert_eq!(normalize_language(Some("unknown")), DEFAULT_LANGUAGE);
+        assert_eq!(normalize_language(Some("ja-JP")), "ja-JP");
+        assert_eq!(normalize_language(Some("../ja-JP")), DEFAULT_LANGUAGE);
         assert_eq!(normalize_language(None), DEFAULT_LANGUAGE);
     }
 
@@ -317,6 +552,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn normalize_file_share_mode_should_fallback_to_read_write() {
+        assert_eq!(normalize_file_share_mode(Some("exclusive")), "exclusive");
+        assert_eq!(normalize_file_share_mode(Some("READ")), "read");
+        assert_eq!(normalize_file_share_mode(Some("invalid")), "readWrite");
+        assert_eq!(normalize_file_share_mode(None), "readWrite");
+    }
+
     #[test]
     fn app_config_default_should_use_expected_defaults() {
         let config = AppConfig::default();
@@ -326,13 +569,21 @@ mod tests {
         assert_eq!(config.tab_width, DEFAULT_TAB_WIDTH);
         assert_eq!(config.tab_indent_mode, "tabs");
         assert_eq!(config.new_file_line_ending, default_line_ending().label());
+        assert_eq!(config.file_share_mode, "readWrite");
         assert!(config.minimap);
         assert!(config.minimap_autohide);
         assert_eq!(config.single_instance_mode, DEFAULT_SINGLE_INSTANCE_MODE);
         assert!(config.remember_window_state);
+        assert!(!config.auto_reload_unmodified_files);
+        assert!(!config.persist_clipboard_history);
         assert!(config.recent_search_keywords.is_empty());
         assert!(config.recent_replace_values.is_empty());
         assert!(config.pinned_tab_paths.is_empty());
+        assert_eq!(
+            config.preview_mode_threshold_mb,
+            DEFAULT_PREVIEW_MODE_THRESHOLD_MB
+        );
+        assert!(config.untrusted_folders.is_empty());
         assert!(config.mouse_gestures_enabled);
         assert!(!config.mouse_gestures.is_empty());
         assert_eq!(config.translation.engine, "google");
@@ -345,4 +596,82 @@ mod tests {
         assert!(!extensions.is_empty());
         assert!(extensions.iter().all(|item| item.starts_with('.')));
     }
+
+    #[test]
+    fn normalize_auto_save_settings_should_fallback_and_clamp() {
+        assert_eq!(normalize_auto_save_mode(Some("after-delay")), "afterDelay");
+        assert_eq!(
+            normalize_auto_save_mode(Some("onFocusChange")),
+            "onFocusChange"
+        );
+        assert_eq!(normalize_auto_save_mode(Some("always")), "off");
+        assert_eq!(normalize_auto_save_mode(None), "off");
+        assert_eq!(normalize_auto_save_interval_secs(0), 1);
+        assert_eq!(
+            normalize_auto_save_interval_secs(u64::MAX),
+            MAX_AUTO_SAVE_INTERVAL_SECS
+        );
+    }
+
+    #[test]
+    fn normalize_idle_trim_settings_should_keep_zero_and_clamp() {
+        assert_eq!(normalize_idle_trim_after_secs(0), 0);
+        assert_eq!(normalize_idle_trim_after_secs(1), MIN_IDLE_TRIM_AFTER_SECS);
+        assert_eq!(
+            normalize_idle_trim_after_secs(u64::MAX),
+            MAX_IDLE_TRIM_AFTER_SECS
+        );
+        assert_eq!(normalize_stale_cache_max_age_mins(0), 1);
+        assert_eq!(
+            normalize_stale_cache_max_age_mins(u64::MAX),
+            MAX_STALE_CACHE_MAX_AGE_MINS
+        );
+        assert_eq!(normalize_max_undo_history(0), 0);
+        assert_eq!(normalize_max_undo_history(50), 50);
+        assert_eq!(normalize_max_undo_history(usize::MAX), MAX_UNDO_HISTORY);
+    }
+
+    #[test]
+    fn normalize_fold_min_block_lines_should_be_clamped_to_valid_range() {
+        assert_eq!(normalize_fold_min_block_lines(0), 2);
+        assert_eq!(normalize_fold_min_block_lines(5), 5);
+        assert_eq!(
+            normalize_fold_min_block_lines(usize::MAX),
+            MAX_FOLD_MIN_BLOCK_LINES
+        );
+    }
+
+    #[test]
+    fn normalize_extra_word_chars_should_keep_unique_punctuation_only() {
+        assert_eq!(normalize_extra_word_chars("-$a_1 .-\t"), "-$.");
+        assert_eq!(normalize_extra_word_chars(""), "");
+
+        let normalized = normalize_extra_word_chars_by_syntax(BTreeMap::from([
+            (" CSS ".to_string(), "--".to_string()),
+            ("  ".to_string(), "$".to_string()),
+        ]));
+        assert_eq!(
+            normalized,
+            BTreeMap::from([("css".to_string(), "-".to_string())])
+        );
+    }
+
+    #[test]
+    fn normalize_syntax_extension_overrides_should_drop_unknown_syntax_keys() {
+        let normalized = normalize_syntax_extension_overrides(BTreeMap::from([
+            (".Gradle".to_string(), " Kotlin ".to_string()),
+            ("tpl".to_string(), "html".to_string()),
+            ("log4j".to_string(), "log4j".to_string()),
+            ("cfg".to_string(), "auto".to_string()),
+            (".".to_string(), "ini".to_string()),
+        ]));
+
+        assert_eq!(
+            normalized,
+            BTreeMap::from([
+                ("gradle".to_string(), "kotlin".to_string()),
+                ("tpl".to_string(), "html".to_string()),
+            ])
+        );
+    }
 }
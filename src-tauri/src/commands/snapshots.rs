@@ -0,0 +1,165 @@
+// In-memory local history: timed checkpoints of each open document's text.
+//
+// The maintenance thread records a checkpoint whenever a document changed and
+// its latest one is at least SNAPSHOT_INTERVAL old; users can also take one
+// by hand. Ropes share unchanged chunks, so checkpoints are cheap. History is
+// dropped when its document closes.
+
+use super::*;
+use dashmap::DashMap;
+use std::time::Duration;
+use tauri::Manager;
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const MAX_SNAPSHOTS_PER_DOCUMENT: usize = 48;
+
+struct DocumentSnapshot {
+    id: String,
+    taken_at_ms: u64,
+    document_version: u64,
+    rope: Rope,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSnapshotSummary {
+    pub id: String,
+    pub taken_at_ms: u64,
+    pub document_version: u64,
+    pub line_count: usize,
+}
+
+fn snapshots() -> &'static DashMap<String, Vec<DocumentSnapshot>> {
+    static MAP: OnceLock<DashMap<String, Vec<DocumentSnapshot>>> = OnceLock::new();
+    MAP.get_or_init(DashMap::new)
+}
+
+fn summarize_snapshot(snapshot: &DocumentSnapshot) -> DocumentSnapshotSummary {
+    DocumentSnapshotSummary {
+        id: snapshot.id.clone(),
+        taken_at_ms: snapshot.taken_at_ms,
+        document_version: snapshot.document_version,
+        line_count: snapshot.rope.len_lines(),
+    }
+}
+
+fn push_snapshot(history: &mut Vec<DocumentSnapshot>, snapshot: DocumentSnapshot) {
+    history.push(snapshot);
+    if history.len() > MAX_SNAPSHOTS_PER_DOCUMENT {
+        let overflow = history.len() - MAX_SNAPSHOTS_PER_DOCUMENT;
+        history.drain(..overflow);
+    }
+}
+
+// A new checkpoint is due when the text moved on from the latest one and that
+// one is old enough; the first checkpoint is always due.
+fn is_snapshot_due(history: &[DocumentSnapshot], document_version: u64, now_ms: u64) -> bool {
+    match history.last() {
+        Some(latest) => {
+            latest.document_version != document_version
+                && now_ms.saturating_sub(latest.taken_at_ms) >= SNAPSHOT_INTERVAL.as_millis() as u64
+        }
+        None => true,
+    }
+}
+
+pub(super) fn capture_document_snapshots_impl(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    snapshots().retain(|id, _| state.documents.contains_key(id));
+
+    let now_ms = now_unix_millis();
+    for entry in state.documents.iter() {
+        let doc = entry.value();
+        if doc.derived_view.is_some() {
+            continue;
+        }
+
+        let mut history = snapshots().entry(entry.key().clone()).or_default();
+        if is_snapshot_due(&history, doc.document_version, now_ms) {
+            push_snapshot(
+                &mut history,
+                DocumentSnapshot {
+                    id: Uuid::new_v4().to_string(),
+                    taken_at_ms: now_ms,
+                    document_version: doc.document_version,
+                    rope: doc.rope.clone(),
+                },
+            );
+        }
+    }
+}
+
+pub(super) fn take_document_snapshot_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<DocumentSnapshotSummary, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let snapshot = DocumentSnapshot {
+        id: Uuid::new_v4().to_string(),
+        taken_at_ms: now_unix_millis(),
+        document_version: doc.document_version,
+        rope: doc.rope.clone(),
+    };
+    let summary = summarize_snapshot(&snapshot);
+    push_snapshot(&mut snapshots().entry(id).or_default(), snapshot);
+    Ok(summary)
+}
+
+// Newest first.
+pub(super) fn list_document_snapshots_impl(id: String) -> Vec<DocumentSnapshotSummary> {
+    snapshots()
+        .get(&id)
+        .map(|history| history.iter().rev().map(summarize_snapshot).collect())
+        .unwrap_or_default()
+}
+
+pub(super) fn snapshot_rope(id: &str, snapshot_id: &str) -> Result<Rope, String> {
+    snapshots()
+        .get(id)
+        .and_then(|history| {
+            history
+                .iter()
+                .find(|snapshot| snapshot.id == snapshot_id)
+                .map(|snapshot| snapshot.rope.clone())
+        })
+        .ok_or_else(|| "Snapshot not found".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_snapshot(document_version: u64, taken_at_ms: u64) -> DocumentSnapshot {
+        DocumentSnapshot {
+            id: format!("snapshot-{document_version}"),
+            taken_at_ms,
+            document_version,
+            rope: Rope::new(),
+        }
+    }
+
+    #[test]
+    fn is_snapshot_due_should_wait_for_changes_and_interval() {
+        let interval_ms = SNAPSHOT_INTERVAL.as_millis() as u64;
+        let history = vec![make_snapshot(3, 1_000)];
+
+        assert!(is_snapshot_due(&[], 0, 0));
+        assert!(!is_snapshot_due(&history, 3, 1_000 + interval_ms * 2));
+        assert!(!is_snapshot_due(&history, 4, 1_000 + interval_ms - 1));
+        assert!(is_snapshot_due(&history, 4, 1_000 + interval_ms));
+    }
+
+    #[test]
+    fn push_snapshot_should_drop_oldest_beyond_limit() {
+        let mut history = Vec::new();
+        for version in 0..(MAX_SNAPSHOTS_PER_DOCUMENT as u64 + 3) {
+            push_snapshot(&mut history, make_snapshot(version, version));
+        }
+
+        assert_eq!(history.len(), MAX_SNAPSHOTS_PER_DOCUMENT);
+        assert_eq!(history[0].document_version, 3);
+    }
+}
@@ -0,0 +1,402 @@
+// Streaming open for files too large to decode up front. Above
+// `STREAMING_FILE_THRESHOLD_BYTES` the file stays memory-mapped and opening
+// only records where each line starts; `get_visible_lines` decodes the
+// requested lines straight from the mapping and search walks it block by
+// block. Streaming documents live outside the editable document map, so they
+// are read-only and editing commands reject their ids; the frontend shows them
+// in a paged line view instead of the editor.
+//
+// Only LF and CRLF files in an ASCII-compatible encoding are streamed, since
+// lines are found by scanning for the LF byte. UTF-16, CR-only and binary
+// files still take the regular open path.
+
+use super::file_sniff::SniffedContent;
+use super::types::{FileDecodeStats, LineEndingCounts};
+use super::*;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// Lines are decoded and searched in blocks of roughly this many bytes. A match
+// never spans two blocks.
+const STREAMING_SEARCH_BLOCK_BYTES: usize = 8 * 1024 * 1024;
+const STREAMING_MAX_LINES_PER_REQUEST: usize = 5000;
+
+pub(super) struct StreamingDocument {
+    path: PathBuf,
+    // Kept open so a truncation on disk is noticed before the mapping is read.
+    file: File,
+    mmap: Mmap,
+    encoding: &'static Encoding,
+    line_ending: LineEnding,
+    // Byte offset where each line starts; the first entry skips the BOM.
+    line_starts: Vec<u64>,
+    decode_stats: FileDecodeStats,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingSearchMatch {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub line_text: String,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingSearchPayload {
+    pub matches: Vec<StreamingSearchMatch>,
+    // 1-based line to pass as `startLine` to continue, or None at the end.
+    pub next_line: Option<usize>,
+}
+
+fn streaming_documents() -> &'static DashMap<String, Arc<StreamingDocument>> {
+    static MAP: OnceLock<DashMap<String, Arc<StreamingDocument>>> = OnceLock::new();
+    MAP.get_or_init(DashMap::new)
+}
+
+fn streaming_document(id: &str) -> Option<Arc<StreamingDocument>> {
+    streaming_documents()
+        .get(id)
+        .map(|entry| Arc::clone(entry.value()))
+}
+
+pub(super) fn is_streaming_document(id: &str) -> bool {
+    streaming_documents().contains_key(id)
+}
+
+// Every LF ends a line, so the count matches what a rope would report for the
+// same LF or CRLF text, including the empty line after a trailing break.
+fn index_mapped_lines(bytes: &[u8], content_start: usize) -> (Vec<u64>, LineEndingCounts) {
+    let mut line_starts = vec![content_start as u64];
+    let mut counts = LineEndingCounts::default();
+
+    let content = &bytes[content_start.min(bytes.len())..];
+    for index in memchr::memchr2_iter(b'\n', b'\r', content).map(|index| index + content_start) {
+        if bytes[index] == b'\n' {
+            if index > content_start && bytes[index - 1] == b'\r' {
+                counts.crlf += 1;
+            } else {
+                counts.lf += 1;
+            }
+            line_starts.push(index as u64 + 1);
+        } else if bytes.get(index + 1) != Some(&b'\n') {
+            counts.cr += 1;
+        }
+    }
+
+    (line_starts, counts)
+}
+
+// Returns None when the file is small enough, or not suitable, for streaming.
+pub(super) fn map_streaming_document(
+    path: &Path,
+    share_mode: FileShareMode,
+) -> Result<Option<StreamingDocument>, String> {
+    let file = file_io::open_file_with_share_mode(path, share_mode, false)
+        .map_err(|error| file_io::describe_file_open_error(path, error))?;
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+    if size <= STREAMING_FILE_THRESHOLD_BYTES {
+        return Ok(None);
+    }
+
+    let mmap = unsafe { Mmap::map(&file).map_err(|e| e.to_string())? };
+    let sniffed = file_sniff::sniff_content(&mmap);
+    if matches!(sniffed, SniffedContent::Binary(_)) {
+        return Ok(None);
+    }
+    let (encoding, encoding_confidence) = file_io::detect_mapped_encoding(&mmap, sniffed);
+    if !encoding.is_ascii_compatible() {
+        return Ok(None);
+    }
+
+    let content_start = Encoding::for_bom(&mmap)
+        .map(|(_, bom_length)| bom_length)
+        .unwrap_or(0);
+    let (line_starts, line_ending_counts) = index_mapped_lines(&mmap, content_start);
+    if line_ending_counts.cr > line_ending_counts.lf + line_ending_counts.crlf {
+        return Ok(None);
+    }
+
+    Ok(Some(StreamingDocument {
+        path: path.to_path_buf(),
+        file,
+        mmap,
+        encoding,
+        line_ending: file_io::detect_line_ending(line_ending_counts),
+        line_starts,
+        decode_stats: FileDecodeStats {
+            encoding_confidence: encoding_confidence.to_string(),
+            line_ending_counts,
+            has_bom: content_start > 0,
+            // Not known without decoding the whole file.
+            had_malformed_sequences: false,
+            raw_bytes_preserved: false,
+            detected_file_type: None,
+        },
+    }))
+}
+
+fn build_streaming_file_info(id: String, document: &StreamingDocument) -> FileInfo {
+    FileInfo {
+        id,
+        path: document.path.to_string_lossy().to_string(),
+        name: document
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        encoding: document.encoding.name().to_string(),
+        line_ending: document.line_ending.label().to_string(),
+        line_count: document.line_starts.len(),
+        size_bytes: document.mmap.len() as u64,
+        large_file_mode: true,
+        syntax_override: None,
+        suggested_syntax: None,
+        decode_stats: Some(document.decode_stats.clone()),
+        preview_mode: true,
+        streaming: true,
+        hex_view: false,
+    }
+}
+
+pub(super) fn register_streaming_document(document: StreamingDocument) -> FileInfo {
+    let id = Uuid::new_v4().to_string();
+    let file_info = build_streaming_file_info(id.clone(), &document);
+    streaming_documents().insert(id, Arc::new(document));
+    file_info
+}
+
+pub(super) fn find_streaming_document_by_path(path: &Path) -> Option<FileInfo> {
+    streaming_documents()
+        .iter()
+        .find(|entry| entry.value().path == path)
+        .map(|entry| build_streaming_file_info(entry.key().clone(), entry.value()))
+}
+
+// Reading a mapping whose file was truncated faults, so the length on disk is
+// checked first. `file` is the handle the mapping was made from.
+pub(super) fn ensure_mapping_not_truncated(
+    file: &File,
+    mmap: &Mmap,
+    path: &Path,
+) -> Result<(), String> {
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+    if size < mmap.len() as u64 {
+        return Err(format!(
+            "{} was truncated on disk; reopen it to continue",
+            path.to_string_lossy()
+        ));
+    }
+    Ok(())
+}
+
+impl StreamingDocument {
+    fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    fn ensure_unchanged_length(&self) -> Result<(), String> {
+        ensure_mapping_not_truncated(&self.file, &self.mmap, &self.path)
+    }
+
+    fn line_byte_offset(&self, line_index: usize) -> usize {
+        self.line_starts
+            .get(line_index)
+            .map(|offset| *offset as usize)
+            .unwrap_or(self.mmap.len())
+    }
+
+    // Decodes lines `start..end` (0-based, end exclusive) as LF text; each
+    // line keeps its trailing break, like a rope slice would.
+    fn decode_lines(&self, start: usize, end: usize) -> String {
+        let bytes = &self.mmap[self.line_byte_offset(start)..self.line_byte_offset(end)];
+        let (text, _) = self.encoding.decode_without_bom_handling(bytes);
+        if self.decode_stats.line_ending_counts.crlf > 0 {
+            text.replace("\r\n", "\n")
+        } else {
+            text.into_owned()
+        }
+    }
+
+    // First line index after `start` at which a search block should end.
+    fn block_end_line(&self, start: usize) -> usize {
+        let limit = self.line_byte_offset(start) + STREAMING_SEARCH_BLOCK_BYTES;
+        let end = self
+            .line_starts
+            .partition_point(|offset| (*offset as usize) <= limit);
+        end.max(start + 1).min(self.line_count())
+    }
+}
+
+pub(super) fn get_streaming_visible_lines(
+    id: &str,
+    start_line: usize,
+    end_line: usize,
+) -> Option<Result<String, String>> {
+    let document = streaming_document(id)?;
+    let len = document.line_count();
+    let start = start_line.min(len);
+    let end = end_line
+        .min(len)
+        .min(start.saturating_add(STREAMING_MAX_LINES_PER_REQUEST));
+    if start >= end {
+        return Some(Ok(String::new()));
+    }
+
+    Some(
+        document
+            .ensure_unchanged_length()
+            .map(|_| document.decode_lines(start, end)),
+    )
+}
+
+// Collects whole lines of matches from `start_line` (1-based) until at least
+// `max_results` were found; the last line's matches are never split, so the
+// result can run slightly over.
+fn search_streaming_lines(
+    document: &StreamingDocument,
+    keyword: &str,
+    mode: &str,
+    case_sensitive: bool,
+    start_line: usize,
+    max_results: usize,
+) -> Result<StreamingSearchPayload, String> {
+    // Reject a bad pattern before any of the file is decoded.
+    collect_search_matches_by_mode("", keyword, mode, case_sensitive)?;
+
+    let mut matches: Vec<StreamingSearchMatch> = Vec::new();
+    let mut block_start = start_line.max(1) - 1;
+
+    while block_start < document.line_count() {
+        let block_end = document.block_end_line(block_start);
+        let text = document.decode_lines(block_start, block_end);
+        let block_text = text.strip_suffix('\n').unwrap_or(&text);
+
+        for search_match in
+            collect_search_matches_by_mode(block_text, keyword, mode, case_sensitive)?
+        {
+            let line = block_start + search_match.line;
+            if matches.len() >= max_results && matches.last().is_some_and(|last| last.line != line)
+            {
+                return Ok(StreamingSearchPayload {
+                    matches,
+                    next_line: Some(line),
+                });
+            }
+            matches.push(StreamingSearchMatch {
+                line,
+                column: search_match.column,
+                text: search_match.text,
+                line_text: search_match.line_text,
+            });
+        }
+
+        block_start = block_end;
+    }
+
+    Ok(StreamingSearchPayload {
+        matches,
+        next_line: None,
+    })
+}
+
+pub(super) async fn search_streaming_document_impl(
+    id: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    start_line: Option<usize>,
+    max_results: usize,
+) -> Result<StreamingSearchPayload, String> {
+    let document =
+        streaming_document(&id).ok_or_else(|| "Streaming document not found".to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        document.ensure_unchanged_length()?;
+        search_streaming_lines(
+            &document,
+            &keyword,
+            &mode,
+            case_sensitive,
+            start_line.unwrap_or(1),
+            max_results.max(1),
+        )
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+pub(super) fn close_streaming_document(id: &str) -> bool {
+    streaming_documents().remove(id).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_document(content: &[u8]) -> StreamingDocument {
+        let path = std::env::temp_dir().join(format!("rutar-streaming-{}.log", Uuid::new_v4()));
+        fs::write(&path, content).expect("write temp file");
+        let file = File::open(&path).expect("open temp file");
+        let mmap = unsafe { Mmap::map(&file).expect("map temp file") };
+        let _ = fs::remove_file(&path);
+
+        let content_start = Encoding::for_bom(&mmap)
+            .map(|(_, bom_length)| bom_length)
+            .unwrap_or(0);
+        let (line_starts, line_ending_counts) = index_mapped_lines(&mmap, content_start);
+        StreamingDocument {
+            path,
+            file,
+            mmap,
+            encoding: encoding_rs::UTF_8,
+            line_ending: file_io::detect_line_ending(line_ending_counts),
+            line_starts,
+            decode_stats: FileDecodeStats {
+                encoding_confidence: "high".to_string(),
+                line_ending_counts,
+                has_bom: content_start > 0,
+                had_malformed_sequences: false,
+                raw_bytes_preserved: false,
+                detected_file_type: None,
+            },
+        }
+    }
+
+    #[test]
+    fn index_mapped_lines_should_skip_bom_and_count_line_endings() {
+        let (line_starts, counts) = index_mapped_lines(b"\xEF\xBB\xBFa\r\nb\nc", 3);
+
+        assert_eq!(line_starts, vec![3, 6, 8]);
+        assert_eq!((counts.crlf, counts.lf, counts.cr), (1, 1, 0));
+    }
+
+    #[test]
+    fn decode_lines_should_match_rope_slices() {
+        let document = make_document(b"first\r\nsecond\r\nthird\r\n");
+
+        assert_eq!(document.line_count(), 4);
+        assert_eq!(document.decode_lines(1, 3), "second\nthird\n");
+        assert_eq!(document.decode_lines(3, 4), "");
+    }
+
+    #[test]
+    fn search_streaming_lines_should_resume_at_the_next_matching_line() {
+        let document = make_document(b"error one\nok\nerror two error\nerror three\n");
+
+        let first = search_streaming_lines(&document, "error", "literal", true, 1, 2)
+            .expect("search should succeed");
+        let lines: Vec<usize> = first.matches.iter().map(|found| found.line).collect();
+        assert_eq!(lines, vec![1, 3, 3]);
+        assert_eq!(first.next_line, Some(4));
+
+        let rest = search_streaming_lines(&document, "error", "literal", true, 4, 2)
+            .expect("search should succeed");
+        assert_eq!(rest.matches.len(), 1);
+        assert_eq!(rest.matches[0].column, 1);
+        assert_eq!(rest.matches[0].line_text, "error three");
+        assert_eq!(rest.next_line, None);
+    }
+}
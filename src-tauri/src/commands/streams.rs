@@ -0,0 +1,358 @@
+// Documents fed by a live byte stream: stdin when launched as
+// `somecommand | rutar -`, or a named pipe. Data is appended to the end of the
+// document outside the undo history; `rutar://stream-appended` tells the
+// frontend to refresh and, in follow mode, scroll to the end.
+//
+// A named pipe is reopened after each writer disconnects, so it keeps
+// collecting output until the stream is stopped or its document is closed.
+// Stopping wakes a reader that is waiting for the next writer; a reader
+// blocked on an idle writer ends when that writer next writes or disconnects.
+
+use super::*;
+use dashmap::DashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{Emitter, Manager};
+
+const STREAM_READ_BUFFER_BYTES: usize = 64 * 1024;
+
+// Set from `rutar -` before the app is built; taken by the first open.
+static STDIN_STREAM_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+struct StreamState {
+    follow: AtomicBool,
+    stopped: AtomicBool,
+    pipe_path: Option<PathBuf>,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamAppendedEventPayload {
+    id: String,
+    line_count: usize,
+    document_version: u64,
+    follow: bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamEndedEventPayload {
+    id: String,
+}
+
+fn streams() -> &'static DashMap<String, StreamState> {
+    static MAP: OnceLock<DashMap<String, StreamState>> = OnceLock::new();
+    MAP.get_or_init(DashMap::new)
+}
+
+pub(super) fn request_stdin_stream_impl() {
+    STDIN_STREAM_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+// Turns raw chunks into LF text. A trailing CR or an incomplete UTF-8
+// sequence is held back until the next chunk decides what it belongs to.
+#[derive(Default)]
+struct StreamDecoder {
+    pending: Vec<u8>,
+}
+
+impl StreamDecoder {
+    fn push(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+        let mut complete = terminal::utf8_complete_prefix_len(&self.pending);
+        if complete > 0 && self.pending[complete - 1] == b'\r' {
+            complete -= 1;
+        }
+        let text = String::from_utf8_lossy(&self.pending[..complete]).to_string();
+        self.pending.drain(..complete);
+        text_utils::normalize_to_lf(&text)
+    }
+
+    fn finish(&mut self) -> String {
+        let text = String::from_utf8_lossy(&self.pending).to_string();
+        self.pending.clear();
+        text_utils::normalize_to_lf(&text)
+    }
+}
+
+// Returns false once the document is gone, which ends the stream.
+fn append_stream_text(app: &AppHandle, id: &str, text: &str) -> bool {
+    let state = app.state::<AppState>();
+    let Some(mut doc) = state.documents.get_mut(id) else {
+        return false;
+    };
+    if text.is_empty() {
+        return true;
+    }
+
+    let mut engine = doc.engine();
+    if engine.append_unrecorded(text).is_err() {
+        return false;
+    }
+    let change = engine.finish();
+    let (line_count, document_version) = (change.line_count, change.document_version);
+    let rope = doc.rope.clone();
+    drop(doc);
+    let filter_session_updates =
+        search::advance_follow_filter_sessions(&rope, document_version, id);
+    clear_outdated_document_search_caches(id, document_version);
+
+    let follow = streams()
+        .get(id)
+        .map(|stream| stream.follow.load(Ordering::Relaxed))
+        .unwrap_or(false);
+    emit_stream_event(
+        app,
+        "rutar://stream-appended",
+        StreamAppendedEventPayload {
+            id: id.to_string(),
+            line_count,
+            document_version,
+            follow,
+        },
+    );
+    for update in filter_session_updates {
+        emit_stream_event(app, "rutar://filter-matches-appended", update.payload);
+        rule_alerts::dispatch_filter_rule_alerts(app, id, update.alert_hits);
+    }
+    true
+}
+
+fn emit_stream_event<P: serde::Serialize + Clone>(app: &AppHandle, event: &str, payload: P) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    if let Err(error) = window.emit(event, payload) {
+        eprintln!("failed to emit {event} event: {error}");
+    }
+}
+
+fn is_stream_active(id: &str) -> bool {
+    streams()
+        .get(id)
+        .is_some_and(|stream| !stream.stopped.load(Ordering::Relaxed))
+}
+
+// Copies one reader into the document; returns false when the stream should
+// not continue with another reader.
+fn pump_stream(app: &AppHandle, id: &str, mut reader: impl Read) -> bool {
+    let mut buffer = vec![0u8; STREAM_READ_BUFFER_BYTES];
+    let mut decoder = StreamDecoder::default();
+
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => {
+                let text = decoder.push(&buffer[..read]);
+                if !append_stream_text(app, id, &text) || !is_stream_active(id) {
+                    return false;
+                }
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+    }
+
+    append_stream_text(app, id, &decoder.finish()) && is_stream_active(id)
+}
+
+fn spawn_stream_reader(
+    app: AppHandle,
+    id: String,
+    open_reader: impl Fn() -> Option<Box<dyn Read + Send>> + Send + 'static,
+    reopen: bool,
+) {
+    let _ = std::thread::Builder::new()
+        .name("rutar-stream-reader".to_string())
+        .spawn(move || {
+            while let Some(reader) = open_reader() {
+                if !pump_stream(&app, &id, reader) || !reopen {
+                    break;
+                }
+            }
+
+            streams().remove(&id);
+            emit_stream_event(&app, "rutar://stream-ended", StreamEndedEventPayload { id });
+        });
+}
+
+fn create_stream_document(
+    state: State<'_, AppState>,
+    name: String,
+    pipe_path: Option<PathBuf>,
+) -> Result<FileInfo, String> {
+    let mut file_info = file_io::new_file_impl(state, None)?;
+    file_info.name = name;
+    streams().insert(
+        file_info.id.clone(),
+        StreamState {
+            follow: AtomicBool::new(true),
+            stopped: AtomicBool::new(false),
+            pipe_path,
+        },
+    );
+    Ok(file_info)
+}
+
+// Returns None unless Rutar was started as `rutar -`; the frontend asks once
+// on startup.
+pub(super) fn open_stdin_stream_impl(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<FileInfo>, String> {
+    if !STDIN_STREAM_REQUESTED.swap(false, Ordering::Relaxed) {
+        return Ok(None);
+    }
+
+    let file_info = create_stream_document(state, "stdin".to_string(), None)?;
+    let stdin_taken = AtomicBool::new(false);
+    spawn_stream_reader(
+        app,
+        file_info.id.clone(),
+        move || {
+            (!stdin_taken.swap(true, Ordering::Relaxed))
+                .then(|| Box::new(std::io::stdin()) as Box<dyn Read + Send>)
+        },
+        false,
+    );
+    Ok(Some(file_info))
+}
+
+#[cfg(unix)]
+fn ensure_named_pipe(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.file_type().is_fifo() => Ok(()),
+        Ok(_) => Err("Path is not a named pipe".to_string()),
+        Err(_) => {
+            use std::os::unix::ffi::OsStrExt;
+
+            let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+                .map_err(|_| "Pipe path must not contain NUL".to_string())?;
+            // SAFETY: `c_path` is a valid NUL-terminated string that outlives the call.
+            if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } == 0 {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Failed to create named pipe: {}",
+                    std::io::Error::last_os_error()
+                ))
+            }
+        }
+    }
+}
+
+// A reader waiting in open() for the next writer only returns once one
+// connects, so connect and leave at once. Non-blocking, this fails instead of
+// hanging when nobody is reading.
+#[cfg(unix)]
+fn wake_pipe_reader(path: &Path) {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let _ = fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path);
+}
+
+// Windows pipe servers are the writers; the reader never waits in open().
+#[cfg(not(unix))]
+fn wake_pipe_reader(_path: &Path) {}
+
+// On Windows the pipe is served by the writing process; it must already exist.
+#[cfg(not(unix))]
+fn ensure_named_pipe(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        Ok(())
+    } else {
+        Err("Named pipe not found".to_string())
+    }
+}
+
+pub(super) fn open_named_pipe_stream_impl(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<FileInfo, String> {
+    let path = path.trim().to_string();
+    if path.is_empty() {
+        return Err("Pipe path must not be empty".to_string());
+    }
+    ensure_named_pipe(Path::new(&path))?;
+
+    let name = Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+    let file_info = create_stream_document(
+        state,
+        format!("{} (pipe)", name),
+        Some(PathBuf::from(&path)),
+    )?;
+    let id = file_info.id.clone();
+    spawn_stream_reader(
+        app,
+        file_info.id.clone(),
+        move || {
+            // Opening blocks until a writer connects.
+            let file = fs::File::open(&path).ok()?;
+            is_stream_active(&id).then(|| Box::new(file) as Box<dyn Read + Send>)
+        },
+        true,
+    );
+    Ok(file_info)
+}
+
+pub(super) fn set_stream_follow_impl(id: String, follow: bool) -> Result<(), String> {
+    let stream = streams()
+        .get(&id)
+        .ok_or_else(|| "Stream not found".to_string())?;
+    stream.follow.store(follow, Ordering::Relaxed);
+    Ok(())
+}
+
+// The reader notices on its next chunk or reconnect; a pipe reader waiting
+// for a writer is woken so it can see the stop.
+pub(super) fn stop_stream_impl(id: String) -> bool {
+    let Some(pipe_path) = streams().get(&id).map(|stream| {
+        stream.stopped.store(true, Ordering::Relaxed);
+        stream.pipe_path.clone()
+    }) else {
+        return false;
+    };
+
+    if let Some(path) = pipe_path {
+        wake_pipe_reader(&path);
+    }
+    true
+}
+
+pub(super) fn close_stream_for_document(id: &str) {
+    stop_stream_impl(id.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_decoder_should_hold_back_split_crlf_and_utf8() {
+        let mut decoder = StreamDecoder::default();
+        let bytes = "a\r\n中b".as_bytes();
+
+        assert_eq!(decoder.push(&bytes[..2]), "a");
+        assert_eq!(decoder.push(&bytes[2..4]), "\n");
+        assert_eq!(decoder.push(&bytes[4..]), "中b");
+        assert_eq!(decoder.push(b"c\r"), "c");
+        assert_eq!(decoder.finish(), "\n");
+    }
+
+    #[test]
+    fn stop_stream_should_report_unknown_ids() {
+        assert!(!stop_stream_impl("missing-stream".to_string()));
+        assert!(set_stream_follow_impl("missing-stream".to_string(), false).is_err());
+    }
+}
@@ -0,0 +1,973 @@
+// Key paths for structured documents (JSON, YAML, TOML, XML and INI).
+//
+// Paths are rendered like `services.web.ports[0]`; keys that are not plain
+// identifiers use the bracket form, e.g. `headers["content-type"]`. INI paths
+// are `section.key`, or just `key` before the first section.
+
+use super::ini::{collect_ini_entries, parse_ini_lines, IniEntry, IniLine, IniLineKind};
+use super::outline::{parse_source_tree, tree_sitter_language_for_syntax};
+use super::syntax::resolve_document_syntax_key;
+use crate::state::AppState;
+use ropey::Rope;
+use tauri::State;
+use tree_sitter::{Node, Tree};
+
+const HOVER_VALUE_PREVIEW_MAX_CHARS: usize = 120;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum StructuredFileType {
+    Json,
+    Yaml,
+    Toml,
+    Xml,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HoverInfoPayload {
+    pub path: String,
+    pub key: Option<String>,
+    pub value_kind: String,
+    pub value_preview: String,
+    // Enclosing XML element names from the root down; empty for other types.
+    pub element_chain: Vec<String>,
+    pub start_char: usize,
+    pub end_char: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+fn structured_file_type_for_syntax(syntax_key: &str) -> Option<StructuredFileType> {
+    match syntax_key {
+        "json" | "jsonc" => Some(StructuredFileType::Json),
+        "yaml" => Some(StructuredFileType::Yaml),
+        "toml" => Some(StructuredFileType::Toml),
+        "xml" => Some(StructuredFileType::Xml),
+        _ => None,
+    }
+}
+
+fn parse_structured_source(source: &str, syntax_key: &str) -> Option<Tree> {
+    let language = tree_sitter_language_for_syntax(syntax_key)?;
+    parse_source_tree(source, &language).ok()
+}
+
+fn node_text<'a>(node: Node<'_>, source: &'a str) -> &'a str {
+    source.get(node.start_byte()..node.end_byte()).unwrap_or("")
+}
+
+fn strip_quotes(value: &str) -> String {
+    let trimmed = value.trim();
+    for quote in ['"', '\''] {
+        if trimmed.len() >= 2 && trimmed.starts_with(quote) && trimmed.ends_with(quote) {
+            return trimmed[1..trimmed.len() - 1].to_string();
+        }
+    }
+
+    trimmed.to_string()
+}
+
+fn named_children(node: Node<'_>) -> Vec<Node<'_>> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| child.is_named() && !child.kind().contains("comment"))
+        .collect()
+}
+
+fn named_child_index(parent: Node<'_>, child: Node<'_>) -> Option<usize> {
+    named_children(parent)
+        .iter()
+        .position(|candidate| candidate.id() == child.id())
+}
+
+fn is_toml_key_kind(kind: &str) -> bool {
+    matches!(kind, "bare_key" | "quoted_key" | "dotted_key")
+}
+
+fn toml_key_segments(node: Node<'_>, source: &str) -> Vec<String> {
+    if node.kind() == "dotted_key" {
+        return named_children(node)
+            .into_iter()
+            .flat_map(|child| toml_key_segments(child, source))
+            .collect();
+    }
+
+    vec![strip_quotes(node_text(node, source))]
+}
+
+fn toml_header_key_node(node: Node<'_>) -> Option<Node<'_>> {
+    named_children(node)
+        .into_iter()
+        .find(|child| is_toml_key_kind(child.kind()))
+}
+
+fn toml_pair_value_node(node: Node<'_>) -> Option<Node<'_>> {
+    named_children(node)
+        .into_iter()
+        .find(|child| !is_toml_key_kind(child.kind()))
+}
+
+fn toml_table_array_index(node: Node<'_>, source: &str) -> usize {
+    let header = toml_header_key_node(node).map(|key| toml_key_segments(key, source));
+    let mut index = 0usize;
+    let mut sibling = node.prev_named_sibling();
+    while let Some(current) = sibling {
+        if current.kind() == "table_array_element"
+            && toml_header_key_node(current).map(|key| toml_key_segments(key, source)) == header
+        {
+            index += 1;
+        }
+        sibling = current.prev_named_sibling();
+    }
+
+    index
+}
+
+fn unwrap_yaml_node(mut node: Node<'_>) -> Node<'_> {
+    while matches!(node.kind(), "block_node" | "flow_node") {
+        let Some(next) = named_children(node)
+            .into_iter()
+            .find(|child| !matches!(child.kind(), "anchor" | "tag"))
+        else {
+            break;
+        };
+        node = next;
+    }
+
+    node
+}
+
+fn xml_element_name(node: Node<'_>, source: &str) -> Option<String> {
+    named_children(node)
+        .into_iter()
+        .filter(|child| matches!(child.kind(), "STag" | "EmptyElemTag"))
+        .find_map(|tag| {
+            named_children(tag)
+                .into_iter()
+                .find(|child| child.kind() == "Name")
+        })
+        .map(|name| node_text(name, source).trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn xml_element_index(node: Node<'_>, source: &str) -> Option<usize> {
+    let name = xml_element_name(node, source);
+    let parent = node.parent()?;
+    let siblings: Vec<Node<'_>> = named_children(parent)
+        .into_iter()
+        .filter(|sibling| sibling.kind() == "element" && xml_element_name(*sibling, source) == name)
+        .collect();
+    if siblings.len() < 2 {
+        return None;
+    }
+
+    siblings
+        .iter()
+        .position(|sibling| sibling.id() == node.id())
+}
+
+fn xml_attribute_name(node: Node<'_>, source: &str) -> Option<String> {
+    named_children(node)
+        .into_iter()
+        .find(|child| child.kind() == "Name")
+        .map(|name| format!("@{}", node_text(name, source).trim()))
+}
+
+struct PathStep<'tree> {
+    segments: Vec<PathSegment>,
+    // The node whose value the step addresses, e.g. a pair's value.
+    value_node: Node<'tree>,
+    key: Option<String>,
+}
+
+// Returns the path segments contributed by `node` when `child` is the
+// previous node on the walk up from the cursor.
+fn path_step<'tree>(
+    file_type: StructuredFileType,
+    node: Node<'tree>,
+    child: Option<Node<'tree>>,
+    source: &str,
+) -> Option<PathStep<'tree>> {
+    match (file_type, node.kind()) {
+        (StructuredFileType::Json, "pair") => {
+            let key = strip_quotes(node_text(node.child_by_field_name("key")?, source));
+            Some(PathStep {
+                segments: vec![PathSegment::Key(key.clone())],
+                value_node: node.child_by_field_name("value").unwrap_or(node),
+                key: Some(key),
+            })
+        }
+        (StructuredFileType::Json, "array")
+        | (StructuredFileType::Toml, "array")
+        | (StructuredFileType::Yaml, "flow_sequence") => {
+            let child = child?;
+            Some(PathStep {
+                segments: vec![PathSegment::Index(named_child_index(node, child)?)],
+                value_node: child,
+                key: None,
+            })
+        }
+        (StructuredFileType::Yaml, "block_mapping_pair" | "flow_pair") => {
+            let key_node = unwrap_yaml_node(node.child_by_field_name("key")?);
+            let key = strip_quotes(node_text(key_node, source));
+            Some(PathStep {
+                segments: vec![PathSegment::Key(key.clone())],
+                value_node: node
+                    .child_by_field_name("value")
+                    .map(unwrap_yaml_node)
+                    .unwrap_or(node),
+                key: Some(key),
+            })
+        }
+        (StructuredFileType::Yaml, "block_sequence") => {
+            let child = child?;
+            let items: Vec<Node<'_>> = named_children(node)
+                .into_iter()
+                .filter(|item| item.kind() == "block_sequence_item")
+                .collect();
+            let index = items.iter().position(|item| item.id() == child.id())?;
+            Some(PathStep {
+                segments: vec![PathSegment::Index(index)],
+                value_node: named_children(child)
+                    .into_iter()
+                    .next()
+                    .map(unwrap_yaml_node)
+                    .unwrap_or(child),
+                key: None,
+            })
+        }
+        (StructuredFileType::Toml, "pair") => {
+            let keys = toml_key_segments(toml_header_key_node(node)?, source);
+            Some(PathStep {
+                key: keys.last().cloned(),
+                segments: keys.into_iter().map(PathSegment::Key).collect(),
+                value_node: toml_pair_value_node(node).unwrap_or(node),
+            })
+        }
+        (StructuredFileType::Toml, "table" | "table_array_element") => {
+            let keys = toml_key_segments(toml_header_key_node(node)?, source);
+            let key = keys.last().cloned();
+            let mut segments: Vec<PathSegment> = keys.into_iter().map(PathSegment::Key).collect();
+            if node.kind() == "table_array_element" {
+                segments.push(PathSegment::Index(toml_table_array_index(node, source)));
+            }
+            Some(PathStep {
+                segments,
+                value_node: node,
+                key,
+            })
+        }
+        (StructuredFileType::Xml, "element") => {
+            let name = xml_element_name(node, source)?;
+            let mut segments = vec![PathSegment::Key(name.clone())];
+            if let Some(index) = xml_element_index(node, source) {
+                segments.push(PathSegment::Index(index));
+            }
+            Some(PathStep {
+                segments,
+                value_node: node,
+                key: Some(name),
+            })
+        }
+        (StructuredFileType::Xml, "Attribute") => {
+            let name = xml_attribute_name(node, source)?;
+            Some(PathStep {
+                segments: vec![PathSegment::Key(name.clone())],
+                value_node: named_children(node)
+                    .into_iter()
+                    .find(|child| child.kind() == "AttValue")
+                    .unwrap_or(node),
+                key: Some(name),
+            })
+        }
+        _ => None,
+    }
+}
+
+struct ResolvedPath<'tree> {
+    segments: Vec<PathSegment>,
+    target: Option<PathStep<'tree>>,
+    element_chain: Vec<String>,
+}
+
+fn resolve_path_for_node<'tree>(
+    file_type: StructuredFileType,
+    node: Node<'tree>,
+    source: &str,
+) -> ResolvedPath<'tree> {
+    let mut steps: Vec<PathStep<'tree>> = Vec::new();
+    let mut element_chain = Vec::new();
+    let mut child: Option<Node<'tree>> = None;
+    let mut current = Some(node);
+
+    while let Some(node) = current {
+        if let Some(step) = path_step(file_type, node, child, source) {
+            if file_type == StructuredFileType::Xml && node.kind() == "element" {
+                if let Some(PathSegment::Key(name)) = step.segments.first() {
+                    element_chain.push(name.clone());
+                }
+            }
+            steps.push(step);
+        }
+        child = Some(node);
+        current = node.parent();
+    }
+
+    element_chain.reverse();
+    let segments = steps
+        .iter()
+        .rev()
+        .flat_map(|step| step.segments.iter().cloned())
+        .collect();
+
+    ResolvedPath {
+        segments,
+        target: steps.into_iter().next(),
+        element_chain,
+    }
+}
+
+fn is_plain_path_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+
+    (first.is_alphabetic() || matches!(first, '_' | '$' | '@'))
+        && chars.all(|ch| ch.is_alphanumeric() || matches!(ch, '_' | '$' | '-'))
+}
+
+fn format_structured_path(segments: &[PathSegment]) -> String {
+    let mut output = String::new();
+
+    for segment in segments {
+        match segment {
+            PathSegment::Key(key) if is_plain_path_key(key) => {
+                if !output.is_empty() {
+                    output.push('.');
+                }
+                output.push_str(key);
+            }
+            PathSegment::Key(key) => {
+                output.push_str("[\"");
+                output.push_str(&key.replace('\\', "\\\\").replace('"', "\\\""));
+                output.push_str("\"]");
+            }
+            PathSegment::Index(index) => {
+                output.push_str(&format!("[{index}]"));
+            }
+        }
+    }
+
+    output
+}
+
+fn value_preview(node: Node<'_>, source: &str) -> String {
+    text_preview(node_text(node, source))
+}
+
+fn text_preview(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= HOVER_VALUE_PREVIEW_MAX_CHARS {
+        return collapsed;
+    }
+
+    let mut preview: String = collapsed
+        .chars()
+        .take(HOVER_VALUE_PREVIEW_MAX_CHARS)
+        .collect();
+    preview.push_str("...");
+    preview
+}
+
+fn char_position(rope: &Rope, char_offset: usize) -> (usize, usize) {
+    let line_index = rope.char_to_line(char_offset);
+    let line_start = rope.line_to_char(line_index);
+    let column = rope
+        .slice(line_start..char_offset)
+        .chars()
+        .map(char::len_utf16)
+        .sum::<usize>();
+    (line_index + 1, column + 1)
+}
+
+fn build_hover_info(
+    rope: &Rope,
+    source: &str,
+    tree: &Tree,
+    file_type: StructuredFileType,
+    char_offset: usize,
+) -> Option<HoverInfoPayload> {
+    let byte_offset = rope.char_to_byte(char_offset.min(rope.len_chars()));
+    let node = tree
+        .root_node()
+        .named_descendant_for_byte_range(byte_offset, byte_offset)?;
+    let resolved = resolve_path_for_node(file_type, node, source);
+    let target = resolved.target?;
+
+    let start_char = rope.byte_to_char(target.value_node.start_byte());
+    let (line, column) = char_position(rope, start_char);
+
+    Some(HoverInfoPayload {
+        path: format_structured_path(&resolved.segments),
+        key: target.key,
+        value_kind: target.value_node.kind().to_string(),
+        value_preview: value_preview(target.value_node, source),
+        element_chain: resolved.element_chain,
+        start_char,
+        end_char: rope.byte_to_char(target.value_node.end_byte()),
+        line,
+        column,
+    })
+}
+
+fn ini_line_char(rope: &Rope, line_index: usize, text: &str, byte_offset: usize) -> usize {
+    rope.line_to_char(line_index) + text[..byte_offset].chars().count()
+}
+
+fn ini_entry_segments(entry: &IniEntry<'_>) -> Vec<PathSegment> {
+    entry
+        .section
+        .into_iter()
+        .chain(std::iter::once(entry.key))
+        .map(|key| PathSegment::Key(key.to_string()))
+        .collect()
+}
+
+fn ini_entry_value_range(
+    rope: &Rope,
+    lines: &[IniLine<'_>],
+    entry: &IniEntry<'_>,
+) -> (usize, usize) {
+    (
+        ini_line_char(
+            rope,
+            entry.line_index,
+            lines[entry.line_index].text,
+            entry.value_start,
+        ),
+        ini_line_char(
+            rope,
+            entry.end_line_index,
+            lines[entry.end_line_index].text,
+            entry.value_end,
+        ),
+    )
+}
+
+fn ini_section_range(rope: &Rope, line_index: usize, text: &str, name: &str) -> (usize, usize) {
+    let name_start = text.find(name).unwrap_or(0);
+    (
+        ini_line_char(rope, line_index, text, name_start),
+        ini_line_char(rope, line_index, text, name_start + name.len()),
+    )
+}
+
+fn build_ini_hover_info(rope: &Rope, source: &str, char_offset: usize) -> Option<HoverInfoPayload> {
+    let line_index = rope.char_to_line(char_offset.min(rope.len_chars()));
+    let lines = parse_ini_lines(source);
+    let line = lines.get(line_index)?;
+
+    let (path, key, value_kind, start_char, end_char) = match line.kind {
+        IniLineKind::Section { name } => {
+            let (start_char, end_char) = ini_section_range(rope, line_index, line.text, name);
+            (
+                format_structured_path(&[PathSegment::Key(name.to_string())]),
+                name.to_string(),
+                "section",
+                start_char,
+                end_char,
+            )
+        }
+        IniLineKind::Entry { .. } | IniLineKind::Continuation => {
+            let entry = collect_ini_entries(&lines).into_iter().find(|entry| {
+                entry.line_index <= line_index && line_index <= entry.end_line_index
+            })?;
+            let (start_char, end_char) = ini_entry_value_range(rope, &lines, &entry);
+            (
+                format_structured_path(&ini_entry_segments(&entry)),
+                entry.key.to_string(),
+                "value",
+                start_char,
+                end_char,
+            )
+        }
+        IniLineKind::Blank | IniLineKind::Comment => return None,
+    };
+    let (line, column) = char_position(rope, start_char);
+
+    Some(HoverInfoPayload {
+        path,
+        key: Some(key),
+        value_kind: value_kind.to_string(),
+        value_preview: text_preview(&rope.slice(start_char..end_char).to_string()),
+        element_chain: Vec::new(),
+        start_char,
+        end_char,
+        line,
+        column,
+    })
+}
+
+// Dotted `.properties` keys also match when written as a plain path, so
+// `server.port` finds the key `server.port` as well as `[server]` / `port`.
+fn find_ini_path(
+    rope: &Rope,
+    source: &str,
+    segments: &[PathSegment],
+) -> Option<StructuredPathLocation> {
+    let keys: Vec<&str> = segments
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Key(key) => Some(key.as_str()),
+            PathSegment::Index(_) => None,
+        })
+        .collect::<Option<_>>()?;
+    let dotted = keys.join(".");
+    let lines = parse_ini_lines(source);
+
+    let (path, start_char, end_char) = if let Some(entry) =
+        collect_ini_entries(&lines).into_iter().find(|entry| {
+            let entry_keys: Vec<&str> = entry
+                .section
+                .into_iter()
+                .chain(std::iter::once(entry.key))
+                .collect();
+            entry_keys == keys || entry_keys.join(".") == dotted
+        }) {
+        let (start_char, end_char) = ini_entry_value_range(rope, &lines, &entry);
+        (
+            format_structured_path(&ini_entry_segments(&entry)),
+            start_char,
+            end_char,
+        )
+    } else {
+        let (line_index, line, name) =
+            lines
+                .iter()
+                .enumerate()
+                .find_map(|(line_index, line)| match line.kind {
+                    IniLineKind::Section { name } if [name] == keys.as_slice() => {
+                        Some((line_index, line, name))
+                    }
+                    _ => None,
+                })?;
+        let (start_char, end_char) = ini_section_range(rope, line_index, line.text, name);
+        (
+            format_structured_path(&[PathSegment::Key(name.to_string())]),
+            start_char,
+            end_char,
+        )
+    };
+    let (line, column) = char_position(rope, start_char);
+    let (end_line, end_column) = char_position(rope, end_char);
+
+    Some(StructuredPathLocation {
+        path,
+        start_char,
+        end_char,
+        line,
+        column,
+        end_line,
+        end_column,
+    })
+}
+
+fn parse_path_expression(expression: &str) -> Result<Vec<PathSegment>, String> {
+    let invalid = || format!("Invalid path expression: {expression}");
+    let chars: Vec<char> = expression.trim().chars().collect();
+    let mut segments = Vec::new();
+    let mut index = 0usize;
+
+    if chars.first() == Some(&'$') {
+        index += 1;
+    }
+
+    while index < chars.len() {
+        match chars[index] {
+            '.' => {
+                index += 1;
+                if index >= chars.len() || matches!(chars[index], '.' | '[') {
+                    return Err(invalid());
+                }
+            }
+            '[' => {
+                index += 1;
+                let quote = chars
+                    .get(index)
+                    .copied()
+                    .filter(|ch| matches!(ch, '"' | '\''));
+                if let Some(quote) = quote {
+                    index += 1;
+                    let mut key = String::new();
+                    loop {
+                        match chars.get(index) {
+                            Some('\\') => {
+                                key.push(*chars.get(index + 1).ok_or_else(invalid)?);
+                                index += 2;
+                            }
+                            Some(ch) if *ch == quote => {
+                                index += 1;
+                                break;
+                            }
+                            Some(ch) => {
+                                key.push(*ch);
+                                index += 1;
+                            }
+                            None => return Err(invalid()),
+                        }
+                    }
+                    if chars.get(index) != Some(&']') {
+                        return Err(invalid());
+                    }
+                    segments.push(PathSegment::Key(key));
+                } else {
+                    let digits_start = index;
+                    while chars.get(index).is_some_and(char::is_ascii_digit) {
+                        index += 1;
+                    }
+                    if digits_start == index || chars.get(index) != Some(&']') {
+                        return Err(invalid());
+                    }
+                    let value: String = chars[digits_start..index].iter().collect();
+                    segments.push(PathSegment::Index(value.parse().map_err(|_| invalid())?));
+                }
+                index += 1;
+            }
+            _ => {
+                let key_start = index;
+                while index < chars.len() && !matches!(chars[index], '.' | '[') {
+                    index += 1;
+                }
+                let key: String = chars[key_start..index].iter().collect();
+                segments.push(PathSegment::Key(key.trim().to_string()));
+            }
+        }
+    }
+
+    if segments.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(segments)
+}
+
+// XML paths only carry an index when an element has same-name siblings, so
+// `item[0]` and `item` address the same element there.
+fn normalize_path_segments(
+    file_type: StructuredFileType,
+    segments: &[PathSegment],
+) -> Vec<PathSegment> {
+    if file_type != StructuredFileType::Xml {
+        return segments.to_vec();
+    }
+
+    let mut output: Vec<PathSegment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        let follows_key = matches!(output.last(), Some(PathSegment::Key(_)));
+        if follows_key && *segment == PathSegment::Index(0) {
+            continue;
+        }
+        output.push(segment.clone());
+    }
+
+    output
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredPathLocation {
+    pub path: String,
+    pub start_char: usize,
+    pub end_char: usize,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+fn find_structured_path(
+    rope: &Rope,
+    source: &str,
+    tree: &Tree,
+    file_type: StructuredFileType,
+    segments: &[PathSegment],
+) -> Option<StructuredPathLocation> {
+    let expected = normalize_path_segments(file_type, segments);
+    let mut cursor = tree.walk();
+    let mut visited_children = false;
+
+    loop {
+        let node = cursor.node();
+        if !visited_children && node.is_named() {
+            let resolved = resolve_path_for_node(file_type, node, source);
+            if normalize_path_segments(file_type, &resolved.segments) == expected {
+                if let Some(target) = resolved.target {
+                    let start_char = rope.byte_to_char(target.value_node.start_byte());
+                    let end_char = rope.byte_to_char(target.value_node.end_byte());
+                    let (line, column) = char_position(rope, start_char);
+                    let (end_line, end_column) = char_position(rope, end_char);
+                    return Some(StructuredPathLocation {
+                        path: format_structured_path(&resolved.segments),
+                        start_char,
+                        end_char,
+                        line,
+                        column,
+                        end_line,
+                        end_column,
+                    });
+                }
+            }
+        }
+
+        if !visited_children && cursor.goto_first_child() {
+            continue;
+        }
+        if cursor.goto_next_sibling() {
+            visited_children = false;
+            continue;
+        }
+        if !cursor.goto_parent() {
+            return None;
+        }
+        visited_children = true;
+    }
+}
+
+struct StructuredSource {
+    rope: Rope,
+    source: String,
+    tree: Tree,
+    file_type: StructuredFileType,
+}
+
+fn load_document_rope(state: &State<'_, AppState>, id: &str) -> Result<(Rope, String), String> {
+    let doc = state
+        .documents
+        .get(id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    Ok((doc.rope.clone(), resolve_document_syntax_key(&doc)))
+}
+
+fn load_structured_source(rope: Rope, syntax_key: &str) -> Option<StructuredSource> {
+    let file_type = structured_file_type_for_syntax(syntax_key)?;
+    let source: String = rope.chunks().collect();
+    let tree = parse_structured_source(&source, syntax_key)?;
+
+    Some(StructuredSource {
+        rope,
+        source,
+        tree,
+        file_type,
+    })
+}
+
+pub(super) fn get_hover_info_impl(
+    state: State<'_, AppState>,
+    id: String,
+    char_offset: usize,
+) -> Result<Option<HoverInfoPayload>, String> {
+    let (rope, syntax_key) = load_document_rope(&state, &id)?;
+    if syntax_key == "ini" {
+        let source: String = rope.chunks().collect();
+        return Ok(build_ini_hover_info(&rope, &source, char_offset));
+    }
+    let Some(structured) = load_structured_source(rope, &syntax_key) else {
+        return Ok(None);
+    };
+
+    Ok(build_hover_info(
+        &structured.rope,
+        &structured.source,
+        &structured.tree,
+        structured.file_type,
+        char_offset,
+    ))
+}
+
+pub(super) fn resolve_structured_path_impl(
+    state: State<'_, AppState>,
+    id: String,
+    path_expression: String,
+) -> Result<Option<StructuredPathLocation>, String> {
+    let segments = parse_path_expression(&path_expression)?;
+    let (rope, syntax_key) = load_document_rope(&state, &id)?;
+    if syntax_key == "ini" {
+        let source: String = rope.chunks().collect();
+        return Ok(find_ini_path(&rope, &source, &segments));
+    }
+    let structured = load_structured_source(rope, &syntax_key)
+        .ok_or_else(|| "Unsupported structured document type".to_string())?;
+
+    Ok(find_structured_path(
+        &structured.rope,
+        &structured.source,
+        &structured.tree,
+        structured.file_type,
+        &segments,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hover_at(source: &str, syntax_key: &str, marker: &str) -> HoverInfoPayload {
+        let rope = Rope::from_str(source);
+        let tree = parse_structured_source(source, syntax_key).expect("source should parse");
+        let file_type = structured_file_type_for_syntax(syntax_key).expect("structured type");
+        let byte_offset = source.find(marker).expect("marker should exist");
+        build_hover_info(
+            &rope,
+            source,
+            &tree,
+            file_type,
+            rope.byte_to_char(byte_offset),
+        )
+        .expect("hover info should exist")
+    }
+
+    #[test]
+    fn hover_info_should_resolve_json_paths() {
+        let source = r#"{"services": {"web": {"ports": [80, 443], "a.b": true}}}"#;
+
+        let hover = hover_at(source, "json", "443");
+        assert_eq!(hover.path, "services.web.ports[1]");
+        assert_eq!(hover.value_preview, "443");
+        assert_eq!(hover.value_kind, "number");
+
+        let hover = hover_at(source, "json", "true");
+        assert_eq!(hover.path, r#"services.web["a.b"]"#);
+        assert_eq!(hover.key.as_deref(), Some("a.b"));
+    }
+
+    #[test]
+    fn hover_info_should_resolve_yaml_paths() {
+        let source = "services:\n  web:\n    ports:\n      - 80\n      - \"8080:80\"\n";
+
+        let hover = hover_at(source, "yaml", "8080");
+        assert_eq!(hover.path, "services.web.ports[1]");
+        assert_eq!(hover.value_preview, "\"8080:80\"");
+
+        let hover = hover_at(source, "yaml", "web");
+        assert_eq!(hover.path, "services.web");
+        assert_eq!(hover.key.as_deref(), Some("web"));
+    }
+
+    #[test]
+    fn hover_info_should_resolve_toml_tables_and_arrays() {
+        let source = "[server]\nhost = \"localhost\"\n\n[[server.routes]]\npath = \"/\"\n\n[[server.routes]]\npath = \"/api\"\n";
+
+        let hover = hover_at(source, "toml", "localhost");
+        assert_eq!(hover.path, "server.host");
+
+        let hover = hover_at(source, "toml", "/api");
+        assert_eq!(hover.path, "server.routes[1].path");
+    }
+
+    #[test]
+    fn hover_info_should_report_xml_element_chain() {
+        let source = "<root><item id=\"a\"/><item id=\"b\"><name>x</name></item></root>";
+
+        let hover = hover_at(source, "xml", "x</name>");
+        assert_eq!(hover.path, "root.item[1].name");
+        assert_eq!(hover.element_chain, vec!["root", "item", "name"]);
+
+        let hover = hover_at(source, "xml", "\"b\"");
+        assert_eq!(hover.path, "root.item[1].@id");
+        assert_eq!(hover.value_preview, "\"b\"");
+    }
+
+    fn locate(source: &str, syntax_key: &str, expression: &str) -> Option<StructuredPathLocation> {
+        let rope = Rope::from_str(source);
+        let tree = parse_structured_source(source, syntax_key).expect("source should parse");
+        let file_type = structured_file_type_for_syntax(syntax_key).expect("structured type");
+        let segments = parse_path_expression(expression).expect("expression should parse");
+        find_structured_path(&rope, source, &tree, file_type, &segments)
+    }
+
+    #[test]
+    fn hover_info_should_resolve_ini_sections_and_keys() {
+        let source = "name = demo\n[server]\nhost = 0.0.0.0\nlist = a, \\\n  b\n";
+        let rope = Rope::from_str(source);
+        let hover_at = |marker: &str| {
+            let char_offset = rope.byte_to_char(source.find(marker).expect("marker"));
+            build_ini_hover_info(&rope, source, char_offset).expect("hover info should exist")
+        };
+
+        assert_eq!(hover_at("demo").path, "name");
+        assert_eq!(hover_at("0.0.0.0").path, "server.host");
+        assert_eq!(hover_at("0.0.0.0").value_preview, "0.0.0.0");
+        assert_eq!(hover_at("[server]").value_kind, "section");
+
+        let hover = hover_at("  b");
+        assert_eq!(hover.path, "server.list");
+        assert_eq!(hover.value_preview, "a, \\ b");
+    }
+
+    #[test]
+    fn find_ini_path_should_match_sections_and_dotted_keys() {
+        let source = "server.port=8080\n[db]\nhost=x\n";
+        let rope = Rope::from_str(source);
+        let locate = |expression: &str| {
+            let segments = parse_path_expression(expression).expect("path");
+            find_ini_path(&rope, source, &segments)
+        };
+
+        let location = locate("server.port").expect("dotted key");
+        assert_eq!((location.line, location.column), (1, 13));
+        assert_eq!(locate("db.host").expect("section key").line, 3);
+        assert_eq!(locate("db").expect("section").path, "db");
+        assert!(locate("db.port").is_none());
+    }
+
+    #[test]
+    fn parse_path_expression_should_support_dotted_and_bracket_forms() {
+        assert_eq!(
+            parse_path_expression("$.services.web['a.b'][2][\"q\\\"x\"]").expect("parse"),
+            vec![
+                PathSegment::Key("services".to_string()),
+                PathSegment::Key("web".to_string()),
+                PathSegment::Key("a.b".to_string()),
+                PathSegment::Index(2),
+                PathSegment::Key("q\"x".to_string()),
+            ]
+        );
+
+        for invalid in ["", "$", "a..b", "a[x]", "a[\"b", "a["] {
+            assert!(
+                parse_path_expression(invalid).is_err(),
+                "expected {invalid:?} to be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn find_structured_path_should_locate_json_and_yaml_values() {
+        let json = "{\n  \"services\": {\"web\": {\"ports\": [80, 443]}}\n}";
+        let location = locate(json, "json", "services.web.ports[1]").expect("json path");
+        assert_eq!(&json[location.start_char..location.end_char], "443");
+        assert_eq!((location.line, location.column), (2, 38));
+
+        let yaml = "services:\n  web:\n    image: nginx\n";
+        let location = locate(yaml, "yaml", "$.services.web.image").expect("yaml path");
+        assert_eq!(&yaml[location.start_char..location.end_char], "nginx");
+        assert_eq!(location.path, "services.web.image");
+
+        assert!(locate(yaml, "yaml", "services.db").is_none());
+    }
+
+    #[test]
+    fn find_structured_path_should_treat_missing_xml_index_as_first_element() {
+        let source = "<root><item id=\"a\"/></root>";
+
+        let location = locate(source, "xml", "root.item[0].@id").expect("xml path");
+        assert_eq!(&source[location.start_char..location.end_char], "\"a\"");
+    }
+}
@@ -1,3 +1,59 @@
+use crate::state::Document;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+
+// Copy of `syntax_extension_overrides` from the config, refreshed whenever the
+// config is loaded or saved so detection never reads the file itself.
+static SYNTAX_EXTENSION_OVERRIDES: OnceLock<RwLock<BTreeMap<String, String>>> = OnceLock::new();
+
+const SUPPORTED_SYNTAX_KEYS: &[&str] = &[
+    "plain_text",
+    "markdown",
+    "javascript",
+    "typescript",
+    "rust",
+    "python",
+    "json",
+    "jsonc",
+    "dockerfile",
+    "makefile",
+    "ini",
+    "html",
+    "css",
+    "bash",
+    "zsh",
+    "toml",
+    "yaml",
+    "xml",
+    "c",
+    "cpp",
+    "go",
+    "java",
+    "csharp",
+    "hcl",
+    "lua",
+    "php",
+    "kotlin",
+    "powershell",
+    "ruby",
+    "sql",
+    "swift",
+    "diff",
+    "csv",
+];
+
+fn syntax_extension_overrides() -> &'static RwLock<BTreeMap<String, String>> {
+    SYNTAX_EXTENSION_OVERRIDES.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+pub(super) fn set_syntax_extension_overrides(overrides: BTreeMap<String, String>) {
+    let mut current = syntax_extension_overrides()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *current = overrides;
+}
+
 pub(super) fn normalize_syntax_override(
     syntax_override: Option<&str>,
 ) -> Result<Option<String>, String> {
@@ -17,46 +73,118 @@ pub(super) fn normalize_syntax_override(
     Err(format!("Unsupported syntax override: {raw_value}"))
 }
 
+fn supported_syntax_key(syntax_key: &str) -> Option<&'static str> {
+    SUPPORTED_SYNTAX_KEYS
+        .iter()
+        .copied()
+        .find(|candidate| *candidate == syntax_key)
+}
+
 fn is_supported_syntax_key(syntax_key: &str) -> bool {
-    matches!(
-        syntax_key,
-        "plain_text"
-            | "markdown"
-            | "javascript"
-            | "typescript"
-            | "rust"
-            | "python"
-            | "json"
-            | "jsonc"
-            | "dockerfile"
-            | "makefile"
-            | "ini"
-            | "html"
-            | "css"
-            | "bash"
-            | "zsh"
-            | "toml"
-            | "yaml"
-            | "xml"
-            | "c"
-            | "cpp"
-            | "go"
-            | "java"
-            | "csharp"
-            | "hcl"
-            | "lua"
-            | "php"
-            | "kotlin"
-            | "powershell"
-            | "ruby"
-            | "sql"
-            | "swift"
-    )
+    supported_syntax_key(syntax_key).is_some()
+}
+
+// Mirrors `detectSyntaxKeyFromTab` in the frontend so backend features agree
+// with the editor on which language a file is.
+pub(super) fn detect_syntax_key_from_path(path: &Path) -> &'static str {
+    let overrides = syntax_extension_overrides()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    detect_syntax_key_with_overrides(path, &overrides)
+}
+
+fn detect_syntax_key_with_overrides(
+    path: &Path,
+    overrides: &BTreeMap<String, String>,
+) -> &'static str {
+    let Some(file_name) = path
+        .file_name()
+        .and_then(|value| value.to_str())
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+    else {
+        return "plain_text";
+    };
+
+    match file_name.as_str() {
+        "dockerfile" | "containerfile" => return "dockerfile",
+        "makefile" | "gnumakefile" => return "makefile",
+        ".zshenv" | ".zprofile" | ".zshrc" | ".zlogin" | ".zlogout" => return "zsh",
+        "gemfile" | "rakefile" => return "ruby",
+        _ => {}
+    }
+
+    let Some((_, extension)) = file_name.rsplit_once('.') else {
+        return "plain_text";
+    };
+
+    if let Some(syntax_key) = overrides
+        .get(extension)
+        .and_then(|syntax_key| supported_syntax_key(syntax_key))
+    {
+        return syntax_key;
+    }
+
+    match extension {
+        "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "ts" | "tsx" | "mts" | "cts" => "typescript",
+        "rs" => "rust",
+        "py" | "pyw" => "python",
+        "json" => "json",
+        "jsonc" => "jsonc",
+        "md" | "markdown" | "mdown" | "mkd" | "mkdn" | "mdwn" | "mdtxt" | "mdtext" | "rmd"
+        | "qmd" | "mdx" => "markdown",
+        "dockerfile" => "dockerfile",
+        "ini" | "cfg" | "conf" | "cnf" | "properties" => "ini",
+        "html" | "htm" | "xhtml" => "html",
+        "css" | "scss" | "sass" | "less" => "css",
+        "sh" | "bash" => "bash",
+        "zsh" => "zsh",
+        "mk" | "mak" => "makefile",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "xml" | "svg" => "xml",
+        "c" | "h" => "c",
+        "cc" | "cp" | "cpp" | "cxx" | "c++" | "hh" | "hpp" | "hxx" => "cpp",
+        "go" => "go",
+        "java" => "java",
+        "cs" => "csharp",
+        "hcl" | "tf" | "tfvars" => "hcl",
+        "lua" => "lua",
+        "php" | "phtml" => "php",
+        "kt" | "kts" => "kotlin",
+        "ps1" | "psd1" | "psm1" => "powershell",
+        "rb" | "rake" | "gemspec" | "ru" => "ruby",
+        "sql" => "sql",
+        "swift" => "swift",
+        "diff" | "patch" => "diff",
+        "csv" | "tsv" => "csv",
+        _ => "plain_text",
+    }
+}
+
+pub(super) fn resolve_document_syntax_key(doc: &Document) -> String {
+    // Preview documents are never parsed.
+    if doc.preview_mode {
+        return "plain_text".to_string();
+    }
+
+    if let Some(syntax_override) = doc.syntax_override.as_deref() {
+        return syntax_override.to_string();
+    }
+
+    doc.path
+        .as_deref()
+        .map(detect_syntax_key_from_path)
+        .unwrap_or("plain_text")
+        .to_string()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::normalize_syntax_override;
+    use super::super::outline::tree_sitter_language_for_syntax;
+    use super::{detect_syntax_key_from_path, normalize_syntax_override};
+    use std::path::Path;
 
     #[test]
     fn auto_and_empty_syntax_overrides_should_clear_override() {
@@ -87,6 +215,8 @@ mod tests {
             "powershell",
             "ruby",
             "sql",
+            "diff",
+            "csv",
         ] {
             assert_eq!(
                 normalize_syntax_override(Some(syntax_key)).expect("override should normalize"),
@@ -110,4 +240,64 @@ mod tests {
             .expect_err("unsupported override should fail");
         assert_eq!(error, "Unsupported syntax override: mermaid");
     }
+
+    #[test]
+    fn detect_syntax_key_from_path_should_match_frontend_detection() {
+        for (path, expected) in [
+            ("src/main.rs", "rust"),
+            ("C:\\work\\App.TSX", "typescript"),
+            ("Dockerfile", "dockerfile"),
+            ("/home/user/.zshrc", "zsh"),
+            ("infra/main.tf", "hcl"),
+            ("fix-crash.patch", "diff"),
+            ("exports/report.tsv", "csv"),
+            ("notes", "plain_text"),
+            ("archive.tar.unknown", "plain_text"),
+        ] {
+            assert_eq!(
+                detect_syntax_key_from_path(Path::new(path)),
+                expected,
+                "unexpected syntax for {path}"
+            );
+        }
+    }
+
+    #[test]
+    fn detect_syntax_key_with_overrides_should_prefer_configured_extensions() {
+        let overrides = BTreeMap::from([
+            ("gradle".to_string(), "kotlin".to_string()),
+            ("rs".to_string(), "plain_text".to_string()),
+            ("tpl".to_string(), "unknown".to_string()),
+        ]);
+
+        assert_eq!(
+            detect_syntax_key_with_overrides(Path::new("build.Gradle"), &overrides),
+            "kotlin"
+        );
+        assert_eq!(
+            detect_syntax_key_with_overrides(Path::new("main.rs"), &overrides),
+            "plain_text"
+        );
+        assert_eq!(
+            detect_syntax_key_with_overrides(Path::new("page.tpl"), &overrides),
+            "plain_text"
+        );
+        assert_eq!(
+            detect_syntax_key_with_overrides(Path::new("Dockerfile"), &overrides),
+            "dockerfile"
+        );
+    }
+
+    #[test]
+    fn tree_sitter_language_should_exist_for_code_syntaxes_only() {
+        for syntax_key in ["rust", "python", "jsonc", "sql", "dockerfile", "powershell"] {
+            assert!(
+                tree_sitter_language_for_syntax(syntax_key).is_some(),
+                "expected language for {syntax_key}"
+            );
+        }
+
+        assert!(tree_sitter_language_for_syntax("plain_text").is_none());
+        assert!(tree_sitter_language_for_syntax("markdown").is_none());
+    }
 }
@@ -0,0 +1,223 @@
+// Guesses a syntax for files whose name says nothing about their content:
+// extensionless, `.log` and `.txt`. Only the first SYNTAX_SUGGESTION_SAMPLE_BYTES
+// are classified, and the result is a suggestion for the status bar rather
+// than an override.
+
+use super::*;
+use std::path::Path;
+
+const SYNTAX_SUGGESTION_SAMPLE_BYTES: usize = 16 * 1024;
+const SYNTAX_SUGGESTION_EXTENSIONS: [&str; 2] = ["log", "txt"];
+// Share of sampled lines that must fit a line-oriented format.
+const MIN_LINE_MATCH_RATIO: f64 = 0.9;
+const MIN_CSV_ROWS: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ContentKind {
+    Diff,
+    Xml,
+    JsonLines,
+    Ini,
+    Csv,
+}
+
+impl ContentKind {
+    fn syntax_key(self) -> Option<&'static str> {
+        match self {
+            ContentKind::Xml => Some("xml"),
+            ContentKind::JsonLines => Some("json"),
+            ContentKind::Ini => Some("ini"),
+            ContentKind::Diff => Some("diff"),
+            ContentKind::Csv => Some("csv"),
+        }
+    }
+}
+
+fn is_syntax_suggestion_candidate(path: &Path) -> bool {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        None => true,
+        Some(extension) => SYNTAX_SUGGESTION_EXTENSIONS
+            .iter()
+            .any(|candidate| extension.eq_ignore_ascii_case(candidate)),
+    }
+}
+
+fn syntax_suggestion_sample(rope: &Rope) -> (String, bool) {
+    let end_byte = rope.len_bytes().min(SYNTAX_SUGGESTION_SAMPLE_BYTES);
+    let end_char = rope.byte_to_char(end_byte);
+    (
+        rope.slice(..end_char).to_string(),
+        end_char < rope.len_chars(),
+    )
+}
+
+fn matches_enough_lines(lines: &[&str], predicate: impl Fn(&str) -> bool) -> bool {
+    if lines.is_empty() {
+        return false;
+    }
+
+    let matched = lines.iter().filter(|line| predicate(line)).count();
+    matched as f64 >= lines.len() as f64 * MIN_LINE_MATCH_RATIO
+}
+
+fn looks_like_diff(lines: &[&str]) -> bool {
+    if lines.iter().any(|line| line.starts_with("diff --git ")) {
+        return true;
+    }
+
+    lines.windows(3).any(|window| {
+        window[0].starts_with("--- ")
+            && window[1].starts_with("+++ ")
+            && window[2].starts_with("@@ ")
+    })
+}
+
+fn looks_like_xml(lines: &[&str]) -> bool {
+    let Some(first) = lines.first() else {
+        return false;
+    };
+    if first.starts_with("<?xml") {
+        return true;
+    }
+
+    first.starts_with('<')
+        && lines.last().is_some_and(|line| line.ends_with('>'))
+        && matches_enough_lines(lines, |line| line.starts_with('<'))
+}
+
+fn looks_like_json_lines(lines: &[&str]) -> bool {
+    matches_enough_lines(lines, |line| {
+        (line.starts_with('{') || line.starts_with('['))
+            && serde_json::from_str::<serde_json::Value>(line).is_ok()
+    })
+}
+
+fn looks_like_ini(lines: &[&str]) -> bool {
+    let is_section = |line: &str| line.starts_with('[') && line.ends_with(']') && line.len() > 2;
+    let is_comment = |line: &str| line.starts_with(';') || line.starts_with('#');
+    let is_pair = |line: &str| {
+        line.split_once('=')
+            .is_some_and(|(key, _)| !key.trim().is_empty())
+    };
+
+    lines.iter().any(|line| is_section(line))
+        && lines.iter().any(|line| is_pair(line))
+        && matches_enough_lines(lines, |line| {
+            is_section(line) || is_comment(line) || is_pair(line)
+        })
+}
+
+fn looks_like_csv(sample: &str, truncated: bool) -> bool {
+    let dialect = csv_dialect::detect_csv_dialect_in_text(sample, truncated);
+    dialect.sample_rows >= MIN_CSV_ROWS && dialect.confidence >= MIN_LINE_MATCH_RATIO
+}
+
+fn classify_content(sample: &str, truncated: bool) -> Option<ContentKind> {
+    let mut lines: Vec<&str> = sample
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    // The last line may have been cut off by the sample limit.
+    if truncated && lines.len() > 1 {
+        lines.pop();
+    }
+    if lines.is_empty() {
+        return None;
+    }
+
+    if looks_like_diff(&lines) {
+        Some(ContentKind::Diff)
+    } else if looks_like_xml(&lines) {
+        Some(ContentKind::Xml)
+    } else if looks_like_json_lines(&lines) {
+        Some(ContentKind::JsonLines)
+    } else if looks_like_ini(&lines) {
+        Some(ContentKind::Ini)
+    } else if looks_like_csv(sample, truncated) {
+        Some(ContentKind::Csv)
+    } else {
+        None
+    }
+}
+
+pub(super) fn suggest_syntax_for_document(path: &Path, rope: &Rope) -> Option<String> {
+    if !is_syntax_suggestion_candidate(path) {
+        return None;
+    }
+
+    let (sample, truncated) = syntax_suggestion_sample(rope);
+    classify_content(&sample, truncated)
+        .and_then(ContentKind::syntax_key)
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_content_should_recognize_line_oriented_formats() {
+        assert_eq!(
+            classify_content(
+                "{\"level\":\"info\",\"msg\":\"start\"}\n{\"level\":\"warn\",\"msg\":\"slow\"}\n",
+                false
+            ),
+            Some(ContentKind::JsonLines)
+        );
+        assert_eq!(
+            classify_content(
+                "<?xml version=\"1.0\"?>\n<root>\n  <item/>\n</root>\n",
+                false
+            ),
+            Some(ContentKind::Xml)
+        );
+        assert_eq!(
+            classify_content("; settings\n[server]\nport = 8080\nhost=localhost\n", false),
+            Some(ContentKind::Ini)
+        );
+        assert_eq!(
+            classify_content(
+                "--- a/main.rs\n+++ b/main.rs\n@@ -1,2 +1,2 @@\n-old\n+new\n",
+                false
+            ),
+            Some(ContentKind::Diff)
+        );
+        assert_eq!(
+            classify_content("id,name,score\n1,ann,3\n2,bob,5\n3,cy,8\n", false),
+            Some(ContentKind::Csv)
+        );
+        assert_eq!(
+            classify_content(
+                "2024-05-01 12:00:01 INFO started\n2024-05-01 12:00:02 WARN slow\n",
+                false
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn suggest_syntax_for_document_should_only_consider_generic_names() {
+        let rope = Rope::from_str("{\"a\":1}\n{\"a\":2}\n");
+
+        assert_eq!(
+            suggest_syntax_for_document(Path::new("events.log"), &rope),
+            Some("json".to_string())
+        );
+        assert_eq!(
+            suggest_syntax_for_document(Path::new("events"), &rope),
+            Some("json".to_string())
+        );
+        assert_eq!(
+            suggest_syntax_for_document(Path::new("events.py"), &rope),
+            None
+        );
+        assert_eq!(
+            suggest_syntax_for_document(
+                Path::new("data.txt"),
+                &Rope::from_str("id,name,score\n1,ann,3\n2,bob,5\n3,cy,8\n")
+            ),
+            Some("csv".to_string())
+        );
+    }
+}
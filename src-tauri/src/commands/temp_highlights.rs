@@ -0,0 +1,168 @@
+// Temporary keyword markers kept per document, separate from filter rules.
+// Only the keywords are stored; match ranges are resolved per viewport.
+
+use super::*;
+use crate::state::TempHighlight;
+
+const TEMP_HIGHLIGHT_MAX_KEYWORDS: usize = 32;
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempHighlightRange {
+    // 1-based line, columns are 1-based chars with an exclusive end.
+    pub line: usize,
+    pub start_column: usize,
+    pub end_column: usize,
+    pub keyword: String,
+    pub color: String,
+}
+
+fn find_temp_highlight_ranges(
+    rope: &Rope,
+    highlights: &[TempHighlight],
+    start_line: usize,
+    end_line: usize,
+) -> Vec<TempHighlightRange> {
+    let mut ranges = Vec::new();
+    let last_line = end_line.min(rope.len_lines());
+
+    for line in start_line.max(1)..=last_line {
+        let text = editing::trimmed_line_without_break(rope, line - 1);
+        for highlight in highlights {
+            for (byte_start, _) in text.match_indices(&highlight.keyword) {
+                let start_column = text[..byte_start].chars().count() + 1;
+                ranges.push(TempHighlightRange {
+                    line,
+                    start_column,
+                    end_column: start_column + highlight.keyword.chars().count(),
+                    keyword: highlight.keyword.clone(),
+                    color: highlight.color.clone(),
+                });
+            }
+        }
+    }
+
+    ranges.sort_by_key(|range| (range.line, range.start_column));
+    ranges
+}
+
+pub(super) fn add_temp_highlight_impl(
+    state: State<'_, AppState>,
+    id: String,
+    keyword: String,
+    color: String,
+) -> Result<Vec<TempHighlight>, String> {
+    if keyword.is_empty() {
+        return Err("Highlight keyword must not be empty".to_string());
+    }
+
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    if let Some(existing) = doc
+        .temp_highlights
+        .iter_mut()
+        .find(|highlight| highlight.keyword == keyword)
+    {
+        existing.color = color;
+    } else {
+        if doc.temp_highlights.len() >= TEMP_HIGHLIGHT_MAX_KEYWORDS {
+            return Err(format!(
+                "At most {} highlight keywords are allowed",
+                TEMP_HIGHLIGHT_MAX_KEYWORDS
+            ));
+        }
+        doc.temp_highlights.push(TempHighlight { keyword, color });
+    }
+
+    Ok(doc.temp_highlights.clone())
+}
+
+pub(super) fn list_temp_highlights_impl(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<TempHighlight>, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    Ok(doc.temp_highlights.clone())
+}
+
+pub(super) fn clear_temp_highlights_impl(
+    state: State<'_, AppState>,
+    id: String,
+    keyword: Option<String>,
+) -> Result<(), String> {
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    match keyword {
+        Some(keyword) => doc
+            .temp_highlights
+            .retain(|highlight| highlight.keyword != keyword),
+        None => doc.temp_highlights.clear(),
+    }
+    Ok(())
+}
+
+pub(super) fn get_temp_highlight_ranges_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_line: usize,
+    end_line: usize,
+) -> Result<Vec<TempHighlightRange>, String> {
+    let doc = state
+        .documents
+        .get(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    if doc.temp_highlights.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(find_temp_highlight_ranges(
+        &doc.rope,
+        &doc.temp_highlights,
+        start_line,
+        end_line,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_temp_highlight_ranges_should_report_char_columns_within_viewport() {
+        let rope = Rope::from_str("id=42 ok\n中文 id=42 id=7\nid=42\n");
+        let highlights = vec![
+            TempHighlight {
+                keyword: "id=42".to_string(),
+                color: "#ff0".to_string(),
+            },
+            TempHighlight {
+                keyword: "id=7".to_string(),
+                color: "#0ff".to_string(),
+            },
+        ];
+
+        let ranges = find_temp_highlight_ranges(&rope, &highlights, 2, 2);
+        let spans: Vec<_> = ranges
+            .iter()
+            .map(|range| (range.line, range.start_column, range.end_column))
+            .collect();
+
+        assert_eq!(spans, vec![(2, 4, 9), (2, 10, 14)]);
+        assert_eq!(ranges[1].color, "#0ff");
+        assert_eq!(
+            find_temp_highlight_ranges(&rope, &highlights, 1, 99).len(),
+            4
+        );
+    }
+}
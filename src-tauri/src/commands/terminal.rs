@@ -0,0 +1,358 @@
+//! PTY-backed terminal sessions for the integrated terminal pane.
+//!
+//! Each session owns a pseudo terminal running the user's default shell. Output
+//! is streamed to the frontend through `rutar://terminal-output` events and the
+//! session is dropped once the shell exits (`rutar://terminal-exit`).
+
+use dashmap::DashMap;
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+const DEFAULT_TERMINAL_COLS: u16 = 120;
+const DEFAULT_TERMINAL_ROWS: u16 = 30;
+const TERMINAL_READ_BUFFER_BYTES: usize = 8 * 1024;
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalInfo {
+    pub id: String,
+    pub shell: String,
+    pub cwd: String,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalOutputEventPayload {
+    id: String,
+    data: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalExitEventPayload {
+    id: String,
+    exit_code: Option<u32>,
+}
+
+struct TerminalSession {
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    child: Box<dyn Child + Send + Sync>,
+}
+
+fn sessions() -> &'static DashMap<String, Mutex<TerminalSession>> {
+    static MAP: OnceLock<DashMap<String, Mutex<TerminalSession>>> = OnceLock::new();
+    MAP.get_or_init(DashMap::new)
+}
+
+fn resolve_default_shell() -> String {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var("COMSPEC")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| "powershell.exe".to_string())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        std::env::var("SHELL")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| "/bin/sh".to_string())
+    }
+}
+
+fn resolve_terminal_cwd(cwd: Option<String>) -> Result<PathBuf, String> {
+    let requested = cwd
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    let Some(requested) = requested else {
+        return std::env::current_dir().map_err(|error| error.to_string());
+    };
+
+    let path = Path::new(&requested);
+    if path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+
+    // Allow opening a terminal "at" a file by falling back to its folder.
+    match path.parent() {
+        Some(parent) if path.is_file() && parent.is_dir() => Ok(parent.to_path_buf()),
+        _ => Err(format!("Terminal directory does not exist: {requested}")),
+    }
+}
+
+fn normalize_terminal_size(cols: Option<u16>, rows: Option<u16>) -> PtySize {
+    PtySize {
+        rows: rows
+            .filter(|value| *value > 0)
+            .unwrap_or(DEFAULT_TERMINAL_ROWS),
+        cols: cols
+            .filter(|value| *value > 0)
+            .unwrap_or(DEFAULT_TERMINAL_COLS),
+        pixel_width: 0,
+        pixel_height: 0,
+    }
+}
+
+// Returns the length of the longest prefix that does not end inside a UTF-8
+// sequence, so multi-byte characters split across reads are not mangled.
+pub(super) fn utf8_complete_prefix_len(bytes: &[u8]) -> usize {
+    match std::str::from_utf8(bytes) {
+        Ok(_) => bytes.len(),
+        Err(error) => {
+            if error.error_len().is_some() {
+                // Invalid bytes in the middle; let lossy decoding handle them.
+                bytes.len()
+            } else {
+                error.valid_up_to()
+            }
+        }
+    }
+}
+
+fn emit_terminal_event<P: serde::Serialize + Clone>(app: &AppHandle, event: &str, payload: P) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    if let Err(error) = window.emit(event, payload) {
+        eprintln!("failed to emit {event} event: {error}");
+    }
+}
+
+fn spawn_terminal_reader(app: AppHandle, id: String, mut reader: Box<dyn Read + Send>) {
+    std::thread::spawn(move || {
+        let mut buffer = [0u8; TERMINAL_READ_BUFFER_BYTES];
+        let mut pending: Vec<u8> = Vec::new();
+
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => {
+                    pending.extend_from_slice(&buffer[..read]);
+                    let complete = utf8_complete_prefix_len(&pending);
+                    if complete == 0 {
+                        continue;
+                    }
+
+                    let data = String::from_utf8_lossy(&pending[..complete]).to_string();
+                    pending.drain(..complete);
+                    emit_terminal_event(
+                        &app,
+                        "rutar://terminal-output",
+                        TerminalOutputEventPayload {
+                            id: id.clone(),
+                            data,
+                        },
+                    );
+                }
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+
+        if !pending.is_empty() {
+            emit_terminal_event(
+                &app,
+                "rutar://terminal-output",
+                TerminalOutputEventPayload {
+                    id: id.clone(),
+                    data: String::from_utf8_lossy(&pending).to_string(),
+                },
+            );
+        }
+
+        let exit_code = sessions().remove(&id).and_then(|(_, session)| {
+            let mut session = session
+                .into_inner()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            session.child.wait().ok().map(|status| status.exit_code())
+        });
+
+        emit_terminal_event(
+            &app,
+            "rutar://terminal-exit",
+            TerminalExitEventPayload { id, exit_code },
+        );
+    });
+}
+
+fn with_terminal_session<T>(
+    id: &str,
+    action: impl FnOnce(&mut TerminalSession) -> Result<T, String>,
+) -> Result<T, String> {
+    let entry = sessions()
+        .get(id)
+        .ok_or_else(|| "Terminal not found".to_string())?;
+    let mut session = entry
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    action(&mut session)
+}
+
+pub(super) fn create_terminal_impl(
+    app: AppHandle,
+    cwd: Option<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+) -> Result<TerminalInfo, String> {
+    let cwd = resolve_terminal_cwd(cwd)?;
+    let size = normalize_terminal_size(cols, rows);
+    let shell = resolve_default_shell();
+
+    let pair = native_pty_system()
+        .openpty(size)
+        .map_err(|error| error.to_string())?;
+
+    let mut command = CommandBuilder::new(&shell);
+    command.cwd(&cwd);
+    command.env("TERM", "xterm-256color");
+
+    let child = pair
+        .slave
+        .spawn_command(command)
+        .map_err(|error| error.to_string())?;
+    // The slave handle must be released so the reader sees EOF when the shell exits.
+    drop(pair.slave);
+
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|error| error.to_string())?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|error| error.to_string())?;
+
+    let id = Uuid::new_v4().to_string();
+    sessions().insert(
+        id.clone(),
+        Mutex::new(TerminalSession {
+            master: pair.master,
+            writer,
+            child,
+        }),
+    );
+    spawn_terminal_reader(app, id.clone(), reader);
+
+    Ok(TerminalInfo {
+        id,
+        shell,
+        cwd: cwd.to_string_lossy().to_string(),
+        cols: size.cols,
+        rows: size.rows,
+    })
+}
+
+pub(super) fn write_terminal_impl(id: String, data: String) -> Result<(), String> {
+    if data.is_empty() {
+        return Ok(());
+    }
+
+    with_terminal_session(&id, |session| {
+        session
+            .writer
+            .write_all(data.as_bytes())
+            .and_then(|_| session.writer.flush())
+            .map_err(|error| error.to_string())
+    })
+}
+
+pub(super) fn resize_terminal_impl(id: String, cols: u16, rows: u16) -> Result<(), String> {
+    if cols == 0 || rows == 0 {
+        return Err("Terminal size must be greater than zero".to_string());
+    }
+
+    with_terminal_session(&id, |session| {
+        session
+            .master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|error| error.to_string())
+    })
+}
+
+pub(super) fn close_terminal_impl(id: String) -> bool {
+    let Some((_, session)) = sessions().remove(&id) else {
+        return false;
+    };
+
+    let mut session = session
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Err(error) = session.child.kill() {
+        eprintln!("failed to kill terminal process: {error}");
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf8_complete_prefix_len_should_hold_back_split_multibyte_sequence() {
+        let bytes = "a中".as_bytes();
+
+        assert_eq!(utf8_complete_prefix_len(bytes), bytes.len());
+        assert_eq!(utf8_complete_prefix_len(&bytes[..2]), 1);
+        assert_eq!(utf8_complete_prefix_len(&bytes[..3]), 1);
+    }
+
+    #[test]
+    fn utf8_complete_prefix_len_should_pass_through_invalid_bytes() {
+        let bytes = [b'a', 0xff, b'b'];
+
+        assert_eq!(utf8_complete_prefix_len(&bytes), bytes.len());
+    }
+
+    #[test]
+    fn normalize_terminal_size_should_fallback_for_missing_or_zero_values() {
+        let size = normalize_terminal_size(Some(0), None);
+
+        assert_eq!(size.cols, DEFAULT_TERMINAL_COLS);
+        assert_eq!(size.rows, DEFAULT_TERMINAL_ROWS);
+
+        let size = normalize_terminal_size(Some(80), Some(24));
+        assert_eq!(size.cols, 80);
+        assert_eq!(size.rows, 24);
+    }
+
+    #[test]
+    fn resolve_terminal_cwd_should_use_parent_directory_for_files() {
+        let dir = std::env::temp_dir().join(format!("rutar-terminal-cwd-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let file_path = dir.join("log.txt");
+        std::fs::write(&file_path, "x").expect("write temp file");
+
+        let resolved = resolve_terminal_cwd(Some(file_path.to_string_lossy().to_string()))
+            .expect("resolve cwd");
+        assert_eq!(resolved, dir);
+
+        let missing = resolve_terminal_cwd(Some(dir.join("missing").to_string_lossy().to_string()));
+        assert!(missing.is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn write_terminal_should_fail_for_unknown_session() {
+        let result = write_terminal_impl("missing-terminal".to_string(), "ls\n".to_string());
+
+        assert_eq!(result, Err("Terminal not found".to_string()));
+    }
+}
@@ -0,0 +1,27 @@
+use super::terminal::{self, TerminalInfo};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn create_terminal(
+    app: AppHandle,
+    cwd: Option<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+) -> Result<TerminalInfo, String> {
+    terminal::create_terminal_impl(app, cwd, cols, rows)
+}
+
+#[tauri::command]
+pub fn write_terminal(id: String, data: String) -> Result<(), String> {
+    terminal::write_terminal_impl(id, data)
+}
+
+#[tauri::command]
+pub fn resize_terminal(id: String, cols: u16, rows: u16) -> Result<(), String> {
+    terminal::resize_terminal_impl(id, cols, rows)
+}
+
+#[tauri::command]
+pub fn close_terminal(id: String) -> bool {
+    terminal::close_terminal_impl(id)
+}
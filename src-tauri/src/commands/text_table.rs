@@ -0,0 +1,417 @@
+// Converts delimited lines into an aligned ASCII box table or a Markdown
+// table, and tables of either kind back into delimited lines.
+//
+// Lines are 1-based and inclusive. The first row is the header: ASCII tables
+// draw a rule under it and Markdown needs one. Columns whose data cells are
+// all numbers are right-aligned. Each conversion is one undo step.
+
+use super::csv_dialect::{detect_csv_dialect_in_text, looks_numeric, parse_csv_records};
+use super::document::is_wide_char;
+use super::editing::trimmed_line_without_break;
+use super::search::clear_outdated_document_search_caches;
+use crate::state::AppState;
+use ropey::Rope;
+use tauri::State;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TableStyle {
+    Ascii,
+    Markdown,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextTableResultPayload {
+    pub rows: usize,
+    pub columns: usize,
+    pub line_count: usize,
+    pub document_version: u64,
+}
+
+fn parse_table_style(value: Option<&str>) -> Result<TableStyle, String> {
+    match value
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some("ascii") => Ok(TableStyle::Ascii),
+        Some("markdown") | Some("md") => Ok(TableStyle::Markdown),
+        Some(other) => Err(format!("Unsupported table style: {other}")),
+    }
+}
+
+// "tab" and "\t" both name the tab character.
+fn parse_table_delimiter(value: &str) -> Result<char, String> {
+    let delimiter = match value {
+        "tab" | "\\t" => "\t",
+        value => value,
+    };
+    let mut chars = delimiter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if !matches!(ch, '\n' | '\r' | '"') => Ok(ch),
+        _ => Err(format!("Unsupported table delimiter: {value}")),
+    }
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|ch| if is_wide_char(ch) { 2 } else { 1 })
+        .sum()
+}
+
+// Trims cells, flattens line breaks kept inside quoted fields and pads ragged
+// rows to the widest one.
+fn normalize_table_rows(records: Vec<Vec<String>>) -> Vec<Vec<String>> {
+    let columns = records.iter().map(Vec::len).max().unwrap_or(0);
+    records
+        .into_iter()
+        .map(|record| {
+            let mut row: Vec<String> = record
+                .iter()
+                .map(|cell| cell.trim().replace(['\r', '\n'], " "))
+                .collect();
+            row.resize(columns, String::new());
+            row
+        })
+        .collect()
+}
+
+// Empty cells don't count against a numeric column.
+fn numeric_columns(rows: &[Vec<String>]) -> Vec<bool> {
+    let columns = rows.first().map_or(0, Vec::len);
+    (0..columns)
+        .map(|column| {
+            let mut data = rows
+                .iter()
+                .skip(1)
+                .map(|row| row[column].as_str())
+                .filter(|cell| !cell.is_empty())
+                .peekable();
+            data.peek().is_some() && data.all(looks_numeric)
+        })
+        .collect()
+}
+
+fn pad_cell(cell: &str, width: usize, right_aligned: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(display_width(cell)));
+    if right_aligned {
+        format!("{padding}{cell}")
+    } else {
+        format!("{cell}{padding}")
+    }
+}
+
+fn render_table(rows: &[Vec<String>], style: TableStyle) -> Vec<String> {
+    let cells: Vec<Vec<String>> = match style {
+        TableStyle::Ascii => rows.to_vec(),
+        TableStyle::Markdown => rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.replace('|', "\\|")).collect())
+            .collect(),
+    };
+    let numeric = numeric_columns(&cells);
+    // Markdown wants at least three dashes per column in the header rule.
+    let min_width = match style {
+        TableStyle::Ascii => 1,
+        TableStyle::Markdown => 3,
+    };
+    let widths: Vec<usize> = (0..numeric.len())
+        .map(|column| {
+            cells
+                .iter()
+                .map(|row| display_width(&row[column]))
+                .max()
+                .unwrap_or(0)
+                .max(min_width)
+        })
+        .collect();
+    let format_row = |row: &[String]| {
+        let padded: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .zip(&numeric)
+            .map(|((cell, width), right_aligned)| pad_cell(cell, *width, *right_aligned))
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+
+    let mut lines = Vec::with_capacity(cells.len() + 3);
+    match style {
+        TableStyle::Ascii => {
+            let rule = format!(
+                "+{}+",
+                widths
+                    .iter()
+                    .map(|width| "-".repeat(width + 2))
+                    .collect::<Vec<_>>()
+                    .join("+")
+            );
+            lines.push(rule.clone());
+            for (index, row) in cells.iter().enumerate() {
+                lines.push(format_row(row));
+                if index == 0 {
+                    lines.push(rule.clone());
+                }
+            }
+            if cells.len() > 1 {
+                lines.push(rule);
+            }
+        }
+        TableStyle::Markdown => {
+            let rule = format!(
+                "| {} |",
+                widths
+                    .iter()
+                    .zip(&numeric)
+                    .map(|(width, right_aligned)| if *right_aligned {
+                        format!("{}:", "-".repeat(width - 1))
+                    } else {
+                        "-".repeat(*width)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            );
+            for (index, row) in cells.iter().enumerate() {
+                lines.push(format_row(row));
+                if index == 0 {
+                    lines.push(rule.clone());
+                }
+            }
+        }
+    }
+    lines
+}
+
+// A line made only of rule characters, like `+----+` or `|---|--:|`.
+fn is_table_rule(line: &str) -> bool {
+    line.contains('-')
+        && line
+            .chars()
+            .all(|ch| matches!(ch, '+' | '-' | '|' | ':' | '=' | ' ' | '\t'))
+}
+
+// Splits on '|' except Markdown's escaped `\|`; the outer pipes are optional.
+fn split_table_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => line,
+    };
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(ch),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+fn parse_table_rows(lines: &[String]) -> Vec<Vec<String>> {
+    lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !is_table_rule(line))
+        .map(split_table_row)
+        .collect()
+}
+
+fn escape_delimited_field(value: &str, delimiter: char) -> String {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// The text of the lines without their line breaks, and the char range from
+// the first line's start to the last line's content end.
+fn line_range_text(
+    rope: &Rope,
+    start_line: usize,
+    end_line: usize,
+) -> Result<(usize, usize, Vec<String>), String> {
+    let line_count = rope.len_lines();
+    let first_line = start_line.min(end_line).max(1);
+    let last_line = start_line.max(end_line).max(1).min(line_count);
+    if first_line > line_count {
+        return Err("Line range is out of bounds".to_string());
+    }
+
+    let lines: Vec<String> = (first_line - 1..last_line)
+        .map(|line_index| trimmed_line_without_break(rope, line_index))
+        .collect();
+    let start_char = rope.line_to_char(first_line - 1);
+    let end_char =
+        rope.line_to_char(last_line - 1) + lines.last().map_or(0, |line| line.chars().count());
+    Ok((start_char, end_char, lines))
+}
+
+// `convert` returns the new lines with the row and column counts.
+fn replace_line_range(
+    state: State<'_, AppState>,
+    id: String,
+    start_line: usize,
+    end_line: usize,
+    convert: impl FnOnce(&[String]) -> Result<(Vec<String>, usize, usize), String>,
+) -> Result<TextTableResultPayload, String> {
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+    let (start_char, end_char, lines) = line_range_text(&doc.rope, start_line, end_line)?;
+    let (output, rows, columns) = convert(&lines)?;
+    let old_text = doc.rope.slice(start_char..end_char).to_string();
+    let new_text = output.join("\n");
+
+    let mut engine = doc.engine();
+    if old_text != new_text {
+        engine.replace(start_char, old_text, new_text)?;
+    }
+
+    let change = engine.finish();
+    if change.changed {
+        drop(doc);
+        clear_outdated_document_search_caches(&id, change.document_version);
+    }
+
+    Ok(TextTableResultPayload {
+        rows,
+        columns,
+        line_count: change.line_count,
+        document_version: change.document_version,
+    })
+}
+
+// Without a delimiter the CSV dialect is detected from the lines themselves.
+// `style` is "ascii" (default) or "markdown".
+pub(super) fn format_as_table_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_line: usize,
+    end_line: usize,
+    delimiter: Option<String>,
+    style: Option<String>,
+) -> Result<TextTableResultPayload, String> {
+    let style = parse_table_style(style.as_deref())?;
+    let delimiter = delimiter
+        .as_deref()
+        .map(parse_table_delimiter)
+        .transpose()?;
+
+    replace_line_range(state, id, start_line, end_line, |lines| {
+        let text = lines.join("\n");
+        let records = match delimiter {
+            Some(delimiter) => parse_csv_records(&text, delimiter, '"'),
+            None => {
+                let dialect = detect_csv_dialect_in_text(&text, false);
+                parse_csv_records(
+                    &text,
+                    dialect.delimiter.chars().next().unwrap_or(','),
+                    dialect.quote_char.chars().next().unwrap_or('"'),
+                )
+            }
+        };
+        let rows = normalize_table_rows(records);
+        let columns = rows.first().map_or(0, Vec::len);
+        if columns == 0 {
+            return Err("No rows to format".to_string());
+        }
+        Ok((render_table(&rows, style), rows.len(), columns))
+    })
+}
+
+// Reads ASCII or Markdown tables; rule lines are dropped. The delimiter
+// defaults to a comma.
+pub(super) fn table_to_delimited_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_line: usize,
+    end_line: usize,
+    delimiter: Option<String>,
+) -> Result<TextTableResultPayload, String> {
+    let delimiter = parse_table_delimiter(delimiter.as_deref().unwrap_or(","))?;
+
+    replace_line_range(state, id, start_line, end_line, |lines| {
+        let rows = normalize_table_rows(parse_table_rows(lines));
+        let columns = rows.first().map_or(0, Vec::len);
+        if columns == 0 {
+            return Err("No table rows found".to_string());
+        }
+        let output = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| escape_delimited_field(cell, delimiter))
+                    .collect::<Vec<_>>()
+                    .join(&delimiter.to_string())
+            })
+            .collect();
+        Ok((output, rows.len(), columns))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(text: &str, delimiter: char, style: TableStyle) -> String {
+        let rows = normalize_table_rows(parse_csv_records(text, delimiter, '"'));
+        render_table(&rows, style).join("\n")
+    }
+
+    #[test]
+    fn render_table_should_align_cells_and_right_align_numbers() {
+        let text = "name,qty,note\nbolt,12,\"a, b\"\n中文,7\n";
+
+        assert_eq!(
+            table(text, ',', TableStyle::Ascii),
+            "+------+-----+------+\n\
+             | name | qty | note |\n\
+             +------+-----+------+\n\
+             | bolt |  12 | a, b |\n\
+             | 中文 |   7 |      |\n\
+             +------+-----+------+"
+        );
+        assert_eq!(
+            table("a|b\tc\nx\t1\n", '\t', TableStyle::Markdown),
+            "| a\\|b |   c |\n\
+             | ---- | --: |\n\
+             | x    |   1 |"
+        );
+        assert!(parse_table_style(Some("html")).is_err());
+        assert_eq!(parse_table_delimiter("tab"), Ok('\t'));
+        assert!(parse_table_delimiter(";;").is_err());
+    }
+
+    #[test]
+    fn parse_table_rows_should_read_back_ascii_and_markdown_tables() {
+        let ascii: Vec<String> = table("name,note\nbolt,\"a, b\"\n", ',', TableStyle::Ascii)
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let markdown: Vec<String> = table("k,v\na|b,2\n", ',', TableStyle::Markdown)
+            .lines()
+            .map(str::to_string)
+            .collect();
+
+        assert_eq!(
+            parse_table_rows(&ascii),
+            vec![vec!["name", "note"], vec!["bolt", "a, b"]]
+        );
+        assert_eq!(
+            parse_table_rows(&markdown),
+            vec![vec!["k", "v"], vec!["a|b", "2"]]
+        );
+        assert_eq!(escape_delimited_field("a, b", ','), "\"a, b\"");
+        assert_eq!(escape_delimited_field("a, b", ';'), "a, b");
+    }
+}
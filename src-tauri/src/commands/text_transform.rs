@@ -0,0 +1,321 @@
+// Text transforms for a selection: letter case, identifier case styles, line
+// order and URL encoding. The result replaces the selection as one undo step.
+
+use super::search::clear_outdated_document_search_caches;
+use crate::state::AppState;
+use tauri::State;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TextTransformAction {
+    Upper,
+    Lower,
+    Title,
+    SnakeCase,
+    CamelCase,
+    KebabCase,
+    ReverseLines,
+    UrlEncode,
+    UrlDecode,
+}
+
+impl TextTransformAction {
+    fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "upper" => Some(Self::Upper),
+            "lower" => Some(Self::Lower),
+            "title" => Some(Self::Title),
+            "snake_case" => Some(Self::SnakeCase),
+            "camel_case" => Some(Self::CamelCase),
+            "kebab_case" => Some(Self::KebabCase),
+            "reverse_lines" => Some(Self::ReverseLines),
+            "url_encode" => Some(Self::UrlEncode),
+            "url_decode" => Some(Self::UrlDecode),
+            _ => None,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformSelectionResultPayload {
+    pub line_count: usize,
+    pub document_version: u64,
+    // The transformed text's range, to reselect it.
+    pub start_char: usize,
+    pub end_char: usize,
+}
+
+fn title_case(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut at_word_start = true;
+    for ch in text.chars() {
+        if ch.is_whitespace() {
+            at_word_start = true;
+            output.push(ch);
+        } else if at_word_start {
+            at_word_start = false;
+            output.extend(ch.to_uppercase());
+        } else {
+            output.extend(ch.to_lowercase());
+        }
+    }
+
+    output
+}
+
+// `parseHTTPResponse2` -> ["parse", "HTTP", "Response2"]. Anything that is
+// not a letter or digit separates words.
+fn split_identifier_words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (index, &ch) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if let Some(&previous) = index.checked_sub(1).and_then(|index| chars.get(index)) {
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            let starts_word = ch.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase() && next_is_lower));
+            if starts_word && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn join_identifier_words(text: &str, action: TextTransformAction) -> String {
+    let words = split_identifier_words(text);
+    match action {
+        TextTransformAction::CamelCase => words
+            .iter()
+            .enumerate()
+            .map(|(index, word)| {
+                if index == 0 {
+                    word.to_lowercase()
+                } else {
+                    capitalize(word)
+                }
+            })
+            .collect(),
+        TextTransformAction::KebabCase => words
+            .iter()
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("-"),
+        _ => words
+            .iter()
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+    }
+}
+
+// Applies `transform` to each line's content, keeping its indentation,
+// trailing whitespace and line break.
+fn map_line_contents(text: &str, transform: impl Fn(&str) -> String) -> String {
+    text.split('\n')
+        .map(|line| {
+            let content_start = line.len() - line.trim_start().len();
+            let content_end = line.trim_end().len().max(content_start);
+            format!(
+                "{}{}{}",
+                &line[..content_start],
+                transform(&line[content_start..content_end]),
+                &line[content_end..]
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// A trailing line break stays at the end, and CRLF text keeps CRLF.
+fn reverse_lines(text: &str) -> String {
+    let separator = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let body = text
+        .strip_suffix(separator)
+        .or_else(|| text.strip_suffix('\n'))
+        .unwrap_or(text);
+    let mut lines: Vec<&str> = body
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    lines.reverse();
+
+    let mut reversed = lines.join(separator);
+    if body.len() < text.len() {
+        reversed.push_str(separator);
+    }
+    reversed
+}
+
+// Percent-encodes everything except RFC 3986 unreserved characters, like
+// JavaScript's `encodeURIComponent`.
+fn url_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    encoded
+}
+
+fn url_decode(text: &str) -> Result<String, String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let value = bytes
+                .get(index + 1..index + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| "Invalid URL-encoded text".to_string())?;
+            decoded.push(value);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| "Invalid UTF-8 text".to_string())
+}
+
+fn transform_text(text: &str, action: TextTransformAction) -> Result<String, String> {
+    Ok(match action {
+        TextTransformAction::Upper => text.to_uppercase(),
+        TextTransformAction::Lower => text.to_lowercase(),
+        TextTransformAction::Title => title_case(text),
+        TextTransformAction::SnakeCase
+        | TextTransformAction::CamelCase
+        | TextTransformAction::KebabCase => {
+            map_line_contents(text, |line| join_identifier_words(line, action))
+        }
+        TextTransformAction::ReverseLines => reverse_lines(text),
+        TextTransformAction::UrlEncode => url_encode(text),
+        TextTransformAction::UrlDecode => url_decode(text)?,
+    })
+}
+
+pub(super) fn transform_selection_text_impl(
+    state: State<'_, AppState>,
+    id: String,
+    start_char: usize,
+    end_char: usize,
+    action: String,
+) -> Result<TransformSelectionResultPayload, String> {
+    let action = TextTransformAction::from_value(&action)
+        .ok_or_else(|| "Unsupported text transform action".to_string())?;
+    let mut doc = state
+        .documents
+        .get_mut(&id)
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    let len_chars = doc.rope.len_chars();
+    let (start_char, end_char) = (
+        start_char.min(end_char).min(len_chars),
+        start_char.max(end_char).min(len_chars),
+    );
+    let previous_text = doc.rope.slice(start_char..end_char).to_string();
+    let next_text = transform_text(&previous_text, action)?;
+    let next_end_char = start_char + next_text.chars().count();
+
+    let mut engine = doc.engine();
+    if previous_text != next_text {
+        engine.replace(start_char, previous_text, next_text)?;
+    }
+
+    let change = engine.finish();
+    if change.changed {
+        drop(doc);
+        clear_outdated_document_search_caches(&id, change.document_version);
+    }
+
+    Ok(TransformSelectionResultPayload {
+        line_count: change.line_count,
+        document_version: change.document_version,
+        start_char,
+        end_char: next_end_char,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(text: &str, action: &str) -> Result<String, String> {
+        transform_text(
+            text,
+            TextTransformAction::from_value(action).expect("action should exist"),
+        )
+    }
+
+    #[test]
+    fn transform_text_should_convert_letter_and_identifier_case() {
+        assert_eq!(transform("straße ok", "upper").unwrap(), "STRASSE OK");
+        assert_eq!(
+            transform("hELLO wORLD-wide", "title").unwrap(),
+            "Hello World-wide"
+        );
+        assert_eq!(
+            transform("  parseHTTPResponse2 value\nuser-id  ", "snake_case").unwrap(),
+            "  parse_http_response2_value\nuser_id  "
+        );
+        assert_eq!(
+            transform("user_account ID", "camel_case").unwrap(),
+            "userAccountId"
+        );
+        assert_eq!(
+            transform("UserAccount id", "kebab_case").unwrap(),
+            "user-account-id"
+        );
+    }
+
+    #[test]
+    fn transform_text_should_reverse_lines_keeping_line_endings() {
+        assert_eq!(
+            transform("a\r\nb\r\nc\r\n", "reverse_lines").unwrap(),
+            "c\r\nb\r\na\r\n"
+        );
+        assert_eq!(transform("a\nb", "reverse_lines").unwrap(), "b\na");
+    }
+
+    #[test]
+    fn transform_text_should_round_trip_url_encoding() {
+        let encoded = transform("a b&c=ü/~", "url_encode").unwrap();
+        assert_eq!(encoded, "a%20b%26c%3D%C3%BC%2F~");
+        assert_eq!(transform(&encoded, "url_decode").unwrap(), "a b&c=ü/~");
+        assert!(transform("%zz", "url_decode").is_err());
+        assert!(transform("%FF", "url_decode").is_err());
+        assert!(TextTransformAction::from_value("rot13").is_none());
+    }
+}
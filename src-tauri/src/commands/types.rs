@@ -1,3 +1,8 @@
+// An `id` argument naming an open document. build.rs keys on this alias to
+// let the command palette fill in the active document, so ids of terminals,
+// recovery files and the like stay plain `String`s.
+pub type DocumentId = String;
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileInfo {
@@ -11,6 +16,40 @@ pub struct FileInfo {
     pub(super) large_file_mode: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) syntax_override: Option<String>,
+    // Guessed from content for extensionless, `.log` and `.txt` files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) suggested_syntax: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) decode_stats: Option<FileDecodeStats>,
+    // Read-only with no syntax parsing until `promote_document_to_full_mode`.
+    pub(super) preview_mode: bool,
+    // Served from a memory map by line index; see `search_streaming_document`.
+    pub(super) streaming: bool,
+    // Binary file shown as rows of bytes; see `get_hex_view_chunk`.
+    pub(super) hex_view: bool,
+}
+
+#[derive(serde::Serialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LineEndingCounts {
+    pub(super) crlf: usize,
+    pub(super) lf: usize,
+    pub(super) cr: usize,
+}
+
+// Populated only when a file is decoded from disk, so the status bar can flag
+// guessed encodings and lossy decodes.
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDecodeStats {
+    pub(super) encoding_confidence: String,
+    pub(super) line_ending_counts: LineEndingCounts,
+    pub(super) has_bom: bool,
+    pub(super) had_malformed_sequences: bool,
+    pub(super) raw_bytes_preserved: bool,
+    // Set when content sniffing recognised a binary format, e.g. "png".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) detected_file_type: Option<String>,
 }
 
 #[derive(serde::Serialize)]
@@ -26,6 +65,23 @@ pub struct EditHistoryState {
     pub(super) can_undo: bool,
     pub(super) can_redo: bool,
     pub(super) is_dirty: bool,
+    // Undo steps oldest first, then redo steps in redo order; only filled
+    // when asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) entries: Option<Vec<EditHistoryEntry>>,
+    // Number of entries currently applied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) current_index: Option<usize>,
+}
+
+// One undo step, which covers a whole edit group.
+#[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EditHistoryEntry {
+    pub(super) label: String,
+    pub(super) inserted_bytes: usize,
+    pub(super) removed_bytes: usize,
+    pub(super) applied_at_ms: u64,
 }
 
 #[derive(serde::Serialize)]
@@ -62,6 +118,11 @@ mod tests {
             size_bytes: 123,
             large_file_mode: false,
             syntax_override: Some("markdown".to_string()),
+            suggested_syntax: None,
+            decode_stats: None,
+            preview_mode: false,
+            streaming: false,
+            hex_view: false,
         };
 
         let value = serde_json::to_value(info).expect("serialization should succeed");
@@ -76,7 +137,54 @@ mod tests {
                 "lineCount":1,
                 "sizeBytes":123,
                 "largeFileMode":false,
-                "syntaxOverride":"markdown"
+                "syntaxOverride":"markdown",
+                "previewMode":false,
+                "streaming":false,
+                "hexView":false
+            })
+        );
+    }
+
+    #[test]
+    fn file_info_serialization_should_nest_decode_stats() {
+        let info = FileInfo {
+            id: "1".to_string(),
+            path: "a.log".to_string(),
+            name: "a.log".to_string(),
+            encoding: "GBK".to_string(),
+            line_ending: "CRLF".to_string(),
+            line_count: 3,
+            size_bytes: 10,
+            large_file_mode: false,
+            syntax_override: None,
+            suggested_syntax: Some("json".to_string()),
+            decode_stats: Some(FileDecodeStats {
+                encoding_confidence: "low".to_string(),
+                line_ending_counts: LineEndingCounts {
+                    crlf: 2,
+                    lf: 1,
+                    cr: 0,
+                },
+                has_bom: false,
+                had_malformed_sequences: true,
+                raw_bytes_preserved: true,
+                detected_file_type: None,
+            }),
+            preview_mode: false,
+            streaming: false,
+            hex_view: false,
+        };
+
+        let value = serde_json::to_value(info).expect("serialization should succeed");
+        assert_eq!(value["suggestedSyntax"], "json");
+        assert_eq!(
+            value["decodeStats"],
+            json!({
+                "encodingConfidence":"low",
+                "lineEndingCounts":{"crlf":2,"lf":1,"cr":0},
+                "hasBom":false,
+                "hadMalformedSequences":true,
+                "rawBytesPreserved":true
             })
         );
     }
@@ -87,6 +195,8 @@ mod tests {
             can_undo: true,
             can_redo: false,
             is_dirty: true,
+            entries: None,
+            current_index: None,
         };
 
         let value = serde_json::to_value(state).expect("serialization should succeed");
@@ -0,0 +1,494 @@
+// A read-only document stitched together from an ordered list of files, the
+// general form of a joined log series. Opening only counts each file's lines;
+// text is decoded when its lines are requested or searched, and a few recently
+// used files stay cached. Virtual documents live outside the editable document
+// map, so editing commands reject their ids. Line numbers are 1-based across
+// the whole document, and every line and match also reports the file and line
+// it came from.
+
+use super::log_series;
+use super::*;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+const VIRTUAL_DOCUMENT_MAX_MEMBERS: usize = 256;
+const VIRTUAL_DOCUMENT_CACHED_MEMBERS: usize = 4;
+const VIRTUAL_DOCUMENT_MAX_LINES_PER_REQUEST: usize = 5000;
+
+struct VirtualMember {
+    path: String,
+    compressed: bool,
+    // 1-based first line in the virtual document.
+    start_line: usize,
+    line_count: usize,
+}
+
+struct VirtualDocument {
+    members: Vec<VirtualMember>,
+    line_count: usize,
+    // Most recently used last.
+    loaded: VecDeque<(usize, Arc<Vec<String>>)>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualMemberPayload {
+    pub path: String,
+    pub start_line: usize,
+    pub line_count: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualDocumentInfo {
+    pub id: String,
+    pub name: String,
+    pub line_count: usize,
+    pub read_only: bool,
+    pub members: Vec<VirtualMemberPayload>,
+    // Files that could not be read and were left out.
+    pub errors: Vec<String>,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualLinePayload {
+    pub line: usize,
+    pub text: String,
+    pub path: String,
+    pub source_line: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualSearchMatch {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub line_text: String,
+    pub path: String,
+    pub source_line: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualSearchPayload {
+    pub matches: Vec<VirtualSearchMatch>,
+    pub truncated: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualFilterPayload {
+    pub lines: Vec<VirtualLinePayload>,
+    pub truncated: bool,
+}
+
+fn virtual_documents() -> &'static DashMap<String, Arc<Mutex<VirtualDocument>>> {
+    static MAP: OnceLock<DashMap<String, Arc<Mutex<VirtualDocument>>>> = OnceLock::new();
+    MAP.get_or_init(DashMap::new)
+}
+
+fn virtual_document(id: &str) -> Result<Arc<Mutex<VirtualDocument>>, String> {
+    virtual_documents()
+        .get(id)
+        .map(|entry| Arc::clone(entry.value()))
+        .ok_or_else(|| "Virtual document not found".to_string())
+}
+
+// A trailing line break does not start another line, and an empty file
+// contributes no lines, matching how log series are joined.
+fn split_member_lines(text: &str) -> Vec<String> {
+    let body = text.strip_suffix('\n').unwrap_or(text);
+    if body.is_empty() {
+        return Vec::new();
+    }
+    body.split('\n').map(str::to_string).collect()
+}
+
+fn read_member_lines(path: &str, compressed: bool) -> Result<Vec<String>, String> {
+    log_series::read_series_file_text(path, compressed).map(|text| split_member_lines(&text))
+}
+
+impl VirtualDocument {
+    fn member_index_for_line(&self, line: usize) -> Option<usize> {
+        let index = self
+            .members
+            .partition_point(|member| member.start_line + member.line_count <= line);
+        self.members
+            .get(index)
+            .filter(|member| line >= member.start_line && member.line_count > 0)
+            .map(|_| index)
+    }
+
+    fn member_lines(&mut self, index: usize) -> Result<Arc<Vec<String>>, String> {
+        if let Some(position) = self
+            .loaded
+            .iter()
+            .position(|(loaded_index, _)| *loaded_index == index)
+        {
+            let entry = self.loaded.remove(position).expect("position is in range");
+            let lines = Arc::clone(&entry.1);
+            self.loaded.push_back(entry);
+            return Ok(lines);
+        }
+
+        let member = &self.members[index];
+        let lines = Arc::new(read_member_lines(&member.path, member.compressed)?);
+        if lines.len() != member.line_count {
+            return Err(format!(
+                "{} changed on disk since the virtual document was opened",
+                member.path
+            ));
+        }
+        if self.loaded.len() >= VIRTUAL_DOCUMENT_CACHED_MEMBERS {
+            self.loaded.pop_front();
+        }
+        self.loaded.push_back((index, Arc::clone(&lines)));
+        Ok(lines)
+    }
+}
+
+pub(super) async fn open_virtual_document_impl(
+    paths: Vec<String>,
+    name: Option<String>,
+) -> Result<VirtualDocumentInfo, String> {
+    let paths: Vec<String> = paths
+        .into_iter()
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect();
+    if paths.is_empty() {
+        return Err("No files to open".to_string());
+    }
+    if paths.len() > VIRTUAL_DOCUMENT_MAX_MEMBERS {
+        return Err(format!(
+            "A virtual document can hold at most {} files",
+            VIRTUAL_DOCUMENT_MAX_MEMBERS
+        ));
+    }
+
+    let (members, errors) = tauri::async_runtime::spawn_blocking(move || {
+        let mut members = Vec::with_capacity(paths.len());
+        let mut errors = Vec::new();
+        let mut next_line = 1usize;
+        for path in paths {
+            let compressed = path.to_ascii_lowercase().ends_with(".gz");
+            match read_member_lines(&path, compressed) {
+                Ok(lines) => {
+                    members.push(VirtualMember {
+                        path,
+                        compressed,
+                        start_line: next_line,
+                        line_count: lines.len(),
+                    });
+                    next_line += lines.len();
+                }
+                Err(error) => errors.push(format!("{}: {}", path, error)),
+            }
+        }
+        (members, errors)
+    })
+    .await
+    .map_err(|error| error.to_string())?;
+
+    if members.is_empty() {
+        return Err(errors.join("; "));
+    }
+
+    let line_count = members.iter().map(|member| member.line_count).sum();
+    let name = name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("{} files (virtual)", members.len()));
+    let member_payloads = members
+        .iter()
+        .map(|member| VirtualMemberPayload {
+            path: member.path.clone(),
+            start_line: member.start_line,
+            line_count: member.line_count,
+        })
+        .collect();
+
+    let id = Uuid::new_v4().to_string();
+    virtual_documents().insert(
+        id.clone(),
+        Arc::new(Mutex::new(VirtualDocument {
+            members,
+            line_count,
+            loaded: VecDeque::new(),
+        })),
+    );
+
+    Ok(VirtualDocumentInfo {
+        id,
+        name,
+        line_count,
+        read_only: true,
+        members: member_payloads,
+        errors,
+    })
+}
+
+pub(super) async fn get_virtual_document_lines_impl(
+    id: String,
+    start_line: usize,
+    max_lines: usize,
+) -> Result<Vec<VirtualLinePayload>, String> {
+    let document = virtual_document(&id)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut document = document
+            .lock()
+            .map_err(|_| "Virtual document is unavailable".to_string())?;
+        let end_line = start_line
+            .max(1)
+            .saturating_add(max_lines.min(VIRTUAL_DOCUMENT_MAX_LINES_PER_REQUEST))
+            .min(document.line_count + 1);
+        let mut lines = Vec::new();
+        let mut line = start_line.max(1);
+
+        while line < end_line {
+            let Some(index) = document.member_index_for_line(line) else {
+                break;
+            };
+            let member_lines = document.member_lines(index)?;
+            let member = &document.members[index];
+            let member_end = (member.start_line + member.line_count).min(end_line);
+            for global_line in line..member_end {
+                let source_line = global_line - member.start_line + 1;
+                lines.push(VirtualLinePayload {
+                    line: global_line,
+                    text: member_lines[source_line - 1].clone(),
+                    path: member.path.clone(),
+                    source_line,
+                });
+            }
+            line = member_end;
+        }
+
+        Ok(lines)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+// Walks the members in order and hands every match to `on_match` together
+// with the member it came from; stops once `on_match` returns false.
+fn scan_virtual_document(
+    document: &mut VirtualDocument,
+    keyword: &str,
+    mode: &str,
+    case_sensitive: bool,
+    mut on_match: impl FnMut(&VirtualMember, SearchMatchResult) -> bool,
+) -> Result<(), String> {
+    // Reject a bad pattern before any file is read.
+    collect_search_matches_by_mode("", keyword, mode, case_sensitive)?;
+
+    for index in 0..document.members.len() {
+        if document.members[index].line_count == 0 {
+            continue;
+        }
+        let member_lines = document.member_lines(index)?;
+        let matches = collect_search_matches_by_mode(
+            &member_lines.join("\n"),
+            keyword,
+            mode,
+            case_sensitive,
+        )?;
+        let member = &document.members[index];
+        for search_match in matches {
+            if !on_match(member, search_match) {
+                return Ok(());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub(super) async fn search_virtual_document_impl(
+    id: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    max_results: usize,
+) -> Result<VirtualSearchPayload, String> {
+    let document = virtual_document(&id)?;
+    let max_results = max_results.max(1);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut document = document
+            .lock()
+            .map_err(|_| "Virtual document is unavailable".to_string())?;
+        let mut matches = Vec::new();
+        let mut truncated = false;
+
+        scan_virtual_document(
+            &mut document,
+            &keyword,
+            &mode,
+            case_sensitive,
+            |member, search_match| {
+                if matches.len() >= max_results {
+                    truncated = true;
+                    return false;
+                }
+                matches.push(VirtualSearchMatch {
+                    line: member.start_line + search_match.line - 1,
+                    column: search_match.column,
+                    text: search_match.text,
+                    line_text: search_match.line_text,
+                    path: member.path.clone(),
+                    source_line: search_match.line,
+                });
+                true
+            },
+        )?;
+
+        Ok(VirtualSearchPayload { matches, truncated })
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+// Like search, but returns each matching line once.
+pub(super) async fn filter_virtual_document_impl(
+    id: String,
+    keyword: String,
+    mode: String,
+    case_sensitive: bool,
+    max_lines: usize,
+) -> Result<VirtualFilterPayload, String> {
+    let document = virtual_document(&id)?;
+    let max_lines = max_lines.max(1);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut document = document
+            .lock()
+            .map_err(|_| "Virtual document is unavailable".to_string())?;
+        let mut lines: Vec<VirtualLinePayload> = Vec::new();
+        let mut truncated = false;
+
+        scan_virtual_document(
+            &mut document,
+            &keyword,
+            &mode,
+            case_sensitive,
+            |member, search_match| {
+                let line = member.start_line + search_match.line - 1;
+                if lines.last().is_some_and(|last| last.line == line) {
+                    return true;
+                }
+                if lines.len() >= max_lines {
+                    truncated = true;
+                    return false;
+                }
+                lines.push(VirtualLinePayload {
+                    line,
+                    text: search_match.line_text,
+                    path: member.path.clone(),
+                    source_line: search_match.line,
+                });
+                true
+            },
+        )?;
+
+        Ok(VirtualFilterPayload { lines, truncated })
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+pub(super) fn close_virtual_document_impl(id: String) -> bool {
+    virtual_documents().remove(&id).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_document(line_counts: &[usize]) -> VirtualDocument {
+        let mut next_line = 1;
+        let members = line_counts
+            .iter()
+            .enumerate()
+            .map(|(index, &line_count)| {
+                let member = VirtualMember {
+                    path: format!("file{}", index),
+                    compressed: false,
+                    start_line: next_line,
+                    line_count,
+                };
+                next_line += line_count;
+                member
+            })
+            .collect();
+        VirtualDocument {
+            members,
+            line_count: next_line - 1,
+            loaded: VecDeque::new(),
+        }
+    }
+
+    #[test]
+    fn split_member_lines_should_ignore_trailing_line_break() {
+        assert_eq!(split_member_lines("a\nb\n"), vec!["a", "b"]);
+        assert_eq!(split_member_lines("a\n\nb"), vec!["a", "", "b"]);
+        assert!(split_member_lines("").is_empty());
+        assert!(split_member_lines("\n").is_empty());
+    }
+
+    #[test]
+    fn member_index_for_line_should_skip_empty_members() {
+        let document = make_document(&[2, 0, 3]);
+
+        assert_eq!(document.member_index_for_line(1), Some(0));
+        assert_eq!(document.member_index_for_line(2), Some(0));
+        assert_eq!(document.member_index_for_line(3), Some(2));
+        assert_eq!(document.member_index_for_line(5), Some(2));
+        assert_eq!(document.member_index_for_line(6), None);
+    }
+
+    #[test]
+    fn scan_virtual_document_should_report_source_file_and_line() {
+        let root = std::env::temp_dir().join(format!("rutar-virtual-doc-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create dir");
+        let first = root.join("a.log");
+        let second = root.join("b.log");
+        fs::write(&first, "start\nerror one\n").expect("write file");
+        fs::write(&second, "ok\nerror two\n").expect("write file");
+
+        let mut document = make_document(&[2, 2]);
+        document.members[0].path = first.to_string_lossy().to_string();
+        document.members[1].path = second.to_string_lossy().to_string();
+
+        let mut found = Vec::new();
+        scan_virtual_document(
+            &mut document,
+            "error",
+            "literal",
+            true,
+            |member, found_match| {
+                found.push((
+                    member.start_line + found_match.line - 1,
+                    member.path.clone(),
+                    found_match.line,
+                ));
+                true
+            },
+        )
+        .expect("scan should succeed");
+
+        assert_eq!(
+            found,
+            vec![
+                (2, first.to_string_lossy().to_string(), 2),
+                (4, second.to_string_lossy().to_string(), 2),
+            ]
+        );
+        assert_eq!(document.loaded.len(), 2);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,8 +1,8 @@
 mod commands;
 mod state;
 
-use state::AppState;
-use std::time::Duration;
+use state::{AppState, FileWatchBatch, GotoPosition, StartupPath};
+use std::time::{Duration, Instant};
 use tauri::{
     AppHandle, Emitter, Listener, Manager, PhysicalSize, Size, WebviewWindow, WindowEvent,
 };
@@ -12,43 +12,126 @@ struct ExternalFileChangeEventPayload {
     id: String,
 }
 
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DirectoryChangeEventPayload {
+    root_path: String,
+    directory_paths: Vec<String>,
+}
+
 #[tauri::command]
 fn show_main_window_when_ready(window: WebviewWindow) -> Result<(), String> {
     wake_main_window(&window);
     Ok(())
 }
 
-fn collect_valid_startup_paths_from_args<I>(args: I) -> Vec<String>
+fn is_safe_mode_requested<I, S>(args: I) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    args.into_iter()
+        .any(|value| value.as_ref() == "--safe-mode")
+}
+
+// `somecommand | rutar -` reads the piped output into a live document.
+fn is_stdin_stream_requested<I, S>(args: I) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    args.into_iter().any(|value| value.as_ref() == "-")
+}
+
+fn collect_valid_startup_paths_from_args<I>(args: I) -> Vec<StartupPath>
 where
     I: IntoIterator<Item = String>,
 {
     collect_valid_startup_paths(args)
 }
 
-fn collect_valid_startup_paths<I, S>(paths: I) -> Vec<String>
+fn parse_position_number(value: &str) -> Option<usize> {
+    value.parse::<usize>().ok().filter(|number| *number > 0)
+}
+
+fn existing_path_string(value: &str) -> Option<String> {
+    let path = std::path::Path::new(value);
+    path.exists().then(|| path.to_string_lossy().to_string())
+}
+
+// `file.txt:12` and `file.txt:12:5` name a position, unless a file with that
+// literal name exists.
+fn resolve_startup_path_target(value: &str) -> Option<(String, Option<usize>, Option<usize>)> {
+    if let Some(path) = existing_path_string(value) {
+        return Some((path, None, None));
+    }
+
+    let (rest, last) = value.rsplit_once(':')?;
+    let last = parse_position_number(last)?;
+    if let Some((base, line)) = rest.rsplit_once(':') {
+        if let (Some(line), Some(path)) = (parse_position_number(line), existing_path_string(base))
+        {
+            return Some((path, Some(line), Some(last)));
+        }
+    }
+
+    existing_path_string(rest).map(|path| (path, Some(last), None))
+}
+
+// `--line N` and `--column N` (or `--line=N`) apply to the path after them.
+fn collect_valid_startup_paths<I, S>(args: I) -> Vec<StartupPath>
 where
     I: IntoIterator<Item = S>,
     S: AsRef<str>,
 {
-    paths
-        .into_iter()
-        .filter_map(|value| {
-            let value = value.as_ref();
-            if value.starts_with('-') {
-                return None;
-            }
-
-            let path = std::path::Path::new(value);
-            if path.exists() {
-                Some(path.to_string_lossy().to_string())
+    let mut startup_paths = Vec::new();
+    let mut pending_line = None;
+    let mut pending_column = None;
+    let mut args = args.into_iter();
+
+    while let Some(value) = args.next() {
+        let value = value.as_ref();
+        let (flag, inline_value) = match value.split_once('=') {
+            Some((flag, inline_value)) => (flag, Some(inline_value.to_string())),
+            None => (value, None),
+        };
+        if flag == "--line" || flag == "--column" {
+            let number = inline_value
+                .or_else(|| args.next().map(|next| next.as_ref().to_string()))
+                .and_then(|number| parse_position_number(&number));
+            if flag == "--line" {
+                pending_line = number;
             } else {
-                None
+                pending_column = number;
             }
-        })
-        .collect()
+            continue;
+        }
+
+        if value.starts_with('-') {
+            continue;
+        }
+
+        let Some((path, line, column)) = resolve_startup_path_target(value) else {
+            continue;
+        };
+        let line = line.or(pending_line);
+        let column = column.or(pending_column);
+        pending_line = None;
+        pending_column = None;
+
+        startup_paths.push(StartupPath {
+            path,
+            goto_position: line.map(|line| GotoPosition {
+                line,
+                column: column.unwrap_or(1),
+            }),
+        });
+    }
+
+    startup_paths
 }
 
-fn collect_valid_startup_paths_from_urls<I>(urls: I) -> Vec<String>
+fn collect_valid_startup_paths_from_urls<I>(urls: I) -> Vec<StartupPath>
 where
     I: IntoIterator<Item = tauri::Url>,
 {
@@ -58,18 +141,29 @@ where
     }))
 }
 
-fn emit_or_queue_open_paths(app: &AppHandle, startup_paths: Vec<String>) {
+fn emit_or_queue_open_paths(app: &AppHandle, startup_paths: Vec<StartupPath>) {
     if startup_paths.is_empty() {
         return;
     }
 
-    if app.state::<AppState>().is_frontend_ready() {
+    if let Some(startup_paths) = app.state::<AppState>().queue_startup_paths(startup_paths) {
         forward_startup_paths_to_main_window(app, startup_paths);
-    } else {
-        app.state::<AppState>().push_startup_paths(startup_paths);
     }
 }
 
+// Checking that each argument exists can stall on a cold disk, so it runs off
+// the main thread while the window starts up.
+fn setup_startup_path_validation(app: &AppHandle) {
+    let app_handle = app.clone();
+
+    let _ = std::thread::Builder::new()
+        .name("rutar-startup-paths".to_string())
+        .spawn(move || {
+            let startup_paths = collect_valid_startup_paths_from_args(std::env::args().skip(1));
+            emit_or_queue_open_paths(&app_handle, startup_paths);
+        });
+}
+
 fn setup_frontend_ready_listener(app: &AppHandle) {
     let app_handle = app.clone();
     app.listen("rutar://frontend-ready", move |_| {
@@ -79,7 +173,7 @@ fn setup_frontend_ready_listener(app: &AppHandle) {
     });
 }
 
-fn forward_startup_paths_to_main_window(app: &AppHandle, startup_paths: Vec<String>) {
+fn forward_startup_paths_to_main_window(app: &AppHandle, startup_paths: Vec<StartupPath>) {
     let window = match app.get_webview_window("main") {
         Some(main_window) => main_window,
         None => return,
@@ -170,6 +264,22 @@ fn setup_main_window_state_tracking(app: &AppHandle) {
     });
 }
 
+// The frontend reports its tabs with `save_session` as they change and restores
+// them with `load_session` on startup; closing folds in whatever changed since
+// the last report.
+fn setup_session_persistence(app: &AppHandle) {
+    let Some(main_window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    let app_handle = app.clone();
+    main_window.on_window_event(move |event| {
+        if matches!(event, WindowEvent::CloseRequested { .. }) {
+            commands::persist_session_on_close(app_handle.state::<AppState>());
+        }
+    });
+}
+
 fn should_track_external_file_changes(window: &WebviewWindow) -> bool {
     match window.is_focused() {
         Ok(is_focused) => is_focused,
@@ -180,23 +290,129 @@ fn should_track_external_file_changes(window: &WebviewWindow) -> bool {
     }
 }
 
+// Watcher events are drained once they settle, so one save or checkout turns
+// into a single round of notifications.
+const FILE_WATCH_TICK: Duration = Duration::from_millis(200);
+const FILE_WATCH_QUIET_PERIOD: Duration = Duration::from_millis(250);
+const FILE_WATCH_MAX_DELAY: Duration = Duration::from_secs(2);
+// Documents in directories the watcher could not cover are rescanned on this
+// interval, and failed or not-yet-possible watches are retried. notify misses
+// changes on some network and FUSE mounts, so every document is also
+// rescanned when the window regains focus.
+const EXTERNAL_CHANGE_FALLBACK_SCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+fn emit_directory_change_event(window: &WebviewWindow, state: &AppState, batch: &FileWatchBatch) {
+    let Some(root_path) = state.watched_folder_path() else {
+        return;
+    };
+
+    let directory_paths: Vec<String> = batch
+        .changed_directories
+        .iter()
+        .filter(|directory| directory.starts_with(&root_path))
+        .map(|directory| directory.to_string_lossy().to_string())
+        .collect();
+    if directory_paths.is_empty() {
+        return;
+    }
+
+    let payload = DirectoryChangeEventPayload {
+        root_path: root_path.to_string_lossy().to_string(),
+        directory_paths,
+    };
+    if let Err(error) = window.emit("rutar://directory-changed", payload) {
+        eprintln!("failed to emit directory change event: {error}");
+    }
+}
+
 fn setup_external_file_change_tracking(app: &AppHandle) {
     let app_handle = app.clone();
 
     let _ = std::thread::Builder::new()
         .name("rutar-external-change-tracker".to_string())
-        .spawn(move || loop {
-            let main_window = app_handle.get_webview_window("main");
-            let tracking_enabled = main_window
-                .as_ref()
-                .map(should_track_external_file_changes)
-                .unwrap_or(false);
-            let changed_ids = commands::collect_external_file_change_document_ids_with_tracking(
-                app_handle.state::<AppState>(),
-                tracking_enabled,
-            );
-
-            if !changed_ids.is_empty() {
+        .spawn(move || {
+            let mut last_fallback_scan = Instant::now();
+            let mut was_tracking = false;
+            let mut last_watch_error = None;
+            let mut auto_save_debounce = commands::AutoSaveDebounce::default();
+
+            loop {
+                std::thread::sleep(FILE_WATCH_TICK);
+
+                commands::run_auto_save_tick(&app_handle, &mut auto_save_debounce);
+                let state = app_handle.state::<AppState>();
+                let fallback_due =
+                    last_fallback_scan.elapsed() >= EXTERNAL_CHANGE_FALLBACK_SCAN_INTERVAL;
+                if fallback_due {
+                    last_fallback_scan = Instant::now();
+                }
+
+                let shared_rules_directory = state.shared_filter_rules_directory();
+                let shared_rules_was_watched = shared_rules_directory
+                    .as_deref()
+                    .is_some_and(|directory| state.is_directory_watched(directory));
+                if state.take_document_watches_stale() || fallback_due {
+                    let watch_error = state.sync_document_watches().err();
+                    if watch_error.is_some() && watch_error != last_watch_error {
+                        eprintln!(
+                            "failed to watch document directories: {}",
+                            watch_error.as_deref().unwrap_or_default()
+                        );
+                    }
+                    last_watch_error = watch_error;
+                }
+
+                let batch = state
+                    .take_settled_file_watch_batch(FILE_WATCH_QUIET_PERIOD, FILE_WATCH_MAX_DELAY);
+                let shared_rules_newly_watched = !shared_rules_was_watched
+                    && shared_rules_directory
+                        .as_deref()
+                        .is_some_and(|directory| state.is_directory_watched(directory));
+                commands::handle_shared_filter_rule_groups_watch_batch(
+                    &app_handle,
+                    batch.as_ref(),
+                    shared_rules_newly_watched,
+                );
+                let main_window = app_handle.get_webview_window("main");
+
+                if let (Some(batch), Some(window)) = (batch.as_ref(), main_window.as_ref()) {
+                    emit_directory_change_event(window, &state, batch);
+                }
+
+                let tracking_enabled = main_window
+                    .as_ref()
+                    .map(should_track_external_file_changes)
+                    .unwrap_or(false);
+                let focus_regained = tracking_enabled && !was_tracking;
+                was_tracking = tracking_enabled;
+
+                if !tracking_enabled {
+                    commands::collect_external_file_change_document_ids_with_tracking(
+                        state,
+                        false,
+                        |_| false,
+                    );
+                    continue;
+                }
+
+                let changed_paths = batch.as_ref().map(|batch| &batch.changed_paths);
+                let documents_touched = changed_paths.is_some_and(|paths| !paths.is_empty());
+                if !(documents_touched || fallback_due || focus_regained) {
+                    continue;
+                }
+
+                let changed_ids = commands::collect_external_file_change_document_ids_with_tracking(
+                    state.clone(),
+                    true,
+                    |path| {
+                        focus_regained
+                            || changed_paths.is_some_and(|paths| paths.contains(path))
+                            || (fallback_due
+                                && !path
+                                    .parent()
+                                    .is_some_and(|directory| state.is_directory_watched(directory)))
+                    },
+                );
                 if let Some(window) = main_window {
                     for id in changed_ids {
                         let payload = ExternalFileChangeEventPayload { id };
@@ -206,14 +422,73 @@ fn setup_external_file_change_tracking(app: &AppHandle) {
                     }
                 }
             }
+        });
+}
+
+// "After delay" auto-save is debounced on the file watch tick in
+// `setup_external_file_change_tracking`; focus loss saves right away.
+fn setup_auto_save(app: &AppHandle) {
+    let Some(main_window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    let app_handle = app.clone();
+    main_window.on_window_event(move |event| {
+        if !matches!(event, WindowEvent::Focused(false)) {
+            return;
+        }
+
+        let (mode, _) = commands::load_auto_save_settings_in_config();
+        if mode == commands::AutoSaveMode::OnFocusChange {
+            tauri::async_runtime::spawn(commands::auto_save_dirty_documents(
+                app_handle.clone(),
+                commands::AutoSaveTrigger::FocusChange,
+            ));
+        }
+    });
+}
+
+// Unsaved text is copied aside often enough that a crash loses little; the
+// frontend offers leftovers through `list_recovery_files` at startup.
+fn setup_recovery_backups(app: &AppHandle) {
+    let app_handle = app.clone();
+    let _ = std::thread::Builder::new()
+        .name("rutar-recovery-backup".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(Duration::from_secs(30));
+            commands::write_recovery_backups(&app_handle);
+        });
+}
 
-            std::thread::sleep(Duration::from_millis(1200));
+// Idle detection only needs coarse granularity; the configured idle time is
+// at least a minute. Local history checkpoints ride on the same tick.
+fn setup_idle_maintenance(app: &AppHandle) {
+    let app_handle = app.clone();
+    let _ = std::thread::Builder::new()
+        .name("rutar-idle-maintenance".to_string())
+        .spawn(move || {
+            let mut tracker = commands::IdleActivityTracker::default();
+            loop {
+                std::thread::sleep(Duration::from_secs(15));
+                commands::capture_document_snapshots(&app_handle);
+                commands::run_idle_maintenance_tick(&app_handle, &mut tracker);
+            }
         });
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let startup_paths = collect_valid_startup_paths_from_args(std::env::args().skip(1));
+    let safe_mode = is_safe_mode_requested(std::env::args().skip(1));
+    if safe_mode {
+        commands::enable_safe_mode();
+    }
+
+    // Piped input cannot be handed to another instance, so this one stays
+    // standalone even in single-instance mode.
+    let stdin_stream = is_stdin_stream_requested(std::env::args().skip(1));
+    if stdin_stream {
+        commands::request_stdin_stream();
+    }
 
     let single_instance_mode_enabled = commands::is_single_instance_mode_enabled_in_config();
 
@@ -221,37 +496,62 @@ pub fn run() {
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
+        .setup(move |app| {
             setup_frontend_ready_listener(app.handle());
-            setup_main_window_state_tracking(app.handle());
-            setup_external_file_change_tracking(app.handle());
+            setup_startup_path_validation(app.handle());
+            // Safe mode skips restoring persisted state, watching files and
+            // every background task that writes to disk on its own.
+            if !safe_mode {
+                setup_main_window_state_tracking(app.handle());
+                setup_external_file_change_tracking(app.handle());
+                setup_session_persistence(app.handle());
+                setup_recovery_backups(app.handle());
+                commands::sync_shared_filter_rule_groups_watch(app.handle());
+                setup_auto_save(app.handle());
+                setup_idle_maintenance(app.handle());
+                commands::start_remote_control_server(app.handle(), emit_or_queue_open_paths);
+            }
             Ok(())
         });
 
-    if single_instance_mode_enabled {
+    if single_instance_mode_enabled && !stdin_stream {
         builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
             let startup_paths = collect_valid_startup_paths_from_args(args.into_iter().skip(1));
             emit_or_queue_open_paths(app, startup_paths);
         }));
     }
 
-    let app = builder
-        .manage(AppState::new(startup_paths))
-        .invoke_handler(tauri::generate_handler![
+    // Every command is timed for `get_performance_report`: sync commands here,
+    // async ones by their `CommandTimer` argument once they finish.
+    let command_handler: Box<dyn Fn(tauri::ipc::Invoke) -> bool + Send + Sync> =
+        Box::new(tauri::generate_handler![
             commands::file_io_commands::open_file,
             commands::file_io_commands::open_files,
             commands::file_io_commands::get_visible_lines,
             commands::file_io_commands::get_document_text,
             commands::file_io_commands::get_document_text_chunks,
             commands::file_io_commands::render_markdown_preview,
+            commands::file_io_commands::preview_file_encodings,
+            commands::file_io_commands::list_supported_encodings,
+            commands::file_io_commands::search_bytes_in_file,
             commands::file_io_commands::get_bookmark_line_previews,
             commands::file_io_commands::close_file,
             commands::file_io_commands::close_files,
+            commands::file_io_commands::create_document_view,
+            commands::file_io_commands::get_document_view,
+            commands::file_io_commands::update_document_view_state,
+            commands::file_io_commands::list_document_views,
+            commands::file_io_commands::close_document_view,
+            commands::file_io_commands::reopen_last_closed,
+            commands::file_io_commands::list_recently_closed,
             commands::file_io_commands::save_file,
             commands::file_io_commands::save_files,
             commands::file_io_commands::save_file_as,
+            commands::file_io_commands::save_copy_as,
+            commands::file_io_commands::extract_selection_to_file,
             commands::file_io_commands::convert_encoding,
             commands::file_io_commands::set_line_ending,
+            commands::file_io_commands::convert_line_endings_in_buffer,
             commands::file_io_commands::set_document_syntax,
             commands::file_io_commands::new_file,
             commands::file_io_commands::read_dir,
@@ -263,64 +563,188 @@ pub fn run() {
             commands::file_io_commands::delete_path,
             commands::file_io_commands::open_in_file_manager,
             commands::file_io_commands::get_word_count_info,
+            commands::file_io_commands::get_selection_statistics,
             commands::file_io_commands::get_document_size_bytes,
             commands::file_io_commands::detect_document_indentation,
             commands::file_io_commands::has_external_file_change,
             commands::file_io_commands::acknowledge_external_file_change,
+            commands::file_io_commands::is_file_locked,
             commands::file_io_commands::reload_file_from_disk,
+            commands::session_commands::save_session,
+            commands::session_commands::load_session,
+            commands::session_commands::get_document_session_extras,
             commands::editing_commands::undo,
             commands::editing_commands::redo,
+            commands::editing_commands::begin_edit_group,
+            commands::editing_commands::end_edit_group,
             commands::editing_commands::get_edit_history_state,
+            commands::editing_commands::get_dirty_state,
+            commands::editing_commands::undo_to_save_point,
+            commands::editing_commands::revert_to_history_index,
             commands::editing_commands::apply_text_edits_by_line_column,
+            commands::editing_commands::edit_text_multi,
             commands::editing_commands::convert_text_base64,
+            commands::editing_commands::transform_selection_text,
             commands::editing_commands::encode_image_file_as_data_url,
             commands::editing_commands::copy_image_file_to_clipboard,
             commands::editing_commands::copy_rgba_image_to_clipboard,
+            commands::editing_commands::push_clipboard_entry,
+            commands::editing_commands::list_clipboard_history,
+            commands::editing_commands::paste_clipboard_entry,
+            commands::editing_commands::clear_clipboard_history,
             commands::editing_commands::find_matching_pair_offsets,
             commands::editing_commands::replace_rectangular_selection_text,
             commands::editing_commands::get_rectangular_selection_text,
+            commands::editing_commands::insert_column_sequence,
+            commands::editing_commands::format_as_table,
+            commands::editing_commands::table_to_delimited,
+            commands::editing_commands::get_line_layout,
+            commands::editing_commands::should_auto_close_pair,
+            commands::editing_commands::compute_auto_indent,
+            commands::editing_commands::toggle_line_comments,
+            commands::editing_commands::toggle_block_comment,
             commands::editing_commands::cleanup_document,
             commands::editing_commands::format_document,
             commands::search_commands::search_first_in_document,
             commands::search_commands::search_in_document_chunk,
             commands::search_commands::search_session_start_in_document,
+            commands::search_commands::cancel_search_session,
             commands::search_commands::search_session_next_in_document,
+            commands::search_commands::search_session_previous_in_document,
+            commands::search_commands::search_session_seek,
             commands::search_commands::search_session_restore_in_document,
+            commands::search_commands::get_line_preview,
             commands::search_commands::dispose_search_session,
             commands::search_commands::step_result_filter_search_in_document,
             commands::search_commands::search_count_in_document,
             commands::search_commands::search_step_from_cursor_in_document,
             commands::search_commands::replace_all_and_search_chunk_in_document,
+            commands::search_commands::preview_replace_in_document,
+            commands::search_commands::replace_selected_in_document,
+            commands::search_commands::replace_selected_matches,
             commands::search_commands::replace_current_and_search_chunk_in_document,
             commands::search_commands::filter_in_document_chunk,
             commands::search_commands::filter_session_start_in_document,
             commands::search_commands::filter_session_next_in_document,
+            commands::search_commands::filter_session_previous_in_document,
             commands::search_commands::filter_session_restore_in_document,
             commands::search_commands::dispose_filter_session,
             commands::search_commands::step_result_filter_search_in_filter_document,
+            commands::search_commands::search_in_rectangle,
+            commands::search_commands::replace_in_rectangle,
+            commands::search_commands::search_in_directory,
+            commands::search_commands::cancel_directory_search,
+            commands::search_commands::preview_replace_in_directory,
+            commands::search_commands::replace_in_directory,
             commands::search_commands::filter_count_in_document,
             commands::path_search_commands::path_search_start,
             commands::path_search_commands::path_search_next,
             commands::path_search_commands::path_search_dispose,
             commands::path_search_commands::path_replace_preview,
             commands::path_search_commands::path_replace_apply,
+            commands::git_commands::get_git_line_status,
+            commands::git_commands::get_git_file_status,
+            commands::terminal_commands::create_terminal,
+            commands::terminal_commands::write_terminal,
+            commands::terminal_commands::resize_terminal,
+            commands::terminal_commands::close_terminal,
             commands::translate_document_text,
             commands::get_document_version,
+            commands::detect_csv_dialect,
+            commands::sort_csv_by_column,
+            commands::filter_csv_rows,
+            commands::filter_json_lines,
+            commands::expand_json_lines_view,
+            commands::map_derived_view_line,
+            commands::open_log_series,
+            commands::map_log_series_line,
+            commands::promote_document_to_full_mode,
+            commands::open_virtual_document,
+            commands::get_virtual_document_lines,
+            commands::search_virtual_document,
+            commands::filter_virtual_document,
+            commands::close_virtual_document,
+            commands::search_streaming_document,
+            commands::get_hex_view_chunk,
+            commands::edit_bytes,
+            commands::undo_byte_edit,
+            commands::redo_byte_edit,
+            commands::set_document_word_wrap,
+            commands::get_visual_line_count,
+            commands::find_extreme_lines,
+            commands::get_word_at,
+            commands::set_line_annotations,
+            commands::get_line_annotations,
+            commands::clear_line_annotations,
+            commands::add_temp_highlight,
+            commands::list_temp_highlights,
+            commands::clear_temp_highlights,
+            commands::get_temp_highlight_ranges,
+            commands::get_bracket_depths,
+            commands::get_fold_ranges,
             commands::compare_documents_by_line,
+            commands::compare_document_with_text,
+            commands::compare_text_fragments,
+            commands::compare_with_snapshot,
+            commands::convert_folder_encoding,
+            commands::normalize_folder_line_endings,
+            commands::take_document_snapshot,
+            commands::list_document_snapshots,
+            commands::search_in_diff,
+            commands::compare_document_with_clipboard,
             commands::get_unsaved_change_line_numbers,
             commands::search_diff_panel_aligned_row_matches,
             commands::preview_aligned_diff_state,
             commands::apply_aligned_diff_panel_copy,
             commands::apply_aligned_diff_edit,
+            commands::apply_diff_hunk,
+            commands::merge_documents,
+            commands::list_merge_conflicts,
+            commands::resolve_merge_conflict,
             commands::get_outline,
+            commands::export_outline,
             commands::filter_outline_nodes,
+            commands::get_patch_hunks,
+            commands::get_adjacent_patch_hunk,
+            commands::resolve_patch_hunk_source,
+            commands::get_hover_info,
+            commands::resolve_structured_path,
+            commands::validate_ini_document,
             commands::list_system_fonts,
+            commands::record_reading_position,
+            commands::get_reading_position,
+            commands::list_reading_positions,
+            commands::add_line_note,
+            commands::list_line_notes,
+            commands::export_notes,
+            commands::pin_search_results,
+            commands::list_pinned_results,
+            commands::open_pinned_results,
+            commands::get_locale_strings,
+            commands::list_palette_commands,
+            commands::invoke_palette_command,
+            commands::get_startup_mode,
+            commands::open_stdin_stream,
+            commands::open_named_pipe_stream,
+            commands::set_stream_follow,
+            commands::stop_stream,
             commands::load_config,
             commands::save_config,
+            commands::add_recent_file,
+            commands::add_recent_folder,
+            commands::remove_recent,
+            commands::clear_recent,
             commands::load_filter_rule_groups_config,
             commands::save_filter_rule_groups_config,
             commands::import_filter_rule_groups,
             commands::export_filter_rule_groups,
+            commands::set_rule_alert,
+            commands::export_filtered_view,
+            commands::export_document,
+            commands::store_secret,
+            commands::has_secret,
+            commands::delete_secret,
+            commands::diff_filter_rule_groups,
             commands::register_windows_context_menu,
             commands::unregister_windows_context_menu,
             commands::is_windows_context_menu_registered,
@@ -329,16 +753,37 @@ pub fn run() {
             commands::remove_windows_file_associations,
             commands::get_windows_file_association_status,
             commands::get_startup_paths,
+            commands::run_idle_maintenance,
+            commands::get_performance_report,
+            commands::list_recovery_files,
+            commands::restore_recovery_file,
+            commands::discard_recovery_file,
             show_main_window_when_ready
-        ])
+        ]);
+
+    let app = builder
+        .manage(AppState::new(Vec::new()))
+        .invoke_handler(move |invoke| {
+            let command = invoke.message.command().to_string();
+            if commands::is_async_command(&command) {
+                return command_handler(invoke);
+            }
+            let params = commands::summarize_invoke_body(invoke.message.payload());
+            let started = Instant::now();
+            let handled = command_handler(invoke);
+            commands::record_command_timing(&command, params, started.elapsed());
+            handled
+        })
         .build(tauri::generate_context!());
 
     match app {
-        Ok(app) => app.run(|app_handle, event| {
-            if let tauri::RunEvent::Opened { urls } = event {
+        Ok(app) => app.run(|app_handle, event| match event {
+            tauri::RunEvent::Opened { urls } => {
                 let startup_paths = collect_valid_startup_paths_from_urls(urls);
                 emit_or_queue_open_paths(app_handle, startup_paths);
             }
+            tauri::RunEvent::Exit => commands::discard_recovery_backups_on_exit(),
+            _ => {}
         }),
         Err(err) => eprintln!("error while running tauri application: {err}"),
     }
@@ -346,7 +791,11 @@ pub fn run() {
 
 #[cfg(test)]
 mod tests {
-    use super::{collect_valid_startup_paths_from_args, collect_valid_startup_paths_from_urls};
+    use super::{
+        collect_valid_startup_paths_from_args, collect_valid_startup_paths_from_urls,
+        is_safe_mode_requested, is_stdin_stream_requested,
+    };
+    use crate::state::{GotoPosition, StartupPath};
     use std::fs;
     use std::path::PathBuf;
 
@@ -388,7 +837,52 @@ mod tests {
         ];
 
         let paths = collect_valid_startup_paths_from_args(args);
-        assert_eq!(paths, vec![existing_file, existing_dir]);
+        assert_eq!(
+            paths,
+            vec![
+                StartupPath::new(existing_file),
+                StartupPath::new(existing_dir)
+            ]
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn collect_valid_startup_paths_from_args_should_parse_goto_positions() {
+        let (root, existing_file, _existing_dir, missing_file) = make_temp_workspace();
+
+        let args = vec![
+            format!("{existing_file}:12:5"),
+            format!("{missing_file}:3"),
+            "--line".to_string(),
+            "7".to_string(),
+            existing_file.clone(),
+            "--column=2".to_string(),
+            format!("{existing_file}:9"),
+            existing_file.clone(),
+        ];
+
+        let paths = collect_valid_startup_paths_from_args(args);
+        let goto = |line, column| Some(GotoPosition { line, column });
+        assert_eq!(
+            paths,
+            vec![
+                StartupPath {
+                    path: existing_file.clone(),
+                    goto_position: goto(12, 5),
+                },
+                StartupPath {
+                    path: existing_file.clone(),
+                    goto_position: goto(7, 1),
+                },
+                StartupPath {
+                    path: existing_file.clone(),
+                    goto_position: goto(9, 2),
+                },
+                StartupPath::new(existing_file),
+            ]
+        );
 
         let _ = fs::remove_dir_all(root);
     }
@@ -402,8 +896,20 @@ mod tests {
         let web_url = tauri::Url::parse("https://example.com/file.txt").expect("valid web url");
 
         let paths = collect_valid_startup_paths_from_urls(vec![existing_url, missing_url, web_url]);
-        assert_eq!(paths, vec![existing_file]);
+        assert_eq!(paths, vec![StartupPath::new(existing_file)]);
 
         let _ = fs::remove_dir_all(root);
     }
+
+    #[test]
+    fn is_safe_mode_requested_should_match_exact_flag_only() {
+        assert!(is_safe_mode_requested(["file.txt", "--safe-mode"]));
+        assert!(!is_safe_mode_requested(["--safe-mode-x", "safe-mode"]));
+    }
+
+    #[test]
+    fn is_stdin_stream_requested_should_match_lone_dash_only() {
+        assert!(is_stdin_stream_requested(["--safe-mode", "-"]));
+        assert!(!is_stdin_stream_requested(["-x", "--", "file.txt"]));
+    }
 }
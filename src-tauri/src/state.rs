@@ -1,10 +1,22 @@
-﻿use dashmap::DashMap;
+﻿use crate::commands::AppConfig;
+use dashmap::DashMap;
 use encoding_rs::Encoding;
-use notify::RecommendedWatcher;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use ropey::Rope;
-use std::path::PathBuf;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Wall-clock milliseconds since the Unix epoch, or 0 for a clock set before
+// it. Used for every timestamp that is shown or persisted.
+pub fn now_unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct FileFingerprint {
@@ -38,6 +50,35 @@ impl LineEnding {
     }
 }
 
+// Controls which access other processes keep while Rutar holds a file handle.
+// Only meaningful on Windows; other platforms use advisory locking and ignore it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FileShareMode {
+    #[default]
+    ReadWrite,
+    Read,
+    Exclusive,
+}
+
+impl FileShareMode {
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_ascii_lowercase().as_str() {
+            "readwrite" => Some(Self::ReadWrite),
+            "read" => Some(Self::Read),
+            "exclusive" => Some(Self::Exclusive),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ReadWrite => "readWrite",
+            Self::Read => "read",
+            Self::Exclusive => "exclusive",
+        }
+    }
+}
+
 #[cfg(windows)]
 pub fn default_line_ending() -> LineEnding {
     LineEnding::CrLf
@@ -54,6 +95,13 @@ pub struct CursorSnapshot {
     pub column: usize,
 }
 
+// An edit group left open this long without new edits closes itself, so a
+// frontend that never ends its group does not swallow all later history.
+pub const EDIT_GROUP_IDLE_TIMEOUT_MS: u64 = 10_000;
+// Insertions typed within this window of each other at adjacent positions
+// undo as one step even without an explicit edit group.
+pub const TYPING_COALESCE_WINDOW_MS: u64 = 1_000;
+
 #[derive(Clone)]
 pub struct EditOperation {
     pub operation_id: u64,
@@ -62,9 +110,19 @@ pub struct EditOperation {
     pub new_text: String,
     pub before_cursor: Option<CursorSnapshot>,
     pub after_cursor: Option<CursorSnapshot>,
+    // Operations sharing a group are undone and redone as one step.
+    pub group_id: Option<u64>,
+    pub applied_at_ms: u64,
 }
 
 impl EditOperation {
+    // A single-line insertion, as produced by typing.
+    fn is_typed_insertion(&self) -> bool {
+        self.old_text.is_empty()
+            && !self.new_text.is_empty()
+            && !self.new_text.contains(['\n', '\r'])
+    }
+
     pub fn inverse(&self) -> Self {
         Self {
             operation_id: self.operation_id,
@@ -73,10 +131,338 @@ impl EditOperation {
             new_text: self.old_text.clone(),
             before_cursor: self.after_cursor.clone(),
             after_cursor: self.before_cursor.clone(),
+            group_id: self.group_id,
+            applied_at_ms: self.applied_at_ms,
+        }
+    }
+}
+
+// 1-based caret target carried along with a path to open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GotoPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+// A path handed to the frontend to open: from the command line, a second
+// instance, the OS or the remote control endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupPath {
+    pub path: String,
+    pub goto_position: Option<GotoPosition>,
+}
+
+impl StartupPath {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            goto_position: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineAnnotation {
+    // 1-based line number, matching bookmarks and diff line numbers.
+    pub line: usize,
+    #[serde(default)]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+// Keyword marked by the user while reading; ranges are resolved on demand so
+// they follow edits without any bookkeeping.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempHighlight {
+    pub keyword: String,
+    pub color: String,
+}
+
+// Gutter annotations grouped by producer (diagnostics, git, bookmarks, ...).
+// Each source's list is kept sorted by line so range queries stay cheap.
+#[derive(Default)]
+pub struct LineAnnotationStore {
+    sources: HashMap<String, Vec<LineAnnotation>>,
+}
+
+impl LineAnnotationStore {
+    pub fn set(&mut self, source: String, mut annotations: Vec<LineAnnotation>) {
+        annotations.retain(|annotation| annotation.line > 0);
+        if annotations.is_empty() {
+            self.sources.remove(&source);
+            return;
+        }
+
+        annotations.sort_by_key(|annotation| annotation.line);
+        self.sources.insert(source, annotations);
+    }
+
+    pub fn clear(&mut self, source: Option<&str>) {
+        match source {
+            Some(source) => {
+                self.sources.remove(source);
+            }
+            None => self.sources.clear(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    pub fn range(&self, start_line: usize, end_line: usize) -> Vec<(&str, &LineAnnotation)> {
+        let mut entries = Vec::new();
+        for (source, annotations) in &self.sources {
+            let first = annotations.partition_point(|annotation| annotation.line < start_line);
+            entries.extend(
+                annotations[first..]
+                    .iter()
+                    .take_while(|annotation| annotation.line <= end_line)
+                    .map(|annotation| (source.as_str(), annotation)),
+            );
+        }
+
+        entries.sort_by(|left, right| left.1.line.cmp(&right.1.line).then(left.0.cmp(right.0)));
+        entries
+    }
+
+    // Keeps annotations attached to their text after `edit`. Annotations on
+    // lines removed or merged away by the edit are dropped.
+    pub fn adjust_for_edit(&mut self, edit: LineShift) {
+        if edit.is_noop() {
+            return;
+        }
+
+        for annotations in self.sources.values_mut() {
+            annotations.retain_mut(|annotation| match edit.shift_line(annotation.line) {
+                Some(line) => {
+                    annotation.line = line;
+                    true
+                }
+                None => false,
+            });
+        }
+        self.sources
+            .retain(|_, annotations| !annotations.is_empty());
+    }
+}
+
+// How one edit moved 1-based lines: starting on `start_line`, it removed and
+// inserted the given number of line breaks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineShift {
+    pub start_line: usize,
+    pub removed_lines: usize,
+    pub inserted_lines: usize,
+    pub starts_at_line_start: bool,
+}
+
+impl LineShift {
+    fn is_noop(&self) -> bool {
+        self.removed_lines == 0 && self.inserted_lines == 0
+    }
+
+    // Where `line` ends up after the edit; None for a line removed or merged
+    // away by it.
+    pub fn shift_line(&self, line: usize) -> Option<usize> {
+        // From column 0 the edit takes the start line with it: removed lines
+        // go away whole and the first line after them moves into their place.
+        // Mid-line, the start line keeps its head and the removed lines merge
+        // into it.
+        let (last_kept_line, first_shifted_line) = if self.starts_at_line_start {
+            (self.start_line - 1, self.start_line + self.removed_lines)
+        } else {
+            (self.start_line, self.start_line + self.removed_lines + 1)
+        };
+
+        if line < first_shifted_line {
+            return (line <= last_kept_line).then_some(line);
+        }
+        Some(line + self.inserted_lines - self.removed_lines)
+    }
+}
+
+// Cursor, scroll and folding of one view onto a document. A document can have
+// several views, e.g. a cloned tab; edits and versions stay on the document.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentViewState {
+    // 1-based, like the editor's positions.
+    pub cursor_line: usize,
+    pub cursor_column: usize,
+    pub scroll_top_line: usize,
+    // 1-based start lines of the folded ranges.
+    pub folded_lines: Vec<usize>,
+}
+
+impl Default for DocumentViewState {
+    fn default() -> Self {
+        Self {
+            cursor_line: 1,
+            cursor_column: 1,
+            scroll_top_line: 1,
+            folded_lines: Vec::new(),
+        }
+    }
+}
+
+pub struct DocumentView {
+    pub document_id: String,
+}
+
+// View states of one document, keyed by view id. They live on the document so
+// edits made through any view shift the others' lines like annotations.
+#[derive(Default)]
+pub struct DocumentViewStore {
+    states: HashMap<String, DocumentViewState>,
+}
+
+impl DocumentViewStore {
+    pub fn get(&self, view_id: &str) -> Option<&DocumentViewState> {
+        self.states.get(view_id)
+    }
+
+    pub fn set(&mut self, view_id: String, state: DocumentViewState) {
+        self.states.insert(view_id, state);
+    }
+
+    pub fn remove(&mut self, view_id: &str) -> bool {
+        self.states.remove(view_id).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    // Cursor and scroll lines removed or merged away by the edit move to its
+    // start line; folds starting on them are dropped.
+    pub fn adjust_for_edit(&mut self, edit: LineShift) {
+        if edit.is_noop() {
+            return;
+        }
+
+        for state in self.states.values_mut() {
+            state.cursor_line = edit
+                .shift_line(state.cursor_line)
+                .unwrap_or(edit.start_line);
+            state.scroll_top_line = edit
+                .shift_line(state.scroll_top_line)
+                .unwrap_or(edit.start_line);
+            state.folded_lines = state
+                .folded_lines
+                .iter()
+                .filter_map(|line| edit.shift_line(*line))
+                .collect();
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardEntry {
+    pub text: String,
+    pub copied_at_ms: u64,
+}
+
+// A generated, read-only document with the source line behind each of its
+// lines, so the view can jump back to the original.
+pub struct DerivedView {
+    pub source_id: String,
+    // 1-based source line for each view line, in view line order.
+    pub source_lines: Vec<usize>,
+}
+
+// Contiguous copy of a document's text with the offsets search works in:
+// where each line starts and char counts at fixed byte intervals, from which
+// byte offsets are converted to char indices on demand.
+pub struct DocumentTextSnapshot {
+    pub document_version: u64,
+    pub text: String,
+    pub line_starts: Vec<usize>,
+    pub char_checkpoints: Vec<usize>,
+}
+
+impl DocumentTextSnapshot {
+    pub fn approximate_bytes(&self) -> usize {
+        self.text.capacity()
+            + (self.line_starts.capacity() + self.char_checkpoints.capacity())
+                * std::mem::size_of::<usize>()
+    }
+}
+
+// Holds the snapshot taken at the latest searched version, so repeated
+// searches, counts and steps share one copy until the text changes.
+#[derive(Default)]
+pub struct TextSnapshotCell {
+    slot: Mutex<Option<(Arc<DocumentTextSnapshot>, Instant)>>,
+}
+
+impl TextSnapshotCell {
+    // Builds under the lock, so concurrent searches wait for one build
+    // instead of each copying the text.
+    pub fn get_or_build(
+        &self,
+        document_version: u64,
+        build: impl FnOnce() -> DocumentTextSnapshot,
+    ) -> Arc<DocumentTextSnapshot> {
+        let mut slot = self
+            .slot
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((snapshot, touched_at)) = slot.as_mut() {
+            if snapshot.document_version == document_version {
+                *touched_at = Instant::now();
+                return Arc::clone(snapshot);
+            }
+        }
+
+        let snapshot = Arc::new(build());
+        *slot = Some((Arc::clone(&snapshot), Instant::now()));
+        snapshot
+    }
+
+    // Returns the approximate bytes released.
+    pub fn clear(&self) -> usize {
+        self.slot
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+            .map(|(snapshot, _)| snapshot.approximate_bytes())
+            .unwrap_or(0)
+    }
+
+    pub fn clear_if_unused_for(&self, max_age: Duration) -> usize {
+        let mut slot = self
+            .slot
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match slot.as_ref() {
+            Some((snapshot, touched_at)) if touched_at.elapsed() >= max_age => {
+                let reclaimed_bytes = snapshot.approximate_bytes();
+                *slot = None;
+                reclaimed_bytes
+            }
+            _ => 0,
         }
     }
 }
 
+// Position in the undo history that matches the file on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SavePoint {
+    pub undo_depth: usize,
+    pub operation_id: Option<u64>,
+}
+
 pub struct Document {
     pub rope: Rope,
     pub saved_rope: Rope,
@@ -86,49 +472,665 @@ pub struct Document {
     pub saved_line_ending: LineEnding,
     pub path: Option<PathBuf>,
     pub syntax_override: Option<String>,
+    // None follows the global word wrap setting.
+    pub word_wrap_override: Option<bool>,
     pub document_version: u64,
     pub saved_document_version: u64,
     pub next_edit_operation_id: u64,
     pub undo_stack: Vec<EditOperation>,
     pub redo_stack: Vec<EditOperation>,
-    pub saved_undo_depth: usize,
+    // Set between begin and end of an edit group; new operations join it.
+    pub open_edit_group: Option<OpenEditGroup>,
+    // None once trimming dropped the saved state from the undo history.
+    pub saved_undo_depth: Option<usize>,
     pub saved_undo_operation_id: Option<u64>,
     pub saved_file_fingerprint: Option<FileFingerprint>,
+    pub share_mode: FileShareMode,
+    // Original file bytes, kept only when decoding was lossy so saves can copy
+    // untouched lines back verbatim.
+    pub raw_bytes: Option<Arc<[u8]>>,
+    pub line_annotations: LineAnnotationStore,
+    pub views: DocumentViewStore,
+    pub temp_highlights: Vec<TempHighlight>,
+    // Set for read-only documents generated from another one.
+    pub derived_view: Option<DerivedView>,
+    // Opened read-only without syntax parsing because of its size or location,
+    // until the user promotes it to full mode.
+    pub preview_mode: bool,
+    pub text_snapshot: TextSnapshotCell,
+}
+
+#[derive(Clone, Copy)]
+pub struct OpenEditGroup {
+    pub group_id: u64,
+    // When the group was begun or last joined by an operation.
+    pub touched_at_ms: u64,
 }
 
 impl Document {
+    // A clean document without history whose saved state is `rope`. Callers
+    // override what differs with struct update syntax.
+    pub fn new(
+        rope: Rope,
+        encoding: &'static Encoding,
+        line_ending: LineEnding,
+        path: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            rope: rope.clone(),
+            saved_rope: rope,
+            encoding,
+            saved_encoding: encoding.name().to_string(),
+            line_ending,
+            saved_line_ending: line_ending,
+            path,
+            syntax_override: None,
+            word_wrap_override: None,
+            document_version: 0,
+            saved_document_version: 0,
+            next_edit_operation_id: 1,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            open_edit_group: None,
+            saved_undo_depth: Some(0),
+            saved_undo_operation_id: None,
+            saved_file_fingerprint: None,
+            share_mode: FileShareMode::default(),
+            raw_bytes: None,
+            line_annotations: LineAnnotationStore::default(),
+            views: DocumentViewStore::default(),
+            temp_highlights: Vec::new(),
+            derived_view: None,
+            preview_mode: false,
+            text_snapshot: TextSnapshotCell::default(),
+        }
+    }
+
+    // Clean UTF-8 document for tests, shared so fixtures follow new fields.
+    #[cfg(test)]
+    pub fn for_test(text: &str, path: Option<&str>) -> Self {
+        Self::new(
+            Rope::from_str(text),
+            encoding_rs::UTF_8,
+            default_line_ending(),
+            path.map(PathBuf::from),
+        )
+    }
+
     pub fn allocate_edit_operation_id(&mut self) -> u64 {
         let operation_id = self.next_edit_operation_id;
         self.next_edit_operation_id = self.next_edit_operation_id.saturating_add(1);
         operation_id
     }
 
+    // Starts grouping new operations into one undo step. Beginning while a
+    // group is open keeps extending that group.
+    pub fn begin_edit_group(&mut self) -> u64 {
+        let now = now_unix_millis();
+        if let Some(group_id) = self.touch_open_edit_group(now) {
+            return group_id;
+        }
+        let group_id = self.allocate_edit_operation_id();
+        self.open_edit_group = Some(OpenEditGroup {
+            group_id,
+            touched_at_ms: now,
+        });
+        group_id
+    }
+
+    // Returns whether a group was still open; one that timed out counts as
+    // already closed.
+    pub fn end_edit_group(&mut self) -> bool {
+        let now = now_unix_millis();
+        self.touch_open_edit_group(now).is_some() && self.open_edit_group.take().is_some()
+    }
+
+    // Closes the open group once it has been idle for
+    // EDIT_GROUP_IDLE_TIMEOUT_MS, otherwise marks it as used at `now`.
+    fn touch_open_edit_group(&mut self, now: u64) -> Option<u64> {
+        let group = self.open_edit_group.as_mut()?;
+        if now.saturating_sub(group.touched_at_ms) > EDIT_GROUP_IDLE_TIMEOUT_MS {
+            self.open_edit_group = None;
+            return None;
+        }
+        group.touched_at_ms = now;
+        Some(group.group_id)
+    }
+
+    // Group for a new operation outside an explicit edit group: a typed
+    // insertion continuing the previous one within TYPING_COALESCE_WINDOW_MS
+    // joins its step, unless the document was saved in between.
+    fn coalesced_group_id(&mut self, operation: &EditOperation) -> Option<u64> {
+        if !operation.is_typed_insertion()
+            || self.saved_undo_depth == Some(self.undo_stack.len())
+            || !self.redo_stack.is_empty()
+        {
+            return None;
+        }
+        let previous = self.undo_stack.last_mut()?;
+        let continues_previous = previous.is_typed_insertion()
+            && previous.start_char + previous.new_text.chars().count() == operation.start_char
+            && operation
+                .applied_at_ms
+                .saturating_sub(previous.applied_at_ms)
+                <= TYPING_COALESCE_WINDOW_MS;
+        if !continues_previous {
+            return None;
+        }
+        Some(*previous.group_id.get_or_insert(previous.operation_id))
+    }
+
+    pub fn current_save_point(&self) -> SavePoint {
+        SavePoint {
+            undo_depth: self.undo_stack.len(),
+            operation_id: self
+                .undo_stack
+                .last()
+                .map(|operation| operation.operation_id),
+        }
+    }
+
+    pub fn mark_save_point(&mut self, save_point: SavePoint) {
+        self.saved_undo_depth = Some(save_point.undo_depth);
+        self.saved_undo_operation_id = save_point.operation_id;
+    }
+
+    // Single operations between the current state and the save point: positive
+    // means undo, negative redo. None once trimming or a new branch of edits
+    // has dropped the save point from the history.
+    pub fn save_point_distance(&self) -> Option<isize> {
+        let depth = self.undo_stack.len();
+        let saved_depth = self.saved_undo_depth?;
+        if saved_depth <= depth {
+            let operation_id = saved_depth
+                .checked_sub(1)
+                .map(|index| self.undo_stack[index].operation_id);
+            return (operation_id == self.saved_undo_operation_id)
+                .then(|| (depth - saved_depth) as isize);
+        }
+
+        let ahead = saved_depth - depth;
+        let index = self.redo_stack.len().checked_sub(ahead)?;
+        (Some(self.redo_stack[index].operation_id) == self.saved_undo_operation_id)
+            .then(|| -(ahead as isize))
+    }
+
     pub fn has_unsaved_text_changes(&self) -> bool {
-        self.saved_undo_depth != self.undo_stack.len()
+        self.saved_undo_depth != Some(self.undo_stack.len())
             || self.saved_undo_operation_id
                 != self
                     .undo_stack
                     .last()
                     .map(|operation| operation.operation_id)
     }
+
+    pub fn engine(&mut self) -> DocumentEngine<'_> {
+        DocumentEngine {
+            doc: self,
+            transaction_depth: 0,
+            changed: false,
+        }
+    }
+}
+
+// Counts line breaks the same way ropey does so annotation shifts match rope lines.
+fn count_line_breaks(text: &str) -> usize {
+    ropey::str_utils::byte_to_line_idx(text, text.len())
+}
+
+// Applies one operation to the rope without touching the history stacks.
+fn apply_operation_to_rope(doc: &mut Document, operation: &EditOperation) -> Result<(), String> {
+    let rope = &mut doc.rope;
+    let start = operation.start_char.min(rope.len_chars());
+    let old_char_len = operation.old_text.chars().count();
+    let end = start
+        .checked_add(old_char_len)
+        .ok_or_else(|| "Edit range overflow".to_string())?;
+
+    if end > rope.len_chars() {
+        return Err("Edit range out of bounds".to_string());
+    }
+
+    let current_old = rope.slice(start..end).to_string();
+    if current_old != operation.old_text {
+        return Err("Edit history out of sync".to_string());
+    }
+
+    let start_line = rope.char_to_line(start);
+    let starts_at_line_start = rope.line_to_char(start_line) == start;
+
+    if start < end {
+        rope.remove(start..end);
+    }
+
+    if !operation.new_text.is_empty() {
+        rope.insert(start, &operation.new_text);
+    }
+
+    let line_shift = LineShift {
+        start_line: start_line + 1,
+        removed_lines: count_line_breaks(&operation.old_text),
+        inserted_lines: count_line_breaks(&operation.new_text),
+        starts_at_line_start,
+    };
+    if !doc.line_annotations.is_empty() {
+        doc.line_annotations.adjust_for_edit(line_shift);
+    }
+    if !doc.views.is_empty() {
+        doc.views.adjust_for_edit(line_shift);
+    }
+
+    doc.document_version = doc.document_version.saturating_add(1);
+    doc.text_snapshot.clear();
+    Ok(())
+}
+
+// What a caller needs after mutating a document: whether anything changed and
+// the version to invalidate version-keyed caches against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DocumentChange {
+    pub changed: bool,
+    pub document_version: u64,
+    pub line_count: usize,
+}
+
+// The single path for text mutations. Commands go through it so version bumps,
+// undo recording, redo invalidation and annotation shifts are handled in one
+// place.
+pub struct DocumentEngine<'a> {
+    doc: &'a mut Document,
+    transaction_depth: usize,
+    changed: bool,
+}
+
+impl DocumentEngine<'_> {
+    pub fn document(&self) -> &Document {
+        self.doc
+    }
+
+    pub fn new_operation(
+        &mut self,
+        start_char: usize,
+        old_text: String,
+        new_text: String,
+    ) -> EditOperation {
+        let mut operation = self.unrecorded_operation(start_char, old_text, new_text);
+        operation.group_id = match self.doc.touch_open_edit_group(operation.applied_at_ms) {
+            Some(group_id) => Some(group_id),
+            None => self.doc.coalesced_group_id(&operation),
+        };
+        operation
+    }
+
+    fn unrecorded_operation(
+        &mut self,
+        start_char: usize,
+        old_text: String,
+        new_text: String,
+    ) -> EditOperation {
+        EditOperation {
+            operation_id: self.doc.allocate_edit_operation_id(),
+            start_char,
+            old_text,
+            new_text,
+            before_cursor: None,
+            after_cursor: None,
+            group_id: None,
+            applied_at_ms: now_unix_millis(),
+        }
+    }
+
+    // Applies and records a new operation. Inside a transaction the redo stack
+    // is left alone until commit so a rollback keeps it intact.
+    pub fn apply(&mut self, operation: EditOperation) -> Result<(), String> {
+        if self.doc.derived_view.is_some() {
+            return Err("Document is a read-only view".to_string());
+        }
+        if self.doc.preview_mode {
+            return Err("Document is open in preview mode".to_string());
+        }
+        apply_operation_to_rope(self.doc, &operation)?;
+        self.doc.undo_stack.push(operation);
+        if self.transaction_depth == 0 {
+            self.doc.redo_stack.clear();
+        }
+        self.changed = true;
+        Ok(())
+    }
+
+    pub fn replace(
+        &mut self,
+        start_char: usize,
+        old_text: String,
+        new_text: String,
+    ) -> Result<(), String> {
+        let operation = self.new_operation(start_char, old_text, new_text);
+        self.apply(operation)
+    }
+
+    // Appends output from a live stream at the end of the document without
+    // recording it, so undo never takes back text the stream produced.
+    // Recorded operations all start before the old end, so their offsets stay
+    // valid.
+    pub fn append_unrecorded(&mut self, text: &str) -> Result<(), String> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        let end = self.doc.rope.len_chars();
+        let operation = self.unrecorded_operation(end, String::new(), text.to_string());
+        apply_operation_to_rope(self.doc, &operation)?;
+        self.changed = true;
+        Ok(())
+    }
+
+    // Encoding and line ending only take effect on save, so they change the
+    // version without touching the text or the undo history.
+    pub fn set_encoding(&mut self, encoding: &'static Encoding) {
+        if self.doc.encoding != encoding {
+            self.doc.encoding = encoding;
+            self.bump_metadata_version();
+        }
+    }
+
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        if self.doc.line_ending != line_ending {
+            self.doc.line_ending = line_ending;
+            self.bump_metadata_version();
+        }
+    }
+
+    fn bump_metadata_version(&mut self) {
+        self.doc.document_version = self.doc.document_version.saturating_add(1);
+        self.changed = true;
+    }
+
+    // Applies operations in order as one all-or-nothing unit.
+    pub fn batch(&mut self, operations: Vec<EditOperation>) -> Result<(), String> {
+        self.transaction(|engine| {
+            operations
+                .into_iter()
+                .try_for_each(|operation| engine.apply(operation))
+        })
+    }
+
+    // Runs `edit`; if it fails, every operation it recorded is reverted so the
+    // document and its history look untouched.
+    pub fn transaction<T>(
+        &mut self,
+        edit: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let undo_depth = self.doc.undo_stack.len();
+        let was_changed = self.changed;
+        self.transaction_depth += 1;
+        let result = edit(self);
+        self.transaction_depth -= 1;
+
+        match result {
+            Ok(value) => {
+                if self.transaction_depth == 0 && self.doc.undo_stack.len() > undo_depth {
+                    self.doc.redo_stack.clear();
+                }
+                Ok(value)
+            }
+            Err(error) => {
+                while self.doc.undo_stack.len() > undo_depth {
+                    let Some(operation) = self.doc.undo_stack.pop() else {
+                        break;
+                    };
+                    apply_operation_to_rope(self.doc, &operation.inverse())?;
+                }
+                self.changed = was_changed;
+                Err(error)
+            }
+        }
+    }
+
+    // Returns the undone operation, now on top of the redo stack. A grouped
+    // operation takes the rest of its group with it, and the one returned is
+    // the group's first. Undo closes any open group.
+    pub fn undo(&mut self) -> Result<Option<&EditOperation>, String> {
+        self.doc.open_edit_group = None;
+        let Some(operation) = self.doc.undo_stack.pop() else {
+            return Ok(None);
+        };
+        let group_id = operation.group_id;
+        self.undo_operation(operation)?;
+        while group_id.is_some()
+            && self
+                .doc
+                .undo_stack
+                .last()
+                .is_some_and(|operation| operation.group_id == group_id)
+        {
+            let Some(operation) = self.doc.undo_stack.pop() else {
+                break;
+            };
+            self.undo_operation(operation)?;
+        }
+        Ok(self.doc.redo_stack.last())
+    }
+
+    // Returns the redone operation, now on top of the undo stack; groups are
+    // redone whole, like undo.
+    pub fn redo(&mut self) -> Result<Option<&EditOperation>, String> {
+        self.doc.open_edit_group = None;
+        let Some(operation) = self.doc.redo_stack.pop() else {
+            return Ok(None);
+        };
+        let group_id = operation.group_id;
+        self.redo_operation(operation)?;
+        while group_id.is_some()
+            && self
+                .doc
+                .redo_stack
+                .last()
+                .is_some_and(|operation| operation.group_id == group_id)
+        {
+            let Some(operation) = self.doc.redo_stack.pop() else {
+                break;
+            };
+            self.redo_operation(operation)?;
+        }
+        Ok(self.doc.undo_stack.last())
+    }
+
+    // Steps one operation at a time, ignoring groups, until the document is
+    // back at its save point. Returns how many operations were stepped over.
+    pub fn undo_to_save_point(&mut self) -> Result<usize, String> {
+        let distance = self
+            .doc
+            .save_point_distance()
+            .ok_or_else(|| "Save point is no longer in the undo history".to_string())?;
+        self.doc.open_edit_group = None;
+
+        for _ in 0..distance.unsigned_abs() {
+            if distance > 0 {
+                let Some(operation) = self.doc.undo_stack.pop() else {
+                    break;
+                };
+                self.undo_operation(operation)?;
+            } else {
+                let Some(operation) = self.doc.redo_stack.pop() else {
+                    break;
+                };
+                self.redo_operation(operation)?;
+            }
+        }
+        Ok(distance.unsigned_abs())
+    }
+
+    fn undo_operation(&mut self, operation: EditOperation) -> Result<(), String> {
+        if let Err(error) = apply_operation_to_rope(self.doc, &operation.inverse()) {
+            self.doc.undo_stack.push(operation);
+            return Err(error);
+        }
+        self.doc.redo_stack.push(operation);
+        self.changed = true;
+        Ok(())
+    }
+
+    fn redo_operation(&mut self, operation: EditOperation) -> Result<(), String> {
+        if let Err(error) = apply_operation_to_rope(self.doc, &operation) {
+            self.doc.redo_stack.push(operation);
+            return Err(error);
+        }
+        self.doc.undo_stack.push(operation);
+        self.changed = true;
+        Ok(())
+    }
+
+    pub fn finish(self) -> DocumentChange {
+        DocumentChange {
+            changed: self.changed,
+            document_version: self.doc.document_version,
+            line_count: self.doc.rope.len_lines(),
+        }
+    }
 }
 
 #[cfg(test)]
 impl Document {
     pub fn mark_saved_undo_checkpoint(&mut self) {
-        self.saved_undo_depth = self.undo_stack.len();
-        self.saved_undo_operation_id = self
-            .undo_stack
-            .last()
-            .map(|operation| operation.operation_id);
+        self.mark_save_point(self.current_save_point());
     }
 }
 
+// Filesystem events collected from the notify watchers. The external change
+// tracker drains it once events stop arriving, so a burst of writes from one
+// save or checkout turns into a single round of notifications.
+#[derive(Default)]
+pub struct FileWatchBatch {
+    pub changed_paths: BTreeSet<PathBuf>,
+    pub changed_directories: BTreeSet<PathBuf>,
+    first_event_at: Option<Instant>,
+    last_event_at: Option<Instant>,
+}
+
+impl FileWatchBatch {
+    fn touch(&mut self, now: Instant) {
+        self.first_event_at.get_or_insert(now);
+        self.last_event_at = Some(now);
+    }
+
+    // Settled once no event arrived for `quiet`, or the oldest event has
+    // waited `max_delay` so a steady stream still gets reported.
+    fn is_settled(&self, now: Instant, quiet: Duration, max_delay: Duration) -> bool {
+        match (self.first_event_at, self.last_event_at) {
+            (Some(first), Some(last)) => {
+                now.duration_since(last) >= quiet || now.duration_since(first) >= max_delay
+            }
+            _ => false,
+        }
+    }
+}
+
+// Modification time and length of config.json, or None while it does not exist.
+pub type ConfigFileStamp = Option<(SystemTime, u64)>;
+
+struct CachedConfig {
+    stamp: ConfigFileStamp,
+    config: AppConfig,
+}
+
+// The normalized config, shared by every command. A hit costs one metadata
+// call; a stamp mismatch, such as after a manual edit, reloads the file.
+// Updates run under one lock and are written before they return, so
+// concurrent read-modify-write commands cannot drop each other's changes and
+// a failed write reaches the caller.
+pub struct ConfigManager {
+    cached: Mutex<Option<CachedConfig>>,
+    stamp: fn() -> ConfigFileStamp,
+    load: fn() -> Result<AppConfig, String>,
+    persist: fn(&AppConfig) -> Result<(), String>,
+}
+
+impl ConfigManager {
+    pub const fn new(
+        stamp: fn() -> ConfigFileStamp,
+        load: fn() -> Result<AppConfig, String>,
+        persist: fn(&AppConfig) -> Result<(), String>,
+    ) -> Self {
+        Self {
+            cached: Mutex::new(None),
+            stamp,
+            load,
+            persist,
+        }
+    }
+
+    // A failed load is not cached, so it is retried.
+    fn current(&self, cached: &mut Option<CachedConfig>) -> Result<AppConfig, String> {
+        let stamp = (self.stamp)();
+        if let Some(hit) = cached.as_ref().filter(|hit| hit.stamp == stamp) {
+            return Ok(hit.config.clone());
+        }
+
+        *cached = None;
+        let config = (self.load)()?;
+        *cached = Some(CachedConfig {
+            stamp,
+            config: config.clone(),
+        });
+        Ok(config)
+    }
+
+    pub fn get(&self) -> Result<AppConfig, String> {
+        let mut cached = self
+            .cached
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.current(&mut cached)
+    }
+
+    // Applies `edit` and writes the result. An error from `edit` or the write
+    // leaves the file as it was. A config that fails to parse is never
+    // replaced; the load error is returned instead.
+    pub fn update<T>(
+        &self,
+        edit: impl FnOnce(&mut AppConfig) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut cached = self
+            .cached
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut next = self.current(&mut cached)?;
+        let result = edit(&mut next)?;
+
+        // Dropped even when the write fails part way. The stamp alone could
+        // miss a rewrite within the file time resolution.
+        *cached = None;
+        (self.persist)(&next)?;
+        *cached = Some(CachedConfig {
+            stamp: (self.stamp)(),
+            config: next,
+        });
+        Ok(result)
+    }
+}
+
+struct DocumentWatchState {
+    // Parent directories are watched rather than the files themselves, since
+    // editors that save through a rename would otherwise drop the watch.
+    watched_directories: HashSet<PathBuf>,
+    watcher: RecommendedWatcher,
+}
+
 pub struct AppState {
     pub documents: DashMap<String, Document>,
-    startup_paths: Mutex<Vec<String>>,
+    // Keyed by view id; see `create_document_view`.
+    pub document_views: DashMap<String, DocumentView>,
+    // Newest first; `None` until first used, when a persisted ring is loaded.
+    // See `push_clipboard_entry`.
+    pub clipboard_history: Mutex<Option<VecDeque<ClipboardEntry>>>,
+    startup_paths: Mutex<Vec<StartupPath>>,
     frontend_ready: AtomicBool,
     folder_watch: Mutex<Option<FolderWatchState>>,
+    document_watch: Mutex<Option<DocumentWatchState>>,
+    // Set when document paths change so the watches are synced on the next tick.
+    document_watches_stale: AtomicBool,
+    // Shared filter rule folder, watched along with document directories.
+    shared_filter_rules_directory: Mutex<Option<PathBuf>>,
+    file_watch_batch: Arc<Mutex<FileWatchBatch>>,
 }
 
 struct FolderWatchState {
@@ -140,16 +1142,22 @@ struct FolderWatchState {
 }
 
 impl AppState {
-    pub fn new(startup_paths: Vec<String>) -> Self {
+    pub fn new(startup_paths: Vec<StartupPath>) -> Self {
         Self {
             documents: DashMap::new(),
+            document_views: DashMap::new(),
+            clipboard_history: Mutex::new(None),
             startup_paths: Mutex::new(startup_paths),
             frontend_ready: AtomicBool::new(false),
             folder_watch: Mutex::new(None),
+            document_watch: Mutex::new(None),
+            document_watches_stale: AtomicBool::new(true),
+            shared_filter_rules_directory: Mutex::new(None),
+            file_watch_batch: Arc::new(Mutex::new(FileWatchBatch::default())),
         }
     }
 
-    pub fn take_startup_paths(&self) -> Vec<String> {
+    pub fn take_startup_paths(&self) -> Vec<StartupPath> {
         let mut paths = self
             .startup_paths
             .lock()
@@ -157,11 +1165,20 @@ impl AppState {
         std::mem::take(&mut *paths)
     }
 
-    pub fn push_startup_paths(&self, new_paths: Vec<String>) {
-        self.startup_paths
+    // Queues paths until the frontend is ready and hands them back once it is.
+    // The check happens under the queue lock, so paths arriving while the
+    // ready listener drains the queue are not lost.
+    pub fn queue_startup_paths(&self, new_paths: Vec<StartupPath>) -> Option<Vec<StartupPath>> {
+        let mut paths = self
+            .startup_paths
             .lock()
-            .unwrap_or_else(|poisoned| poisoned.into_inner())
-            .extend(new_paths);
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if self.is_frontend_ready() {
+            return Some(new_paths);
+        }
+
+        paths.extend(new_paths);
+        None
     }
 
     pub fn mark_frontend_ready(&self) {
@@ -198,38 +1215,182 @@ impl AppState {
             .unwrap_or_else(|poisoned| poisoned.into_inner());
         *watch_state = None;
     }
+
+    pub fn record_changed_directories(&self, directories: Vec<PathBuf>) {
+        if directories.is_empty() {
+            return;
+        }
+
+        let mut batch = self
+            .file_watch_batch
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        batch.changed_directories.extend(directories);
+        batch.touch(Instant::now());
+    }
+
+    pub fn take_settled_file_watch_batch(
+        &self,
+        quiet: Duration,
+        max_delay: Duration,
+    ) -> Option<FileWatchBatch> {
+        let mut batch = self
+            .file_watch_batch
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !batch.is_settled(Instant::now(), quiet, max_delay) {
+            return None;
+        }
+
+        Some(std::mem::take(&mut *batch))
+    }
+
+    pub fn shared_filter_rules_directory(&self) -> Option<PathBuf> {
+        self.shared_filter_rules_directory
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    // Returns whether the directory changed.
+    pub fn set_shared_filter_rules_directory(&self, directory: Option<PathBuf>) -> bool {
+        let mut current = self
+            .shared_filter_rules_directory
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if *current == directory {
+            return false;
+        }
+
+        *current = directory;
+        self.mark_document_watches_stale();
+        true
+    }
+
+    pub fn mark_document_watches_stale(&self) {
+        self.document_watches_stale.store(true, Ordering::SeqCst);
+    }
+
+    pub fn take_document_watches_stale(&self) -> bool {
+        self.document_watches_stale.swap(false, Ordering::SeqCst)
+    }
+
+    pub fn is_directory_watched(&self, directory: &Path) -> bool {
+        self.document_watch
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_ref()
+            .is_some_and(|watch_state| watch_state.watched_directories.contains(directory))
+    }
+
+    // Brings the document watcher in line with the paths of open documents:
+    // directories no longer holding an open file are unwatched, new ones are
+    // added. The watcher itself is created on first use. The shared filter
+    // rule folder rides along once it exists, so a share mounted later is
+    // picked up on a following tick.
+    pub fn sync_document_watches(&self) -> Result<(), String> {
+        let mut wanted: HashSet<PathBuf> = self
+            .documents
+            .iter()
+            .filter_map(|doc| doc.path.as_ref().and_then(|path| path.parent()))
+            .filter(|directory| !directory.as_os_str().is_empty())
+            .map(|directory| directory.to_path_buf())
+            .collect();
+        if let Some(directory) = self.shared_filter_rules_directory() {
+            if directory.is_dir() {
+                wanted.insert(directory);
+            }
+        }
+
+        let mut watch_state = self
+            .document_watch
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if watch_state.is_none() {
+            if wanted.is_empty() {
+                return Ok(());
+            }
+
+            let batch = Arc::clone(&self.file_watch_batch);
+            let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+                let event = match result {
+                    Ok(event) => event,
+                    Err(error) => {
+                        eprintln!("failed to watch document event: {error}");
+                        return;
+                    }
+                };
+                if matches!(event.kind, EventKind::Access(_)) || event.paths.is_empty() {
+                    return;
+                }
+
+                let mut batch = batch
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                batch.changed_paths.extend(event.paths);
+                batch.touch(Instant::now());
+            })
+            .map_err(|error| error.to_string())?;
+
+            *watch_state = Some(DocumentWatchState {
+                watched_directories: HashSet::new(),
+                watcher,
+            });
+        }
+
+        let Some(watch_state) = watch_state.as_mut() else {
+            return Ok(());
+        };
+
+        let stale: Vec<PathBuf> = watch_state
+            .watched_directories
+            .difference(&wanted)
+            .cloned()
+            .collect();
+        for directory in stale {
+            // The directory may already be gone, which also ends the watch.
+            let _ = watch_state.watcher.unwatch(directory.as_path());
+            watch_state.watched_directories.remove(&directory);
+        }
+
+        let mut errors = Vec::new();
+        for directory in wanted {
+            if watch_state.watched_directories.contains(&directory) {
+                continue;
+            }
+            match watch_state
+                .watcher
+                .watch(directory.as_path(), RecursiveMode::NonRecursive)
+            {
+                Ok(()) => {
+                    watch_state.watched_directories.insert(directory);
+                }
+                Err(error) => errors.push(format!("{}: {}", directory.display(), error)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{default_line_ending, CursorSnapshot, Document, EditOperation};
-    use encoding_rs::UTF_8;
+    use super::{
+        CursorSnapshot, Document, DocumentTextSnapshot, DocumentViewState, EditOperation,
+        FileShareMode, FileWatchBatch, LineAnnotation, LineAnnotationStore, LineShift,
+    };
     use ropey::Rope;
-
-    fn make_document() -> Document {
-        Document {
-            rope: Rope::new(),
-            saved_rope: Rope::new(),
-            encoding: UTF_8,
-            saved_encoding: UTF_8.name().to_string(),
-            line_ending: default_line_ending(),
-            saved_line_ending: default_line_ending(),
-            path: None,
-            syntax_override: None,
-            document_version: 0,
-            saved_document_version: 0,
-            next_edit_operation_id: 1,
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
-            saved_undo_depth: 0,
-            saved_undo_operation_id: None,
-            saved_file_fingerprint: None,
-        }
-    }
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn undo_checkpoint_should_restore_clean_state_after_pop_to_saved_marker() {
-        let mut document = make_document();
+        let mut document = Document::for_test("", None);
         assert!(!document.has_unsaved_text_changes());
 
         let first_operation = EditOperation {
@@ -239,6 +1400,8 @@ mod tests {
             new_text: "a".to_string(),
             before_cursor: None,
             after_cursor: None,
+            group_id: None,
+            applied_at_ms: 0,
         };
         document.undo_stack.push(first_operation);
         assert!(document.has_unsaved_text_changes());
@@ -253,6 +1416,8 @@ mod tests {
             new_text: "b".to_string(),
             before_cursor: None,
             after_cursor: None,
+            group_id: None,
+            applied_at_ms: 0,
         };
         document.undo_stack.push(second_operation);
         assert!(document.has_unsaved_text_changes());
@@ -263,7 +1428,7 @@ mod tests {
 
     #[test]
     fn undo_checkpoint_should_remain_dirty_for_branch_with_same_depth() {
-        let mut document = make_document();
+        let mut document = Document::for_test("", None);
 
         let first_operation = EditOperation {
             operation_id: document.allocate_edit_operation_id(),
@@ -272,6 +1437,8 @@ mod tests {
             new_text: "a".to_string(),
             before_cursor: None,
             after_cursor: None,
+            group_id: None,
+            applied_at_ms: 0,
         };
         document.undo_stack.push(first_operation);
 
@@ -282,6 +1449,8 @@ mod tests {
             new_text: "b".to_string(),
             before_cursor: None,
             after_cursor: None,
+            group_id: None,
+            applied_at_ms: 0,
         };
         document.undo_stack.push(second_operation);
 
@@ -297,12 +1466,313 @@ mod tests {
             new_text: "c".to_string(),
             before_cursor: None,
             after_cursor: None,
+            group_id: None,
+            applied_at_ms: 0,
         };
         document.undo_stack.push(branch_operation);
 
         assert!(document.has_unsaved_text_changes());
     }
 
+    #[test]
+    fn engine_should_record_undo_and_clear_redo_on_apply() {
+        let mut document = Document::for_test("", None);
+        document.rope = Rope::from_str("hello");
+
+        let mut engine = document.engine();
+        engine
+            .replace(0, "hello".to_string(), "hi".to_string())
+            .expect("replace should apply");
+        assert!(engine.undo().expect("undo should apply").is_some());
+        engine
+            .replace(0, "hello".to_string(), "hey".to_string())
+            .expect("replace should apply");
+        let change = engine.finish();
+
+        assert!(change.changed);
+        assert_eq!(change.document_version, 3);
+        assert_eq!(document.rope.to_string(), "hey");
+        assert_eq!(document.undo_stack.len(), 1);
+        assert!(document.redo_stack.is_empty());
+    }
+
+    #[test]
+    fn text_snapshot_should_be_reused_until_an_edit_lands() {
+        let mut document = Document::for_test("", None);
+        document.rope = Rope::from_str("abc");
+        let build = |document: &Document| {
+            let version = document.document_version;
+            let text = document.rope.to_string();
+            document
+                .text_snapshot
+                .get_or_build(version, || DocumentTextSnapshot {
+                    document_version: version,
+                    text,
+                    line_starts: vec![0],
+                    char_checkpoints: vec![0],
+                })
+        };
+
+        let first = build(&document);
+        assert!(Arc::ptr_eq(&first, &build(&document)));
+
+        let mut engine = document.engine();
+        engine
+            .replace(0, "a".to_string(), "x".to_string())
+            .expect("replace should apply");
+        engine.finish();
+
+        let rebuilt = build(&document);
+        assert!(!Arc::ptr_eq(&first, &rebuilt));
+        assert_eq!(rebuilt.text, "xbc");
+        assert_eq!(rebuilt.document_version, 1);
+    }
+
+    #[test]
+    fn undo_to_save_point_should_walk_back_and_forward_to_saved_state() {
+        let mut document = Document::for_test("", None);
+        document.rope = Rope::from_str("");
+        let mut engine = document.engine();
+        engine
+            .replace(0, String::new(), "a".to_string())
+            .expect("insert a");
+        engine.finish();
+        document.mark_saved_undo_checkpoint();
+
+        let mut engine = document.engine();
+        engine
+            .replace(1, String::new(), "b".to_string())
+            .expect("insert b");
+        engine
+            .replace(2, String::new(), "c".to_string())
+            .expect("insert c");
+        engine.finish();
+        assert_eq!(document.save_point_distance(), Some(2));
+
+        let mut engine = document.engine();
+        assert_eq!(engine.undo_to_save_point(), Ok(2));
+        engine.finish();
+        assert_eq!(document.rope.to_string(), "a");
+        assert!(!document.has_unsaved_text_changes());
+
+        let mut engine = document.engine();
+        engine.undo().expect("undo");
+        engine.finish();
+        assert_eq!(document.save_point_distance(), Some(-1));
+        let mut engine = document.engine();
+        assert_eq!(engine.undo_to_save_point(), Ok(1));
+        engine.finish();
+        assert_eq!(document.rope.to_string(), "a");
+
+        let mut engine = document.engine();
+        engine.undo().expect("undo");
+        engine
+            .replace(0, String::new(), "x".to_string())
+            .expect("branch");
+        engine.finish();
+        assert_eq!(document.save_point_distance(), None);
+    }
+
+    #[test]
+    fn engine_metadata_changes_should_bump_version_without_recording() {
+        let mut document = Document::for_test("", None);
+        let line_ending = document.line_ending;
+
+        let mut engine = document.engine();
+        engine.set_line_ending(line_ending);
+        assert!(!engine.finish().changed);
+
+        let mut engine = document.engine();
+        engine.set_encoding(encoding_rs::UTF_16LE);
+        let change = engine.finish();
+
+        assert!(change.changed);
+        assert_eq!(change.document_version, 1);
+        assert!(document.undo_stack.is_empty());
+        assert!(!document.has_unsaved_text_changes());
+    }
+
+    #[test]
+    fn engine_transaction_should_roll_back_applied_operations_on_error() {
+        let mut document = Document::for_test("", None);
+        document.rope = Rope::from_str("abc");
+        document.redo_stack.push(EditOperation {
+            operation_id: 99,
+            start_char: 0,
+            old_text: String::new(),
+            new_text: "z".to_string(),
+            before_cursor: None,
+            after_cursor: None,
+            group_id: None,
+            applied_at_ms: 0,
+        });
+
+        let mut engine = document.engine();
+        let first = engine.new_operation(0, "a".to_string(), "A".to_string());
+        let stale = engine.new_operation(1, "x".to_string(), "X".to_string());
+        assert!(engine.batch(vec![first, stale]).is_err());
+        let change = engine.finish();
+
+        assert!(!change.changed);
+        assert_eq!(document.rope.to_string(), "abc");
+        assert!(document.undo_stack.is_empty());
+        assert_eq!(document.redo_stack.len(), 1);
+    }
+
+    #[test]
+    fn engine_redo_should_reapply_undone_operation() {
+        let mut document = Document::for_test("", None);
+
+        let mut engine = document.engine();
+        engine
+            .replace(0, String::new(), "a\nb".to_string())
+            .expect("insert should apply");
+        engine.undo().expect("undo should apply");
+        let redone = engine
+            .redo()
+            .expect("redo should apply")
+            .map(|operation| operation.new_text.clone());
+        assert_eq!(redone.as_deref(), Some("a\nb"));
+        assert!(engine.redo().expect("empty redo is not an error").is_none());
+        assert_eq!(engine.finish().line_count, 2);
+    }
+
+    #[test]
+    fn engine_should_reject_edits_to_derived_view() {
+        let mut document = Document::for_test("", None);
+        document.rope = Rope::from_str("view");
+        document.derived_view = Some(DerivedView {
+            source_id: "source".to_string(),
+            source_lines: vec![1],
+        });
+
+        let mut engine = document.engine();
+        assert!(engine
+            .replace(0, "view".to_string(), "edit".to_string())
+            .is_err());
+        assert!(!engine.finish().changed);
+        assert_eq!(document.rope.to_string(), "view");
+    }
+
+    #[test]
+    fn engine_undo_redo_should_treat_edit_group_as_one_step() {
+        let mut document = Document::for_test("", None);
+        document.rope = Rope::from_str("");
+
+        let mut engine = document.engine();
+        engine
+            .replace(0, String::new(), "a".to_string())
+            .expect("insert a");
+        engine.finish();
+
+        let group_id = document.begin_edit_group();
+        assert_eq!(document.begin_edit_group(), group_id);
+        let mut engine = document.engine();
+        engine
+            .replace(1, String::new(), "b".to_string())
+            .expect("insert b");
+        engine
+            .replace(2, String::new(), "c".to_string())
+            .expect("insert c");
+        engine.finish();
+        assert!(document.end_edit_group());
+        assert!(!document.end_edit_group());
+
+        let mut engine = document.engine();
+        let undone = engine
+            .undo()
+            .expect("undo")
+            .map(|operation| operation.new_text.clone());
+        assert_eq!(undone.as_deref(), Some("b"));
+        engine.finish();
+        assert_eq!(document.rope.to_string(), "a");
+
+        let mut engine = document.engine();
+        engine.redo().expect("redo");
+        engine.finish();
+        assert_eq!(document.rope.to_string(), "abc");
+        assert_eq!(document.undo_stack.len(), 3);
+    }
+
+    #[test]
+    fn edit_group_should_close_after_idle_timeout() {
+        let mut document = Document::for_test("", None);
+
+        let group_id = document.begin_edit_group();
+        if let Some(group) = document.open_edit_group.as_mut() {
+            group.touched_at_ms = 0;
+        }
+        let mut engine = document.engine();
+        let operation = engine.new_operation(0, String::new(), "a".to_string());
+        engine.finish();
+
+        assert_eq!(operation.group_id, None);
+        assert!(document.open_edit_group.is_none());
+        assert_ne!(document.begin_edit_group(), group_id);
+        if let Some(group) = document.open_edit_group.as_mut() {
+            group.touched_at_ms = 0;
+        }
+        assert!(!document.end_edit_group());
+    }
+
+    #[test]
+    fn typed_insertions_should_coalesce_into_one_undo_step() {
+        let mut document = Document::for_test("", None);
+
+        let mut engine = document.engine();
+        for (index, text) in ["a", "b", "c"].into_iter().enumerate() {
+            engine
+                .replace(index, String::new(), text.to_string())
+                .expect("type");
+        }
+        engine
+            .replace(3, String::new(), "\n".to_string())
+            .expect("line break");
+        engine.finish();
+
+        let group_ids: Vec<Option<u64>> = document
+            .undo_stack
+            .iter()
+            .map(|operation| operation.group_id)
+            .collect();
+        assert!(group_ids[0].is_some());
+        assert_eq!(group_ids[1], group_ids[0]);
+        assert_eq!(group_ids[2], group_ids[0]);
+        assert_eq!(group_ids[3], None);
+
+        let mut engine = document.engine();
+        engine.undo().expect("undo line break");
+        engine.undo().expect("undo typing");
+        engine.finish();
+        assert_eq!(document.rope.to_string(), "");
+    }
+
+    #[test]
+    fn typed_insertions_should_not_coalesce_when_stale_or_not_adjacent() {
+        let mut document = Document::for_test("", None);
+
+        let mut engine = document.engine();
+        engine
+            .replace(0, String::new(), "ab".to_string())
+            .expect("type");
+        engine
+            .replace(0, String::new(), "x".to_string())
+            .expect("type elsewhere");
+        engine.finish();
+        document.undo_stack[1].applied_at_ms = 0;
+
+        let mut engine = document.engine();
+        engine
+            .replace(1, String::new(), "y".to_string())
+            .expect("type after a pause");
+        engine.finish();
+
+        assert!(document
+            .undo_stack
+            .iter()
+            .all(|operation| operation.group_id.is_none()));
+    }
+
     #[test]
     fn inverse_operation_should_swap_cursor_snapshots() {
         let operation = EditOperation {
@@ -312,6 +1782,8 @@ mod tests {
             new_text: "b".to_string(),
             before_cursor: Some(CursorSnapshot { line: 2, column: 3 }),
             after_cursor: Some(CursorSnapshot { line: 4, column: 5 }),
+            group_id: None,
+            applied_at_ms: 0,
         };
 
         let inverse = operation.inverse();
@@ -329,4 +1801,238 @@ mod tests {
         assert_eq!(after.line, 2);
         assert_eq!(after.column, 3);
     }
+
+    #[test]
+    fn file_share_mode_labels_should_round_trip_case_insensitively() {
+        for mode in [
+            FileShareMode::ReadWrite,
+            FileShareMode::Read,
+            FileShareMode::Exclusive,
+        ] {
+            assert_eq!(FileShareMode::from_label(mode.label()), Some(mode));
+        }
+
+        assert_eq!(
+            FileShareMode::from_label("READWRITE"),
+            Some(FileShareMode::ReadWrite)
+        );
+        assert_eq!(FileShareMode::from_label("none"), None);
+    }
+
+    fn make_annotation(line: usize, kind: &str) -> LineAnnotation {
+        LineAnnotation {
+            line,
+            kind: kind.to_string(),
+            text: None,
+            color: None,
+            data: None,
+        }
+    }
+
+    #[test]
+    fn line_annotation_store_should_return_sorted_range_across_sources() {
+        let mut store = LineAnnotationStore::default();
+        store.set(
+            "git".to_string(),
+            vec![make_annotation(9, "added"), make_annotation(2, "modified")],
+        );
+        store.set(
+            "diagnostics".to_string(),
+            vec![make_annotation(0, "error"), make_annotation(5, "warning")],
+        );
+
+        let lines: Vec<(&str, usize)> = store
+            .range(2, 5)
+            .into_iter()
+            .map(|(source, annotation)| (source, annotation.line))
+            .collect();
+        assert_eq!(lines, vec![("git", 2), ("diagnostics", 5)]);
+
+        store.set("git".to_string(), Vec::new());
+        assert_eq!(store.range(1, 10).len(), 1);
+    }
+
+    #[test]
+    fn line_annotation_store_should_follow_inserted_and_removed_lines() {
+        let mut store = LineAnnotationStore::default();
+        store.set(
+            "bookmarks".to_string(),
+            vec![
+                make_annotation(1, "mark"),
+                make_annotation(3, "mark"),
+                make_annotation(4, "mark"),
+                make_annotation(6, "mark"),
+            ],
+        );
+
+        // Join lines 3..=5 into line 3: line 4 disappears, line 6 moves up.
+        store.adjust_for_edit(LineShift {
+            start_line: 3,
+            removed_lines: 2,
+            inserted_lines: 0,
+            starts_at_line_start: false,
+        });
+        let lines: Vec<usize> = store.range(1, 10).iter().map(|(_, a)| a.line).collect();
+        assert_eq!(lines, vec![1, 3, 4]);
+
+        // Inserting two lines at the start of line 3 pushes it down.
+        store.adjust_for_edit(LineShift {
+            start_line: 3,
+            removed_lines: 0,
+            inserted_lines: 2,
+            starts_at_line_start: true,
+        });
+        let lines: Vec<usize> = store.range(1, 10).iter().map(|(_, a)| a.line).collect();
+        assert_eq!(lines, vec![1, 5, 6]);
+    }
+
+    #[test]
+    fn line_annotation_store_should_drop_whole_lines_deleted_from_column_zero() {
+        let mut store = LineAnnotationStore::default();
+        store.set(
+            "bookmarks".to_string(),
+            vec![
+                make_annotation(2, "mark"),
+                make_annotation(3, "mark"),
+                make_annotation(4, "mark"),
+                make_annotation(7, "mark"),
+            ],
+        );
+
+        // Delete line 3: its mark goes, line 4 moves up into its place.
+        store.adjust_for_edit(LineShift {
+            start_line: 3,
+            removed_lines: 1,
+            inserted_lines: 0,
+            starts_at_line_start: true,
+        });
+        let lines: Vec<usize> = store.range(1, 10).iter().map(|(_, a)| a.line).collect();
+        assert_eq!(lines, vec![2, 3, 6]);
+
+        // Delete lines 2..=4: only the mark on line 6 survives, now on line 3.
+        store.adjust_for_edit(LineShift {
+            start_line: 2,
+            removed_lines: 3,
+            inserted_lines: 0,
+            starts_at_line_start: true,
+        });
+        let lines: Vec<usize> = store.range(1, 10).iter().map(|(_, a)| a.line).collect();
+        assert_eq!(lines, vec![3]);
+    }
+
+    #[test]
+    fn document_engine_should_shift_annotations_when_deleting_whole_lines() {
+        let mut document = Document::for_test("", None);
+        document.rope = Rope::from_str("a\nb\nc\nd\n");
+        document.line_annotations.set(
+            "bookmarks".to_string(),
+            vec![make_annotation(2, "mark"), make_annotation(3, "mark")],
+        );
+
+        let mut engine = document.engine();
+        engine
+            .replace(2, "b\n".to_string(), String::new())
+            .expect("delete line 2");
+        engine.finish();
+
+        let lines: Vec<usize> = document
+            .line_annotations
+            .range(1, 10)
+            .iter()
+            .map(|(_, a)| a.line)
+            .collect();
+        assert_eq!(lines, vec![2]);
+    }
+
+    #[test]
+    fn document_views_should_follow_edits_made_through_the_document() {
+        let mut document = Document::for_test("", None);
+        document.rope = Rope::from_str("a\nb\nc\nd\ne\n");
+        document.views.set(
+            "view".to_string(),
+            DocumentViewState {
+                cursor_line: 4,
+                cursor_column: 2,
+                scroll_top_line: 2,
+                folded_lines: vec![2, 5],
+            },
+        );
+
+        let mut engine = document.engine();
+        engine
+            .replace(0, String::new(), "x\ny\n".to_string())
+            .expect("insert lines");
+        // Join lines 5..=7 into line 5: the cursor on line 6 moves to line 5
+        // and the fold on line 7 goes away.
+        engine
+            .replace(9, "\nd\ne".to_string(), String::new())
+            .expect("remove lines");
+        engine.finish();
+
+        assert_eq!(
+            document.views.get("view"),
+            Some(&DocumentViewState {
+                cursor_line: 5,
+                cursor_column: 2,
+                scroll_top_line: 4,
+                folded_lines: vec![4],
+            })
+        );
+    }
+
+    #[test]
+    fn document_views_should_follow_whole_lines_deleted_above_them() {
+        let mut document = Document::for_test("", None);
+        document.rope = Rope::from_str("a\nb\nc\nd\ne\nf\n");
+        document.views.set(
+            "view".to_string(),
+            DocumentViewState {
+                cursor_line: 4,
+                cursor_column: 1,
+                scroll_top_line: 2,
+                folded_lines: vec![3, 5],
+            },
+        );
+
+        // Delete lines 2..=3 from column 0: the fold on line 3 goes, the one
+        // on line 5 moves up to line 3 and the scroll anchor on line 2 lands on
+        // the line that took its place.
+        let mut engine = document.engine();
+        engine
+            .replace(2, "b\nc\n".to_string(), String::new())
+            .expect("delete lines");
+        engine.finish();
+
+        assert_eq!(
+            document.views.get("view"),
+            Some(&DocumentViewState {
+                cursor_line: 2,
+                cursor_column: 1,
+                scroll_top_line: 2,
+                folded_lines: vec![3],
+            })
+        );
+    }
+
+    #[test]
+    fn file_watch_batch_should_settle_after_quiet_period_or_max_delay() {
+        let quiet = Duration::from_millis(250);
+        let max_delay = Duration::from_secs(2);
+        let start = Instant::now();
+        let mut batch = FileWatchBatch::default();
+        assert!(!batch.is_settled(start, quiet, max_delay));
+
+        batch.touch(start);
+        assert!(!batch.is_settled(start + Duration::from_millis(100), quiet, max_delay));
+        assert!(batch.is_settled(start + quiet, quiet, max_delay));
+
+        // Events keep arriving, so only the max delay releases the batch.
+        let mut now = start;
+        while now < start + max_delay {
+            now += Duration::from_millis(100);
+            batch.touch(now);
+            let settled = batch.is_settled(now, quiet, max_delay);
+            assert_eq!(settled, now >= start + max_delay);
+        }
+    }
 }
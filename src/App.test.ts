@@ -229,6 +229,7 @@ function createInvokeHandler(overrides: Record<string, InvokeOverride> = {}) {
         highlightCurrentLine: true,
         singleInstanceMode: true,
         rememberWindowState: true,
+        autoReloadUnmodifiedFiles: false,
         recentFiles: [],
         recentFolders: [],
         recentSearchKeywords: [],
@@ -1834,6 +1835,47 @@ describe('App component', () => {
     }
   });
 
+  it('reloads unmodified file without asking when auto-reload is enabled', async () => {
+    const fileTab = createFileTab({
+      id: 'tab-external-change-auto-reload',
+      lineCount: 3,
+      isDirty: false,
+    });
+    useStore.setState({
+      tabs: [fileTab],
+      activeTabId: fileTab.id,
+    });
+
+    const defaultInvoke = createInvokeHandler();
+    vi.mocked(invoke).mockImplementation(
+      createInvokeHandler({
+        load_config: async () => ({
+          ...((await defaultInvoke('load_config')) as Record<string, unknown>),
+          autoReloadUnmodifiedFiles: true,
+        }),
+        has_external_file_change: async () => true,
+        reload_file_from_disk: async () =>
+          createFileTab({
+            id: fileTab.id,
+            path: fileTab.path,
+            lineCount: 5,
+            isDirty: false,
+          }),
+      })
+    );
+
+    render(React.createElement(App));
+
+    await waitFor(() => {
+      expect(vi.mocked(invoke)).toHaveBeenCalledWith('reload_file_from_disk', {
+        id: fileTab.id,
+      });
+      const currentTab = useStore.getState().tabs.find((tab) => tab.id === fileTab.id);
+      expect(currentTab?.lineCount).toBe(5);
+    });
+    expect(vi.mocked(ask)).not.toHaveBeenCalled();
+  });
+
   it('acknowledges deleted file after confirmed reload failure and prevents prompt loop', async () => {
     const fileTab = createFileTab({
       id: 'tab-external-change-reload-missing-file',
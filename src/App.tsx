@@ -6,13 +6,20 @@ import { lazy, Suspense, useCallback, useEffect, useMemo, useRef, useState } fro
 import { useShallow } from 'zustand/react/shallow';
 import { RefreshCw } from 'lucide-react';
 import { useFolderWatch } from '@/hooks/useFolderWatch';
+import { useLiveStreams } from '@/hooks/useLiveStreams';
+import { useSessionPersistence } from '@/hooks/useSessionPersistence';
 import { useMouseGestures } from '@/hooks/useMouseGestures';
 import { useSingleInstance } from '@/hooks/useSingleInstance';
 import { TitleBar } from '@/components/TitleBar';
 import { Toolbar } from '@/components/Toolbar';
 import { MarkdownToolbar } from '@/components/MarkdownToolbar';
 import { t } from '@/i18n';
-import { openFilePaths } from '@/lib/openFile';
+import {
+  IncomingOpenTarget,
+  navigateOpenedPathToPosition,
+  normalizeIncomingOpenTarget,
+  openFilePaths,
+} from '@/lib/openFile';
 import {
   type MouseGestureAction,
   type MouseGestureBinding,
@@ -37,8 +44,18 @@ import {
   defaultTranslationSettings,
 } from '@/store/useStore';
 import { detectOutlineType, loadOutline } from '@/lib/outline';
+import { setSyntaxExtensionOverrides } from '@/lib/syntax';
 import { addRecentFolderPath, sanitizeRecentPathList } from '@/lib/recentPaths';
+import { recordReadingPositions } from '@/lib/readingPositions';
+import { loadBackendErrorMessages } from '@/lib/backendErrors';
+import { restoreSavedSession, saveSession } from '@/lib/session';
 import { dispatchDocumentUpdated } from '@/lib/documentEvents';
+import {
+  buildFilterRuleAlertBody,
+  isFilterRuleAlertPayload,
+  playFilterRuleAlertSound,
+  showFilterRuleAlertNotification,
+} from '@/lib/filterRuleAlerts';
 
 let hasInitializedStartupTab = false;
 
@@ -135,6 +152,7 @@ interface AppConfig {
   fontSize: number;
   tabWidth: number;
   tabIndentMode?: TabIndentMode;
+  foldMinBlockLines?: number;
   newFileLineEnding: LineEnding;
   wordWrap: boolean;
   minimap?: boolean;
@@ -144,6 +162,7 @@ interface AppConfig {
   highlightCurrentLine: boolean;
   singleInstanceMode: boolean;
   rememberWindowState: boolean;
+  autoReloadUnmodifiedFiles?: boolean;
   recentFiles?: string[];
   recentFolders?: string[];
   recentSearchKeywords?: string[];
@@ -153,6 +172,7 @@ interface AppConfig {
   mouseGesturesEnabled?: boolean;
   mouseGestures?: MouseGestureBinding[];
   translation?: unknown;
+  syntaxExtensionOverrides?: Record<string, string>;
 }
 
 interface WindowsFileAssociationStatus {
@@ -168,6 +188,10 @@ const Editor = lazy(async () => ({
   default: (await import('@/components/Editor')).Editor,
 }));
 
+const StreamingDocumentView = lazy(async () => ({
+  default: (await import('@/components/StreamingDocumentView')).StreamingDocumentView,
+}));
+
 const DiffEditor = lazy(async () => ({
   default: (await import('@/components/DiffEditor')).DiffEditor,
 }));
@@ -264,6 +288,12 @@ function isAppInForeground() {
   return document.hasFocus();
 }
 
+// Unmodified tabs reload without a prompt when the setting, or the tab's own
+// override, asks for it.
+function shouldAutoReloadTab(tab: FileTab, autoReloadUnmodifiedFiles: boolean) {
+  return !tab.isDirty && (tab.autoReloadOverride ?? autoReloadUnmodifiedFiles);
+}
+
 export const appTestUtils = {
   detectWindowsPlatform,
   areStringArraysEqual,
@@ -305,6 +335,7 @@ function App() {
       fontSize: state.settings.fontSize,
       tabWidth: state.settings.tabWidth,
       tabIndentMode: state.settings.tabIndentMode,
+      foldMinBlockLines: state.settings.foldMinBlockLines,
       newFileLineEnding: state.settings.newFileLineEnding,
       wordWrap: state.settings.wordWrap,
       minimap: state.settings.minimap,
@@ -314,6 +345,7 @@ function App() {
       highlightCurrentLine: state.settings.highlightCurrentLine,
       singleInstanceMode: state.settings.singleInstanceMode,
       rememberWindowState: state.settings.rememberWindowState,
+      autoReloadUnmodifiedFiles: state.settings.autoReloadUnmodifiedFiles,
       recentFiles: state.settings.recentFiles,
       recentFolders: state.settings.recentFolders,
       recentSearchKeywords: state.settings.recentSearchKeywords,
@@ -396,6 +428,7 @@ function App() {
     }
 
     const tabIds = closableTabs.map((tab) => tab.id);
+    await recordReadingPositions(tabIds);
 
     for (const tabId of tabIds) {
       state.closeTab(tabId);
@@ -531,8 +564,14 @@ function App() {
     }
   }, [closeTabsWithConfirm]);
 
-  const openIncomingPaths = useCallback(async (paths: string[]) => {
-    for (const incomingPath of paths) {
+  const openIncomingPaths = useCallback(async (targets: IncomingOpenTarget[]) => {
+    for (const target of targets) {
+      const normalizedTarget = normalizeIncomingOpenTarget(target);
+      if (!normalizedTarget) {
+        continue;
+      }
+
+      const { path: incomingPath, gotoPosition } = normalizedTarget;
       try {
         const entries = await invoke<any[] | null>('read_dir_if_directory', { path: incomingPath });
         if (entries) {
@@ -546,6 +585,9 @@ function App() {
 
       try {
         await openFilePaths([incomingPath]);
+        if (gotoPosition) {
+          navigateOpenedPathToPosition(incomingPath, gotoPosition);
+        }
       } catch (error) {
         console.error(`Failed to open incoming path: ${incomingPath}`, error);
       }
@@ -570,7 +612,10 @@ function App() {
       return;
     }
 
-    if (suppressedExternalChangePromptTabIdsRef.current.has(snapshotTab.id)) {
+    if (
+      suppressedExternalChangePromptTabIdsRef.current.has(snapshotTab.id)
+      && !shouldAutoReloadTab(snapshotTab, useStore.getState().settings.autoReloadUnmodifiedFiles)
+    ) {
       return;
     }
 
@@ -607,21 +652,26 @@ function App() {
         }
       };
 
-      const fileName = latestTab.name || latestTab.path;
-      const promptText = t(latestState.settings.language, 'app.externalFileChanged.prompt')
-        .replace('{fileName}', fileName);
-      const unsavedWarningText = t(
-        latestState.settings.language,
-        'app.externalFileChanged.unsavedWarning'
-      );
-      const messageText = latestTab.isDirty
-        ? `${promptText}\n\n${unsavedWarningText}`
-        : promptText;
-
-      const shouldReload = await ask(messageText, {
-        title: 'Rutar',
-        kind: 'warning',
-      });
+      const confirmReload = async () => {
+        const fileName = latestTab.name || latestTab.path;
+        const promptText = t(latestState.settings.language, 'app.externalFileChanged.prompt')
+          .replace('{fileName}', fileName);
+        const unsavedWarningText = t(
+          latestState.settings.language,
+          'app.externalFileChanged.unsavedWarning'
+        );
+        const messageText = latestTab.isDirty
+          ? `${promptText}\n\n${unsavedWarningText}`
+          : promptText;
+
+        return ask(messageText, {
+          title: 'Rutar',
+          kind: 'warning',
+        });
+      };
+
+      const shouldReload = shouldAutoReloadTab(latestTab, latestState.settings.autoReloadUnmodifiedFiles)
+        || await confirmReload();
 
       if (shouldReload) {
         try {
@@ -634,6 +684,7 @@ function App() {
             lineCount: fileInfo.lineCount,
             largeFileMode: fileInfo.largeFileMode,
             syntaxOverride: fileInfo.syntaxOverride ?? null,
+            suggestedSyntax: fileInfo.suggestedSyntax ?? null,
             isDirty: false,
           });
 
@@ -814,7 +865,16 @@ function App() {
 
     const openStartupPaths = async () => {
       try {
-        const startupPaths = await invoke<string[]>('get_startup_paths');
+        await restoreSavedSession();
+      } catch (error) {
+        console.error('Failed to restore session:', error);
+      }
+      if (cancelled) {
+        return;
+      }
+
+      try {
+        const startupPaths = await invoke<IncomingOpenTarget[]>('get_startup_paths');
         if (cancelled || startupPaths.length === 0) {
           return;
         }
@@ -884,6 +944,8 @@ function App() {
     };
   }, []);
   useSingleInstance(openIncomingPaths);
+  useLiveStreams();
+  useSessionPersistence();
 
   useEffect(() => {
     if (hasInitializedStartupTab) {
@@ -961,8 +1023,11 @@ function App() {
             return;
           }
 
-          const currentActiveTabId = useStore.getState().activeTabId;
-          if (changedTabId !== currentActiveTabId) {
+          const latestState = useStore.getState();
+          const changedTab = latestState.tabs.find((tab) => tab.id === changedTabId);
+          const autoReload = !!changedTab
+            && shouldAutoReloadTab(changedTab, latestState.settings.autoReloadUnmodifiedFiles);
+          if (changedTabId !== latestState.activeTabId && !autoReload) {
             return;
           }
 
@@ -990,6 +1055,106 @@ function App() {
     };
   }, [checkTabForExternalChange]);
 
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+
+    const setupFilterRuleAlertListener = async () => {
+      try {
+        const unsubscribe = await listen<unknown>('rutar://filter-rule-alert', (event) => {
+          const payload = event.payload;
+          if (!isFilterRuleAlertPayload(payload)) {
+            return;
+          }
+
+          if (payload.sound) {
+            playFilterRuleAlertSound();
+          }
+
+          if (!payload.notification) {
+            return;
+          }
+
+          const latestState = useStore.getState();
+          const tab = latestState.tabs.find((item) => item.id === payload.documentId);
+          const fileName = tab?.name || tab?.path || payload.documentId;
+          const title = t(latestState.settings.language, 'app.filterRuleAlert.title')
+            .replace('{keyword}', payload.keyword)
+            .replace('{fileName}', fileName);
+          const body = buildFilterRuleAlertBody(
+            payload,
+            t(latestState.settings.language, 'app.filterRuleAlert.moreHits')
+          );
+          void showFilterRuleAlertNotification(title, body).catch((error) => {
+            console.error('Failed to show filter rule alert notification:', error);
+          });
+        });
+
+        if (disposed) {
+          unsubscribe();
+          return;
+        }
+
+        unlisten = unsubscribe;
+      } catch (error) {
+        console.error('Failed to listen filter rule alert event:', error);
+      }
+    };
+
+    void setupFilterRuleAlertListener();
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+
+    // Text pushed through the remote control `/paste` endpoint arrives as an
+    // untitled document the backend already created.
+    const setupRemoteOpenDocumentListener = async () => {
+      try {
+        const unsubscribe = await listen<FileTab>('rutar://remote-open-document', (event) => {
+          const fileInfo = event.payload;
+          if (!fileInfo?.id) {
+            return;
+          }
+
+          const latestState = useStore.getState();
+          if (latestState.tabs.some((tab) => tab.id === fileInfo.id)) {
+            latestState.setActiveTab(fileInfo.id);
+            return;
+          }
+
+          latestState.addTab(fileInfo);
+        });
+
+        if (disposed) {
+          unsubscribe();
+          return;
+        }
+
+        unlisten = unsubscribe;
+      } catch (error) {
+        console.error('Failed to listen remote open document event:', error);
+      }
+    };
+
+    void setupRemoteOpenDocumentListener();
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
   useEffect(() => {
     if (!activeTabId) {
       return;
@@ -1033,6 +1198,12 @@ function App() {
               }
             }
           }
+
+          try {
+            await saveSession();
+          } catch (error) {
+            console.error('Failed to save session:', error);
+          }
         });
 
         if (disposed) {
@@ -1067,6 +1238,7 @@ function App() {
           return;
         }
 
+        setSyntaxExtensionOverrides(config.syntaxExtensionOverrides);
         updateSettings({
           language: config.language === 'en-US' ? 'en-US' : 'zh-CN',
           theme: config.theme === 'dark' ? 'dark' : 'light',
@@ -1074,6 +1246,9 @@ function App() {
           fontSize: Number.isFinite(config.fontSize) ? config.fontSize : 14,
           tabWidth: Number.isFinite(config.tabWidth) ? Math.min(8, Math.max(1, config.tabWidth)) : 4,
           tabIndentMode: normalizeTabIndentMode(config.tabIndentMode),
+          foldMinBlockLines: Number.isFinite(config.foldMinBlockLines)
+            ? Math.min(100, Math.max(2, config.foldMinBlockLines as number))
+            : 2,
           newFileLineEnding: normalizeLineEnding(config.newFileLineEnding),
           wordWrap: !!config.wordWrap,
           minimap: config.minimap !== false,
@@ -1083,6 +1258,7 @@ function App() {
           highlightCurrentLine: config.highlightCurrentLine !== false,
           singleInstanceMode: config.singleInstanceMode !== false,
           rememberWindowState: config.rememberWindowState !== false,
+          autoReloadUnmodifiedFiles: config.autoReloadUnmodifiedFiles === true,
           recentFiles: sanitizeRecentPathList(config.recentFiles),
           recentFolders: sanitizeRecentPathList(config.recentFolders),
           recentSearchKeywords: sanitizeRecentTextHistory(config.recentSearchKeywords),
@@ -1116,6 +1292,10 @@ function App() {
     root.classList.toggle('dark', settings.theme === 'dark');
   }, [settings.theme]);
 
+  useEffect(() => {
+    void loadBackendErrorMessages(settings.language);
+  }, [settings.language]);
+
   useEffect(() => {
     if (!configReady) {
       return;
@@ -1130,6 +1310,7 @@ function App() {
           fontSize: settings.fontSize,
           tabWidth: settings.tabWidth,
           tabIndentMode: settings.tabIndentMode,
+          foldMinBlockLines: settings.foldMinBlockLines,
           newFileLineEnding: settings.newFileLineEnding,
           wordWrap: settings.wordWrap,
           minimap: settings.minimap,
@@ -1139,8 +1320,7 @@ function App() {
           highlightCurrentLine: settings.highlightCurrentLine,
           singleInstanceMode: settings.singleInstanceMode,
           rememberWindowState: settings.rememberWindowState,
-          recentFiles: settings.recentFiles,
-          recentFolders: settings.recentFolders,
+          autoReloadUnmodifiedFiles: settings.autoReloadUnmodifiedFiles,
           recentSearchKeywords: settings.recentSearchKeywords,
           recentReplaceValues: settings.recentReplaceValues,
           pinnedTabPaths: settings.pinnedTabPaths,
@@ -1163,6 +1343,7 @@ function App() {
     settings.fontSize,
     settings.tabWidth,
     settings.tabIndentMode,
+    settings.foldMinBlockLines,
     settings.newFileLineEnding,
     settings.language,
     settings.theme,
@@ -1174,8 +1355,7 @@ function App() {
     settings.highlightCurrentLine,
     settings.singleInstanceMode,
     settings.rememberWindowState,
-    settings.recentFiles,
-    settings.recentFolders,
+    settings.autoReloadUnmodifiedFiles,
     settings.recentSearchKeywords,
     settings.recentReplaceValues,
     settings.pinnedTabPaths,
@@ -1393,6 +1573,8 @@ function App() {
                 <Suspense fallback={editorFallback}>
                   {isDiffTab(activeTab) ? (
                     <DiffEditor key={activeTab.id} tab={activeTab} />
+                  ) : activeTab.streaming ? (
+                    <StreamingDocumentView key={activeTab.id} tab={activeTab} />
                   ) : (
                     <Editor tab={activeTab} />
                   )}
@@ -178,6 +178,9 @@ vi.mock('monaco-editor', () => {
       };
     }),
     onDidLayoutChange: vi.fn(() => ({ dispose: vi.fn() })),
+    onDidScrollChange: vi.fn(() => ({ dispose: vi.fn() })),
+    onDidCompositionStart: vi.fn(() => ({ dispose: vi.fn() })),
+    onDidCompositionEnd: vi.fn(() => ({ dispose: vi.fn() })),
     setModel: vi.fn(),
     getModel: vi.fn(() => model),
     getLayoutInfo: vi.fn(() => ({ height: 320, viewportColumn: 100 })),
@@ -21,6 +21,8 @@ import {
   type MarkdownToolbarAction,
 } from '@/lib/markdownToolbar';
 import { resolveRutarMonacoTheme } from '@/lib/monaco/theme';
+import { DIFF_MONACO_LANGUAGE_ID } from '@/lib/monaco/diffLanguage';
+import { registerBackendFoldingRangeProvider } from '@/lib/monaco/foldingRanges';
 import { resolveMonacoLanguage } from '@/lib/monaco/language';
 import { resolveMonacoMinimapOptions } from '@/lib/monaco/minimap';
 import {
@@ -29,6 +31,8 @@ import {
   resolveMonacoWordWrapColumn,
   updateMonacoWordWrapColumn,
 } from '@/lib/monaco/wrapping';
+import { navigateToAdjacentPatchHunk, openPatchHunkSource } from '@/lib/patchHunks';
+import { rememberTopVisibleLine } from '@/lib/readingPositions';
 import { type FileTab, useStore } from '@/store/useStore';
 import { EditorBase64DecodeToast } from './EditorBase64DecodeToast';
 import { useEditorBookmarkDecorations } from './useEditorBookmarkDecorations';
@@ -256,6 +260,7 @@ export function Editor({
       highlightCurrentLine: state.settings.highlightCurrentLine,
       tabIndentMode: state.settings.tabIndentMode,
       tabWidth: state.settings.tabWidth,
+      foldMinBlockLines: state.settings.foldMinBlockLines,
       theme: state.settings.theme,
     })),
   );
@@ -287,6 +292,8 @@ export function Editor({
     tabIndentMode: settings.tabIndentMode,
     tabWidth: settings.tabWidth,
   });
+  const foldMinBlockLinesRef = useRef(settings.foldMinBlockLines);
+  const refreshFoldingRangesRef = useRef<(() => void) | null>(null);
   const cursorSnapshotRef = useRef<{ line: number; column: number }>({ line: 1, column: 1 });
   const engineStateRef = useRef<MonacoEngineState>({
     modelId: tab.id,
@@ -351,6 +358,9 @@ export function Editor({
     bookmarkMenuLabel,
     addBookmarkLabel,
     removeBookmarkLabel,
+    previousPatchHunkLabel,
+    nextPatchHunkLabel,
+    openPatchHunkSourceLabel,
     editSubmenuPositionClassName,
     sortSubmenuPositionClassName,
     convertSubmenuPositionClassName,
@@ -445,6 +455,23 @@ export function Editor({
     },
     [updateTab]
   );
+  // IME composition produces several model changes for one typed word; the
+  // backend groups them into a single undo step. Queued behind the edit sync
+  // so the boundary lands between the right edits.
+  const queueEditGroupBoundary = useCallback(
+    (targetTabId: string, command: 'begin_edit_group' | 'end_edit_group') => {
+      syncChainRef.current = syncChainRef.current
+        .catch(() => undefined)
+        .then(async () => {
+          try {
+            await invoke(command, { id: targetTabId });
+          } catch (error) {
+            console.error(`Failed to ${command.replace(/_/g, ' ')}:`, error);
+          }
+        });
+    },
+    []
+  );
   const flushPendingSync = useCallback(async () => {
     await syncChainRef.current.catch(() => undefined);
   }, []);
@@ -820,6 +847,26 @@ export function Editor({
     removeBookmark(tab.id, line);
     setEditorContextMenu(null);
   }, [removeBookmark, resolveContextLineNumber, tab.id]);
+  const handleNavigatePatchHunkFromContext = useCallback(
+    (forward: boolean) => {
+      const line = resolveContextLineNumber();
+      setEditorContextMenu(null);
+      void navigateToAdjacentPatchHunk(tab.id, line, forward).catch((error) => {
+        console.error('Failed to navigate patch hunks:', error);
+      });
+    },
+    [resolveContextLineNumber, tab.id]
+  );
+  const handleOpenPatchHunkSourceFromContext = useCallback(() => {
+    const line = resolveContextLineNumber();
+    setEditorContextMenu(null);
+    void openPatchHunkSource(tab.id, line).catch(async (error) => {
+      await message(error instanceof Error ? error.message : String(error), {
+        title: openPatchHunkSourceLabel,
+        kind: 'warning',
+      });
+    });
+  }, [openPatchHunkSourceLabel, resolveContextLineNumber, tab.id]);
   const handleSelectCurrentLineFromContext = useCallback(() => {
     selectLineByNumber(resolveContextLineNumber());
     setEditorContextMenu(null);
@@ -1031,6 +1078,19 @@ export function Editor({
       queueSyncEdits(currentTab, edits, beforeCursor, afterCursor);
     });
 
+    const compositionStartDisposable = editor.onDidCompositionStart(() => {
+      const currentTabId = activeTabIdRef.current;
+      if (currentTabId) {
+        queueEditGroupBoundary(currentTabId, 'begin_edit_group');
+      }
+    });
+    const compositionEndDisposable = editor.onDidCompositionEnd(() => {
+      const currentTabId = activeTabIdRef.current;
+      if (currentTabId) {
+        queueEditGroupBoundary(currentTabId, 'end_edit_group');
+      }
+    });
+
     const cursorDisposable = editor.onDidChangeCursorPosition((event: monaco.editor.ICursorPositionChangedEvent) => {
       const currentTabId = activeTabIdRef.current;
       if (!currentTabId) {
@@ -1076,9 +1136,29 @@ export function Editor({
       tryOpenUrlAtPosition(editor, event);
     });
 
+    const scrollDisposable = editor.onDidScrollChange(() => {
+      const activeTabId = activeTabIdRef.current;
+      const visibleRange = editor.getVisibleRanges()[0];
+      if (activeTabId && visibleRange) {
+        rememberTopVisibleLine(activeTabId, visibleRange.startLineNumber);
+      }
+    });
+
     const contextMenuDisposable = editor.onContextMenu(handleMonacoContextMenu);
+    const foldingRangeProvider = registerBackendFoldingRangeProvider(monaco, {
+      // Registered for every language, so only answer for this editor's model.
+      resolveDocumentId: (model) => (editor.getModel() === model ? activeTabIdRef.current : null),
+      waitForSync: flushPendingSync,
+      getMinBlockLines: () => foldMinBlockLinesRef.current,
+    });
+    refreshFoldingRangesRef.current = foldingRangeProvider?.refresh ?? null;
     return () => {
       contentDisposable.dispose();
+      foldingRangeProvider?.dispose();
+      refreshFoldingRangesRef.current = null;
+      compositionStartDisposable.dispose();
+      compositionEndDisposable.dispose();
+      scrollDisposable.dispose();
       cursorDisposable.dispose();
       mouseDownDisposable.dispose();
       contextMenuDisposable.dispose();
@@ -1099,7 +1179,9 @@ export function Editor({
   }, [
     applyMarkdownToolbarEdit,
     clearQuotePairDecorations,
+    flushPendingSync,
     handleMonacoContextMenu,
+    queueEditGroupBoundary,
     queueSyncEdits,
     setCursorPosition,
     updateQuotePairDecorations,
@@ -1109,6 +1191,11 @@ export function Editor({
     wordWrapRef.current = wordWrap;
   }, [wordWrap]);
 
+  useEffect(() => {
+    foldMinBlockLinesRef.current = settings.foldMinBlockLines;
+    refreshFoldingRangesRef.current?.();
+  }, [settings.foldMinBlockLines]);
+
   useEditorBookmarkDecorations(editorRef, bookmarks, tab.id);
 
   useEffect(() => {
@@ -1591,7 +1678,11 @@ export function Editor({
         bookmarkMenuLabel={bookmarkMenuLabel}
         addBookmarkLabel={addBookmarkLabel}
         removeBookmarkLabel={removeBookmarkLabel}
+        previousPatchHunkLabel={previousPatchHunkLabel}
+        nextPatchHunkLabel={nextPatchHunkLabel}
+        openPatchHunkSourceLabel={openPatchHunkSourceLabel}
         hasContextBookmark={hasContextBookmark}
+        showPatchHunkActions={monacoLanguage === DIFF_MONACO_LANGUAGE_ID}
         onSelectCurrentLine={handleSelectCurrentLineFromContext}
         onAddCurrentLineBookmark={handleAddCurrentLineBookmarkFromContext}
         onEditorAction={handleEditorContextMenuActionVoid}
@@ -1601,6 +1692,8 @@ export function Editor({
         onConvert={handleConvertSelectionFromContext}
         onAddBookmark={handleAddBookmarkFromContext}
         onRemoveBookmark={handleRemoveBookmarkFromContext}
+        onNavigatePatchHunk={handleNavigatePatchHunkFromContext}
+        onOpenPatchHunkSource={handleOpenPatchHunkSourceFromContext}
       />
       <EditorBase64DecodeToast
         visible={showBase64DecodeErrorToast}
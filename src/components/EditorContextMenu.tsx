@@ -61,7 +61,11 @@ interface EditorContextMenuProps {
   bookmarkMenuLabel: string;
   addBookmarkLabel: string;
   removeBookmarkLabel: string;
+  previousPatchHunkLabel: string;
+  nextPatchHunkLabel: string;
+  openPatchHunkSourceLabel: string;
   hasContextBookmark: boolean;
+  showPatchHunkActions: boolean;
   onSelectCurrentLine: () => void;
   onAddCurrentLineBookmark: () => void;
   onEditorAction: (action: EditorContextMenuAction) => void;
@@ -71,6 +75,8 @@ interface EditorContextMenuProps {
   onConvert: (action: EditorConvertAction) => Promise<void>;
   onAddBookmark: () => void;
   onRemoveBookmark: () => void;
+  onNavigatePatchHunk: (forward: boolean) => void;
+  onOpenPatchHunkSource: () => void;
 }
 
 const menuButtonClassName =
@@ -110,7 +116,11 @@ function EditorContextMenuImpl({
   bookmarkMenuLabel,
   addBookmarkLabel,
   removeBookmarkLabel,
+  previousPatchHunkLabel,
+  nextPatchHunkLabel,
+  openPatchHunkSourceLabel,
   hasContextBookmark,
+  showPatchHunkActions,
   onSelectCurrentLine,
   onAddCurrentLineBookmark,
   onEditorAction,
@@ -120,6 +130,8 @@ function EditorContextMenuImpl({
   onConvert,
   onAddBookmark,
   onRemoveBookmark,
+  onNavigatePatchHunk,
+  onOpenPatchHunkSource,
 }: EditorContextMenuProps) {
   if (!editorContextMenu) {
     return null;
@@ -351,6 +363,20 @@ function EditorContextMenuImpl({
               </button>
             </div>
           </div>
+          {showPatchHunkActions && (
+            <>
+              <div className="my-1 h-px bg-border" />
+              <button type="button" className={menuButtonClassName} onClick={() => onNavigatePatchHunk(false)}>
+                {previousPatchHunkLabel}
+              </button>
+              <button type="button" className={menuButtonClassName} onClick={() => onNavigatePatchHunk(true)}>
+                {nextPatchHunkLabel}
+              </button>
+              <button type="button" className={menuButtonClassName} onClick={onOpenPatchHunkSource}>
+                {openPatchHunkSourceLabel}
+              </button>
+            </>
+          )}
         </>
       )}
     </div>
@@ -13,12 +13,14 @@ import type { FilterRulesEditorProps } from '@/components/search-panel/FilterRul
 import type { PathSearchMatch } from '@/components/search-panel/types';
 import { evaluateCrossFileTarget } from '@/components/search-panel/crossFileTarget';
 import { openFilePath } from '@/lib/openFile';
+import { loadSavedTabSessionExtras, updateTabSessionExtras } from '@/lib/sessionExtras';
 import { useStore } from '@/store/useStore';
 import {
   SearchSidebarBody,
   SearchPanelOverlays,
   SearchSidebarChrome,
   useCrossFileSearch,
+  useFilterMatchesAppended,
   useFilterRules,
   useSearchInput,
   useSearchPanelChrome,
@@ -347,6 +349,32 @@ export function SearchReplacePanel() {
     setFilterRuleGroups,
   });
 
+  useEffect(() => {
+    if (safeActiveTabId) {
+      updateTabSessionExtras(safeActiveTabId, { filterRuleGroup: selectedFilterGroupName || null });
+    }
+  }, [safeActiveTabId, selectedFilterGroupName]);
+
+  useEffect(() => {
+    if (!safeActiveTabId) {
+      return;
+    }
+
+    let cancelled = false;
+    void loadSavedTabSessionExtras(safeActiveTabId).then((extras) => {
+      const groupName = extras?.filterRuleGroup;
+      if (!cancelled && groupName && normalizedFilterRuleGroups.some((group) => group.name === groupName)) {
+        handleSelectedFilterGroupChange(groupName);
+      }
+    });
+
+    return () => {
+      cancelled = true;
+    };
+    // Only on tab change; the saved group is applied once per tab.
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [safeActiveTabId]);
+
   const {
     cancelPendingBatchLoad,
     executeFilter,
@@ -604,6 +632,15 @@ export function SearchReplacePanel() {
     setResultPanelState,
   });
 
+  useFilterMatchesAppended({
+    filterSessionIdRef,
+    filterLineCursorRef,
+    cachedFilterRef,
+    filterCountCacheRef,
+    setFilterMatches,
+    setTotalFilterMatchedLineCount,
+  });
+
   const navigateResultFilterByStepRef = useRef<((step: number) => Promise<void>) | null>(null);
 
   const {
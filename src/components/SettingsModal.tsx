@@ -311,6 +311,8 @@ export function SettingsModal() {
   const singleInstanceModeRestartToast = tr('settings.singleInstanceModeRestartToast');
   const rememberWindowStateLabel = tr('settings.rememberWindowState');
   const rememberWindowStateDesc = tr('settings.rememberWindowStateDesc');
+  const autoReloadUnmodifiedFilesLabel = tr('settings.autoReloadUnmodifiedFiles');
+  const autoReloadUnmodifiedFilesDesc = tr('settings.autoReloadUnmodifiedFilesDesc');
   const mouseGesturesLabel = tr('settings.mouseGestures');
   const mouseGesturesDesc = tr('settings.mouseGesturesDesc');
   const mouseGestureSequenceLabel = tr('settings.mouseGestureSequence');
@@ -1205,6 +1207,50 @@ export function SettingsModal() {
                   </div>
                 </section>
 
+                <section className="rounded-xl border border-border/70 bg-card/80 p-5 shadow-sm">
+                  <div className="flex items-center justify-between gap-3">
+                    <div className="min-w-0">
+                      <p className="text-sm font-medium leading-none">{autoReloadUnmodifiedFilesLabel}</p>
+                      <p className="mt-1 text-xs text-muted-foreground">{autoReloadUnmodifiedFilesDesc}</p>
+                    </div>
+                    <button
+                      type="button"
+                      onClick={() => updateSettings({ autoReloadUnmodifiedFiles: !settings.autoReloadUnmodifiedFiles })}
+                      className={cn(
+                        'relative inline-flex h-7 w-14 shrink-0 items-center rounded-full border p-0.5 transition-[background-color,border-color,box-shadow] duration-200',
+                        'focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-primary/40 focus-visible:ring-offset-2 focus-visible:ring-offset-background',
+                        settings.autoReloadUnmodifiedFiles
+                          ? 'justify-end border-emerald-500/90 bg-emerald-500 shadow-[0_0_0_1px_rgba(16,185,129,0.35)] dark:border-emerald-400/90 dark:bg-emerald-500/85'
+                          : 'justify-start border-zinc-400/80 bg-zinc-300/70 dark:border-zinc-500/90 dark:bg-zinc-700/80'
+                      )}
+                      aria-pressed={!!settings.autoReloadUnmodifiedFiles}
+                      aria-label={autoReloadUnmodifiedFilesLabel}
+                    >
+                      <span
+                        className={cn(
+                          'pointer-events-none absolute left-2 text-[9px] font-semibold tracking-[0.08em] transition-opacity',
+                          settings.autoReloadUnmodifiedFiles
+                            ? 'opacity-0 text-primary-foreground/80'
+                            : 'opacity-90 text-zinc-700 dark:text-zinc-200'
+                        )}
+                      >
+                        {switchOffText}
+                      </span>
+                      <span
+                        className={cn(
+                          'pointer-events-none absolute right-2 text-[9px] font-semibold tracking-[0.08em] transition-opacity',
+                          settings.autoReloadUnmodifiedFiles
+                            ? 'opacity-95 text-primary-foreground'
+                            : 'opacity-0 text-zinc-700 dark:text-zinc-200'
+                        )}
+                      >
+                        {switchOnText}
+                      </span>
+                      <span className="relative z-10 h-5 w-5 rounded-full border border-black/10 bg-white shadow-sm transition-transform dark:border-white/20" />
+                    </button>
+                  </div>
+                </section>
+
                 {isWindows && (
                   <>
                     <section className="rounded-xl border border-border/70 bg-card/80 p-5 shadow-sm">
@@ -1635,6 +1681,35 @@ export function SettingsModal() {
                       </p>
                     </div>
 
+                    <div className="space-y-2 lg:col-span-2">
+                      <label htmlFor="settings-fold-min-block-lines-input" className="text-sm font-medium leading-none">
+                        {tr('settings.foldMinBlockLines')}
+                      </label>
+                      <div className="relative max-w-[220px]">
+                        <input
+                          id="settings-fold-min-block-lines-input"
+                          type="number"
+                          className={cn(controlClassName, 'pr-10')}
+                          value={settings.foldMinBlockLines}
+                          onChange={(e) => {
+                            const value = Number.parseInt(e.target.value, 10);
+                            updateSettings({
+                              foldMinBlockLines: Number.isFinite(value) ? Math.min(100, Math.max(2, value)) : 2,
+                            });
+                          }}
+                          min={2}
+                          max={100}
+                          name="settings-fold-min-block-lines"
+                        />
+                        <span className="pointer-events-none absolute inset-y-0 right-3 flex items-center text-xs text-muted-foreground">
+                          ln
+                        </span>
+                      </div>
+                      <p className="text-xs text-muted-foreground">
+                        {tr('settings.foldMinBlockLinesDesc')}
+                      </p>
+                    </div>
+
                     <div className="space-y-2 lg:col-span-2">
                       <label htmlFor="settings-tab-indent-mode-input" className="text-sm font-medium leading-none">
                         {tr('settings.tabIndentMode')}
@@ -213,6 +213,13 @@ export function StatusBar() {
     const currentSyntax = activeTab.syntaxOverride ?? null;
     const syntaxSelectValue = currentSyntax ?? 'auto';
     const autoSyntaxLabel = `Auto (${getSyntaxLabel(detectedSyntax)})`;
+    // Only offered while the name alone would leave the file as plain text.
+    const suggestedSyntax = !currentSyntax
+        && detectedSyntax === 'plain_text'
+        && activeTab.suggestedSyntax
+        && syntaxOptions.some((option) => option.value === activeTab.suggestedSyntax)
+        ? activeTab.suggestedSyntax
+        : null;
     const lineEndingSelectLabel = settings.language === 'zh-CN' ? '行尾符' : 'Line ending';
     const encodingSelectLabel = settings.language === 'zh-CN' ? '编码' : 'Encoding';
     const syntaxSelectLabel = settings.language === 'zh-CN' ? '语法' : 'Syntax';
@@ -291,6 +298,18 @@ export function StatusBar() {
                     </select>
                 </div>
                 <div className="w-[1px] h-3 bg-border" />
+                {suggestedSyntax && (
+                    <>
+                        <button
+                            type="button"
+                            className="text-primary transition-colors hover:text-foreground"
+                            onClick={() => void handleSyntaxChange(suggestedSyntax)}
+                        >
+                            {tr('status.suggestedSyntax').replace('{syntax}', getSyntaxLabel(suggestedSyntax))}
+                        </button>
+                        <div className="w-[1px] h-3 bg-border" />
+                    </>
+                )}
                 <div className="group flex items-center gap-1.5 cursor-pointer transition-colors hover:text-foreground focus-within:text-foreground">
                     <select
                         className={statusSelectClassName}
@@ -0,0 +1,220 @@
+import { invoke } from '@tauri-apps/api/core';
+import { useCallback, useEffect, useRef, useState } from 'react';
+import { t } from '@/i18n';
+import { type FileTab, useStore } from '@/store/useStore';
+
+// Streamed files are too large to hand to Monaco, so they are paged in with
+// get_visible_lines and drawn as plain rows. Browsers cap element height, so
+// past MAX_SCROLL_HEIGHT the scrollbar maps proportionally onto lines.
+const MAX_SCROLL_HEIGHT = 10_000_000;
+const LINES_PER_REQUEST = 400;
+const OVERSCAN_LINES = 50;
+
+interface StreamingSearchMatch {
+  line: number;
+  column: number;
+  text: string;
+  lineText: string;
+}
+
+interface StreamingSearchPayload {
+  matches: StreamingSearchMatch[];
+  nextLine: number | null;
+}
+
+interface LoadedLines {
+  // 0-based line of lines[0].
+  startLine: number;
+  lines: string[];
+}
+
+function splitVisibleLines(text: string) {
+  const lines = text.split('\n');
+  if (lines.length > 0 && lines[lines.length - 1] === '') {
+    lines.pop();
+  }
+
+  return lines.map((line) => (line.endsWith('\r') ? line.slice(0, -1) : line));
+}
+
+export function StreamingDocumentView({ tab }: { tab: FileTab }) {
+  const fontFamily = useStore((state) => state.settings.fontFamily);
+  const fontSize = useStore((state) => state.settings.fontSize);
+  const language = useStore((state) => state.settings.language);
+  const containerRef = useRef<HTMLDivElement | null>(null);
+  const loadRequestRef = useRef(0);
+  const nextSearchLineRef = useRef(1);
+  const [viewport, setViewport] = useState({ scrollTop: 0, height: 0 });
+  const [loaded, setLoaded] = useState<LoadedLines>({ startLine: 0, lines: [] });
+  const [keyword, setKeyword] = useState('');
+  const [searchStatus, setSearchStatus] = useState<string | null>(null);
+  const [highlightedLine, setHighlightedLine] = useState<number | null>(null);
+
+  const lineHeight = Math.round(fontSize * 1.5);
+  const lineCount = Math.max(1, tab.lineCount);
+  const totalHeight = lineCount * lineHeight;
+  const scaled = totalHeight > MAX_SCROLL_HEIGHT;
+  const scrollHeight = Math.min(totalHeight, MAX_SCROLL_HEIGHT);
+  const visibleLineCount = Math.ceil(viewport.height / lineHeight) + 1;
+  const maxFirstLine = Math.max(0, lineCount - visibleLineCount + 1);
+  const scrollRange = Math.max(1, scrollHeight - viewport.height);
+  const firstLine = scaled
+    ? Math.min(maxFirstLine, Math.floor((viewport.scrollTop / scrollRange) * maxFirstLine))
+    : Math.floor(viewport.scrollTop / lineHeight);
+  const rowsTop = scaled ? viewport.scrollTop : firstLine * lineHeight;
+
+  useEffect(() => {
+    const container = containerRef.current;
+    if (!container) {
+      return;
+    }
+
+    const updateViewport = () => {
+      setViewport({ scrollTop: container.scrollTop, height: container.clientHeight });
+    };
+    updateViewport();
+    const observer = new ResizeObserver(updateViewport);
+    observer.observe(container);
+    return () => {
+      observer.disconnect();
+    };
+  }, []);
+
+  useEffect(() => {
+    const lastNeededLine = Math.min(lineCount, firstLine + visibleLineCount);
+    if (
+      firstLine >= loaded.startLine
+      && lastNeededLine <= loaded.startLine + loaded.lines.length
+    ) {
+      return;
+    }
+
+    const startLine = Math.max(0, firstLine - OVERSCAN_LINES);
+    const requestId = loadRequestRef.current + 1;
+    loadRequestRef.current = requestId;
+    void invoke<string>('get_visible_lines', {
+      id: tab.id,
+      startLine,
+      endLine: startLine + LINES_PER_REQUEST,
+    })
+      .then((text) => {
+        if (loadRequestRef.current === requestId) {
+          setLoaded({ startLine, lines: splitVisibleLines(text) });
+        }
+      })
+      .catch((error) => {
+        console.error('Failed to load streamed lines:', error);
+      });
+  }, [firstLine, lineCount, loaded, tab.id, visibleLineCount]);
+
+  const scrollToLine = useCallback(
+    (lineIndex: number) => {
+      const container = containerRef.current;
+      if (!container) {
+        return;
+      }
+
+      const targetLine = Math.max(0, lineIndex - Math.floor(visibleLineCount / 2));
+      container.scrollTop = scaled
+        ? (Math.min(targetLine, maxFirstLine) / Math.max(1, maxFirstLine)) * scrollRange
+        : targetLine * lineHeight;
+    },
+    [lineHeight, maxFirstLine, scaled, scrollRange, visibleLineCount]
+  );
+
+  const findNext = useCallback(async () => {
+    if (!keyword) {
+      return;
+    }
+
+    try {
+      const payload = await invoke<StreamingSearchPayload>('search_streaming_document', {
+        id: tab.id,
+        keyword,
+        mode: 'literal',
+        caseSensitive: false,
+        startLine: nextSearchLineRef.current,
+        maxResults: 1,
+      });
+      const found = payload.matches[0];
+      if (!found) {
+        nextSearchLineRef.current = 1;
+        setSearchStatus(t(language, 'streamingView.noMoreMatches'));
+        return;
+      }
+
+      nextSearchLineRef.current = found.line + 1;
+      setSearchStatus(null);
+      setHighlightedLine(found.line - 1);
+      scrollToLine(found.line - 1);
+    } catch (error) {
+      setSearchStatus(error instanceof Error ? error.message : String(error));
+    }
+  }, [keyword, language, scrollToLine, tab.id]);
+
+  const rows: Array<{ index: number; text: string }> = [];
+  for (let index = firstLine; index < Math.min(lineCount, firstLine + visibleLineCount); index += 1) {
+    rows.push({
+      index,
+      text: loaded.lines[index - loaded.startLine] ?? '',
+    });
+  }
+
+  return (
+    <div className="flex h-full w-full flex-col">
+      <div className="flex h-8 items-center gap-2 border-b border-border px-3 text-xs text-muted-foreground">
+        <span>{t(language, 'streamingView.readOnly')}</span>
+        <input
+          value={keyword}
+          onChange={(event) => {
+            setKeyword(event.target.value);
+            nextSearchLineRef.current = 1;
+            setSearchStatus(null);
+          }}
+          onKeyDown={(event) => {
+            if (event.key === 'Enter') {
+              event.preventDefault();
+              void findNext();
+            }
+          }}
+          placeholder={t(language, 'streamingView.findPlaceholder')}
+          className="ml-auto h-6 w-56 rounded-md border border-input bg-background px-2 text-xs text-foreground outline-none focus-visible:ring-1 focus-visible:ring-ring"
+        />
+        <button
+          type="button"
+          onClick={() => void findNext()}
+          className="rounded-md border border-border px-2 py-0.5 text-xs text-foreground hover:bg-muted focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring"
+        >
+          {t(language, 'streamingView.findNext')}
+        </button>
+        {searchStatus ? <span>{searchStatus}</span> : null}
+      </div>
+      <div
+        ref={containerRef}
+        className="relative min-h-0 flex-1 overflow-auto"
+        onScroll={(event) => {
+          const target = event.currentTarget;
+          setViewport({ scrollTop: target.scrollTop, height: target.clientHeight });
+        }}
+      >
+        <div style={{ height: scrollHeight }} />
+        <div
+          className="absolute left-0 right-0"
+          style={{ top: rowsTop, fontFamily, fontSize, lineHeight: `${lineHeight}px` }}
+        >
+          {rows.map((row) => (
+            <div
+              key={row.index}
+              className={`flex whitespace-pre ${row.index === highlightedLine ? 'bg-muted' : ''}`}
+            >
+              <span className="w-20 shrink-0 select-none pr-3 text-right text-muted-foreground">
+                {row.index + 1}
+              </span>
+              <span className="text-foreground">{row.text}</span>
+            </div>
+          ))}
+        </div>
+      </div>
+    </div>
+  );
+}
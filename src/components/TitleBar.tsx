@@ -41,6 +41,7 @@ import {
 } from '@/lib/tabClose';
 import { detectSyntaxKeyFromTab } from '@/lib/syntax';
 import { getParentDirectoryPath, pathBaseName } from '@/lib/pathUtils';
+import { recordReadingPositions } from '@/lib/readingPositions';
 
 const appWindow = getCurrentWindow();
 const noDragStyle = { WebkitAppRegion: 'no-drag' } as CSSProperties;
@@ -260,6 +261,14 @@ export function TitleBar() {
     const contextMenuTabIsLocked = contextMenuTabLockOrder !== null;
     const canToggleContextMenuTabLock = isRegularFileTab(contextMenuTab)
         && normalizeTabPath(contextMenuTab.path).length > 0;
+    const contextMenuTabAutoReloads = !!contextMenuTab
+        && (contextMenuTab.autoReloadOverride ?? settings.autoReloadUnmodifiedFiles);
+    const autoReloadTabLabel = settings.language === 'zh-CN'
+        ? '自动重新加载'
+        : 'Auto-reload';
+    const stopAutoReloadTabLabel = settings.language === 'zh-CN'
+        ? '停止自动重新加载'
+        : 'Stop Auto-reload';
 
     const copyToClipboard = useCallback(async (text: string) => {
         if (!navigator.clipboard?.writeText) {
@@ -398,6 +407,7 @@ export function TitleBar() {
             }
 
             const tabIds = closableTabs.map((tab) => tab.id);
+            await recordReadingPositions(tabIds);
 
             tabIds.forEach((id) => closeTab(id));
 
@@ -592,6 +602,15 @@ export function TitleBar() {
         latestState.updateSettings({ pinnedTabPaths: nextPinnedPaths });
     }, []);
 
+    const handleToggleTabAutoReload = useCallback((tab: FileTab | null) => {
+        if (!tab?.path) {
+            return;
+        }
+
+        const autoReloads = tab.autoReloadOverride ?? settings.autoReloadUnmodifiedFiles;
+        updateTab(tab.id, { autoReloadOverride: !autoReloads });
+    }, [settings.autoReloadUnmodifiedFiles, updateTab]);
+
     const handleTabContextMenu = useCallback((event: MouseEvent<HTMLDivElement>, tab: FileTab) => {
         event.preventDefault();
         event.stopPropagation();
@@ -1223,6 +1242,17 @@ export function TitleBar() {
                     >
                         {contextMenuTabIsLocked ? unlockTabLabel : lockTabLabel}
                     </button>
+                    <button
+                        type="button"
+                        className="w-full rounded-sm px-3 py-1.5 text-left text-xs hover:bg-accent hover:text-accent-foreground focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring disabled:cursor-not-allowed disabled:opacity-50"
+                        onClick={() => {
+                            setTabContextMenu(null);
+                            handleToggleTabAutoReload(contextMenuTab);
+                        }}
+                        disabled={!contextMenuTab?.path}
+                    >
+                        {contextMenuTabAutoReloads ? stopAutoReloadTabLabel : autoReloadTabLabel}
+                    </button>
                     <button
                         type="button"
                         className="w-full rounded-sm px-3 py-1.5 text-left text-xs hover:bg-accent hover:text-accent-foreground focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring disabled:cursor-not-allowed disabled:opacity-50"
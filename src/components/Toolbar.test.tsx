@@ -6,7 +6,13 @@ import { invoke } from "@tauri-apps/api/core";
 import { message, open } from "@tauri-apps/plugin-dialog";
 import { readText as readClipboardText } from "@tauri-apps/plugin-clipboard-manager";
 import { openFilePath } from "@/lib/openFile";
-import { addRecentFolderPath, removeRecentFilePath, removeRecentFolderPath } from "@/lib/recentPaths";
+import {
+  addRecentFolderPath,
+  clearRecentFilePaths,
+  clearRecentFolderPaths,
+  removeRecentFilePath,
+  removeRecentFolderPath,
+} from "@/lib/recentPaths";
 import { detectOutlineType, loadOutline } from "@/lib/outline";
 import { detectStructuredFormatSyntaxKey, isStructuredFormatSupported } from "@/lib/structuredFormat";
 import { confirmTabClose, saveTab } from "@/lib/tabClose";
@@ -33,6 +39,8 @@ vi.mock("@/lib/openFile", () => ({
 
 vi.mock("@/lib/recentPaths", () => ({
   addRecentFolderPath: vi.fn(),
+  clearRecentFilePaths: vi.fn(),
+  clearRecentFolderPaths: vi.fn(),
   removeRecentFilePath: vi.fn(),
   removeRecentFolderPath: vi.fn(),
 }));
@@ -65,6 +73,8 @@ const messageMock = vi.mocked(message);
 const openMock = vi.mocked(open);
 const openFilePathMock = vi.mocked(openFilePath);
 const addRecentFolderPathMock = vi.mocked(addRecentFolderPath);
+const clearRecentFilePathsMock = vi.mocked(clearRecentFilePaths);
+const clearRecentFolderPathsMock = vi.mocked(clearRecentFolderPaths);
 const removeRecentFilePathMock = vi.mocked(removeRecentFilePath);
 const removeRecentFolderPathMock = vi.mocked(removeRecentFolderPath);
 const detectOutlineTypeMock = vi.mocked(detectOutlineType);
@@ -2611,7 +2621,7 @@ describe("Toolbar", () => {
     fireEvent.click(await screen.findByRole("button", { name: "Clear recent files" }));
 
     await waitFor(() => {
-      expect(useStore.getState().settings.recentFiles).toEqual([]);
+      expect(clearRecentFilePathsMock).toHaveBeenCalledTimes(1);
     });
     expect(screen.queryByTitle("C:\\repo\\recent-d.ts")).toBeNull();
   });
@@ -2959,7 +2969,7 @@ describe("Toolbar", () => {
     fireEvent.click(await screen.findByRole("button", { name: "Clear recent folders" }));
 
     await waitFor(() => {
-      expect(useStore.getState().settings.recentFolders).toEqual([]);
+      expect(clearRecentFolderPathsMock).toHaveBeenCalledTimes(1);
     });
     expect(screen.queryByTitle("C:\\repo\\folder-c")).toBeNull();
   });
@@ -15,8 +15,15 @@ import {
     type MouseEvent as ReactMouseEvent,
     type RefObject,
 } from 'react';
-import { openFilePath } from '@/lib/openFile';
-import { addRecentFolderPath, removeRecentFilePath, removeRecentFolderPath } from '@/lib/recentPaths';
+import { openFilePath, reopenLastClosedTab } from '@/lib/openFile';
+import { takeTopVisibleLine } from '@/lib/readingPositions';
+import {
+    addRecentFolderPath,
+    clearRecentFilePaths,
+    clearRecentFolderPaths,
+    removeRecentFilePath,
+    removeRecentFolderPath,
+} from '@/lib/recentPaths';
 import { useStore, FileTab, isDiffTab, type DiffPanelSide } from '@/store/useStore';
 import { dispatchDocumentUpdated } from '@/lib/documentEvents';
 import { t } from '@/i18n';
@@ -27,6 +34,7 @@ import { isMarkdownTab } from '@/lib/markdown';
 import { dispatchEditorFindOpen } from '@/lib/editorFind';
 import { pathBaseName } from '@/lib/pathUtils';
 import { cn } from '@/lib/utils';
+import { localizeBackendError } from '@/lib/backendErrors';
 import { getDocumentText } from '@/lib/documentText';
 import { translateDocumentText } from '@/lib/translation';
 
@@ -91,7 +99,7 @@ function dispatchReplaceDocumentText(tabId: string, text: string) {
 }
 
 function getErrorMessage(error: unknown) {
-    return error instanceof Error ? error.message : String(error);
+    return error instanceof Error ? error.message : localizeBackendError(String(error));
 }
 
 function getActiveEditorElement() {
@@ -769,11 +777,18 @@ export function Toolbar({ onMarkdownPreviewToggleIntent }: ToolbarProps) {
         }
 
         const shouldCreateBlankTab = useStore.getState().tabs.length === 1;
+        const cursor = useStore.getState().cursorPositionByTab[activeTab.id];
+        const readingLine = takeTopVisibleLine(activeTab.id);
 
         closeTab(activeTab.id);
 
         try {
-            await invoke('close_file', { id: activeTab.id });
+            await invoke('close_file', {
+                id: activeTab.id,
+                readingLine,
+                cursorLine: cursor?.line ?? null,
+                cursorColumn: cursor?.column ?? null,
+            });
 
             if (shouldCreateBlankTab) {
                 const fileInfo = await invoke<FileTab>('new_file', { newFileLineEnding });
@@ -784,6 +799,14 @@ export function Toolbar({ onMarkdownPreviewToggleIntent }: ToolbarProps) {
         }
     }, [activeTab, addTab, closeTab, language, newFileLineEnding, persistTab]);
 
+    const handleReopenClosedTab = useCallback(async () => {
+        try {
+            await reopenLastClosedTab();
+        } catch (e) {
+            console.error('Failed to reopen closed tab:', e);
+        }
+    }, []);
+
     const handleUndo = useCallback(async () => {
         if (activeDiffTab && activeDiffPanel) {
             dispatchDiffHistoryAction(activeDiffTab.id, activeDiffPanel, 'undo');
@@ -1097,6 +1120,12 @@ export function Toolbar({ onMarkdownPreviewToggleIntent }: ToolbarProps) {
                 return;
             }
 
+            if (isKey('t') && event.shiftKey) {
+                event.preventDefault();
+                void handleReopenClosedTab();
+                return;
+            }
+
             if (isKey('z') && !event.shiftKey) {
                 preventDuplicateShortcutHandling();
                 void handleUndo();
@@ -1145,6 +1174,7 @@ export function Toolbar({ onMarkdownPreviewToggleIntent }: ToolbarProps) {
         handleOpenFile,
         handleToggleLineNumbers,
         handleRedo,
+        handleReopenClosedTab,
         handleReplace,
         handleFilter,
         handleFormatBeautify,
@@ -1232,7 +1262,7 @@ export function Toolbar({ onMarkdownPreviewToggleIntent }: ToolbarProps) {
                 onItemClick={handleOpenRecentFile}
                 onItemRemove={handleRemoveRecentFile}
                 onClear={() => {
-                    updateSettings({ recentFiles: [] });
+                    clearRecentFilePaths();
                     setRecentMenu(null);
                 }}
             />
@@ -1255,7 +1285,7 @@ export function Toolbar({ onMarkdownPreviewToggleIntent }: ToolbarProps) {
                 onItemClick={handleOpenRecentFolder}
                 onItemRemove={handleRemoveRecentFolder}
                 onClear={() => {
-                    updateSettings({ recentFolders: [] });
+                    clearRecentFolderPaths();
                     setRecentMenu(null);
                 }}
             />
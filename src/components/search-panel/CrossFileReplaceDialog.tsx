@@ -9,6 +9,7 @@ import {
   useState,
 } from 'react';
 import { getSearchPanelMessages } from '@/i18n';
+import { localizeBackendError } from '@/lib/backendErrors';
 import { dispatchDocumentUpdated } from '@/lib/documentEvents';
 import { dispatchEditorForceRefresh } from './utils';
 import { useStore } from '@/store/useStore';
@@ -42,7 +43,7 @@ export interface CrossFileReplaceDialogProps {
 }
 
 function describeError(error: unknown): string {
-  if (typeof error === 'string') return error;
+  if (typeof error === 'string') return localizeBackendError(error);
   if (error instanceof Error) return error.message;
   try {
     return JSON.stringify(error);
@@ -1,3 +1,5 @@
+import { localizeBackendError } from '@/lib/backendErrors';
+
 interface ApplySearchPanelErrorMessageOptions {
   error: unknown;
   prefix: string;
@@ -5,7 +7,7 @@ interface ApplySearchPanelErrorMessageOptions {
 }
 
 export function resolveSearchPanelErrorMessage(error: unknown): string {
-  return error instanceof Error ? error.message : String(error);
+  return error instanceof Error ? error.message : localizeBackendError(String(error));
 }
 
 export function applySearchPanelErrorMessage({
@@ -6,9 +6,14 @@ import type {
   SearchCursorStepBackendResult,
   SearchSessionNextBackendResult,
   SearchSessionRestoreBackendResult,
+  SearchMode,
   SearchSessionStartBackendResult,
 } from './types';
 
+export function isSearchMode(value: unknown): value is SearchMode {
+  return value === 'literal' || value === 'regex' || value === 'wildcard';
+}
+
 export function isSearchSessionStartBackendResult(value: unknown): value is SearchSessionStartBackendResult {
   if (!value || typeof value !== 'object') {
     return false;
@@ -298,6 +298,8 @@ export function buildFilterSessionStartRequest({
     resultFilterKeyword: effectiveResultFilterKeyword,
     resultFilterCaseSensitive: caseSensitive,
     maxResults,
+    // Only streamed documents ever append, so following is free elsewhere.
+    followAppended: true,
   };
 }
 
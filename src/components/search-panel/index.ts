@@ -7,6 +7,7 @@ export { SearchPanelOverlays } from './SearchPanelOverlays';
 export { SearchSidebarChrome } from './SearchSidebarChrome';
 
 export { useFilterRules } from './useFilterRules';
+export { useFilterMatchesAppended } from './useFilterMatchesAppended';
 
 export { useSearchInput } from './useSearchInput';
 export { useSearchPanelChrome } from './useSearchPanelChrome';
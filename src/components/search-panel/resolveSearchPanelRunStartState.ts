@@ -34,6 +34,26 @@ interface ResolvedSearchRunStartState {
   totalMatches: number;
 }
 
+// Session id of the search scan still running in the backend, so a newer
+// search or closing the panel can stop it with cancel_search_session.
+let pendingSearchSessionId: string | null = null;
+
+export function getPendingSearchSessionId() {
+  return pendingSearchSessionId;
+}
+
+export function cancelPendingSearchSession() {
+  const sessionId = pendingSearchSessionId;
+  pendingSearchSessionId = null;
+  if (!sessionId) {
+    return;
+  }
+
+  void invoke<boolean>('cancel_search_session', { sessionId }).catch((error) => {
+    console.warn('Failed to cancel search session:', error);
+  });
+}
+
 interface ResolveFilterRunStartStateOptions {
   activeTabId: string;
   caseSensitive: boolean;
@@ -57,23 +77,38 @@ export async function resolveSearchRunStartState({
   effectiveSearchKeyword,
   maxResults,
   searchMode,
-}: ResolveSearchRunStartStateOptions): Promise<ResolvedSearchRunStartState> {
-  const sessionStartResult = await invoke<unknown>(
-    'search_session_start_in_document',
-    buildSearchSessionStartRequest({
-      activeTabId,
-      caseSensitive,
-      effectiveResultFilterKeyword,
-      effectiveSearchKeyword,
-      maxResults,
-      searchMode,
-    })
-  );
+}: ResolveSearchRunStartStateOptions): Promise<ResolvedSearchRunStartState | null> {
+  cancelPendingSearchSession();
+  const requestSessionId = `search-${Date.now()}-${Math.random().toString(36).slice(2)}`;
+  pendingSearchSessionId = requestSessionId;
+
+  let sessionStartResult: unknown;
+  try {
+    sessionStartResult = await invoke<unknown>('search_session_start_in_document', {
+      ...buildSearchSessionStartRequest({
+        activeTabId,
+        caseSensitive,
+        effectiveResultFilterKeyword,
+        effectiveSearchKeyword,
+        maxResults,
+        searchMode,
+      }),
+      sessionId: requestSessionId,
+    });
+  } finally {
+    if (pendingSearchSessionId === requestSessionId) {
+      pendingSearchSessionId = null;
+    }
+  }
 
   if (!isSearchSessionStartBackendResult(sessionStartResult)) {
     throw new Error('Invalid search_session_start_in_document response');
   }
 
+  if (sessionStartResult.cancelled) {
+    return null;
+  }
+
   const {
     documentVersion,
     nextMatches,
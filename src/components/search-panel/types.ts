@@ -5,6 +5,13 @@ export type PanelMode = 'find' | 'replace' | 'filter';
 export type FilterRuleMatchMode = 'contains' | 'regex' | 'wildcard';
 export type FilterRuleApplyTo = 'line' | 'match';
 
+export interface FilterRuleAlertOptions {
+  notification: boolean;
+  sound: boolean;
+  flashTaskbar: boolean;
+  minIntervalSeconds: number;
+}
+
 export interface FilterRule {
   id: string;
   keyword: string;
@@ -14,6 +21,7 @@ export interface FilterRule {
   bold: boolean;
   italic: boolean;
   applyTo: FilterRuleApplyTo;
+  alert?: FilterRuleAlertOptions;
 }
 
 export interface FilterRuleDragState {
@@ -60,11 +68,14 @@ export interface FilterRuleInputPayload {
   bold: boolean;
   italic: boolean;
   applyTo: FilterRuleApplyTo;
+  alert?: FilterRuleAlertOptions;
 }
 
 export interface FilterRuleGroupPayload {
   name: string;
   rules: FilterRuleInputPayload[];
+  // File in the shared rule folder this read-only group comes from.
+  sharedSource?: string;
 }
 
 export interface SearchMatch {
@@ -112,6 +123,15 @@ export interface SearchSessionStartBackendResult {
   nextOffset: number | null;
   totalMatches: number;
   totalMatchedLines: number;
+  // Set when cancel_search_session stopped the scan; nothing else is filled in.
+  cancelled?: boolean;
+}
+
+// Latest rutar://search-progress event of the running search.
+export interface SearchProgress {
+  bytesScanned: number;
+  totalBytes: number;
+  matchesSoFar: number;
 }
 
 export interface SearchSessionNextBackendResult {
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useCallback, useEffect, useRef, useState } from 'react';
+import { localizeBackendError } from '@/lib/backendErrors';
 import type {
   PathSearchFileError,
   PathSearchMatch,
@@ -39,7 +40,7 @@ export interface UseCrossFileSearchResult {
 
 function describeError(error: unknown): string {
   if (typeof error === 'string') {
-    return error;
+    return localizeBackendError(error);
   }
   if (error instanceof Error) {
     return error.message;
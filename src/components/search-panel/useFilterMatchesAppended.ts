@@ -0,0 +1,89 @@
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import type { Dispatch, MutableRefObject, SetStateAction } from 'react';
+import type { FilterMatch } from './types';
+
+interface FilterMatchesAppendedPayload {
+  sessionId: string;
+  documentId: string;
+  // 1-based; earlier matches from this line on are superseded by `matches`.
+  replaceFromLine: number;
+  matches: FilterMatch[];
+  documentVersion: number;
+  totalMatchedLines: number;
+}
+
+interface UseFilterMatchesAppendedOptions {
+  filterSessionIdRef: MutableRefObject<string | null>;
+  filterLineCursorRef: MutableRefObject<number | null>;
+  cachedFilterRef: MutableRefObject<unknown>;
+  filterCountCacheRef: MutableRefObject<unknown>;
+  setFilterMatches: Dispatch<SetStateAction<FilterMatch[]>>;
+  setTotalFilterMatchedLineCount: Dispatch<SetStateAction<number | null>>;
+}
+
+// Keeps the filter results of a streamed document current. Follow sessions
+// re-run their rules on appended text in the backend; when every page is
+// already loaded the new matches are merged here, otherwise the next page
+// request picks them up from the session.
+export function useFilterMatchesAppended({
+  filterSessionIdRef,
+  filterLineCursorRef,
+  cachedFilterRef,
+  filterCountCacheRef,
+  setFilterMatches,
+  setTotalFilterMatchedLineCount,
+}: UseFilterMatchesAppendedOptions) {
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+    const setupFilterMatchesAppendedListener = async () => {
+      try {
+        const unsubscribe = await listen<FilterMatchesAppendedPayload>(
+          'rutar://filter-matches-appended',
+          (event) => {
+            const payload = event.payload;
+            if (!payload || payload.sessionId !== filterSessionIdRef.current) {
+              return;
+            }
+
+            cachedFilterRef.current = null;
+            filterCountCacheRef.current = null;
+            if (filterLineCursorRef.current === null) {
+              setFilterMatches((previous) => [
+                ...previous.filter((match) => match.line < payload.replaceFromLine),
+                ...payload.matches,
+              ]);
+            }
+            setTotalFilterMatchedLineCount(payload.totalMatchedLines);
+          }
+        );
+
+        if (disposed) {
+          unsubscribe();
+          return;
+        }
+
+        unlisten = unsubscribe;
+      } catch (error) {
+        console.error('Failed to listen filter-matches-appended event:', error);
+      }
+    };
+
+    void setupFilterMatchesAppendedListener();
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [
+    cachedFilterRef,
+    filterCountCacheRef,
+    filterLineCursorRef,
+    filterSessionIdRef,
+    setFilterMatches,
+    setTotalFilterMatchedLineCount,
+  ]);
+}
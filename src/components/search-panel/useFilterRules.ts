@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { open, save } from '@tauri-apps/plugin-dialog';
 import {
   useCallback,
@@ -266,8 +267,32 @@ export function useFilterRules({
 
     void loadFilterRuleGroups();
 
+    // Groups from the shared rule folder change on disk; reload them as they do.
+    let unlisten: (() => void) | undefined;
+    const setupSharedFilterRuleGroupsListener = async () => {
+      try {
+        const unsubscribe = await listen('rutar://filter-rule-groups-changed', () => {
+          void loadFilterRuleGroups();
+        });
+
+        if (cancelled) {
+          unsubscribe();
+          return;
+        }
+
+        unlisten = unsubscribe;
+      } catch (error) {
+        console.error('Failed to listen filter-rule-groups-changed event:', error);
+      }
+    };
+
+    void setupSharedFilterRuleGroupsListener();
+
     return () => {
       cancelled = true;
+      if (unlisten) {
+        unlisten();
+      }
     };
   }, [messages.filterGroupLoadFailed, setErrorMessage, setFilterRuleGroups]);
 
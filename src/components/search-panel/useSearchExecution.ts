@@ -7,6 +7,7 @@ import {
   type SetStateAction,
   type TransitionStartFunction,
 } from 'react';
+import { loadSavedTabSessionExtras, updateTabSessionExtras } from '@/lib/sessionExtras';
 import { applyFilterSessionRestoreResult, handleFilterSessionRestoreError } from './applyFilterSessionRestoreResult';
 import { applyResolvedSearchFirstMatchResult } from './applySearchPanelFirstMatchResult';
 import { applySearchPanelErrorMessage } from './applySearchPanelErrorMessage';
@@ -23,7 +24,11 @@ import {
   createSearchRunSuccessResult,
 } from './applySearchPanelRunResults';
 import { applySearchSessionRestoreResult, handleSearchSessionRestoreError } from './applySearchSessionRestoreResult';
-import { isFilterSessionRestoreBackendResult, isSearchSessionRestoreBackendResult } from './backendGuards';
+import {
+  isFilterSessionRestoreBackendResult,
+  isSearchMode,
+  isSearchSessionRestoreBackendResult,
+} from './backendGuards';
 import { buildFilterSessionRestoreRequest, buildSearchSessionRestoreRequest } from './buildSearchPanelRestoreRequests';
 import {
   createEmptyFilterRunResult,
@@ -371,14 +376,7 @@ export function useSearchExecution(options: UseSearchExecutionOptions): UseSearc
         setIsSearching,
         silent,
         run: async (runVersion) => {
-          const {
-            documentVersion,
-            nextMatches,
-            nextOffset,
-            sessionId,
-            totalMatchedLines,
-            totalMatches,
-          } = await resolveSearchRunStartState({
+          const startState = await resolveSearchRunStartState({
             activeTabId,
             caseSensitive,
             effectiveResultFilterKeyword,
@@ -387,10 +385,20 @@ export function useSearchExecution(options: UseSearchExecutionOptions): UseSearc
             searchMode,
           });
 
-          if (isSearchPanelRunStale({ runVersion, runVersionRef })) {
+          // A cancelled scan was superseded or its panel closed.
+          if (!startState || isSearchPanelRunStale({ runVersion, runVersionRef })) {
             return null;
           }
 
+          const {
+            documentVersion,
+            nextMatches,
+            nextOffset,
+            sessionId,
+            totalMatchedLines,
+            totalMatches,
+          } = startState;
+
           applySearchRunResult({
             activeTabId,
             cachedSearchRef,
@@ -1017,6 +1025,25 @@ export function useSearchExecution(options: UseSearchExecutionOptions): UseSearc
         setTotalMatchCount,
         setTotalMatchedLineCount,
       });
+
+      // First visit of the tab in this run: bring back what the previous
+      // session saved for its file.
+      void loadSavedTabSessionExtras(activeTabId).then((extras) => {
+        if (!extras || restoreRunVersion !== sessionRestoreRunVersionRef.current) {
+          return;
+        }
+
+        setKeyword(extras.searchKeyword ?? '');
+        if (isSearchMode(extras.searchMode)) {
+          setSearchMode(extras.searchMode);
+        }
+        setCaseSensitive(extras.searchCaseSensitive ?? false);
+        setPanelMode(extras.resultPanelTab === 'filter' ? 'filter' : 'find');
+        if (extras.resultPanelHeight) {
+          setResultPanelHeight(extras.resultPanelHeight);
+        }
+        setIsOpen(extras.resultPanelOpen ?? false);
+      });
     }
 
     finalizeSearchPanelRestoreCycle({
@@ -1066,6 +1093,14 @@ export function useSearchExecution(options: UseSearchExecutionOptions): UseSearc
       filterDocumentVersion: cachedFilterRef.current?.documentVersion ?? null,
       filterRulesKey: cachedFilterRef.current?.rulesKey ?? filterRulesKey,
     };
+    updateTabSessionExtras(activeTabId, {
+      searchKeyword: keyword || null,
+      searchMode,
+      searchCaseSensitive: caseSensitive,
+      resultPanelOpen: resultPanelState !== 'closed',
+      resultPanelTab: panelMode === 'filter' ? 'filter' : 'search',
+      resultPanelHeight,
+    });
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [
     activeTabId,
@@ -28,6 +28,7 @@ import type {
   SearchOpenEventDetail,
   SearchResultPanelState,
 } from './types';
+import { useSearchProgress } from './useSearchProgress';
 import { dispatchSearchClose, getReservedLayoutHeight, getSearchStatusText } from './utils';
 
 type SearchInputContextMenuProps = ComponentProps<typeof SearchInputContextMenu>;
@@ -484,6 +485,7 @@ export function useSearchPanelChrome({
   const matchCount = matches.length;
   const filterMatchCount = filterMatches.length;
   const hasConfiguredFilterRules = effectiveFilterRulesLength > 0;
+  const searchProgress = useSearchProgress({ isOpen, isSearching });
 
   const statusText = useMemo(
     () =>
@@ -498,6 +500,7 @@ export function useSearchPanelChrome({
         keyword,
         matchCount,
         messages,
+        searchProgress,
         totalFilterMatchedLineCount: displayTotalFilterMatchedLineCount,
         totalMatchCount: displayTotalMatchCount,
       }),
@@ -514,6 +517,7 @@ export function useSearchPanelChrome({
       keyword,
       matchCount,
       messages,
+      searchProgress,
     ]
   );
 
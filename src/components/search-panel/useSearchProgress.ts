@@ -0,0 +1,73 @@
+import { listen } from '@tauri-apps/api/event';
+import { useEffect, useState } from 'react';
+import { cancelPendingSearchSession, getPendingSearchSessionId } from './resolveSearchPanelRunStartState';
+import type { SearchProgress } from './types';
+
+interface SearchProgressEventPayload extends SearchProgress {
+  sessionId: string;
+  documentId: string;
+}
+
+interface UseSearchProgressOptions {
+  isOpen: boolean;
+  isSearching: boolean;
+}
+
+// Tracks the rutar://search-progress events of the search scan that is still
+// running, and stops that scan when the panel closes.
+export function useSearchProgress({ isOpen, isSearching }: UseSearchProgressOptions) {
+  const [searchProgress, setSearchProgress] = useState<SearchProgress | null>(null);
+
+  useEffect(() => {
+    if (!isSearching) {
+      setSearchProgress(null);
+    }
+  }, [isSearching]);
+
+  useEffect(() => {
+    if (!isOpen) {
+      cancelPendingSearchSession();
+    }
+  }, [isOpen]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+    const setupSearchProgressListener = async () => {
+      try {
+        const unsubscribe = await listen<SearchProgressEventPayload>('rutar://search-progress', (event) => {
+          const payload = event.payload;
+          if (!payload || payload.sessionId !== getPendingSearchSessionId()) {
+            return;
+          }
+
+          setSearchProgress({
+            bytesScanned: payload.bytesScanned,
+            totalBytes: payload.totalBytes,
+            matchesSoFar: payload.matchesSoFar,
+          });
+        });
+
+        if (disposed) {
+          unsubscribe();
+          return;
+        }
+
+        unlisten = unsubscribe;
+      } catch (error) {
+        console.error('Failed to listen search-progress event:', error);
+      }
+    };
+
+    void setupSearchProgressListener();
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  return searchProgress;
+}
@@ -1,5 +1,5 @@
 import { describe, expect, it } from 'vitest';
-import { getReservedLayoutHeight } from './utils';
+import { getReservedLayoutHeight, normalizeFilterRuleGroups } from './utils';
 
 function mockHeight(element: HTMLElement, height: number) {
   Object.defineProperty(element, 'getBoundingClientRect', {
@@ -36,4 +36,25 @@ describe('search-panel utils', () => {
     toolbar.remove();
     markdownToolbar.remove();
   });
+
+  it('keeps the shared source of read-only filter rule groups', () => {
+    const rule = {
+      keyword: ' ERROR ',
+      matchMode: 'contains' as const,
+      backgroundColor: '#f00',
+      textColor: '',
+      bold: true,
+      italic: false,
+      applyTo: 'line' as const,
+    };
+
+    const groups = normalizeFilterRuleGroups([
+      { name: ' team/Errors ', rules: [rule], sharedSource: 'team.json' },
+      { name: 'Mine', rules: [rule] },
+    ]);
+
+    expect(groups[0].name).toBe('team/Errors');
+    expect(groups[0].sharedSource).toBe('team.json');
+    expect(groups[1]).not.toHaveProperty('sharedSource');
+  });
 });
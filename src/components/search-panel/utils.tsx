@@ -9,6 +9,7 @@ import type {
   FilterRuleMatchMode,
   SearchMatch,
   SearchMode,
+  SearchProgress,
   SearchSidebarTextInputElement,
 } from './types';
 
@@ -137,6 +138,7 @@ export function normalizeFilterRuleInputPayload(rule: FilterRuleInputPayload): F
     bold: !!rule.bold,
     italic: !!rule.italic,
     applyTo,
+    ...(rule.alert ? { alert: rule.alert } : {}),
   };
 }
 
@@ -145,6 +147,7 @@ export function normalizeFilterRuleGroups(groups: FilterRuleGroupPayload[]): Fil
     .map((group) => ({
       name: group.name.trim(),
       rules: (group.rules || []).map(normalizeFilterRuleInputPayload).filter((rule): rule is FilterRuleInputPayload => !!rule),
+      ...(group.sharedSource ? { sharedSource: group.sharedSource } : {}),
     }))
     .filter((group) => group.name.length > 0 && group.rules.length > 0);
 }
@@ -167,6 +170,7 @@ export function buildFilterRulesFromPayload(rules: FilterRuleInputPayload[]): Fi
     bold: rule.bold,
     italic: rule.italic,
     applyTo: rule.applyTo,
+    ...(rule.alert ? { alert: rule.alert } : {}),
   }));
 }
 
@@ -199,6 +203,7 @@ export function normalizeFilterRules(rules: FilterRule[]) {
       bold: rule.bold,
       italic: rule.italic,
       applyTo: rule.applyTo,
+      ...(rule.alert ? { alert: rule.alert } : {}),
     }))
     .filter((rule) => rule.keyword.length > 0);
 }
@@ -212,6 +217,7 @@ export function buildFilterRulesPayload(rules: FilterRule[]): FilterRuleInputPay
     bold: rule.bold,
     italic: rule.italic,
     applyTo: rule.applyTo,
+    ...(rule.alert ? { alert: rule.alert } : {}),
   }));
 }
 
@@ -284,6 +290,7 @@ interface SearchStatusTextArgs {
   keyword: string;
   matchCount: number;
   messages: ReturnType<typeof getSearchPanelMessages>;
+  searchProgress: SearchProgress | null;
   totalFilterMatchedLineCount: number | null;
   totalMatchCount: number | null;
 }
@@ -333,6 +340,7 @@ export function getSearchStatusText({
   keyword,
   matchCount,
   messages,
+  searchProgress,
   totalFilterMatchedLineCount,
   totalMatchCount,
 }: SearchStatusTextArgs) {
@@ -372,6 +380,11 @@ export function getSearchStatusText({
   }
 
   if (isSearching) {
+    if (searchProgress && searchProgress.totalBytes > 0) {
+      const percent = Math.floor((searchProgress.bytesScanned / searchProgress.totalBytes) * 100);
+      return messages.statusSearchingProgress(percent, searchProgress.matchesSoFar);
+    }
+
     return messages.statusSearching;
   }
 
@@ -50,6 +50,9 @@ export function useEditorContextMenuConfig({
   const bookmarkMenuLabel = tr('bookmark.menu.title');
   const addBookmarkLabel = tr('bookmark.add');
   const removeBookmarkLabel = tr('bookmark.remove');
+  const previousPatchHunkLabel = tr('editor.context.patch.previousHunk');
+  const nextPatchHunkLabel = tr('editor.context.patch.nextHunk');
+  const openPatchHunkSourceLabel = tr('editor.context.patch.openSource');
 
   const submenuHorizontalPositionClassName =
     submenuDirection === 'left' ? 'right-full mr-1 before:-right-2' : 'left-full ml-1 before:-left-2';
@@ -150,6 +153,9 @@ export function useEditorContextMenuConfig({
     bookmarkMenuLabel,
     addBookmarkLabel,
     removeBookmarkLabel,
+    previousPatchHunkLabel,
+    nextPatchHunkLabel,
+    openPatchHunkSourceLabel,
     editSubmenuPositionClassName,
     sortSubmenuPositionClassName,
     convertSubmenuPositionClassName,
@@ -0,0 +1,94 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import { dispatchDocumentUpdated } from '@/lib/documentEvents';
+import { type FileTab, useStore } from '@/store/useStore';
+
+interface StreamAppendedPayload {
+  id: string;
+  lineCount: number;
+  documentVersion: number;
+  follow: boolean;
+}
+
+interface StreamEndedPayload {
+  id: string;
+}
+
+// Opens the stdin tab when Rutar was started as `somecommand | rutar -` and
+// keeps tabs fed by stdin or a named pipe in step with the backend: each
+// appended chunk refreshes the tab, and in follow mode the active editor
+// jumps to the last line.
+export function useLiveStreams() {
+  useEffect(() => {
+    let cancelled = false;
+    const openStdinStream = async () => {
+      try {
+        const fileInfo = await invoke<FileTab | null>('open_stdin_stream');
+        if (!fileInfo || cancelled) {
+          return;
+        }
+
+        useStore.getState().addTab(fileInfo);
+      } catch (error) {
+        console.error('Failed to open stdin stream:', error);
+      }
+    };
+
+    void openStdinStream();
+
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlisteners: Array<() => void> = [];
+    let disposed = false;
+    const setupStreamListeners = async () => {
+      try {
+        const unsubscribers = await Promise.all([
+          listen<StreamAppendedPayload>('rutar://stream-appended', (event) => {
+            const payload = event.payload;
+            const state = useStore.getState();
+            if (!payload || !state.tabs.some((tab) => tab.id === payload.id)) {
+              return;
+            }
+
+            state.updateTab(payload.id, { lineCount: payload.lineCount });
+            dispatchDocumentUpdated(payload.id);
+            if (payload.follow && state.activeTabId === payload.id) {
+              window.dispatchEvent(
+                new CustomEvent('rutar:navigate-to-line', {
+                  detail: { tabId: payload.id, line: payload.lineCount, column: 1 },
+                })
+              );
+            }
+          }),
+          listen<StreamEndedPayload>('rutar://stream-ended', (event) => {
+            const id = event.payload?.id;
+            if (id && useStore.getState().tabs.some((tab) => tab.id === id)) {
+              dispatchDocumentUpdated(id);
+            }
+          }),
+        ]);
+
+        if (disposed) {
+          unsubscribers.forEach((unsubscribe) => unsubscribe());
+          return;
+        }
+
+        unlisteners.push(...unsubscribers);
+      } catch (error) {
+        console.error('Failed to listen stream events:', error);
+      }
+    };
+
+    void setupStreamListeners();
+
+    return () => {
+      disposed = true;
+      unlisteners.forEach((unlisten) => unlisten());
+    };
+  }, []);
+}
@@ -0,0 +1,34 @@
+import { useEffect } from 'react';
+import { saveSession } from '@/lib/session';
+import { useStore } from '@/store/useStore';
+
+const SESSION_SAVE_DELAY_MS = 1000;
+
+// Saves the session shortly after the tabs, the active tab or a caret change,
+// so the backend has the latest state when the window closes.
+export function useSessionPersistence() {
+  useEffect(() => {
+    let timer: number | undefined;
+    const unsubscribe = useStore.subscribe((state, previous) => {
+      if (
+        state.tabs === previous.tabs
+        && state.activeTabId === previous.activeTabId
+        && state.cursorPositionByTab === previous.cursorPositionByTab
+      ) {
+        return;
+      }
+
+      window.clearTimeout(timer);
+      timer = window.setTimeout(() => {
+        void saveSession().catch((error) => {
+          console.error('Failed to save session:', error);
+        });
+      }, SESSION_SAVE_DELAY_MS);
+    });
+
+    return () => {
+      window.clearTimeout(timer);
+      unsubscribe();
+    };
+  }, []);
+}
@@ -4,6 +4,8 @@ export type I18nKey =
   | 'app.readyOpenHint'
   | 'app.externalFileChanged.prompt'
   | 'app.externalFileChanged.unsavedWarning'
+  | 'app.filterRuleAlert.title'
+  | 'app.filterRuleAlert.moreHits'
   | 'titleBar.settings'
   | 'titleBar.closeOtherTabs'
   | 'titleBar.closeAllTabs'
@@ -125,6 +127,7 @@ export type I18nKey =
   | 'status.indentation.tabs'
   | 'status.indentation.spaces'
   | 'status.largeFileHighlightOff'
+  | 'status.suggestedSyntax'
   | 'sidebar.empty'
   | 'sidebar.close'
   | 'sidebar.context.search'
@@ -170,6 +173,8 @@ export type I18nKey =
   | 'settings.singleInstanceModeRestartToast'
   | 'settings.rememberWindowState'
   | 'settings.rememberWindowStateDesc'
+  | 'settings.autoReloadUnmodifiedFiles'
+  | 'settings.autoReloadUnmodifiedFilesDesc'
   | 'settings.about'
   | 'settings.aboutDesc'
   | 'settings.aboutPanelDesc'
@@ -231,6 +236,8 @@ export type I18nKey =
   | 'settings.typography'
   | 'settings.tabWidth'
   | 'settings.tabWidthDesc'
+  | 'settings.foldMinBlockLines'
+  | 'settings.foldMinBlockLinesDesc'
   | 'settings.tabIndentMode'
   | 'settings.tabIndentModeDesc'
   | 'settings.tabIndentMode.tabs'
@@ -280,6 +287,9 @@ export type I18nKey =
   | 'editor.context.convert.copyBase64EncodeResult'
   | 'editor.context.convert.copyBase64DecodeResult'
   | 'editor.context.convert.base64DecodeFailed'
+  | 'editor.context.patch.previousHunk'
+  | 'editor.context.patch.nextHunk'
+  | 'editor.context.patch.openSource'
   | 'editor.context.sort.ascending'
   | 'editor.context.sort.ascendingIgnoreCase'
   | 'editor.context.sort.descending'
@@ -317,7 +327,11 @@ export type I18nKey =
   | 'diffEditor.nextDiffLine'
   | 'diffEditor.noDiffLine'
   | 'diffEditor.noMatch'
-  | 'diffEditor.resizePanelsAriaLabel';
+  | 'diffEditor.resizePanelsAriaLabel'
+  | 'streamingView.readOnly'
+  | 'streamingView.findPlaceholder'
+  | 'streamingView.findNext'
+  | 'streamingView.noMoreMatches';
 
 type Messages = Record<I18nKey, string>;
 
@@ -325,6 +339,8 @@ const zhCN: Messages = {
   'app.readyOpenHint': '就绪：打开文件或文件夹',
   'app.externalFileChanged.prompt': '文件“{fileName}”在外部发生了变化。是否重新加载？',
   'app.externalFileChanged.unsavedWarning': '注意：重新加载会丢失当前未保存修改。',
+  'app.filterRuleAlert.title': '过滤规则“{keyword}”在 {fileName} 中命中',
+  'app.filterRuleAlert.moreHits': '另有 {count} 处命中',
   'titleBar.settings': '设置',
   'titleBar.closeOtherTabs': '关闭其他标签页',
   'titleBar.closeAllTabs': '关闭所有标签页',
@@ -362,7 +378,7 @@ const zhCN: Messages = {
   'toolbar.filter': '过滤',
   'toolbar.format.beautify': '格式化文档 (Ctrl+Alt+F)',
   'toolbar.format.minify': '最小化文档 (Ctrl+Alt+M)',
-  'toolbar.format.unsupported': '仅支持 JSON / YAML / XML / HTML / TOML 文件格式化。',
+  'toolbar.format.unsupported': '仅支持 JSON / YAML / XML / HTML / TOML / INI 文件格式化。',
   'toolbar.format.failed': '格式化失败：',
   'toolbar.recent.noFiles': '暂无最近文件',
   'toolbar.recent.noFolders': '暂无最近文件夹',
@@ -446,6 +462,7 @@ const zhCN: Messages = {
   'status.indentation.tabs': '制表符',
   'status.indentation.spaces': '空格',
   'status.largeFileHighlightOff': '高亮已关闭（大文件模式）',
+  'status.suggestedSyntax': '建议语法：{syntax}',
   'sidebar.empty': '空文件夹',
   'sidebar.close': '关闭侧边栏',
   'sidebar.context.search': '搜索',
@@ -491,6 +508,8 @@ const zhCN: Messages = {
   'settings.singleInstanceModeRestartToast': '单实例模式设置已变更，重启 Rutar 后生效。',
   'settings.rememberWindowState': '记住窗口状态',
   'settings.rememberWindowStateDesc': '保存并恢复窗口大小与最大化状态。最大化时仅记录最大化状态，不记录宽高。',
+  'settings.autoReloadUnmodifiedFiles': '自动重新加载未修改的文件',
+  'settings.autoReloadUnmodifiedFilesDesc': '文件在外部被修改时，若标签页没有未保存的更改，则直接重新加载而不询问。可在标签页右键菜单中单独开关。',
   'settings.about': '关于',
   'settings.aboutDesc': '项目信息与开源地址',
   'settings.aboutPanelDesc': '查看 Rutar 项目信息与源码地址。',
@@ -552,6 +571,8 @@ const zhCN: Messages = {
   'settings.typography': '排版',
   'settings.tabWidth': '制表符宽度',
   'settings.tabWidthDesc': '用于工具栏格式化按钮的缩进宽度。',
+  'settings.foldMinBlockLines': '最小折叠行数',
+  'settings.foldMinBlockLinesDesc': '少于该行数（含首行）的代码块不显示折叠控件。',
   'settings.tabIndentMode': 'Tab 缩进方式',
   'settings.tabIndentModeDesc': '按下 Tab 键时，插入制表符或空格。',
   'settings.tabIndentMode.tabs': '制表符',
@@ -601,6 +622,9 @@ const zhCN: Messages = {
   'editor.context.convert.copyBase64EncodeResult': '复制 Base64 编码结果',
   'editor.context.convert.copyBase64DecodeResult': '复制 Base64 解码结果',
   'editor.context.convert.base64DecodeFailed': 'Base64 解码失败：所选内容不是有效的 Base64 文本',
+  'editor.context.patch.previousHunk': '上一个差异块',
+  'editor.context.patch.nextHunk': '下一个差异块',
+  'editor.context.patch.openSource': '打开差异块对应的文件',
   'editor.context.sort.ascending': '升序排列行',
   'editor.context.sort.ascendingIgnoreCase': '升序排列行（忽略大小写）',
   'editor.context.sort.descending': '降序排列行',
@@ -639,12 +663,18 @@ const zhCN: Messages = {
   'diffEditor.noDiffLine': '未找到不同行',
   'diffEditor.noMatch': '未找到匹配',
   'diffEditor.resizePanelsAriaLabel': '调整差异面板宽度',
+  'streamingView.readOnly': '大文件只读视图',
+  'streamingView.findPlaceholder': '查找...',
+  'streamingView.findNext': '查找下一个',
+  'streamingView.noMoreMatches': '没有更多匹配项',
 };
 
 const enUS: Messages = {
   'app.readyOpenHint': 'READY: Open a file or folder',
   'app.externalFileChanged.prompt': 'File "{fileName}" has changed outside the editor. Reload now?',
   'app.externalFileChanged.unsavedWarning': 'Warning: Reloading will discard unsaved changes in this tab.',
+  'app.filterRuleAlert.title': 'Filter rule "{keyword}" matched in {fileName}',
+  'app.filterRuleAlert.moreHits': '{count} more hits',
   'titleBar.settings': 'Settings',
   'titleBar.closeOtherTabs': 'Close Other Tabs',
   'titleBar.closeAllTabs': 'Close All Tabs',
@@ -682,7 +712,7 @@ const enUS: Messages = {
   'toolbar.filter': 'Filter',
   'toolbar.format.beautify': 'Beautify (Ctrl+Alt+F)',
   'toolbar.format.minify': 'Minify (Ctrl+Alt+M)',
-  'toolbar.format.unsupported': 'Only JSON, YAML, XML, HTML, and TOML are supported.',
+  'toolbar.format.unsupported': 'Only JSON, YAML, XML, HTML, TOML, and INI are supported.',
   'toolbar.format.failed': 'Format failed:',
   'toolbar.recent.noFiles': 'No recent files',
   'toolbar.recent.noFolders': 'No recent folders',
@@ -766,6 +796,7 @@ const enUS: Messages = {
   'status.indentation.tabs': 'Tabs',
   'status.indentation.spaces': 'Spaces',
   'status.largeFileHighlightOff': 'Highlight Off (Large File)',
+  'status.suggestedSyntax': 'Suggested: {syntax}',
   'sidebar.empty': 'Empty',
   'sidebar.close': 'Close Sidebar',
   'sidebar.context.search': 'Search',
@@ -811,6 +842,8 @@ const enUS: Messages = {
   'settings.singleInstanceModeRestartToast': 'Single instance setting changed. Restart Rutar to apply it.',
   'settings.rememberWindowState': 'Remember Window State',
   'settings.rememberWindowStateDesc': 'Persist and restore window size and maximized state. When maximized, only maximized state is stored.',
+  'settings.autoReloadUnmodifiedFiles': 'Auto-reload Unmodified Files',
+  'settings.autoReloadUnmodifiedFilesDesc': 'Reload files changed on disk without asking when the tab has no unsaved changes. Can be toggled per tab from the tab context menu.',
   'settings.about': 'About',
   'settings.aboutDesc': 'Project info and repository',
   'settings.aboutPanelDesc': 'View project information and source repository for Rutar.',
@@ -872,6 +905,8 @@ const enUS: Messages = {
   'settings.typography': 'Typography',
   'settings.tabWidth': 'Tab Width',
   'settings.tabWidthDesc': 'Indent width used by toolbar beautify action.',
+  'settings.foldMinBlockLines': 'Minimum Fold Size',
+  'settings.foldMinBlockLinesDesc': 'Blocks shorter than this many lines, including the first line, get no fold control.',
   'settings.tabIndentMode': 'Tab Indentation',
   'settings.tabIndentModeDesc': 'Choose whether Tab inserts a tab character or spaces.',
   'settings.tabIndentMode.tabs': 'Tabs',
@@ -921,6 +956,9 @@ const enUS: Messages = {
   'editor.context.convert.copyBase64EncodeResult': 'Copy Base64 Encode Result',
   'editor.context.convert.copyBase64DecodeResult': 'Copy Base64 Decode Result',
   'editor.context.convert.base64DecodeFailed': 'Base64 decode failed: selected text is not valid Base64',
+  'editor.context.patch.previousHunk': 'Previous Hunk',
+  'editor.context.patch.nextHunk': 'Next Hunk',
+  'editor.context.patch.openSource': 'Open File at Hunk',
   'editor.context.sort.ascending': 'Sort Lines Ascending',
   'editor.context.sort.ascendingIgnoreCase': 'Sort Lines Ascending (Ignore Case)',
   'editor.context.sort.descending': 'Sort Lines Descending',
@@ -959,6 +997,10 @@ const enUS: Messages = {
   'diffEditor.noDiffLine': 'No diff lines',
   'diffEditor.noMatch': 'No matches',
   'diffEditor.resizePanelsAriaLabel': 'Resize diff panels',
+  'streamingView.readOnly': 'Large file, read-only',
+  'streamingView.findPlaceholder': 'Find...',
+  'streamingView.findNext': 'Find Next',
+  'streamingView.noMoreMatches': 'No more matches',
 };
 
 const dictionaries: Record<AppLanguage, Messages> = {
@@ -985,6 +1027,7 @@ export function getSearchPanelMessages(language: AppLanguage) {
       replacedAll: (count: number) => `Replaced all ${count} matches`,
       statusEnterToSearch: 'Enter keyword and press Enter to search',
       statusSearching: 'Searching...',
+      statusSearchingProgress: (percent: number, matches: number) => `Searching... ${percent}% · ${matches} matches so far`,
       statusNoMatches: 'No matches found',
       statusTotalPending: (current: number) => `Total matches counting… · Current ${current}/?`,
       statusTotalReady: (total: number, current: number) => `Total ${total} matches · Current ${current}/${Math.max(total, 1)}`,
@@ -1158,6 +1201,7 @@ export function getSearchPanelMessages(language: AppLanguage) {
     replacedAll: (count: number) => `已全部替换 ${count} 处`,
     statusEnterToSearch: '输入关键词后按 Enter 开始搜索',
     statusSearching: '正在搜索...',
+    statusSearchingProgress: (percent: number, matches: number) => `正在搜索... ${percent}% · 已找到 ${matches} 项`,
     statusNoMatches: '未找到匹配项',
     statusTotalPending: (current: number) => `匹配总计 统计中… · 当前 ${current}/?`,
     statusTotalReady: (total: number, current: number) => `匹配总计 ${total} 项 · 当前 ${current}/${Math.max(total, 1)}`,
@@ -0,0 +1,25 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { AppLanguage } from '@/store/useStore';
+
+interface LocaleStringsPayload {
+  language: string;
+  strings: Record<string, string>;
+  errors: Record<string, string>;
+}
+
+// Commands fail with English messages; the backend locale table maps each
+// known message to the selected language.
+let localizedErrors: Record<string, string> = {};
+
+export async function loadBackendErrorMessages(language: AppLanguage) {
+  try {
+    const payload = await invoke<LocaleStringsPayload>('get_locale_strings', { language });
+    localizedErrors = payload.errors ?? {};
+  } catch (error) {
+    console.error('Failed to load backend error messages:', error);
+  }
+}
+
+export function localizeBackendError(message: string) {
+  return localizedErrors[message] ?? message;
+}
@@ -0,0 +1,76 @@
+export interface FilterRuleAlertPayload {
+  documentId: string;
+  ruleIndex: number;
+  keyword: string;
+  line: number;
+  lineText: string;
+  hitCount: number;
+  notification: boolean;
+  sound: boolean;
+}
+
+const MAX_ALERT_BODY_LENGTH = 200;
+
+export function isFilterRuleAlertPayload(value: unknown): value is FilterRuleAlertPayload {
+  if (!value || typeof value !== 'object') {
+    return false;
+  }
+
+  const payload = value as Partial<FilterRuleAlertPayload>;
+  return (
+    typeof payload.documentId === 'string'
+    && typeof payload.keyword === 'string'
+    && typeof payload.line === 'number'
+    && typeof payload.lineText === 'string'
+    && typeof payload.hitCount === 'number'
+  );
+}
+
+export function buildFilterRuleAlertBody(payload: FilterRuleAlertPayload, moreHitsTemplate: string) {
+  const lineText = payload.lineText.trim();
+  const clippedLineText = lineText.length > MAX_ALERT_BODY_LENGTH
+    ? `${lineText.slice(0, MAX_ALERT_BODY_LENGTH)}…`
+    : lineText;
+  const body = `${payload.line}: ${clippedLineText}`;
+  if (payload.hitCount <= 1) {
+    return body;
+  }
+
+  return `${body}\n${moreHitsTemplate.replace('{count}', String(payload.hitCount - 1))}`;
+}
+
+export async function showFilterRuleAlertNotification(title: string, body: string) {
+  if (typeof Notification === 'undefined') {
+    return;
+  }
+
+  let permission = Notification.permission;
+  if (permission === 'default') {
+    permission = await Notification.requestPermission();
+  }
+
+  if (permission === 'granted') {
+    new Notification(title, { body });
+  }
+}
+
+export function playFilterRuleAlertSound() {
+  if (typeof AudioContext === 'undefined') {
+    return;
+  }
+
+  const context = new AudioContext();
+  const oscillator = context.createOscillator();
+  const gain = context.createGain();
+  oscillator.type = 'sine';
+  oscillator.frequency.value = 880;
+  gain.gain.setValueAtTime(0.2, context.currentTime);
+  gain.gain.exponentialRampToValueAtTime(0.001, context.currentTime + 0.3);
+  oscillator.connect(gain);
+  gain.connect(context.destination);
+  oscillator.onended = () => {
+    void context.close();
+  };
+  oscillator.start();
+  oscillator.stop(context.currentTime + 0.3);
+}
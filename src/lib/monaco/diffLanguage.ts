@@ -0,0 +1,41 @@
+import type * as Monaco from 'monaco-editor';
+
+export const DIFF_MONACO_LANGUAGE_ID = 'diff';
+
+let diffLanguageRegistered = false;
+
+// Line-based tokens for unified diffs; colors live in `theme.ts`.
+const diffMonarchLanguage: Monaco.languages.IMonarchLanguage = {
+  defaultToken: '',
+  tokenPostfix: '.diff',
+  tokenizer: {
+    root: [
+      [/^(diff|index|new file mode|deleted file mode|similarity index|rename from|rename to) .*$/, 'meta.header'],
+      [/^(---|\+\+\+) .*$/, 'meta.file'],
+      [/^(@@ [^@]* @@)(.*)$/, ['meta.hunk', 'meta.section']],
+      [/^\+.*$/, 'inserted'],
+      [/^-.*$/, 'deleted'],
+      [/^\\.*$/, 'comment'],
+      [/.+$/, ''],
+    ],
+  },
+};
+
+export function registerDiffMonacoLanguage(monaco: typeof Monaco) {
+  if (diffLanguageRegistered) {
+    return;
+  }
+
+  const languagesApi = monaco.languages as Partial<typeof Monaco.languages> | undefined;
+  if (typeof languagesApi?.register !== 'function' || typeof languagesApi.setMonarchTokensProvider !== 'function') {
+    return;
+  }
+
+  languagesApi.register({
+    id: DIFF_MONACO_LANGUAGE_ID,
+    extensions: ['.diff', '.patch'],
+    aliases: ['Diff', 'Patch'],
+  });
+  languagesApi.setMonarchTokensProvider(DIFF_MONACO_LANGUAGE_ID, diffMonarchLanguage);
+  diffLanguageRegistered = true;
+}
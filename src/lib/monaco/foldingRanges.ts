@@ -0,0 +1,82 @@
+import { invoke } from '@tauri-apps/api/core';
+import type * as Monaco from 'monaco-editor';
+
+interface FoldRangePayload {
+  startLine: number;
+  endLine: number;
+}
+
+interface FoldRangesPayload {
+  ranges: FoldRangePayload[];
+  fromSyntaxTree: boolean;
+  documentVersion: number;
+}
+
+export interface BackendFoldingRangeOptions {
+  resolveDocumentId: (model: Monaco.editor.ITextModel) => string | null;
+  waitForSync: () => Promise<void>;
+  getMinBlockLines: () => number;
+}
+
+export interface BackendFoldingRangeProvider {
+  refresh: () => void;
+  dispose: () => void;
+}
+
+// Folding comes from `get_fold_ranges` so tree-sitter grammars and the
+// indentation fallback for logs and plain text fold the same way everywhere.
+export function registerBackendFoldingRangeProvider(
+  monaco: typeof Monaco,
+  options: BackendFoldingRangeOptions
+): BackendFoldingRangeProvider | null {
+  const languagesApi = monaco.languages as Partial<typeof Monaco.languages> | undefined;
+  if (typeof languagesApi?.registerFoldingRangeProvider !== 'function' || typeof monaco.Emitter !== 'function') {
+    return null;
+  }
+
+  const changeEmitter = new monaco.Emitter<Monaco.languages.FoldingRangeProvider>();
+  const provider: Monaco.languages.FoldingRangeProvider = {
+    onDidChange: changeEmitter.event,
+    async provideFoldingRanges(model) {
+      const id = options.resolveDocumentId(model);
+      if (!id) {
+        return null;
+      }
+
+      const versionId = model.getVersionId();
+      // Edits reach the backend through the sync chain; asking earlier would
+      // fold the previous text.
+      await options.waitForSync();
+      if (model.isDisposed() || model.getVersionId() !== versionId) {
+        return null;
+      }
+
+      try {
+        const payload = await invoke<FoldRangesPayload>('get_fold_ranges', {
+          id,
+          minBlockLines: options.getMinBlockLines(),
+        });
+        if (model.isDisposed() || model.getVersionId() !== versionId) {
+          return null;
+        }
+
+        const lineCount = model.getLineCount();
+        return payload.ranges
+          .filter((range) => range.startLine < range.endLine && range.endLine <= lineCount)
+          .map((range) => ({ start: range.startLine, end: range.endLine }));
+      } catch (error) {
+        console.error('Failed to get fold ranges:', error);
+        return null;
+      }
+    },
+  };
+
+  const registration = languagesApi.registerFoldingRangeProvider('*', provider);
+  return {
+    refresh: () => changeEmitter.fire(provider),
+    dispose: () => {
+      registration.dispose();
+      changeEmitter.dispose();
+    },
+  };
+}
@@ -1,5 +1,6 @@
 import { type FileTab } from '@/store/useStore';
 import { detectSyntaxKeyFromTab } from '@/lib/syntax';
+import { DIFF_MONACO_LANGUAGE_ID } from './diffLanguage';
 
 export function resolveMonacoLanguage(fileTab: FileTab | null | undefined): string {
   if (!fileTab) {
@@ -71,6 +72,8 @@ export function resolveMonacoLanguage(fileTab: FileTab | null | undefined): stri
       return 'sql';
     case 'swift':
       return 'swift';
+    case 'diff':
+      return DIFF_MONACO_LANGUAGE_ID;
     default:
       return 'plaintext';
   }
@@ -10,6 +10,8 @@
     jsonWorkerCtor: createWorker('json'),
     tsWorkerCtor: createWorker('ts'),
     defineTheme: vi.fn(),
+    registerLanguage: vi.fn(),
+    setMonarchTokensProvider: vi.fn(),
     tsDefaults: {
       setEagerModelSync: vi.fn(),
       setDiagnosticsOptions: vi.fn(),
@@ -47,6 +49,8 @@ vi.mock('monaco-editor', () => ({
     defineTheme: setupMonacoMockState.defineTheme,
   },
   languages: {
+    register: setupMonacoMockState.registerLanguage,
+    setMonarchTokensProvider: setupMonacoMockState.setMonarchTokensProvider,
     typescript: {
       typescriptDefaults: setupMonacoMockState.tsDefaults,
       javascriptDefaults: setupMonacoMockState.jsDefaults,
@@ -127,6 +131,13 @@ describe('setupMonacoEnvironment', () => {
         trailingCommas: 'ignore',
       })
     );
+    expect(setupMonacoMockState.registerLanguage).toHaveBeenCalledWith(
+      expect.objectContaining({ id: 'diff' })
+    );
+    expect(setupMonacoMockState.setMonarchTokensProvider).toHaveBeenCalledWith(
+      'diff',
+      expect.objectContaining({ tokenPostfix: '.diff' })
+    );
     expect(setupMonacoMockState.defineTheme).toHaveBeenCalledTimes(2);
     expect(setupMonacoMockState.defineTheme).toHaveBeenNthCalledWith(
       1,
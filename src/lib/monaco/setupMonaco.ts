@@ -4,6 +4,7 @@ import cssWorker from 'monaco-editor/esm/vs/language/css/css.worker?worker';
 import htmlWorker from 'monaco-editor/esm/vs/language/html/html.worker?worker';
 import jsonWorker from 'monaco-editor/esm/vs/language/json/json.worker?worker';
 import tsWorker from 'monaco-editor/esm/vs/language/typescript/ts.worker?worker';
+import { registerDiffMonacoLanguage } from './diffLanguage';
 import { defineRutarMonacoThemes } from './theme';
 
 declare global {
@@ -142,5 +143,6 @@ export function setupMonacoEnvironment() {
 
   // Use Monaco's built-in language services and diagnostics without external LSP.
   configureMonacoBuiltinLanguageServices();
+  registerDiffMonacoLanguage(monaco);
   defineRutarMonacoThemes(monaco);
 }
@@ -31,6 +31,14 @@ export function defineRutarMonacoThemes(monaco: typeof Monaco) {
     { token: 'tag.yaml', foreground: '0550AE' },
     { token: 'namespace.yaml', foreground: '0E7490' },
   ];
+  const diffTokenRules: Monaco.editor.ITokenThemeRule[] = [
+    { token: 'inserted.diff', foreground: '1A7F37' },
+    { token: 'deleted.diff', foreground: 'CF222E' },
+    { token: 'meta.hunk.diff', foreground: '8250DF', fontStyle: 'bold' },
+    { token: 'meta.section.diff', foreground: '6E7781' },
+    { token: 'meta.file.diff', foreground: '0550AE', fontStyle: 'bold' },
+    { token: 'meta.header.diff', foreground: '6E7781', fontStyle: 'bold' },
+  ];
 
   monaco.editor.defineTheme(RUTAR_MONACO_LIGHT_THEME, {
     base: 'vs',
@@ -38,6 +46,7 @@ export function defineRutarMonacoThemes(monaco: typeof Monaco) {
     rules: [
       { token: 'comment.yaml', foreground: '6E7781', fontStyle: 'italic' },
       ...sharedYamlTokenRules,
+      ...diffTokenRules,
     ],
     colors: {
       'editor.selectionBackground': '#2563eb66',
@@ -85,6 +94,20 @@ export function defineRutarMonacoThemes(monaco: typeof Monaco) {
             return rule;
         }
       }),
+      ...diffTokenRules.map((rule) => {
+        switch (rule.token) {
+          case 'inserted.diff':
+            return { ...rule, foreground: '7EE787' };
+          case 'deleted.diff':
+            return { ...rule, foreground: 'FF7B72' };
+          case 'meta.hunk.diff':
+            return { ...rule, foreground: 'D2A8FF' };
+          case 'meta.file.diff':
+            return { ...rule, foreground: '79C0FF' };
+          default:
+            return { ...rule, foreground: '8B949E' };
+        }
+      }),
     ],
     colors: {
       'editor.selectionBackground': '#60a5fa66',
@@ -20,6 +20,88 @@ interface OpenFileBatchResultItem {
   error?: string;
 }
 
+export interface GotoPosition {
+  line: number;
+  column: number;
+}
+
+// Paths from the command line, a second instance or the OS. The backend sends
+// objects; plain strings are still accepted.
+export type IncomingOpenTarget = string | { path: string; gotoPosition?: GotoPosition | null };
+
+export function normalizeIncomingOpenTarget(target: IncomingOpenTarget) {
+  if (typeof target === 'string') {
+    return { path: target, gotoPosition: null };
+  }
+
+  if (!target || typeof target.path !== 'string') {
+    return null;
+  }
+
+  const gotoPosition = target.gotoPosition;
+  if (!gotoPosition || !Number.isFinite(gotoPosition.line)) {
+    return { path: target.path, gotoPosition: null };
+  }
+
+  return {
+    path: target.path,
+    gotoPosition: {
+      line: Math.max(1, Math.floor(gotoPosition.line)),
+      column: Number.isFinite(gotoPosition.column) ? Math.max(1, Math.floor(gotoPosition.column)) : 1,
+    },
+  };
+}
+
+interface ReadingPositionPayload {
+  path: string;
+  line: number;
+  fileShrunk: boolean;
+}
+
+// Matches READING_POSITION_MIN_BYTES in the backend.
+const READING_POSITION_MIN_BYTES = 1024 * 1024;
+
+interface ReopenedDocumentPayload {
+  file: FileTab;
+  cursorLine?: number | null;
+  cursorColumn?: number | null;
+}
+
+// The editor of a tab opened just now may not be mounted yet, so the jump is
+// repeated once the next frame has rendered.
+export function navigateTabToPosition(tabId: string, position: GotoPosition) {
+  if (typeof window === 'undefined') {
+    return;
+  }
+
+  const emitNavigate = () => {
+    window.dispatchEvent(
+      new CustomEvent('rutar:navigate-to-line', {
+        detail: {
+          tabId,
+          line: position.line,
+          column: position.column,
+          length: 0,
+        },
+      })
+    );
+  };
+
+  emitNavigate();
+  window.requestAnimationFrame(() => {
+    emitNavigate();
+  });
+}
+
+export function navigateOpenedPathToPosition(path: string, position: GotoPosition) {
+  const tab = useStore.getState().tabs.find((item) => item.path === path);
+  if (!tab) {
+    return;
+  }
+
+  navigateTabToPosition(tab.id, position);
+}
+
 function dispatchFileOpenLoading(detail: FileOpenLoadingEventDetail) {
   if (typeof window === 'undefined') {
     return;
@@ -75,10 +157,34 @@ function patchTabWithFileInfo(tabId: string, fileInfo: FileTab) {
     largeFileMode: fileInfo.largeFileMode,
     wordWrap: useStore.getState().settings.wordWrap,
     syntaxOverride: fileInfo.syntaxOverride ?? null,
+    suggestedSyntax: fileInfo.suggestedSyntax ?? null,
     isDirty: false,
   });
 }
 
+// Scrolls a large file back to where reading stopped last time. Skipped once
+// the caret has left the first line, e.g. for a go-to position given with the
+// path, and when the file has shrunk since.
+async function restoreReadingPosition(fileInfo: FileTab) {
+  if ((fileInfo.sizeBytes ?? 0) < READING_POSITION_MIN_BYTES) {
+    return;
+  }
+
+  try {
+    const position = await invoke<ReadingPositionPayload | null>('get_reading_position', {
+      path: fileInfo.path,
+    });
+    const cursor = useStore.getState().cursorPositionByTab[fileInfo.id];
+    if (!position || position.fileShrunk || (cursor && cursor.line !== 1)) {
+      return;
+    }
+
+    navigateTabToPosition(fileInfo.id, { line: position.line, column: 1 });
+  } catch (error) {
+    console.error('Failed to restore reading position:', error);
+  }
+}
+
 async function applyOpenedFileInfo(path: string, fileInfo: FileTab) {
   const latestState = useStore.getState();
   const existedTab = latestState.tabs.find((tab) => tab.id === fileInfo.id);
@@ -96,11 +202,37 @@ async function applyOpenedFileInfo(path: string, fileInfo: FileTab) {
     latestState.setActiveTab(fileInfo.id);
     await invoke('close_file', { id: activeTab.id });
     addRecentFilePath(path);
+    void restoreReadingPosition(fileInfo);
     return;
   }
 
   latestState.addTab(fileInfo);
   addRecentFilePath(path);
+  void restoreReadingPosition(fileInfo);
+}
+
+// Brings back the most recently closed tab with its caret where it was left.
+// Resolves to false when nothing is left to reopen.
+export async function reopenLastClosedTab() {
+  const reopened = await invoke<ReopenedDocumentPayload | null>('reopen_last_closed');
+  if (!reopened) {
+    return false;
+  }
+
+  const { file } = reopened;
+  if (file.path) {
+    await applyOpenedFileInfo(file.path, file);
+  } else {
+    useStore.getState().addTab(file);
+  }
+
+  if (reopened.cursorLine) {
+    navigateTabToPosition(file.id, {
+      line: reopened.cursorLine,
+      column: reopened.cursorColumn ?? 1,
+    });
+  }
+  return true;
 }
 
 export async function openFilePath(path: string) {
@@ -21,6 +21,9 @@ const OUTLINE_TYPE_BY_SYNTAX_KEY: Record<string, Exclude<OutlineType, null>> = {
   php: 'php',
   kotlin: 'kotlin',
   swift: 'swift',
+  ruby: 'ruby',
+  lua: 'lua',
+  sql: 'sql',
 };
 
 const OUTLINE_TYPE_BY_EXTENSION: Record<string, Exclude<OutlineType, null>> = {
@@ -75,6 +78,12 @@ const OUTLINE_TYPE_BY_EXTENSION: Record<string, Exclude<OutlineType, null>> = {
   kt: 'kotlin',
   kts: 'kotlin',
   swift: 'swift',
+  rb: 'ruby',
+  rake: 'ruby',
+  gemspec: 'ruby',
+  ru: 'ruby',
+  lua: 'lua',
+  sql: 'sql',
 };
 
 export function detectOutlineType(tab: FileTab | null | undefined): OutlineType {
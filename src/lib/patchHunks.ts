@@ -0,0 +1,51 @@
+import { invoke } from '@tauri-apps/api/core';
+import { navigateOpenedPathToPosition, openFilePath } from '@/lib/openFile';
+
+export interface PatchHunk {
+  startLine: number;
+  endLine: number;
+  oldPath: string | null;
+  newPath: string | null;
+  oldStart: number;
+  oldLines: number;
+  newStart: number;
+  newLines: number;
+  section: string;
+}
+
+export interface PatchHunkSourceLocation {
+  path: string;
+  line: number;
+}
+
+// Returns false when there is no hunk in that direction.
+export async function navigateToAdjacentPatchHunk(tabId: string, line: number, forward: boolean) {
+  const hunk = await invoke<PatchHunk | null>('get_adjacent_patch_hunk', { id: tabId, line, forward });
+  if (!hunk) {
+    return false;
+  }
+
+  window.dispatchEvent(
+    new CustomEvent('rutar:navigate-to-line', {
+      detail: {
+        tabId,
+        line: hunk.startLine,
+        column: 1,
+        length: 0,
+      },
+    })
+  );
+  return true;
+}
+
+// Opens the file the hunk under `line` changes, at the matching line.
+export async function openPatchHunkSource(tabId: string, line: number) {
+  const location = await invoke<PatchHunkSourceLocation | null>('resolve_patch_hunk_source', { id: tabId, line });
+  if (!location) {
+    return false;
+  }
+
+  await openFilePath(location.path);
+  navigateOpenedPathToPosition(location.path, { line: location.line, column: 1 });
+  return true;
+}
@@ -0,0 +1,39 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// Top visible line of each tab, updated as its editor scrolls. The backend
+// keeps it per path for large files so reading resumes there next time.
+const topVisibleLineByTab = new Map<string, number>();
+
+export function rememberTopVisibleLine(tabId: string, line: number) {
+  topVisibleLineByTab.set(tabId, Math.max(1, Math.floor(line)));
+}
+
+export function getTopVisibleLine(tabId: string) {
+  return topVisibleLineByTab.get(tabId) ?? null;
+}
+
+// Returns and forgets the line; the tab is being closed.
+export function takeTopVisibleLine(tabId: string) {
+  const line = topVisibleLineByTab.get(tabId) ?? null;
+  topVisibleLineByTab.delete(tabId);
+  return line;
+}
+
+// For close paths that go through close_files, which takes ids only. Must run
+// before the documents are closed.
+export async function recordReadingPositions(tabIds: string[]) {
+  await Promise.all(
+    tabIds.map(async (id) => {
+      const line = takeTopVisibleLine(id);
+      if (line === null) {
+        return;
+      }
+
+      try {
+        await invoke<boolean>('record_reading_position', { id, line });
+      } catch (error) {
+        console.error('Failed to record reading position:', error);
+      }
+    })
+  );
+}
@@ -3,12 +3,19 @@ import {
   addRecentFilePath,
   addRecentFolderPath,
   appendRecentPath,
+  clearRecentFilePaths,
+  clearRecentFolderPaths,
   removeRecentFilePath,
   removeRecentFolderPath,
   removeRecentPath,
   sanitizeRecentPathList,
 } from "./recentPaths";
 import { useStore } from "@/store/useStore";
+import { invoke } from "@tauri-apps/api/core";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(async () => []),
+}));
 
 describe("appendRecentPath", () => {
   it("prepends, de-duplicates, and keeps max length", () => {
@@ -75,6 +82,7 @@ describe("recent path store helpers", () => {
   const initialState = useStore.getState();
 
   beforeEach(() => {
+    vi.mocked(invoke).mockClear();
     useStore.setState(initialState, true);
     useStore.getState().updateSettings({
       recentFiles: [],
@@ -90,6 +98,7 @@ describe("recent path store helpers", () => {
     const nextRef = useStore.getState().settings.recentFiles;
     expect(nextRef).toEqual(["C:\\repo\\main.ts"]);
     expect(nextRef).not.toBe(previousRef);
+    expect(invoke).toHaveBeenCalledWith("add_recent_file", { path: "  C:\\repo\\main.ts  " });
   });
 
   it("addRecentFilePath keeps reference for blank or already-first path", () => {
@@ -103,6 +112,7 @@ describe("recent path store helpers", () => {
 
     addRecentFilePath("  C:\\repo\\main.ts  ");
     expect(useStore.getState().settings.recentFiles).toBe(beforeBlank);
+    expect(invoke).not.toHaveBeenCalled();
   });
 
   it("addRecentFolderPath updates settings when folder is new", () => {
@@ -163,5 +173,23 @@ describe("recent path store helpers", () => {
     const afterHitRef = useStore.getState().settings.recentFolders;
     removeRecentFolderPath("C:\\repo\\none");
     expect(useStore.getState().settings.recentFolders).toBe(afterHitRef);
+    expect(invoke).toHaveBeenCalledTimes(1);
+    expect(invoke).toHaveBeenCalledWith("remove_recent", { kind: "folders", path: " C:\\repo\\demo " });
+  });
+
+  it("clear helpers empty the list and clear it in the backend", () => {
+    useStore.getState().updateSettings({
+      recentFiles: ["C:\\repo\\main.ts"],
+      recentFolders: ["C:\\repo"],
+    });
+
+    clearRecentFilePaths();
+    expect(useStore.getState().settings.recentFiles).toEqual([]);
+    expect(useStore.getState().settings.recentFolders).toEqual(["C:\\repo"]);
+    expect(invoke).toHaveBeenCalledWith("clear_recent", { kind: "files" });
+
+    clearRecentFolderPaths();
+    expect(useStore.getState().settings.recentFolders).toEqual([]);
+    expect(invoke).toHaveBeenCalledWith("clear_recent", { kind: "folders" });
   });
 });
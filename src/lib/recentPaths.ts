@@ -1,7 +1,18 @@
+import { invoke } from '@tauri-apps/api/core';
 import { useStore } from '@/store/useStore';
 
 export const MAX_RECENT_PATHS = 12;
 
+type RecentPathKind = 'files' | 'folders';
+
+// The store is updated right away; the backend owns the persisted lists, so
+// only the changed entry is sent instead of the whole config.
+function syncRecentPathCommand(command: string, args: Record<string, unknown>) {
+  void invoke(command, args).catch((error) => {
+    console.warn('Failed to update recent paths:', error);
+  });
+}
+
 export function appendRecentPath(paths: string[], path: string): string[] {
   const normalizedPath = path.trim();
   if (!normalizedPath) {
@@ -64,6 +75,7 @@ export function addRecentFilePath(path: string) {
 
   if (nextPaths !== state.settings.recentFiles) {
     state.updateSettings({ recentFiles: nextPaths });
+    syncRecentPathCommand('add_recent_file', { path });
   }
 }
 
@@ -73,6 +85,7 @@ export function addRecentFolderPath(path: string) {
 
   if (nextPaths !== state.settings.recentFolders) {
     state.updateSettings({ recentFolders: nextPaths });
+    syncRecentPathCommand('add_recent_folder', { path });
   }
 }
 
@@ -82,6 +95,7 @@ export function removeRecentFilePath(path: string) {
 
   if (nextPaths !== state.settings.recentFiles) {
     state.updateSettings({ recentFiles: nextPaths });
+    syncRecentPathCommand('remove_recent', { kind: 'files', path });
   }
 }
 
@@ -91,5 +105,20 @@ export function removeRecentFolderPath(path: string) {
 
   if (nextPaths !== state.settings.recentFolders) {
     state.updateSettings({ recentFolders: nextPaths });
+    syncRecentPathCommand('remove_recent', { kind: 'folders', path });
   }
 }
+
+function clearRecentPaths(kind: RecentPathKind) {
+  const key = kind === 'files' ? 'recentFiles' : 'recentFolders';
+  useStore.getState().updateSettings({ [key]: [] });
+  syncRecentPathCommand('clear_recent', { kind });
+}
+
+export function clearRecentFilePaths() {
+  clearRecentPaths('files');
+}
+
+export function clearRecentFolderPaths() {
+  clearRecentPaths('folders');
+}
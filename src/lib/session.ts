@@ -0,0 +1,72 @@
+import { invoke } from '@tauri-apps/api/core';
+import { navigateTabToPosition } from '@/lib/openFile';
+import { getTopVisibleLine } from '@/lib/readingPositions';
+import { getTabSessionExtras } from '@/lib/sessionExtras';
+import { isReusableBlankTab } from '@/lib/tabUtils';
+import { type FileTab, useStore } from '@/store/useStore';
+
+interface RestoredSessionTab {
+  fileInfo: FileTab;
+  cursorLine: number;
+  cursorColumn: number;
+  scrollTopLine: number;
+  pinned: boolean;
+}
+
+interface SessionRestorePayload {
+  tabs: RestoredSessionTab[];
+  activeId: string | null;
+  missingPaths: string[];
+  errors: string[];
+}
+
+// Reopens the tabs saved by the previous run. A blank startup tab is dropped
+// once saved tabs take its place.
+export async function restoreSavedSession() {
+  const payload = await invoke<SessionRestorePayload>('load_session');
+  if (payload.errors.length > 0) {
+    console.warn('Some session tabs could not be restored:', payload.errors);
+  }
+  if (payload.tabs.length === 0) {
+    return;
+  }
+
+  const blankTabs = useStore.getState().tabs.filter(isReusableBlankTab);
+  for (const tab of payload.tabs) {
+    if (!useStore.getState().tabs.some((item) => item.id === tab.fileInfo.id)) {
+      useStore.getState().addTab(tab.fileInfo);
+    }
+  }
+
+  const activeId = payload.activeId ?? payload.tabs[0].fileInfo.id;
+  useStore.getState().setActiveTab(activeId);
+  for (const tab of payload.tabs) {
+    navigateTabToPosition(tab.fileInfo.id, { line: tab.cursorLine, column: tab.cursorColumn });
+  }
+
+  for (const blankTab of blankTabs) {
+    useStore.getState().closeTab(blankTab.id);
+    await invoke('close_file', { id: blankTab.id });
+  }
+}
+
+// Reports the open tabs with their caret and scroll position so the backend
+// can write them to session.json.
+export async function saveSession() {
+  const state = useStore.getState();
+  const tabs = state.tabs
+    .filter((tab) => tab.tabType !== 'diff' && tab.path)
+    .map((tab) => {
+      const cursor = state.cursorPositionByTab[tab.id];
+      return {
+        id: tab.id,
+        cursorLine: cursor?.line ?? 1,
+        cursorColumn: cursor?.column ?? 1,
+        scrollTopLine: getTopVisibleLine(tab.id) ?? 1,
+        pinned: state.settings.pinnedTabPaths.includes(tab.path),
+        extras: getTabSessionExtras(tab.id),
+      };
+    });
+
+  await invoke<number>('save_session', { tabs, activeId: state.activeTabId });
+}
@@ -0,0 +1,41 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// The search and panel state of a tab that the session keeps with it; mirrors
+// SessionDocumentExtras in the backend.
+export interface SessionDocumentExtras {
+  searchKeyword?: string | null;
+  searchMode?: string | null;
+  searchCaseSensitive?: boolean;
+  filterRuleGroup?: string | null;
+  resultPanelOpen?: boolean;
+  resultPanelTab?: string | null;
+  resultPanelHeight?: number | null;
+}
+
+const extrasByTab = new Map<string, SessionDocumentExtras>();
+const savedExtrasByTab = new Map<string, Promise<SessionDocumentExtras | null>>();
+
+// Called by the search panel as its state for the tab changes.
+export function updateTabSessionExtras(tabId: string, extras: SessionDocumentExtras) {
+  extrasByTab.set(tabId, { ...extrasByTab.get(tabId), ...extras });
+}
+
+export function getTabSessionExtras(tabId: string) {
+  return extrasByTab.get(tabId) ?? null;
+}
+
+// What the previous session saved for the tab's file, asked once per tab.
+export function loadSavedTabSessionExtras(tabId: string) {
+  let saved = savedExtrasByTab.get(tabId);
+  if (!saved) {
+    saved = invoke<SessionDocumentExtras | null>('get_document_session_extras', { id: tabId }).catch(
+      (error) => {
+        console.warn('Failed to load session extras:', error);
+        return null;
+      }
+    );
+    savedExtrasByTab.set(tabId, saved);
+  }
+
+  return saved;
+}
@@ -37,6 +37,7 @@ describe("structuredFormat", () => {
 
   it("reports support state correctly", () => {
     expect(isStructuredFormatSupported(createTab({ path: "C:\\repo\\data.toml" }))).toBe(true);
+    expect(isStructuredFormatSupported(createTab({ path: "C:\\repo\\app.properties" }))).toBe(true);
     expect(isStructuredFormatSupported(createTab({ path: "C:\\repo\\script.py" }))).toBe(false);
   });
 });
@@ -3,6 +3,7 @@ import {
   detectSyntaxKeyFromTab,
   getLineCommentPrefixForSyntaxKey,
   getSyntaxLabel,
+  setSyntaxExtensionOverrides,
 } from "./syntax";
 
 describe("detectSyntaxKeyFromTab", () => {
@@ -114,6 +115,8 @@ describe("detectSyntaxKeyFromTab", () => {
       { fileName: "Gemfile", expected: "ruby" },
       { fileName: "schema.sql", expected: "sql" },
       { fileName: "App.swift", expected: "swift" },
+      { fileName: "fix-crash.patch", expected: "diff" },
+      { fileName: "report.tsv", expected: "csv" },
     ];
 
     for (const testCase of cases) {
@@ -134,6 +137,17 @@ describe("detectSyntaxKeyFromTab", () => {
       })
     ).toBe("kotlin");
   });
+
+  it("applies configured extension overrides before built-in extensions", () => {
+    setSyntaxExtensionOverrides({ ".Gradle": "kotlin", rs: "plain_text", tpl: "unknown" });
+    try {
+      expect(detectSyntaxKeyFromTab({ name: "build.gradle", path: "" })).toBe("kotlin");
+      expect(detectSyntaxKeyFromTab({ name: "main.rs", path: "" })).toBe("plain_text");
+      expect(detectSyntaxKeyFromTab({ name: "page.tpl", path: "" })).toBe("plain_text");
+    } finally {
+      setSyntaxExtensionOverrides(undefined);
+    }
+  });
 });
 
 describe("syntax helpers", () => {
@@ -33,9 +33,27 @@ export const SYNTAX_OPTIONS: Array<{ value: SyntaxKey; label: string }> = [
   { value: 'ruby', label: 'Ruby' },
   { value: 'sql', label: 'SQL' },
   { value: 'swift', label: 'Swift' },
+  { value: 'diff', label: 'Diff / Patch' },
+  { value: 'csv', label: 'CSV' },
 ];
 
 const syntaxLabelByValue = new Map(SYNTAX_OPTIONS.map((item) => [item.value, item.label]));
+let syntaxExtensionOverrides = new Map<string, SyntaxKey>();
+
+export function setSyntaxExtensionOverrides(overrides: unknown) {
+  const nextOverrides = new Map<string, SyntaxKey>();
+  if (overrides && typeof overrides === 'object') {
+    for (const [rawExtension, rawSyntaxKey] of Object.entries(overrides)) {
+      const extension = rawExtension.trim().replace(/^\.+/, '').toLowerCase();
+      const syntaxKey = typeof rawSyntaxKey === 'string' ? rawSyntaxKey.trim().toLowerCase() : '';
+      if (extension && syntaxLabelByValue.has(syntaxKey as SyntaxKey)) {
+        nextOverrides.set(extension, syntaxKey as SyntaxKey);
+      }
+    }
+  }
+
+  syntaxExtensionOverrides = nextOverrides;
+}
 const lineCommentPrefixBySyntax: Partial<Record<SyntaxKey, string>> = {
   plain_text: '#',
   markdown: '#',
@@ -108,6 +126,11 @@ export function detectSyntaxKeyFromTab(tab: Pick<FileTab, 'name' | 'path'>): Syn
   }
 
   const extension = fileName.slice(dotIndex + 1);
+  const overriddenSyntaxKey = syntaxExtensionOverrides.get(extension);
+  if (overriddenSyntaxKey) {
+    return overriddenSyntaxKey;
+  }
+
   switch (extension) {
     case 'js':
     case 'jsx':
@@ -219,6 +242,12 @@ export function detectSyntaxKeyFromTab(tab: Pick<FileTab, 'name' | 'path'>): Syn
       return 'sql';
     case 'swift':
       return 'swift';
+    case 'diff':
+    case 'patch':
+      return 'diff';
+    case 'csv':
+    case 'tsv':
+      return 'csv';
     default:
       return 'plain_text';
   }
@@ -123,6 +123,25 @@ describe("tabClose", () => {
     });
   });
 
+  it("confirmTabClose asks the backend whether a hex view has unsaved edits", async () => {
+    invokeMock.mockResolvedValue({ isDirty: true });
+    requestTabCloseConfirmMock.mockResolvedValue("cancel");
+
+    const decision = await confirmTabClose(
+      createTab({ name: "image.png", isDirty: false, hexView: true }),
+      "en-US",
+      false
+    );
+
+    expect(decision).toBe("cancel");
+    expect(invokeMock).toHaveBeenCalledWith("get_dirty_state", { id: "tab-1" });
+    expect(requestTabCloseConfirmMock).toHaveBeenCalledWith({
+      language: "en-US",
+      tabName: "image.png",
+      allowAllActions: false,
+    });
+  });
+
   it("confirmTabClose falls back to Untitled display name", async () => {
     requestTabCloseConfirmMock.mockResolvedValue("discard");
 
@@ -55,12 +55,31 @@ export async function saveTab(tab: FileTab, updateTab: UpdateTab): Promise<boole
   return true;
 }
 
+// Hex views keep their byte edits in the backend, so ask it.
+async function hasUnsavedChanges(tab: FileTab) {
+  if (tab.isDirty) {
+    return true;
+  }
+
+  if (!tab.hexView) {
+    return false;
+  }
+
+  try {
+    const dirtyState = await invoke<{ isDirty: boolean }>('get_dirty_state', { id: tab.id });
+    return dirtyState.isDirty;
+  } catch (error) {
+    console.error('Failed to read hex view dirty state:', error);
+    return false;
+  }
+}
+
 export async function confirmTabClose(
   tab: FileTab,
   language: AppLanguage,
   allowAllActions: boolean
 ): Promise<TabCloseDecision> {
-  if (!tab.isDirty) {
+  if (!(await hasUnsavedChanges(tab))) {
     return 'discard';
   }
 
@@ -11,7 +11,14 @@ export interface FileTab {
   largeFileMode: boolean;
   wordWrap: boolean;
   syntaxOverride?: SyntaxKey | null;
+  suggestedSyntax?: SyntaxKey | null;
+  // Overrides settings.autoReloadUnmodifiedFiles for this tab when set.
+  autoReloadOverride?: boolean | null;
   isDirty?: boolean;
+  // Binary file opened as a hex view; its byte edits are not tracked in isDirty.
+  hexView?: boolean;
+  // Too large to load as text; shown by StreamingDocumentView from the memory map.
+  streaming?: boolean;
   tabType?: 'file' | 'diff';
   diffPayload?: DiffTabPayload;
 }
@@ -83,7 +90,9 @@ export type SyntaxKey =
   | 'powershell'
   | 'ruby'
   | 'sql'
-  | 'swift';
+  | 'swift'
+  | 'diff'
+  | 'csv';
 
 export type AppLanguage = 'zh-CN' | 'en-US';
 export type AppTheme = 'light' | 'dark';
@@ -127,6 +136,9 @@ export type OutlineType =
   | 'php'
   | 'kotlin'
   | 'swift'
+  | 'ruby'
+  | 'lua'
+  | 'sql'
   | null;
 
 export interface OutlineNode {
@@ -152,6 +164,7 @@ export interface SettingsState {
   fontSize: number;
   tabWidth: number;
   tabIndentMode: TabIndentMode;
+  foldMinBlockLines: number;
   newFileLineEnding: LineEnding;
   wordWrap: boolean;
   minimap: boolean;
@@ -161,6 +174,7 @@ export interface SettingsState {
   highlightCurrentLine: boolean;
   singleInstanceMode: boolean;
   rememberWindowState: boolean;
+  autoReloadUnmodifiedFiles: boolean;
   recentFiles: string[];
   recentFolders: string[];
   recentSearchKeywords: string[];
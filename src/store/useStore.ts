@@ -58,6 +58,7 @@ export const useStore = create<AppState>((set) => ({
     fontSize: 14,
     tabWidth: 4,
     tabIndentMode: 'tabs',
+    foldMinBlockLines: 2,
     newFileLineEnding: defaultNewFileLineEnding,
     wordWrap: false,
     minimap: true,
@@ -67,6 +68,7 @@ export const useStore = create<AppState>((set) => ({
     highlightCurrentLine: true,
     singleInstanceMode: true,
     rememberWindowState: true,
+    autoReloadUnmodifiedFiles: false,
     recentFiles: [],
     recentFolders: [],
     recentSearchKeywords: [],